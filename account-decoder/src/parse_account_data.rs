@@ -4,7 +4,8 @@ use {
         parse_address_lookup_table::parse_address_lookup_table,
         parse_bpf_loader::parse_bpf_upgradeable_loader, parse_config::parse_config,
         parse_nonce::parse_nonce, parse_stake::parse_stake, parse_sysvar::parse_sysvar,
-        parse_token::parse_token_v3, parse_vote::parse_vote,
+        parse_token::parse_token_v3, parse_upgrade_timelock::parse_upgrade_timelock,
+        parse_vote::parse_vote,
     },
     inflector::Inflector,
     solana_clock::UnixTimestamp,
@@ -13,6 +14,7 @@ use {
     solana_sdk_ids::{
         address_lookup_table, bpf_loader_upgradeable, config, stake, system_program, sysvar, vote,
     },
+    solana_vote_program::vote_state::commission_schedule::PendingCommission,
     spl_token_2022::extension::{
         interest_bearing_mint::InterestBearingConfig, scaled_ui_amount::ScaledUiAmountConfig,
     },
@@ -37,6 +39,10 @@ pub static PARSABLE_PROGRAM_IDS: std::sync::LazyLock<HashMap<Pubkey, ParsableAcc
         m.insert(spl_token_2022::id(), ParsableAccount::SplToken2022);
         m.insert(stake::id(), ParsableAccount::Stake);
         m.insert(sysvar::id(), ParsableAccount::Sysvar);
+        m.insert(
+            solana_upgrade_timelock_interface::id(),
+            ParsableAccount::UpgradeTimelock,
+        );
         m.insert(vote::id(), ParsableAccount::Vote);
         m
     });
@@ -70,6 +76,7 @@ pub enum ParsableAccount {
     SplToken2022,
     Stake,
     Sysvar,
+    UpgradeTimelock,
     Vote,
 }
 
@@ -88,6 +95,9 @@ pub struct AccountAdditionalDataV2 {
 #[derive(Clone, Copy, Default)]
 pub struct AccountAdditionalDataV3 {
     pub spl_token_additional_data: Option<SplTokenAdditionalDataV2>,
+    /// The scheduled commission increase of a vote account, read from its pending commission
+    /// account
+    pub vote_pending_commission: Option<PendingCommission>,
 }
 
 #[allow(deprecated)]
@@ -95,6 +105,7 @@ impl From<AccountAdditionalDataV2> for AccountAdditionalDataV3 {
     fn from(v: AccountAdditionalDataV2) -> Self {
         Self {
             spl_token_additional_data: v.spl_token_additional_data.map(Into::into),
+            vote_pending_commission: None,
         }
     }
 }
@@ -156,6 +167,7 @@ pub fn parse_account_data(
             spl_token_additional_data: d
                 .spl_token_decimals
                 .map(SplTokenAdditionalDataV2::with_decimals),
+            vote_pending_commission: None,
         }),
     )
 }
@@ -195,7 +207,10 @@ pub fn parse_account_data_v3(
         )?,
         ParsableAccount::Stake => serde_json::to_value(parse_stake(data)?)?,
         ParsableAccount::Sysvar => serde_json::to_value(parse_sysvar(data, pubkey)?)?,
-        ParsableAccount::Vote => serde_json::to_value(parse_vote(data)?)?,
+        ParsableAccount::UpgradeTimelock => serde_json::to_value(parse_upgrade_timelock(data)?)?,
+        ParsableAccount::Vote => {
+            serde_json::to_value(parse_vote(data, additional_data.vote_pending_commission)?)?
+        }
     };
     Ok(ParsedAccount {
         program: format!("{program_name:?}").to_kebab_case(),
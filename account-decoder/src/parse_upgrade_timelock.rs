@@ -0,0 +1,148 @@
+use {
+    crate::parse_account_data::{ParsableAccount, ParseAccountError},
+    solana_upgrade_timelock_interface::state::{PendingRelease, PendingUpgrade, UpgradeTimelock},
+};
+
+pub fn parse_upgrade_timelock(
+    data: &[u8],
+) -> Result<UpgradeTimelockAccountType, ParseAccountError> {
+    UpgradeTimelock::deserialize(data)
+        .map(|upgrade_timelock| UpgradeTimelockAccountType::Timelock(upgrade_timelock.into()))
+        .map_err(|_| ParseAccountError::AccountNotParsable(ParsableAccount::UpgradeTimelock))
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "type", content = "info")]
+pub enum UpgradeTimelockAccountType {
+    Timelock(UiUpgradeTimelock),
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiUpgradeTimelock {
+    pub program: String,
+    pub authority: String,
+    pub delay_slots: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_upgrade: Option<UiPendingUpgrade>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_release: Option<UiPendingRelease>,
+}
+
+impl From<UpgradeTimelock> for UiUpgradeTimelock {
+    fn from(upgrade_timelock: UpgradeTimelock) -> Self {
+        Self {
+            program: upgrade_timelock.program.to_string(),
+            authority: upgrade_timelock.authority.to_string(),
+            delay_slots: upgrade_timelock.delay_slots.to_string(),
+            pending_upgrade: upgrade_timelock.pending_upgrade.map(Into::into),
+            pending_release: upgrade_timelock.pending_release.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiPendingUpgrade {
+    pub buffer: String,
+    pub buffer_hash: String,
+    pub staged_slot: String,
+    pub earliest_slot: String,
+}
+
+impl From<PendingUpgrade> for UiPendingUpgrade {
+    fn from(pending_upgrade: PendingUpgrade) -> Self {
+        Self {
+            buffer: pending_upgrade.buffer.to_string(),
+            buffer_hash: pending_upgrade.buffer_hash.to_string(),
+            staged_slot: pending_upgrade.staged_slot.to_string(),
+            earliest_slot: pending_upgrade.earliest_slot.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiPendingRelease {
+    pub new_authority: String,
+    pub staged_slot: String,
+    pub earliest_slot: String,
+}
+
+impl From<PendingRelease> for UiPendingRelease {
+    fn from(pending_release: PendingRelease) -> Self {
+        Self {
+            new_authority: pending_release.new_authority.to_string(),
+            staged_slot: pending_release.staged_slot.to_string(),
+            earliest_slot: pending_release.earliest_slot.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, solana_hash::Hash, solana_pubkey::Pubkey};
+
+    #[test]
+    fn test_parse_upgrade_timelock() {
+        let program = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let buffer = Pubkey::new_unique();
+        let buffer_hash = Hash::new_from_array([7; 32]);
+        let new_authority = Pubkey::new_unique();
+        let mut upgrade_timelock = UpgradeTimelock {
+            program,
+            authority,
+            delay_slots: 100,
+            pending_upgrade: Some(PendingUpgrade {
+                buffer,
+                buffer_hash,
+                staged_slot: 1000,
+                earliest_slot: 1100,
+            }),
+            pending_release: Some(PendingRelease {
+                new_authority,
+                staged_slot: 1010,
+                earliest_slot: 1110,
+            }),
+        };
+        let mut data = vec![0; UpgradeTimelock::size_of()];
+        upgrade_timelock.serialize(&mut data).unwrap();
+        assert_eq!(
+            parse_upgrade_timelock(&data).unwrap(),
+            UpgradeTimelockAccountType::Timelock(UiUpgradeTimelock {
+                program: program.to_string(),
+                authority: authority.to_string(),
+                delay_slots: 100.to_string(),
+                pending_upgrade: Some(UiPendingUpgrade {
+                    buffer: buffer.to_string(),
+                    buffer_hash: buffer_hash.to_string(),
+                    staged_slot: 1000.to_string(),
+                    earliest_slot: 1100.to_string(),
+                }),
+                pending_release: Some(UiPendingRelease {
+                    new_authority: new_authority.to_string(),
+                    staged_slot: 1010.to_string(),
+                    earliest_slot: 1110.to_string(),
+                }),
+            }),
+        );
+
+        upgrade_timelock.pending_upgrade = None;
+        upgrade_timelock.pending_release = None;
+        upgrade_timelock.serialize(&mut data).unwrap();
+        assert_eq!(
+            parse_upgrade_timelock(&data).unwrap(),
+            UpgradeTimelockAccountType::Timelock(UiUpgradeTimelock {
+                program: program.to_string(),
+                authority: authority.to_string(),
+                delay_slots: 100.to_string(),
+                pending_upgrade: None,
+                pending_release: None,
+            }),
+        );
+
+        let bad_data = vec![0; 4];
+        assert!(parse_upgrade_timelock(&bad_data).is_err());
+    }
+}
@@ -3,9 +3,19 @@ use {
     solana_clock::{Epoch, Slot},
     solana_pubkey::Pubkey,
     solana_vote_interface::state::{BlockTimestamp, Lockout, VoteState},
+    solana_vote_program::vote_state::commission_schedule::PendingCommission,
 };
 
-pub fn parse_vote(data: &[u8]) -> Result<VoteAccountType, ParseAccountError> {
+/// Parses a vote account, together with its scheduled commission increase if the caller read
+/// one from the vote account's pending commission account.
+///
+/// The pending commission account itself is not parsed: its data is too short to tell it apart
+/// from any other account of the same size owned by the vote program, and its address can only be
+/// checked from the address of the vote account.
+pub fn parse_vote(
+    data: &[u8],
+    pending_commission: Option<PendingCommission>,
+) -> Result<VoteAccountType, ParseAccountError> {
     let mut vote_state = VoteState::deserialize(data).map_err(ParseAccountError::from)?;
     let epoch_credits = vote_state
         .epoch_credits()
@@ -55,6 +65,7 @@ pub fn parse_vote(data: &[u8]) -> Result<VoteAccountType, ParseAccountError> {
         prior_voters,
         epoch_credits,
         last_timestamp: vote_state.last_timestamp,
+        pending_commission: pending_commission.map(UiPendingCommission::from),
     }))
 }
 
@@ -78,6 +89,25 @@ pub struct UiVoteState {
     prior_voters: Vec<UiPriorVoters>,
     epoch_credits: Vec<UiEpochCredits>,
     last_timestamp: BlockTimestamp,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pending_commission: Option<UiPendingCommission>,
+}
+
+/// A scheduled commission increase of a vote account
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiPendingCommission {
+    commission: u8,
+    effective_epoch: Epoch,
+}
+
+impl From<PendingCommission> for UiPendingCommission {
+    fn from(pending_commission: PendingCommission) -> Self {
+        Self {
+            commission: pending_commission.commission,
+            effective_epoch: pending_commission.effective_epoch,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -135,11 +165,39 @@ mod test {
             ..UiVoteState::default()
         };
         assert_eq!(
-            parse_vote(&vote_account_data).unwrap(),
+            parse_vote(&vote_account_data, None).unwrap(),
             VoteAccountType::Vote(expected_vote_state)
         );
 
         let bad_data = vec![0; 4];
-        assert!(parse_vote(&bad_data).is_err());
+        assert!(parse_vote(&bad_data, None).is_err());
+    }
+
+    #[test]
+    fn test_parse_vote_pending_commission() {
+        let vote_state = VoteState::default();
+        let mut vote_account_data: Vec<u8> = vec![0; VoteState::size_of()];
+        let versioned = VoteStateVersions::new_current(vote_state);
+        VoteState::serialize(&versioned, &mut vote_account_data).unwrap();
+        let pending_commission = PendingCommission {
+            commission: 42,
+            effective_epoch: 7,
+        };
+        let expected_vote_state = UiVoteState {
+            node_pubkey: Pubkey::default().to_string(),
+            authorized_withdrawer: Pubkey::default().to_string(),
+            pending_commission: Some(UiPendingCommission {
+                commission: 42,
+                effective_epoch: 7,
+            }),
+            ..UiVoteState::default()
+        };
+        assert_eq!(
+            parse_vote(&vote_account_data, Some(pending_commission)).unwrap(),
+            VoteAccountType::Vote(expected_vote_state)
+        );
+
+        // the pending commission account itself is not parsed
+        assert!(parse_vote(&pending_commission.to_bytes(), None).is_err());
     }
 }
@@ -2,16 +2,20 @@ use {
     bzip2::bufread::BzDecoder,
     log::*,
     rand::{thread_rng, Rng},
+    serde::{Deserialize, Serialize},
     solana_genesis_config::{GenesisConfig, DEFAULT_GENESIS_ARCHIVE, DEFAULT_GENESIS_FILE},
     solana_perf::packet::bytes::{Buf, Bytes, BytesMut},
     std::{
+        cell::Cell,
         collections::{HashMap, VecDeque},
         fs::{self, File},
-        io::{self, BufReader, Read},
+        io::{self, BufReader, Read, Seek, SeekFrom},
         path::{
             Component::{self, CurDir, Normal},
             Path, PathBuf,
         },
+        sync::atomic::{AtomicUsize, Ordering},
+        thread,
         time::Instant,
     },
     tar::{
@@ -607,6 +611,219 @@ where
     )
 }
 
+/// Unpacks the account storage file `file_name` from `archive` into `dst_dir`,
+/// skipping every other entry.
+///
+/// Returns the path of the unpacked file, or None if `archive` doesn't
+/// contain it.
+pub fn unpack_snapshot_storage_file<A: Read>(
+    archive: Archive<A>,
+    file_name: &str,
+    dst_dir: &Path,
+) -> Result<Option<PathBuf>> {
+    let unpacked_path = Cell::new(None);
+    unpack_archive(
+        archive,
+        MAX_SNAPSHOT_ARCHIVE_UNPACKED_APPARENT_SIZE,
+        MAX_SNAPSHOT_ARCHIVE_UNPACKED_ACTUAL_SIZE,
+        MAX_SNAPSHOT_ARCHIVE_UNPACKED_COUNT,
+        |parts, kind| {
+            if !is_valid_snapshot_archive_entry(parts, kind) {
+                UnpackPath::Invalid
+            } else if parts == ["accounts", file_name] {
+                UnpackPath::Valid(dst_dir)
+            } else {
+                UnpackPath::Ignore
+            }
+        },
+        |entry_path| unpacked_path.set(Some(entry_path)),
+    )?;
+    Ok(unpacked_path.into_inner())
+}
+
+// A chunked archive is a sequence of independently compressed zstd frames,
+// each holding whole tar entries, followed by a skippable frame holding the
+// index of the chunks. Concatenated, the frames still decode to a single tar
+// archive, so chunked archives remain readable by any zstd and tar tools.
+//
+// The index is located through a fixed size footer at the very end of the
+// file: the length of the serialized index as a little endian u64, followed
+// by `CHUNKED_ARCHIVE_INDEX_MAGIC`.
+
+/// Marks the end of a chunked archive
+pub const CHUNKED_ARCHIVE_INDEX_MAGIC: [u8; 8] = *b"SOLCHIDX";
+const CHUNKED_ARCHIVE_FOOTER_SIZE: u64 = 16;
+// Magic number of the first zstd skippable frame type
+const ZSTD_SKIPPABLE_FRAME_MAGIC: u32 = 0x184D2A50;
+const ZSTD_SKIPPABLE_FRAME_HEADER_SIZE: u64 = 8;
+// 256 MiB, enough to index the maximum number of storage files
+const MAX_CHUNKED_ARCHIVE_INDEX_SIZE: u64 = 256 * 1024 * 1024;
+
+/// An account storage file held by a chunk of a chunked archive
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveChunkEntry {
+    /// Path of the entry in the archive, e.g. `accounts/<slot>.<id>`
+    pub path: String,
+    pub size: u64,
+}
+
+/// A single independently compressed chunk of a chunked archive
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveChunk {
+    /// Offset of the chunk's zstd frame in the archive file
+    pub offset: u64,
+    pub compressed_size: u64,
+    pub decompressed_size: u64,
+    /// The account storage files held by the chunk. The version and bank
+    /// snapshot files are always held by the first chunk.
+    pub entries: Vec<ArchiveChunkEntry>,
+}
+
+/// The index of a chunked archive
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChunkedArchiveIndex {
+    pub chunks: Vec<ArchiveChunk>,
+}
+
+impl ChunkedArchiveIndex {
+    /// Returns the chunk holding the account storage file `file_name`
+    pub fn find_storage_file(&self, file_name: &str) -> Option<&ArchiveChunk> {
+        let path = format!("accounts/{file_name}");
+        self.chunks
+            .iter()
+            .find(|chunk| chunk.entries.iter().any(|entry| entry.path == path))
+    }
+
+    /// Writes the index as the trailing skippable frame of a chunked archive
+    pub fn write_to(&self, mut output: impl io::Write) -> io::Result<()> {
+        let index = bincode::serialize(self).map_err(io::Error::other)?;
+        let frame_size = u32::try_from(index.len() as u64 + CHUNKED_ARCHIVE_FOOTER_SIZE)
+            .map_err(|_| io::Error::other("chunked archive index is too large"))?;
+        output.write_all(&ZSTD_SKIPPABLE_FRAME_MAGIC.to_le_bytes())?;
+        output.write_all(&frame_size.to_le_bytes())?;
+        output.write_all(&index)?;
+        output.write_all(&(index.len() as u64).to_le_bytes())?;
+        output.write_all(&CHUNKED_ARCHIVE_INDEX_MAGIC)
+    }
+}
+
+/// Reads and validates the index of the chunked archive at `archive_path`
+pub fn read_chunked_archive_index(archive_path: &Path) -> Result<ChunkedArchiveIndex> {
+    let mut file = File::open(archive_path)?;
+    let file_size = file.metadata()?.len();
+    let invalid = |reason: &str| {
+        UnpackError::Archive(format!(
+            "invalid chunked archive '{}': {reason}",
+            archive_path.display()
+        ))
+    };
+    if file_size < CHUNKED_ARCHIVE_FOOTER_SIZE + ZSTD_SKIPPABLE_FRAME_HEADER_SIZE {
+        return Err(invalid("too short"));
+    }
+
+    let mut footer = [0; CHUNKED_ARCHIVE_FOOTER_SIZE as usize];
+    file.seek(SeekFrom::End(-(CHUNKED_ARCHIVE_FOOTER_SIZE as i64)))?;
+    file.read_exact(&mut footer)?;
+    let (index_size, magic) = footer.split_at(8);
+    if magic != CHUNKED_ARCHIVE_INDEX_MAGIC {
+        return Err(invalid("missing index"));
+    }
+    let index_size = u64::from_le_bytes(index_size.try_into().unwrap());
+    let chunks_end = (file_size - CHUNKED_ARCHIVE_FOOTER_SIZE - ZSTD_SKIPPABLE_FRAME_HEADER_SIZE)
+        .checked_sub(index_size)
+        .ok_or_else(|| invalid("index size exceeds the file size"))?;
+    if index_size > MAX_CHUNKED_ARCHIVE_INDEX_SIZE {
+        return Err(invalid("index is too large"));
+    }
+
+    let mut index = vec![0; index_size as usize];
+    file.seek(SeekFrom::Start(
+        chunks_end + ZSTD_SKIPPABLE_FRAME_HEADER_SIZE,
+    ))?;
+    file.read_exact(&mut index)?;
+    let index: ChunkedArchiveIndex =
+        bincode::deserialize(&index).map_err(|err| invalid(&err.to_string()))?;
+
+    // Chunks must be laid out back to back from the start of the file up to
+    // the index, so that decompressing every chunk restores the whole archive
+    let mut previous_end = 0;
+    for chunk in &index.chunks {
+        if chunk.offset != previous_end {
+            return Err(invalid("chunks are not contiguous"));
+        }
+        previous_end = chunk
+            .offset
+            .checked_add(chunk.compressed_size)
+            .ok_or_else(|| invalid("chunk size overflows"))?;
+        if previous_end > chunks_end {
+            return Err(invalid("chunk out of bounds"));
+        }
+    }
+    if previous_end != chunks_end {
+        return Err(invalid("chunks do not cover the archive"));
+    }
+    Ok(index)
+}
+
+/// Returns a reader decompressing a single chunk of the chunked archive at
+/// `archive_path`
+pub fn open_archive_chunk(archive_path: &Path, chunk: &ArchiveChunk) -> io::Result<impl Read> {
+    let mut file = File::open(archive_path)?;
+    file.seek(SeekFrom::Start(chunk.offset))?;
+    Ok(zstd::stream::read::Decoder::new(file.take(chunk.compressed_size))?.single_frame())
+}
+
+/// Decompresses the chunks of the chunked archive at `archive_path` on
+/// `num_threads` threads, and sends them into `chunk_sender` split like
+/// `ArchiveChunker` does.
+pub fn decode_and_send_archive_chunks(
+    archive_path: &Path,
+    num_threads: usize,
+    chunk_sender: crossbeam_channel::Sender<MultiBytes>,
+) -> Result<()> {
+    let index = read_chunked_archive_index(archive_path)?;
+    let next_chunk = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        let handles = (0..num_threads.max(1))
+            .map(|thread_index| {
+                let chunk_sender = chunk_sender.clone();
+                thread::Builder::new()
+                    .name(format!("solChnkDecmp{thread_index:02}"))
+                    .spawn_scoped(scope, || -> Result<()> {
+                        let chunk_sender = chunk_sender;
+                        while let Some(chunk) =
+                            index.chunks.get(next_chunk.fetch_add(1, Ordering::Relaxed))
+                        {
+                            let reader = open_archive_chunk(archive_path, chunk)?;
+                            ArchiveChunker::new(reader)
+                                .decode_and_send_chunks(chunk_sender.clone())?;
+                        }
+                        Ok(())
+                    })
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .try_for_each(|handle| handle.join().unwrap())
+    })
+}
+
+/// Like `unpack_snapshot_storage_file`, but only decompresses the chunk
+/// holding `file_name`
+pub fn unpack_snapshot_storage_file_from_chunked_archive(
+    archive_path: &Path,
+    file_name: &str,
+    dst_dir: &Path,
+) -> Result<Option<PathBuf>> {
+    let index = read_chunked_archive_index(archive_path)?;
+    let Some(chunk) = index.find_storage_file(file_name) else {
+        return Ok(None);
+    };
+    let archive = Archive::new(open_archive_chunk(archive_path, chunk)?);
+    unpack_snapshot_storage_file(archive, file_name, dst_dir)
+}
+
 fn all_digits(v: &str) -> bool {
     if v.is_empty() {
         return false;
@@ -1254,4 +1471,54 @@ mod tests {
         });
         assert_matches!(result, Ok(()));
     }
+
+    #[test]
+    fn test_read_chunked_archive_index_layout() {
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        let archive_path = tmp_dir.path().join("snapshot.chunked.tar.zst");
+        let chunk = |offset, compressed_size| ArchiveChunk {
+            offset,
+            compressed_size,
+            decompressed_size: 0,
+            entries: vec![],
+        };
+        // Writes 100 bytes of chunk data followed by the index of `chunks`
+        let read_index = |chunks: Vec<ArchiveChunk>| {
+            let mut archive = vec![0; 100];
+            ChunkedArchiveIndex { chunks }
+                .write_to(&mut archive)
+                .unwrap();
+            std::fs::write(&archive_path, archive).unwrap();
+            read_chunked_archive_index(&archive_path)
+        };
+
+        assert_matches!(read_index(vec![chunk(0, 40), chunk(40, 60)]), Ok(_));
+
+        // The first chunk does not start at the beginning of the archive
+        assert_matches!(
+            read_index(vec![chunk(10, 30), chunk(40, 60)]),
+            Err(UnpackError::Archive(_))
+        );
+        // A gap between two chunks
+        assert_matches!(
+            read_index(vec![chunk(0, 30), chunk(40, 60)]),
+            Err(UnpackError::Archive(_))
+        );
+        // Overlapping chunks
+        assert_matches!(
+            read_index(vec![chunk(0, 50), chunk(40, 60)]),
+            Err(UnpackError::Archive(_))
+        );
+        // The chunks do not reach the index
+        assert_matches!(
+            read_index(vec![chunk(0, 40), chunk(40, 50)]),
+            Err(UnpackError::Archive(_))
+        );
+        // A chunk overlaps the index
+        assert_matches!(
+            read_index(vec![chunk(0, 40), chunk(40, 70)]),
+            Err(UnpackError::Archive(_))
+        );
+        assert_matches!(read_index(vec![]), Err(UnpackError::Archive(_)));
+    }
 }
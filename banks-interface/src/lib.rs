@@ -10,7 +10,7 @@ use {
     solana_pubkey::Pubkey,
     solana_signature::Signature,
     solana_transaction::versioned::VersionedTransaction,
-    solana_transaction_context::TransactionReturnData,
+    solana_transaction_context::{InstructionInvocation, TransactionReturnData},
     solana_transaction_error::TransactionError,
 };
 
@@ -41,6 +41,7 @@ pub struct TransactionSimulationDetails {
     pub loaded_accounts_data_size: u32,
     pub return_data: Option<TransactionReturnData>,
     pub inner_instructions: Option<Vec<InnerInstructions>>,
+    pub invocation_trace: Option<Vec<InstructionInvocation>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -49,6 +50,7 @@ pub struct TransactionMetadata {
     pub log_messages: Vec<String>,
     pub compute_units_consumed: u64,
     pub return_data: Option<TransactionReturnData>,
+    pub invocation_trace: Vec<InstructionInvocation>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
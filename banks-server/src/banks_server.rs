@@ -191,7 +191,8 @@ fn simulate_transaction(
         loaded_accounts_data_size,
         return_data,
         inner_instructions,
-    } = bank.simulate_transaction_unchecked(&sanitized_transaction, true);
+        invocation_trace,
+    } = bank.simulate_transaction_unchecked(&sanitized_transaction, true, true);
 
     let simulation_details = TransactionSimulationDetails {
         logs,
@@ -199,6 +200,7 @@ fn simulate_transaction(
         loaded_accounts_data_size,
         return_data,
         inner_instructions,
+        invocation_trace,
     };
     BanksTransactionResultWithSimulation {
         result: Some(result),
@@ -364,6 +366,7 @@ impl Banks for BanksServer {
                     compute_units_consumed: details.executed_units,
                     log_messages: details.log_messages.unwrap_or_default(),
                     return_data: details.return_data,
+                    invocation_trace: details.invocation_trace.unwrap_or_default(),
                 }),
             },
         }
@@ -127,6 +127,20 @@ pub static BUILTINS: &[BuiltinPrototype] = &[
         program_id: solana_sdk_ids::zk_elgamal_proof_program::id(),
         entrypoint: solana_zk_elgamal_proof_program::Entrypoint::vm,
     }),
+    testable_prototype!(BuiltinPrototype {
+        core_bpf_migration_config: None,
+        name: upgrade_timelock_program,
+        enable_feature_id: Some(feature_set::enable_upgrade_timelock_program::id()),
+        program_id: solana_upgrade_timelock_interface::id(),
+        entrypoint: solana_upgrade_timelock_program::Entrypoint::vm,
+    }),
+    testable_prototype!(BuiltinPrototype {
+        core_bpf_migration_config: None,
+        name: multisig_program,
+        enable_feature_id: Some(feature_set::enable_multisig_program::id()),
+        program_id: solana_multisig_interface::id(),
+        entrypoint: solana_multisig_program::Entrypoint::vm,
+    }),
 ];
 
 pub static STATELESS_BUILTINS: &[StatelessBuiltinPrototype] = &[StatelessBuiltinPrototype {
@@ -353,6 +367,46 @@ pub mod test_only {
             datapoint_name: "migrate_builtin_to_core_bpf_zk_elgamal_proof_program",
         };
     }
+
+    pub mod upgrade_timelock_program {
+        pub mod feature {
+            solana_pubkey::declare_id!("6TDSwWmnY4nfoXhyAKwTGS5F1LYoV3XMcuSNquRxYsEY");
+        }
+        pub mod source_buffer {
+            solana_pubkey::declare_id!("84dftFnjSedamdmMDc4hMVHTgB5kMPYVJtnJWfv3hKJ8");
+        }
+        pub mod upgrade_authority {
+            solana_pubkey::declare_id!("9Lnjsw25UkmoXWfnLJBioJT4MDRNPfiJpUqDYuxqQx19");
+        }
+        pub const CONFIG: super::CoreBpfMigrationConfig = super::CoreBpfMigrationConfig {
+            source_buffer_address: source_buffer::id(),
+            upgrade_authority_address: Some(upgrade_authority::id()),
+            feature_id: feature::id(),
+            migration_target: super::CoreBpfMigrationTargetType::Builtin,
+            verified_build_hash: None,
+            datapoint_name: "migrate_builtin_to_core_bpf_upgrade_timelock_program",
+        };
+    }
+
+    pub mod multisig_program {
+        pub mod feature {
+            solana_pubkey::declare_id!("4ssthyoJXwW6Uy5iskgPCrepsCLAwexDT2Qibbp6a3iD");
+        }
+        pub mod source_buffer {
+            solana_pubkey::declare_id!("BqUBm3uW6BLvrLPuPg5GR5T8Xgp3Wfhuctdi6RZwqJEH");
+        }
+        pub mod upgrade_authority {
+            solana_pubkey::declare_id!("E7RZid6BVYh7mrkDRGKE3pBr7tCXeNq4fcpSa7HWNuLQ");
+        }
+        pub const CONFIG: super::CoreBpfMigrationConfig = super::CoreBpfMigrationConfig {
+            source_buffer_address: source_buffer::id(),
+            upgrade_authority_address: Some(upgrade_authority::id()),
+            feature_id: feature::id(),
+            migration_target: super::CoreBpfMigrationTargetType::Builtin,
+            verified_build_hash: None,
+            datapoint_name: "migrate_builtin_to_core_bpf_multisig_program",
+        };
+    }
 }
 
 #[cfg(test)]
@@ -400,5 +454,13 @@ mod tests {
             &super::BUILTINS[9].core_bpf_migration_config,
             &Some(super::test_only::zk_elgamal_proof_program::CONFIG)
         );
+        assert_eq!(
+            &super::BUILTINS[10].core_bpf_migration_config,
+            &Some(super::test_only::upgrade_timelock_program::CONFIG)
+        );
+        assert_eq!(
+            &super::BUILTINS[11].core_bpf_migration_config,
+            &Some(super::test_only::multisig_program::CONFIG)
+        );
     }
 }
@@ -247,6 +247,19 @@ EOF
       "test-shuttle skipped as no relevant files were modified"
   fi
 
+  # XDP tests over a veth pair, they need root to set it up
+  if affects \
+             ^xdp/ \
+             ^streamer/src/xdp.rs \
+             ^streamer/tests/xdp.rs \
+             ^ci/test-xdp-veth.sh \
+      ; then
+    command_step xdp-veth "sudo ci/test-xdp-veth.sh" 10
+  else
+    annotate --style info \
+      "test-xdp-veth skipped as no relevant files were modified"
+  fi
+
   # Coverage...
   if affects \
              .rs$ \
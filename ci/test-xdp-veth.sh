@@ -0,0 +1,36 @@
+#!/usr/bin/env bash
+#
+# Runs the XDP receive tests over a veth pair. Requires root.
+#
+
+set -eo pipefail
+
+source ci/_
+
+netns=agave-xdp-test
+interface=agave-xdp0
+peer_interface=agave-xdp1
+addr=10.201.0.1
+peer_addr=10.201.0.2
+
+cleanup() {
+  ip link del "$interface" 2>/dev/null || true
+  ip netns del "$netns" 2>/dev/null || true
+}
+trap cleanup EXIT
+cleanup
+
+ip netns add "$netns"
+ip link add "$interface" type veth peer name "$peer_interface"
+ip link set "$peer_interface" netns "$netns"
+ip addr add "$addr/24" dev "$interface"
+ip link set "$interface" up
+ip netns exec "$netns" ip addr add "$peer_addr/24" dev "$peer_interface"
+ip netns exec "$netns" ip link set "$peer_interface" up
+ip netns exec "$netns" ip link set lo up
+
+export AGAVE_XDP_TEST_INTERFACE="$interface"
+export AGAVE_XDP_TEST_ADDR="$addr"
+export AGAVE_XDP_TEST_PEER_NETNS="$netns"
+
+_ cargo test -p solana-streamer --test xdp -- --ignored --test-threads 1
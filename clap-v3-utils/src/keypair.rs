@@ -460,8 +460,8 @@ pub struct SignerFromPathConfig {
 ///   phrases.
 ///
 /// - `usb:` &mdash; Use a USB hardware device as the signer. In this case, the
-///   URI host indicates the device type, and is required. The only currently valid host
-///   value is "ledger".
+///   URI host indicates the device type, and is required. The currently valid host
+///   values are "ledger" and "trezor".
 ///
 ///   Optionally, the first segment of the URI path indicates the base-58
 ///   encoded pubkey of the wallet, and the "account" and "change" indices of
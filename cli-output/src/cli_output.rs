@@ -36,8 +36,8 @@ use {
     solana_sysvar::stake_history::StakeHistoryEntry,
     solana_transaction::{versioned::VersionedTransaction, Transaction},
     solana_transaction_status::{
-        EncodedConfirmedBlock, EncodedTransaction, TransactionConfirmationStatus,
-        UiTransactionStatusMeta,
+        EncodedConfirmedBlock, EncodedTransaction, TransactionConfirmationStatus, UiInstruction,
+        UiParsedInstruction, UiTransactionStatusMeta,
     },
     solana_transaction_status_client_types::UiTransactionError,
     solana_vote_program::{
@@ -1699,6 +1699,8 @@ pub struct CliVoteAccount {
     pub authorized_withdrawer: String,
     pub credits: u64,
     pub commission: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_commission: Option<CliPendingCommission>,
     pub root_slot: Option<Slot>,
     pub recent_timestamp: BlockTimestamp,
     pub votes: Vec<CliLandedVote>,
@@ -1726,6 +1728,13 @@ impl fmt::Display for CliVoteAccount {
         writeln!(f, "Withdraw Authority: {}", self.authorized_withdrawer)?;
         writeln!(f, "Credits: {}", self.credits)?;
         writeln!(f, "Commission: {}%", self.commission)?;
+        if let Some(pending_commission) = &self.pending_commission {
+            writeln!(
+                f,
+                "  New Commission for Epoch {} Rewards: {}%",
+                pending_commission.effective_epoch, pending_commission.commission
+            )?;
+        }
         writeln!(
             f,
             "Root Slot: {}",
@@ -1746,6 +1755,13 @@ impl fmt::Display for CliVoteAccount {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliPendingCommission {
+    pub commission: u8,
+    pub effective_epoch: Epoch,
+}
+
 #[derive(Default, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CliAuthorizedVoters {
@@ -1987,6 +2003,100 @@ impl fmt::Display for CliSignOnlyData {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CliTransactionProposalSigner {
+    pub pubkey: String,
+    pub status: CliSignatureVerificationStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CliTransactionProposal {
+    pub fee_payer: String,
+    pub blockhash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce_account: Option<String>,
+    pub instructions: Vec<UiInstruction>,
+    pub signers: Vec<CliTransactionProposalSigner>,
+}
+
+impl CliTransactionProposal {
+    pub fn is_complete(&self) -> bool {
+        self.signers
+            .iter()
+            .all(|signer| signer.status == CliSignatureVerificationStatus::Pass)
+    }
+}
+
+impl QuietDisplay for CliTransactionProposal {}
+impl VerboseDisplay for CliTransactionProposal {}
+
+impl fmt::Display for CliTransactionProposal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        writeln_name_value(f, "Fee Payer:", &self.fee_payer)?;
+        match &self.nonce_account {
+            Some(nonce_account) => {
+                writeln_name_value(f, "Nonce Account:", nonce_account)?;
+                writeln_name_value(f, "Nonce:", &self.blockhash)?;
+            }
+            None => {
+                writeln_name_value(f, "Blockhash:", &self.blockhash)?;
+                writeln!(
+                    f,
+                    "{WARNING} The transaction doesn't use a durable nonce and will expire \
+                     shortly after its blockhash"
+                )?;
+            }
+        }
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            writeln!(f, "{}", style(format!("Instruction {index}:")).bold())?;
+            match instruction {
+                UiInstruction::Parsed(UiParsedInstruction::Parsed(instruction)) => {
+                    writeln!(
+                        f,
+                        "  Program: {} ({})",
+                        instruction.program, instruction.program_id
+                    )?;
+                    let parsed = serde_json::to_string_pretty(&instruction.parsed)
+                        .map_err(|_| fmt::Error)?;
+                    for line in parsed.lines() {
+                        writeln!(f, "  {line}")?;
+                    }
+                }
+                UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(instruction)) => {
+                    writeln!(f, "  Program: {}", instruction.program_id)?;
+                    for (index, account) in instruction.accounts.iter().enumerate() {
+                        writeln!(f, "  Account {index}: {account}")?;
+                    }
+                    writeln!(f, "  Data: {}", instruction.data)?;
+                }
+                UiInstruction::Compiled(instruction) => {
+                    writeln!(f, "  Program Index: {}", instruction.program_id_index)?;
+                    writeln!(f, "  Account Indexes: {:?}", instruction.accounts)?;
+                    writeln!(f, "  Data: {}", instruction.data)?;
+                }
+            }
+        }
+        writeln!(f, "{}", style("Signers:").bold())?;
+        for signer in &self.signers {
+            writeln!(f, "  {:<44}  {}", signer.pubkey, signer.status)?;
+        }
+        let missing = self
+            .signers
+            .iter()
+            .filter(|signer| signer.status != CliSignatureVerificationStatus::Pass)
+            .count();
+        if missing == 0 {
+            writeln!(f, "{CHECK_MARK}Ready to submit")?;
+        } else {
+            writeln!(f, "{CROSS_MARK}Missing {missing} valid signature(s)")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CliSignature {
@@ -2289,6 +2399,12 @@ pub struct CliProgramV4 {
     pub last_deploy_slot: u64,
     pub status: String,
     pub data_len: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_elf_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorded_elf_hash: Option<CliRecordedElfHash>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upgrade_timelock: Option<CliUpgradeTimelock>,
 }
 impl QuietDisplay for CliProgramV4 {}
 impl VerboseDisplay for CliProgramV4 {}
@@ -2309,10 +2425,99 @@ impl fmt::Display for CliProgramV4 {
             "Data Length:",
             &format!("{:?} ({:#x?}) bytes", self.data_len, self.data_len),
         )?;
+        if let Some(current_elf_hash) = &self.current_elf_hash {
+            writeln_name_value(f, "Current ELF Hash:", current_elf_hash)?;
+        }
+        if let Some(recorded_elf_hash) = &self.recorded_elf_hash {
+            writeln_name_value(
+                f,
+                "Recorded ELF Hash:",
+                &format!(
+                    "{} (deployed in slot {})",
+                    recorded_elf_hash.elf_hash, recorded_elf_hash.slot
+                ),
+            )?;
+        }
+        if let Some(upgrade_timelock) = &self.upgrade_timelock {
+            writeln_name_value(f, "Timelock Authority:", &upgrade_timelock.authority)?;
+            writeln_name_value(
+                f,
+                "Timelock Delay:",
+                &format!("{} slots", upgrade_timelock.delay_slots),
+            )?;
+            if let Some(pending_upgrade) = &upgrade_timelock.pending_upgrade {
+                writeln!(f, "Pending Upgrade:")?;
+                writeln_name_value(f, "  Buffer:", &pending_upgrade.buffer)?;
+                writeln_name_value(f, "  Buffer Hash:", &pending_upgrade.buffer_hash)?;
+                writeln_name_value(
+                    f,
+                    "  Staged In Slot:",
+                    &pending_upgrade.staged_slot.to_string(),
+                )?;
+                writeln_name_value(
+                    f,
+                    "  Applicable From Slot:",
+                    &pending_upgrade.earliest_slot.to_string(),
+                )?;
+            } else {
+                writeln_name_value(f, "Pending Upgrade:", "none")?;
+            }
+            if let Some(pending_release) = &upgrade_timelock.pending_release {
+                writeln!(f, "Pending Authority Release:")?;
+                writeln_name_value(f, "  New Authority:", &pending_release.new_authority)?;
+                writeln_name_value(
+                    f,
+                    "  Staged In Slot:",
+                    &pending_release.staged_slot.to_string(),
+                )?;
+                writeln_name_value(
+                    f,
+                    "  Applicable From Slot:",
+                    &pending_release.earliest_slot.to_string(),
+                )?;
+            }
+        }
         Ok(())
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliRecordedElfHash {
+    pub address: String,
+    pub elf_hash: String,
+    pub slot: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliUpgradeTimelock {
+    pub address: String,
+    pub authority: String,
+    pub delay_slots: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_upgrade: Option<CliPendingUpgrade>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_release: Option<CliPendingRelease>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliPendingUpgrade {
+    pub buffer: String,
+    pub buffer_hash: String,
+    pub staged_slot: Slot,
+    pub earliest_slot: Slot,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliPendingRelease {
+    pub new_authority: String,
+    pub staged_slot: Slot,
+    pub earliest_slot: Slot,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CliProgramsV4 {
@@ -2625,6 +2830,114 @@ impl fmt::Display for CliAddressLookupTableCreated {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CliMultisig {
+    pub multisig_address: String,
+    pub vault_address: String,
+    pub vault_balance: u64,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub use_lamports_unit: bool,
+    pub threshold: u8,
+    pub signers: Vec<String>,
+    pub proposal_count: u64,
+    pub proposals: Vec<CliMultisigProposal>,
+}
+impl QuietDisplay for CliMultisig {}
+impl VerboseDisplay for CliMultisig {}
+impl fmt::Display for CliMultisig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        writeln_name_value(f, "Multisig Address:", &self.multisig_address)?;
+        writeln_name_value(f, "Vault Address:", &self.vault_address)?;
+        writeln_name_value(
+            f,
+            "Vault Balance:",
+            &build_balance_message(self.vault_balance, self.use_lamports_unit, true),
+        )?;
+        writeln_name_value(
+            f,
+            "Threshold:",
+            &format!("{} of {}", self.threshold, self.signers.len()),
+        )?;
+        writeln!(f, "{}", style("Signers:".to_string()).bold())?;
+        for (index, signer) in self.signers.iter().enumerate() {
+            writeln!(f, "  {index:<5}  {signer}")?;
+        }
+        writeln_name_value(f, "Proposal Count:", &self.proposal_count.to_string())?;
+        if self.proposals.is_empty() {
+            writeln_name_value(f, "Pending Proposals:", "None")?;
+        } else {
+            writeln!(f, "{}", style("Pending Proposals:".to_string()).bold())?;
+            for proposal in &self.proposals {
+                writeln!(f)?;
+                write!(f, "{proposal}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CliMultisigProposal {
+    pub proposal_address: String,
+    pub proposal_index: u64,
+    pub proposer: String,
+    pub approvals: Vec<String>,
+    pub instruction_count: usize,
+}
+impl QuietDisplay for CliMultisigProposal {}
+impl VerboseDisplay for CliMultisigProposal {}
+impl fmt::Display for CliMultisigProposal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln_name_value(f, "  Proposal Index:", &self.proposal_index.to_string())?;
+        writeln_name_value(f, "  Proposal Address:", &self.proposal_address)?;
+        writeln_name_value(f, "  Proposer:", &self.proposer)?;
+        writeln_name_value(f, "  Instructions:", &self.instruction_count.to_string())?;
+        writeln_name_value(f, "  Approvals:", &self.approvals.join(", "))?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliMultisigCreated {
+    pub multisig_address: String,
+    pub vault_address: String,
+    pub signature: String,
+}
+impl QuietDisplay for CliMultisigCreated {}
+impl VerboseDisplay for CliMultisigCreated {}
+impl fmt::Display for CliMultisigCreated {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        writeln_name_value(f, "Signature:", &self.signature)?;
+        writeln_name_value(f, "Multisig Address:", &self.multisig_address)?;
+        writeln_name_value(f, "Vault Address:", &self.vault_address)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliMultisigProposalCreated {
+    pub proposal_address: String,
+    pub proposal_index: u64,
+    pub signature: String,
+}
+impl QuietDisplay for CliMultisigProposalCreated {}
+impl VerboseDisplay for CliMultisigProposalCreated {}
+impl fmt::Display for CliMultisigProposalCreated {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        writeln_name_value(f, "Signature:", &self.signature)?;
+        writeln_name_value(f, "Proposal Index:", &self.proposal_index.to_string())?;
+        writeln_name_value(f, "Proposal Address:", &self.proposal_address)?;
+        Ok(())
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ReturnSignersConfig {
     pub dump_transaction_message: bool,
@@ -2740,7 +3053,7 @@ pub fn parse_sign_only_reply_string(reply: &str) -> SignOnly {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum CliSignatureVerificationStatus {
     None,
@@ -1,7 +1,8 @@
 use {
     crate::{
         address_lookup_table::AddressLookupTableSubCommands, cli::*, cluster_query::*, feature::*,
-        inflation::*, nonce::*, program::*, program_v4::ProgramV4SubCommands, stake::*,
+        inflation::*, multisig::MultisigSubCommands, nonce::*, program::*,
+        program_v4::ProgramV4SubCommands, stake::*, transaction::TransactionSubCommands,
         validator_info::*, vote::*, wallet::*,
     },
     clap::{App, AppSettings, Arg, ArgGroup, SubCommand},
@@ -224,7 +225,9 @@ pub fn get_clap_app<'a>(name: &'a str, about: &'a str, version: &'a str) -> App<
         .program_subcommands()
         .program_v4_subcommands()
         .address_lookup_table_subcommands()
+        .multisig_subcommands()
         .stake_subcommands()
+        .transaction_subcommands()
         .validator_info_subcommands()
         .vote_subcommands()
         .wallet_subcommands()
@@ -1,7 +1,8 @@
 use {
     crate::{
-        address_lookup_table::*, clap_app::*, cluster_query::*, feature::*, inflation::*, nonce::*,
-        program::*, program_v4::*, spend_utils::*, stake::*, validator_info::*, vote::*, wallet::*,
+        address_lookup_table::*, clap_app::*, cluster_query::*, feature::*, inflation::*,
+        multisig::*, nonce::*, program::*, program_v4::*, spend_utils::*, stake::*, transaction::*,
+        validator_info::*, vote::*, wallet::*,
     },
     clap::{value_t_or_exit, ArgMatches},
     log::*,
@@ -454,6 +455,10 @@ pub enum CliCommand {
     },
     // Address lookup table commands
     AddressLookupTable(AddressLookupTableCliCommand),
+    // Native multisig program commands
+    Multisig(MultisigCliCommand),
+    // Multisig transaction proposal commands
+    Transaction(TransactionCliCommand),
     SignOffchainMessage {
         message: OffchainMessage,
     },
@@ -704,6 +709,12 @@ pub fn parse_command(
         Some(("address-lookup-table", matches)) => {
             parse_address_lookup_table_subcommand(matches, default_signer, wallet_manager)
         }
+        Some(("multisig", matches)) => {
+            parse_multisig_subcommand(matches, default_signer, wallet_manager)
+        }
+        Some(("transaction", matches)) => {
+            parse_transaction_subcommand(matches, default_signer, wallet_manager)
+        }
         Some(("wait-for-max-stake", matches)) => {
             let max_stake_percent = value_t_or_exit!(matches, "max_percent", f32);
             Ok(CliCommandInfo::without_signers(
@@ -1708,6 +1719,14 @@ pub fn process_command(config: &CliConfig) -> ProcessResult {
         CliCommand::AddressLookupTable(subcommand) => {
             process_address_lookup_table_subcommand(rpc_client, config, subcommand)
         }
+        // Multisig Commands
+        CliCommand::Multisig(subcommand) => {
+            process_multisig_subcommand(rpc_client, config, subcommand)
+        }
+        // Transaction Proposal Commands
+        CliCommand::Transaction(subcommand) => {
+            process_transaction_subcommand(rpc_client, config, subcommand)
+        }
         CliCommand::SignOffchainMessage { message } => {
             process_sign_offchain_message(config, message)
         }
@@ -31,12 +31,14 @@ pub mod compute_budget;
 pub mod feature;
 pub mod inflation;
 pub mod memo;
+pub mod multisig;
 pub mod nonce;
 pub mod program;
 pub mod program_v4;
 pub mod spend_utils;
 pub mod stake;
 pub mod test_utils;
+pub mod transaction;
 pub mod validator_info;
 pub mod vote;
 pub mod wallet;
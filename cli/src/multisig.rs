@@ -0,0 +1,742 @@
+use {
+    crate::cli::{CliCommand, CliCommandInfo, CliConfig, CliError, ProcessResult},
+    clap::{App, AppSettings, Arg, ArgMatches, SubCommand},
+    solana_clap_v3_utils::{input_parsers::*, input_validators::*, keypair::*},
+    solana_cli_output::{
+        CliMultisig, CliMultisigCreated, CliMultisigProposal, CliMultisigProposalCreated,
+        CliSignature,
+    },
+    solana_instruction::Instruction,
+    solana_message::Message,
+    solana_multisig_interface::{
+        self as multisig_program, get_proposal_address, get_vault_address,
+        instruction::{approve, create_multisig, execute, propose},
+        state::{Multisig, Proposal, ProposalStatus},
+        MAX_SIGNERS,
+    },
+    solana_pubkey::Pubkey,
+    solana_remote_wallet::remote_wallet::RemoteWalletManager,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_api::config::RpcSendTransactionConfig,
+    solana_signer::Signer,
+    solana_system_interface::instruction::{authorize_nonce_account, transfer},
+    solana_transaction::Transaction,
+    std::{rc::Rc, sync::Arc},
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MultisigCliCommand {
+    Create {
+        multisig_signer_index: SignerIndex,
+        threshold: u8,
+        signers: Vec<Pubkey>,
+    },
+    Show {
+        multisig_pubkey: Pubkey,
+        use_lamports_unit: bool,
+    },
+    ProposeTransfer {
+        multisig_pubkey: Pubkey,
+        proposer_signer_index: SignerIndex,
+        to_pubkey: Pubkey,
+        lamports: u64,
+    },
+    ProposeAuthorizeNonce {
+        multisig_pubkey: Pubkey,
+        proposer_signer_index: SignerIndex,
+        nonce_account_pubkey: Pubkey,
+        new_authority_pubkey: Pubkey,
+    },
+    Approve {
+        multisig_pubkey: Pubkey,
+        proposal_index: u64,
+        member_signer_index: SignerIndex,
+    },
+    Execute {
+        multisig_pubkey: Pubkey,
+        proposal_index: u64,
+    },
+}
+
+pub trait MultisigSubCommands {
+    fn multisig_subcommands(self) -> Self;
+}
+
+fn multisig_address_arg<'a>() -> Arg<'a> {
+    Arg::with_name("multisig_address")
+        .index(1)
+        .value_name("MULTISIG_ADDRESS")
+        .takes_value(true)
+        .required(true)
+        .validator(is_pubkey)
+        .help("Address of the multisig")
+}
+
+fn proposal_index_arg<'a>() -> Arg<'a> {
+    Arg::with_name("proposal_index")
+        .index(2)
+        .value_name("PROPOSAL_INDEX")
+        .takes_value(true)
+        .required(true)
+        .validator(is_parsable::<u64>)
+        .help("Index of the proposal")
+}
+
+fn proposer_arg<'a>() -> Arg<'a> {
+    Arg::with_name("proposer")
+        .long("proposer")
+        .value_name("PROPOSER_SIGNER")
+        .takes_value(true)
+        .validator(crate::clap_app::validate_signer)
+        .help(
+            "Member of the multisig making the proposal \
+             [default: the default configured keypair]",
+        )
+}
+
+impl<'a> MultisigSubCommands for App<'a> {
+    fn multisig_subcommands(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("multisig")
+                .about("Native M-of-N multisig management")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("create")
+                        .about("Create a multisig account")
+                        .arg(
+                            Arg::with_name("multisig_keypair")
+                                .index(1)
+                                .value_name("MULTISIG_ACCOUNT_KEYPAIR")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(crate::clap_app::validate_signer)
+                                .help("Keypair of the multisig account to create"),
+                        )
+                        .arg(
+                            Arg::with_name("threshold")
+                                .long("threshold")
+                                .value_name("NUMBER")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_parsable::<u8>)
+                                .help("Number of approvals required to execute a proposal"),
+                        )
+                        .arg(
+                            Arg::with_name("signers")
+                                .long("signers")
+                                .value_name("ADDRESS_1,ADDRESS_2")
+                                .takes_value(true)
+                                .use_delimiter(true)
+                                .required(true)
+                                .validator(is_pubkey)
+                                .help(
+                                    "Comma separated list of the members which can propose and \
+                                     approve",
+                                ),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("show")
+                        .about("Display information about a multisig and its pending proposals")
+                        .arg(multisig_address_arg())
+                        .arg(
+                            Arg::with_name("lamports")
+                                .long("lamports")
+                                .takes_value(false)
+                                .help("Display balance in lamports instead of SOL"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("propose-transfer")
+                        .about("Propose a transfer from the vault of a multisig")
+                        .arg(multisig_address_arg())
+                        .arg(
+                            Arg::with_name("to")
+                                .index(2)
+                                .value_name("RECIPIENT_ADDRESS")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_pubkey)
+                                .help("Address of the recipient"),
+                        )
+                        .arg(
+                            Arg::with_name("amount")
+                                .index(3)
+                                .value_name("AMOUNT")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(crate::clap_app::validate_amount)
+                                .help("The amount to transfer, in SOL"),
+                        )
+                        .arg(proposer_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("propose-authorize-nonce")
+                        .about(
+                            "Propose to change the authority of a nonce account held by the \
+                             vault of a multisig",
+                        )
+                        .arg(multisig_address_arg())
+                        .arg(
+                            Arg::with_name("nonce_account")
+                                .index(2)
+                                .value_name("NONCE_ACCOUNT_ADDRESS")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_pubkey)
+                                .help("Address of the nonce account"),
+                        )
+                        .arg(
+                            Arg::with_name("new_authority")
+                                .index(3)
+                                .value_name("AUTHORITY_PUBKEY")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_pubkey)
+                                .help("Account to be granted authority of the nonce account"),
+                        )
+                        .arg(proposer_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("approve")
+                        .about("Approve a pending proposal")
+                        .arg(multisig_address_arg())
+                        .arg(proposal_index_arg())
+                        .arg(
+                            Arg::with_name("member")
+                                .long("member")
+                                .value_name("MEMBER_SIGNER")
+                                .takes_value(true)
+                                .validator(crate::clap_app::validate_signer)
+                                .help(
+                                    "Member of the multisig approving the proposal \
+                                     [default: the default configured keypair]",
+                                ),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("execute")
+                        .about("Execute a proposal which has enough approvals")
+                        .arg(multisig_address_arg())
+                        .arg(proposal_index_arg()),
+                ),
+        )
+    }
+}
+
+fn member_signer_of(
+    matches: &ArgMatches,
+    name: &str,
+    default_signer: &DefaultSigner,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+    bulk_signers: &mut Vec<Option<Box<dyn Signer>>>,
+) -> Result<Option<Pubkey>, CliError> {
+    if let Ok((member_signer, Some(member_pubkey))) = signer_of(matches, name, wallet_manager) {
+        bulk_signers.push(member_signer);
+        Ok(Some(member_pubkey))
+    } else {
+        Ok(Some(
+            default_signer
+                .signer_from_path(matches, wallet_manager)?
+                .pubkey(),
+        ))
+    }
+}
+
+pub fn parse_multisig_subcommand(
+    matches: &ArgMatches,
+    default_signer: &DefaultSigner,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    let (subcommand, sub_matches) = match matches.subcommand() {
+        Some((subcommand, sub_matches)) => (subcommand, sub_matches),
+        None => {
+            return Err(CliError::CommandNotRecognized(
+                "No subcommand specified".to_string(),
+            ))
+        }
+    };
+
+    let response = match (subcommand, sub_matches) {
+        ("create", matches) => {
+            let (multisig_signer, multisig_pubkey) =
+                signer_of(matches, "multisig_keypair", wallet_manager)?;
+            let threshold = value_of(matches, "threshold").unwrap();
+            let signers: Vec<Pubkey> = values_of(matches, "signers").unwrap();
+
+            let signer_info = default_signer.generate_unique_signers(
+                vec![None, multisig_signer],
+                matches,
+                wallet_manager,
+            )?;
+
+            CliCommandInfo {
+                command: CliCommand::Multisig(MultisigCliCommand::Create {
+                    multisig_signer_index: signer_info.index_of(multisig_pubkey).unwrap(),
+                    threshold,
+                    signers,
+                }),
+                signers: signer_info.signers,
+            }
+        }
+        ("show", matches) => {
+            let multisig_pubkey = pubkey_of(matches, "multisig_address").unwrap();
+
+            CliCommandInfo::without_signers(CliCommand::Multisig(MultisigCliCommand::Show {
+                multisig_pubkey,
+                use_lamports_unit: matches.is_present("lamports"),
+            }))
+        }
+        ("propose-transfer", matches) => {
+            let multisig_pubkey = pubkey_of(matches, "multisig_address").unwrap();
+            let to_pubkey = pubkey_of(matches, "to").unwrap();
+            let lamports = lamports_of_sol(matches, "amount").unwrap();
+
+            let mut bulk_signers = vec![Some(
+                default_signer.signer_from_path(matches, wallet_manager)?,
+            )];
+            let proposer_pubkey = member_signer_of(
+                matches,
+                "proposer",
+                default_signer,
+                wallet_manager,
+                &mut bulk_signers,
+            )?;
+
+            let signer_info =
+                default_signer.generate_unique_signers(bulk_signers, matches, wallet_manager)?;
+
+            CliCommandInfo {
+                command: CliCommand::Multisig(MultisigCliCommand::ProposeTransfer {
+                    multisig_pubkey,
+                    proposer_signer_index: signer_info.index_of(proposer_pubkey).unwrap(),
+                    to_pubkey,
+                    lamports,
+                }),
+                signers: signer_info.signers,
+            }
+        }
+        ("propose-authorize-nonce", matches) => {
+            let multisig_pubkey = pubkey_of(matches, "multisig_address").unwrap();
+            let nonce_account_pubkey = pubkey_of(matches, "nonce_account").unwrap();
+            let new_authority_pubkey = pubkey_of(matches, "new_authority").unwrap();
+
+            let mut bulk_signers = vec![Some(
+                default_signer.signer_from_path(matches, wallet_manager)?,
+            )];
+            let proposer_pubkey = member_signer_of(
+                matches,
+                "proposer",
+                default_signer,
+                wallet_manager,
+                &mut bulk_signers,
+            )?;
+
+            let signer_info =
+                default_signer.generate_unique_signers(bulk_signers, matches, wallet_manager)?;
+
+            CliCommandInfo {
+                command: CliCommand::Multisig(MultisigCliCommand::ProposeAuthorizeNonce {
+                    multisig_pubkey,
+                    proposer_signer_index: signer_info.index_of(proposer_pubkey).unwrap(),
+                    nonce_account_pubkey,
+                    new_authority_pubkey,
+                }),
+                signers: signer_info.signers,
+            }
+        }
+        ("approve", matches) => {
+            let multisig_pubkey = pubkey_of(matches, "multisig_address").unwrap();
+            let proposal_index = value_of(matches, "proposal_index").unwrap();
+
+            let mut bulk_signers = vec![Some(
+                default_signer.signer_from_path(matches, wallet_manager)?,
+            )];
+            let member_pubkey = member_signer_of(
+                matches,
+                "member",
+                default_signer,
+                wallet_manager,
+                &mut bulk_signers,
+            )?;
+
+            let signer_info =
+                default_signer.generate_unique_signers(bulk_signers, matches, wallet_manager)?;
+
+            CliCommandInfo {
+                command: CliCommand::Multisig(MultisigCliCommand::Approve {
+                    multisig_pubkey,
+                    proposal_index,
+                    member_signer_index: signer_info.index_of(member_pubkey).unwrap(),
+                }),
+                signers: signer_info.signers,
+            }
+        }
+        ("execute", matches) => {
+            let multisig_pubkey = pubkey_of(matches, "multisig_address").unwrap();
+            let proposal_index = value_of(matches, "proposal_index").unwrap();
+
+            let signer_info =
+                default_signer.generate_unique_signers(vec![None], matches, wallet_manager)?;
+
+            CliCommandInfo {
+                command: CliCommand::Multisig(MultisigCliCommand::Execute {
+                    multisig_pubkey,
+                    proposal_index,
+                }),
+                signers: signer_info.signers,
+            }
+        }
+        _ => unreachable!(),
+    };
+    Ok(response)
+}
+
+pub fn process_multisig_subcommand(
+    rpc_client: Arc<RpcClient>,
+    config: &CliConfig,
+    subcommand: &MultisigCliCommand,
+) -> ProcessResult {
+    match subcommand {
+        MultisigCliCommand::Create {
+            multisig_signer_index,
+            threshold,
+            signers,
+        } => process_create_multisig(
+            &rpc_client,
+            config,
+            *multisig_signer_index,
+            *threshold,
+            signers.to_vec(),
+        ),
+        MultisigCliCommand::Show {
+            multisig_pubkey,
+            use_lamports_unit,
+        } => process_show_multisig(&rpc_client, config, *multisig_pubkey, *use_lamports_unit),
+        MultisigCliCommand::ProposeTransfer {
+            multisig_pubkey,
+            proposer_signer_index,
+            to_pubkey,
+            lamports,
+        } => {
+            let vault_pubkey = get_vault_address(multisig_pubkey);
+            process_propose(
+                &rpc_client,
+                config,
+                *multisig_pubkey,
+                *proposer_signer_index,
+                vec![transfer(&vault_pubkey, to_pubkey, *lamports)],
+            )
+        }
+        MultisigCliCommand::ProposeAuthorizeNonce {
+            multisig_pubkey,
+            proposer_signer_index,
+            nonce_account_pubkey,
+            new_authority_pubkey,
+        } => {
+            let vault_pubkey = get_vault_address(multisig_pubkey);
+            process_propose(
+                &rpc_client,
+                config,
+                *multisig_pubkey,
+                *proposer_signer_index,
+                vec![authorize_nonce_account(
+                    nonce_account_pubkey,
+                    &vault_pubkey,
+                    new_authority_pubkey,
+                )],
+            )
+        }
+        MultisigCliCommand::Approve {
+            multisig_pubkey,
+            proposal_index,
+            member_signer_index,
+        } => process_approve(
+            &rpc_client,
+            config,
+            *multisig_pubkey,
+            *proposal_index,
+            *member_signer_index,
+        ),
+        MultisigCliCommand::Execute {
+            multisig_pubkey,
+            proposal_index,
+        } => process_execute(&rpc_client, config, *multisig_pubkey, *proposal_index),
+    }
+}
+
+fn get_multisig(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    multisig_pubkey: &Pubkey,
+) -> Result<Multisig, Box<dyn std::error::Error>> {
+    let multisig_account = rpc_client
+        .get_account_with_commitment(multisig_pubkey, config.commitment)?
+        .value
+        .ok_or_else(|| format!("Multisig account {multisig_pubkey} not found"))?;
+    if !multisig_program::check_id(&multisig_account.owner) {
+        return Err(
+            format!("Account {multisig_pubkey} is not owned by the multisig program").into(),
+        );
+    }
+    Ok(Multisig::deserialize(&multisig_account.data)?)
+}
+
+fn get_proposal(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    multisig_pubkey: &Pubkey,
+    proposal_index: u64,
+) -> Result<Proposal, Box<dyn std::error::Error>> {
+    let proposal_pubkey = get_proposal_address(multisig_pubkey, proposal_index);
+    let proposal_account = rpc_client
+        .get_account_with_commitment(&proposal_pubkey, config.commitment)?
+        .value
+        .ok_or_else(|| {
+            format!("Proposal {proposal_index} of multisig {multisig_pubkey} not found")
+        })?;
+    if !multisig_program::check_id(&proposal_account.owner) {
+        return Err(
+            format!("Account {proposal_pubkey} is not owned by the multisig program").into(),
+        );
+    }
+    Ok(Proposal::deserialize(&proposal_account.data)?)
+}
+
+fn send_multisig_transaction(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    instruction: Instruction,
+    signers: &[&dyn Signer],
+) -> Result<String, Box<dyn std::error::Error>> {
+    let blockhash = rpc_client.get_latest_blockhash()?;
+    let mut tx = Transaction::new_unsigned(Message::new(
+        &[instruction],
+        Some(&config.signers[0].pubkey()),
+    ));
+    tx.try_sign(signers, blockhash)?;
+    let signature = rpc_client.send_and_confirm_transaction_with_spinner_and_config(
+        &tx,
+        config.commitment,
+        RpcSendTransactionConfig {
+            skip_preflight: false,
+            preflight_commitment: Some(config.commitment.commitment),
+            ..RpcSendTransactionConfig::default()
+        },
+    )?;
+    Ok(signature.to_string())
+}
+
+fn process_create_multisig(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    multisig_signer_index: usize,
+    threshold: u8,
+    signers: Vec<Pubkey>,
+) -> ProcessResult {
+    if signers.is_empty() || signers.len() > MAX_SIGNERS {
+        return Err(format!("A multisig must have between 1 and {MAX_SIGNERS} signers").into());
+    }
+    if threshold == 0 || usize::from(threshold) > signers.len() {
+        return Err(format!("Threshold must be between 1 and {}", signers.len()).into());
+    }
+
+    let multisig_signer = config.signers[multisig_signer_index];
+    let multisig_address = multisig_signer.pubkey();
+    let create_multisig_ix = create_multisig(
+        &multisig_address,
+        &config.signers[0].pubkey(),
+        threshold,
+        signers,
+    );
+
+    let result = send_multisig_transaction(
+        rpc_client,
+        config,
+        create_multisig_ix,
+        &[config.signers[0], multisig_signer],
+    );
+    match result {
+        Err(err) => Err(format!("Create failed: {err}").into()),
+        Ok(signature) => Ok(config.output_format.formatted_string(&CliMultisigCreated {
+            multisig_address: multisig_address.to_string(),
+            vault_address: get_vault_address(&multisig_address).to_string(),
+            signature,
+        })),
+    }
+}
+
+fn process_propose(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    multisig_pubkey: Pubkey,
+    proposer_signer_index: usize,
+    instructions: Vec<Instruction>,
+) -> ProcessResult {
+    let proposer_signer = config.signers[proposer_signer_index];
+    let proposer_pubkey = proposer_signer.pubkey();
+
+    let multisig = get_multisig(rpc_client, config, &multisig_pubkey)?;
+    if multisig.signer_index(&proposer_pubkey).is_none() {
+        return Err(
+            format!("{proposer_pubkey} is not a member of multisig {multisig_pubkey}").into(),
+        );
+    }
+
+    let proposal_index = multisig.proposal_count;
+    let propose_ix = propose(
+        &multisig_pubkey,
+        proposal_index,
+        &proposer_pubkey,
+        &config.signers[0].pubkey(),
+        instructions.into_iter().map(Into::into).collect(),
+    );
+
+    let result = send_multisig_transaction(
+        rpc_client,
+        config,
+        propose_ix,
+        &[config.signers[0], proposer_signer],
+    );
+    match result {
+        Err(err) => Err(format!("Propose failed: {err}").into()),
+        Ok(signature) => Ok(config
+            .output_format
+            .formatted_string(&CliMultisigProposalCreated {
+                proposal_address: get_proposal_address(&multisig_pubkey, proposal_index)
+                    .to_string(),
+                proposal_index,
+                signature,
+            })),
+    }
+}
+
+fn process_approve(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    multisig_pubkey: Pubkey,
+    proposal_index: u64,
+    member_signer_index: usize,
+) -> ProcessResult {
+    let member_signer = config.signers[member_signer_index];
+    let member_pubkey = member_signer.pubkey();
+
+    let multisig = get_multisig(rpc_client, config, &multisig_pubkey)?;
+    let signer_index = multisig
+        .signer_index(&member_pubkey)
+        .ok_or_else(|| format!("{member_pubkey} is not a member of multisig {multisig_pubkey}"))?;
+    let proposal = get_proposal(rpc_client, config, &multisig_pubkey, proposal_index)?;
+    if proposal.is_approved_by(signer_index) {
+        return Err(
+            format!("Proposal {proposal_index} was already approved by {member_pubkey}").into(),
+        );
+    }
+
+    let approve_ix = approve(&multisig_pubkey, proposal_index, &member_pubkey);
+    let result = send_multisig_transaction(
+        rpc_client,
+        config,
+        approve_ix,
+        &[config.signers[0], member_signer],
+    );
+    match result {
+        Err(err) => Err(format!("Approve failed: {err}").into()),
+        Ok(signature) => Ok(config
+            .output_format
+            .formatted_string(&CliSignature { signature })),
+    }
+}
+
+fn process_execute(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    multisig_pubkey: Pubkey,
+    proposal_index: u64,
+) -> ProcessResult {
+    let multisig = get_multisig(rpc_client, config, &multisig_pubkey)?;
+    let proposal = get_proposal(rpc_client, config, &multisig_pubkey, proposal_index)?;
+    if proposal.status == ProposalStatus::Executed {
+        return Err(format!("Proposal {proposal_index} was already executed").into());
+    }
+    if !multisig.is_current(proposal_index) {
+        return Err(format!(
+            "Proposal {proposal_index} is stale, the multisig configuration changed after it was \
+             made"
+        )
+        .into());
+    }
+    if proposal.approval_count() < u32::from(multisig.threshold) {
+        return Err(format!(
+            "Proposal {proposal_index} has {} of the {} required approvals",
+            proposal.approval_count(),
+            multisig.threshold,
+        )
+        .into());
+    }
+
+    let execute_ix = execute(&multisig_pubkey, proposal_index, &proposal.instructions);
+    let result = send_multisig_transaction(rpc_client, config, execute_ix, &[config.signers[0]]);
+    match result {
+        Err(err) => Err(format!("Execute failed: {err}").into()),
+        Ok(signature) => Ok(config
+            .output_format
+            .formatted_string(&CliSignature { signature })),
+    }
+}
+
+fn process_show_multisig(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    multisig_pubkey: Pubkey,
+    use_lamports_unit: bool,
+) -> ProcessResult {
+    let multisig = get_multisig(rpc_client, config, &multisig_pubkey)?;
+    let vault_pubkey = get_vault_address(&multisig_pubkey);
+    let vault_balance = rpc_client
+        .get_balance_with_commitment(&vault_pubkey, config.commitment)?
+        .value;
+
+    let proposal_pubkeys: Vec<Pubkey> = (multisig.stale_proposal_index..multisig.proposal_count)
+        .map(|proposal_index| get_proposal_address(&multisig_pubkey, proposal_index))
+        .collect();
+    let mut proposals = vec![];
+    for chunk in proposal_pubkeys.chunks(100) {
+        let accounts =
+            rpc_client.get_multiple_accounts_with_commitment(chunk, config.commitment)?;
+        for (proposal_pubkey, account) in chunk.iter().zip(accounts.value) {
+            let Some(proposal) = account
+                .filter(|account| multisig_program::check_id(&account.owner))
+                .and_then(|account| Proposal::deserialize(&account.data).ok())
+            else {
+                continue;
+            };
+            if proposal.status != ProposalStatus::Active {
+                continue;
+            }
+            proposals.push(CliMultisigProposal {
+                proposal_address: proposal_pubkey.to_string(),
+                proposal_index: proposal.index,
+                proposer: proposal.proposer.to_string(),
+                approvals: multisig
+                    .signers
+                    .iter()
+                    .enumerate()
+                    .filter(|(signer_index, _)| proposal.is_approved_by(*signer_index))
+                    .map(|(_, signer)| signer.to_string())
+                    .collect(),
+                instruction_count: proposal.instructions.len(),
+            });
+        }
+    }
+
+    Ok(config.output_format.formatted_string(&CliMultisig {
+        multisig_address: multisig_pubkey.to_string(),
+        vault_address: vault_pubkey.to_string(),
+        vault_balance,
+        use_lamports_unit,
+        threshold: multisig.threshold,
+        signers: multisig.signers.iter().map(ToString::to_string).collect(),
+        proposal_count: multisig.proposal_count,
+        proposals,
+    }))
+}
@@ -22,7 +22,8 @@ use {
         offline::{OfflineArgs, DUMP_TRANSACTION_MESSAGE, SIGN_ONLY_ARG},
     },
     solana_cli_output::{
-        return_signers_with_config, CliProgramId, CliProgramV4, CliProgramsV4, ReturnSignersConfig,
+        return_signers_with_config, CliPendingRelease, CliPendingUpgrade, CliProgramId,
+        CliProgramV4, CliProgramsV4, CliRecordedElfHash, CliUpgradeTimelock, ReturnSignersConfig,
     },
     solana_client::{
         connection_cache::ConnectionCache,
@@ -31,7 +32,7 @@ use {
         },
         tpu_client::{TpuClient, TpuClientConfig},
     },
-    solana_instruction::Instruction,
+    solana_instruction::{AccountMeta, Instruction},
     solana_loader_v4_interface::{
         instruction,
         state::{
@@ -39,6 +40,7 @@ use {
             LoaderV4Status::{self, Retracted},
         },
     },
+    solana_loader_v4_program::{get_elf_hash, get_elf_hash_record, get_elf_hash_record_address},
     solana_message::Message,
     solana_program_runtime::{
         execution_budget::SVMTransactionExecutionBudget, invoke_context::InvokeContext,
@@ -57,6 +59,7 @@ use {
     solana_signer::Signer,
     solana_system_interface::{instruction as system_instruction, MAX_PERMITTED_DATA_LENGTH},
     solana_transaction::Transaction,
+    solana_upgrade_timelock_interface::{get_timelock_address, state::UpgradeTimelock},
     std::{
         cmp::Ordering,
         fs::File,
@@ -128,6 +131,10 @@ pub enum ProgramV4CliCommand {
         account_pubkey: Option<Pubkey>,
         output_location: String,
     },
+    Verify {
+        program_address: Pubkey,
+        path_to_elf: String,
+    },
 }
 
 pub trait ProgramV4SubCommands {
@@ -347,6 +354,26 @@ impl<'a> ProgramV4SubCommands for App<'a> {
                                 .takes_value(true)
                                 .help("Executable program's address"),
                         ),
+                )
+                .subcommand(
+                    SubCommand::with_name("verify")
+                        .about("Verify that a deployed program matches a local build")
+                        .arg(
+                            Arg::with_name("path-to-elf")
+                                .index(1)
+                                .value_name("PATH-TO-ELF")
+                                .takes_value(true)
+                                .required(true)
+                                .help("./target/deploy/program.so"),
+                        )
+                        .arg(
+                            Arg::with_name("program-id")
+                                .long("program-id")
+                                .value_name("PROGRAM_ID")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Executable program's address"),
+                        ),
                 ),
         )
     }
@@ -537,6 +564,14 @@ pub fn parse_program_v4_subcommand(
                 output_location: matches.value_of("path-to-elf").unwrap().to_string(),
             }))
         }
+        "verify" => {
+            let matches = sub_matches;
+            CliCommandInfo::without_signers(CliCommand::ProgramV4(ProgramV4CliCommand::Verify {
+                program_address: pubkey_of(matches, "program-id")
+                    .expect("Program address is missing"),
+                path_to_elf: matches.value_of("path-to-elf").unwrap().to_string(),
+            }))
+        }
         _ => unreachable!(),
     };
     Ok(response)
@@ -624,6 +659,17 @@ pub fn process_program_v4_subcommand(
             account_pubkey,
             output_location,
         } => process_dump(rpc_client, config, *account_pubkey, output_location),
+        ProgramV4CliCommand::Verify {
+            program_address,
+            path_to_elf,
+        } => {
+            let mut program_data = Vec::new();
+            let mut file = File::open(path_to_elf)
+                .map_err(|err| format!("Unable to open program file: {err}"))?;
+            file.read_to_end(&mut program_data)
+                .map_err(|err| format!("Unable to read program file: {err}"))?;
+            process_verify(rpc_client, config, program_address, &program_data)
+        }
     }
 }
 
@@ -643,6 +689,9 @@ pub fn process_program_v4_subcommand(
 // * Two-step redeploy an existing program using a buffer account
 //   - buffer_address must be `Some(buffer_signer.pubkey())`
 //   - upload_signer_index must be None
+//
+// Chunks which already hold the expected bytes on-chain are not written again,
+// so an interrupted deployment can be resumed by rerunning the same command.
 pub fn process_deploy_program(
     rpc_client: Arc<RpcClient>,
     config: &CliConfig,
@@ -842,16 +891,26 @@ pub fn process_deploy_program(
             Some(&payer_pubkey),
         );
         let chunk_size = calculate_max_chunk_size(first_write_message);
-        for (chunk, i) in program_data[upload_range.clone()]
-            .chunks(chunk_size)
-            .zip(0usize..)
-        {
-            write_messages.push(vec![instruction::write(
-                upload_address,
-                &authority_pubkey,
-                (upload_range.start as u32).saturating_add(i.saturating_mul(chunk_size) as u32),
-                chunk.to_vec(),
-            )]);
+        let uploaded_data = upload_account
+            .as_ref()
+            .filter(|account| loader_v4::check_id(&account.owner))
+            .and_then(|account| account.data.get(LoaderV4State::program_data_offset()..))
+            .unwrap_or_default();
+        write_messages = build_write_messages(
+            upload_address,
+            &authority_pubkey,
+            program_data,
+            upload_range.clone(),
+            chunk_size,
+            uploaded_data,
+        );
+        let total_chunks = upload_range.len().div_ceil(chunk_size);
+        if write_messages.len() < total_chunks && !additional_cli_config.sign_only {
+            println!(
+                "Skipping {} of {} chunks which are already written",
+                total_chunks.saturating_sub(write_messages.len()),
+                total_chunks,
+            );
         }
     }
 
@@ -869,12 +928,16 @@ pub fn process_deploy_program(
                 0,
                 upload_range.len() as u32,
             ),
-            instruction::deploy(program_address, &authority_pubkey),
+            deploy_recording_elf_hash(program_address, &authority_pubkey, &payer_pubkey),
             instruction::set_program_length(buffer_address, &authority_pubkey, 0, &payer_pubkey),
         ]
     } else {
         // Initial deployment or redeployment without a buffer
-        vec![instruction::deploy(program_address, &authority_pubkey)]
+        vec![deploy_recording_elf_hash(
+            program_address,
+            &authority_pubkey,
+            &payer_pubkey,
+        )]
     };
 
     send_messages(
@@ -901,6 +964,36 @@ pub fn process_deploy_program(
     )
 }
 
+// Build one write message per chunk of `upload_range`, skipping chunks whose bytes
+// already match `uploaded_data`, the program data currently stored on-chain
+fn build_write_messages(
+    upload_address: &Pubkey,
+    authority_pubkey: &Pubkey,
+    program_data: &[u8],
+    upload_range: Range<usize>,
+    chunk_size: usize,
+    uploaded_data: &[u8],
+) -> Vec<Vec<Instruction>> {
+    program_data[upload_range.clone()]
+        .chunks(chunk_size)
+        .zip(0usize..)
+        .filter_map(|(chunk, i)| {
+            let offset = upload_range
+                .start
+                .saturating_add(i.saturating_mul(chunk_size));
+            if uploaded_data.get(offset..offset.saturating_add(chunk.len())) == Some(chunk) {
+                return None;
+            }
+            Some(vec![instruction::write(
+                upload_address,
+                authority_pubkey,
+                offset as u32,
+                chunk.to_vec(),
+            )])
+        })
+        .collect()
+}
+
 fn process_retract_program(
     rpc_client: Arc<RpcClient>,
     config: &CliConfig,
@@ -1040,6 +1133,22 @@ fn process_finalize_program(
     )
 }
 
+/// Deploy instruction which records the ELF hash of the program in its ELF hash record, created
+/// and funded by `payer` on the first deploy
+fn deploy_recording_elf_hash(
+    program_address: &Pubkey,
+    authority_address: &Pubkey,
+    payer_address: &Pubkey,
+) -> Instruction {
+    let mut instruction = instruction::deploy(program_address, authority_address);
+    instruction.accounts.extend([
+        AccountMeta::new(get_elf_hash_record_address(program_address), false),
+        AccountMeta::new(*payer_address, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    instruction
+}
+
 fn process_show(
     rpc_client: Arc<RpcClient>,
     config: &CliConfig,
@@ -1059,6 +1168,12 @@ fn process_show(
                         LoaderV4Status::Deployed => "deployed",
                         LoaderV4Status::Finalized => "finalized",
                     };
+                    let upgrade_timelock = get_upgrade_timelock(
+                        &rpc_client,
+                        config,
+                        &program_address,
+                        &state.authority_address_or_next_version,
+                    )?;
                     Ok(config.output_format.formatted_string(&CliProgramV4 {
                         program_id: program_address.to_string(),
                         owner: account.owner.to_string(),
@@ -1069,6 +1184,16 @@ fn process_show(
                             .len()
                             .saturating_sub(LoaderV4State::program_data_offset()),
                         status: status.to_string(),
+                        current_elf_hash: account
+                            .data
+                            .get(LoaderV4State::program_data_offset()..)
+                            .map(|programdata| get_elf_hash(programdata).to_string()),
+                        recorded_elf_hash: get_recorded_elf_hash(
+                            &rpc_client,
+                            config,
+                            &program_address,
+                        )?,
+                        upgrade_timelock,
                     }))
                 } else {
                     Err(format!("{program_address} program state is invalid").into())
@@ -1086,6 +1211,73 @@ fn process_show(
     }
 }
 
+fn get_recorded_elf_hash(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    program_address: &Pubkey,
+) -> Result<Option<CliRecordedElfHash>, Box<dyn std::error::Error>> {
+    let record_address = get_elf_hash_record_address(program_address);
+    let Some(account) = rpc_client
+        .get_account_with_commitment(&record_address, config.commitment)?
+        .value
+    else {
+        return Ok(None);
+    };
+    if !loader_v4::check_id(&account.owner) {
+        return Ok(None);
+    }
+    let (slot, elf_hash) = get_elf_hash_record(&account.data)
+        .ok_or_else(|| format!("{record_address} ELF hash record is invalid"))?;
+    Ok(Some(CliRecordedElfHash {
+        address: record_address.to_string(),
+        elf_hash: elf_hash.to_string(),
+        slot,
+    }))
+}
+
+fn get_upgrade_timelock(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    program_address: &Pubkey,
+    authority_address: &Pubkey,
+) -> Result<Option<CliUpgradeTimelock>, Box<dyn std::error::Error>> {
+    let timelock_address = get_timelock_address(program_address);
+    if *authority_address != timelock_address {
+        return Ok(None);
+    }
+    let Some(account) = rpc_client
+        .get_account_with_commitment(&timelock_address, config.commitment)?
+        .value
+    else {
+        return Ok(None);
+    };
+    if account.owner != solana_upgrade_timelock_interface::id() {
+        return Ok(None);
+    }
+    let timelock = UpgradeTimelock::deserialize(&account.data)
+        .map_err(|_| format!("{timelock_address} upgrade timelock state is invalid"))?;
+    Ok(Some(CliUpgradeTimelock {
+        address: timelock_address.to_string(),
+        authority: timelock.authority.to_string(),
+        delay_slots: timelock.delay_slots,
+        pending_upgrade: timelock
+            .pending_upgrade
+            .map(|pending_upgrade| CliPendingUpgrade {
+                buffer: pending_upgrade.buffer.to_string(),
+                buffer_hash: pending_upgrade.buffer_hash.to_string(),
+                staged_slot: pending_upgrade.staged_slot,
+                earliest_slot: pending_upgrade.earliest_slot,
+            }),
+        pending_release: timelock
+            .pending_release
+            .map(|pending_release| CliPendingRelease {
+                new_authority: pending_release.new_authority.to_string(),
+                staged_slot: pending_release.staged_slot,
+                earliest_slot: pending_release.earliest_slot,
+            }),
+    }))
+}
+
 pub fn process_dump(
     rpc_client: Arc<RpcClient>,
     config: &CliConfig,
@@ -1112,6 +1304,51 @@ pub fn process_dump(
     }
 }
 
+fn process_verify(
+    rpc_client: Arc<RpcClient>,
+    config: &CliConfig,
+    program_address: &Pubkey,
+    program_data: &[u8],
+) -> ProcessResult {
+    let Some(account) = rpc_client
+        .get_account_with_commitment(program_address, config.commitment)?
+        .value
+    else {
+        return Err(format!("Unable to find the account {program_address}").into());
+    };
+    if !loader_v4::check_id(&account.owner) {
+        return Err(format!("{program_address} is not owned by loader-v4").into());
+    }
+    let Some(deployed_data) = account.data.get(LoaderV4State::program_data_offset()..) else {
+        return Err(format!("{program_address} program state is invalid").into());
+    };
+
+    let current_hash = get_elf_hash(deployed_data);
+    let local_hash = get_elf_hash(program_data);
+    if current_hash != local_hash {
+        let difference = match deployed_data
+            .iter()
+            .zip(program_data)
+            .position(|(deployed, local)| deployed != local)
+        {
+            Some(offset) => format!("first difference at byte offset {offset}"),
+            None => format!(
+                "on-chain length {} differs from local length {}",
+                deployed_data.len(),
+                program_data.len(),
+            ),
+        };
+        return Err(format!(
+            "{program_address} does not match the local program: current on-chain ELF hash \
+             {current_hash}, local ELF hash {local_hash}, {difference}"
+        )
+        .into());
+    }
+    Ok(format!(
+        "{program_address} matches the local program, ELF hash {local_hash}"
+    ))
+}
+
 #[allow(clippy::too_many_arguments)]
 fn send_messages(
     rpc_client: Arc<RpcClient>,
@@ -1452,6 +1689,12 @@ fn get_programs(
     let mut programs = vec![];
     for (program, account) in results.iter() {
         if let Ok(state) = solana_loader_v4_program::get_state(&account.data) {
+            // ELF hash records look like finalized programs without a next version
+            if matches!(state.status, LoaderV4Status::Finalized)
+                && state.authority_address_or_next_version == Pubkey::default()
+            {
+                continue;
+            }
             let status = match state.status {
                 LoaderV4Status::Retracted => "retracted",
                 LoaderV4Status::Deployed => "deployed",
@@ -1467,6 +1710,9 @@ fn get_programs(
                     .data
                     .len()
                     .saturating_sub(LoaderV4State::program_data_offset()),
+                current_elf_hash: None,
+                recorded_elf_hash: None,
+                upgrade_timelock: None,
             });
         } else {
             return Err(format!("Error parsing Program account {program}").into());
@@ -1480,6 +1726,7 @@ mod tests {
     use {
         super::*,
         crate::{clap_app::get_clap_app, cli::parse_command},
+        base64::{prelude::BASE64_STANDARD, Engine},
         serde_json::json,
         solana_keypair::{keypair_from_seed, read_keypair_file, write_keypair_file, Keypair},
         solana_rpc_client_api::{
@@ -1900,6 +2147,154 @@ mod tests {
         .is_ok());
     }
 
+    #[test]
+    fn test_build_write_messages() {
+        let upload_address = Pubkey::new_unique();
+        let authority_pubkey = Pubkey::new_unique();
+        let program_data = (0..=255u8).cycle().take(1000).collect::<Vec<_>>();
+        let chunk_size = 300;
+
+        // Nothing uploaded yet
+        let write_messages = build_write_messages(
+            &upload_address,
+            &authority_pubkey,
+            &program_data,
+            0..program_data.len(),
+            chunk_size,
+            &[],
+        );
+        assert_eq!(write_messages.len(), 4);
+
+        // Resume after the first two chunks were written
+        let mut uploaded_data = vec![0; program_data.len()];
+        uploaded_data[..600].copy_from_slice(&program_data[..600]);
+        let write_messages = build_write_messages(
+            &upload_address,
+            &authority_pubkey,
+            &program_data,
+            0..program_data.len(),
+            chunk_size,
+            &uploaded_data,
+        );
+        assert_eq!(
+            write_messages,
+            vec![
+                vec![instruction::write(
+                    &upload_address,
+                    &authority_pubkey,
+                    600,
+                    program_data[600..900].to_vec(),
+                )],
+                vec![instruction::write(
+                    &upload_address,
+                    &authority_pubkey,
+                    900,
+                    program_data[900..].to_vec(),
+                )],
+            ]
+        );
+
+        // Only the chunks of the upload range are compared
+        uploaded_data.copy_from_slice(&program_data);
+        uploaded_data[950] = 0;
+        let write_messages = build_write_messages(
+            &upload_address,
+            &authority_pubkey,
+            &program_data,
+            100..1000,
+            chunk_size,
+            &uploaded_data,
+        );
+        assert_eq!(
+            write_messages,
+            vec![vec![instruction::write(
+                &upload_address,
+                &authority_pubkey,
+                700,
+                program_data[700..].to_vec(),
+            )]]
+        );
+
+        // Everything is uploaded already
+        let write_messages = build_write_messages(
+            &upload_address,
+            &authority_pubkey,
+            &program_data,
+            0..program_data.len(),
+            chunk_size,
+            &program_data,
+        );
+        assert!(write_messages.is_empty());
+    }
+
+    #[test]
+    fn test_verify() {
+        let config = CliConfig::default();
+        let mut program_data = Vec::new();
+        let mut file = File::open("tests/fixtures/noop.so").unwrap();
+        file.read_to_end(&mut program_data).unwrap();
+        let program_address = Pubkey::new_unique();
+
+        let rpc_client_with_program = |program_data: &[u8]| {
+            let mut account_data = BASE64_STANDARD
+                .decode("AAAAAAAAAADtSSjGKNHCxurpAziQWZVhKVknOlxj+TY2wUYUrIc30QEAAAAAAAAA")
+                .unwrap();
+            account_data.extend_from_slice(program_data);
+            Arc::new(rpc_client_with_program_data(
+                &BASE64_STANDARD.encode(account_data),
+                true,
+            ))
+        };
+
+        assert_eq!(
+            process_verify(
+                rpc_client_with_program(&program_data),
+                &config,
+                &program_address,
+                &program_data,
+            )
+            .unwrap(),
+            format!(
+                "{program_address} matches the local program, ELF hash {}",
+                get_elf_hash(&program_data)
+            ),
+        );
+
+        let mut modified_program_data = program_data.clone();
+        modified_program_data[100] = modified_program_data[100].wrapping_add(1);
+        let err = process_verify(
+            rpc_client_with_program(&modified_program_data),
+            &config,
+            &program_address,
+            &program_data,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("first difference at byte offset 100"));
+
+        let err = process_verify(
+            rpc_client_with_program(&program_data[..program_data.len() - 1]),
+            &config,
+            &program_address,
+            &program_data,
+        )
+        .unwrap_err();
+        assert!(err.to_string().ends_with(&format!(
+            "on-chain length {} differs from local length {}",
+            program_data.len() - 1,
+            program_data.len(),
+        )));
+
+        assert!(process_verify(
+            Arc::new(rpc_client_wrong_account_owner()),
+            &config,
+            &program_address,
+            &program_data,
+        )
+        .is_err());
+    }
+
     fn make_tmp_path(name: &str) -> String {
         let out_dir = std::env::var("FARF_DIR").unwrap_or_else(|_| "farf".to_string());
         let keypair = Keypair::new();
@@ -2345,4 +2740,31 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_cli_parse_verify() {
+        let test_commands = get_clap_app("test", "desc", "version");
+
+        let default_keypair = Keypair::new();
+        let keypair_file = make_tmp_path("keypair_file");
+        write_keypair_file(&default_keypair, &keypair_file).unwrap();
+        let default_signer = DefaultSigner::new("", &keypair_file);
+
+        let program_address = Pubkey::new_unique();
+        let test_command = test_commands.clone().get_matches_from(vec![
+            "test",
+            "program-v4",
+            "verify",
+            "/Users/test/program.so",
+            "--program-id",
+            &program_address.to_string(),
+        ]);
+        assert_eq!(
+            parse_command(&test_command, &default_signer, &mut None).unwrap(),
+            CliCommandInfo::without_signers(CliCommand::ProgramV4(ProgramV4CliCommand::Verify {
+                program_address,
+                path_to_elf: "/Users/test/program.so".to_string(),
+            })),
+        );
+    }
 }
@@ -2801,6 +2801,20 @@ pub fn process_delegate_stake(
                 println!("--force supplied, ignoring: {err}");
             }
         }
+
+        if let Some(pending_commission) =
+            crate::vote::get_pending_commission(rpc_client, vote_account_pubkey, config.commitment)?
+        {
+            let (_, vote_state) =
+                crate::vote::get_vote_account(rpc_client, vote_account_pubkey, config.commitment)?;
+            println!(
+                "Warning: vote account {vote_account_pubkey} will increase its commission from \
+                 {}% to {}% for the rewards of epoch {}",
+                vote_state.commission,
+                pending_commission.commission,
+                pending_commission.effective_epoch
+            );
+        }
     }
 
     let recent_blockhash = blockhash_query.get_blockhash(rpc_client, config.commitment)?;
@@ -0,0 +1,723 @@
+//! Proposal-based workflow for transactions that need several signers.
+//!
+//! A proposal is a JSON file holding a transaction message and the signatures
+//! collected so far. It is created once with `transaction propose`, passed
+//! around between signers who each run `transaction sign`, and broadcast with
+//! `transaction submit` once every signature is present. Since signers rarely
+//! sign within the lifetime of a recent blockhash, proposals are expected to
+//! use a durable nonce.
+
+use {
+    crate::{
+        clap_app::{validate_pubkey, validate_signer},
+        cli::{CliCommand, CliCommandInfo, CliConfig, CliError, ProcessResult},
+    },
+    base64::{prelude::BASE64_STANDARD, Engine},
+    clap::{App, AppSettings, Arg, ArgMatches, SubCommand},
+    serde::{Deserialize, Serialize},
+    solana_clap_v3_utils::{input_parsers::*, keypair::*},
+    solana_cli_output::{
+        CliSignature, CliSignatureVerificationStatus, CliTransactionProposal,
+        CliTransactionProposalSigner,
+    },
+    solana_instruction::{AccountMeta, Instruction},
+    solana_message::{AccountKeys, Message, VersionedMessage},
+    solana_pubkey::Pubkey,
+    solana_remote_wallet::remote_wallet::RemoteWalletManager,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_nonce_utils::{self as nonce_utils, data_from_account},
+    solana_sanitize::Sanitize,
+    solana_signature::Signature,
+    solana_signer::Signer,
+    solana_transaction::{uses_durable_nonce, versioned::VersionedTransaction, Transaction},
+    solana_transaction_status::parse_ui_instruction,
+    std::{
+        fs::File,
+        path::{Path, PathBuf},
+        rc::Rc,
+        sync::Arc,
+    },
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TransactionCliCommand {
+    Propose {
+        message: Message,
+        nonce_account: Option<Pubkey>,
+        nonce_authority: Option<Pubkey>,
+        output_file: PathBuf,
+    },
+    Sign {
+        proposal_file: PathBuf,
+    },
+    Inspect {
+        proposal_file: PathBuf,
+    },
+    Submit {
+        proposal_file: PathBuf,
+    },
+}
+
+pub trait TransactionSubCommands {
+    fn transaction_subcommands(self) -> Self;
+}
+
+impl TransactionSubCommands for App<'_> {
+    fn transaction_subcommands(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("transaction")
+                .about("Collect signatures for a transaction from multiple signers")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("propose")
+                        .about("Create a transaction proposal file from a transaction message")
+                        .arg(
+                            Arg::with_name("message")
+                                .index(1)
+                                .value_name("MESSAGE")
+                                .takes_value(true)
+                                .required(true)
+                                .help(
+                                    "Base64-encoded transaction message, as printed by \
+                                     `--sign-only --dump-transaction-message`",
+                                ),
+                        )
+                        .arg(
+                            Arg::with_name("output_file")
+                                .long("output-file")
+                                .short('o')
+                                .value_name("FILEPATH")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Write the transaction proposal to this file"),
+                        )
+                        .arg(
+                            Arg::with_name("nonce")
+                                .long("nonce")
+                                .value_name("NONCE_ACCOUNT")
+                                .takes_value(true)
+                                .validator(validate_pubkey)
+                                .help(
+                                    "Use the durable nonce stored in this account instead of \
+                                     the message's blockhash. An instruction advancing the nonce \
+                                     is prepended to the message if it doesn't have one.",
+                                ),
+                        )
+                        .arg(
+                            Arg::with_name("nonce_authority")
+                                .long("nonce-authority")
+                                .value_name("NONCE_AUTHORITY_PUBKEY")
+                                .takes_value(true)
+                                .requires("nonce")
+                                .validator(validate_pubkey)
+                                .help(
+                                    "Authority of the nonce account \
+                                     [default: the message's fee payer]",
+                                ),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("sign")
+                        .about("Add signatures to a transaction proposal file")
+                        .arg(
+                            Arg::with_name("proposal_file")
+                                .index(1)
+                                .value_name("FILEPATH")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Transaction proposal file, updated in place"),
+                        )
+                        .arg(
+                            Arg::with_name("signers")
+                                .long("signer")
+                                .value_name("SIGNER")
+                                .takes_value(true)
+                                .multiple(true)
+                                .validator(validate_signer)
+                                .help(
+                                    "Sign the transaction with this signer. May be specified \
+                                     multiple times [default: the default configured keypair]",
+                                ),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("inspect")
+                        .about("Show the instructions and signature status of a proposal")
+                        .arg(
+                            Arg::with_name("proposal_file")
+                                .index(1)
+                                .value_name("FILEPATH")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Transaction proposal file"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("submit")
+                        .about("Broadcast a fully signed transaction proposal")
+                        .arg(
+                            Arg::with_name("proposal_file")
+                                .index(1)
+                                .value_name("FILEPATH")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Transaction proposal file"),
+                        ),
+                ),
+        )
+    }
+}
+
+pub fn parse_transaction_subcommand(
+    matches: &ArgMatches,
+    default_signer: &DefaultSigner,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Result<CliCommandInfo, CliError> {
+    let response = match matches.subcommand() {
+        Some(("propose", matches)) => {
+            let message = decode_message(matches.value_of("message").unwrap())?;
+            CliCommandInfo::without_signers(CliCommand::Transaction(
+                TransactionCliCommand::Propose {
+                    message,
+                    nonce_account: pubkey_of(matches, "nonce"),
+                    nonce_authority: pubkey_of(matches, "nonce_authority"),
+                    output_file: PathBuf::from(matches.value_of("output_file").unwrap()),
+                },
+            ))
+        }
+        Some(("sign", matches)) => {
+            let signers = match matches.values_of("signers") {
+                Some(paths) => paths
+                    .map(|path| signer_from_path(matches, path, "signer", wallet_manager))
+                    .collect::<Result<Vec<_>, _>>()?,
+                None => vec![default_signer.signer_from_path(matches, wallet_manager)?],
+            };
+            CliCommandInfo {
+                command: CliCommand::Transaction(TransactionCliCommand::Sign {
+                    proposal_file: PathBuf::from(matches.value_of("proposal_file").unwrap()),
+                }),
+                signers,
+            }
+        }
+        Some(("inspect", matches)) => CliCommandInfo::without_signers(CliCommand::Transaction(
+            TransactionCliCommand::Inspect {
+                proposal_file: PathBuf::from(matches.value_of("proposal_file").unwrap()),
+            },
+        )),
+        Some(("submit", matches)) => CliCommandInfo::without_signers(CliCommand::Transaction(
+            TransactionCliCommand::Submit {
+                proposal_file: PathBuf::from(matches.value_of("proposal_file").unwrap()),
+            },
+        )),
+        _ => unreachable!(),
+    };
+    Ok(response)
+}
+
+pub fn process_transaction_subcommand(
+    rpc_client: Arc<RpcClient>,
+    config: &CliConfig,
+    subcommand: &TransactionCliCommand,
+) -> ProcessResult {
+    match subcommand {
+        TransactionCliCommand::Propose {
+            message,
+            nonce_account,
+            nonce_authority,
+            output_file,
+        } => process_propose(
+            &rpc_client,
+            config,
+            message.clone(),
+            nonce_account.as_ref(),
+            nonce_authority.as_ref(),
+            output_file,
+        ),
+        TransactionCliCommand::Sign { proposal_file } => process_sign(config, proposal_file),
+        TransactionCliCommand::Inspect { proposal_file } => {
+            let transaction = TransactionProposal::read(proposal_file)?.into_transaction()?;
+            Ok(config
+                .output_format
+                .formatted_string(&summarize_proposal(&transaction)))
+        }
+        TransactionCliCommand::Submit { proposal_file } => {
+            process_submit(&rpc_client, config, proposal_file)
+        }
+    }
+}
+
+/// On-disk representation of a transaction proposal.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionProposal {
+    /// Base64-encoded transaction message
+    pub message: String,
+    /// One entry per required signer, in message order
+    pub signatures: Vec<ProposalSignature>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalSignature {
+    pub pubkey: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl TransactionProposal {
+    pub fn from_transaction(transaction: &Transaction) -> Self {
+        let signatures = transaction
+            .signatures
+            .iter()
+            .zip(&transaction.message.account_keys)
+            .map(|(signature, pubkey)| ProposalSignature {
+                pubkey: pubkey.to_string(),
+                signature: (*signature != Signature::default()).then(|| signature.to_string()),
+            })
+            .collect();
+        Self {
+            message: BASE64_STANDARD.encode(transaction.message_data()),
+            signatures,
+        }
+    }
+
+    pub fn into_transaction(self) -> Result<Transaction, CliError> {
+        let message = decode_message(&self.message)?;
+        let num_required_signatures = usize::from(message.header.num_required_signatures);
+        if self.signatures.len() != num_required_signatures {
+            return Err(CliError::BadParameter(format!(
+                "Proposal has {} signatures but the message requires {num_required_signatures}",
+                self.signatures.len(),
+            )));
+        }
+
+        let mut signatures = Vec::with_capacity(num_required_signatures);
+        for (entry, pubkey) in self.signatures.into_iter().zip(&message.account_keys) {
+            if entry.pubkey != pubkey.to_string() {
+                return Err(CliError::BadParameter(format!(
+                    "Proposal signer {} does not match message signer {pubkey}",
+                    entry.pubkey,
+                )));
+            }
+            let signature = match entry.signature {
+                Some(signature) => signature.parse::<Signature>().map_err(|_| {
+                    CliError::BadParameter(format!("Invalid signature for signer {pubkey}"))
+                })?,
+                None => Signature::default(),
+            };
+            signatures.push(signature);
+        }
+        Ok(Transaction {
+            signatures,
+            message,
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, CliError> {
+        let file = File::open(path).map_err(|err| {
+            CliError::BadParameter(format!("Unable to open {}: {err}", path.display()))
+        })?;
+        serde_json::from_reader(file).map_err(|err| {
+            CliError::BadParameter(format!(
+                "{} is not a transaction proposal: {err}",
+                path.display()
+            ))
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), CliError> {
+        let file = File::create(path).map_err(|err| {
+            CliError::BadParameter(format!("Unable to create {}: {err}", path.display()))
+        })?;
+        serde_json::to_writer_pretty(file, self)
+            .map_err(|err| CliError::BadParameter(format!("Unable to write proposal: {err}")))
+    }
+}
+
+fn decode_message(encoded: &str) -> Result<Message, CliError> {
+    let message = BASE64_STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|data| bincode::deserialize::<VersionedMessage>(&data).ok())
+        .ok_or_else(|| {
+            CliError::BadParameter("Unable to decode transaction message".to_string())
+        })?;
+    let VersionedMessage::Legacy(message) = message else {
+        return Err(CliError::BadParameter(
+            "Only legacy transaction messages are supported".to_string(),
+        ));
+    };
+    message
+        .sanitize()
+        .map_err(|err| CliError::BadParameter(format!("Invalid transaction message: {err}")))?;
+    Ok(message)
+}
+
+/// Returns the nonce account and nonce authority of a durable nonce
+/// transaction.
+fn durable_nonce_of(transaction: &Transaction) -> Option<(Pubkey, Pubkey)> {
+    let instruction = uses_durable_nonce(transaction)?;
+    let account_keys = &transaction.message.account_keys;
+    let nonce_account = account_keys.get(usize::from(*instruction.accounts.first()?))?;
+    let nonce_authority = account_keys.get(usize::from(*instruction.accounts.get(2)?))?;
+    Some((*nonce_account, *nonce_authority))
+}
+
+/// Recovers the instructions a legacy message was compiled from.
+fn decompile_instructions(message: &Message) -> Vec<Instruction> {
+    message
+        .instructions
+        .iter()
+        .map(|instruction| Instruction {
+            program_id: *instruction.program_id(&message.account_keys),
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|index| {
+                    let index = usize::from(*index);
+                    AccountMeta {
+                        pubkey: message.account_keys[index],
+                        is_signer: message.is_signer(index),
+                        is_writable: message.is_maybe_writable(index, None),
+                    }
+                })
+                .collect(),
+            data: instruction.data.clone(),
+        })
+        .collect()
+}
+
+pub fn summarize_proposal(transaction: &Transaction) -> CliTransactionProposal {
+    let message = &transaction.message;
+    let account_keys = AccountKeys::new(&message.account_keys, None);
+    let sigverify_status = CliSignatureVerificationStatus::verify_transaction(
+        &VersionedTransaction::from(transaction.clone()),
+    );
+    CliTransactionProposal {
+        fee_payer: message.account_keys[0].to_string(),
+        blockhash: message.recent_blockhash.to_string(),
+        nonce_account: durable_nonce_of(transaction)
+            .map(|(nonce_account, _)| nonce_account.to_string()),
+        instructions: message
+            .instructions
+            .iter()
+            .map(|instruction| parse_ui_instruction(instruction, &account_keys, None))
+            .collect(),
+        signers: message
+            .account_keys
+            .iter()
+            .zip(sigverify_status)
+            .map(|(pubkey, status)| CliTransactionProposalSigner {
+                pubkey: pubkey.to_string(),
+                status,
+            })
+            .collect(),
+    }
+}
+
+fn process_propose(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    mut message: Message,
+    nonce_account: Option<&Pubkey>,
+    nonce_authority: Option<&Pubkey>,
+    output_file: &Path,
+) -> ProcessResult {
+    if let Some(nonce_account) = nonce_account {
+        let fee_payer = message.account_keys[0];
+        let nonce_authority = match durable_nonce_of(&Transaction::new_unsigned(message.clone())) {
+            Some((existing_nonce_account, existing_nonce_authority)) => {
+                if existing_nonce_account != *nonce_account {
+                    return Err(CliError::BadParameter(format!(
+                        "Message already uses nonce account {existing_nonce_account}"
+                    ))
+                    .into());
+                }
+                if nonce_authority.is_some_and(|authority| *authority != existing_nonce_authority) {
+                    return Err(CliError::BadParameter(format!(
+                        "Message already uses nonce authority {existing_nonce_authority}"
+                    ))
+                    .into());
+                }
+                existing_nonce_authority
+            }
+            None => {
+                let nonce_authority = nonce_authority.copied().unwrap_or(fee_payer);
+                message = Message::new_with_nonce(
+                    decompile_instructions(&message),
+                    Some(&fee_payer),
+                    nonce_account,
+                    &nonce_authority,
+                );
+                nonce_authority
+            }
+        };
+
+        let nonce_account =
+            nonce_utils::get_account_with_commitment(rpc_client, nonce_account, config.commitment)?;
+        let nonce_data = data_from_account(&nonce_account)?;
+        if nonce_data.authority != nonce_authority {
+            return Err(
+                CliError::InvalidNonce(nonce_utils::Error::InvalidAuthority {
+                    provided: nonce_authority,
+                    expected: nonce_data.authority,
+                })
+                .into(),
+            );
+        }
+        message.recent_blockhash = nonce_data.blockhash();
+    }
+
+    let transaction = Transaction::new_unsigned(message);
+    TransactionProposal::from_transaction(&transaction).write(output_file)?;
+    Ok(config
+        .output_format
+        .formatted_string(&summarize_proposal(&transaction)))
+}
+
+fn process_sign(config: &CliConfig, proposal_file: &Path) -> ProcessResult {
+    let mut transaction = TransactionProposal::read(proposal_file)?.into_transaction()?;
+    let num_required_signatures = usize::from(transaction.message.header.num_required_signatures);
+    let required_signers = &transaction.message.account_keys[..num_required_signatures];
+    let signers: Vec<&dyn Signer> = config
+        .signers
+        .iter()
+        .filter(|signer| required_signers.contains(&signer.pubkey()))
+        .copied()
+        .collect();
+    if signers.is_empty() {
+        return Err(CliError::BadParameter(
+            "None of the provided signers are required to sign this transaction".to_string(),
+        )
+        .into());
+    }
+
+    let blockhash = transaction.message.recent_blockhash;
+    transaction.try_partial_sign(&signers, blockhash)?;
+    TransactionProposal::from_transaction(&transaction).write(proposal_file)?;
+    Ok(config
+        .output_format
+        .formatted_string(&summarize_proposal(&transaction)))
+}
+
+fn process_submit(
+    rpc_client: &RpcClient,
+    config: &CliConfig,
+    proposal_file: &Path,
+) -> ProcessResult {
+    let transaction = TransactionProposal::read(proposal_file)?.into_transaction()?;
+    let proposal = summarize_proposal(&transaction);
+    if !proposal.is_complete() {
+        let missing = proposal
+            .signers
+            .iter()
+            .filter(|signer| signer.status != CliSignatureVerificationStatus::Pass)
+            .map(|signer| signer.pubkey.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(format!("Transaction is missing valid signatures from: {missing}").into());
+    }
+
+    let blockhash = transaction.message.recent_blockhash;
+    if let Some((nonce_account, _)) = durable_nonce_of(&transaction) {
+        let nonce_account = nonce_utils::get_account_with_commitment(
+            rpc_client,
+            &nonce_account,
+            config.commitment,
+        )?;
+        let nonce = data_from_account(&nonce_account)?.blockhash();
+        if nonce != blockhash {
+            return Err(CliError::InvalidNonce(nonce_utils::Error::InvalidHash {
+                provided: blockhash,
+                expected: nonce,
+            })
+            .into());
+        }
+    } else if !rpc_client.is_blockhash_valid(&blockhash, config.commitment)? {
+        return Err(format!(
+            "Blockhash {blockhash} has expired, the transaction must be proposed again"
+        )
+        .into());
+    }
+
+    let signature = rpc_client.send_and_confirm_transaction_with_spinner_and_config(
+        &transaction,
+        config.commitment,
+        config.send_transaction_config,
+    )?;
+    Ok(config.output_format.formatted_string(&CliSignature {
+        signature: signature.to_string(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{clap_app::get_clap_app, cli::parse_command},
+        solana_hash::Hash,
+        solana_keypair::{read_keypair_file, write_keypair, Keypair},
+        solana_system_interface::instruction as system_instruction,
+        tempfile::NamedTempFile,
+    };
+
+    fn two_signer_transaction(fee_payer: &Pubkey, from: &Pubkey) -> Transaction {
+        let mut message = Message::new(
+            &[system_instruction::transfer(
+                from,
+                &Pubkey::new_unique(),
+                42,
+            )],
+            Some(fee_payer),
+        );
+        message.recent_blockhash = Hash::new_unique();
+        Transaction::new_unsigned(message)
+    }
+
+    #[test]
+    fn test_proposal_round_trip() {
+        let fee_payer = Keypair::new();
+        let from = Keypair::new();
+        let mut transaction = two_signer_transaction(&fee_payer.pubkey(), &from.pubkey());
+        let blockhash = transaction.message.recent_blockhash;
+        transaction.partial_sign(&[&from], blockhash);
+
+        let proposal = TransactionProposal::from_transaction(&transaction);
+        assert_eq!(proposal.signatures[0].signature, None);
+        assert!(proposal.signatures[1].signature.is_some());
+        assert_eq!(proposal.into_transaction().unwrap(), transaction);
+
+        let summary = summarize_proposal(&transaction);
+        assert!(!summary.is_complete());
+        assert_eq!(
+            summary.signers[0].status,
+            CliSignatureVerificationStatus::None
+        );
+        assert_eq!(
+            summary.signers[1].status,
+            CliSignatureVerificationStatus::Pass
+        );
+        assert_eq!(summary.nonce_account, None);
+    }
+
+    #[test]
+    fn test_proposal_rejects_mismatched_signers() {
+        let fee_payer = Pubkey::new_unique();
+        let transaction = two_signer_transaction(&fee_payer, &Pubkey::new_unique());
+        let mut proposal = TransactionProposal::from_transaction(&transaction);
+        proposal.signatures.swap(0, 1);
+        assert!(proposal.into_transaction().is_err());
+
+        let mut proposal = TransactionProposal::from_transaction(&transaction);
+        proposal.signatures.pop();
+        assert!(proposal.into_transaction().is_err());
+    }
+
+    #[test]
+    fn test_decompile_and_add_nonce() {
+        let fee_payer = Pubkey::new_unique();
+        let nonce_account = Pubkey::new_unique();
+        let transaction = two_signer_transaction(&fee_payer, &Pubkey::new_unique());
+        let instructions = decompile_instructions(&transaction.message);
+        assert_eq!(
+            Message::new(&instructions, Some(&fee_payer)).instructions,
+            transaction.message.instructions
+        );
+
+        let message =
+            Message::new_with_nonce(instructions, Some(&fee_payer), &nonce_account, &fee_payer);
+        assert_eq!(
+            durable_nonce_of(&Transaction::new_unsigned(message)),
+            Some((nonce_account, fee_payer))
+        );
+    }
+
+    #[test]
+    fn test_parse_command() {
+        let test_commands = get_clap_app("test", "desc", "version");
+        let default_keypair = Keypair::new();
+        let mut default_keypair_file = NamedTempFile::new().unwrap();
+        write_keypair(&default_keypair, default_keypair_file.as_file_mut()).unwrap();
+        let default_keypair_path = default_keypair_file.path().to_str().unwrap().to_string();
+        let default_signer = DefaultSigner::new("", &default_keypair_path);
+        let signer_keypair = Keypair::new();
+        let mut signer_keypair_file = NamedTempFile::new().unwrap();
+        write_keypair(&signer_keypair, signer_keypair_file.as_file_mut()).unwrap();
+        let signer_keypair_path = signer_keypair_file.path().to_str().unwrap().to_string();
+
+        let fee_payer = Pubkey::new_unique();
+        let nonce_account = Pubkey::new_unique();
+        let transaction = two_signer_transaction(&fee_payer, &Pubkey::new_unique());
+        let encoded_message = BASE64_STANDARD.encode(transaction.message_data());
+        let test_propose = test_commands.clone().get_matches_from(vec![
+            "test",
+            "transaction",
+            "propose",
+            &encoded_message,
+            "--output-file",
+            "proposal.json",
+            "--nonce",
+            &nonce_account.to_string(),
+        ]);
+        assert_eq!(
+            parse_command(&test_propose, &default_signer, &mut None).unwrap(),
+            CliCommandInfo::without_signers(CliCommand::Transaction(
+                TransactionCliCommand::Propose {
+                    message: transaction.message,
+                    nonce_account: Some(nonce_account),
+                    nonce_authority: None,
+                    output_file: PathBuf::from("proposal.json"),
+                }
+            ))
+        );
+
+        let test_sign = test_commands.clone().get_matches_from(vec![
+            "test",
+            "transaction",
+            "sign",
+            "proposal.json",
+        ]);
+        assert_eq!(
+            parse_command(&test_sign, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Transaction(TransactionCliCommand::Sign {
+                    proposal_file: PathBuf::from("proposal.json"),
+                }),
+                signers: vec![Box::new(read_keypair_file(&default_keypair_path).unwrap())],
+            }
+        );
+
+        let test_sign = test_commands.clone().get_matches_from(vec![
+            "test",
+            "transaction",
+            "sign",
+            "proposal.json",
+            "--signer",
+            &signer_keypair_path,
+            "--signer",
+            &default_keypair_path,
+        ]);
+        assert_eq!(
+            parse_command(&test_sign, &default_signer, &mut None).unwrap(),
+            CliCommandInfo {
+                command: CliCommand::Transaction(TransactionCliCommand::Sign {
+                    proposal_file: PathBuf::from("proposal.json"),
+                }),
+                signers: vec![
+                    Box::new(read_keypair_file(&signer_keypair_path).unwrap()),
+                    Box::new(read_keypair_file(&default_keypair_path).unwrap()),
+                ],
+            }
+        );
+
+        let test_propose = test_commands.clone().get_matches_from(vec![
+            "test",
+            "transaction",
+            "propose",
+            "not a message",
+            "--output-file",
+            "proposal.json",
+        ]);
+        assert!(parse_command(&test_propose, &default_signer, &mut None).is_err());
+    }
+}
@@ -27,8 +27,8 @@ use {
         offline::*,
     },
     solana_cli_output::{
-        return_signers_with_config, CliEpochVotingHistory, CliLandedVote, CliVoteAccount,
-        ReturnSignersConfig,
+        return_signers_with_config, CliEpochVotingHistory, CliLandedVote, CliPendingCommission,
+        CliVoteAccount, ReturnSignersConfig,
     },
     solana_commitment_config::CommitmentConfig,
     solana_message::Message,
@@ -44,6 +44,7 @@ use {
         vote_error::VoteError,
         vote_instruction::{self, withdraw, CreateVoteAccountConfig},
         vote_state::{
+            commission_schedule::{self, PendingCommission},
             VoteAuthorize, VoteInit, VoteState, VoteStateVersions, VOTE_CREDITS_MAXIMUM_PER_SLOT,
         },
     },
@@ -1199,7 +1200,7 @@ pub fn process_vote_update_commission(
         BlockhashQuery::None(_) | BlockhashQuery::FeeCalculator(_, _) => ComputeUnitLimit::Default,
         BlockhashQuery::All(_) => ComputeUnitLimit::Simulated,
     };
-    let ixs = vec![vote_instruction::update_commission(
+    let ixs = vec![commission_schedule::update_commission(
         vote_account_pubkey,
         &authorized_withdrawer.pubkey(),
         commission,
@@ -1285,6 +1286,23 @@ pub(crate) fn get_vote_account(
     Ok((vote_account, vote_state))
 }
 
+/// Fetch the commission increase scheduled for a vote account, if any
+pub(crate) fn get_pending_commission(
+    rpc_client: &RpcClient,
+    vote_account_pubkey: &Pubkey,
+    commitment_config: CommitmentConfig,
+) -> Result<Option<PendingCommission>, Box<dyn std::error::Error>> {
+    let pending_commission_account = rpc_client
+        .get_account_with_commitment(
+            &commission_schedule::pending_commission_address(vote_account_pubkey),
+            commitment_config,
+        )?
+        .value;
+    Ok(pending_commission_account
+        .filter(|account| account.owner == solana_vote_program::id())
+        .and_then(|account| PendingCommission::from_account_data(&account.data)))
+}
+
 pub fn process_show_vote_account(
     rpc_client: &RpcClient,
     config: &CliConfig,
@@ -1351,6 +1369,15 @@ pub fn process_show_vote_account(
         authorized_withdrawer: vote_state.authorized_withdrawer.to_string(),
         credits: vote_state.credits(),
         commission: vote_state.commission,
+        pending_commission: get_pending_commission(
+            rpc_client,
+            vote_account_address,
+            config.commitment,
+        )?
+        .map(|pending_commission| CliPendingCommission {
+            commission: pending_commission.commission,
+            effective_epoch: pending_commission.effective_epoch,
+        }),
         root_slot: vote_state.root_slot,
         recent_timestamp: vote_state.last_timestamp.clone(),
         votes,
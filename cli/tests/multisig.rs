@@ -0,0 +1,134 @@
+use {
+    solana_cli::{
+        cli::{process_command, CliCommand, CliConfig},
+        multisig::MultisigCliCommand,
+    },
+    solana_cli_output::{
+        CliMultisig, CliMultisigCreated, CliMultisigProposalCreated, OutputFormat,
+    },
+    solana_faucet::faucet::run_local_faucet,
+    solana_keypair::Keypair,
+    solana_native_token::LAMPORTS_PER_SOL,
+    solana_pubkey::Pubkey,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_signer::Signer,
+    solana_streamer::socket::SocketAddrSpace,
+    solana_test_validator::TestValidator,
+    std::str::FromStr,
+};
+
+#[test]
+fn test_cli_multisig_propose_approve_and_execute() {
+    let mint_keypair = Keypair::new();
+    let mint_pubkey = mint_keypair.pubkey();
+    let faucet_addr = run_local_faucet(mint_keypair, None);
+    let test_validator =
+        TestValidator::with_no_fees(mint_pubkey, Some(faucet_addr), SocketAddrSpace::Unspecified);
+    let rpc_client = RpcClient::new(test_validator.rpc_url());
+
+    let mut config = CliConfig::recent_for_tests();
+    let keypair = Keypair::new();
+    let member = Keypair::new();
+    let multisig_keypair = Keypair::new();
+    config.json_rpc_url = test_validator.rpc_url();
+    config.signers = vec![&keypair];
+    config.output_format = OutputFormat::JsonCompact;
+
+    // Airdrop SOL for transaction fees
+    config.command = CliCommand::Airdrop {
+        pubkey: None,
+        lamports: 10 * LAMPORTS_PER_SOL,
+    };
+    process_command(&config).unwrap();
+
+    // Create a 2-of-2 multisig
+    config.signers = vec![&keypair, &multisig_keypair];
+    config.command = CliCommand::Multisig(MultisigCliCommand::Create {
+        multisig_signer_index: 1,
+        threshold: 2,
+        signers: vec![keypair.pubkey(), member.pubkey()],
+    });
+    let response: CliMultisigCreated =
+        serde_json::from_str(&process_command(&config).unwrap()).unwrap();
+    let multisig_pubkey = multisig_keypair.pubkey();
+    assert_eq!(response.multisig_address, multisig_pubkey.to_string());
+    let vault_pubkey = Pubkey::from_str(&response.vault_address).unwrap();
+
+    // Fund the vault
+    config.signers = vec![&keypair];
+    config.command = CliCommand::Airdrop {
+        pubkey: Some(vault_pubkey),
+        lamports: LAMPORTS_PER_SOL,
+    };
+    process_command(&config).unwrap();
+
+    // Propose a transfer out of the vault
+    let recipient = Pubkey::new_unique();
+    config.command = CliCommand::Multisig(MultisigCliCommand::ProposeTransfer {
+        multisig_pubkey,
+        proposer_signer_index: 0,
+        to_pubkey: recipient,
+        lamports: LAMPORTS_PER_SOL / 2,
+    });
+    let response: CliMultisigProposalCreated =
+        serde_json::from_str(&process_command(&config).unwrap()).unwrap();
+    assert_eq!(response.proposal_index, 0);
+
+    // Validate pending proposal
+    {
+        config.command = CliCommand::Multisig(MultisigCliCommand::Show {
+            multisig_pubkey,
+            use_lamports_unit: false,
+        });
+        let response: CliMultisig =
+            serde_json::from_str(&process_command(&config).unwrap()).unwrap();
+        assert_eq!(response.threshold, 2);
+        assert_eq!(response.proposal_count, 1);
+        assert_eq!(response.vault_balance, LAMPORTS_PER_SOL);
+        assert_eq!(response.proposals.len(), 1);
+        assert_eq!(
+            response.proposals[0].approvals,
+            vec![keypair.pubkey().to_string()]
+        );
+    }
+
+    // Execute without enough approvals
+    config.command = CliCommand::Multisig(MultisigCliCommand::Execute {
+        multisig_pubkey,
+        proposal_index: 0,
+    });
+    process_command(&config).unwrap_err();
+
+    // Approve by the second member
+    config.signers = vec![&keypair, &member];
+    config.command = CliCommand::Multisig(MultisigCliCommand::Approve {
+        multisig_pubkey,
+        proposal_index: 0,
+        member_signer_index: 1,
+    });
+    process_command(&config).unwrap();
+
+    // Execute
+    config.signers = vec![&keypair];
+    config.command = CliCommand::Multisig(MultisigCliCommand::Execute {
+        multisig_pubkey,
+        proposal_index: 0,
+    });
+    process_command(&config).unwrap();
+    assert_eq!(
+        rpc_client.get_balance(&recipient).unwrap(),
+        LAMPORTS_PER_SOL / 2
+    );
+
+    // Executed proposals are no longer pending
+    {
+        config.command = CliCommand::Multisig(MultisigCliCommand::Show {
+            multisig_pubkey,
+            use_lamports_unit: false,
+        });
+        let response: CliMultisig =
+            serde_json::from_str(&process_command(&config).unwrap()).unwrap();
+        assert!(response.proposals.is_empty());
+        assert_eq!(response.vault_balance, LAMPORTS_PER_SOL / 2);
+    }
+}
@@ -0,0 +1,113 @@
+#![allow(clippy::arithmetic_side_effects)]
+use {
+    solana_cli::{
+        check_balance,
+        cli::{process_command, request_and_confirm_airdrop, CliCommand, CliConfig},
+        spend_utils::SpendAmount,
+        transaction::{TransactionCliCommand, TransactionProposal},
+    },
+    solana_commitment_config::CommitmentConfig,
+    solana_faucet::faucet::run_local_faucet,
+    solana_keypair::Keypair,
+    solana_message::Message,
+    solana_native_token::sol_to_lamports,
+    solana_pubkey::Pubkey,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_signer::Signer,
+    solana_streamer::socket::SocketAddrSpace,
+    solana_system_interface::instruction as system_instruction,
+    solana_test_validator::TestValidator,
+};
+
+#[test]
+fn test_transaction_proposal_with_nonce() {
+    let mint_keypair = Keypair::new();
+    let mint_pubkey = mint_keypair.pubkey();
+    let faucet_addr = run_local_faucet(mint_keypair, None);
+    let test_validator =
+        TestValidator::with_no_fees(mint_pubkey, Some(faucet_addr), SocketAddrSpace::Unspecified);
+
+    let rpc_client =
+        RpcClient::new_with_commitment(test_validator.rpc_url(), CommitmentConfig::processed());
+
+    let fee_payer = Keypair::new();
+    let sender = Keypair::new();
+    let nonce_keypair = Keypair::new();
+    let recipient = Pubkey::new_unique();
+
+    let mut config = CliConfig::recent_for_tests();
+    config.json_rpc_url = test_validator.rpc_url();
+    config.signers = vec![&fee_payer];
+    for pubkey in [fee_payer.pubkey(), sender.pubkey()] {
+        request_and_confirm_airdrop(&rpc_client, &config, &pubkey, sol_to_lamports(10.0)).unwrap();
+    }
+
+    // Create a nonce account controlled by the fee payer
+    config.signers = vec![&fee_payer, &nonce_keypair];
+    config.command = CliCommand::CreateNonceAccount {
+        nonce_account: 1,
+        seed: None,
+        nonce_authority: None,
+        memo: None,
+        amount: SpendAmount::Some(sol_to_lamports(1.0)),
+        compute_unit_price: None,
+    };
+    process_command(&config).unwrap();
+
+    // Propose a transfer that needs both the fee payer and the sender
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let proposal_file = tmp_dir.path().join("proposal.json");
+    config.signers = vec![];
+    config.command = CliCommand::Transaction(TransactionCliCommand::Propose {
+        message: Message::new(
+            &[system_instruction::transfer(
+                &sender.pubkey(),
+                &recipient,
+                sol_to_lamports(1.0),
+            )],
+            Some(&fee_payer.pubkey()),
+        ),
+        nonce_account: Some(nonce_keypair.pubkey()),
+        nonce_authority: None,
+        output_file: proposal_file.clone(),
+    });
+    process_command(&config).unwrap();
+
+    // The sender signs first
+    config.signers = vec![&sender];
+    config.command = CliCommand::Transaction(TransactionCliCommand::Sign {
+        proposal_file: proposal_file.clone(),
+    });
+    process_command(&config).unwrap();
+    let proposal = TransactionProposal::read(&proposal_file).unwrap();
+    assert!(proposal.signatures[0].signature.is_none());
+    assert!(proposal.signatures[1].signature.is_some());
+
+    // Signers unrelated to the transaction are rejected
+    let stranger = Keypair::new();
+    config.signers = vec![&stranger];
+    process_command(&config).unwrap_err();
+
+    // Submitting fails until every signature is present
+    config.signers = vec![];
+    config.command = CliCommand::Transaction(TransactionCliCommand::Submit {
+        proposal_file: proposal_file.clone(),
+    });
+    process_command(&config).unwrap_err();
+
+    config.signers = vec![&fee_payer];
+    config.command = CliCommand::Transaction(TransactionCliCommand::Sign {
+        proposal_file: proposal_file.clone(),
+    });
+    process_command(&config).unwrap();
+
+    config.signers = vec![];
+    config.command = CliCommand::Transaction(TransactionCliCommand::Submit {
+        proposal_file: proposal_file.clone(),
+    });
+    process_command(&config).unwrap();
+    check_balance!(sol_to_lamports(1.0), &rpc_client, &recipient);
+
+    // The nonce has been advanced, so the proposal can't be replayed
+    process_command(&config).unwrap_err();
+}
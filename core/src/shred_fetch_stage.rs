@@ -21,6 +21,7 @@ use {
     solana_streamer::{
         evicting_sender::EvictingSender,
         streamer::{self, ChannelSend, PacketBatchReceiver, StreamerReceiveStats},
+        xdp::{spawn_xdp_receivers, XdpReceiverConfig},
     },
     std::{
         net::{SocketAddr, UdpSocket},
@@ -206,11 +207,31 @@ impl ShredFetchStage {
         flags: PacketFlags,
         repair_context: Option<RepairContext>,
         turbine_disabled: Arc<AtomicBool>,
+        xdp_config: Option<&XdpReceiverConfig>,
     ) -> (Vec<JoinHandle<()>>, JoinHandle<()>) {
         let (packet_sender, packet_receiver) =
             EvictingSender::new_bounded(SHRED_FETCH_CHANNEL_SIZE);
         let receiver_stats = Arc::new(StreamerReceiveStats::new(receiver_name));
-        let streamers = sockets
+        // The recvmmsg receivers keep running alongside the XDP ones, and receive the packets
+        // which aren't redirected to an AF_XDP socket.
+        let xdp_receivers = xdp_config
+            .and_then(|xdp_config| {
+                spawn_xdp_receivers(
+                    "solShredXdp",
+                    xdp_config,
+                    &sockets,
+                    exit.clone(),
+                    packet_sender.clone(),
+                    recycler.clone(),
+                    receiver_stats.clone(),
+                )
+                .inspect_err(|err| {
+                    warn!("failed to start xdp receivers, falling back to recvmmsg: {err}")
+                })
+                .ok()
+            })
+            .unwrap_or_default();
+        let mut streamers: Vec<_> = sockets
             .into_iter()
             .enumerate()
             .map(|(i, socket)| {
@@ -228,6 +249,7 @@ impl ShredFetchStage {
                 )
             })
             .collect();
+        streamers.extend(xdp_receivers);
         let modifier_hdl = Builder::new()
             .name(modifier_thread_name.to_string())
             .spawn(move || {
@@ -259,6 +281,7 @@ impl ShredFetchStage {
         cluster_info: Arc<ClusterInfo>,
         outstanding_repair_requests: Arc<RwLock<OutstandingShredRepairs>>,
         turbine_disabled: Arc<AtomicBool>,
+        xdp_config: Option<XdpReceiverConfig>,
         exit: Arc<AtomicBool>,
     ) -> Self {
         let recycler = PacketBatchRecycler::warmed(100, 1024);
@@ -282,6 +305,7 @@ impl ShredFetchStage {
             PacketFlags::empty(),
             None, // repair_context
             turbine_disabled.clone(),
+            xdp_config.as_ref(),
         );
 
         let (repair_receiver, repair_handler) = Self::packet_modifier(
@@ -298,6 +322,7 @@ impl ShredFetchStage {
             PacketFlags::REPAIR,
             Some(repair_context.clone()),
             turbine_disabled.clone(),
+            None, // xdp_config
         );
 
         tvu_threads.extend(repair_receiver);
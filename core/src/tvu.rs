@@ -46,7 +46,7 @@ use {
         prioritization_fee_cache::PrioritizationFeeCache, snapshot_controller::SnapshotController,
         vote_sender_types::ReplayVoteSender,
     },
-    solana_streamer::evicting_sender::EvictingSender,
+    solana_streamer::{evicting_sender::EvictingSender, xdp::XdpReceiverConfig},
     solana_turbine::{retransmit_stage::RetransmitStage, xdp::XdpConfig},
     std::{
         collections::HashSet,
@@ -100,6 +100,7 @@ pub struct TvuConfig {
     pub replay_transactions_threads: NonZeroUsize,
     pub shred_sigverify_threads: NonZeroUsize,
     pub retransmit_xdp: Option<XdpConfig>,
+    pub shred_fetch_xdp: Option<XdpReceiverConfig>,
 }
 
 impl Default for TvuConfig {
@@ -114,6 +115,7 @@ impl Default for TvuConfig {
             replay_transactions_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             shred_sigverify_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             retransmit_xdp: None,
+            shred_fetch_xdp: None,
         }
     }
 }
@@ -198,6 +200,7 @@ impl Tvu {
             cluster_info.clone(),
             outstanding_repair_requests.clone(),
             turbine_disabled,
+            tvu_config.shred_fetch_xdp.clone(),
             exit.clone(),
         );
 
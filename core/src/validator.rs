@@ -118,7 +118,10 @@ use {
     solana_send_transaction_service::send_transaction_service::Config as SendTransactionServiceConfig,
     solana_shred_version::compute_shred_version,
     solana_signer::Signer,
-    solana_streamer::{quic::QuicServerParams, socket::SocketAddrSpace, streamer::StakedNodes},
+    solana_streamer::{
+        quic::QuicServerParams, socket::SocketAddrSpace, streamer::StakedNodes,
+        xdp::XdpReceiverConfig,
+    },
     solana_time_utils::timestamp,
     solana_tpu_client::tpu_client::{
         DEFAULT_TPU_CONNECTION_POOL_SIZE, DEFAULT_TPU_USE_QUIC, DEFAULT_VOTE_USE_QUIC,
@@ -292,6 +295,7 @@ pub struct ValidatorConfig {
     pub delay_leader_block_for_pending_fork: bool,
     pub use_tpu_client_next: bool,
     pub retransmit_xdp: Option<XdpConfig>,
+    pub shred_fetch_xdp: Option<XdpReceiverConfig>,
     pub repair_handler_type: RepairHandlerType,
 }
 
@@ -367,6 +371,7 @@ impl Default for ValidatorConfig {
             delay_leader_block_for_pending_fork: false,
             use_tpu_client_next: true,
             retransmit_xdp: None,
+            shred_fetch_xdp: None,
             repair_handler_type: RepairHandlerType::default(),
         }
     }
@@ -1533,6 +1538,7 @@ impl Validator {
                 replay_transactions_threads: config.replay_transactions_threads,
                 shred_sigverify_threads: config.tvu_shred_sigverify_threads,
                 retransmit_xdp: config.retransmit_xdp.clone(),
+                shred_fetch_xdp: config.shred_fetch_xdp.clone(),
             },
             &max_slots,
             block_metadata_notifier,
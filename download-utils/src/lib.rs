@@ -71,6 +71,9 @@ pub fn download_snapshot_archive(
         ArchiveFormat::TarZstd {
             config: ZstdConfig::default(),
         },
+        ArchiveFormat::TarZstdChunked {
+            config: ZstdConfig::default(),
+        },
         ArchiveFormat::TarLz4,
     ] {
         let destination_path = match snapshot_kind {
@@ -157,6 +157,12 @@ impl FeatureSet {
                 .is_active(&disable_zk_elgamal_proof_program::id()),
             reenable_zk_elgamal_proof_program: self
                 .is_active(&reenable_zk_elgamal_proof_program::id()),
+            enable_stake_redelegate_instruction: self
+                .is_active(&enable_stake_redelegate_instruction::id()),
+            enable_vote_commission_schedule: self.is_active(&enable_vote_commission_schedule::id()),
+            enable_zk_proof_batch_verification: self
+                .is_active(&enable_zk_proof_batch_verification::id()),
+            record_loader_v4_elf_hash: self.is_active(&record_loader_v4_elf_hash::id()),
         }
     }
 }
@@ -1111,6 +1117,30 @@ pub mod reenable_zk_elgamal_proof_program {
     solana_pubkey::declare_id!("zkemPXcuM3G4wpMDZ36Cpw34EjUpvm1nuioiSGbGZPR");
 }
 
+pub mod enable_stake_redelegate_instruction {
+    solana_pubkey::declare_id!("AJDFbAhUTnKYc1Jw9bfMCk9FqbxZb575KMw8VzPm6Eot");
+}
+
+pub mod enable_vote_commission_schedule {
+    solana_pubkey::declare_id!("3VdfEZh22sM15GT2aPrx13dtMQq45aBW1Xj8vsBh1Cfy");
+}
+
+pub mod enable_upgrade_timelock_program {
+    solana_pubkey::declare_id!("HGKeQsiRsSDJD5i4Liv9xe6aHkXquP7UvR6EPb4PUatc");
+}
+
+pub mod enable_multisig_program {
+    solana_pubkey::declare_id!("4SC2dFb5RemcTiXnCPEjqqhtctAgYvidHfaNGuZbyEN4");
+}
+
+pub mod enable_zk_proof_batch_verification {
+    solana_pubkey::declare_id!("J9ot27y92KaoSjSWox6eiz1hVQDNeQN4jgocdfpWS3sN");
+}
+
+pub mod record_loader_v4_elf_hash {
+    solana_pubkey::declare_id!("6pDQSnqSgywc6wK14k7NZhG4HECufn3SboxRDa2EN1Zg");
+}
+
 pub static FEATURE_NAMES: LazyLock<AHashMap<Pubkey, &'static str>> = LazyLock::new(|| {
     [
         (secp256k1_program_enabled::id(), "secp256k1 program"),
@@ -1349,6 +1379,12 @@ pub static FEATURE_NAMES: LazyLock<AHashMap<Pubkey, &'static str>> = LazyLock::n
         (alpenglow::id(), "Enable Alpenglow"),
         (disable_zk_elgamal_proof_program::id(), "Disables zk-elgamal-proof program"),
         (reenable_zk_elgamal_proof_program::id(), "Re-enables zk-elgamal-proof program"),
+        (enable_stake_redelegate_instruction::id(), "Enable the stake program Redelegate instruction"),
+        (enable_vote_commission_schedule::id(), "Delay vote account commission increases by a notice period"),
+        (enable_upgrade_timelock_program::id(), "Enable the upgrade timelock program for loader-v4 programs"),
+        (enable_multisig_program::id(), "Enable the native M-of-N multisig program"),
+        (enable_zk_proof_batch_verification::id(), "Enable batch verification of proofs in the zk-elgamal-proof program"),
+        (record_loader_v4_elf_hash::id(), "Record the ELF hash of loader-v4 programs on deploy"),
         /*************** ADD NEW FEATURES HERE ***************/
     ]
     .iter()
@@ -0,0 +1,595 @@
+//! Declarative genesis spec
+//!
+//! A single YAML or TOML file declaring the validators, vesting stakes, programs, token mints
+//! and token accounts to bake into genesis, by their human readable fields. For example:
+//!
+//! ```yaml
+//! validators:
+//!   - identity: validator-identity.json
+//!     vote: validator-vote.json
+//!     stake: validator-stake.json
+//!     balance_lamports: 500000000000
+//!     stake_lamports: 1000000000000
+//! stakes:
+//!   - name: team
+//!     staker: 7vH5...
+//!     lamports: 1000000000000000
+//!     vesting:
+//!       cliff_fraction: 0.25
+//!       cliff_years: 1.0
+//!       unlocks: 36
+//!       unlock_years: 3.0
+//!       custodian: Mc5X...
+//! programs:
+//!   - address: program-keypair.json
+//!     path: program.so
+//!     upgrade_authority: 9xQe...
+//! token_mints:
+//!   - address: mint-keypair.json
+//!     decimals: 6
+//!     mint_authority: 9xQe...
+//! token_accounts:
+//!   - mint: mint-keypair.json
+//!     owner: 9xQe...
+//!     amount: "1000000.5"
+//! ```
+use {
+    crate::{
+        stakes::{create_and_add_stakes, StakerInfo},
+        unlocks::UnlockInfo,
+    },
+    serde::Deserialize,
+    solana_account::{Account, AccountSharedData},
+    solana_genesis_config::GenesisConfig,
+    solana_keypair::read_keypair_file,
+    solana_loader_v3_interface::state::UpgradeableLoaderState,
+    solana_program_option::COption,
+    solana_program_pack::Pack,
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::{bpf_loader_upgradeable, system_program},
+    solana_signer::Signer,
+    spl_associated_token_account::get_associated_token_address_with_program_id,
+    spl_token::state::{Account as TokenAccount, AccountState, Mint},
+    std::{collections::HashMap, fs, io, path::PathBuf},
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisSpec {
+    #[serde(default)]
+    pub validators: Vec<ValidatorSpec>,
+    #[serde(default)]
+    pub accounts: Vec<SystemAccountSpec>,
+    #[serde(default)]
+    pub stakes: Vec<StakeSpec>,
+    #[serde(default)]
+    pub programs: Vec<ProgramSpec>,
+    #[serde(default)]
+    pub token_mints: Vec<TokenMintSpec>,
+    #[serde(default)]
+    pub token_accounts: Vec<TokenAccountSpec>,
+}
+
+/// A staked validator. Addresses are pubkeys or keypair files
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatorSpec {
+    pub identity: String,
+    pub vote: String,
+    pub stake: String,
+    pub balance_lamports: u64,
+    pub stake_lamports: u64,
+    /// The vote commission, --vote-commission-percentage if unset
+    pub commission: Option<u8>,
+    /// The stake authority, the identity if unset
+    pub stake_authority: Option<String>,
+}
+
+/// A system account holding lamports
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SystemAccountSpec {
+    pub address: String,
+    pub lamports: u64,
+}
+
+/// Lamports delegated to stake accounts which unlock on a vesting schedule
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StakeSpec {
+    pub name: String,
+    pub staker: String,
+    /// The withdraw authority, the staker if unset
+    pub withdrawer: Option<String>,
+    pub lamports: u64,
+    /// The largest each stake account should be, in lamports
+    pub granularity: Option<u64>,
+    pub vesting: VestingSpec,
+}
+
+/// See `Unlocks::new`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VestingSpec {
+    pub cliff_fraction: f64,
+    pub cliff_years: f64,
+    #[serde(default)]
+    pub unlocks: usize,
+    #[serde(default)]
+    pub unlock_years: f64,
+    pub custodian: String,
+}
+
+/// A program, upgradeable by default
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProgramSpec {
+    pub address: String,
+    /// The loader owning the program, the upgradeable loader if unset
+    pub loader: Option<String>,
+    pub path: PathBuf,
+    /// The upgrade authority of an upgradeable program, the program can't be upgraded if unset
+    pub upgrade_authority: Option<String>,
+}
+
+/// An initialized token mint, its supply is the sum of its token accounts
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenMintSpec {
+    pub address: String,
+    pub decimals: u8,
+    pub mint_authority: Option<String>,
+    pub freeze_authority: Option<String>,
+    /// The token program owning the mint and its accounts, the token program if unset
+    pub program_id: Option<String>,
+}
+
+/// An initialized token account of a mint declared in `token_mints`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenAccountSpec {
+    pub mint: String,
+    pub owner: String,
+    /// The amount of tokens, in UI units, eg "1.5" for 1_500_000 base units of a mint with 6
+    /// decimals
+    pub amount: String,
+    /// The address of the account, the associated token account of `owner` if unset
+    pub address: Option<String>,
+    #[serde(default)]
+    pub frozen: bool,
+}
+
+impl GenesisSpec {
+    /// Loads a spec from a TOML file if its extension is `.toml`, a YAML file otherwise
+    pub fn load(file: &str) -> io::Result<Self> {
+        let contents = fs::read_to_string(file)?;
+        if file.ends_with(".toml") {
+            toml::from_str(&contents).map_err(|err| io::Error::other(format!("{err}")))
+        } else {
+            serde_yaml::from_str(&contents).map_err(|err| io::Error::other(format!("{err:?}")))
+        }
+    }
+
+    /// Adds the system accounts, returns the lamports added
+    pub fn add_system_accounts(&self, genesis_config: &mut GenesisConfig) -> io::Result<u64> {
+        let mut lamports = 0;
+        for account in &self.accounts {
+            genesis_config.add_account(
+                parse_address(&account.address, "address")?,
+                AccountSharedData::new(account.lamports, 0, &system_program::id()),
+            );
+            lamports += account.lamports;
+        }
+        Ok(lamports)
+    }
+
+    /// Adds the vesting stakes, returns the lamports added
+    pub fn add_stakes(&self, genesis_config: &mut GenesisConfig) -> io::Result<u64> {
+        let mut lamports = 0;
+        for stake in &self.stakes {
+            // the stakes generator works with the static strings of the built-in schedules
+            let leak = |s: String| -> &'static str { Box::leak(s.into_boxed_str()) };
+            let staker = parse_address(&stake.staker, "staker")?;
+            let withdrawer = stake
+                .withdrawer
+                .as_deref()
+                .map(|withdrawer| parse_address(withdrawer, "withdrawer"))
+                .transpose()?;
+            let custodian = parse_address(&stake.vesting.custodian, "custodian")?;
+            lamports += create_and_add_stakes(
+                genesis_config,
+                &StakerInfo {
+                    name: leak(stake.name.clone()),
+                    staker: leak(staker.to_string()),
+                    withdrawer: withdrawer.map(|withdrawer| leak(withdrawer.to_string())),
+                    lamports: stake.lamports,
+                },
+                &UnlockInfo {
+                    cliff_fraction: stake.vesting.cliff_fraction,
+                    cliff_years: stake.vesting.cliff_years,
+                    unlocks: stake.vesting.unlocks,
+                    unlock_years: stake.vesting.unlock_years,
+                    custodian: leak(custodian.to_string()),
+                },
+                stake.granularity,
+            );
+        }
+        Ok(lamports)
+    }
+
+    /// Adds the programs
+    pub fn add_programs(&self, genesis_config: &mut GenesisConfig) -> io::Result<()> {
+        for program in &self.programs {
+            let address = parse_address(&program.address, "address")?;
+            let loader = program
+                .loader
+                .as_deref()
+                .map(|loader| parse_address(loader, "loader"))
+                .transpose()?
+                .unwrap_or(bpf_loader_upgradeable::id());
+            let elf = fs::read(&program.path).map_err(|err| {
+                io::Error::other(format!("failed to read {}: {err}", program.path.display()))
+            })?;
+            if loader == bpf_loader_upgradeable::id() {
+                let upgrade_authority = program
+                    .upgrade_authority
+                    .as_deref()
+                    .map(|authority| parse_address(authority, "upgrade_authority"))
+                    .transpose()?;
+                add_upgradeable_program(
+                    genesis_config,
+                    &address,
+                    &loader,
+                    &elf,
+                    upgrade_authority.unwrap_or_default(),
+                );
+            } else {
+                if program.upgrade_authority.is_some() {
+                    return Err(io::Error::other(format!(
+                        "program {address}: upgrade_authority requires the upgradeable loader"
+                    )));
+                }
+                add_program(genesis_config, &address, &loader, elf);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the token mints and accounts, returns the lamports added
+    pub fn add_token_accounts(&self, genesis_config: &mut GenesisConfig) -> io::Result<u64> {
+        let mut mints = HashMap::new();
+        for mint in &self.token_mints {
+            let address = parse_address(&mint.address, "mint")?;
+            let program_id = mint
+                .program_id
+                .as_deref()
+                .map(|program_id| parse_address(program_id, "program_id"))
+                .transpose()?
+                .unwrap_or(spl_token::id());
+            let state = Mint {
+                mint_authority: parse_optional_address(&mint.mint_authority, "mint_authority")?,
+                supply: 0,
+                decimals: mint.decimals,
+                is_initialized: true,
+                freeze_authority: parse_optional_address(
+                    &mint.freeze_authority,
+                    "freeze_authority",
+                )?,
+            };
+            if mints.insert(address, (program_id, state)).is_some() {
+                return Err(io::Error::other(format!("duplicate token mint {address}")));
+            }
+        }
+
+        let mut lamports = 0;
+        let mut accounts = HashMap::new();
+        for account in &self.token_accounts {
+            let mint = parse_address(&account.mint, "mint")?;
+            let owner = parse_address(&account.owner, "owner")?;
+            let Some((program_id, mint_state)) = mints.get_mut(&mint) else {
+                return Err(io::Error::other(format!(
+                    "token account of {owner}: mint {mint} is not declared in token_mints"
+                )));
+            };
+            if account.frozen && mint_state.freeze_authority.is_none() {
+                return Err(io::Error::other(format!(
+                    "token account of {owner}: mint {mint} has no freeze authority"
+                )));
+            }
+            let amount = ui_amount_to_amount(&account.amount, mint_state.decimals)?;
+            mint_state.supply = mint_state.supply.checked_add(amount).ok_or_else(|| {
+                io::Error::other(format!("token mint {mint}: supply overflows u64"))
+            })?;
+            let address = match &account.address {
+                Some(address) => parse_address(address, "address")?,
+                None => get_associated_token_address_with_program_id(&owner, &mint, program_id),
+            };
+            let state = TokenAccount {
+                mint,
+                owner,
+                amount,
+                delegate: COption::None,
+                state: if account.frozen {
+                    AccountState::Frozen
+                } else {
+                    AccountState::Initialized
+                },
+                is_native: COption::None,
+                delegated_amount: 0,
+                close_authority: COption::None,
+            };
+            if accounts.insert(address, (*program_id, state)).is_some() {
+                return Err(io::Error::other(format!(
+                    "duplicate token account {address}"
+                )));
+            }
+        }
+
+        for (address, (program_id, state)) in mints {
+            lamports += add_packed_account(genesis_config, address, &program_id, &state);
+        }
+        for (address, (program_id, state)) in accounts {
+            lamports += add_packed_account(genesis_config, address, &program_id, &state);
+        }
+        Ok(lamports)
+    }
+}
+
+/// Parses a pubkey, or reads it from a keypair file
+pub fn parse_address(address: &str, input_type: &str) -> io::Result<Pubkey> {
+    address.parse::<Pubkey>().or_else(|_| {
+        read_keypair_file(address)
+            .map(|keypair| keypair.pubkey())
+            .map_err(|err| io::Error::other(format!("invalid {input_type} {address}: {err}")))
+    })
+}
+
+fn parse_optional_address(
+    address: &Option<String>,
+    input_type: &str,
+) -> io::Result<COption<Pubkey>> {
+    Ok(address
+        .as_deref()
+        .map(|address| parse_address(address, input_type))
+        .transpose()?
+        .into())
+}
+
+/// Converts an amount in UI units to base units, without losing precision
+pub fn ui_amount_to_amount(ui_amount: &str, decimals: u8) -> io::Result<u64> {
+    let invalid = || io::Error::other(format!("invalid amount {ui_amount}"));
+    let (integer, fraction) = ui_amount.split_once('.').unwrap_or((ui_amount, ""));
+    if integer.is_empty() && fraction.is_empty()
+        || !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    if fraction.len() > decimals as usize {
+        return Err(io::Error::other(format!(
+            "amount {ui_amount} has more than {decimals} decimals"
+        )));
+    }
+    let digits = format!("{integer}{fraction:0<width$}", width = decimals as usize);
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    digits.parse::<u64>().map_err(|_| invalid())
+}
+
+// Adds a rent exempt account holding `state`, returns its lamports
+fn add_packed_account<T: Pack>(
+    genesis_config: &mut GenesisConfig,
+    address: Pubkey,
+    owner: &Pubkey,
+    state: &T,
+) -> u64 {
+    let mut data = vec![0; T::LEN];
+    state.pack_into_slice(&mut data);
+    let lamports = genesis_config.rent.minimum_balance(data.len());
+    genesis_config.add_account(
+        address,
+        AccountSharedData::from(Account {
+            lamports,
+            data,
+            owner: *owner,
+            executable: false,
+            rent_epoch: 0,
+        }),
+    );
+    lamports
+}
+
+/// Adds a program owned by a non-upgradeable loader
+pub fn add_program(
+    genesis_config: &mut GenesisConfig,
+    address: &Pubkey,
+    loader: &Pubkey,
+    elf: Vec<u8>,
+) {
+    genesis_config.add_account(
+        *address,
+        AccountSharedData::from(Account {
+            lamports: genesis_config.rent.minimum_balance(elf.len()),
+            data: elf,
+            executable: true,
+            owner: *loader,
+            rent_epoch: 0,
+        }),
+    );
+}
+
+/// Adds a program owned by the upgradeable loader, along with its program data account
+pub fn add_upgradeable_program(
+    genesis_config: &mut GenesisConfig,
+    address: &Pubkey,
+    loader: &Pubkey,
+    elf: &[u8],
+    upgrade_authority_address: Pubkey,
+) {
+    let (programdata_address, _) = Pubkey::find_program_address(&[address.as_ref()], loader);
+    let mut program_data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(upgrade_authority_address),
+    })
+    .unwrap();
+    program_data.extend_from_slice(elf);
+    genesis_config.add_account(
+        programdata_address,
+        AccountSharedData::from(Account {
+            lamports: genesis_config.rent.minimum_balance(program_data.len()),
+            data: program_data,
+            owner: *loader,
+            executable: false,
+            rent_epoch: 0,
+        }),
+    );
+
+    let program_data = bincode::serialize(&UpgradeableLoaderState::Program {
+        programdata_address,
+    })
+    .unwrap();
+    genesis_config.add_account(
+        *address,
+        AccountSharedData::from(Account {
+            lamports: genesis_config.rent.minimum_balance(program_data.len()),
+            data: program_data,
+            owner: *loader,
+            executable: true,
+            rent_epoch: 0,
+        }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_account::ReadableAccount};
+
+    #[test]
+    fn test_ui_amount_to_amount() {
+        assert_eq!(ui_amount_to_amount("1", 6).unwrap(), 1_000_000);
+        assert_eq!(ui_amount_to_amount("1.5", 6).unwrap(), 1_500_000);
+        assert_eq!(ui_amount_to_amount(".000001", 6).unwrap(), 1);
+        assert_eq!(ui_amount_to_amount("42", 0).unwrap(), 42);
+        assert_eq!(ui_amount_to_amount("0.0", 9).unwrap(), 0);
+        assert_eq!(
+            ui_amount_to_amount("18446744073709.551615", 6).unwrap(),
+            u64::MAX
+        );
+        assert!(ui_amount_to_amount("18446744073709.551616", 6).is_err());
+        assert!(ui_amount_to_amount("1.0000001", 6).is_err());
+        assert!(ui_amount_to_amount("1.", 0).is_ok());
+        assert!(ui_amount_to_amount(".", 6).is_err());
+        assert!(ui_amount_to_amount("-1", 6).is_err());
+        assert!(ui_amount_to_amount("1e6", 6).is_err());
+    }
+
+    #[test]
+    fn test_add_token_accounts() {
+        let mint = solana_pubkey::new_rand();
+        let mint_authority = solana_pubkey::new_rand();
+        let owner = solana_pubkey::new_rand();
+        let other_owner = solana_pubkey::new_rand();
+        let other_address = solana_pubkey::new_rand();
+        let spec: GenesisSpec = serde_yaml::from_str(&format!(
+            r#"
+token_mints:
+  - address: {mint}
+    decimals: 6
+    mint_authority: {mint_authority}
+    freeze_authority: {mint_authority}
+token_accounts:
+  - mint: {mint}
+    owner: {owner}
+    amount: "1.5"
+  - mint: {mint}
+    owner: {other_owner}
+    address: {other_address}
+    amount: "2"
+    frozen: true
+"#
+        ))
+        .unwrap();
+
+        let mut genesis_config = GenesisConfig::default();
+        let lamports = spec.add_token_accounts(&mut genesis_config).unwrap();
+        assert_eq!(
+            lamports,
+            genesis_config.rent.minimum_balance(Mint::LEN)
+                + 2 * genesis_config.rent.minimum_balance(TokenAccount::LEN)
+        );
+
+        let account = &genesis_config.accounts[&mint];
+        assert_eq!(account.owner(), &spl_token::id());
+        assert_eq!(
+            Mint::unpack(account.data()).unwrap(),
+            Mint {
+                mint_authority: COption::Some(mint_authority),
+                supply: 3_500_000,
+                decimals: 6,
+                is_initialized: true,
+                freeze_authority: COption::Some(mint_authority),
+            }
+        );
+
+        let associated_address =
+            get_associated_token_address_with_program_id(&owner, &mint, &spl_token::id());
+        let account = &genesis_config.accounts[&associated_address];
+        assert_eq!(account.owner(), &spl_token::id());
+        let state = TokenAccount::unpack(account.data()).unwrap();
+        assert_eq!(state.mint, mint);
+        assert_eq!(state.owner, owner);
+        assert_eq!(state.amount, 1_500_000);
+        assert_eq!(state.state, AccountState::Initialized);
+
+        let state = TokenAccount::unpack(genesis_config.accounts[&other_address].data()).unwrap();
+        assert_eq!(state.owner, other_owner);
+        assert_eq!(state.amount, 2_000_000);
+        assert_eq!(state.state, AccountState::Frozen);
+    }
+
+    #[test]
+    fn test_add_token_accounts_errors() {
+        let mint = solana_pubkey::new_rand();
+        let owner = solana_pubkey::new_rand();
+
+        // the mint isn't declared
+        let spec: GenesisSpec = toml::from_str(&format!(
+            r#"
+[[token_accounts]]
+mint = "{mint}"
+owner = "{owner}"
+amount = "1"
+"#
+        ))
+        .unwrap();
+        assert!(spec
+            .add_token_accounts(&mut GenesisConfig::default())
+            .is_err());
+
+        // frozen without a freeze authority
+        let spec: GenesisSpec = toml::from_str(&format!(
+            r#"
+[[token_mints]]
+address = "{mint}"
+decimals = 2
+
+[[token_accounts]]
+mint = "{mint}"
+owner = "{owner}"
+amount = "1"
+frozen = true
+"#
+        ))
+        .unwrap();
+        assert!(spec
+            .add_token_accounts(&mut GenesisConfig::default())
+            .is_err());
+
+        // unknown fields are rejected
+        assert!(toml::from_str::<GenesisSpec>("[[token_mints]]\nsupply = 1\n").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(clippy::arithmetic_side_effects)]
 pub mod address_generator;
 pub mod genesis_accounts;
+pub mod genesis_spec;
 pub mod stakes;
 pub mod unlocks;
 
@@ -7,7 +7,7 @@ use {
     chrono::DateTime,
     clap::{Arg, ArgAction, ArgMatches, Command},
     itertools::Itertools,
-    solana_account::{Account, AccountSharedData, ReadableAccount, WritableAccount},
+    solana_account::{AccountSharedData, ReadableAccount, WritableAccount},
 
     solana_clap_utils::input_validators::{
         is_pubkey, is_pubkey_or_keypair, is_rfc3339_datetime, is_slot, is_url_or_moniker,
@@ -20,14 +20,14 @@ use {
     solana_feature_gate_interface as feature,
     solana_fee_calculator::FeeRateGovernor,
     solana_genesis::{
-        genesis_accounts::add_genesis_accounts, Base64Account, StakedValidatorAccountInfo,
-        ValidatorAccountsFile,
+        genesis_accounts::add_genesis_accounts,
+        genesis_spec::{self, add_program, add_upgradeable_program, GenesisSpec},
+        Base64Account, StakedValidatorAccountInfo, ValidatorAccountsFile,
     },
     solana_genesis_config::{ClusterType, GenesisConfig},
     solana_inflation::Inflation,
     solana_keypair::{read_keypair_file, Keypair},
     solana_ledger::{blockstore::create_new_ledger, blockstore_options::LedgerColumnOptions},
-    solana_loader_v3_interface::state::UpgradeableLoaderState,
 
     solana_poh_config::PohConfig,
     solana_pubkey::Pubkey,
@@ -154,6 +154,41 @@ pub fn load_validator_accounts(
     Ok(())
 }
 
+/// Adds the validators, accounts, stakes, token mints and token accounts declared in a genesis
+/// spec file. The programs are added later, along with the --bpf-program ones.
+pub fn load_genesis_spec(
+    spec: &GenesisSpec,
+    commission: u8,
+    rent: &Rent,
+    genesis_config: &mut GenesisConfig,
+) -> io::Result<()> {
+    for validator in &spec.validators {
+        let pubkeys = [
+            genesis_spec::parse_address(&validator.identity, "identity")?,
+            genesis_spec::parse_address(&validator.vote, "vote")?,
+            genesis_spec::parse_address(&validator.stake, "stake")?,
+        ];
+        let stake_authority = validator
+            .stake_authority
+            .as_deref()
+            .map(|authority| genesis_spec::parse_address(authority, "stake_authority"))
+            .transpose()?;
+        add_validator_accounts(
+            genesis_config,
+            &mut pubkeys.iter(),
+            validator.balance_lamports,
+            validator.stake_lamports,
+            validator.commission.unwrap_or(commission),
+            rent,
+            stake_authority.as_ref(),
+        )?;
+    }
+    spec.add_system_accounts(genesis_config)?;
+    spec.add_stakes(genesis_config)?;
+    spec.add_token_accounts(genesis_config)?;
+    Ok(())
+}
+
 fn check_rpc_genesis_hash(
     cluster_type: &ClusterType,
     rpc_client: &RpcClient,
@@ -482,6 +517,13 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                 .action(ArgAction::Append)
                 .help("The location of a file containing a list of identity, vote, and stake pubkeys and balances for validator accounts to bake into genesis")
         )
+        .arg(
+            Arg::new("genesis_spec")
+                .long("spec")
+                .value_name("FILENAME")
+                .action(ArgAction::Append)
+                .help("The location of a YAML or TOML genesis spec declaring validators, vesting stakes, programs, token mints and token accounts to bake into genesis")
+        )
         .arg(
             Arg::new("cluster_type")
                 .long("cluster-type")
@@ -785,6 +827,19 @@ fn main() -> Result<(), Box<dyn error::Error>> {
         }
     }
 
+    let genesis_specs = matches
+        .get_many::<String>("genesis_spec")
+        .map(|files| {
+            files
+                .map(|file| GenesisSpec::load(file))
+                .collect::<io::Result<Vec<_>>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+    for spec in &genesis_specs {
+        load_genesis_spec(spec, commission, &rent, &mut genesis_config)?;
+    }
+
     let max_genesis_archive_unpacked_size =
         matches
             .get_one::<String>("max_genesis_archive_unpacked_size")
@@ -823,16 +878,7 @@ fn main() -> Result<(), Box<dyn error::Error>> {
             let address = parse_address(address, "address");
             let loader = parse_address(loader, "loader");
             let program_data = parse_program_data(program);
-            genesis_config.add_account(
-                address,
-                AccountSharedData::from(Account {
-                    lamports: genesis_config.rent.minimum_balance(program_data.len()),
-                    data: program_data,
-                    executable: true,
-                    owner: loader,
-                    rent_epoch: 0,
-                }),
-            );
+            add_program(&mut genesis_config, &address, &loader, program_data);
         }
     }
 
@@ -856,42 +902,20 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                 })
             };
 
-            let (programdata_address, _) =
-                Pubkey::find_program_address(&[address.as_ref()], &loader);
-            let mut program_data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
-                slot: 0,
-                upgrade_authority_address: Some(upgrade_authority_address),
-            })
-            .unwrap();
-            program_data.extend_from_slice(&program_data_elf);
-            genesis_config.add_account(
-                programdata_address,
-                AccountSharedData::from(Account {
-                    lamports: genesis_config.rent.minimum_balance(program_data.len()),
-                    data: program_data,
-                    owner: loader,
-                    executable: false,
-                    rent_epoch: 0,
-                }),
-            );
-
-            let program_data = bincode::serialize(&UpgradeableLoaderState::Program {
-                programdata_address,
-            })
-            .unwrap();
-            genesis_config.add_account(
-                address,
-                AccountSharedData::from(Account {
-                    lamports: genesis_config.rent.minimum_balance(program_data.len()),
-                    data: program_data,
-                    owner: loader,
-                    executable: true,
-                    rent_epoch: 0,
-                }),
+            add_upgradeable_program(
+                &mut genesis_config,
+                &address,
+                &loader,
+                &program_data_elf,
+                upgrade_authority_address,
             );
         }
     }
 
+    for spec in &genesis_specs {
+        spec.add_programs(&mut genesis_config)?;
+    }
+
     solana_logger::setup();
     create_new_ledger(
         &ledger_path,
@@ -1370,4 +1394,62 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_load_genesis_spec() {
+        let identity = solana_pubkey::new_rand();
+        let vote = solana_pubkey::new_rand();
+        let stake = solana_pubkey::new_rand();
+        let stake_authority = solana_pubkey::new_rand();
+        let faucet = solana_pubkey::new_rand();
+        let spec: GenesisSpec = toml::from_str(&format!(
+            r#"
+[[validators]]
+identity = "{identity}"
+vote = "{vote}"
+stake = "{stake}"
+balance_lamports = 42
+stake_lamports = 1000000000
+commission = 5
+stake_authority = "{stake_authority}"
+
+[[accounts]]
+address = "{faucet}"
+lamports = 7
+"#
+        ))
+        .unwrap();
+
+        let rent = Rent::default();
+        let mut genesis_config = GenesisConfig::default();
+        load_genesis_spec(&spec, 100, &rent, &mut genesis_config).unwrap();
+
+        assert_eq!(genesis_config.accounts[&identity].lamports, 42);
+        assert_eq!(genesis_config.accounts[&faucet].lamports, 7);
+        let vote_state = VoteStateV3::deserialize(&genesis_config.accounts[&vote].data).unwrap();
+        assert_eq!(vote_state.node_pubkey, identity);
+        assert_eq!(vote_state.commission, 5);
+        let stake_state =
+            borsh1::try_from_slice_unchecked::<StakeStateV2>(&genesis_config.accounts[&stake].data)
+                .unwrap();
+        let StakeStateV2::Stake(meta, stake, _) = stake_state else {
+            panic!("Expected StakeStateV2::Stake variant");
+        };
+        assert_eq!(meta.authorized.staker, stake_authority);
+        assert_eq!(stake.delegation.voter_pubkey, vote);
+
+        // stake lamports must be rent exempt
+        let spec: GenesisSpec = toml::from_str(&format!(
+            r#"
+[[validators]]
+identity = "{identity}"
+vote = "{vote}"
+stake = "{stake}"
+balance_lamports = 42
+stake_lamports = 1
+"#
+        ))
+        .unwrap();
+        assert!(load_genesis_spec(&spec, 100, &rent, &mut GenesisConfig::default()).is_err());
+    }
 }
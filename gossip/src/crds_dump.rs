@@ -0,0 +1,295 @@
+//! Dumps of the CRDS table, used by `solana-gossip crds` to inspect the values
+//! gossiped in a cluster and to compare two dumps.
+
+use {
+    crate::{
+        cluster_info::ClusterInfo,
+        contact_info::Protocol,
+        crds::Crds,
+        crds_data::CrdsData,
+        crds_value::{CrdsValue, CrdsValueLabel},
+    },
+    solana_pubkey::Pubkey,
+    std::collections::{BTreeMap, HashSet},
+};
+
+/// Names of the CRDS value types, as reported in `CrdsDumpEntry::kind`
+pub const CRDS_KINDS: &[&str] = &[
+    "LegacyContactInfo",
+    "Vote",
+    "LowestSlot",
+    "LegacySnapshotHashes",
+    "AccountsHashes",
+    "EpochSlots",
+    "LegacyVersion",
+    "Version",
+    "NodeInstance",
+    "DuplicateShred",
+    "SnapshotHashes",
+    "ContactInfo",
+    "RestartLastVotedForkSlots",
+    "RestartHeaviestFork",
+];
+
+/// A CRDS value, with a human readable summary of its data
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CrdsDumpEntry {
+    pub kind: String,
+    /// Index of the value, for the types an origin can publish several values of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<u16>,
+    pub origin: String,
+    pub wallclock: u64,
+    pub hash: String,
+    pub summary: String,
+}
+
+impl CrdsDumpEntry {
+    fn new(value: &CrdsValue) -> Self {
+        let index = match value.label() {
+            CrdsValueLabel::Vote(index, _) | CrdsValueLabel::EpochSlots(index, _) => {
+                Some(u16::from(index))
+            }
+            CrdsValueLabel::DuplicateShred(index, _) => Some(index),
+            _ => None,
+        };
+        Self {
+            kind: kind(value.data()).to_string(),
+            index,
+            origin: value.pubkey().to_string(),
+            wallclock: value.wallclock(),
+            hash: value.hash().to_string(),
+            summary: summary(value.data()),
+        }
+    }
+
+    /// Identifies the entry across dumps, an origin publishes at most one value per key
+    pub fn key(&self) -> (String, Option<u16>, String) {
+        (self.kind.clone(), self.index, self.origin.clone())
+    }
+}
+
+fn kind(data: &CrdsData) -> &'static str {
+    match data {
+        CrdsData::LegacyContactInfo(_) => "LegacyContactInfo",
+        CrdsData::Vote(..) => "Vote",
+        CrdsData::LowestSlot(..) => "LowestSlot",
+        CrdsData::LegacySnapshotHashes(_) => "LegacySnapshotHashes",
+        CrdsData::AccountsHashes(_) => "AccountsHashes",
+        CrdsData::EpochSlots(..) => "EpochSlots",
+        CrdsData::LegacyVersion(_) => "LegacyVersion",
+        CrdsData::Version(_) => "Version",
+        CrdsData::NodeInstance(_) => "NodeInstance",
+        CrdsData::DuplicateShred(..) => "DuplicateShred",
+        CrdsData::SnapshotHashes(_) => "SnapshotHashes",
+        CrdsData::ContactInfo(_) => "ContactInfo",
+        CrdsData::RestartLastVotedForkSlots(_) => "RestartLastVotedForkSlots",
+        CrdsData::RestartHeaviestFork(_) => "RestartHeaviestFork",
+    }
+}
+
+fn summary(data: &CrdsData) -> String {
+    match data {
+        CrdsData::ContactInfo(node) => format!(
+            "shred_version: {}, version: {:?}, gossip: {:?}, tpu: {:?}, rpc: {:?}",
+            node.shred_version(),
+            node.version(),
+            node.gossip(),
+            node.tpu(Protocol::UDP),
+            node.rpc(),
+        ),
+        CrdsData::Vote(_, vote) => format!("slot: {:?}", vote.slot()),
+        CrdsData::LowestSlot(_, lowest_slot) => format!("lowest: {}", lowest_slot.lowest),
+        CrdsData::EpochSlots(_, epoch_slots) => format!(
+            "first_slot: {:?}, compressed_slots: {}",
+            epoch_slots.first_slot(),
+            epoch_slots.slots.len()
+        ),
+        CrdsData::DuplicateShred(_, shred) => format!("slot: {}", shred.slot),
+        CrdsData::SnapshotHashes(hashes) => format!(
+            "full: {}:{}, incremental: {:?}",
+            hashes.full.0,
+            hashes.full.1,
+            hashes
+                .incremental
+                .iter()
+                .map(|(slot, hash)| format!("{slot}:{hash}"))
+                .collect::<Vec<_>>()
+        ),
+        CrdsData::RestartLastVotedForkSlots(slots) => format!(
+            "last_voted_slot: {}, last_voted_hash: {}, shred_version: {}",
+            slots.last_voted_slot, slots.last_voted_hash, slots.shred_version
+        ),
+        CrdsData::RestartHeaviestFork(fork) => format!(
+            "last_slot: {}, last_slot_hash: {}, observed_stake: {}, shred_version: {}",
+            fork.last_slot, fork.last_slot_hash, fork.observed_stake, fork.shred_version
+        ),
+        CrdsData::LegacyContactInfo(_)
+        | CrdsData::LegacySnapshotHashes(_)
+        | CrdsData::AccountsHashes(_)
+        | CrdsData::LegacyVersion(_)
+        | CrdsData::Version(_)
+        | CrdsData::NodeInstance(_) => format!("{data:?}"),
+    }
+}
+
+/// Selects the dumped values by type and origin, an empty set selects all of them
+#[derive(Clone, Debug, Default)]
+pub struct CrdsDumpFilter {
+    pub kinds: HashSet<String>,
+    pub origins: HashSet<Pubkey>,
+}
+
+impl CrdsDumpFilter {
+    fn selects(&self, value: &CrdsValue) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(kind(value.data())))
+            && (self.origins.is_empty() || self.origins.contains(&value.pubkey()))
+    }
+
+    /// Filters entries loaded from a previous dump
+    pub fn selects_entry(&self, entry: &CrdsDumpEntry) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&entry.kind))
+            && (self.origins.is_empty()
+                || self
+                    .origins
+                    .iter()
+                    .any(|origin| origin.to_string() == entry.origin))
+    }
+}
+
+/// Dumps the values of the CRDS table selected by `filter`, sorted by type and origin
+pub fn dump_crds(crds: &Crds, filter: &CrdsDumpFilter) -> Vec<CrdsDumpEntry> {
+    let mut entries: Vec<_> = crds
+        .values()
+        .map(|versioned| &versioned.value)
+        .filter(|value| filter.selects(value))
+        .map(CrdsDumpEntry::new)
+        .collect();
+    entries.sort_unstable_by_key(CrdsDumpEntry::key);
+    entries
+}
+
+/// Dumps the values of the CRDS table of `cluster_info` selected by `filter`
+pub fn dump_cluster_info(
+    cluster_info: &ClusterInfo,
+    filter: &CrdsDumpFilter,
+) -> Vec<CrdsDumpEntry> {
+    dump_crds(&cluster_info.gossip.crds.read().unwrap(), filter)
+}
+
+/// Difference between two dumps
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CrdsDumpDiff {
+    Added(CrdsDumpEntry),
+    Removed(CrdsDumpEntry),
+    Updated {
+        old: CrdsDumpEntry,
+        new: CrdsDumpEntry,
+    },
+}
+
+/// Compares the dumps `old` and `new`, values with the same key are updated if their hash
+/// changed
+pub fn diff_dumps(old: &[CrdsDumpEntry], new: &[CrdsDumpEntry]) -> Vec<CrdsDumpDiff> {
+    let mut old: BTreeMap<_, _> = old.iter().map(|entry| (entry.key(), entry)).collect();
+    let mut diffs = vec![];
+    for entry in new {
+        match old.remove(&entry.key()) {
+            None => diffs.push(CrdsDumpDiff::Added(entry.clone())),
+            Some(old_entry) if old_entry.hash != entry.hash => diffs.push(CrdsDumpDiff::Updated {
+                old: old_entry.clone(),
+                new: entry.clone(),
+            }),
+            Some(_) => (),
+        }
+    }
+    diffs.extend(
+        old.into_values()
+            .map(|entry| CrdsDumpDiff::Removed(entry.clone())),
+    );
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::crds::GossipRoute, solana_keypair::Keypair, solana_signer::Signer,
+        solana_time_utils::timestamp,
+    };
+
+    fn new_crds(values: &[CrdsValue]) -> Crds {
+        let mut crds = Crds::default();
+        for value in values {
+            crds.insert(value.clone(), timestamp(), GossipRoute::LocalMessage)
+                .unwrap();
+        }
+        crds
+    }
+
+    #[test]
+    fn test_dump_crds() {
+        let mut rng = rand::thread_rng();
+        let keypairs: Vec<_> = std::iter::repeat_with(Keypair::new).take(4).collect();
+        let values: Vec<_> = keypairs
+            .iter()
+            .map(|keypair| CrdsValue::new_rand(&mut rng, Some(keypair)))
+            .collect();
+        let crds = new_crds(&values);
+
+        let entries = dump_crds(&crds, &CrdsDumpFilter::default());
+        assert_eq!(entries.len(), values.len());
+        for value in &values {
+            let entry = CrdsDumpEntry::new(value);
+            assert!(entries.contains(&entry));
+            assert!(CRDS_KINDS.contains(&entry.kind.as_str()));
+        }
+
+        let filter = CrdsDumpFilter {
+            origins: HashSet::from([keypairs[0].pubkey()]),
+            ..CrdsDumpFilter::default()
+        };
+        let entries = dump_crds(&crds, &filter);
+        assert_eq!(entries, vec![CrdsDumpEntry::new(&values[0])]);
+        assert!(filter.selects_entry(&entries[0]));
+
+        let filter = CrdsDumpFilter {
+            kinds: HashSet::from(["NoSuchKind".to_string()]),
+            ..CrdsDumpFilter::default()
+        };
+        assert!(dump_crds(&crds, &filter).is_empty());
+    }
+
+    #[test]
+    fn test_diff_dumps() {
+        let entry = |kind: &str, origin: &str, hash: &str| CrdsDumpEntry {
+            kind: kind.to_string(),
+            index: None,
+            origin: origin.to_string(),
+            wallclock: 0,
+            hash: hash.to_string(),
+            summary: String::default(),
+        };
+        let old = vec![
+            entry("ContactInfo", "a", "1"),
+            entry("ContactInfo", "b", "2"),
+            entry("SnapshotHashes", "a", "3"),
+        ];
+        let new = vec![
+            entry("ContactInfo", "a", "1"),
+            entry("SnapshotHashes", "a", "4"),
+            entry("SnapshotHashes", "c", "5"),
+        ];
+        assert_eq!(
+            diff_dumps(&old, &new),
+            vec![
+                CrdsDumpDiff::Updated {
+                    old: old[2].clone(),
+                    new: new[1].clone(),
+                },
+                CrdsDumpDiff::Added(new[2].clone()),
+                CrdsDumpDiff::Removed(old[1].clone()),
+            ]
+        );
+    }
+}
@@ -15,6 +15,7 @@ pub mod cluster_info_metrics;
 pub mod contact_info;
 pub mod crds;
 pub mod crds_data;
+pub mod crds_dump;
 pub mod crds_entry;
 mod crds_filter;
 pub mod crds_gossip;
@@ -1,19 +1,32 @@
 //! A command-line executable for monitoring a cluster's gossip plane.
 
 use {
-    clap::{Arg, ArgAction, ArgMatches, Command},
+    clap::{builder::PossibleValuesParser, Arg, ArgAction, ArgMatches, Command},
     log::{error, info, warn},
     solana_clap_utils::{
         hidden_unless_forced,
         input_validators::{is_keypair_or_ask_keyword, is_port, is_pubkey},
     },
-    solana_gossip::{contact_info::ContactInfo, gossip_service::discover},
+    solana_gossip::{
+        contact_info::ContactInfo,
+        crds_dump::{
+            diff_dumps, dump_cluster_info, CrdsDumpDiff, CrdsDumpEntry, CrdsDumpFilter, CRDS_KINDS,
+        },
+        gossip_service::{discover, make_gossip_node},
+    },
+    solana_keypair::Keypair,
     solana_pubkey::Pubkey,
     solana_streamer::socket::SocketAddrSpace,
     std::{
-        error,
+        error, fs,
+        io::{self, BufRead, BufReader},
         net::{IpAddr, Ipv4Addr, SocketAddr},
         process::exit,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::sleep,
         time::Duration,
     },
 };
@@ -43,6 +56,27 @@ fn parse_matches() -> ArgMatches {
         .value_parser(|s: &str| solana_net_utils::is_host(s.to_string()))
         .help("IP address to bind the node to for gossip (replaces --gossip-host)");
 
+    let crds_type_arg = Arg::new("crds_type")
+        .long("type")
+        .value_name("TYPE")
+        .action(ArgAction::Append)
+        .value_parser(PossibleValuesParser::new(CRDS_KINDS))
+        .help("Only show CRDS values of this type");
+
+    let crds_origin_arg = Arg::new("crds_origin")
+        .long("origin")
+        .value_name("PUBKEY")
+        .action(ArgAction::Append)
+        .value_parser(|s: &str| is_pubkey(s.to_string()))
+        .help("Only show CRDS values published by this node");
+
+    let output_arg = Arg::new("output")
+        .long("output")
+        .value_name("FORMAT")
+        .value_parser(["display", "ndjson"])
+        .default_value("display")
+        .help("Output format, ndjson prints one JSON object per line");
+
     Command::new(env!("CARGO_PKG_NAME"))
         .about(env!("CARGO_PKG_DESCRIPTION"))
         .version("3.0.0")
@@ -147,6 +181,53 @@ fn parse_matches() -> ArgMatches {
                         .help("Maximum time to wait in seconds [default: wait forever]"),
                 ),
         )
+        .subcommand(
+            Command::new("crds")
+                .about("Join gossip as a spy and dump the CRDS values")
+                .disable_version_flag(true)
+                .arg(
+                    Arg::new("entrypoint")
+                        .short('n')
+                        .long("entrypoint")
+                        .value_name("HOST:PORT")
+                        .required(true)
+                        .value_parser(|s: &str| solana_net_utils::is_host_port(s.to_string()))
+                        .help("Rendezvous with the cluster at this entrypoint"),
+                )
+                .arg(
+                    Arg::new("wait")
+                        .long("wait")
+                        .value_name("SECONDS")
+                        .default_value("30")
+                        .help("Time to spend pulling CRDS values before dumping them"),
+                )
+                .arg(&crds_type_arg)
+                .arg(&crds_origin_arg)
+                .arg(&output_arg)
+                .arg(&shred_version_arg),
+        )
+        .subcommand(
+            Command::new("crds-diff")
+                .about("Compare two CRDS dumps in ndjson format")
+                .disable_version_flag(true)
+                .arg(
+                    Arg::new("old")
+                        .index(1)
+                        .value_name("OLD_DUMP")
+                        .required(true)
+                        .help("Path to the older dump"),
+                )
+                .arg(
+                    Arg::new("new")
+                        .index(2)
+                        .value_name("NEW_DUMP")
+                        .required(true)
+                        .help("Path to the newer dump"),
+                )
+                .arg(&crds_type_arg)
+                .arg(&crds_origin_arg)
+                .arg(&output_arg),
+        )
         .get_matches()
 }
 
@@ -389,6 +470,133 @@ fn get_gossip_address(matches: &ArgMatches, entrypoint_addr: Option<SocketAddr>)
     )
 }
 
+fn parse_crds_dump_filter(matches: &ArgMatches) -> CrdsDumpFilter {
+    CrdsDumpFilter {
+        kinds: matches
+            .get_many::<String>("crds_type")
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default(),
+        origins: matches
+            .get_many::<String>("crds_origin")
+            .map(|values| {
+                values
+                    .map(|value| value.parse::<Pubkey>().unwrap())
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+fn format_crds_dump_entry(entry: &CrdsDumpEntry) -> String {
+    let kind = match entry.index {
+        Some(index) => format!("{}[{index}]", entry.kind),
+        None => entry.kind.clone(),
+    };
+    format!(
+        "{kind:<30} {:<44} {:>14} {}",
+        entry.origin, entry.wallclock, entry.summary
+    )
+}
+
+fn process_crds(matches: &ArgMatches, socket_addr_space: SocketAddrSpace) -> io::Result<()> {
+    let wait = matches
+        .get_one::<String>("wait")
+        .unwrap()
+        .parse::<u64>()
+        .unwrap();
+    let filter = parse_crds_dump_filter(matches);
+    let ndjson = matches.get_one::<String>("output").unwrap() == "ndjson";
+    let entrypoint_addr = parse_entrypoint(matches);
+
+    let mut shred_version = matches
+        .get_one::<String>("shred_version")
+        .unwrap()
+        .parse::<u16>()
+        .unwrap();
+    if shred_version == 0 {
+        shred_version = get_entrypoint_shred_version(&entrypoint_addr)
+            .expect("need non-zero shred-version to join the cluster");
+    }
+
+    let exit = Arc::new(AtomicBool::new(false));
+    let (gossip_service, _ip_echo, cluster_info) = make_gossip_node(
+        Keypair::new(),
+        entrypoint_addr.as_ref(),
+        exit.clone(),
+        None, // gossip_addr, join as a spy
+        shred_version,
+        true, // should_check_duplicate_instance
+        socket_addr_space,
+    );
+    info!("Pulling CRDS values for {wait}s");
+    sleep(Duration::from_secs(wait));
+    let entries = dump_cluster_info(&cluster_info, &filter);
+    exit.store(true, Ordering::Relaxed);
+    gossip_service.join().unwrap();
+
+    for entry in entries {
+        if ndjson {
+            println!("{}", serde_json::to_string(&entry)?);
+        } else {
+            println!("{}", format_crds_dump_entry(&entry));
+        }
+    }
+    Ok(())
+}
+
+fn load_crds_dump(path: &str) -> io::Result<Vec<CrdsDumpEntry>> {
+    let file = BufReader::new(fs::File::open(path)?);
+    let mut entries = vec![];
+    for (line_number, line) in file.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{path}:{}: {err}", line_number + 1),
+            )
+        })?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+fn process_crds_diff(matches: &ArgMatches) -> io::Result<()> {
+    let filter = parse_crds_dump_filter(matches);
+    let ndjson = matches.get_one::<String>("output").unwrap() == "ndjson";
+    let load = |name: &str| -> io::Result<Vec<_>> {
+        let mut entries = load_crds_dump(matches.get_one::<String>(name).unwrap())?;
+        entries.retain(|entry| filter.selects_entry(entry));
+        Ok(entries)
+    };
+    let (old, new) = (load("old")?, load("new")?);
+
+    for diff in diff_dumps(&old, &new) {
+        if ndjson {
+            let diff = match diff {
+                CrdsDumpDiff::Added(entry) => serde_json::json!({ "added": entry }),
+                CrdsDumpDiff::Removed(entry) => serde_json::json!({ "removed": entry }),
+                CrdsDumpDiff::Updated { old, new } => {
+                    serde_json::json!({ "updated": { "old": old, "new": new } })
+                }
+            };
+            println!("{diff}");
+            continue;
+        }
+        match diff {
+            CrdsDumpDiff::Added(entry) => println!("+ {}", format_crds_dump_entry(&entry)),
+            CrdsDumpDiff::Removed(entry) => println!("- {}", format_crds_dump_entry(&entry)),
+            CrdsDumpDiff::Updated { old, new } => {
+                println!("< {}", format_crds_dump_entry(&old));
+                println!("> {}", format_crds_dump_entry(&new));
+            }
+        }
+    }
+    Ok(())
+}
+
 fn main() -> Result<(), Box<dyn error::Error>> {
     solana_logger::setup_with_default_filter();
 
@@ -401,6 +609,12 @@ fn main() -> Result<(), Box<dyn error::Error>> {
         Some(("rpc-url", matches)) => {
             process_rpc_url(matches, socket_addr_space)?;
         }
+        Some(("crds", matches)) => {
+            process_crds(matches, socket_addr_space)?;
+        }
+        Some(("crds-diff", matches)) => {
+            process_crds_diff(matches)?;
+        }
         _ => unreachable!(),
     }
 
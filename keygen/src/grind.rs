@@ -0,0 +1,474 @@
+//! Combined patterns, throughput estimates and resumable progress for `solana-keygen grind`
+
+use {
+    serde_json::{json, Value},
+    std::{
+        fs, io,
+        path::{Path, PathBuf},
+        str::FromStr,
+        sync::Mutex,
+        time::Duration,
+    },
+};
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Length of the vast majority of base58 encoded public keys
+const PUBKEY_BASE58_LEN: usize = 44;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Atom {
+    Any,
+    Char(char),
+    Class(Vec<char>),
+}
+
+impl Atom {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Atom::Any => true,
+            Atom::Char(expected) => *expected == c,
+            Atom::Class(chars) => chars.contains(&c),
+        }
+    }
+
+    /// Number of base58 characters matching the atom
+    fn num_matching(&self, ignore_case: bool) -> usize {
+        BASE58_ALPHABET
+            .chars()
+            .map(|c| {
+                if ignore_case {
+                    c.to_ascii_lowercase()
+                } else {
+                    c
+                }
+            })
+            .filter(|c| self.matches(*c))
+            .count()
+    }
+}
+
+/// A sequence of atoms, optionally anchored to the start (`^`) or the end (`$`) of the pubkey
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    anchored_start: bool,
+    anchored_end: bool,
+    atoms: Vec<Atom>,
+}
+
+impl Term {
+    fn matches_at(&self, pubkey: &[char], offset: usize) -> bool {
+        offset + self.atoms.len() <= pubkey.len()
+            && (!self.anchored_end || offset + self.atoms.len() == pubkey.len())
+            && self
+                .atoms
+                .iter()
+                .zip(&pubkey[offset..])
+                .all(|(atom, c)| atom.matches(*c))
+    }
+
+    fn matches(&self, pubkey: &[char]) -> bool {
+        if pubkey.len() < self.atoms.len() {
+            return false;
+        }
+        let last_offset = pubkey.len() - self.atoms.len();
+        if self.anchored_start {
+            self.matches_at(pubkey, 0)
+        } else if self.anchored_end {
+            self.matches_at(pubkey, last_offset)
+        } else {
+            (0..=last_offset).any(|offset| self.matches_at(pubkey, offset))
+        }
+    }
+
+    fn probability(&self, ignore_case: bool) -> f64 {
+        let probability: f64 = self
+            .atoms
+            .iter()
+            .map(|atom| atom.num_matching(ignore_case) as f64 / 58.0)
+            .product();
+        let num_offsets = if self.anchored_start || self.anchored_end {
+            1
+        } else {
+            PUBKEY_BASE58_LEN.saturating_sub(self.atoms.len()) + 1
+        };
+        (probability * num_offsets as f64).min(1.0)
+    }
+}
+
+/// A regex-like pattern a pubkey must match, made of terms that must all match, separated by
+/// `&`.  Each term is a sequence of base58 characters, `.` for any character and `[...]` for a
+/// class of characters (ranges like `a-f` are allowed), optionally anchored with `^` and `$`.
+///
+/// For example `^Gorb&[1-9]$` matches pubkeys starting with `Gorb` and ending with a digit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrindPattern {
+    pattern: String,
+    terms: Vec<Term>,
+}
+
+impl FromStr for GrindPattern {
+    type Err = String;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let terms = pattern
+            .split('&')
+            .map(parse_term)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            pattern: pattern.to_string(),
+            terms,
+        })
+    }
+}
+
+fn check_base58(c: char) -> Result<char, String> {
+    if BASE58_ALPHABET.contains(c) {
+        Ok(c)
+    } else {
+        Err(format!("'{c}' is not a base58 character"))
+    }
+}
+
+fn parse_term(term: &str) -> Result<Term, String> {
+    let (anchored_start, term) = match term.strip_prefix('^') {
+        Some(term) => (true, term),
+        None => (false, term),
+    };
+    let (anchored_end, term) = match term.strip_suffix('$') {
+        Some(term) => (true, term),
+        None => (false, term),
+    };
+    if term.is_empty() {
+        return Err("Expected a non-empty pattern between each &".to_string());
+    }
+
+    let mut atoms = vec![];
+    let mut chars = term.chars();
+    while let Some(c) = chars.next() {
+        let atom = match c {
+            '.' => Atom::Any,
+            '[' => {
+                let mut class = vec![];
+                let mut closed = false;
+                let mut prev = None;
+                while let Some(c) = chars.next() {
+                    match c {
+                        ']' => {
+                            closed = true;
+                            break;
+                        }
+                        '-' if prev.is_some() => {
+                            let first = prev.take().unwrap();
+                            let last = chars
+                                .next()
+                                .filter(|c| *c != ']')
+                                .ok_or("Expected a character after - in [...]")?;
+                            if last < first {
+                                return Err(format!("Invalid range {first}-{last}"));
+                            }
+                            class.extend(
+                                BASE58_ALPHABET
+                                    .chars()
+                                    .filter(|c| (first..=last).contains(c)),
+                            );
+                        }
+                        c => {
+                            class.push(check_base58(c)?);
+                            prev = Some(c);
+                        }
+                    }
+                }
+                if !closed {
+                    return Err("Expected ] to close [".to_string());
+                }
+                if class.is_empty() {
+                    return Err("Expected at least one character in [...]".to_string());
+                }
+                Atom::Class(class)
+            }
+            c => Atom::Char(check_base58(c)?),
+        };
+        atoms.push(atom);
+    }
+    Ok(Term {
+        anchored_start,
+        anchored_end,
+        atoms,
+    })
+}
+
+impl GrindPattern {
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Lowercases the pattern, to match lowercased pubkeys
+    pub fn to_lowercase(&self) -> Self {
+        let lowercase = |c: &char| c.to_ascii_lowercase();
+        let terms = self
+            .terms
+            .iter()
+            .map(|term| Term {
+                atoms: term
+                    .atoms
+                    .iter()
+                    .map(|atom| match atom {
+                        Atom::Any => Atom::Any,
+                        Atom::Char(c) => Atom::Char(lowercase(c)),
+                        Atom::Class(chars) => Atom::Class(chars.iter().map(lowercase).collect()),
+                    })
+                    .collect(),
+                ..*term
+            })
+            .collect();
+        Self {
+            pattern: self.pattern.to_lowercase(),
+            terms,
+        }
+    }
+
+    pub fn is_match(&self, pubkey: &str) -> bool {
+        let pubkey: Vec<char> = pubkey.chars().collect();
+        self.terms.iter().all(|term| term.matches(&pubkey))
+    }
+
+    /// The literal characters every matching pubkey starts with, if any
+    pub fn literal_prefix(&self) -> Option<String> {
+        let prefix: String = self
+            .terms
+            .iter()
+            .find(|term| term.anchored_start)?
+            .atoms
+            .iter()
+            .map_while(|atom| match atom {
+                Atom::Char(c) => Some(*c),
+                _ => None,
+            })
+            .collect();
+        (!prefix.is_empty()).then_some(prefix)
+    }
+
+    /// Estimated probability of a random pubkey matching the pattern, assuming uniformly
+    /// distributed base58 characters and independent terms
+    pub fn probability(&self, ignore_case: bool) -> f64 {
+        self.terms
+            .iter()
+            .map(|term| term.probability(ignore_case))
+            .product()
+    }
+}
+
+/// Estimated probability of a random pubkey starting with `starts` and ending with `ends`
+pub fn starts_and_ends_probability(starts: &str, ends: &str, ignore_case: bool) -> f64 {
+    [starts, ends]
+        .iter()
+        .flat_map(|s| s.chars())
+        .map(|c| Atom::Char(c).num_matching(ignore_case) as f64 / 58.0)
+        .product()
+}
+
+/// Formats the expected time to find `count` pubkeys matching with `probability`, grinding
+/// `keys_per_sec`
+pub fn format_eta(count: u64, probability: f64, keys_per_sec: f64) -> String {
+    if count == 0 {
+        return "done".to_string();
+    }
+    if probability <= 0.0 {
+        return "never".to_string();
+    }
+    if keys_per_sec <= 0.0 {
+        return "unknown".to_string();
+    }
+    let secs = count as f64 / probability / keys_per_sec;
+    if secs > Duration::MAX.as_secs_f64() {
+        return "forever".to_string();
+    }
+    let secs = secs.ceil() as u64;
+    let (days, hours, mins) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+    match (days, hours, mins) {
+        (0, 0, 0) => format!("{secs}s"),
+        (0, 0, mins) => format!("{mins}m {}s", secs % 60),
+        (0, hours, mins) => format!("{hours}h {mins}m"),
+        (days, hours, _) => format!("{days}d {hours}h"),
+    }
+}
+
+/// Grind progress persisted to a file, so an interrupted grind can be resumed
+pub struct GrindProgress {
+    path: Option<PathBuf>,
+    /// Matches found so far, also serializes writes to the progress file
+    found: Mutex<Vec<Value>>,
+}
+
+/// Progress restored from a progress file
+pub struct ResumedProgress {
+    pub attempts: u64,
+    /// Remaining count by pattern description
+    pub remaining: Vec<(String, u64)>,
+}
+
+impl GrindProgress {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            found: Mutex::default(),
+        }
+    }
+
+    /// Loads the progress file, if it exists
+    pub fn load(&self) -> io::Result<Option<ResumedProgress>> {
+        let Some(path) = &self.path else {
+            return Ok(None);
+        };
+        if !path.exists() {
+            return Ok(None);
+        }
+        let invalid = |err: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {err}", path.display()),
+            )
+        };
+        let progress: Value = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| invalid(err.to_string()))?;
+        let attempts = progress["attempts"]
+            .as_u64()
+            .ok_or_else(|| invalid("missing attempts".to_string()))?;
+        let remaining = progress["matches"]
+            .as_array()
+            .ok_or_else(|| invalid("missing matches".to_string()))?
+            .iter()
+            .map(|m| Some((m["pattern"].as_str()?.to_string(), m["remaining"].as_u64()?)))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| invalid("invalid matches".to_string()))?;
+        if let Some(found) = progress["found"].as_array() {
+            self.found.lock().unwrap().clone_from(found);
+        }
+        Ok(Some(ResumedProgress {
+            attempts,
+            remaining,
+        }))
+    }
+
+    pub fn num_found(&self) -> u64 {
+        self.found.lock().unwrap().len() as u64
+    }
+
+    /// Records a match, without its private key or seed phrase
+    pub fn add_found(&self, pubkey: &str, pattern: &str) {
+        self.found
+            .lock()
+            .unwrap()
+            .push(json!({ "pubkey": pubkey, "pattern": pattern }));
+    }
+
+    /// Writes the progress file, if any.  `remaining` is the remaining count by pattern
+    /// description
+    pub fn save(&self, attempts: u64, remaining: &[(String, u64)]) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let found = self.found.lock().unwrap();
+        let progress = json!({
+            "attempts": attempts,
+            "matches": remaining
+                .iter()
+                .map(|(pattern, remaining)| json!({ "pattern": pattern, "remaining": remaining }))
+                .collect::<Vec<_>>(),
+            "found": *found,
+        });
+        // Write to a temporary file first, so an interrupted write doesn't lose the progress
+        let tmp_path = tmp_path(path);
+        fs::write(&tmp_path, serde_json::to_string_pretty(&progress)?)?;
+        fs::rename(tmp_path, path)
+    }
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    PathBuf::from(tmp_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grind_pattern() {
+        let pattern = GrindPattern::from_str("^Gorb&[1-9]$").unwrap();
+        assert!(pattern.is_match("GorbxSomething7"));
+        assert!(!pattern.is_match("GorbxSomethingz"));
+        assert!(!pattern.is_match("xGorbSomething7"));
+        assert_eq!(pattern.literal_prefix(), Some("Gorb".to_string()));
+
+        let pattern = GrindPattern::from_str("sol&^.a").unwrap();
+        assert!(pattern.is_match("Xabcsolz"));
+        assert!(!pattern.is_match("Xbcsolz"));
+        assert!(!pattern.is_match("Xabcsoz"));
+        assert_eq!(pattern.literal_prefix(), None);
+
+        let pattern = GrindPattern::from_str("^ab$").unwrap();
+        assert!(pattern.is_match("ab"));
+        assert!(!pattern.is_match("abc"));
+
+        let pattern = GrindPattern::from_str("^L[A-C]").unwrap().to_lowercase();
+        assert!(pattern.is_match("lbxyz"));
+        assert!(!pattern.is_match("Lbxyz"));
+
+        assert!(GrindPattern::from_str("0x").is_err());
+        assert!(GrindPattern::from_str("a&").is_err());
+        assert!(GrindPattern::from_str("[ab").is_err());
+        assert!(GrindPattern::from_str("[]").is_err());
+        assert!(GrindPattern::from_str("[z-a]").is_err());
+    }
+
+    #[test]
+    fn test_probability() {
+        let pattern = GrindPattern::from_str("^ab").unwrap();
+        assert_eq!(pattern.probability(false), 1.0 / (58.0 * 58.0));
+        assert_eq!(
+            pattern.probability(false),
+            starts_and_ends_probability("a", "b", false)
+        );
+        // Both `a` and `A` are base58 characters, but `l` isn't
+        assert_eq!(
+            GrindPattern::from_str("^a").unwrap().probability(true),
+            2.0 / 58.0
+        );
+        assert_eq!(
+            GrindPattern::from_str("^l").unwrap().probability(true),
+            1.0 / 58.0
+        );
+        assert_eq!(
+            GrindPattern::from_str("^[1-9]").unwrap().probability(false),
+            9.0 / 58.0
+        );
+        assert_eq!(GrindPattern::from_str(".").unwrap().probability(false), 1.0);
+
+        assert_eq!(format_eta(0, 0.5, 10.0), "done");
+        assert_eq!(format_eta(1, 0.5, 1.0), "2s");
+        assert_eq!(format_eta(1, 1.0 / 3_600.0, 1.0), "1h 0m");
+        assert_eq!(format_eta(1, 1.0, 0.0), "unknown");
+    }
+
+    #[test]
+    fn test_grind_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("progress.json");
+
+        let progress = GrindProgress::new(Some(path.clone()));
+        assert!(progress.load().unwrap().is_none());
+        progress.add_found("GorbPubkey", "matches:^Gorb");
+        progress
+            .save(42, &[("matches:^Gorb".to_string(), 2)])
+            .unwrap();
+
+        let progress = GrindProgress::new(Some(path));
+        let resumed = progress.load().unwrap().unwrap();
+        assert_eq!(resumed.attempts, 42);
+        assert_eq!(resumed.remaining, vec![("matches:^Gorb".to_string(), 2)]);
+        assert_eq!(progress.found.lock().unwrap().len(), 1);
+    }
+}
@@ -38,7 +38,9 @@ use {
     std::{
         collections::HashSet,
         error,
+        path::PathBuf,
         rc::Rc,
+        str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
             Arc,
@@ -48,6 +50,10 @@ use {
     },
 };
 
+mod grind;
+
+use grind::{format_eta, GrindPattern, GrindProgress};
+
 mod smallest_length_44_public_key {
     use solana_pubkey::Pubkey;
 
@@ -64,22 +70,70 @@ mod smallest_length_44_public_key {
 struct GrindMatch {
     starts: String,
     ends: String,
+    pattern: Option<GrindPattern>,
     count: AtomicU64,
 }
 
+impl GrindMatch {
+    /// Identifies the match in the output and in the progress file
+    fn description(&self) -> String {
+        match &self.pattern {
+            Some(pattern) => format!("matches:{}", pattern.as_str()),
+            None if self.ends.is_empty() => format!("starts-with:{}", self.starts),
+            None if self.starts.is_empty() => format!("ends-with:{}", self.ends),
+            None => format!("starts-and-ends-with:{}:{}", self.starts, self.ends),
+        }
+    }
+
+    fn is_match(&self, pubkey: &str) -> bool {
+        match &self.pattern {
+            Some(pattern) => pattern.is_match(pubkey),
+            None => pubkey.starts_with(&self.starts) && pubkey.ends_with(&self.ends),
+        }
+    }
+
+    /// Estimated probability of a random pubkey matching
+    fn probability(&self, ignore_case: bool) -> f64 {
+        match &self.pattern {
+            Some(pattern) => pattern.probability(ignore_case),
+            None => grind::starts_and_ends_probability(&self.starts, &self.ends, ignore_case),
+        }
+    }
+
+    /// The literal characters every matching pubkey starts with, if known
+    fn literal_prefix(&self) -> Option<String> {
+        match &self.pattern {
+            Some(pattern) => pattern.literal_prefix(),
+            None => Some(self.starts.clone()),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 enum GrindType {
     Starts,
     Ends,
     StartsAndEnds,
+    Pattern,
 }
 
 fn grind_parser(grind_type: GrindType) -> ValueParser {
     ValueParser::from(move |v: &str| -> Result<String, String> {
+        if let GrindType::Pattern = grind_type {
+            let (pattern, count) = v
+                .rsplit_once(':')
+                .ok_or("Expected : between PATTERN and COUNT")?;
+            GrindPattern::from_str(pattern).map_err(|err| format!("{pattern}: {err}"))?;
+            if count.parse::<u64>().map(|count| count == 0).unwrap_or(true) {
+                return Err(String::from("Expected COUNT to be of type u64"));
+            }
+            return Ok(v.to_string());
+        }
         let (required_div_count, prefix_suffix) = match grind_type {
             GrindType::Starts => (1, "PREFIX"),
             GrindType::Ends => (1, "SUFFIX"),
             GrindType::StartsAndEnds => (2, "PREFIX and SUFFIX"),
+            GrindType::Pattern => unreachable!(),
         };
         if v.matches(':').count() != required_div_count || (v.starts_with(':') || v.ends_with(':'))
         {
@@ -135,9 +189,21 @@ fn output_keypair(
     Ok(())
 }
 
-fn grind_print_info(grind_matches: &[GrindMatch], num_threads: usize) {
+fn grind_print_info(grind_matches: &[GrindMatch], num_threads: usize, ignore_case: bool) {
     println!("Searching with {num_threads} threads for:");
     for gm in grind_matches {
+        let count = gm.count.load(Ordering::Relaxed);
+        let expected_attempts = (count as f64 / gm.probability(ignore_case)).ceil();
+        if let Some(pattern) = &gm.pattern {
+            println!(
+                "\t{} {} matching '{}', expecting ~{} attempts",
+                count,
+                if count > 1 { "pubkeys" } else { "pubkey" },
+                pattern.as_str(),
+                expected_attempts,
+            );
+            continue;
+        }
         let mut msg = Vec::<String>::new();
         if gm.count.load(Ordering::Relaxed) > 1 {
             msg.push("pubkeys".to_string());
@@ -149,23 +215,76 @@ fn grind_print_info(grind_matches: &[GrindMatch], num_threads: usize) {
             msg.push("ends".to_string());
         }
         println!(
-            "\t{} {} that {} with '{}' and {} with '{}'",
-            gm.count.load(Ordering::Relaxed),
-            msg[0],
-            msg[1],
-            gm.starts,
-            msg[2],
-            gm.ends
+            "\t{} {} that {} with '{}' and {} with '{}', expecting ~{} attempts",
+            count, msg[0], msg[1], gm.starts, msg[2], gm.ends, expected_attempts,
         );
     }
 }
 
+/// Reports the grind progress, with the estimated time left for each match based on the
+/// `session_attempts` made in `elapsed_secs`
+fn grind_print_progress(
+    grind_matches: &[GrindMatch],
+    ignore_case: bool,
+    json_output: bool,
+    attempts: u64,
+    session_attempts: u64,
+    elapsed_secs: f64,
+    found: u64,
+) {
+    let keys_per_sec = session_attempts as f64 / elapsed_secs.max(f64::EPSILON);
+    let etas: Vec<_> = grind_matches
+        .iter()
+        .map(|gm| {
+            let remaining = gm.count.load(Ordering::Relaxed);
+            (
+                gm.description(),
+                remaining,
+                format_eta(remaining, gm.probability(ignore_case), keys_per_sec),
+            )
+        })
+        .collect();
+    if json_output {
+        let progress = serde_json::json!({
+            "type": "progress",
+            "attempts": attempts,
+            "elapsedSecs": elapsed_secs as u64,
+            "keysPerSec": keys_per_sec as u64,
+            "found": found,
+            "matches": etas
+                .iter()
+                .map(|(pattern, remaining, eta)| {
+                    serde_json::json!({ "pattern": pattern, "remaining": remaining, "eta": eta })
+                })
+                .collect::<Vec<_>>(),
+        });
+        println!("{progress}");
+        return;
+    }
+    println!(
+        "Searched {} keypairs in {}s ({} keypairs/s). {} matches found.",
+        attempts, elapsed_secs as u64, keys_per_sec as u64, found,
+    );
+    for (pattern, remaining, eta) in etas {
+        if remaining > 0 {
+            println!("\t{pattern}: {remaining} remaining, ETA {eta}");
+        }
+    }
+}
+
+fn grind_remaining(grind_matches: &[GrindMatch]) -> Vec<(String, u64)> {
+    grind_matches
+        .iter()
+        .map(|gm| (gm.description(), gm.count.load(Ordering::Relaxed)))
+        .collect()
+}
+
 fn grind_parse_args(
     ignore_case: bool,
     starts_with_args: HashSet<String>,
     ends_with_args: HashSet<String>,
     starts_and_ends_with_args: HashSet<String>,
-    num_threads: usize,
+    matches_args: HashSet<String>,
 ) -> Vec<GrindMatch> {
     let mut grind_matches = Vec::<GrindMatch>::new();
     for sw in starts_with_args {
@@ -177,6 +296,7 @@ fn grind_parse_args(
                 args[0].to_string()
             },
             ends: "".to_string(),
+            pattern: None,
             count: AtomicU64::new(args[1].parse::<u64>().unwrap()),
         });
     }
@@ -189,6 +309,7 @@ fn grind_parse_args(
             } else {
                 args[0].to_string()
             },
+            pattern: None,
             count: AtomicU64::new(args[1].parse::<u64>().unwrap()),
         });
     }
@@ -205,10 +326,24 @@ fn grind_parse_args(
             } else {
                 args[1].to_string()
             },
+            pattern: None,
             count: AtomicU64::new(args[2].parse::<u64>().unwrap()),
         });
     }
-    grind_print_info(&grind_matches, num_threads);
+    for m in matches_args {
+        let (pattern, count) = m.rsplit_once(':').unwrap();
+        let pattern = GrindPattern::from_str(pattern).unwrap();
+        grind_matches.push(GrindMatch {
+            starts: "".to_string(),
+            ends: "".to_string(),
+            pattern: Some(if ignore_case {
+                pattern.to_lowercase()
+            } else {
+                pattern
+            }),
+            count: AtomicU64::new(count.parse::<u64>().unwrap()),
+        });
+    }
     grind_matches
 }
 
@@ -328,6 +463,17 @@ fn app<'a>(num_threads: &'a str, crate_version: &'a str) -> Command<'a> {
                         .value_parser(grind_parser(GrindType::StartsAndEnds))
                         .help("Saves specified number of keypairs whos public key starts and ends with the indicated prefix and suffix\nExample: --starts-and-ends-with sol:ana:4\nPREFIX and SUFFIX type is Base58\nCOUNT type is u64"),
                 )
+                .arg(
+                    Arg::new("matches")
+                        .long("matches")
+                        .value_name("PATTERN:COUNT")
+                        .number_of_values(1)
+                        .takes_value(true)
+                        .action(ArgAction::Append)
+                        .multiple_values(true)
+                        .value_parser(grind_parser(GrindType::Pattern))
+                        .help("Saves specified number of keypairs whos public key matches all the patterns separated by &\nExample: --matches '^Gorb&[1-9]$:4'\nA pattern is a sequence of Base58 characters, . for any character and [...] for a class of characters like [a-f], optionally anchored with ^ to the start and $ to the end\nCOUNT type is u64"),
+                )
                 .arg(
                     Arg::new("num_threads")
                         .long("num-threads")
@@ -342,6 +488,25 @@ fn app<'a>(num_threads: &'a str, crate_version: &'a str) -> Command<'a> {
                         .long("use-mnemonic")
                         .help("Generate using a mnemonic key phrase.  Expect a significant slowdown in this mode"),
                 )
+                .arg(
+                    Arg::new("progress_file")
+                        .long("progress-file")
+                        .value_name("FILEPATH")
+                        .takes_value(true)
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Persist the progress to this file, and resume from it if it exists. \
+                               Private keys and seed phrases are never written to it"),
+                )
+                .arg(
+                    Arg::new("output_format")
+                        .long("output")
+                        .value_name("FORMAT")
+                        .takes_value(true)
+                        .value_parser(["display", "json"])
+                        .default_value("display")
+                        .help("Return information in specified output format, \
+                               json prints one JSON object per line"),
+                )
                 .arg(
                     derivation_path_arg()
                         .requires("use_mnemonic")
@@ -595,25 +760,56 @@ fn do_main(matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
                 HashSet::new()
             };
 
+            let matches_args: HashSet<String> = matches
+                .get_many::<String>("matches")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default();
+
             if starts_with_args.is_empty()
                 && ends_with_args.is_empty()
                 && starts_and_ends_with_args.is_empty()
+                && matches_args.is_empty()
             {
                 return Err(
-                    "Error: No keypair search criteria provided (--starts-with or --ends-with or --starts-and-ends-with)".into()
+                    "Error: No keypair search criteria provided (--starts-with or --ends-with or --starts-and-ends-with or --matches)".into()
                 );
             }
 
             let num_threads = *matches.get_one::<usize>("num_threads").unwrap();
+            let json_output = matches.get_one::<String>("output_format").unwrap() == "json";
 
             let grind_matches = grind_parse_args(
                 ignore_case,
                 starts_with_args,
                 ends_with_args,
                 starts_and_ends_with_args,
-                num_threads,
+                matches_args,
             );
 
+            let progress = GrindProgress::new(matches.get_one::<PathBuf>("progress_file").cloned());
+            let resumed_attempts = match progress.load()? {
+                Some(resumed) => {
+                    for gm in &grind_matches {
+                        let description = gm.description();
+                        if let Some((_, remaining)) = resumed
+                            .remaining
+                            .iter()
+                            .find(|(pattern, _)| *pattern == description)
+                        {
+                            gm.count.store(*remaining, Ordering::Relaxed);
+                        }
+                    }
+                    if !json_output {
+                        println!("Resuming after {} keypairs", resumed.attempts);
+                    }
+                    resumed.attempts
+                }
+                None => 0,
+            };
+            if !json_output {
+                grind_print_info(&grind_matches, num_threads, ignore_case);
+            }
+
             let use_mnemonic = matches.try_contains_id("use_mnemonic")?;
 
             let derivation_path = acquire_derivation_path(matches)?;
@@ -633,24 +829,27 @@ fn do_main(matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
             // these only encapsulate prefixes 1-9 and A-H.  If the user is searching
             // for a keypair that starts with a prefix of J-Z or a-z, then there is no
             // reason to waste time searching for a keypair that will never match
-            let skip_len_44_pubkeys = grind_matches
-                .iter()
-                .map(|g| {
-                    let target_key = if ignore_case {
-                        g.starts.to_ascii_uppercase()
-                    } else {
-                        g.starts.clone()
-                    };
-                    let target_key =
-                        target_key + &(0..44 - g.starts.len()).map(|_| "1").collect::<String>();
-                    bs58::decode(target_key).into_vec()
-                })
-                .filter_map(|s| s.ok())
-                .all(|s| s.len() > 32);
+            let skip_len_44_pubkeys = grind_matches.iter().all(|g| {
+                let Some(starts) = g.literal_prefix() else {
+                    return false;
+                };
+                let target_key = if ignore_case {
+                    starts.to_ascii_uppercase()
+                } else {
+                    starts.clone()
+                };
+                let target_key =
+                    target_key + &(0..44 - starts.len()).map(|_| "1").collect::<String>();
+                bs58::decode(target_key)
+                    .into_vec()
+                    .map(|s| s.len() > 32)
+                    .unwrap_or(true)
+            });
 
             let grind_matches_thread_safe = Arc::new(grind_matches);
-            let attempts = Arc::new(AtomicU64::new(1));
-            let found = Arc::new(AtomicU64::new(0));
+            let progress = Arc::new(progress);
+            let attempts = Arc::new(AtomicU64::new(resumed_attempts + 1));
+            let found = Arc::new(AtomicU64::new(progress.num_found()));
             let start = Instant::now();
             let done = Arc::new(AtomicBool::new(false));
 
@@ -660,6 +859,7 @@ fn do_main(matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
                     let attempts = attempts.clone();
                     let found = found.clone();
                     let grind_matches_thread_safe = grind_matches_thread_safe.clone();
+                    let progress = progress.clone();
                     let passphrase = passphrase.clone();
                     let passphrase_message = passphrase_message.clone();
                     let derivation_path = derivation_path.clone();
@@ -670,12 +870,19 @@ fn do_main(matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
                         }
                         let attempts = attempts.fetch_add(1, Ordering::Relaxed);
                         if attempts % 1_000_000 == 0 {
-                            println!(
-                                "Searched {} keypairs in {}s. {} matches found.",
+                            grind_print_progress(
+                                &grind_matches_thread_safe,
+                                ignore_case,
+                                json_output,
                                 attempts,
-                                start.elapsed().as_secs(),
+                                attempts - resumed_attempts,
+                                start.elapsed().as_secs_f64(),
                                 found.load(Ordering::Relaxed),
                             );
+                            let remaining = grind_remaining(&grind_matches_thread_safe);
+                            if let Err(err) = progress.save(attempts, &remaining) {
+                                eprintln!("Failed to save the progress: {err}");
+                            }
                         }
                         let (keypair, phrase) = if use_mnemonic {
                             let mnemonic = Mnemonic::new(mnemonic_type, language);
@@ -697,33 +904,36 @@ fn do_main(matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
                             pubkey = pubkey.to_lowercase();
                         }
                         let mut total_matches_found = 0;
-                        for i in 0..grind_matches_thread_safe.len() {
-                            if grind_matches_thread_safe[i].count.load(Ordering::Relaxed) == 0 {
+                        for grind_match in grind_matches_thread_safe.iter() {
+                            if grind_match.count.load(Ordering::Relaxed) == 0 {
                                 total_matches_found += 1;
                                 continue;
                             }
-                            if (!grind_matches_thread_safe[i].starts.is_empty()
-                                && grind_matches_thread_safe[i].ends.is_empty()
-                                && pubkey.starts_with(&grind_matches_thread_safe[i].starts))
-                                || (grind_matches_thread_safe[i].starts.is_empty()
-                                    && !grind_matches_thread_safe[i].ends.is_empty()
-                                    && pubkey.ends_with(&grind_matches_thread_safe[i].ends))
-                                || (!grind_matches_thread_safe[i].starts.is_empty()
-                                    && !grind_matches_thread_safe[i].ends.is_empty()
-                                    && pubkey.starts_with(&grind_matches_thread_safe[i].starts)
-                                    && pubkey.ends_with(&grind_matches_thread_safe[i].ends))
-                            {
+                            if grind_match.is_match(&pubkey) {
                                 let _found = found.fetch_add(1, Ordering::Relaxed);
-                                grind_matches_thread_safe[i]
-                                    .count
-                                    .fetch_sub(1, Ordering::Relaxed);
+                                grind_match.count.fetch_sub(1, Ordering::Relaxed);
+                                let outfile = format!("{}.json", keypair.pubkey());
                                 if !no_outfile {
-                                    write_keypair_file(&keypair, format!("{}.json", keypair.pubkey()))
-                                    .unwrap();
-                                    println!(
-                                        "Wrote keypair to {}",
-                                        &format!("{}.json", keypair.pubkey())
-                                    );
+                                    write_keypair_file(&keypair, &outfile).unwrap();
+                                }
+                                progress.add_found(&keypair.pubkey().to_string(), &grind_match.description());
+                                let remaining = grind_remaining(&grind_matches_thread_safe);
+                                if let Err(err) = progress.save(attempts, &remaining) {
+                                    eprintln!("Failed to save the progress: {err}");
+                                }
+                                if json_output {
+                                    let found_match = serde_json::json!({
+                                        "type": "found",
+                                        "pubkey": keypair.pubkey().to_string(),
+                                        "pattern": grind_match.description(),
+                                        "outfile": (!no_outfile).then_some(&outfile),
+                                        "seedPhrase": use_mnemonic.then_some(&phrase),
+                                    });
+                                    println!("{found_match}");
+                                    continue;
+                                }
+                                if !no_outfile {
+                                    println!("Wrote keypair to {outfile}");
                                 }
                                 if use_mnemonic {
                                     let divider = String::from_utf8(vec![b'='; phrase.len()]).unwrap();
@@ -747,6 +957,10 @@ fn do_main(matches: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
             for thread_handle in thread_handles {
                 thread_handle.join().unwrap();
             }
+            progress.save(
+                attempts.load(Ordering::Relaxed),
+                &grind_remaining(&grind_matches_thread_safe),
+            )?;
         }
         ("verify", matches) => {
             let keypair = get_keypair_from_matches(matches, config, &mut wallet_manager)?;
@@ -1150,6 +1364,42 @@ mod tests {
             "b:1",
         ])
         .unwrap();
+
+        let outdir = tempdir().unwrap();
+        let progress_file = outdir.path().join("progress.json");
+        let progress_file = progress_file.to_str().unwrap();
+        for _ in 0..2 {
+            // The second run resumes from the progress file, with nothing left to find
+            process_test_command(&[
+                "solana-keygen",
+                "grind",
+                "--no-outfile",
+                "--no-bip39-passphrase",
+                "--use-mnemonic",
+                "--matches",
+                "^[a-c]&[1-9]$:1",
+                "--progress-file",
+                progress_file,
+                "--output",
+                "json",
+            ])
+            .unwrap();
+            let progress: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(progress_file).unwrap()).unwrap();
+            assert_eq!(progress["matches"][0]["pattern"], "matches:^[a-c]&[1-9]$");
+            assert_eq!(progress["matches"][0]["remaining"], 0);
+            assert_eq!(progress["found"].as_array().unwrap().len(), 1);
+        }
+
+        let default_num_threads = num_cpus::get().to_string();
+        let solana_version = solana_version::version!();
+        let result = app(&default_num_threads, solana_version).try_get_matches_from([
+            "solana-keygen",
+            "grind",
+            "--matches",
+            "^0:1",
+        ]);
+        assert!(result.is_err());
     }
 
     #[test]
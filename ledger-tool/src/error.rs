@@ -1,4 +1,7 @@
-use {solana_ledger::blockstore::BlockstoreError, thiserror::Error};
+use {
+    solana_ledger::blockstore::BlockstoreError, solana_runtime::snapshot_utils::SnapshotError,
+    thiserror::Error,
+};
 
 pub type Result<T> = std::result::Result<T, LedgerToolError>;
 
@@ -16,6 +19,9 @@ pub enum LedgerToolError {
     #[error("{0}")]
     Io(#[from] std::io::Error),
 
+    #[error("{0}")]
+    Snapshot(#[from] SnapshotError),
+
     #[error("{0}")]
     Generic(String),
 
@@ -11,6 +11,7 @@ use {
             SlotBankHash,
         },
         program::*,
+        snapshot::*,
     },
     agave_feature_set::{self as feature_set, FeatureSet},
     agave_reserved_account_keys::ReservedAccountKeys,
@@ -18,7 +19,7 @@ use {
         crate_description, crate_name,
         Arg, ArgMatches, Command as ClapCommand, ArgAction,
     },
-    dashmap::DashMap,
+    dashmap::{DashMap, DashSet},
     log::*,
     serde_derive::Serialize,
     solana_account::{state_traits::StateMut, AccountSharedData, ReadableAccount, WritableAccount},
@@ -107,6 +108,7 @@ mod ledger_path;
 mod ledger_utils;
 mod output;
 mod program;
+mod snapshot;
 
 fn render_dot(dot: String, output_file: &str, output_format: &str) -> io::Result<()> {
     let mut child = Command::new("dot")
@@ -514,27 +516,65 @@ fn compute_slot_cost(
     Ok(())
 }
 
-/// Finds the accounts needed to replay slots `snapshot_slot` to `ending_slot`.
+/// Finds the accounts needed to replay slots `snapshot_slot` to `ending_slot`,
+/// if any, along with `included_accounts` and the accounts owned by
+/// `included_owners`.
 /// Removes all other accounts from accounts_db, and updates the accounts hash
-/// and capitalization. This is used by the --minimize option in create-snapshot
+/// and capitalization. This is used by the --minimize, --include-owner and
+/// --include-account-file options in create-snapshot
 /// Returns true if the minimized snapshot may be incomplete.
 fn minimize_bank_for_snapshot(
     blockstore: &Blockstore,
     bank: &Bank,
     snapshot_slot: Slot,
-    ending_slot: Slot,
+    ending_slot: Option<Slot>,
+    included_owners: HashSet<Pubkey>,
+    included_accounts: Vec<Pubkey>,
 ) -> bool {
-    let ((transaction_account_set, possibly_incomplete), transaction_accounts_measure) = measure_time!(
-        blockstore.get_accounts_used_in_range(bank, snapshot_slot, ending_slot),
-        "get transaction accounts"
-    );
-    let total_accounts_len = transaction_account_set.len();
-    info!("Added {total_accounts_len} accounts from transactions. {transaction_accounts_measure}");
+    let (account_set, possibly_incomplete) = if let Some(ending_slot) = ending_slot {
+        let ((transaction_account_set, possibly_incomplete), transaction_accounts_measure) = measure_time!(
+            blockstore.get_accounts_used_in_range(bank, snapshot_slot, ending_slot),
+            "get transaction accounts"
+        );
+        let total_accounts_len = transaction_account_set.len();
+        info!("Added {total_accounts_len} accounts from transactions. {transaction_accounts_measure}");
+        (transaction_account_set, possibly_incomplete)
+    } else {
+        (DashSet::new(), false)
+    };
+    let included_accounts_len = included_accounts.len();
+    for pubkey in included_accounts {
+        account_set.insert(pubkey);
+    }
+    info!("Added {included_accounts_len} included accounts");
 
-    SnapshotMinimizer::minimize(bank, snapshot_slot, transaction_account_set);
+    SnapshotMinimizer::minimize_with_included_owners(
+        bank,
+        snapshot_slot,
+        account_set,
+        included_owners,
+    );
     possibly_incomplete
 }
 
+/// Reads the pubkeys listed in `path`, one per line. Blank lines and lines
+/// starting with `#` are ignored.
+fn read_pubkeys_file(path: &Path) -> io::Result<Vec<Pubkey>> {
+    std::fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            Pubkey::from_str(line).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid pubkey '{line}': {err}"),
+                )
+            })
+        })
+        .collect()
+}
+
 fn assert_capitalization(bank: &Bank) {
     let calculated = bank.calculate_capitalization_for_tests();
     let expected = bank.capitalization();
@@ -1429,6 +1469,30 @@ fn main() {
                         .conflicts_with("incremental")
                         .requires("ending_slot"),
                 )
+                .arg(
+                    Arg::new("include_owner")
+                        .long("include-owner")
+                        .value_name("PUBKEY")
+                        .value_parser(clap::value_parser!(String))
+                        .action(ArgAction::Append)
+                        .conflicts_with("incremental")
+                        .help(
+                            "Create a minimized snapshot that keeps every account owned by \
+                             PUBKEY, along with the accounts needed to boot from the \
+                             snapshot. May be specified multiple times.",
+                        ),
+                )
+                .arg(
+                    Arg::new("include_account_file")
+                        .long("include-account-file")
+                        .value_name("PATH")
+                        .conflicts_with("incremental")
+                        .help(
+                            "Create a minimized snapshot that keeps the accounts listed in PATH, \
+                             one pubkey per line, along with the accounts needed to boot from \
+                             the snapshot. Blank lines and lines starting with '#' are ignored.",
+                        ),
+                )
                 .arg(
                     Arg::new("ending_slot")
                         .long("ending-slot")
@@ -1636,6 +1700,7 @@ fn main() {
                 .arg(&allow_dead_slots_arg),
         )
         .program_subcommand()
+        .snapshot_subcommand()
         .get_matches();
 
     info!("{} {}", crate_name!(), solana_version::version!());
@@ -1653,6 +1718,7 @@ fn main() {
         Some(("bigtable", arg_matches)) => bigtable_process_command(&ledger_path, arg_matches),
         Some(("blockstore", arg_matches)) => blockstore_process_command(&ledger_path, arg_matches),
         Some(("program", arg_matches)) => program(&ledger_path, arg_matches),
+        Some(("snapshot", arg_matches)) => snapshot_process_command(&ledger_path, arg_matches),
         // This match case provides legacy support for commands that were previously top level
         // subcommands of the binary, but have been moved under the blockstore subcommand.
         Some(("analyze-storage", _))
@@ -1929,6 +1995,28 @@ fn main() {
 
                     let is_incremental = arg_matches.get_flag("incremental");
                     let is_minimized = arg_matches.get_flag("minimized");
+                    let included_owners: HashSet<Pubkey> = arg_matches
+                        .get_many::<String>("include_owner")
+                        .map(|values| {
+                            values
+                                .map(|s| {
+                                    s.parse().unwrap_or_else(|_| {
+                                        eprintln!("Error: invalid --include-owner pubkey: {s}");
+                                        exit(1);
+                                    })
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    let included_accounts = arg_matches
+                        .get_one::<String>("include_account_file")
+                        .map(|path| {
+                            read_pubkeys_file(Path::new(path)).unwrap_or_else(|err| {
+                                eprintln!("Error: unable to read {path}: {err}");
+                                exit(1);
+                            })
+                        });
+                    let is_scoped = !included_owners.is_empty() || included_accounts.is_some();
                     let output_directory = arg_matches.get_one::<String>("output_directory").map(|s| s.parse::<PathBuf>().unwrap())
                         .unwrap_or_else(|| {
                             let snapshot_archive_path = arg_matches.get_one::<String>("snapshots")
@@ -2004,7 +2092,9 @@ fn main() {
                             .unwrap_or_else(|| {
                                 panic!("Archive format not recognized: {archive_format_str}")
                             });
-                        if let ArchiveFormat::TarZstd { config } = &mut archive_format {
+                        if let ArchiveFormat::TarZstd { config }
+                        | ArchiveFormat::TarZstdChunked { config } = &mut archive_format
+                        {
                             config.compression_level = arg_matches.get_one::<String>("snapshot_zstd_compression_level")
                                 .unwrap().parse().unwrap();
                         }
@@ -2064,7 +2154,7 @@ fn main() {
 
                     let snapshot_type_str = if is_incremental {
                         "incremental "
-                    } else if is_minimized {
+                    } else if is_minimized || is_scoped {
                         "minimized "
                     } else {
                         ""
@@ -2373,12 +2463,14 @@ fn main() {
                         bank
                     };
 
-                    let minimize_snapshot_possibly_incomplete = if is_minimized {
+                    let minimize_snapshot_possibly_incomplete = if is_minimized || is_scoped {
                         minimize_bank_for_snapshot(
                             &blockstore,
                             &bank,
                             snapshot_slot,
-                            ending_slot.unwrap(),
+                            ending_slot,
+                            included_owners,
+                            included_accounts.unwrap_or_default(),
                         )
                     } else {
                         false
@@ -17,7 +17,7 @@ use {
         display::writeln_transaction, CliAccount, CliAccountNewConfig, OutputFormat, QuietDisplay,
         VerboseDisplay,
     },
-    solana_clock::{Slot, UnixTimestamp},
+    solana_clock::{Epoch, Slot, UnixTimestamp},
     solana_hash::Hash,
     solana_ledger::{
         blockstore::{Blockstore, BlockstoreError},
@@ -26,7 +26,11 @@ use {
     },
     solana_native_token::lamports_to_sol,
     solana_pubkey::Pubkey,
-    solana_runtime::bank::{Bank, TotalAccountsStats},
+    solana_runtime::{
+        bank::{Bank, TotalAccountsStats},
+        snapshot_bank_utils::SnapshotSummary,
+        snapshot_package::AccountDiff,
+    },
     solana_transaction::versioned::VersionedTransaction,
     solana_transaction_status::{
         BlockEncodingOptions, ConfirmedBlock, Encodable, EncodedConfirmedBlock,
@@ -938,3 +942,306 @@ impl fmt::Display for CliAccounts {
 }
 impl QuietDisplay for CliAccounts {}
 impl VerboseDisplay for CliAccounts {}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSnapshotSummary {
+    pub slot: Slot,
+    pub parent_slot: Slot,
+    pub epoch: Epoch,
+    pub block_height: u64,
+    pub bank_hash: String,
+    pub parent_bank_hash: String,
+    pub capitalization: u64,
+    pub transaction_count: u64,
+    pub signature_count: u64,
+    pub accounts_data_len: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accounts_lt_hash_checksum: Option<String>,
+    pub epoch_stakes: Vec<CliEpochStakes>,
+    pub status_cache: CliStatusCacheSummary,
+    pub storages: CliStoragesSummary,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliEpochStakes {
+    pub epoch: Epoch,
+    pub total_stake: u64,
+    pub num_vote_accounts: usize,
+    pub num_nodes: usize,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliStatusCacheSummary {
+    pub num_slots: usize,
+    pub num_blockhashes: usize,
+    pub num_statuses: usize,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliStoragesSummary {
+    pub num_files: usize,
+    pub num_stored_accounts: usize,
+    pub total_bytes: u64,
+    pub largest_file_bytes: u64,
+    pub oldest_slot: Option<Slot>,
+    pub newest_slot: Option<Slot>,
+}
+
+impl From<SnapshotSummary> for CliSnapshotSummary {
+    fn from(summary: SnapshotSummary) -> Self {
+        Self {
+            slot: summary.slot,
+            parent_slot: summary.parent_slot,
+            epoch: summary.epoch,
+            block_height: summary.block_height,
+            bank_hash: summary.bank_hash.to_string(),
+            parent_bank_hash: summary.parent_bank_hash.to_string(),
+            capitalization: summary.capitalization,
+            transaction_count: summary.transaction_count,
+            signature_count: summary.signature_count,
+            accounts_data_len: summary.accounts_data_len,
+            accounts_lt_hash_checksum: summary
+                .accounts_lt_hash
+                .map(|accounts_lt_hash| accounts_lt_hash.0.checksum().to_string()),
+            epoch_stakes: summary
+                .epoch_stakes
+                .into_iter()
+                .map(|epoch_stakes| CliEpochStakes {
+                    epoch: epoch_stakes.epoch,
+                    total_stake: epoch_stakes.total_stake,
+                    num_vote_accounts: epoch_stakes.num_vote_accounts,
+                    num_nodes: epoch_stakes.num_nodes,
+                })
+                .collect(),
+            status_cache: CliStatusCacheSummary {
+                num_slots: summary.status_cache.num_slots,
+                num_blockhashes: summary.status_cache.num_blockhashes,
+                num_statuses: summary.status_cache.num_statuses,
+            },
+            storages: CliStoragesSummary {
+                num_files: summary.storages.num_files,
+                num_stored_accounts: summary.storages.num_stored_accounts,
+                total_bytes: summary.storages.total_bytes,
+                largest_file_bytes: summary.storages.largest_file_bytes,
+                oldest_slot: summary.storages.oldest_slot,
+                newest_slot: summary.storages.newest_slot,
+            },
+        }
+    }
+}
+
+impl QuietDisplay for CliSnapshotSummary {}
+impl VerboseDisplay for CliSnapshotSummary {}
+
+impl Display for CliSnapshotSummary {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "Slot: {}", self.slot)?;
+        writeln!(f, "Parent slot: {}", self.parent_slot)?;
+        writeln!(f, "Epoch: {}", self.epoch)?;
+        writeln!(f, "Block height: {}", self.block_height)?;
+        writeln!(f, "Bank hash: {}", self.bank_hash)?;
+        writeln!(f, "Parent bank hash: {}", self.parent_bank_hash)?;
+        writeln!(
+            f,
+            "Capitalization: {} SOL",
+            lamports_to_sol(self.capitalization)
+        )?;
+        writeln!(f, "Transaction count: {}", self.transaction_count)?;
+        writeln!(f, "Signature count: {}", self.signature_count)?;
+        writeln!(f, "Accounts data len: {}", self.accounts_data_len)?;
+        if let Some(checksum) = &self.accounts_lt_hash_checksum {
+            writeln!(f, "Accounts lt hash checksum: {checksum}")?;
+        }
+
+        writeln!(f, "Epoch stakes:")?;
+        for epoch_stakes in &self.epoch_stakes {
+            writeln!(
+                f,
+                "  epoch {}: {} SOL staked to {} vote accounts of {} nodes",
+                epoch_stakes.epoch,
+                lamports_to_sol(epoch_stakes.total_stake),
+                epoch_stakes.num_vote_accounts,
+                epoch_stakes.num_nodes,
+            )?;
+        }
+
+        writeln!(
+            f,
+            "Status cache: {} slots, {} blockhashes, {} statuses",
+            self.status_cache.num_slots,
+            self.status_cache.num_blockhashes,
+            self.status_cache.num_statuses,
+        )?;
+
+        write!(
+            f,
+            "Storages: {} files holding {} accounts in {} bytes, largest file is {} bytes",
+            self.storages.num_files,
+            self.storages.num_stored_accounts,
+            self.storages.total_bytes,
+            self.storages.largest_file_bytes,
+        )?;
+        if let (Some(oldest_slot), Some(newest_slot)) =
+            (self.storages.oldest_slot, self.storages.newest_slot)
+        {
+            write!(f, ", slots {oldest_slot} to {newest_slot}")?;
+        }
+        writeln!(f)
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliAccountState {
+    pub owner: String,
+    pub lamports: u64,
+    pub data_len: usize,
+    pub executable: bool,
+}
+
+impl From<&AccountSharedData> for CliAccountState {
+    fn from(account: &AccountSharedData) -> Self {
+        Self {
+            owner: account.owner().to_string(),
+            lamports: account.lamports(),
+            data_len: account.data().len(),
+            executable: account.executable(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliAccountDiff {
+    pub pubkey: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<CliAccountState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<CliAccountState>,
+    pub data_changed: bool,
+}
+
+impl CliAccountDiff {
+    pub fn new(pubkey: &Pubkey, diff: &AccountDiff) -> Self {
+        let (old, new, data_changed) = match diff {
+            AccountDiff::Added(new) => (None, Some(new.into()), false),
+            AccountDiff::Removed(old) => (Some(old.into()), None, false),
+            AccountDiff::Changed { old, new } => {
+                (Some(old.into()), Some(new.into()), old.data() != new.data())
+            }
+        };
+        Self {
+            pubkey: pubkey.to_string(),
+            old,
+            new,
+            data_changed,
+        }
+    }
+}
+
+impl Display for CliAccountDiff {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match (&self.old, &self.new) {
+            (None, Some(new)) => writeln!(
+                f,
+                "+ {}: {} lamports, {} bytes, owner {}",
+                self.pubkey, new.lamports, new.data_len, new.owner
+            ),
+            (Some(old), None) => writeln!(
+                f,
+                "- {}: {} lamports, {} bytes, owner {}",
+                self.pubkey, old.lamports, old.data_len, old.owner
+            ),
+            (Some(old), Some(new)) => {
+                write!(f, "~ {}:", self.pubkey)?;
+                if old.lamports != new.lamports {
+                    write!(f, " lamports {} -> {}", old.lamports, new.lamports)?;
+                }
+                if old.data_len != new.data_len {
+                    write!(f, " bytes {} -> {}", old.data_len, new.data_len)?;
+                } else if self.data_changed {
+                    write!(f, " data changed")?;
+                }
+                if old.owner != new.owner {
+                    write!(f, " owner {} -> {}", old.owner, new.owner)?;
+                }
+                if old.executable != new.executable {
+                    write!(f, " executable {} -> {}", old.executable, new.executable)?;
+                }
+                writeln!(f)
+            }
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CliOwnerDiffSummary {
+    pub owner: String,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub lamports_delta: i128,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSnapshotDiff {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<CliAccountDiff>,
+    /// Summaries of the differences per owner, sorted by owner
+    pub owners: Vec<CliOwnerDiffSummary>,
+}
+
+impl CliSnapshotDiff {
+    /// Accounts are attributed to their owner in the new snapshot, unless they were removed
+    pub fn accumulate_owner_summary(
+        owners: &mut HashMap<Pubkey, CliOwnerDiffSummary>,
+        diff: &AccountDiff,
+    ) {
+        let (owner, old_lamports, new_lamports) = match diff {
+            AccountDiff::Added(new) => (new.owner(), 0, new.lamports()),
+            AccountDiff::Removed(old) => (old.owner(), old.lamports(), 0),
+            AccountDiff::Changed { old, new } => (new.owner(), old.lamports(), new.lamports()),
+        };
+        let summary = owners.entry(*owner).or_insert_with(|| CliOwnerDiffSummary {
+            owner: owner.to_string(),
+            ..CliOwnerDiffSummary::default()
+        });
+        match diff {
+            AccountDiff::Added(_) => summary.added += 1,
+            AccountDiff::Removed(_) => summary.removed += 1,
+            AccountDiff::Changed { .. } => summary.changed += 1,
+        }
+        summary.lamports_delta += i128::from(new_lamports) - i128::from(old_lamports);
+    }
+}
+
+impl QuietDisplay for CliSnapshotDiff {}
+impl VerboseDisplay for CliSnapshotDiff {}
+
+impl Display for CliSnapshotDiff {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for account in &self.accounts {
+            write!(f, "{account}")?;
+        }
+        writeln!(
+            f,
+            "{:<44}  {:>10}  {:>10}  {:>10}  {:>24}",
+            "Owner", "Added", "Removed", "Changed", "Lamports Delta"
+        )?;
+        for owner in &self.owners {
+            writeln!(
+                f,
+                "{:<44}  {:>10}  {:>10}  {:>10}  {:>24}",
+                owner.owner, owner.added, owner.removed, owner.changed, owner.lamports_delta
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,394 @@
+//! The `snapshot` subcommand
+
+use {
+    crate::{
+        args::{accounts_db_args, get_accounts_db_config, load_genesis_arg},
+        error::{LedgerToolError, Result},
+        ledger_path::{canonicalize_ledger_path, LEDGER_TOOL_DIRECTORY},
+        ledger_utils::open_genesis_config_by,
+        output::{CliAccountDiff, CliSnapshotDiff, CliSnapshotSummary},
+    },
+    clap::{Arg, ArgAction, ArgMatches, Command},
+    crossbeam_channel::{bounded, Sender},
+    log::*,
+    solana_account::AccountSharedData,
+    solana_accounts_db::{
+        accounts_db::AccountsFileId,
+        utils::{create_all_accounts_run_and_snapshot_dirs, move_and_async_delete_path_contents},
+    },
+    solana_cli_output::OutputFormat,
+    solana_clock::Slot,
+    solana_genesis_config::GenesisConfig,
+    solana_pubkey::Pubkey,
+    solana_runtime::{
+        bank::Bank,
+        runtime_config::RuntimeConfig,
+        snapshot_archive_info::{
+            FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter,
+        },
+        snapshot_bank_utils,
+        snapshot_package::{diff_accounts_sorted_by_pubkey, AccountDiff},
+        snapshot_utils,
+    },
+    std::{
+        collections::HashMap,
+        fs,
+        path::{Path, PathBuf},
+        sync::{atomic::AtomicBool, Arc},
+        thread,
+    },
+};
+
+/// Number of accounts buffered between each snapshot's accounts scan and the comparison
+const DIFF_SCAN_CHANNEL_SIZE: usize = 10_000;
+
+pub trait SnapshotSubCommand {
+    fn snapshot_subcommand(self) -> Self;
+}
+
+impl SnapshotSubCommand for Command {
+    fn snapshot_subcommand(self) -> Self {
+        self.subcommand(
+            Command::new("snapshot")
+                .about("Commands to inspect and compare snapshot archives")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("inspect")
+                        .about(
+                            "Print the bank fields, epoch stakes, status cache and storage \
+                             statistics of a snapshot, without rebuilding its bank",
+                        )
+                        .arg(
+                            Arg::new("full_snapshot_archive")
+                                .index(1)
+                                .value_name("FULL_SNAPSHOT_ARCHIVE")
+                                .required(true)
+                                .help("Path to the full snapshot archive"),
+                        )
+                        .arg(
+                            Arg::new("incremental_snapshot_archive")
+                                .long("incremental")
+                                .value_name("INCREMENTAL_SNAPSHOT_ARCHIVE")
+                                .help("Path to an incremental snapshot archive on top of the full one"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("diff")
+                        .about(
+                            "Report the accounts added, removed and changed between two snapshots",
+                        )
+                        .arg(&load_genesis_arg())
+                        .args(&accounts_db_args())
+                        .arg(
+                            Arg::new("old_full_snapshot_archive")
+                                .index(1)
+                                .value_name("OLD_FULL_SNAPSHOT_ARCHIVE")
+                                .required(true)
+                                .help("Path to the full snapshot archive to compare from"),
+                        )
+                        .arg(
+                            Arg::new("new_full_snapshot_archive")
+                                .index(2)
+                                .value_name("NEW_FULL_SNAPSHOT_ARCHIVE")
+                                .required(true)
+                                .help("Path to the full snapshot archive to compare to"),
+                        )
+                        .arg(
+                            Arg::new("old_incremental_snapshot_archive")
+                                .long("old-incremental")
+                                .value_name("INCREMENTAL_SNAPSHOT_ARCHIVE")
+                                .help("Incremental snapshot archive on top of the old full one"),
+                        )
+                        .arg(
+                            Arg::new("new_incremental_snapshot_archive")
+                                .long("new-incremental")
+                                .value_name("INCREMENTAL_SNAPSHOT_ARCHIVE")
+                                .help("Incremental snapshot archive on top of the new full one"),
+                        )
+                        .arg(
+                            Arg::new("summary_only")
+                                .long("summary-only")
+                                .action(ArgAction::SetTrue)
+                                .help("Only print the per-owner summaries, not every account"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("extract-storage")
+                        .about(
+                            "Extract a single account storage file from a snapshot archive. Only \
+                             the chunk holding the file is decompressed from a chunked archive",
+                        )
+                        .arg(
+                            Arg::new("snapshot_archive")
+                                .index(1)
+                                .value_name("SNAPSHOT_ARCHIVE")
+                                .required(true)
+                                .help("Path to the full or incremental snapshot archive"),
+                        )
+                        .arg(
+                            Arg::new("slot")
+                                .long("slot")
+                                .value_name("SLOT")
+                                .required(true)
+                                .value_parser(clap::value_parser!(Slot))
+                                .help("Slot of the account storage file"),
+                        )
+                        .arg(
+                            Arg::new("id")
+                                .long("id")
+                                .value_name("ID")
+                                .required(true)
+                                .value_parser(clap::value_parser!(AccountsFileId))
+                                .help("Id of the account storage file"),
+                        )
+                        .arg(
+                            Arg::new("output_directory")
+                                .long("output-dir")
+                                .value_name("DIR")
+                                .default_value(".")
+                                .help("Directory to extract the account storage file into"),
+                        ),
+                ),
+        )
+    }
+}
+
+pub fn snapshot_process_command(ledger_path: &Path, matches: &ArgMatches) {
+    do_snapshot_process_command(ledger_path, matches).unwrap_or_else(|err| {
+        eprintln!("Failed to complete command: {err:?}");
+        std::process::exit(1);
+    });
+}
+
+fn do_snapshot_process_command(ledger_path: &Path, matches: &ArgMatches) -> Result<()> {
+    let output_format = match matches
+        .get_one::<String>("output_format")
+        .map(|s| s.as_str())
+    {
+        Some("json") => OutputFormat::Json,
+        Some("json-compact") => OutputFormat::JsonCompact,
+        _ => OutputFormat::Display,
+    };
+
+    match matches.subcommand() {
+        Some(("inspect", arg_matches)) => {
+            let (full_snapshot_archive_info, incremental_snapshot_archive_info) =
+                snapshot_archive_infos_of(
+                    arg_matches,
+                    "full_snapshot_archive",
+                    "incremental_snapshot_archive",
+                )?;
+            let summary = snapshot_bank_utils::summarize_snapshot_archives(
+                &full_snapshot_archive_info,
+                incremental_snapshot_archive_info.as_ref(),
+                Default::default(),
+            )?;
+            println!(
+                "{}",
+                output_format.formatted_string(&CliSnapshotSummary::from(summary))
+            );
+        }
+        Some(("diff", arg_matches)) => {
+            let ledger_path = canonicalize_ledger_path(ledger_path);
+            let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+            let old_bank = load_bank_for_diff(
+                &ledger_path,
+                arg_matches,
+                &genesis_config,
+                "old",
+                snapshot_archive_infos_of(
+                    arg_matches,
+                    "old_full_snapshot_archive",
+                    "old_incremental_snapshot_archive",
+                )?,
+            )?;
+            let new_bank = load_bank_for_diff(
+                &ledger_path,
+                arg_matches,
+                &genesis_config,
+                "new",
+                snapshot_archive_infos_of(
+                    arg_matches,
+                    "new_full_snapshot_archive",
+                    "new_incremental_snapshot_archive",
+                )?,
+            )?;
+
+            // Accounts are only printed as they are found in display mode, json output is
+            // only printed once the comparison is done
+            let stream_accounts = output_format == OutputFormat::Display;
+            let summary_only = arg_matches.get_flag("summary_only");
+            let mut snapshot_diff = CliSnapshotDiff::default();
+            let mut owners = HashMap::new();
+            diff_banks(&old_bank, &new_bank, |pubkey, diff| {
+                CliSnapshotDiff::accumulate_owner_summary(&mut owners, &diff);
+                if !summary_only {
+                    let account_diff = CliAccountDiff::new(&pubkey, &diff);
+                    if stream_accounts {
+                        print!("{account_diff}");
+                    } else {
+                        snapshot_diff.accounts.push(account_diff);
+                    }
+                }
+            });
+
+            snapshot_diff.owners = owners.into_values().collect();
+            snapshot_diff
+                .owners
+                .sort_unstable_by(|a, b| a.owner.cmp(&b.owner));
+            println!("{}", output_format.formatted_string(&snapshot_diff));
+        }
+        Some(("extract-storage", arg_matches)) => {
+            let snapshot_archive_path =
+                PathBuf::from(arg_matches.get_one::<String>("snapshot_archive").unwrap());
+            let archive_format =
+                FullSnapshotArchiveInfo::new_from_path(snapshot_archive_path.clone())
+                    .map(|info| info.archive_format())
+                    .or_else(|_| {
+                        IncrementalSnapshotArchiveInfo::new_from_path(snapshot_archive_path.clone())
+                            .map(|info| info.archive_format())
+                    })
+                    .map_err(|err| {
+                        LedgerToolError::BadArgument(format!(
+                            "invalid snapshot archive '{}': {err}",
+                            snapshot_archive_path.display()
+                        ))
+                    })?;
+            let output_directory =
+                PathBuf::from(arg_matches.get_one::<String>("output_directory").unwrap());
+            fs::create_dir_all(&output_directory)?;
+            let unpacked_path = snapshot_utils::unarchive_snapshot_storage_file(
+                &snapshot_archive_path,
+                archive_format,
+                *arg_matches.get_one::<Slot>("slot").unwrap(),
+                *arg_matches.get_one::<AccountsFileId>("id").unwrap(),
+                &output_directory,
+            )?;
+            println!("{}", unpacked_path.display());
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+fn snapshot_archive_infos_of(
+    matches: &ArgMatches,
+    full_name: &str,
+    incremental_name: &str,
+) -> Result<(
+    FullSnapshotArchiveInfo,
+    Option<IncrementalSnapshotArchiveInfo>,
+)> {
+    let full_snapshot_archive_path = PathBuf::from(matches.get_one::<String>(full_name).unwrap());
+    let full_snapshot_archive_info = FullSnapshotArchiveInfo::new_from_path(
+        full_snapshot_archive_path.clone(),
+    )
+    .map_err(|err| {
+        LedgerToolError::BadArgument(format!(
+            "invalid full snapshot archive '{}': {err}",
+            full_snapshot_archive_path.display()
+        ))
+    })?;
+    let incremental_snapshot_archive_info = matches
+        .get_one::<String>(incremental_name)
+        .map(|path| {
+            IncrementalSnapshotArchiveInfo::new_from_path(PathBuf::from(path)).map_err(|err| {
+                LedgerToolError::BadArgument(format!(
+                    "invalid incremental snapshot archive '{path}': {err}"
+                ))
+            })
+        })
+        .transpose()?;
+    Ok((
+        full_snapshot_archive_info,
+        incremental_snapshot_archive_info,
+    ))
+}
+
+/// Rebuilds the bank of a snapshot in its own directory under the ledger tool directory, so
+/// that both banks of a diff can be loaded at once
+fn load_bank_for_diff(
+    ledger_path: &Path,
+    arg_matches: &ArgMatches,
+    genesis_config: &GenesisConfig,
+    name: &str,
+    (full_snapshot_archive_info, incremental_snapshot_archive_info): (
+        FullSnapshotArchiveInfo,
+        Option<IncrementalSnapshotArchiveInfo>,
+    ),
+) -> Result<Bank> {
+    let working_dir = ledger_path
+        .join(LEDGER_TOOL_DIRECTORY)
+        .join("snapshot_diff")
+        .join(name);
+    let bank_snapshots_dir = working_dir.join("snapshot");
+    if bank_snapshots_dir.exists() {
+        move_and_async_delete_path_contents(&bank_snapshots_dir);
+    }
+    fs::create_dir_all(&bank_snapshots_dir)?;
+    let (account_run_paths, _account_snapshot_paths) =
+        create_all_accounts_run_and_snapshot_dirs(&[working_dir.join("accounts")])?;
+    account_run_paths
+        .iter()
+        .for_each(move_and_async_delete_path_contents);
+
+    let mut accounts_db_config = get_accounts_db_config(ledger_path, arg_matches);
+    if let Some(index) = accounts_db_config.index.as_mut() {
+        index.drives = Some(vec![working_dir.join("accounts_index")]);
+    }
+    accounts_db_config.base_working_path = Some(working_dir);
+
+    info!(
+        "Loading {name} bank from {}",
+        full_snapshot_archive_info.path().display()
+    );
+    let (bank, _timings) = snapshot_bank_utils::bank_from_snapshot_archives(
+        &account_run_paths,
+        &bank_snapshots_dir,
+        &full_snapshot_archive_info,
+        incremental_snapshot_archive_info.as_ref(),
+        genesis_config,
+        &RuntimeConfig::default(),
+        None,
+        None,
+        None,
+        true,
+        false,
+        false,
+        Some(accounts_db_config),
+        None,
+        Arc::new(AtomicBool::new(false)),
+    )?;
+    Ok(bank)
+}
+
+/// Scans the accounts of both banks in pubkey order, and calls `f` with every account that
+/// differs between them
+fn diff_banks(old_bank: &Bank, new_bank: &Bank, f: impl FnMut(Pubkey, AccountDiff)) {
+    fn scan_accounts(bank: &Bank, sender: Sender<(Pubkey, AccountSharedData)>) {
+        bank.scan_all_accounts(
+            |item| {
+                if let Some((pubkey, account, _slot)) = item {
+                    // The receiver is only dropped early if the comparison panicked
+                    let _ = sender.send((*pubkey, account));
+                }
+            },
+            true,
+        )
+        .unwrap();
+    }
+
+    let (old_sender, old_receiver) = bounded(DIFF_SCAN_CHANNEL_SIZE);
+    let (new_sender, new_receiver) = bounded(DIFF_SCAN_CHANNEL_SIZE);
+    thread::scope(|scope| {
+        thread::Builder::new()
+            .name("solSnapDiffOld".to_string())
+            .spawn_scoped(scope, move || scan_accounts(old_bank, old_sender))
+            .unwrap();
+        thread::Builder::new()
+            .name("solSnapDiffNew".to_string())
+            .spawn_scoped(scope, move || scan_accounts(new_bank, new_sender))
+            .unwrap();
+        diff_accounts_sorted_by_pubkey(old_receiver, new_receiver, f);
+    });
+}
@@ -0,0 +1,88 @@
+//! A minimal HTTP/1.1 responder for the local endpoints that serve metrics and status pages
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Reads a single request from `stream` and answers it with the content type and body returned
+/// by `route` for the requested path.  Requests other than `GET`, and paths for which `route`
+/// returns `None`, are answered with a 404.  The connection is closed after the response.
+pub fn respond_to_get_request<'a>(
+    stream: TcpStream,
+    route: impl FnOnce(&str) -> Option<(&'a str, String)>,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain the headers, the request never has a body
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let response = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(path)) => {
+            route(path).map(|(content_type, body)| ("200 OK", content_type, body))
+        }
+        _ => None,
+    };
+    let (status, content_type, body) =
+        response.unwrap_or(("404 Not Found", "text/plain", "not found\n".to_string()));
+
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: \
+         close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::{io::Read, net::TcpListener, thread},
+    };
+
+    fn request(request: &str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            respond_to_get_request(stream, |path| {
+                (path == "/status").then(|| ("text/plain", "ok\n".to_string()))
+            })
+            .unwrap();
+        });
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        server.join().unwrap();
+        response
+    }
+
+    #[test]
+    fn test_respond_to_get_request() {
+        assert_eq!(
+            request("GET /status HTTP/1.1\r\nHost: localhost\r\n\r\n"),
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 3\r\nConnection: \
+             close\r\n\r\nok\n"
+        );
+        let not_found = "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain\r\nContent-Length: \
+                         10\r\nConnection: close\r\n\r\nnot found\n";
+        assert_eq!(request("GET /other HTTP/1.1\r\n\r\n"), not_found);
+        assert_eq!(request("POST /status HTTP/1.1\r\n\r\n"), not_found);
+    }
+}
@@ -1,7 +1,9 @@
 #![allow(clippy::arithmetic_side_effects)]
 pub mod counter;
 pub mod datapoint;
+pub mod http;
 pub mod metrics;
+pub mod prometheus;
 pub use crate::metrics::{flush, query, set_host_id, set_panic_hook, submit};
 use std::sync::{
     atomic::{AtomicU64, Ordering},
@@ -1,7 +1,8 @@
-//! The `metrics` module enables sending measurements to an `InfluxDB` instance
+//! The `metrics` module enables sending measurements to an `InfluxDB` instance, or serving
+//! them to Prometheus
 
 use {
-    crate::{counter::CounterPoint, datapoint::DataPoint},
+    crate::{counter::CounterPoint, datapoint::DataPoint, prometheus::PrometheusMetricsWriter},
     crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender},
     gethostname::gethostname,
     log::*,
@@ -66,6 +67,11 @@ pub trait MetricsWriter {
     // Write the points and empty the vector.  Called on the internal
     // MetricsAgent worker thread.
     fn write(&self, points: Vec<DataPoint>);
+
+    // Called with the counters accumulated since the last write, before
+    // they are combined into the points passed to `write`.  Writers that
+    // keep running totals, rather than forwarding every point, use this.
+    fn write_counters(&self, _counters: &[CounterPoint]) {}
 }
 
 struct InfluxDbMetricsWriter {
@@ -179,11 +185,20 @@ impl Default for MetricsAgent {
             })
             .unwrap_or(4000);
 
-        Self::new(
-            Arc::new(InfluxDbMetricsWriter::new()),
-            Duration::from_secs(10),
-            max_points_per_sec,
-        )
+        let writer: Arc<dyn MetricsWriter + Send + Sync> =
+            match env::var("SOLANA_METRICS_PROMETHEUS_BIND_ADDRESS") {
+                Ok(bind_address) => Arc::new(
+                    PrometheusMetricsWriter::new(
+                        bind_address
+                            .parse()
+                            .expect("Failed to parse SOLANA_METRICS_PROMETHEUS_BIND_ADDRESS"),
+                    )
+                    .expect("Failed to bind SOLANA_METRICS_PROMETHEUS_BIND_ADDRESS"),
+                ),
+                Err(_) => Arc::new(InfluxDbMetricsWriter::new()),
+            };
+
+        Self::new(writer, Duration::from_secs(10), max_points_per_sec)
     }
 }
 
@@ -271,6 +286,10 @@ impl MetricsAgent {
         let now = Instant::now();
         let secs_since_last_write = now.duration_since(last_write_time).as_secs();
 
+        if !counters.is_empty() {
+            let counters: Vec<_> = counters.values().cloned().collect();
+            writer.write_counters(&counters);
+        }
         writer.write(Self::combine_points(
             max_points,
             max_points_per_sec,
@@ -403,6 +422,10 @@ static HOST_ID: std::sync::LazyLock<RwLock<String>> = std::sync::LazyLock::new(|
     })
 });
 
+pub(crate) fn host_id() -> String {
+    HOST_ID.read().unwrap().clone()
+}
+
 pub fn set_host_id(host_id: String) {
     info!("host id: {}", host_id);
     *HOST_ID.write().unwrap() = host_id;
@@ -0,0 +1,322 @@
+//! The `prometheus` module serves the latest metrics values on a local HTTP endpoint, in the
+//! OpenMetrics text format, for Prometheus to scrape.
+//!
+//! Each numeric field of a datapoint becomes a gauge named `<datapoint>_<field>`, and the
+//! datapoint's tags become labels.  Counters additionally become counters named `<counter>`,
+//! which keep running totals.  Names are sanitized to the characters allowed by OpenMetrics.
+
+use {
+    crate::{
+        counter::CounterPoint, datapoint::DataPoint, http::respond_to_get_request,
+        metrics::MetricsWriter,
+    },
+    log::*,
+    std::{
+        collections::BTreeMap,
+        fmt::{self, Write as _},
+        io,
+        net::{SocketAddr, TcpListener},
+        sync::{Arc, Mutex, Weak},
+        thread,
+    },
+};
+
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SampleValue {
+    Int(i64),
+    Float(f64),
+}
+
+impl SampleValue {
+    /// Parses a field value as formatted by `DataPoint`, returns `None` for string fields
+    fn from_field(value: &str) -> Option<Self> {
+        if let Some(value) = value.strip_suffix('i') {
+            return value.parse().ok().map(Self::Int);
+        }
+        match value {
+            "true" => Some(Self::Int(1)),
+            "false" => Some(Self::Int(0)),
+            _ => value.parse().ok().map(Self::Float),
+        }
+    }
+}
+
+impl fmt::Display for SampleValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Float(value) if value.is_nan() => write!(f, "NaN"),
+            Self::Float(value) if value.is_infinite() => {
+                write!(f, "{}Inf", if *value > 0.0 { "+" } else { "-" })
+            }
+            Self::Float(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MetricType {
+    Gauge,
+    Counter,
+}
+
+#[derive(Debug)]
+struct MetricFamily {
+    metric_type: MetricType,
+    /// Latest value of each sample, by rendered label set
+    samples: BTreeMap<String, SampleValue>,
+}
+
+/// The latest value of every metric, by family name
+#[derive(Debug, Default)]
+struct MetricFamilies(BTreeMap<String, MetricFamily>);
+
+impl MetricFamilies {
+    fn family(&mut self, name: String, metric_type: MetricType) -> Option<&mut MetricFamily> {
+        let family = self.0.entry(name).or_insert_with(|| MetricFamily {
+            metric_type,
+            samples: BTreeMap::new(),
+        });
+        // A datapoint field may clash with a counter of the same name, the first one wins
+        (family.metric_type == metric_type).then_some(family)
+    }
+
+    fn update_points(&mut self, points: &[DataPoint], host_id: &str) {
+        for point in points {
+            let labels = render_labels(host_id, &point.tags);
+            for (field, value) in &point.fields {
+                let Some(value) = SampleValue::from_field(value) else {
+                    continue;
+                };
+                let name = sanitize_name(&format!("{}_{field}", point.name));
+                if let Some(family) = self.family(name, MetricType::Gauge) {
+                    family.samples.insert(labels.clone(), value);
+                }
+            }
+        }
+    }
+
+    fn update_counters(&mut self, counters: &[CounterPoint], host_id: &str) {
+        let labels = render_labels(host_id, &[]);
+        for counter in counters {
+            if let Some(family) = self.family(sanitize_name(counter.name), MetricType::Counter) {
+                let total = family
+                    .samples
+                    .entry(labels.clone())
+                    .or_insert(SampleValue::Int(0));
+                if let SampleValue::Int(total) = total {
+                    *total = total.saturating_add(counter.count);
+                }
+            }
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut text = String::new();
+        for (name, family) in &self.0 {
+            let (metric_type, suffix) = match family.metric_type {
+                MetricType::Gauge => ("gauge", ""),
+                MetricType::Counter => ("counter", "_total"),
+            };
+            let _ = writeln!(text, "# TYPE {name} {metric_type}");
+            for (labels, value) in &family.samples {
+                let _ = writeln!(text, "{name}{suffix}{labels} {value}");
+            }
+        }
+        text.push_str("# EOF\n");
+        text
+    }
+}
+
+/// Replaces the characters not allowed in OpenMetrics metric and label names
+fn sanitize_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if sanitized.is_empty() || sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn render_labels(host_id: &str, tags: &[(&'static str, String)]) -> String {
+    let mut labels = format!("{{host_id=\"{}\"", escape_label_value(host_id));
+    for (name, value) in tags {
+        let _ = write!(
+            labels,
+            ",{}=\"{}\"",
+            sanitize_name(name),
+            escape_label_value(value)
+        );
+    }
+    labels.push('}');
+    labels
+}
+
+/// A `MetricsWriter` keeping the latest value of every metric, and serving them on a
+/// `/metrics` HTTP endpoint
+pub struct PrometheusMetricsWriter {
+    families: Arc<Mutex<MetricFamilies>>,
+    local_addr: SocketAddr,
+}
+
+impl PrometheusMetricsWriter {
+    /// Binds the `/metrics` endpoint to `bind_address`, and serves it on a background thread
+    /// for as long as the writer is alive
+    pub fn new(bind_address: SocketAddr) -> io::Result<Self> {
+        let listener = TcpListener::bind(bind_address)?;
+        let local_addr = listener.local_addr()?;
+        let families = Arc::new(Mutex::new(MetricFamilies::default()));
+
+        let server_families = Arc::downgrade(&families);
+        thread::Builder::new()
+            .name("solMetricsHttp".into())
+            .spawn(move || Self::serve(listener, server_families))?;
+        info!("serving prometheus metrics on http://{local_addr}/metrics");
+
+        Ok(Self {
+            families,
+            local_addr,
+        })
+    }
+
+    /// The address the `/metrics` endpoint is served on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Renders the latest metrics in the OpenMetrics text format
+    pub fn render(&self) -> String {
+        self.families.lock().unwrap().render()
+    }
+
+    fn serve(listener: TcpListener, families: Weak<Mutex<MetricFamilies>>) {
+        for stream in listener.incoming() {
+            let Some(families) = families.upgrade() else {
+                break;
+            };
+            let result = stream.and_then(|stream| {
+                respond_to_get_request(stream, |path| {
+                    (path == "/metrics")
+                        .then(|| (OPENMETRICS_CONTENT_TYPE, families.lock().unwrap().render()))
+                })
+            });
+            if let Err(err) = result {
+                debug!("prometheus metrics request failed: {err}");
+            }
+        }
+    }
+}
+
+impl MetricsWriter for PrometheusMetricsWriter {
+    fn write(&self, points: Vec<DataPoint>) {
+        let host_id = crate::metrics::host_id();
+        self.families
+            .lock()
+            .unwrap()
+            .update_points(&points, &host_id);
+    }
+
+    fn write_counters(&self, counters: &[CounterPoint]) {
+        let host_id = crate::metrics::host_id();
+        self.families
+            .lock()
+            .unwrap()
+            .update_counters(counters, &host_id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        std::{
+            io::{Read, Write},
+            net::TcpStream,
+        },
+    };
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(
+            sanitize_name("bank-new_from.parent"),
+            "bank_new_from_parent"
+        );
+        assert_eq!(sanitize_name("1st"), "_1st");
+        assert_eq!(sanitize_name(""), "_");
+    }
+
+    #[test]
+    fn test_render() {
+        let mut families = MetricFamilies::default();
+        families.update_points(
+            &[
+                DataPoint::new("replay-slot")
+                    .add_tag("commitment", "fin\"al")
+                    .add_field_i64("slot", 42)
+                    .add_field_f64("ratio", 0.5)
+                    .add_field_bool("is_leader", true)
+                    .add_field_str("version", "2.3.0")
+                    .to_owned(),
+                DataPoint::new("replay-slot")
+                    .add_tag("commitment", "fin\"al")
+                    .add_field_i64("slot", 43)
+                    .to_owned(),
+            ],
+            "host",
+        );
+        let counter = CounterPoint {
+            name: "packets.received",
+            count: 7,
+            timestamp: std::time::UNIX_EPOCH,
+        };
+        families.update_counters(&[counter.clone(), counter], "host");
+
+        assert_eq!(
+            families.render(),
+            "# TYPE packets_received counter\n\
+             packets_received_total{host_id=\"host\"} 14\n\
+             # TYPE replay_slot_is_leader gauge\n\
+             replay_slot_is_leader{host_id=\"host\",commitment=\"fin\\\"al\"} 1\n\
+             # TYPE replay_slot_ratio gauge\n\
+             replay_slot_ratio{host_id=\"host\",commitment=\"fin\\\"al\"} 0.5\n\
+             # TYPE replay_slot_slot gauge\n\
+             replay_slot_slot{host_id=\"host\",commitment=\"fin\\\"al\"} 43\n\
+             # EOF\n",
+        );
+    }
+
+    #[test]
+    fn test_serve_metrics() {
+        let writer = PrometheusMetricsWriter::new("127.0.0.1:0".parse().unwrap()).unwrap();
+        writer.write(vec![DataPoint::new("point")
+            .add_field_i64("value", 1)
+            .to_owned()]);
+
+        let request = |path: &str| {
+            let mut stream = TcpStream::connect(writer.local_addr()).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let response = request("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(OPENMETRICS_CONTENT_TYPE));
+        assert!(response.ends_with(&writer.render()));
+        assert!(response.contains("point_value{host_id="));
+
+        assert!(request("/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
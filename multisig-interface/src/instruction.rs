@@ -0,0 +1,233 @@
+//! Instructions of the multisig program
+
+use {
+    crate::{get_proposal_address, get_vault_address, id, state::ProposedInstruction},
+    serde_derive::{Deserialize, Serialize},
+    solana_instruction::{AccountMeta, Instruction},
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::system_program,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MultisigInstruction {
+    /// Creates a multisig account.
+    ///
+    /// # Account references
+    ///   0. `[writable, signer]` The new multisig account.
+    ///   1. `[writable, signer]` The payer for the multisig account.
+    ///   2. `[]` The system program.
+    CreateMultisig {
+        /// Number of approvals required to execute a proposal
+        threshold: u8,
+        /// Members which can propose and approve
+        signers: Vec<Pubkey>,
+    },
+
+    /// Creates a proposal, which counts as approved by the proposer.
+    ///
+    /// # Account references
+    ///   0. `[writable]` The multisig account.
+    ///   1. `[writable]` The new proposal account, see `get_proposal_address`.
+    ///   2. `[signer]` The proposing member.
+    ///   3. `[writable, signer]` The payer for the proposal account.
+    ///   4. `[]` The system program.
+    Propose {
+        /// Instructions to execute with the vault as signer
+        instructions: Vec<ProposedInstruction>,
+    },
+
+    /// Approves a proposal.
+    ///
+    /// # Account references
+    ///   0. `[]` The multisig account.
+    ///   1. `[writable]` The proposal account.
+    ///   2. `[signer]` The approving member.
+    Approve,
+
+    /// Executes a proposal which has reached the threshold of approvals.
+    /// Anyone can execute an approved proposal.
+    ///
+    /// # Account references
+    ///   0. `[]` The multisig account.
+    ///   1. `[writable]` The proposal account.
+    ///   2. .. All accounts and programs referenced by the proposed instructions.
+    Execute,
+
+    /// Replaces the members and threshold of a multisig. All proposals which
+    /// have not been executed yet become stale.
+    ///
+    /// This instruction can only be invoked by executing a proposal.
+    ///
+    /// # Account references
+    ///   0. `[writable]` The multisig account.
+    ///   1. `[signer]` The vault of the multisig, see `get_vault_address`.
+    SetConfig {
+        /// Number of approvals required to execute a proposal
+        threshold: u8,
+        /// Members which can propose and approve
+        signers: Vec<Pubkey>,
+    },
+}
+
+pub fn create_multisig(
+    multisig_address: &Pubkey,
+    payer: &Pubkey,
+    threshold: u8,
+    signers: Vec<Pubkey>,
+) -> Instruction {
+    Instruction::new_with_bincode(
+        id(),
+        &MultisigInstruction::CreateMultisig { threshold, signers },
+        vec![
+            AccountMeta::new(*multisig_address, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn propose(
+    multisig_address: &Pubkey,
+    proposal_index: u64,
+    proposer: &Pubkey,
+    payer: &Pubkey,
+    instructions: Vec<ProposedInstruction>,
+) -> Instruction {
+    Instruction::new_with_bincode(
+        id(),
+        &MultisigInstruction::Propose { instructions },
+        vec![
+            AccountMeta::new(*multisig_address, false),
+            AccountMeta::new(
+                get_proposal_address(multisig_address, proposal_index),
+                false,
+            ),
+            AccountMeta::new_readonly(*proposer, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn approve(multisig_address: &Pubkey, proposal_index: u64, signer: &Pubkey) -> Instruction {
+    Instruction::new_with_bincode(
+        id(),
+        &MultisigInstruction::Approve,
+        vec![
+            AccountMeta::new_readonly(*multisig_address, false),
+            AccountMeta::new(
+                get_proposal_address(multisig_address, proposal_index),
+                false,
+            ),
+            AccountMeta::new_readonly(*signer, true),
+        ],
+    )
+}
+
+/// Builds the instruction to execute a proposal with the given `instructions`.
+///
+/// The vault does not sign the transaction, it is signed for by the program.
+pub fn execute(
+    multisig_address: &Pubkey,
+    proposal_index: u64,
+    instructions: &[ProposedInstruction],
+) -> Instruction {
+    let vault_address = get_vault_address(multisig_address);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*multisig_address, false),
+        AccountMeta::new(
+            get_proposal_address(multisig_address, proposal_index),
+            false,
+        ),
+    ];
+    let mut add_account = |pubkey: Pubkey, is_signer: bool, is_writable: bool| {
+        let is_signer = is_signer && pubkey != vault_address;
+        if let Some(account_meta) = accounts
+            .iter_mut()
+            .find(|account_meta| account_meta.pubkey == pubkey)
+        {
+            account_meta.is_signer |= is_signer;
+            account_meta.is_writable |= is_writable;
+        } else {
+            accounts.push(AccountMeta {
+                pubkey,
+                is_signer,
+                is_writable,
+            });
+        }
+    };
+    for instruction in instructions {
+        add_account(instruction.program_id, false, false);
+        for account_meta in &instruction.accounts {
+            add_account(
+                account_meta.pubkey,
+                account_meta.is_signer,
+                account_meta.is_writable,
+            );
+        }
+    }
+    Instruction::new_with_bincode(id(), &MultisigInstruction::Execute, accounts)
+}
+
+/// Builds the instruction to change the configuration of a multisig, to be
+/// used as part of a proposal.
+pub fn set_config(multisig_address: &Pubkey, threshold: u8, signers: Vec<Pubkey>) -> Instruction {
+    Instruction::new_with_bincode(
+        id(),
+        &MultisigInstruction::SetConfig { threshold, signers },
+        vec![
+            AccountMeta::new(*multisig_address, false),
+            AccountMeta::new_readonly(get_vault_address(multisig_address), true),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::state::ProposedAccountMeta};
+
+    #[test]
+    fn test_execute_accounts() {
+        let multisig_address = Pubkey::new_unique();
+        let vault_address = get_vault_address(&multisig_address);
+        let recipient = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let instructions = vec![
+            ProposedInstruction {
+                program_id,
+                accounts: vec![
+                    ProposedAccountMeta {
+                        pubkey: vault_address,
+                        is_signer: true,
+                        is_writable: true,
+                    },
+                    ProposedAccountMeta {
+                        pubkey: recipient,
+                        is_signer: false,
+                        is_writable: false,
+                    },
+                ],
+                data: vec![],
+            },
+            ProposedInstruction {
+                program_id,
+                accounts: vec![ProposedAccountMeta {
+                    pubkey: recipient,
+                    is_signer: false,
+                    is_writable: true,
+                }],
+                data: vec![],
+            },
+        ];
+        assert_eq!(
+            execute(&multisig_address, 7, &instructions).accounts,
+            vec![
+                AccountMeta::new_readonly(multisig_address, false),
+                AccountMeta::new(get_proposal_address(&multisig_address, 7), false),
+                AccountMeta::new_readonly(program_id, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(recipient, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,34 @@
+//! Interface of the multisig program.
+//!
+//! A multisig account holds a set of up to `MAX_SIGNERS` members and a
+//! threshold. Members propose a list of instructions, which can be executed by
+//! anyone once the threshold of members approved it. The instructions are
+//! executed with the vault of the multisig as signer, so the vault can hold
+//! lamports and act as authority of nonce accounts or other programs.
+
+pub mod instruction;
+pub mod state;
+
+use solana_pubkey::Pubkey;
+
+solana_pubkey::declare_id!("MuLtisig11111111111111111111111111111111111");
+
+/// Maximum number of members of a multisig
+pub const MAX_SIGNERS: usize = 11;
+
+/// Returns the address of the vault of `multisig_address`.
+///
+/// The vault is a system account which signs the executed proposals.
+pub fn get_vault_address(multisig_address: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[multisig_address.as_ref(), b"vault"], &id()).0
+}
+
+/// Returns the address of the proposal with index `proposal_index` of
+/// `multisig_address`.
+pub fn get_proposal_address(multisig_address: &Pubkey, proposal_index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[multisig_address.as_ref(), &proposal_index.to_le_bytes()],
+        &id(),
+    )
+    .0
+}
@@ -0,0 +1,191 @@
+//! State of multisig and proposal accounts
+
+use {
+    serde_derive::{Deserialize, Serialize},
+    solana_instruction::{error::InstructionError, AccountMeta, Instruction},
+    solana_pubkey::Pubkey,
+};
+
+/// State of a multisig account
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Multisig {
+    /// Number of approvals required to execute a proposal
+    pub threshold: u8,
+    /// Members which can propose and approve
+    pub signers: Vec<Pubkey>,
+    /// Index of the next proposal
+    pub proposal_count: u64,
+    /// Proposals with a lower index were made under a previous configuration
+    /// and can neither be approved nor executed anymore
+    pub stale_proposal_index: u64,
+}
+
+impl Multisig {
+    /// Size of the serialized state with `MAX_SIGNERS` members
+    pub const fn size_of() -> usize {
+        377 // see test_multisig_size_of
+    }
+
+    pub fn deserialize(data: &[u8]) -> Result<Self, InstructionError> {
+        bincode::deserialize(data).map_err(|_| InstructionError::InvalidAccountData)
+    }
+
+    pub fn serialize(&self, data: &mut [u8]) -> Result<(), InstructionError> {
+        bincode::serialize_into(data, self).map_err(|_| InstructionError::AccountDataTooSmall)
+    }
+
+    /// Returns the position of `address` among the members
+    pub fn signer_index(&self, address: &Pubkey) -> Option<usize> {
+        self.signers.iter().position(|signer| signer == address)
+    }
+
+    /// Returns true if a proposal with `proposal_index` can still be approved
+    /// and executed
+    pub fn is_current(&self, proposal_index: u64) -> bool {
+        proposal_index >= self.stale_proposal_index
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProposalStatus {
+    Active,
+    Executed,
+}
+
+/// State of a proposal account
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Proposal {
+    /// The multisig this proposal belongs to
+    pub multisig: Pubkey,
+    /// Index of this proposal in the multisig
+    pub index: u64,
+    /// Member which made the proposal
+    pub proposer: Pubkey,
+    /// Bitmap of the approving members, indexed by their position in
+    /// `Multisig::signers`
+    pub approvals: u16,
+    pub status: ProposalStatus,
+    /// Instructions which are executed with the vault as signer
+    pub instructions: Vec<ProposedInstruction>,
+}
+
+impl Proposal {
+    pub fn size_of(&self) -> usize {
+        bincode::serialized_size(self).unwrap_or(u64::MAX) as usize
+    }
+
+    pub fn deserialize(data: &[u8]) -> Result<Self, InstructionError> {
+        bincode::deserialize(data).map_err(|_| InstructionError::InvalidAccountData)
+    }
+
+    pub fn serialize(&self, data: &mut [u8]) -> Result<(), InstructionError> {
+        bincode::serialize_into(data, self).map_err(|_| InstructionError::AccountDataTooSmall)
+    }
+
+    pub fn is_approved_by(&self, signer_index: usize) -> bool {
+        self.approvals & (1 << signer_index) != 0
+    }
+
+    pub fn approve(&mut self, signer_index: usize) {
+        self.approvals |= 1 << signer_index;
+    }
+
+    pub fn approval_count(&self) -> u32 {
+        self.approvals.count_ones()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposedAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// An instruction stored in a proposal
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposedInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<ProposedAccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl From<Instruction> for ProposedInstruction {
+    fn from(instruction: Instruction) -> Self {
+        Self {
+            program_id: instruction.program_id,
+            accounts: instruction
+                .accounts
+                .into_iter()
+                .map(|account_meta| ProposedAccountMeta {
+                    pubkey: account_meta.pubkey,
+                    is_signer: account_meta.is_signer,
+                    is_writable: account_meta.is_writable,
+                })
+                .collect(),
+            data: instruction.data,
+        }
+    }
+}
+
+impl From<ProposedInstruction> for Instruction {
+    fn from(proposed_instruction: ProposedInstruction) -> Self {
+        Self {
+            program_id: proposed_instruction.program_id,
+            accounts: proposed_instruction
+                .accounts
+                .into_iter()
+                .map(|account_meta| AccountMeta {
+                    pubkey: account_meta.pubkey,
+                    is_signer: account_meta.is_signer,
+                    is_writable: account_meta.is_writable,
+                })
+                .collect(),
+            data: proposed_instruction.data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::MAX_SIGNERS};
+
+    #[test]
+    fn test_multisig_size_of() {
+        let multisig = Multisig {
+            threshold: u8::MAX,
+            signers: vec![Pubkey::new_unique(); MAX_SIGNERS],
+            proposal_count: u64::MAX,
+            stale_proposal_index: u64::MAX,
+        };
+        let size = bincode::serialized_size(&multisig).unwrap();
+        assert_eq!(size as usize, Multisig::size_of());
+
+        let mut data = vec![0; Multisig::size_of()];
+        multisig.serialize(&mut data).unwrap();
+        assert_eq!(Multisig::deserialize(&data).unwrap(), multisig);
+    }
+
+    #[test]
+    fn test_proposal_approvals() {
+        let mut proposal = Proposal {
+            multisig: Pubkey::new_unique(),
+            index: 0,
+            proposer: Pubkey::new_unique(),
+            approvals: 0,
+            status: ProposalStatus::Active,
+            instructions: vec![],
+        };
+        proposal.approve(0);
+        proposal.approve(MAX_SIGNERS - 1);
+        proposal.approve(0);
+        assert!(proposal.is_approved_by(0));
+        assert!(!proposal.is_approved_by(1));
+        assert!(proposal.is_approved_by(MAX_SIGNERS - 1));
+        assert_eq!(proposal.approval_count(), 2);
+
+        let mut data = vec![0; proposal.size_of()];
+        proposal.serialize(&mut data).unwrap();
+        assert_eq!(Proposal::deserialize(&data).unwrap(), proposal);
+    }
+}
@@ -24,14 +24,17 @@ use {
         vm::{Config, ContextObject, EbpfVm},
     },
     solana_sdk_ids::{
-        bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, loader_v4, native_loader, sysvar,
+        bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, loader_v4, native_loader,
+        system_program, sysvar,
     },
     solana_stable_layout::stable_instruction::StableInstruction,
     solana_svm_callback::InvokeContextCallback,
     solana_svm_feature_set::SVMFeatureSet,
+    solana_system_interface::instruction as system_instruction,
     solana_timings::{ExecuteDetailsTimings, ExecuteTimings},
     solana_transaction_context::{
-        IndexOfAccount, InstructionAccount, TransactionAccount, TransactionContext,
+        IndexOfAccount, InstructionAccount, InstructionInvocation, TransactionAccount,
+        TransactionContext,
     },
     solana_type_overrides::sync::{atomic::Ordering, Arc},
     std::{
@@ -181,6 +184,59 @@ pub struct SerializedAccountMetadata {
     pub vm_owner_addr: u64,
 }
 
+/// Records an [InstructionInvocation] for every stack frame pushed
+#[derive(Debug, Default)]
+struct InvocationTracer {
+    invocations: Vec<InstructionInvocation>,
+    /// For each frame on the stack: the index of its invocation, and the
+    /// remaining compute units and accounts resize delta when it was pushed
+    frames: Vec<(usize, u64, i64)>,
+}
+
+impl InvocationTracer {
+    fn push(
+        &mut self,
+        program_id: Pubkey,
+        stack_height: usize,
+        remaining_units: u64,
+        accounts_resize_delta: i64,
+    ) {
+        self.frames.push((
+            self.invocations.len(),
+            remaining_units,
+            accounts_resize_delta,
+        ));
+        self.invocations.push(InstructionInvocation {
+            program_id,
+            stack_height,
+            ..InstructionInvocation::default()
+        });
+    }
+
+    fn pop(&mut self, remaining_units: u64, accounts_resize_delta: i64) {
+        let Some((index, pre_remaining_units, pre_accounts_resize_delta)) = self.frames.pop()
+        else {
+            return;
+        };
+        if let Some(invocation) = self.invocations.get_mut(index) {
+            invocation.compute_units_consumed = pre_remaining_units.saturating_sub(remaining_units);
+            invocation.accounts_data_len_delta =
+                accounts_resize_delta.saturating_sub(pre_accounts_resize_delta);
+        }
+    }
+
+    fn record_syscall_units(&mut self, amount: u64) {
+        if let Some(invocation) = self
+            .frames
+            .last()
+            .and_then(|(index, ..)| self.invocations.get_mut(*index))
+        {
+            invocation.syscall_units_consumed =
+                invocation.syscall_units_consumed.saturating_add(amount);
+        }
+    }
+}
+
 /// Main pipeline from runtime to program execution.
 pub struct InvokeContext<'a> {
     /// Information about the currently executing transaction.
@@ -202,6 +258,7 @@ pub struct InvokeContext<'a> {
     pub timings: ExecuteDetailsTimings,
     pub syscall_context: Vec<Option<SyscallContext>>,
     traces: Vec<Vec<[u64; 12]>>,
+    invocation_tracer: Option<RefCell<InvocationTracer>>,
 }
 
 impl<'a> InvokeContext<'a> {
@@ -226,6 +283,7 @@ impl<'a> InvokeContext<'a> {
             timings: ExecuteDetailsTimings::default(),
             syscall_context: Vec::new(),
             traces: Vec::new(),
+            invocation_tracer: None,
         }
     }
 
@@ -283,7 +341,29 @@ impl<'a> InvokeContext<'a> {
         }
 
         self.syscall_context.push(None);
-        self.transaction_context.push()
+        self.transaction_context.push()?;
+
+        // The frame is pushed at this point, so tracing must not fail
+        if let Some(invocation_tracer) = &self.invocation_tracer {
+            let program_id = self
+                .transaction_context
+                .get_current_instruction_context()
+                .and_then(|instruction_context| {
+                    instruction_context
+                        .get_last_program_key(self.transaction_context)
+                        .copied()
+                })
+                .unwrap_or_default();
+            invocation_tracer.borrow_mut().push(
+                program_id,
+                self.get_stack_height(),
+                self.get_remaining(),
+                self.transaction_context
+                    .accounts_resize_delta()
+                    .unwrap_or_default(),
+            );
+        }
+        Ok(())
     }
 
     /// Pop a stack frame from the invocation stack
@@ -291,6 +371,14 @@ impl<'a> InvokeContext<'a> {
         if let Some(Some(syscall_context)) = self.syscall_context.pop() {
             self.traces.push(syscall_context.trace_log);
         }
+        if let Some(invocation_tracer) = &self.invocation_tracer {
+            invocation_tracer.borrow_mut().pop(
+                self.get_remaining(),
+                self.transaction_context
+                    .accounts_resize_delta()
+                    .unwrap_or_default(),
+            );
+        }
         self.transaction_context.pop()
     }
 
@@ -320,6 +408,58 @@ impl<'a> InvokeContext<'a> {
         Ok(())
     }
 
+    /// Creates the program derived account `address` with `space` bytes of data owned by
+    /// `owner`, signing for `address` in the system program invocations.
+    ///
+    /// The account must be a system account without data, so that an account in use can't be
+    /// taken over. Only its rent shortfall is transferred from `payer`, so that lamports sent to
+    /// the address beforehand can't block the creation. Without a `payer` the caller must have
+    /// funded the account already.
+    pub fn create_program_derived_account(
+        &mut self,
+        address: &Pubkey,
+        payer: Option<&Pubkey>,
+        space: usize,
+        owner: &Pubkey,
+    ) -> Result<(), InstructionError> {
+        let transaction_context = &self.transaction_context;
+        let instruction_context = transaction_context.get_current_instruction_context()?;
+        let index_in_instruction = instruction_context
+            .find_index_of_instruction_account(transaction_context, address)
+            .ok_or(InstructionError::MissingAccount)?;
+        let account = instruction_context
+            .try_borrow_instruction_account(transaction_context, index_in_instruction)?;
+        if !system_program::check_id(account.get_owner()) || !account.get_data().is_empty() {
+            ic_msg!(self, "Account {} is already in use", address);
+            return Err(InstructionError::AccountAlreadyInitialized);
+        }
+        let shortfall = self
+            .get_sysvar_cache()
+            .get_rent()?
+            .minimum_balance(space)
+            .saturating_sub(account.get_lamports());
+        drop(account);
+
+        if shortfall > 0 {
+            let Some(payer) = payer else {
+                ic_msg!(self, "Account {} is not rent exempt", address);
+                return Err(InstructionError::InsufficientFunds);
+            };
+            self.native_invoke(
+                system_instruction::transfer(payer, address, shortfall).into(),
+                &[],
+            )?;
+        }
+        self.native_invoke(
+            system_instruction::allocate(address, space as u64).into(),
+            &[*address],
+        )?;
+        self.native_invoke(
+            system_instruction::assign(address, owner).into(),
+            &[*address],
+        )
+    }
+
     /// Helper to prepare for process_instruction()
     #[allow(clippy::type_complexity)]
     pub fn prepare_instruction(
@@ -721,6 +861,25 @@ impl<'a> InvokeContext<'a> {
     pub fn get_traces(&self) -> &Vec<Vec<[u64; 12]>> {
         &self.traces
     }
+
+    /// Record an [InstructionInvocation] for every instruction processed from now on
+    pub fn enable_invocation_tracing(&mut self) {
+        self.invocation_tracer = Some(RefCell::default());
+    }
+
+    /// Attribute compute units charged by a syscall to the current invocation
+    pub fn record_syscall_units(&self, amount: u64) {
+        if let Some(invocation_tracer) = &self.invocation_tracer {
+            invocation_tracer.borrow_mut().record_syscall_units(amount);
+        }
+    }
+
+    /// Take the recorded invocations, in the order they were started
+    pub fn take_invocation_trace(&mut self) -> Option<Vec<InstructionInvocation>> {
+        self.invocation_tracer
+            .take()
+            .map(|invocation_tracer| invocation_tracer.into_inner().invocations)
+    }
 }
 
 #[macro_export]
@@ -1277,6 +1436,102 @@ mod tests {
         invoke_context.pop().unwrap();
     }
 
+    #[test]
+    fn test_invocation_trace() {
+        let callee_program_id = solana_pubkey::new_rand();
+        let owned_account = AccountSharedData::new(42, 1, &callee_program_id);
+        let not_owned_account = AccountSharedData::new(84, 1, &solana_pubkey::new_rand());
+        let readonly_account = AccountSharedData::new(168, 1, &solana_pubkey::new_rand());
+        let loader_account = AccountSharedData::new(0, 1, &native_loader::id());
+        let mut program_account = AccountSharedData::new(1, 1, &native_loader::id());
+        program_account.set_executable(true);
+        let transaction_accounts = vec![
+            (solana_pubkey::new_rand(), owned_account),
+            (solana_pubkey::new_rand(), not_owned_account),
+            (solana_pubkey::new_rand(), readonly_account),
+            (callee_program_id, program_account),
+            (solana_pubkey::new_rand(), loader_account),
+        ];
+        let caller_program_id = transaction_accounts.get(4).unwrap().0;
+        let metas = vec![
+            AccountMeta::new(transaction_accounts.first().unwrap().0, false),
+            AccountMeta::new(transaction_accounts.get(1).unwrap().0, false),
+            AccountMeta::new_readonly(transaction_accounts.get(2).unwrap().0, false),
+        ];
+        let instruction_accounts = (0..4)
+            .map(|instruction_account_index| {
+                InstructionAccount::new(
+                    instruction_account_index,
+                    instruction_account_index,
+                    instruction_account_index,
+                    false,
+                    instruction_account_index < 2,
+                )
+            })
+            .collect::<Vec<_>>();
+        with_mock_invoke_context!(invoke_context, transaction_context, transaction_accounts);
+        let mut program_cache_for_tx_batch = ProgramCacheForTxBatch::default();
+        program_cache_for_tx_batch.replenish(
+            callee_program_id,
+            Arc::new(ProgramCacheEntry::new_builtin(0, 1, MockBuiltin::vm)),
+        );
+        invoke_context.program_cache_for_tx_batch = &mut program_cache_for_tx_batch;
+        invoke_context.enable_invocation_tracing();
+
+        let compute_units_to_consume = 10;
+        let syscall_units = 3;
+        invoke_context
+            .transaction_context
+            .get_next_instruction_context()
+            .unwrap()
+            .configure(&[4], &instruction_accounts, &[]);
+        invoke_context.push().unwrap();
+        invoke_context.record_syscall_units(syscall_units);
+        let inner_instruction = StableInstruction::from(Instruction::new_with_bincode(
+            callee_program_id,
+            &MockInstruction::ConsumeComputeUnits {
+                compute_units_to_consume,
+                desired_result: Ok(()),
+            },
+            metas,
+        ));
+        let (inner_instruction_accounts, program_indices) = invoke_context
+            .prepare_instruction(&inner_instruction, &[])
+            .unwrap();
+        invoke_context
+            .process_instruction(
+                &inner_instruction.data,
+                &inner_instruction_accounts,
+                &program_indices,
+                &mut 0,
+                &mut ExecuteTimings::default(),
+            )
+            .unwrap();
+        invoke_context.pop().unwrap();
+
+        let callee_units = compute_units_to_consume.saturating_add(MOCK_BUILTIN_COMPUTE_UNIT_COST);
+        assert_eq!(
+            invoke_context.take_invocation_trace(),
+            Some(vec![
+                InstructionInvocation {
+                    program_id: caller_program_id,
+                    stack_height: 1,
+                    compute_units_consumed: callee_units,
+                    syscall_units_consumed: syscall_units,
+                    accounts_data_len_delta: 0,
+                },
+                InstructionInvocation {
+                    program_id: callee_program_id,
+                    stack_height: 2,
+                    compute_units_consumed: callee_units,
+                    syscall_units_consumed: 0,
+                    accounts_data_len_delta: 0,
+                },
+            ])
+        );
+        assert_eq!(invoke_context.take_invocation_trace(), None);
+    }
+
     #[test]
     fn test_invoke_context_compute_budget() {
         let transaction_accounts = vec![(solana_pubkey::new_rand(), AccountSharedData::default())];
@@ -85,6 +85,9 @@ pub enum ProgramTestError {
     /// The chosen warp slot is not in the future, so warp is not performed
     #[error("Warp slot not in the future")]
     InvalidWarpSlot,
+    /// The checkpoint is unknown or its bank has been pruned by a later root
+    #[error("Checkpoint is unknown or no longer restorable")]
+    InvalidCheckpoint,
 }
 
 thread_local! {
@@ -1024,6 +1027,10 @@ impl<T> Drop for DroppableTask<T> {
     }
 }
 
+/// Handle to bank state captured by [`ProgramTestContext::checkpoint`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CheckpointId(usize);
+
 pub struct ProgramTestContext {
     pub banks_client: BanksClient,
     pub last_blockhash: Hash,
@@ -1031,6 +1038,7 @@ pub struct ProgramTestContext {
     genesis_config: GenesisConfig,
     bank_forks: Arc<RwLock<BankForks>>,
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
+    checkpoints: Vec<Arc<Bank>>,
     _bank_task: DroppableTask<()>,
 }
 
@@ -1077,6 +1085,7 @@ impl ProgramTestContext {
             genesis_config: genesis_config_info.genesis_config,
             bank_forks,
             block_commitment_cache,
+            checkpoints: Vec::new(),
             _bank_task: bank_task,
         }
     }
@@ -1239,6 +1248,80 @@ impl ProgramTestContext {
         Ok(())
     }
 
+    /// Capture the current bank state so that it can later be restored
+    ///
+    /// The working bank is frozen and kept as the checkpoint, and a new
+    /// working bank is created as its child. Restoring forks another child
+    /// from the frozen bank, so any number of test cases can branch from the
+    /// same fixture without repeating its setup.
+    pub fn checkpoint(&mut self) -> CheckpointId {
+        let mut bank_forks = self.bank_forks.write().unwrap();
+        let bank = bank_forks.working_bank();
+
+        // Fill ticks until a new blockhash is recorded, otherwise retried transactions will have
+        // the same signature
+        bank.fill_bank_with_ticks_for_tests();
+        bank.freeze();
+
+        let child_slot = bank.slot() + 1;
+        bank_forks.insert(Bank::new_from_parent(
+            bank.clone(),
+            &Pubkey::default(),
+            child_slot,
+        ));
+        self.checkpoints.push(bank);
+
+        // Update block commitment cache, otherwise banks server will poll at
+        // the wrong slot
+        self.block_commitment_cache
+            .write()
+            .unwrap()
+            .set_all_slots(child_slot, child_slot);
+
+        self.last_blockhash = bank_forks.working_bank().last_blockhash();
+        CheckpointId(self.checkpoints.len() - 1)
+    }
+
+    /// Discard the state of the working bank and continue from a checkpoint
+    ///
+    /// A checkpoint can be restored any number of times. It can no longer be
+    /// restored once a warp has rooted a slot past it, since its bank has then
+    /// been pruned from the bank forks.
+    pub fn restore(&mut self, checkpoint: CheckpointId) -> Result<(), ProgramTestError> {
+        let parent = self
+            .checkpoints
+            .get(checkpoint.0)
+            .ok_or(ProgramTestError::InvalidCheckpoint)?
+            .clone();
+
+        let mut bank_forks = self.bank_forks.write().unwrap();
+        let is_restorable = bank_forks
+            .get(parent.slot())
+            .is_some_and(|bank| bank.bank_id() == parent.bank_id());
+        if !is_restorable {
+            return Err(ProgramTestError::InvalidCheckpoint);
+        }
+
+        // The restored bank must be the highest slot so that it becomes the
+        // working bank, leaving the abandoned fork behind it
+        let restore_slot = bank_forks.highest_slot() + 1;
+        bank_forks.insert(Bank::new_from_parent(
+            parent,
+            &Pubkey::default(),
+            restore_slot,
+        ));
+
+        // Update block commitment cache, otherwise banks server will poll at
+        // the wrong slot
+        self.block_commitment_cache
+            .write()
+            .unwrap()
+            .set_all_slots(restore_slot, restore_slot);
+
+        self.last_blockhash = bank_forks.working_bank().last_blockhash();
+        Ok(())
+    }
+
     /// Get a new latest blockhash, similar in spirit to RpcClient::get_latest_blockhash()
     pub async fn get_new_latest_blockhash(&mut self) -> io::Result<Hash> {
         let blockhash = self
@@ -0,0 +1,129 @@
+use {
+    solana_keypair::Keypair,
+    solana_program_test::{ProgramTest, ProgramTestError},
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    solana_system_interface::instruction as system_instruction,
+    solana_transaction::Transaction,
+};
+
+#[tokio::test]
+async fn restore_discards_changes_since_checkpoint() {
+    let mut context = ProgramTest::default().start_with_context().await;
+    let recipient = Pubkey::new_unique();
+    let lamports = 1_000_000_000;
+
+    let checkpoint = context.checkpoint();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &context.payer.pubkey(),
+            &recipient,
+            lamports,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction.clone())
+        .await
+        .unwrap();
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        lamports
+    );
+
+    context.restore(checkpoint).unwrap();
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        0
+    );
+
+    // The restored fork has never seen the transaction, so it can be replayed
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        lamports
+    );
+}
+
+#[tokio::test]
+async fn restore_same_checkpoint_repeatedly() {
+    let mut context = ProgramTest::default().start_with_context().await;
+    let fixture = Keypair::new();
+    let lamports = 1_000_000;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &context.payer.pubkey(),
+            &fixture.pubkey(),
+            lamports,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+    let checkpoint = context.checkpoint();
+
+    for _ in 0..3 {
+        context.restore(checkpoint).unwrap();
+        assert_eq!(
+            context
+                .banks_client
+                .get_balance(fixture.pubkey())
+                .await
+                .unwrap(),
+            lamports
+        );
+
+        let transaction = Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(
+                &context.payer.pubkey(),
+                &fixture.pubkey(),
+                lamports,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+        assert_eq!(
+            context
+                .banks_client
+                .get_balance(fixture.pubkey())
+                .await
+                .unwrap(),
+            2 * lamports
+        );
+    }
+}
+
+#[tokio::test]
+async fn restore_after_warp_past_checkpoint_fails() {
+    let mut context = ProgramTest::default().start_with_context().await;
+
+    let checkpoint = context.checkpoint();
+    context.warp_to_slot(100).unwrap();
+    assert_eq!(
+        context.restore(checkpoint),
+        Err(ProgramTestError::InvalidCheckpoint)
+    );
+
+    // Checkpoints taken after the warp are unaffected
+    let checkpoint = context.checkpoint();
+    context.restore(checkpoint).unwrap();
+}
@@ -2,10 +2,12 @@ use {
     solana_instruction::{AccountMeta, Instruction},
     solana_keypair::Keypair,
     solana_program_test::ProgramTest,
+    solana_pubkey::Pubkey,
     solana_signer::Signer,
     solana_system_interface::instruction as system_instruction,
     solana_sysvar::rent,
     solana_transaction::Transaction,
+    solana_transaction_context::InstructionInvocation,
 };
 
 #[should_panic]
@@ -57,3 +59,41 @@ async fn max_compute_units() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn invocation_trace_in_metadata() {
+    let context = ProgramTest::default().start_with_context().await;
+    let account = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let space = 82;
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &account.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &Pubkey::new_unique(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &account],
+        context.last_blockhash,
+    );
+
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    assert!(result.result.is_ok());
+    let metadata = result.metadata.unwrap();
+    assert_eq!(
+        metadata.invocation_trace,
+        vec![InstructionInvocation {
+            program_id: solana_system_interface::program::id(),
+            stack_height: 1,
+            compute_units_consumed: metadata.compute_units_consumed,
+            syscall_units_consumed: 0,
+            accounts_data_len_delta: space as i64,
+        }]
+    );
+}
@@ -281,6 +281,7 @@ impl<T> VmSlice<T> {
 
 fn consume_compute_meter(invoke_context: &InvokeContext, amount: u64) -> Result<(), Error> {
     invoke_context.consume_checked(amount)?;
+    invoke_context.record_syscall_units(amount);
     Ok(())
 }
 
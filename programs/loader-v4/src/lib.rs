@@ -3,6 +3,7 @@ use qualifier_attr::qualifiers;
 use {
     solana_bincode::limited_deserialize,
     solana_bpf_loader_program::{deploy_program, execute},
+    solana_hash::{Hash, HASH_BYTES},
     solana_instruction::error::InstructionError,
     solana_loader_v3_interface::state::UpgradeableLoaderState,
     solana_loader_v4_interface::{
@@ -19,6 +20,7 @@ use {
     solana_pubkey::Pubkey,
     solana_sbpf::{declare_builtin_function, memory_region::MemoryMapping},
     solana_sdk_ids::{bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, loader_v4},
+    solana_sha256_hasher::hash,
     solana_transaction_context::{BorrowedAccount, InstructionContext},
     solana_type_overrides::sync::{atomic::Ordering, Arc},
     std::{cell::RefCell, rc::Rc},
@@ -27,6 +29,58 @@ use {
 #[cfg_attr(feature = "agave-unstable-api", qualifiers(pub))]
 const DEFAULT_COMPUTE_UNITS: u64 = 2_000;
 
+/// Seed of the ELF hash record of a program, derived from the loader
+pub const ELF_HASH_RECORD_SEED: &[u8] = b"elf_hash";
+/// An ELF hash record starts with the state of a finalized program without a next version, so
+/// that no loader instruction can modify or deploy it, followed by the ELF hash. No ELF is that
+/// short, so a record can't be mistaken for a program.
+pub const ELF_HASH_RECORD_SIZE: usize =
+    LoaderV4State::program_data_offset().saturating_add(HASH_BYTES);
+
+/// SHA-256 hash of the ELF currently stored in a program account, i.e. the data after the state
+/// header. Deploy records it in the ELF hash record of the program, see
+/// [`get_elf_hash_record_address`].
+pub fn get_elf_hash(programdata: &[u8]) -> Hash {
+    hash(programdata)
+}
+
+/// Charge for hashing `programdata` like the `sol_sha256` syscall would
+pub fn consume_elf_hash_cost(
+    invoke_context: &InvokeContext,
+    programdata: &[u8],
+) -> Result<(), InstructionError> {
+    let execution_cost = invoke_context.get_execution_cost();
+    let byte_cost = execution_cost
+        .sha256_byte_cost
+        .saturating_mul((programdata.len() as u64).saturating_div(2));
+    invoke_context
+        .consume_checked(
+            execution_cost
+                .sha256_base_cost
+                .saturating_add(execution_cost.mem_op_base_cost.max(byte_cost)),
+        )
+        .map_err(|_| InstructionError::ComputationalBudgetExceeded)
+}
+
+/// Address of the account which records the ELF hash and the slot of the last deploy of a program
+pub fn get_elf_hash_record_address(program_address: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[ELF_HASH_RECORD_SEED, program_address.as_ref()],
+        &loader_v4::id(),
+    )
+    .0
+}
+
+/// Returns the slot of the last deploy and the ELF hash kept in an ELF hash record
+pub fn get_elf_hash_record(data: &[u8]) -> Option<(u64, Hash)> {
+    if data.len() != ELF_HASH_RECORD_SIZE {
+        return None;
+    }
+    let state = get_state(data).ok()?;
+    let elf_hash = data.get(LoaderV4State::program_data_offset()..)?;
+    Some((state.slot, Hash::new_from_array(elf_hash.try_into().ok()?)))
+}
+
 pub fn get_state(data: &[u8]) -> Result<&LoaderV4State, InstructionError> {
     unsafe {
         let data = data
@@ -313,10 +367,73 @@ fn process_instruction_deploy(invoke_context: &mut InvokeContext) -> Result<(),
         programdata,
         current_slot,
     );
+    let record_elf_hash = invoke_context.get_feature_set().record_loader_v4_elf_hash
+        && instruction_context.get_number_of_instruction_accounts() > 2;
+    if record_elf_hash {
+        consume_elf_hash_cost(invoke_context, programdata)?;
+    }
+    let elf_hash = get_elf_hash(programdata);
+    ic_logger_msg!(log_collector, "Deployed program with ELF hash {}", elf_hash);
 
     let state = get_state_mut(program.get_data_mut()?)?;
     state.slot = current_slot;
     state.status = LoaderV4Status::Deployed;
+    let program_address = *program.get_key();
+    drop(program);
+
+    if record_elf_hash {
+        write_elf_hash_record(invoke_context, &program_address, current_slot, &elf_hash)?;
+    }
+    Ok(())
+}
+
+/// Writes the ELF hash record of a program, the third instruction account of `Deploy`. The record
+/// is created on the first deploy, funded by the fourth instruction account.
+fn write_elf_hash_record(
+    invoke_context: &mut InvokeContext,
+    program_address: &Pubkey,
+    slot: u64,
+    elf_hash: &Hash,
+) -> Result<(), InstructionError> {
+    let log_collector = invoke_context.get_log_collector();
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let record = instruction_context.try_borrow_instruction_account(transaction_context, 2)?;
+    let record_address = *record.get_key();
+    if record_address != get_elf_hash_record_address(program_address) {
+        ic_logger_msg!(
+            log_collector,
+            "ELF hash record is not derived from the program"
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    let is_created = loader_v4::check_id(record.get_owner());
+    drop(record);
+    if !is_created {
+        let payer_address = instruction_context
+            .get_index_of_instruction_account_in_transaction(3)
+            .and_then(|index| transaction_context.get_key_of_account_at_index(index))
+            .ok()
+            .copied();
+        invoke_context.create_program_derived_account(
+            &record_address,
+            payer_address.as_ref(),
+            ELF_HASH_RECORD_SIZE,
+            &loader_v4::id(),
+        )?;
+    }
+
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let mut record = instruction_context.try_borrow_instruction_account(transaction_context, 2)?;
+    let data = record.get_data_mut()?;
+    let state = get_state_mut(data)?;
+    state.slot = slot;
+    state.authority_address_or_next_version = Pubkey::default();
+    state.status = LoaderV4Status::Finalized;
+    data.get_mut(LoaderV4State::program_data_offset()..ELF_HASH_RECORD_SIZE)
+        .ok_or(InstructionError::AccountDataTooSmall)?
+        .copy_from_slice(elf_hash.as_ref());
     Ok(())
 }
 
@@ -529,7 +646,9 @@ mod tests {
         solana_bpf_loader_program::test_utils,
         solana_clock::Slot,
         solana_instruction::AccountMeta,
-        solana_program_runtime::invoke_context::mock_process_instruction,
+        solana_program_runtime::invoke_context::mock_process_instruction_with_feature_set,
+        solana_sdk_ids::{native_loader, system_program},
+        solana_svm_feature_set::SVMFeatureSet,
         solana_sysvar::{clock, rent},
         solana_transaction_context::IndexOfAccount,
         std::{fs::File, io::Read, path::Path},
@@ -541,6 +660,24 @@ mod tests {
         transaction_accounts: Vec<(Pubkey, AccountSharedData)>,
         instruction_accounts: &[(IndexOfAccount, bool, bool)],
         expected_result: Result<(), InstructionError>,
+    ) -> Vec<AccountSharedData> {
+        process_instruction_with_feature_set(
+            program_indices,
+            instruction_data,
+            transaction_accounts,
+            instruction_accounts,
+            expected_result,
+            &SVMFeatureSet::all_enabled(),
+        )
+    }
+
+    fn process_instruction_with_feature_set(
+        program_indices: Vec<IndexOfAccount>,
+        instruction_data: &[u8],
+        transaction_accounts: Vec<(Pubkey, AccountSharedData)>,
+        instruction_accounts: &[(IndexOfAccount, bool, bool)],
+        expected_result: Result<(), InstructionError>,
+        feature_set: &SVMFeatureSet,
     ) -> Vec<AccountSharedData> {
         let instruction_accounts = instruction_accounts
             .iter()
@@ -552,7 +689,7 @@ mod tests {
                 },
             )
             .collect::<Vec<_>>();
-        mock_process_instruction(
+        mock_process_instruction_with_feature_set(
             &loader_v4::id(),
             program_indices,
             instruction_data,
@@ -562,8 +699,17 @@ mod tests {
             Entrypoint::vm,
             |invoke_context| {
                 test_utils::load_all_invoked_programs(invoke_context);
+                invoke_context.program_cache_for_tx_batch.replenish(
+                    system_program::id(),
+                    Arc::new(ProgramCacheEntry::new_builtin(
+                        0,
+                        0,
+                        solana_system_program::system_processor::Entrypoint::vm,
+                    )),
+                );
             },
             |_invoke_context| {},
+            feature_set,
         )
     }
 
@@ -1310,6 +1456,135 @@ mod tests {
         test_loader_instruction_general_errors(LoaderV4Instruction::Deploy);
     }
 
+    #[test]
+    fn test_loader_instruction_deploy_elf_hash_record() {
+        let authority_address = Pubkey::new_unique();
+        let program_address = Pubkey::new_unique();
+        let program_account = load_program_account_from_elf(
+            authority_address,
+            LoaderV4Status::Retracted,
+            "sbpfv3_return_ok",
+        );
+        let elf_hash =
+            get_elf_hash(&program_account.data()[LoaderV4State::program_data_offset()..]);
+        let record_address = get_elf_hash_record_address(&program_address);
+        let rent = rent::Rent::default();
+        let record_rent = rent.minimum_balance(ELF_HASH_RECORD_SIZE);
+        let mut transaction_accounts = vec![
+            (program_address, program_account),
+            (
+                authority_address,
+                AccountSharedData::new(0, 0, &Pubkey::new_unique()),
+            ),
+            (
+                record_address,
+                AccountSharedData::new(0, 0, &system_program::id()),
+            ),
+            (
+                Pubkey::new_unique(),
+                AccountSharedData::new(record_rent, 0, &system_program::id()),
+            ),
+            (
+                system_program::id(),
+                AccountSharedData::new(0, 0, &native_loader::id()),
+            ),
+            (clock::id(), clock(1000)),
+            (rent::id(), create_account_shared_data_for_test(&rent)),
+        ];
+        let deploy = bincode::serialize(&LoaderV4Instruction::Deploy).unwrap();
+        let instruction_accounts = [
+            (0, false, true),
+            (1, true, false),
+            (2, false, true),
+            (3, true, true),
+            (4, false, false),
+        ];
+
+        // Error: ELF hash record is not derived from the program
+        process_instruction(
+            vec![],
+            &deploy,
+            transaction_accounts.clone(),
+            &[
+                (0, false, true),
+                (1, true, false),
+                (3, true, true),
+                (4, false, false),
+            ],
+            Err(InstructionError::InvalidArgument),
+        );
+
+        // Error: The record can't be created without a payer
+        process_instruction(
+            vec![],
+            &deploy,
+            transaction_accounts.clone(),
+            &instruction_accounts[..3],
+            Err(InstructionError::InsufficientFunds),
+        );
+
+        // Without the feature the record is not written
+        let accounts = process_instruction_with_feature_set(
+            vec![],
+            &deploy,
+            transaction_accounts.clone(),
+            &instruction_accounts,
+            Ok(()),
+            &SVMFeatureSet {
+                record_loader_v4_elf_hash: false,
+                ..SVMFeatureSet::all_enabled()
+            },
+        );
+        assert_eq!(accounts[2], transaction_accounts[2].1);
+
+        // The first deploy creates the record, funded by the payer
+        let accounts = process_instruction(
+            vec![],
+            &deploy,
+            transaction_accounts.clone(),
+            &instruction_accounts,
+            Ok(()),
+        );
+        assert_eq!(accounts[2].owner(), &loader_v4::id());
+        assert_eq!(accounts[2].lamports(), record_rent);
+        assert_eq!(accounts[3].lamports(), 0);
+        assert_eq!(
+            get_elf_hash_record(accounts[2].data()),
+            Some((1000, elf_hash))
+        );
+
+        // Error: The record is not a program the authority can modify
+        transaction_accounts[2].1 = accounts[2].clone();
+        process_instruction(
+            vec![],
+            &bincode::serialize(&LoaderV4Instruction::Retract).unwrap(),
+            transaction_accounts.clone(),
+            &[(2, false, true), (1, true, false)],
+            Err(InstructionError::IncorrectAuthority),
+        );
+
+        // A redeploy updates the record, which needs no payer anymore
+        let redeploy_slot = 1000 + DEPLOYMENT_COOLDOWN_IN_SLOTS;
+        let mut program_account = accounts[0].clone();
+        get_state_mut(program_account.data_as_mut_slice())
+            .unwrap()
+            .status = LoaderV4Status::Retracted;
+        transaction_accounts[0].1 = program_account;
+        transaction_accounts[5].1 = clock(redeploy_slot);
+        let accounts = process_instruction(
+            vec![],
+            &deploy,
+            transaction_accounts,
+            &instruction_accounts[..3],
+            Ok(()),
+        );
+        assert_eq!(accounts[2].lamports(), record_rent);
+        assert_eq!(
+            get_elf_hash_record(accounts[2].data()),
+            Some((redeploy_slot, elf_hash))
+        );
+    }
+
     #[test]
     fn test_loader_instruction_retract() {
         let authority_address = Pubkey::new_unique();
@@ -0,0 +1,799 @@
+#![forbid(unsafe_code)]
+
+use {
+    solana_bincode::limited_deserialize,
+    solana_instruction::{error::InstructionError, Instruction},
+    solana_log_collector::ic_msg,
+    solana_multisig_interface::{
+        get_proposal_address, get_vault_address, id,
+        instruction::MultisigInstruction,
+        state::{Multisig, Proposal, ProposalStatus, ProposedInstruction},
+        MAX_SIGNERS,
+    },
+    solana_program_runtime::{declare_process_instruction, invoke_context::InvokeContext},
+    solana_pubkey::Pubkey,
+    solana_system_interface::instruction as system_instruction,
+    solana_transaction_context::{BorrowedAccount, IndexOfAccount, InstructionContext},
+};
+
+pub const DEFAULT_COMPUTE_UNITS: u64 = 2_000;
+/// Compute units charged by `Execute` for each instruction of the proposal, on top of the
+/// units consumed by the invoked program
+pub const EXECUTE_INSTRUCTION_COMPUTE_UNITS: u64 = 1_000;
+/// Compute units charged by `Execute` for each account of each instruction of the proposal
+pub const EXECUTE_ACCOUNT_COMPUTE_UNITS: u64 = 100;
+
+fn get_key_of_instruction_account(
+    invoke_context: &InvokeContext,
+    instruction_context: &InstructionContext,
+    index_in_instruction: IndexOfAccount,
+) -> Result<Pubkey, InstructionError> {
+    let transaction_context = &invoke_context.transaction_context;
+    instruction_context
+        .get_index_of_instruction_account_in_transaction(index_in_instruction)
+        .and_then(|index| transaction_context.get_key_of_account_at_index(index))
+        .copied()
+}
+
+fn check_config(
+    invoke_context: &InvokeContext,
+    threshold: u8,
+    signers: &[Pubkey],
+) -> Result<(), InstructionError> {
+    if signers.is_empty() || signers.len() > MAX_SIGNERS {
+        ic_msg!(
+            invoke_context,
+            "Number of signers must be between 1 and {}",
+            MAX_SIGNERS
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    if threshold == 0 || usize::from(threshold) > signers.len() {
+        ic_msg!(
+            invoke_context,
+            "Threshold must be between 1 and the number of signers"
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    for (index, signer) in signers.iter().enumerate() {
+        if signers[..index].contains(signer) {
+            ic_msg!(invoke_context, "Duplicate signer {}", signer);
+            return Err(InstructionError::InvalidArgument);
+        }
+    }
+    Ok(())
+}
+
+fn get_multisig(
+    invoke_context: &InvokeContext,
+    multisig_account: &BorrowedAccount,
+) -> Result<Multisig, InstructionError> {
+    if *multisig_account.get_owner() != id() {
+        ic_msg!(
+            invoke_context,
+            "Multisig account not owned by the multisig program"
+        );
+        return Err(InstructionError::InvalidAccountOwner);
+    }
+    Multisig::deserialize(multisig_account.get_data())
+}
+
+fn get_proposal(
+    invoke_context: &InvokeContext,
+    proposal_account: &BorrowedAccount,
+    multisig_address: &Pubkey,
+) -> Result<Proposal, InstructionError> {
+    if *proposal_account.get_owner() != id() {
+        ic_msg!(
+            invoke_context,
+            "Proposal account not owned by the multisig program"
+        );
+        return Err(InstructionError::InvalidAccountOwner);
+    }
+    let proposal = Proposal::deserialize(proposal_account.get_data())?;
+    if proposal.multisig != *multisig_address {
+        ic_msg!(invoke_context, "Proposal does not belong to the multisig");
+        return Err(InstructionError::InvalidArgument);
+    }
+    Ok(proposal)
+}
+
+/// Returns the position of the signing member among the members of `multisig`
+fn check_member(
+    invoke_context: &InvokeContext,
+    instruction_context: &InstructionContext,
+    multisig: &Multisig,
+    index_in_instruction: IndexOfAccount,
+) -> Result<usize, InstructionError> {
+    let signer_address =
+        get_key_of_instruction_account(invoke_context, instruction_context, index_in_instruction)?;
+    let Some(signer_index) = multisig.signer_index(&signer_address) else {
+        ic_msg!(invoke_context, "Signer is not a member of the multisig");
+        return Err(InstructionError::IncorrectAuthority);
+    };
+    if !instruction_context.is_instruction_account_signer(index_in_instruction)? {
+        ic_msg!(invoke_context, "Member did not sign");
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    Ok(signer_index)
+}
+
+fn check_active(
+    invoke_context: &InvokeContext,
+    multisig: &Multisig,
+    proposal: &Proposal,
+) -> Result<(), InstructionError> {
+    if proposal.status != ProposalStatus::Active {
+        ic_msg!(invoke_context, "Proposal was already executed");
+        return Err(InstructionError::InvalidArgument);
+    }
+    if !multisig.is_current(proposal.index) {
+        ic_msg!(
+            invoke_context,
+            "Proposal is stale, the multisig configuration changed"
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    Ok(())
+}
+
+fn process_create_multisig(
+    invoke_context: &mut InvokeContext,
+    threshold: u8,
+    signers: Vec<Pubkey>,
+) -> Result<(), InstructionError> {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    instruction_context.check_number_of_instruction_accounts(3)?;
+    check_config(invoke_context, threshold, &signers)?;
+    let multisig_address = get_key_of_instruction_account(invoke_context, instruction_context, 0)?;
+    let payer_address = get_key_of_instruction_account(invoke_context, instruction_context, 1)?;
+
+    let space = Multisig::size_of();
+    let lamports = invoke_context
+        .get_sysvar_cache()
+        .get_rent()?
+        .minimum_balance(space);
+    invoke_context.native_invoke(
+        system_instruction::create_account(
+            Some(&payer_address),
+            &multisig_address,
+            lamports,
+            space as u64,
+            &id(),
+        )
+        .into(),
+        &[],
+    )?;
+
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let mut multisig_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    Multisig {
+        threshold,
+        signers,
+        proposal_count: 0,
+        stale_proposal_index: 0,
+    }
+    .serialize(multisig_account.get_data_mut()?)
+}
+
+fn process_propose(
+    invoke_context: &mut InvokeContext,
+    instructions: Vec<ProposedInstruction>,
+) -> Result<(), InstructionError> {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    instruction_context.check_number_of_instruction_accounts(5)?;
+    if instructions.is_empty() {
+        ic_msg!(
+            invoke_context,
+            "Proposal must contain at least one instruction"
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    let mut multisig_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    let multisig_address = *multisig_account.get_key();
+    let mut multisig = get_multisig(invoke_context, &multisig_account)?;
+    let signer_index = check_member(invoke_context, instruction_context, &multisig, 2)?;
+    let proposal_address = get_key_of_instruction_account(invoke_context, instruction_context, 1)?;
+    let proposer_address = get_key_of_instruction_account(invoke_context, instruction_context, 2)?;
+    let payer_address = get_key_of_instruction_account(invoke_context, instruction_context, 3)?;
+    if proposal_address != get_proposal_address(&multisig_address, multisig.proposal_count) {
+        ic_msg!(
+            invoke_context,
+            "Proposal address does not match the next proposal index {}",
+            multisig.proposal_count
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    let mut proposal = Proposal {
+        multisig: multisig_address,
+        index: multisig.proposal_count,
+        proposer: proposer_address,
+        approvals: 0,
+        status: ProposalStatus::Active,
+        instructions,
+    };
+    proposal.approve(signer_index);
+    multisig.proposal_count = multisig.proposal_count.saturating_add(1);
+    multisig.serialize(multisig_account.get_data_mut()?)?;
+    drop(multisig_account);
+
+    invoke_context.create_program_derived_account(
+        &proposal_address,
+        &payer_address,
+        proposal.size_of(),
+        &id(),
+    )?;
+
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let mut proposal_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 1)?;
+    proposal.serialize(proposal_account.get_data_mut()?)
+}
+
+fn process_approve(invoke_context: &mut InvokeContext) -> Result<(), InstructionError> {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    instruction_context.check_number_of_instruction_accounts(3)?;
+    let multisig_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    let multisig = get_multisig(invoke_context, &multisig_account)?;
+    let signer_index = check_member(invoke_context, instruction_context, &multisig, 2)?;
+    let mut proposal_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 1)?;
+    let mut proposal = get_proposal(
+        invoke_context,
+        &proposal_account,
+        multisig_account.get_key(),
+    )?;
+    check_active(invoke_context, &multisig, &proposal)?;
+    if proposal.is_approved_by(signer_index) {
+        ic_msg!(invoke_context, "Member already approved the proposal");
+        return Err(InstructionError::InvalidArgument);
+    }
+    proposal.approve(signer_index);
+    proposal.serialize(proposal_account.get_data_mut()?)
+}
+
+fn process_execute(invoke_context: &mut InvokeContext) -> Result<(), InstructionError> {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    instruction_context.check_number_of_instruction_accounts(2)?;
+    let multisig_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    let multisig_address = *multisig_account.get_key();
+    let multisig = get_multisig(invoke_context, &multisig_account)?;
+    let mut proposal_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 1)?;
+    let mut proposal = get_proposal(invoke_context, &proposal_account, &multisig_address)?;
+    check_active(invoke_context, &multisig, &proposal)?;
+    if proposal.approval_count() < u32::from(multisig.threshold) {
+        ic_msg!(
+            invoke_context,
+            "Proposal has {} of {} required approvals",
+            proposal.approval_count(),
+            multisig.threshold
+        );
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    let compute_units = proposal
+        .instructions
+        .iter()
+        .map(|instruction| {
+            EXECUTE_ACCOUNT_COMPUTE_UNITS
+                .saturating_mul(instruction.accounts.len() as u64)
+                .saturating_add(EXECUTE_INSTRUCTION_COMPUTE_UNITS)
+        })
+        .fold(0u64, u64::saturating_add);
+    invoke_context
+        .consume_checked(compute_units)
+        .map_err(|_| InstructionError::ComputationalBudgetExceeded)?;
+    // Mark the proposal as executed before invoking, so it can not be executed twice
+    proposal.status = ProposalStatus::Executed;
+    proposal.serialize(proposal_account.get_data_mut()?)?;
+    drop(proposal_account);
+    drop(multisig_account);
+
+    let signers = [get_vault_address(&multisig_address)];
+    for instruction in proposal.instructions {
+        invoke_context.native_invoke(Instruction::from(instruction).into(), &signers)?;
+    }
+    Ok(())
+}
+
+fn process_set_config(
+    invoke_context: &mut InvokeContext,
+    threshold: u8,
+    signers: Vec<Pubkey>,
+) -> Result<(), InstructionError> {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    instruction_context.check_number_of_instruction_accounts(2)?;
+    check_config(invoke_context, threshold, &signers)?;
+    let mut multisig_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    let mut multisig = get_multisig(invoke_context, &multisig_account)?;
+    let vault_address = get_key_of_instruction_account(invoke_context, instruction_context, 1)?;
+    if vault_address != get_vault_address(multisig_account.get_key()) {
+        ic_msg!(invoke_context, "Incorrect vault provided");
+        return Err(InstructionError::IncorrectAuthority);
+    }
+    if !instruction_context.is_instruction_account_signer(1)? {
+        ic_msg!(invoke_context, "Vault did not sign");
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    multisig.threshold = threshold;
+    multisig.signers = signers;
+    multisig.stale_proposal_index = multisig.proposal_count;
+    multisig.serialize(multisig_account.get_data_mut()?)
+}
+
+declare_process_instruction!(Entrypoint, DEFAULT_COMPUTE_UNITS, |invoke_context| {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let instruction_data = instruction_context.get_instruction_data();
+    match limited_deserialize(instruction_data, solana_packet::PACKET_DATA_SIZE as u64)? {
+        MultisigInstruction::CreateMultisig { threshold, signers } => {
+            process_create_multisig(invoke_context, threshold, signers)
+        }
+        MultisigInstruction::Propose { instructions } => {
+            process_propose(invoke_context, instructions)
+        }
+        MultisigInstruction::Approve => process_approve(invoke_context),
+        MultisigInstruction::Execute => process_execute(invoke_context),
+        MultisigInstruction::SetConfig { threshold, signers } => {
+            process_set_config(invoke_context, threshold, signers)
+        }
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_account::{
+            create_account_shared_data_for_test, AccountSharedData, ReadableAccount,
+            WritableAccount,
+        },
+        solana_multisig_interface::instruction,
+        solana_program_runtime::{
+            invoke_context::mock_process_instruction, loaded_programs::ProgramCacheEntry,
+        },
+        solana_sdk_ids::{native_loader, system_program},
+        solana_sysvar::rent::{self, Rent},
+        std::sync::Arc,
+    };
+
+    fn process_instruction(
+        instruction: &Instruction,
+        transaction_accounts: Vec<(Pubkey, AccountSharedData)>,
+        expected_result: Result<(), InstructionError>,
+    ) -> Vec<AccountSharedData> {
+        mock_process_instruction(
+            &id(),
+            Vec::new(),
+            &instruction.data,
+            transaction_accounts,
+            instruction.accounts.clone(),
+            expected_result,
+            Entrypoint::vm,
+            |invoke_context| {
+                invoke_context.program_cache_for_tx_batch.replenish(
+                    system_program::id(),
+                    Arc::new(ProgramCacheEntry::new_builtin(
+                        0,
+                        0,
+                        solana_system_program::system_processor::Entrypoint::vm,
+                    )),
+                );
+            },
+            |_invoke_context| {},
+        )
+    }
+
+    fn multisig_account(multisig: &Multisig) -> AccountSharedData {
+        let mut account = AccountSharedData::new(
+            Rent::default().minimum_balance(Multisig::size_of()),
+            Multisig::size_of(),
+            &id(),
+        );
+        multisig.serialize(account.data_as_mut_slice()).unwrap();
+        account
+    }
+
+    fn proposal_account(proposal: &Proposal) -> AccountSharedData {
+        let mut account = AccountSharedData::new(
+            Rent::default().minimum_balance(proposal.size_of()),
+            proposal.size_of(),
+            &id(),
+        );
+        proposal.serialize(account.data_as_mut_slice()).unwrap();
+        account
+    }
+
+    fn system_account(lamports: u64) -> AccountSharedData {
+        AccountSharedData::new(lamports, 0, &system_program::id())
+    }
+
+    #[test]
+    fn test_create_multisig() {
+        let multisig_address = Pubkey::new_unique();
+        let payer_address = Pubkey::new_unique();
+        let signers = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let transaction_accounts = vec![
+            (multisig_address, AccountSharedData::default()),
+            (payer_address, system_account(1_000_000_000)),
+            (
+                system_program::id(),
+                AccountSharedData::new(0, 0, &native_loader::id()),
+            ),
+            (
+                rent::id(),
+                create_account_shared_data_for_test(&Rent::default()),
+            ),
+        ];
+
+        // Error: Threshold of zero
+        process_instruction(
+            &instruction::create_multisig(&multisig_address, &payer_address, 0, signers.clone()),
+            transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+
+        // Error: Threshold above the number of signers
+        process_instruction(
+            &instruction::create_multisig(&multisig_address, &payer_address, 3, signers.clone()),
+            transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+
+        // Error: Duplicate signer
+        process_instruction(
+            &instruction::create_multisig(
+                &multisig_address,
+                &payer_address,
+                2,
+                vec![signers[0], signers[0]],
+            ),
+            transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+
+        // Error: Too many signers
+        process_instruction(
+            &instruction::create_multisig(
+                &multisig_address,
+                &payer_address,
+                1,
+                (0..=MAX_SIGNERS).map(|_| Pubkey::new_unique()).collect(),
+            ),
+            transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+
+        let accounts = process_instruction(
+            &instruction::create_multisig(&multisig_address, &payer_address, 2, signers.clone()),
+            transaction_accounts,
+            Ok(()),
+        );
+        assert_eq!(accounts[0].owner(), &id());
+        assert_eq!(
+            Multisig::deserialize(accounts[0].data()).unwrap(),
+            Multisig {
+                threshold: 2,
+                signers,
+                proposal_count: 0,
+                stale_proposal_index: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_propose_approve_and_execute() {
+        let multisig_address = Pubkey::new_unique();
+        let vault_address = get_vault_address(&multisig_address);
+        let proposal_address = get_proposal_address(&multisig_address, 0);
+        let signers = vec![
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let outsider_address = Pubkey::new_unique();
+        let payer_address = Pubkey::new_unique();
+        let recipient_address = Pubkey::new_unique();
+        let multisig = Multisig {
+            threshold: 2,
+            signers: signers.clone(),
+            proposal_count: 0,
+            stale_proposal_index: 0,
+        };
+        let proposed_instructions: Vec<ProposedInstruction> =
+            vec![system_instruction::transfer(&vault_address, &recipient_address, 100).into()];
+        let mut transaction_accounts = vec![
+            (multisig_address, multisig_account(&multisig)),
+            (proposal_address, AccountSharedData::default()),
+            (vault_address, system_account(1_000)),
+            (recipient_address, system_account(0)),
+            (payer_address, system_account(1_000_000_000)),
+            (
+                system_program::id(),
+                AccountSharedData::new(0, 0, &native_loader::id()),
+            ),
+            (
+                rent::id(),
+                create_account_shared_data_for_test(&Rent::default()),
+            ),
+        ];
+        for signer in signers.iter().chain([&outsider_address]) {
+            transaction_accounts.push((*signer, system_account(0)));
+        }
+
+        // Error: Proposer is not a member
+        process_instruction(
+            &instruction::propose(
+                &multisig_address,
+                0,
+                &outsider_address,
+                &payer_address,
+                proposed_instructions.clone(),
+            ),
+            transaction_accounts.clone(),
+            Err(InstructionError::IncorrectAuthority),
+        );
+
+        // Error: Proposal address does not match the next proposal index
+        let mut other_transaction_accounts = transaction_accounts.clone();
+        other_transaction_accounts[1].0 = get_proposal_address(&multisig_address, 1);
+        process_instruction(
+            &instruction::propose(
+                &multisig_address,
+                1,
+                &signers[0],
+                &payer_address,
+                proposed_instructions.clone(),
+            ),
+            other_transaction_accounts,
+            Err(InstructionError::InvalidArgument),
+        );
+
+        // Error: Proposal account is already in use
+        let mut in_use_transaction_accounts = transaction_accounts.clone();
+        in_use_transaction_accounts[1].1 = AccountSharedData::new(1, 1, &system_program::id());
+        process_instruction(
+            &instruction::propose(
+                &multisig_address,
+                0,
+                &signers[0],
+                &payer_address,
+                proposed_instructions.clone(),
+            ),
+            in_use_transaction_accounts,
+            Err(InstructionError::AccountAlreadyInitialized),
+        );
+
+        // A pre-funded proposal account only needs the rent shortfall
+        let proposal_rent = Rent::default().minimum_balance(
+            Proposal {
+                multisig: multisig_address,
+                index: 0,
+                proposer: signers[0],
+                approvals: 0,
+                status: ProposalStatus::Active,
+                instructions: proposed_instructions.clone(),
+            }
+            .size_of(),
+        );
+        let mut prefunded_transaction_accounts = transaction_accounts.clone();
+        prefunded_transaction_accounts[1].1 = system_account(proposal_rent - 1);
+        let accounts = process_instruction(
+            &instruction::propose(
+                &multisig_address,
+                0,
+                &signers[0],
+                &payer_address,
+                proposed_instructions.clone(),
+            ),
+            prefunded_transaction_accounts,
+            Ok(()),
+        );
+        assert_eq!(accounts[1].owner(), &id());
+        assert_eq!(accounts[1].lamports(), proposal_rent);
+        assert_eq!(accounts[4].lamports(), 1_000_000_000 - 1);
+
+        let accounts = process_instruction(
+            &instruction::propose(
+                &multisig_address,
+                0,
+                &signers[0],
+                &payer_address,
+                proposed_instructions.clone(),
+            ),
+            transaction_accounts.clone(),
+            Ok(()),
+        );
+        assert_eq!(accounts[1].lamports(), proposal_rent);
+        assert_eq!(
+            Multisig::deserialize(accounts[0].data())
+                .unwrap()
+                .proposal_count,
+            1
+        );
+        let proposal = Proposal::deserialize(accounts[1].data()).unwrap();
+        assert_eq!(
+            proposal,
+            Proposal {
+                multisig: multisig_address,
+                index: 0,
+                proposer: signers[0],
+                approvals: 0b1,
+                status: ProposalStatus::Active,
+                instructions: proposed_instructions.clone(),
+            }
+        );
+        transaction_accounts[0].1 = accounts[0].clone();
+        transaction_accounts[1].1 = accounts[1].clone();
+
+        // Error: Threshold not reached yet
+        process_instruction(
+            &instruction::execute(&multisig_address, 0, &proposed_instructions),
+            transaction_accounts.clone(),
+            Err(InstructionError::MissingRequiredSignature),
+        );
+
+        // Error: Proposer already approved
+        process_instruction(
+            &instruction::approve(&multisig_address, 0, &signers[0]),
+            transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+
+        // Error: Approver is not a member
+        process_instruction(
+            &instruction::approve(&multisig_address, 0, &outsider_address),
+            transaction_accounts.clone(),
+            Err(InstructionError::IncorrectAuthority),
+        );
+
+        // Error: Member did not sign
+        let mut approve_instruction = instruction::approve(&multisig_address, 0, &signers[2]);
+        approve_instruction.accounts[2].is_signer = false;
+        process_instruction(
+            &approve_instruction,
+            transaction_accounts.clone(),
+            Err(InstructionError::MissingRequiredSignature),
+        );
+
+        let accounts = process_instruction(
+            &instruction::approve(&multisig_address, 0, &signers[2]),
+            transaction_accounts.clone(),
+            Ok(()),
+        );
+        assert_eq!(
+            Proposal::deserialize(accounts[1].data()).unwrap().approvals,
+            0b101
+        );
+        transaction_accounts[1].1 = accounts[1].clone();
+
+        // Error: Not enough compute units for the instructions and accounts of the proposal
+        let execute_instruction =
+            instruction::execute(&multisig_address, 0, &proposed_instructions);
+        mock_process_instruction(
+            &id(),
+            Vec::new(),
+            &execute_instruction.data,
+            transaction_accounts.clone(),
+            execute_instruction.accounts.clone(),
+            Err(InstructionError::ComputationalBudgetExceeded),
+            Entrypoint::vm,
+            |invoke_context| {
+                invoke_context.mock_set_remaining(
+                    DEFAULT_COMPUTE_UNITS
+                        + EXECUTE_INSTRUCTION_COMPUTE_UNITS
+                        + 2 * EXECUTE_ACCOUNT_COMPUTE_UNITS
+                        - 1,
+                );
+            },
+            |_invoke_context| {},
+        );
+
+        let accounts = process_instruction(
+            &instruction::execute(&multisig_address, 0, &proposed_instructions),
+            transaction_accounts.clone(),
+            Ok(()),
+        );
+        assert_eq!(
+            Proposal::deserialize(accounts[1].data()).unwrap().status,
+            ProposalStatus::Executed
+        );
+        assert_eq!(accounts[2].lamports(), 900);
+        assert_eq!(accounts[3].lamports(), 100);
+        transaction_accounts[1].1 = accounts[1].clone();
+
+        // Error: Proposal was already executed
+        process_instruction(
+            &instruction::execute(&multisig_address, 0, &proposed_instructions),
+            transaction_accounts,
+            Err(InstructionError::InvalidArgument),
+        );
+    }
+
+    #[test]
+    fn test_set_config() {
+        let multisig_address = Pubkey::new_unique();
+        let vault_address = get_vault_address(&multisig_address);
+        let signers = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let new_signers = vec![signers[1], Pubkey::new_unique(), Pubkey::new_unique()];
+        let multisig = Multisig {
+            threshold: 1,
+            signers: signers.clone(),
+            proposal_count: 2,
+            stale_proposal_index: 0,
+        };
+        let proposed_instructions: Vec<ProposedInstruction> =
+            vec![instruction::set_config(&multisig_address, 2, new_signers.clone()).into()];
+        let proposal = |index| Proposal {
+            multisig: multisig_address,
+            index,
+            proposer: signers[0],
+            approvals: 0b1,
+            status: ProposalStatus::Active,
+            instructions: proposed_instructions.clone(),
+        };
+        let mut transaction_accounts = vec![
+            (multisig_address, multisig_account(&multisig)),
+            (
+                get_proposal_address(&multisig_address, 0),
+                proposal_account(&proposal(0)),
+            ),
+            (
+                get_proposal_address(&multisig_address, 1),
+                proposal_account(&proposal(1)),
+            ),
+            (vault_address, system_account(0)),
+            (signers[1], system_account(0)),
+        ];
+
+        // Error: Vault did not sign
+        let mut set_config_instruction =
+            instruction::set_config(&multisig_address, 2, new_signers.clone());
+        set_config_instruction.accounts[1].is_signer = false;
+        process_instruction(
+            &set_config_instruction,
+            transaction_accounts.clone(),
+            Err(InstructionError::MissingRequiredSignature),
+        );
+
+        let accounts = process_instruction(
+            &instruction::execute(&multisig_address, 0, &proposed_instructions),
+            transaction_accounts.clone(),
+            Ok(()),
+        );
+        assert_eq!(
+            Multisig::deserialize(accounts[0].data()).unwrap(),
+            Multisig {
+                threshold: 2,
+                signers: new_signers,
+                proposal_count: 2,
+                stale_proposal_index: 2,
+            }
+        );
+        transaction_accounts[0].1 = accounts[0].clone();
+
+        // Error: Proposals made under the previous configuration are stale
+        process_instruction(
+            &instruction::execute(&multisig_address, 1, &proposed_instructions),
+            transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+        process_instruction(
+            &instruction::approve(&multisig_address, 1, &signers[1]),
+            transaction_accounts,
+            Err(InstructionError::InvalidArgument),
+        );
+    }
+}
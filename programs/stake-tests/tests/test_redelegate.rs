@@ -0,0 +1,780 @@
+#![allow(clippy::arithmetic_side_effects)]
+
+// NOTE the utility functions in this file follow test_move_stake_and_lamports.rs and, like those,
+// should not be broken out into modules or used elsewhere
+
+use {
+    agave_feature_set::enable_stake_redelegate_instruction,
+    solana_account::Account as SolanaAccount,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::Keypair,
+    solana_program_error::{ProgramError, ProgramResult},
+    solana_program_test::*,
+    solana_pubkey::Pubkey,
+    solana_signer::{signers::Signers, Signer},
+    solana_stake_interface::{
+        self as stake,
+        error::StakeError,
+        instruction::{self as ixn, StakeInstruction},
+        program as stake_program,
+        stake_flags::StakeFlags,
+        state::{Authorized, Lockup, Meta, Stake, StakeStateV2},
+    },
+    solana_system_interface::{instruction as system_instruction, program as system_program},
+    solana_sysvar::{clock::Clock, stake_history::StakeHistory},
+    solana_transaction::Transaction,
+    solana_transaction_error::TransactionError,
+    solana_vote_program::{
+        vote_instruction,
+        vote_state::{VoteInit, VoteStateV3, VoteStateVersions},
+    },
+    test_case::{test_case, test_matrix},
+};
+
+const NO_SIGNERS: &[Keypair] = &[];
+
+fn program_test() -> ProgramTest {
+    program_test_without_features(&[])
+}
+
+fn program_test_without_features(feature_ids: &[Pubkey]) -> ProgramTest {
+    let mut program_test = ProgramTest::default();
+    for feature_id in feature_ids {
+        program_test.deactivate_feature(*feature_id);
+    }
+
+    program_test
+}
+
+#[allow(deprecated)]
+fn redelegate(
+    stake_pubkey: &Pubkey,
+    authorized_pubkey: &Pubkey,
+    vote_pubkey: &Pubkey,
+    uninitialized_stake_pubkey: &Pubkey,
+) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new(*stake_pubkey, false),
+        AccountMeta::new(*uninitialized_stake_pubkey, false),
+        AccountMeta::new_readonly(*vote_pubkey, false),
+        AccountMeta::new_readonly(stake::config::ID, false),
+        AccountMeta::new_readonly(*authorized_pubkey, true),
+    ];
+    Instruction::new_with_bincode(
+        stake_program::id(),
+        &StakeInstruction::Redelegate,
+        account_metas,
+    )
+}
+
+struct Accounts {
+    validator: Keypair,
+    voter: Keypair,
+    withdrawer: Keypair,
+    vote_account: Keypair,
+    alt_validator: Keypair,
+    alt_vote_account: Keypair,
+}
+
+impl Accounts {
+    async fn initialize(&self, context: &mut ProgramTestContext) {
+        let slot = context.genesis_config().epoch_schedule.first_normal_slot + 1;
+        context.warp_to_slot(slot).unwrap();
+
+        create_vote(
+            context,
+            &self.validator,
+            &self.voter.pubkey(),
+            &self.withdrawer.pubkey(),
+            &self.vote_account,
+        )
+        .await;
+
+        create_vote(
+            context,
+            &self.alt_validator,
+            &self.voter.pubkey(),
+            &self.withdrawer.pubkey(),
+            &self.alt_vote_account,
+        )
+        .await;
+    }
+}
+
+impl Default for Accounts {
+    fn default() -> Self {
+        Self {
+            validator: Keypair::new(),
+            voter: Keypair::new(),
+            withdrawer: Keypair::new(),
+            vote_account: Keypair::new(),
+            alt_validator: Keypair::new(),
+            alt_vote_account: Keypair::new(),
+        }
+    }
+}
+
+async fn create_vote(
+    context: &mut ProgramTestContext,
+    validator: &Keypair,
+    voter: &Pubkey,
+    withdrawer: &Pubkey,
+    vote_account: &Keypair,
+) {
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let rent_voter = rent.minimum_balance(VoteStateV3::size_of());
+
+    let mut instructions = vec![system_instruction::create_account(
+        &context.payer.pubkey(),
+        &validator.pubkey(),
+        rent.minimum_balance(0),
+        0,
+        &system_program::id(),
+    )];
+    instructions.append(&mut vote_instruction::create_account_with_config(
+        &context.payer.pubkey(),
+        &vote_account.pubkey(),
+        &VoteInit {
+            node_pubkey: validator.pubkey(),
+            authorized_voter: *voter,
+            authorized_withdrawer: *withdrawer,
+            ..VoteInit::default()
+        },
+        rent_voter,
+        vote_instruction::CreateVoteAccountConfig {
+            space: VoteStateVersions::vote_state_size_of(true) as u64,
+            ..Default::default()
+        },
+    ));
+
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&context.payer.pubkey()),
+        &[validator, vote_account, &context.payer],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+async fn transfer(context: &mut ProgramTestContext, recipient: &Pubkey, amount: u64) {
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &context.payer.pubkey(),
+            recipient,
+            amount,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+async fn advance_epoch(context: &mut ProgramTestContext) {
+    refresh_blockhash(context).await;
+
+    let root_slot = context.banks_client.get_root_slot().await.unwrap();
+    let slots_per_epoch = context.genesis_config().epoch_schedule.slots_per_epoch;
+    context.warp_to_slot(root_slot + slots_per_epoch).unwrap();
+}
+
+async fn refresh_blockhash(context: &mut ProgramTestContext) {
+    context.last_blockhash = context
+        .banks_client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+}
+
+async fn get_account(banks_client: &mut BanksClient, pubkey: &Pubkey) -> SolanaAccount {
+    banks_client
+        .get_account(*pubkey)
+        .await
+        .expect("client error")
+        .expect("account not found")
+}
+
+async fn get_stake_account(
+    banks_client: &mut BanksClient,
+    pubkey: &Pubkey,
+) -> (Meta, Stake, StakeFlags, u64) {
+    let stake_account = get_account(banks_client, pubkey).await;
+    let lamports = stake_account.lamports;
+    match bincode::deserialize::<StakeStateV2>(&stake_account.data).unwrap() {
+        StakeStateV2::Stake(meta, stake, stake_flags) => (meta, stake, stake_flags, lamports),
+        _ => panic!("panic: not delegated"),
+    }
+}
+
+async fn get_stake_account_rent(banks_client: &mut BanksClient) -> u64 {
+    let rent = banks_client.get_rent().await.unwrap();
+    rent.minimum_balance(std::mem::size_of::<stake::state::StakeStateV2>())
+}
+
+async fn get_effective_stake(banks_client: &mut BanksClient, pubkey: &Pubkey) -> u64 {
+    let clock = banks_client.get_sysvar::<Clock>().await.unwrap();
+    let stake_history = banks_client.get_sysvar::<StakeHistory>().await.unwrap();
+    let stake_account = get_account(banks_client, pubkey).await;
+    match bincode::deserialize::<StakeStateV2>(&stake_account.data).unwrap() {
+        StakeStateV2::Stake(_, stake, _) => {
+            stake
+                .delegation
+                .stake_activating_and_deactivating(clock.epoch, &stake_history, Some(0))
+                .effective
+        }
+        _ => 0,
+    }
+}
+
+async fn get_minimum_delegation(context: &mut ProgramTestContext) -> u64 {
+    let transaction = Transaction::new_signed_with_payer(
+        &[stake::instruction::get_minimum_delegation()],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let mut data = context
+        .banks_client
+        .simulate_transaction(transaction)
+        .await
+        .unwrap()
+        .simulation_details
+        .unwrap()
+        .return_data
+        .unwrap()
+        .data;
+    data.resize(8, 0);
+
+    data.try_into().map(u64::from_le_bytes).unwrap()
+}
+
+async fn create_blank_stake_account_from_keypair(
+    context: &mut ProgramTestContext,
+    stake: &Keypair,
+) -> Pubkey {
+    let lamports = get_stake_account_rent(&mut context.banks_client).await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &stake.pubkey(),
+            lamports,
+            StakeStateV2::size_of() as u64,
+            &stake_program::id(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, stake],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    stake.pubkey()
+}
+
+async fn process_instruction<T: Signers + ?Sized>(
+    context: &mut ProgramTestContext,
+    instruction: &Instruction,
+    additional_signers: &T,
+) -> ProgramResult {
+    let mut transaction =
+        Transaction::new_with_payer(&[instruction.clone()], Some(&context.payer.pubkey()));
+
+    transaction.partial_sign(&[&context.payer], context.last_blockhash);
+    transaction.sign(additional_signers, context.last_blockhash);
+
+    match context.banks_client.process_transaction(transaction).await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            // banks client error -> transaction error -> instruction error -> program error
+            match e.unwrap() {
+                TransactionError::InstructionError(_, e) => Err(e.try_into().unwrap()),
+                TransactionError::InsufficientFundsForRent { .. } => {
+                    Err(ProgramError::InsufficientFunds)
+                }
+                _ => panic!("couldnt convert {e:?} to ProgramError"),
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum StakeLifecycle {
+    Uninitialized = 0,
+    Initialized,
+    Activating,
+    Active,
+    Deactivating,
+    Deactive,
+}
+impl StakeLifecycle {
+    // (stake, staker, withdrawer)
+    async fn new_stake_account(
+        self,
+        context: &mut ProgramTestContext,
+        vote_account: &Pubkey,
+        staked_amount: u64,
+    ) -> (Keypair, Keypair, Keypair) {
+        let stake_keypair = Keypair::new();
+        let staker_keypair = Keypair::new();
+        let withdrawer_keypair = Keypair::new();
+
+        let authorized = Authorized {
+            staker: staker_keypair.pubkey(),
+            withdrawer: withdrawer_keypair.pubkey(),
+        };
+
+        let stake = create_blank_stake_account_from_keypair(context, &stake_keypair).await;
+        if staked_amount > 0 {
+            transfer(context, &stake, staked_amount).await;
+        }
+
+        if self >= StakeLifecycle::Initialized {
+            let instruction = ixn::initialize(&stake, &authorized, &Lockup::default());
+            process_instruction(context, &instruction, NO_SIGNERS)
+                .await
+                .unwrap();
+        }
+
+        if self >= StakeLifecycle::Activating {
+            let instruction = ixn::delegate_stake(&stake, &staker_keypair.pubkey(), vote_account);
+            process_instruction(context, &instruction, &vec![&staker_keypair])
+                .await
+                .unwrap();
+        }
+
+        if self >= StakeLifecycle::Active {
+            advance_epoch(context).await;
+            assert_eq!(
+                get_effective_stake(&mut context.banks_client, &stake).await,
+                staked_amount,
+            );
+        }
+
+        if self >= StakeLifecycle::Deactivating {
+            let instruction = ixn::deactivate_stake(&stake, &staker_keypair.pubkey());
+            process_instruction(context, &instruction, &vec![&staker_keypair])
+                .await
+                .unwrap();
+        }
+
+        if self == StakeLifecycle::Deactive {
+            advance_epoch(context).await;
+            assert_eq!(
+                get_effective_stake(&mut context.banks_client, &stake).await,
+                0,
+            );
+        }
+
+        (stake_keypair, staker_keypair, withdrawer_keypair)
+    }
+}
+
+#[tokio::test]
+async fn test_redelegate() {
+    let mut context = program_test().start_with_context().await;
+    let accounts = Accounts::default();
+    accounts.initialize(&mut context).await;
+
+    let rent_exempt_reserve = get_stake_account_rent(&mut context.banks_client).await;
+    let minimum_delegation = get_minimum_delegation(&mut context).await;
+    let staked_amount = minimum_delegation * 2;
+
+    let (source_keypair, staker_keypair, withdrawer_keypair) = StakeLifecycle::Active
+        .new_stake_account(&mut context, &accounts.vote_account.pubkey(), staked_amount)
+        .await;
+    let source = source_keypair.pubkey();
+
+    // unstaked lamports stay behind in the source account
+    let source_excess = minimum_delegation;
+    transfer(&mut context, &source, source_excess).await;
+
+    let dest_keypair = Keypair::new();
+    let dest = create_blank_stake_account_from_keypair(&mut context, &dest_keypair).await;
+
+    let instruction = redelegate(
+        &source,
+        &staker_keypair.pubkey(),
+        &accounts.alt_vote_account.pubkey(),
+        &dest,
+    );
+
+    // the stake authority must sign
+    let mut unsigned_instruction = instruction.clone();
+    unsigned_instruction.accounts[4].is_signer = false;
+    let e = process_instruction(&mut context, &unsigned_instruction, NO_SIGNERS)
+        .await
+        .unwrap_err();
+    assert_eq!(e, ProgramError::MissingRequiredSignature);
+
+    // so must the staker, not the withdrawer
+    let mut wrong_authority_instruction = instruction.clone();
+    wrong_authority_instruction.accounts[4].pubkey = withdrawer_keypair.pubkey();
+    let e = process_instruction(
+        &mut context,
+        &wrong_authority_instruction,
+        &vec![&withdrawer_keypair],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(e, ProgramError::MissingRequiredSignature);
+
+    process_instruction(&mut context, &instruction, &vec![&staker_keypair])
+        .await
+        .unwrap();
+
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+
+    // source is deactivating and only holds its rent exempt reserve and excess lamports
+    let (source_meta, source_stake, source_flags, source_lamports) =
+        get_stake_account(&mut context.banks_client, &source).await;
+    assert_eq!(source_lamports, rent_exempt_reserve + source_excess);
+    assert_eq!(source_stake.delegation.stake, staked_amount);
+    assert_eq!(source_stake.delegation.deactivation_epoch, clock.epoch);
+    assert_eq!(source_flags, StakeFlags::empty());
+
+    // destination inherits the authorities and activates towards the new vote account
+    let (dest_meta, dest_stake, dest_flags, dest_lamports) =
+        get_stake_account(&mut context.banks_client, &dest).await;
+    assert_eq!(dest_lamports, rent_exempt_reserve + staked_amount);
+    assert_eq!(dest_meta.authorized, source_meta.authorized);
+    assert_eq!(dest_meta.lockup, source_meta.lockup);
+    assert_eq!(dest_meta.rent_exempt_reserve, rent_exempt_reserve);
+    assert_eq!(
+        dest_stake.delegation.voter_pubkey,
+        accounts.alt_vote_account.pubkey()
+    );
+    assert_eq!(dest_stake.delegation.stake, staked_amount);
+    assert_eq!(dest_stake.delegation.activation_epoch, clock.epoch);
+    assert_eq!(dest_stake.delegation.deactivation_epoch, u64::MAX);
+    #[allow(deprecated)]
+    let must_fully_activate = StakeFlags::MUST_FULLY_ACTIVATE_BEFORE_DEACTIVATION_IS_PERMITTED;
+    assert_eq!(dest_flags, must_fully_activate);
+
+    // the stake is still earning on the old vote account for the rest of this epoch
+    assert_eq!(
+        get_effective_stake(&mut context.banks_client, &source).await,
+        staked_amount
+    );
+    assert_eq!(
+        get_effective_stake(&mut context.banks_client, &dest).await,
+        0
+    );
+
+    // the activating stake can be neither deactivated nor redelegated again
+    let instruction = ixn::deactivate_stake(&dest, &staker_keypair.pubkey());
+    let e = process_instruction(&mut context, &instruction, &vec![&staker_keypair])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        e,
+        StakeError::RedelegatedStakeMustFullyActivateBeforeDeactivationIsPermitted.into()
+    );
+
+    let other_dest_keypair = Keypair::new();
+    let other_dest =
+        create_blank_stake_account_from_keypair(&mut context, &other_dest_keypair).await;
+    let instruction = redelegate(
+        &dest,
+        &staker_keypair.pubkey(),
+        &accounts.vote_account.pubkey(),
+        &other_dest,
+    );
+    let e = process_instruction(&mut context, &instruction, &vec![&staker_keypair])
+        .await
+        .unwrap_err();
+    assert_eq!(e, StakeError::RedelegateTransientOrInactiveStake.into());
+
+    // one epoch later the stake has fully moved over
+    advance_epoch(&mut context).await;
+    assert_eq!(
+        get_effective_stake(&mut context.banks_client, &source).await,
+        0
+    );
+    assert_eq!(
+        get_effective_stake(&mut context.banks_client, &dest).await,
+        staked_amount
+    );
+
+    // the source can be fully withdrawn
+    let recipient = Pubkey::new_unique();
+    let instruction = ixn::withdraw(
+        &source,
+        &withdrawer_keypair.pubkey(),
+        &recipient,
+        rent_exempt_reserve + source_excess,
+        None,
+    );
+    process_instruction(&mut context, &instruction, &vec![&withdrawer_keypair])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_account(&mut context.banks_client, &recipient)
+            .await
+            .lamports,
+        rent_exempt_reserve + source_excess
+    );
+
+    // and the destination behaves like any other active stake
+    let instruction = ixn::deactivate_stake(&dest, &staker_keypair.pubkey());
+    process_instruction(&mut context, &instruction, &vec![&staker_keypair])
+        .await
+        .unwrap();
+    let (_, dest_stake, dest_flags, _) = get_stake_account(&mut context.banks_client, &dest).await;
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    assert_eq!(dest_stake.delegation.deactivation_epoch, clock.epoch);
+    assert_eq!(dest_flags, StakeFlags::empty());
+}
+
+#[tokio::test]
+async fn test_redelegate_feature_disabled() {
+    let mut context = program_test_without_features(&[enable_stake_redelegate_instruction::id()])
+        .start_with_context()
+        .await;
+    let accounts = Accounts::default();
+    accounts.initialize(&mut context).await;
+
+    let minimum_delegation = get_minimum_delegation(&mut context).await;
+    let (source_keypair, staker_keypair, _) = StakeLifecycle::Active
+        .new_stake_account(
+            &mut context,
+            &accounts.vote_account.pubkey(),
+            minimum_delegation,
+        )
+        .await;
+    let dest_keypair = Keypair::new();
+    let dest = create_blank_stake_account_from_keypair(&mut context, &dest_keypair).await;
+
+    let instruction = redelegate(
+        &source_keypair.pubkey(),
+        &staker_keypair.pubkey(),
+        &accounts.alt_vote_account.pubkey(),
+        &dest,
+    );
+    let e = process_instruction(&mut context, &instruction, &vec![&staker_keypair])
+        .await
+        .unwrap_err();
+    assert_eq!(e, ProgramError::InvalidInstructionData);
+}
+
+#[tokio::test]
+async fn test_deactivate_ignores_redelegate_flag_feature_disabled() {
+    let mut context = program_test_without_features(&[enable_stake_redelegate_instruction::id()])
+        .start_with_context()
+        .await;
+    let accounts = Accounts::default();
+    accounts.initialize(&mut context).await;
+
+    let minimum_delegation = get_minimum_delegation(&mut context).await;
+    let (stake_keypair, staker_keypair, _) = StakeLifecycle::Activating
+        .new_stake_account(
+            &mut context,
+            &accounts.vote_account.pubkey(),
+            minimum_delegation,
+        )
+        .await;
+    let stake = stake_keypair.pubkey();
+
+    // without the feature the flag is not enforced, nor cleared
+    #[allow(deprecated)]
+    let must_fully_activate = StakeFlags::MUST_FULLY_ACTIVATE_BEFORE_DEACTIVATION_IS_PERMITTED;
+    let (meta, stake_state, _, _) = get_stake_account(&mut context.banks_client, &stake).await;
+    let mut stake_account = get_account(&mut context.banks_client, &stake).await;
+    stake_account.data =
+        bincode::serialize(&StakeStateV2::Stake(meta, stake_state, must_fully_activate)).unwrap();
+    context.set_account(&stake, &stake_account.into());
+
+    let instruction = ixn::deactivate_stake(&stake, &staker_keypair.pubkey());
+    process_instruction(&mut context, &instruction, &vec![&staker_keypair])
+        .await
+        .unwrap();
+    let (_, stake_state, stake_flags, _) =
+        get_stake_account(&mut context.banks_client, &stake).await;
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    assert_eq!(stake_state.delegation.deactivation_epoch, clock.epoch);
+    assert_eq!(stake_flags, must_fully_activate);
+}
+
+#[test_matrix(
+    [StakeLifecycle::Initialized, StakeLifecycle::Activating,
+     StakeLifecycle::Deactivating, StakeLifecycle::Deactive]
+)]
+#[tokio::test]
+async fn test_redelegate_inactive_source_fail(source_type: StakeLifecycle) {
+    let mut context = program_test().start_with_context().await;
+    let accounts = Accounts::default();
+    accounts.initialize(&mut context).await;
+
+    let minimum_delegation = get_minimum_delegation(&mut context).await;
+    let (source_keypair, staker_keypair, _) = source_type
+        .new_stake_account(
+            &mut context,
+            &accounts.vote_account.pubkey(),
+            minimum_delegation,
+        )
+        .await;
+    let dest_keypair = Keypair::new();
+    let dest = create_blank_stake_account_from_keypair(&mut context, &dest_keypair).await;
+
+    let instruction = redelegate(
+        &source_keypair.pubkey(),
+        &staker_keypair.pubkey(),
+        &accounts.alt_vote_account.pubkey(),
+        &dest,
+    );
+    let e = process_instruction(&mut context, &instruction, &vec![&staker_keypair])
+        .await
+        .unwrap_err();
+    if source_type == StakeLifecycle::Initialized {
+        assert_eq!(e, ProgramError::InvalidAccountData);
+    } else {
+        assert_eq!(e, StakeError::RedelegateTransientOrInactiveStake.into());
+    }
+}
+
+#[tokio::test]
+async fn test_redelegate_same_vote_account_fail() {
+    let mut context = program_test().start_with_context().await;
+    let accounts = Accounts::default();
+    accounts.initialize(&mut context).await;
+
+    let minimum_delegation = get_minimum_delegation(&mut context).await;
+    let (source_keypair, staker_keypair, _) = StakeLifecycle::Active
+        .new_stake_account(
+            &mut context,
+            &accounts.vote_account.pubkey(),
+            minimum_delegation,
+        )
+        .await;
+    let dest_keypair = Keypair::new();
+    let dest = create_blank_stake_account_from_keypair(&mut context, &dest_keypair).await;
+
+    let instruction = redelegate(
+        &source_keypair.pubkey(),
+        &staker_keypair.pubkey(),
+        &accounts.vote_account.pubkey(),
+        &dest,
+    );
+    let e = process_instruction(&mut context, &instruction, &vec![&staker_keypair])
+        .await
+        .unwrap_err();
+    assert_eq!(e, StakeError::RedelegateToSameVoteAccount.into());
+}
+
+#[test_case(StakeLifecycle::Initialized; "initialized_destination")]
+#[test_case(StakeLifecycle::Active; "active_destination")]
+#[tokio::test]
+async fn test_redelegate_initialized_destination_fail(dest_type: StakeLifecycle) {
+    let mut context = program_test().start_with_context().await;
+    let accounts = Accounts::default();
+    accounts.initialize(&mut context).await;
+
+    let minimum_delegation = get_minimum_delegation(&mut context).await;
+    let (source_keypair, staker_keypair, _) = StakeLifecycle::Active
+        .new_stake_account(
+            &mut context,
+            &accounts.vote_account.pubkey(),
+            minimum_delegation,
+        )
+        .await;
+    let (dest_keypair, _, _) = dest_type
+        .new_stake_account(
+            &mut context,
+            &accounts.vote_account.pubkey(),
+            minimum_delegation,
+        )
+        .await;
+
+    let instruction = redelegate(
+        &source_keypair.pubkey(),
+        &staker_keypair.pubkey(),
+        &accounts.alt_vote_account.pubkey(),
+        &dest_keypair.pubkey(),
+    );
+    let e = process_instruction(&mut context, &instruction, &vec![&staker_keypair])
+        .await
+        .unwrap_err();
+    assert_eq!(e, ProgramError::AccountAlreadyInitialized);
+}
+
+#[tokio::test]
+async fn test_redelegate_invalid_accounts_fail() {
+    let mut context = program_test().start_with_context().await;
+    let accounts = Accounts::default();
+    accounts.initialize(&mut context).await;
+
+    let minimum_delegation = get_minimum_delegation(&mut context).await;
+    let (source_keypair, staker_keypair, _) = StakeLifecycle::Active
+        .new_stake_account(
+            &mut context,
+            &accounts.vote_account.pubkey(),
+            minimum_delegation,
+        )
+        .await;
+    let source = source_keypair.pubkey();
+
+    // destination not owned by the stake program
+    let system_account = Pubkey::new_unique();
+    transfer(
+        &mut context,
+        &system_account,
+        get_stake_account_rent(&mut context.banks_client).await,
+    )
+    .await;
+    let instruction = redelegate(
+        &source,
+        &staker_keypair.pubkey(),
+        &accounts.alt_vote_account.pubkey(),
+        &system_account,
+    );
+    let e = process_instruction(&mut context, &instruction, &vec![&staker_keypair])
+        .await
+        .unwrap_err();
+    assert_eq!(e, ProgramError::IncorrectProgramId);
+
+    // vote account not owned by the vote program
+    let dest_keypair = Keypair::new();
+    let dest = create_blank_stake_account_from_keypair(&mut context, &dest_keypair).await;
+    let instruction = redelegate(
+        &source,
+        &staker_keypair.pubkey(),
+        &accounts.alt_validator.pubkey(),
+        &dest,
+    );
+    let e = process_instruction(&mut context, &instruction, &vec![&staker_keypair])
+        .await
+        .unwrap_err();
+    assert_eq!(e, ProgramError::IncorrectProgramId);
+
+    // and succeeds once the accounts are valid
+    let instruction = redelegate(
+        &source,
+        &staker_keypair.pubkey(),
+        &accounts.alt_vote_account.pubkey(),
+        &dest,
+    );
+    process_instruction(&mut context, &instruction, &vec![&staker_keypair])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_stake_account(&mut context.banks_client, &dest)
+            .await
+            .1
+            .delegation
+            .stake,
+        minimum_delegation
+    );
+}
@@ -1,8 +1,8 @@
 use {
     crate::stake_state::{
         authorize, authorize_with_seed, deactivate, deactivate_delinquent, delegate, initialize,
-        merge, move_lamports, move_stake, new_warmup_cooldown_rate_epoch, set_lockup, split,
-        withdraw,
+        merge, move_lamports, move_stake, new_warmup_cooldown_rate_epoch, redelegate, set_lockup,
+        split, withdraw,
     },
     log::*,
     solana_bincode::limited_deserialize,
@@ -216,7 +216,7 @@ declare_process_instruction!(Entrypoint, DEFAULT_COMPUTE_UNITS, |invoke_context|
             let mut me = get_stake_account()?;
             let clock =
                 get_sysvar_with_account_check::clock(invoke_context, instruction_context, 1)?;
-            deactivate(&mut me, &clock, &signers)
+            deactivate(invoke_context, &mut me, &clock, &signers)
         }
         StakeInstruction::SetLockup(lockup) => {
             let mut me = get_stake_account()?;
@@ -333,8 +333,24 @@ declare_process_instruction!(Entrypoint, DEFAULT_COMPUTE_UNITS, |invoke_context|
         }
         #[allow(deprecated)]
         StakeInstruction::Redelegate => {
-            let _ = get_stake_account()?;
-            Err(InstructionError::InvalidInstructionData)
+            let mut me = get_stake_account()?;
+            if invoke_context
+                .get_feature_set()
+                .enable_stake_redelegate_instruction
+            {
+                instruction_context.check_number_of_instruction_accounts(3)?;
+                redelegate(
+                    invoke_context,
+                    transaction_context,
+                    instruction_context,
+                    &mut me,
+                    1,
+                    2,
+                    &signers,
+                )
+            } else {
+                Err(InstructionError::InvalidInstructionData)
+            }
         }
         StakeInstruction::MoveStake(lamports) => {
             instruction_context.check_number_of_instruction_accounts(3)?;
@@ -0,0 +1,1059 @@
+#![forbid(unsafe_code)]
+
+use {
+    solana_bincode::limited_deserialize,
+    solana_hash::Hash,
+    solana_instruction::error::InstructionError,
+    solana_loader_v4_interface::{
+        instruction as loader_v4_instruction,
+        state::{LoaderV4State, LoaderV4Status},
+    },
+    solana_loader_v4_program::{consume_elf_hash_cost, get_elf_hash, get_state},
+    solana_log_collector::ic_msg,
+    solana_program_runtime::{declare_process_instruction, invoke_context::InvokeContext},
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::{loader_v4, system_program},
+    solana_transaction_context::{BorrowedAccount, IndexOfAccount, InstructionContext},
+    solana_upgrade_timelock_interface::{
+        get_timelock_address, id,
+        instruction::UpgradeTimelockInstruction,
+        state::{PendingRelease, PendingUpgrade, UpgradeTimelock},
+    },
+};
+
+pub const DEFAULT_COMPUTE_UNITS: u64 = 2_000;
+
+fn get_key_of_instruction_account(
+    invoke_context: &InvokeContext,
+    instruction_context: &InstructionContext,
+    index_in_instruction: IndexOfAccount,
+) -> Result<Pubkey, InstructionError> {
+    let transaction_context = &invoke_context.transaction_context;
+    instruction_context
+        .get_index_of_instruction_account_in_transaction(index_in_instruction)
+        .and_then(|index| transaction_context.get_key_of_account_at_index(index))
+        .copied()
+}
+
+fn get_timelock(
+    invoke_context: &InvokeContext,
+    timelock_account: &BorrowedAccount,
+) -> Result<UpgradeTimelock, InstructionError> {
+    if *timelock_account.get_owner() != id() {
+        ic_msg!(
+            invoke_context,
+            "Timelock account not owned by the timelock program"
+        );
+        return Err(InstructionError::InvalidAccountOwner);
+    }
+    UpgradeTimelock::deserialize(timelock_account.get_data())
+}
+
+fn check_authority(
+    invoke_context: &InvokeContext,
+    instruction_context: &InstructionContext,
+    timelock: &UpgradeTimelock,
+    index_in_instruction: IndexOfAccount,
+) -> Result<(), InstructionError> {
+    let authority_address =
+        get_key_of_instruction_account(invoke_context, instruction_context, index_in_instruction)?;
+    if authority_address != timelock.authority {
+        ic_msg!(invoke_context, "Incorrect timelock authority provided");
+        return Err(InstructionError::IncorrectAuthority);
+    }
+    if !instruction_context.is_instruction_account_signer(index_in_instruction)? {
+        ic_msg!(invoke_context, "Timelock authority did not sign");
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+fn get_buffer_programdata<'a>(
+    invoke_context: &InvokeContext,
+    buffer: &'a BorrowedAccount,
+) -> Result<&'a [u8], InstructionError> {
+    if !loader_v4::check_id(buffer.get_owner()) {
+        ic_msg!(invoke_context, "Buffer not owned by loader-v4");
+        return Err(InstructionError::InvalidAccountOwner);
+    }
+    match buffer
+        .get_data()
+        .get(LoaderV4State::program_data_offset()..)
+    {
+        Some(programdata) if !programdata.is_empty() => Ok(programdata),
+        _ => {
+            ic_msg!(invoke_context, "Buffer is empty");
+            Err(InstructionError::AccountDataTooSmall)
+        }
+    }
+}
+
+fn process_initialize(
+    invoke_context: &mut InvokeContext,
+    authority: Pubkey,
+    delay_slots: u64,
+) -> Result<(), InstructionError> {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    instruction_context.check_number_of_instruction_accounts(6)?;
+    if delay_slots == 0 {
+        ic_msg!(invoke_context, "Delay must be at least one slot");
+        return Err(InstructionError::InvalidArgument);
+    }
+    let timelock_address = get_key_of_instruction_account(invoke_context, instruction_context, 0)?;
+    let current_authority_address =
+        get_key_of_instruction_account(invoke_context, instruction_context, 2)?;
+    let payer_address = get_key_of_instruction_account(invoke_context, instruction_context, 3)?;
+    if !instruction_context.is_instruction_account_signer(2)? {
+        ic_msg!(invoke_context, "Program authority did not sign");
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    let program = instruction_context.try_borrow_instruction_account(transaction_context, 1)?;
+    let program_address = *program.get_key();
+    if !loader_v4::check_id(program.get_owner()) {
+        ic_msg!(invoke_context, "Program not owned by loader-v4");
+        return Err(InstructionError::InvalidAccountOwner);
+    }
+    if matches!(
+        get_state(program.get_data())?.status,
+        LoaderV4Status::Finalized
+    ) {
+        ic_msg!(invoke_context, "Program is finalized");
+        return Err(InstructionError::Immutable);
+    }
+    drop(program);
+    if timelock_address != get_timelock_address(&program_address) {
+        ic_msg!(
+            invoke_context,
+            "Timelock address is not derived from the program"
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+
+    invoke_context.create_program_derived_account(
+        &timelock_address,
+        Some(&payer_address),
+        UpgradeTimelock::size_of(),
+        &id(),
+    )?;
+
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let mut timelock_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    UpgradeTimelock {
+        program: program_address,
+        authority,
+        delay_slots,
+        pending_upgrade: None,
+        pending_release: None,
+    }
+    .serialize(timelock_account.get_data_mut()?)?;
+    drop(timelock_account);
+
+    invoke_context.native_invoke(
+        loader_v4_instruction::transfer_authority(
+            &program_address,
+            &current_authority_address,
+            &timelock_address,
+        )
+        .into(),
+        &[timelock_address],
+    )
+}
+
+fn process_stage_upgrade(
+    invoke_context: &mut InvokeContext,
+    buffer_hash: Hash,
+) -> Result<(), InstructionError> {
+    let clock = invoke_context.get_sysvar_cache().get_clock()?;
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    instruction_context.check_number_of_instruction_accounts(3)?;
+    let mut timelock_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    let mut timelock = get_timelock(invoke_context, &timelock_account)?;
+    check_authority(invoke_context, instruction_context, &timelock, 2)?;
+    let buffer = instruction_context.try_borrow_instruction_account(transaction_context, 1)?;
+    if *buffer.get_key() == timelock.program {
+        ic_msg!(invoke_context, "Buffer must not be the program itself");
+        return Err(InstructionError::InvalidArgument);
+    }
+    let programdata = get_buffer_programdata(invoke_context, &buffer)?;
+    consume_elf_hash_cost(invoke_context, programdata)?;
+    if get_elf_hash(programdata) != buffer_hash {
+        ic_msg!(invoke_context, "Buffer hash does not match");
+        return Err(InstructionError::InvalidArgument);
+    }
+    timelock.pending_upgrade = Some(PendingUpgrade {
+        buffer: *buffer.get_key(),
+        buffer_hash,
+        staged_slot: clock.slot,
+        earliest_slot: clock.slot.saturating_add(timelock.delay_slots),
+    });
+    timelock.serialize(timelock_account.get_data_mut()?)
+}
+
+fn process_cancel_upgrade(invoke_context: &mut InvokeContext) -> Result<(), InstructionError> {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    instruction_context.check_number_of_instruction_accounts(2)?;
+    let mut timelock_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    let mut timelock = get_timelock(invoke_context, &timelock_account)?;
+    check_authority(invoke_context, instruction_context, &timelock, 1)?;
+    if timelock.pending_upgrade.take().is_none() {
+        ic_msg!(invoke_context, "No upgrade is pending");
+        return Err(InstructionError::InvalidArgument);
+    }
+    timelock.serialize(timelock_account.get_data_mut()?)
+}
+
+fn process_apply_upgrade(invoke_context: &mut InvokeContext) -> Result<(), InstructionError> {
+    let clock = invoke_context.get_sysvar_cache().get_clock()?;
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    instruction_context.check_number_of_instruction_accounts(4)?;
+    let mut timelock_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    let timelock_address = *timelock_account.get_key();
+    let mut timelock = get_timelock(invoke_context, &timelock_account)?;
+    let program_address = get_key_of_instruction_account(invoke_context, instruction_context, 1)?;
+    if program_address != timelock.program {
+        ic_msg!(invoke_context, "Program does not match the timelock");
+        return Err(InstructionError::InvalidArgument);
+    }
+    let Some(pending_upgrade) = timelock.pending_upgrade.take() else {
+        ic_msg!(invoke_context, "No upgrade is pending");
+        return Err(InstructionError::InvalidArgument);
+    };
+    if clock.slot < pending_upgrade.earliest_slot {
+        ic_msg!(
+            invoke_context,
+            "Upgrade can not be applied before slot {}",
+            pending_upgrade.earliest_slot
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    let buffer = instruction_context.try_borrow_instruction_account(transaction_context, 2)?;
+    if *buffer.get_key() != pending_upgrade.buffer {
+        ic_msg!(invoke_context, "Buffer does not match the pending upgrade");
+        return Err(InstructionError::InvalidArgument);
+    }
+    let programdata = get_buffer_programdata(invoke_context, &buffer)?;
+    consume_elf_hash_cost(invoke_context, programdata)?;
+    if get_elf_hash(programdata) != pending_upgrade.buffer_hash {
+        ic_msg!(
+            invoke_context,
+            "Buffer was modified after the upgrade was staged"
+        );
+        return Err(InstructionError::InvalidAccountData);
+    }
+    let program_length = programdata.len() as u32;
+    drop(buffer);
+    timelock.serialize(timelock_account.get_data_mut()?)?;
+    drop(timelock_account);
+    let program = instruction_context.try_borrow_instruction_account(transaction_context, 1)?;
+    let is_deployed = matches!(
+        get_state(program.get_data())?.status,
+        LoaderV4Status::Deployed
+    );
+    drop(program);
+
+    let signers = [timelock_address];
+    if is_deployed {
+        invoke_context.native_invoke(
+            loader_v4_instruction::retract(&program_address, &timelock_address).into(),
+            &signers,
+        )?;
+    }
+    // Passing the program as recipient keeps surplus lamports in the program account
+    invoke_context.native_invoke(
+        loader_v4_instruction::set_program_length(
+            &program_address,
+            &timelock_address,
+            program_length,
+            &program_address,
+        )
+        .into(),
+        &signers,
+    )?;
+    invoke_context.native_invoke(
+        loader_v4_instruction::copy(
+            &program_address,
+            &timelock_address,
+            &pending_upgrade.buffer,
+            0,
+            0,
+            program_length,
+        )
+        .into(),
+        &signers,
+    )?;
+    invoke_context.native_invoke(
+        loader_v4_instruction::deploy(&program_address, &timelock_address).into(),
+        &signers,
+    )
+}
+
+fn process_stage_release(
+    invoke_context: &mut InvokeContext,
+    new_authority: Pubkey,
+) -> Result<(), InstructionError> {
+    let clock = invoke_context.get_sysvar_cache().get_clock()?;
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    instruction_context.check_number_of_instruction_accounts(2)?;
+    let mut timelock_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    let mut timelock = get_timelock(invoke_context, &timelock_account)?;
+    check_authority(invoke_context, instruction_context, &timelock, 1)?;
+    if new_authority == *timelock_account.get_key() {
+        ic_msg!(
+            invoke_context,
+            "New authority must not be the timelock itself"
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    timelock.pending_release = Some(PendingRelease {
+        new_authority,
+        staged_slot: clock.slot,
+        earliest_slot: clock.slot.saturating_add(timelock.delay_slots),
+    });
+    timelock.serialize(timelock_account.get_data_mut()?)
+}
+
+fn process_cancel_release(invoke_context: &mut InvokeContext) -> Result<(), InstructionError> {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    instruction_context.check_number_of_instruction_accounts(2)?;
+    let mut timelock_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    let mut timelock = get_timelock(invoke_context, &timelock_account)?;
+    check_authority(invoke_context, instruction_context, &timelock, 1)?;
+    if timelock.pending_release.take().is_none() {
+        ic_msg!(invoke_context, "No release is pending");
+        return Err(InstructionError::InvalidArgument);
+    }
+    timelock.serialize(timelock_account.get_data_mut()?)
+}
+
+fn process_release_authority(invoke_context: &mut InvokeContext) -> Result<(), InstructionError> {
+    let clock = invoke_context.get_sysvar_cache().get_clock()?;
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    instruction_context.check_number_of_instruction_accounts(4)?;
+    let timelock_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    let timelock_address = *timelock_account.get_key();
+    let timelock = get_timelock(invoke_context, &timelock_account)?;
+    drop(timelock_account);
+    let program_address = get_key_of_instruction_account(invoke_context, instruction_context, 1)?;
+    if program_address != timelock.program {
+        ic_msg!(invoke_context, "Program does not match the timelock");
+        return Err(InstructionError::InvalidArgument);
+    }
+    let Some(pending_release) = timelock.pending_release else {
+        ic_msg!(invoke_context, "No release is pending");
+        return Err(InstructionError::InvalidArgument);
+    };
+    if clock.slot < pending_release.earliest_slot {
+        ic_msg!(
+            invoke_context,
+            "Release can not be applied before slot {}",
+            pending_release.earliest_slot
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    let new_authority_address =
+        get_key_of_instruction_account(invoke_context, instruction_context, 2)?;
+    if new_authority_address != pending_release.new_authority {
+        ic_msg!(
+            invoke_context,
+            "New authority does not match the pending release"
+        );
+        return Err(InstructionError::IncorrectAuthority);
+    }
+
+    // The loader requires the new authority to sign, which it did for this instruction
+    invoke_context.native_invoke(
+        loader_v4_instruction::transfer_authority(
+            &program_address,
+            &timelock_address,
+            &new_authority_address,
+        )
+        .into(),
+        &[timelock_address],
+    )?;
+
+    // Close the timelock account, so that the program can be put under a timelock again
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let mut timelock_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    let lamports = timelock_account.get_lamports();
+    timelock_account.set_lamports(0)?;
+    timelock_account.set_data_length(0)?;
+    timelock_account.set_owner(&system_program::id().to_bytes())?;
+    drop(timelock_account);
+    instruction_context
+        .try_borrow_instruction_account(transaction_context, 2)?
+        .checked_add_lamports(lamports)
+}
+
+declare_process_instruction!(Entrypoint, DEFAULT_COMPUTE_UNITS, |invoke_context| {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let instruction_data = instruction_context.get_instruction_data();
+    match limited_deserialize(instruction_data, solana_packet::PACKET_DATA_SIZE as u64)? {
+        UpgradeTimelockInstruction::Initialize {
+            authority,
+            delay_slots,
+        } => process_initialize(invoke_context, authority, delay_slots),
+        UpgradeTimelockInstruction::StageUpgrade { buffer_hash } => {
+            process_stage_upgrade(invoke_context, buffer_hash)
+        }
+        UpgradeTimelockInstruction::CancelUpgrade => process_cancel_upgrade(invoke_context),
+        UpgradeTimelockInstruction::ApplyUpgrade => process_apply_upgrade(invoke_context),
+        UpgradeTimelockInstruction::StageRelease { new_authority } => {
+            process_stage_release(invoke_context, new_authority)
+        }
+        UpgradeTimelockInstruction::CancelRelease => process_cancel_release(invoke_context),
+        UpgradeTimelockInstruction::ReleaseAuthority => process_release_authority(invoke_context),
+    }
+});
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_account::{
+            create_account_shared_data_for_test, AccountSharedData, ReadableAccount,
+            WritableAccount,
+        },
+        solana_clock::Slot,
+        solana_instruction::Instruction,
+        solana_program_runtime::{
+            invoke_context::mock_process_instruction, loaded_programs::ProgramCacheEntry,
+        },
+        solana_sdk_ids::{native_loader, system_program},
+        solana_sysvar::{
+            clock::{self, Clock},
+            rent::{self, Rent},
+        },
+        solana_upgrade_timelock_interface::instruction,
+        std::{fs::File, io::Read, sync::Arc},
+    };
+
+    fn process_instruction(
+        instruction: &Instruction,
+        transaction_accounts: Vec<(Pubkey, AccountSharedData)>,
+        expected_result: Result<(), InstructionError>,
+    ) -> Vec<AccountSharedData> {
+        mock_process_instruction(
+            &id(),
+            Vec::new(),
+            &instruction.data,
+            transaction_accounts,
+            instruction.accounts.clone(),
+            expected_result,
+            Entrypoint::vm,
+            |invoke_context| {
+                invoke_context.program_cache_for_tx_batch.replenish(
+                    system_program::id(),
+                    Arc::new(ProgramCacheEntry::new_builtin(
+                        0,
+                        0,
+                        solana_system_program::system_processor::Entrypoint::vm,
+                    )),
+                );
+                invoke_context.program_cache_for_tx_batch.replenish(
+                    loader_v4::id(),
+                    Arc::new(ProgramCacheEntry::new_builtin(
+                        0,
+                        0,
+                        solana_loader_v4_program::Entrypoint::vm,
+                    )),
+                );
+            },
+            |_invoke_context| {},
+        )
+    }
+
+    fn load_elf(name: &str) -> Vec<u8> {
+        let mut file =
+            File::open(format!("../bpf_loader/test_elfs/out/{name}.so")).expect("file open failed");
+        let mut elf = Vec::new();
+        file.read_to_end(&mut elf).unwrap();
+        elf
+    }
+
+    fn loader_v4_account(
+        authority_address: &Pubkey,
+        status: LoaderV4Status,
+        elf: &[u8],
+        extra_lamports: u64,
+    ) -> AccountSharedData {
+        let account_size = LoaderV4State::program_data_offset().saturating_add(elf.len());
+        let mut account = AccountSharedData::new(
+            Rent::default().minimum_balance(account_size) + extra_lamports,
+            account_size,
+            &loader_v4::id(),
+        );
+        let data = account.data_as_mut_slice();
+        data[8..40].copy_from_slice(authority_address.as_ref());
+        data[40..48].copy_from_slice(&(status as u64).to_le_bytes());
+        data[LoaderV4State::program_data_offset()..].copy_from_slice(elf);
+        account
+    }
+
+    fn timelock_account(timelock: &UpgradeTimelock) -> AccountSharedData {
+        let mut account = AccountSharedData::new(
+            Rent::default().minimum_balance(UpgradeTimelock::size_of()),
+            UpgradeTimelock::size_of(),
+            &id(),
+        );
+        timelock.serialize(account.data_as_mut_slice()).unwrap();
+        account
+    }
+
+    fn clock_account(slot: Slot) -> AccountSharedData {
+        create_account_shared_data_for_test(&Clock {
+            slot,
+            ..Clock::default()
+        })
+    }
+
+    #[test]
+    fn test_initialize() {
+        let program_address = Pubkey::new_unique();
+        let timelock_address = get_timelock_address(&program_address);
+        let current_authority_address = Pubkey::new_unique();
+        let timelock_authority_address = Pubkey::new_unique();
+        let payer_address = Pubkey::new_unique();
+        let transaction_accounts = vec![
+            (timelock_address, AccountSharedData::default()),
+            (
+                program_address,
+                loader_v4_account(
+                    &current_authority_address,
+                    LoaderV4Status::Deployed,
+                    &load_elf("sbpfv3_return_ok"),
+                    0,
+                ),
+            ),
+            (
+                current_authority_address,
+                AccountSharedData::new(0, 0, &system_program::id()),
+            ),
+            (
+                payer_address,
+                AccountSharedData::new(1_000_000_000, 0, &system_program::id()),
+            ),
+            (
+                system_program::id(),
+                AccountSharedData::new(0, 0, &native_loader::id()),
+            ),
+            (
+                loader_v4::id(),
+                AccountSharedData::new(0, 0, &native_loader::id()),
+            ),
+            (clock::id(), clock_account(1000)),
+            (
+                rent::id(),
+                create_account_shared_data_for_test(&Rent::default()),
+            ),
+        ];
+
+        // Error: Zero delay
+        process_instruction(
+            &instruction::initialize(
+                &program_address,
+                &current_authority_address,
+                &timelock_authority_address,
+                0,
+                &payer_address,
+            ),
+            transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+
+        // Error: Timelock address is not derived from the program
+        let other_address = Pubkey::new_unique();
+        let mut initialize_instruction = instruction::initialize(
+            &program_address,
+            &current_authority_address,
+            &timelock_authority_address,
+            100,
+            &payer_address,
+        );
+        initialize_instruction.accounts[0].pubkey = other_address;
+        let mut other_transaction_accounts = transaction_accounts.clone();
+        other_transaction_accounts[0].0 = other_address;
+        process_instruction(
+            &initialize_instruction,
+            other_transaction_accounts,
+            Err(InstructionError::InvalidArgument),
+        );
+
+        // Error: Current authority did not sign
+        let mut initialize_instruction = instruction::initialize(
+            &program_address,
+            &current_authority_address,
+            &timelock_authority_address,
+            100,
+            &payer_address,
+        );
+        initialize_instruction.accounts[2].is_signer = false;
+        process_instruction(
+            &initialize_instruction,
+            transaction_accounts.clone(),
+            Err(InstructionError::MissingRequiredSignature),
+        );
+
+        // Error: Timelock account is already in use
+        let mut in_use_transaction_accounts = transaction_accounts.clone();
+        in_use_transaction_accounts[0].1 = timelock_account(&UpgradeTimelock {
+            program: program_address,
+            authority: timelock_authority_address,
+            delay_slots: 100,
+            pending_upgrade: None,
+            pending_release: None,
+        });
+        process_instruction(
+            &instruction::initialize(
+                &program_address,
+                &current_authority_address,
+                &timelock_authority_address,
+                100,
+                &payer_address,
+            ),
+            in_use_transaction_accounts,
+            Err(InstructionError::AccountAlreadyInitialized),
+        );
+
+        // A pre-funded timelock account only needs the rent shortfall
+        let timelock_rent = Rent::default().minimum_balance(UpgradeTimelock::size_of());
+        let mut prefunded_transaction_accounts = transaction_accounts.clone();
+        prefunded_transaction_accounts[0].1 =
+            AccountSharedData::new(timelock_rent - 1, 0, &system_program::id());
+        let accounts = process_instruction(
+            &instruction::initialize(
+                &program_address,
+                &current_authority_address,
+                &timelock_authority_address,
+                100,
+                &payer_address,
+            ),
+            prefunded_transaction_accounts,
+            Ok(()),
+        );
+        assert_eq!(accounts[0].owner(), &id());
+        assert_eq!(accounts[0].lamports(), timelock_rent);
+        assert_eq!(accounts[3].lamports(), 1_000_000_000 - 1);
+
+        let accounts = process_instruction(
+            &instruction::initialize(
+                &program_address,
+                &current_authority_address,
+                &timelock_authority_address,
+                100,
+                &payer_address,
+            ),
+            transaction_accounts,
+            Ok(()),
+        );
+        assert_eq!(accounts[0].lamports(), timelock_rent);
+        assert_eq!(accounts[3].lamports(), 1_000_000_000 - timelock_rent);
+        assert_eq!(accounts[0].owner(), &id());
+        assert_eq!(
+            UpgradeTimelock::deserialize(accounts[0].data()).unwrap(),
+            UpgradeTimelock {
+                program: program_address,
+                authority: timelock_authority_address,
+                delay_slots: 100,
+                pending_upgrade: None,
+                pending_release: None,
+            }
+        );
+        assert_eq!(
+            get_state(accounts[1].data())
+                .unwrap()
+                .authority_address_or_next_version,
+            timelock_address
+        );
+    }
+
+    #[test]
+    fn test_stage_and_apply_upgrade() {
+        let program_address = Pubkey::new_unique();
+        let timelock_address = get_timelock_address(&program_address);
+        let timelock_authority_address = Pubkey::new_unique();
+        let buffer_address = Pubkey::new_unique();
+        let new_elf = load_elf("sbpfv3_return_err");
+        let new_elf_hash = get_elf_hash(&new_elf);
+        let timelock = UpgradeTimelock {
+            program: program_address,
+            authority: timelock_authority_address,
+            delay_slots: 100,
+            pending_upgrade: None,
+            pending_release: None,
+        };
+        let mut transaction_accounts = vec![
+            (timelock_address, timelock_account(&timelock)),
+            (
+                program_address,
+                loader_v4_account(
+                    &timelock_address,
+                    LoaderV4Status::Deployed,
+                    &load_elf("sbpfv3_return_ok"),
+                    1_000_000_000,
+                ),
+            ),
+            (
+                buffer_address,
+                loader_v4_account(
+                    &Pubkey::new_unique(),
+                    LoaderV4Status::Retracted,
+                    &new_elf,
+                    0,
+                ),
+            ),
+            (
+                timelock_authority_address,
+                AccountSharedData::new(0, 0, &system_program::id()),
+            ),
+            (
+                loader_v4::id(),
+                AccountSharedData::new(0, 0, &native_loader::id()),
+            ),
+            (clock::id(), clock_account(1000)),
+            (
+                rent::id(),
+                create_account_shared_data_for_test(&Rent::default()),
+            ),
+        ];
+
+        // Error: Buffer hash does not match
+        process_instruction(
+            &instruction::stage_upgrade(
+                &program_address,
+                &timelock_authority_address,
+                &buffer_address,
+                Hash::default(),
+            ),
+            transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+
+        // Error: Timelock authority did not sign
+        let mut stage_instruction = instruction::stage_upgrade(
+            &program_address,
+            &timelock_authority_address,
+            &buffer_address,
+            new_elf_hash,
+        );
+        stage_instruction.accounts[2].is_signer = false;
+        process_instruction(
+            &stage_instruction,
+            transaction_accounts.clone(),
+            Err(InstructionError::MissingRequiredSignature),
+        );
+
+        // Error: No upgrade is pending
+        process_instruction(
+            &instruction::apply_upgrade(&program_address, &buffer_address),
+            transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+
+        let accounts = process_instruction(
+            &instruction::stage_upgrade(
+                &program_address,
+                &timelock_authority_address,
+                &buffer_address,
+                new_elf_hash,
+            ),
+            transaction_accounts.clone(),
+            Ok(()),
+        );
+        assert_eq!(
+            UpgradeTimelock::deserialize(accounts[0].data())
+                .unwrap()
+                .pending_upgrade,
+            Some(PendingUpgrade {
+                buffer: buffer_address,
+                buffer_hash: new_elf_hash,
+                staged_slot: 1000,
+                earliest_slot: 1100,
+            })
+        );
+        transaction_accounts[0].1 = accounts[0].clone();
+
+        // Error: Delay has not passed yet
+        transaction_accounts[5].1 = clock_account(1099);
+        process_instruction(
+            &instruction::apply_upgrade(&program_address, &buffer_address),
+            transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+        transaction_accounts[5].1 = clock_account(1100);
+
+        // Error: Buffer was modified after the upgrade was staged
+        let mut modified_transaction_accounts = transaction_accounts.clone();
+        let buffer_data = modified_transaction_accounts[2].1.data_as_mut_slice();
+        let last_byte = buffer_data.len() - 1;
+        buffer_data[last_byte] = buffer_data[last_byte].wrapping_add(1);
+        process_instruction(
+            &instruction::apply_upgrade(&program_address, &buffer_address),
+            modified_transaction_accounts,
+            Err(InstructionError::InvalidAccountData),
+        );
+
+        let accounts = process_instruction(
+            &instruction::apply_upgrade(&program_address, &buffer_address),
+            transaction_accounts.clone(),
+            Ok(()),
+        );
+        assert_eq!(
+            UpgradeTimelock::deserialize(accounts[0].data())
+                .unwrap()
+                .pending_upgrade,
+            None
+        );
+        let state = get_state(accounts[1].data()).unwrap();
+        assert!(matches!(state.status, LoaderV4Status::Deployed));
+        assert_eq!(state.slot, 1100);
+        assert_eq!(state.authority_address_or_next_version, timelock_address);
+        assert_eq!(
+            &accounts[1].data()[LoaderV4State::program_data_offset()..],
+            new_elf.as_slice()
+        );
+    }
+
+    #[test]
+    fn test_cancel_upgrade() {
+        let program_address = Pubkey::new_unique();
+        let timelock_address = get_timelock_address(&program_address);
+        let timelock_authority_address = Pubkey::new_unique();
+        let timelock = UpgradeTimelock {
+            program: program_address,
+            authority: timelock_authority_address,
+            delay_slots: 100,
+            pending_upgrade: Some(PendingUpgrade {
+                buffer: Pubkey::new_unique(),
+                buffer_hash: Hash::default(),
+                staged_slot: 1000,
+                earliest_slot: 1100,
+            }),
+            pending_release: None,
+        };
+        let mut transaction_accounts = vec![
+            (timelock_address, timelock_account(&timelock)),
+            (
+                timelock_authority_address,
+                AccountSharedData::new(0, 0, &system_program::id()),
+            ),
+        ];
+
+        // Error: Incorrect timelock authority
+        let other_authority_address = Pubkey::new_unique();
+        process_instruction(
+            &instruction::cancel_upgrade(&program_address, &other_authority_address),
+            vec![
+                transaction_accounts[0].clone(),
+                (
+                    other_authority_address,
+                    AccountSharedData::new(0, 0, &system_program::id()),
+                ),
+            ],
+            Err(InstructionError::IncorrectAuthority),
+        );
+
+        let accounts = process_instruction(
+            &instruction::cancel_upgrade(&program_address, &timelock_authority_address),
+            transaction_accounts.clone(),
+            Ok(()),
+        );
+        assert_eq!(
+            UpgradeTimelock::deserialize(accounts[0].data()).unwrap(),
+            UpgradeTimelock {
+                pending_upgrade: None,
+                ..timelock
+            }
+        );
+        transaction_accounts[0].1 = accounts[0].clone();
+
+        // Error: No upgrade is pending
+        process_instruction(
+            &instruction::cancel_upgrade(&program_address, &timelock_authority_address),
+            transaction_accounts,
+            Err(InstructionError::InvalidArgument),
+        );
+    }
+
+    #[test]
+    fn test_stage_cancel_and_release_authority() {
+        let program_address = Pubkey::new_unique();
+        let timelock_address = get_timelock_address(&program_address);
+        let timelock_authority_address = Pubkey::new_unique();
+        let new_authority_address = Pubkey::new_unique();
+        let timelock = UpgradeTimelock {
+            program: program_address,
+            authority: timelock_authority_address,
+            delay_slots: 100,
+            pending_upgrade: Some(PendingUpgrade {
+                buffer: Pubkey::new_unique(),
+                buffer_hash: Hash::default(),
+                staged_slot: 1000,
+                earliest_slot: 1100,
+            }),
+            pending_release: None,
+        };
+        let mut transaction_accounts = vec![
+            (timelock_address, timelock_account(&timelock)),
+            (
+                program_address,
+                loader_v4_account(
+                    &timelock_address,
+                    LoaderV4Status::Deployed,
+                    &load_elf("sbpfv3_return_ok"),
+                    0,
+                ),
+            ),
+            (
+                timelock_authority_address,
+                AccountSharedData::new(0, 0, &system_program::id()),
+            ),
+            (
+                new_authority_address,
+                AccountSharedData::new(0, 0, &system_program::id()),
+            ),
+            (
+                loader_v4::id(),
+                AccountSharedData::new(0, 0, &native_loader::id()),
+            ),
+            (clock::id(), clock_account(1000)),
+        ];
+
+        // Error: The authority can not be released without staging it first
+        process_instruction(
+            &instruction::release_authority(&program_address, &new_authority_address),
+            transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+
+        // Error: Timelock authority did not sign
+        let mut stage_instruction = instruction::stage_release(
+            &program_address,
+            &timelock_authority_address,
+            &new_authority_address,
+        );
+        stage_instruction.accounts[1].is_signer = false;
+        process_instruction(
+            &stage_instruction,
+            transaction_accounts.clone(),
+            Err(InstructionError::MissingRequiredSignature),
+        );
+
+        let accounts = process_instruction(
+            &instruction::stage_release(
+                &program_address,
+                &timelock_authority_address,
+                &new_authority_address,
+            ),
+            transaction_accounts.clone(),
+            Ok(()),
+        );
+        let pending_release = PendingRelease {
+            new_authority: new_authority_address,
+            staged_slot: 1000,
+            earliest_slot: 1100,
+        };
+        assert_eq!(
+            UpgradeTimelock::deserialize(accounts[0].data())
+                .unwrap()
+                .pending_release,
+            Some(pending_release)
+        );
+        let staged_timelock_account = accounts[0].clone();
+        transaction_accounts[0].1 = staged_timelock_account.clone();
+
+        // Error: The release can not be applied immediately
+        process_instruction(
+            &instruction::release_authority(&program_address, &new_authority_address),
+            transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+
+        // Error: Delay has not passed yet
+        transaction_accounts[5].1 = clock_account(1099);
+        process_instruction(
+            &instruction::release_authority(&program_address, &new_authority_address),
+            transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+        transaction_accounts[5].1 = clock_account(1100);
+
+        // Error: New authority does not match the pending release
+        let other_authority_address = Pubkey::new_unique();
+        let mut other_transaction_accounts = transaction_accounts.clone();
+        other_transaction_accounts[3].0 = other_authority_address;
+        process_instruction(
+            &instruction::release_authority(&program_address, &other_authority_address),
+            other_transaction_accounts,
+            Err(InstructionError::IncorrectAuthority),
+        );
+
+        // Error: New authority did not sign
+        let mut release_instruction =
+            instruction::release_authority(&program_address, &new_authority_address);
+        release_instruction.accounts[2].is_signer = false;
+        process_instruction(
+            &release_instruction,
+            transaction_accounts.clone(),
+            Err(InstructionError::MissingRequiredSignature),
+        );
+
+        // A cancelled release can not be applied
+        let accounts = process_instruction(
+            &instruction::cancel_release(&program_address, &timelock_authority_address),
+            transaction_accounts.clone(),
+            Ok(()),
+        );
+        assert_eq!(
+            UpgradeTimelock::deserialize(accounts[0].data()).unwrap(),
+            timelock
+        );
+        let mut cancelled_transaction_accounts = transaction_accounts.clone();
+        cancelled_transaction_accounts[0].1 = accounts[0].clone();
+        process_instruction(
+            &instruction::release_authority(&program_address, &new_authority_address),
+            cancelled_transaction_accounts.clone(),
+            Err(InstructionError::InvalidArgument),
+        );
+
+        // Error: No release is pending
+        process_instruction(
+            &instruction::cancel_release(&program_address, &timelock_authority_address),
+            cancelled_transaction_accounts,
+            Err(InstructionError::InvalidArgument),
+        );
+
+        let accounts = process_instruction(
+            &instruction::release_authority(&program_address, &new_authority_address),
+            transaction_accounts.clone(),
+            Ok(()),
+        );
+        assert_eq!(accounts[0].lamports(), 0);
+        assert!(accounts[0].data().is_empty());
+        assert_eq!(accounts[0].owner(), &system_program::id());
+        assert_eq!(accounts[3].lamports(), staged_timelock_account.lamports());
+        assert_eq!(
+            get_state(accounts[1].data())
+                .unwrap()
+                .authority_address_or_next_version,
+            new_authority_address
+        );
+    }
+}
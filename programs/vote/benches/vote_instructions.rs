@@ -12,13 +12,14 @@ use {
     },
     solana_pubkey::Pubkey,
     solana_rent::Rent,
-    solana_sdk_ids::{sysvar, vote::id},
+    solana_sdk_ids::{native_loader, system_program, sysvar, vote::id},
     solana_slot_hashes::{SlotHashes, MAX_ENTRIES},
     solana_transaction_context::TransactionAccount,
     solana_vote_program::{
         vote_instruction::VoteInstruction,
         vote_processor::Entrypoint,
         vote_state::{
+            commission_schedule::{pending_commission_address, PENDING_COMMISSION_SIZE},
             create_account, create_account_with_authorized, TowerSync, Vote, VoteAuthorize,
             VoteAuthorizeCheckedWithSeedArgs, VoteAuthorizeWithSeedArgs, VoteInit, VoteState,
             VoteStateUpdate, VoteStateVersions, MAX_LOCKOUT_HISTORY,
@@ -474,10 +475,22 @@ impl BenchUpdateCommission {
     fn new() -> Self {
         let (vote_pubkey, _authorized_voter, authorized_withdrawer, vote_account) =
             create_test_account_with_authorized();
+        let pending_commission_pubkey = pending_commission_address(&vote_pubkey);
+        // the commission increase overwrites an already scheduled one
+        let pending_commission_account = AccountSharedData::new(
+            Rent::default().minimum_balance(PENDING_COMMISSION_SIZE),
+            PENDING_COMMISSION_SIZE,
+            &id(),
+        );
         let instruction_data = serialize(&VoteInstruction::UpdateCommission(u8::MAX)).unwrap();
         let transaction_accounts = vec![
             (vote_pubkey, vote_account),
             (authorized_withdrawer, AccountSharedData::default()),
+            (pending_commission_pubkey, pending_commission_account),
+            (
+                system_program::id(),
+                AccountSharedData::new(0, 0, &native_loader::id()),
+            ),
             // Add the sysvar accounts so they're in the cache for mock processing
             (
                 sysvar::clock::id(),
@@ -487,6 +500,7 @@ impl BenchUpdateCommission {
                 sysvar::epoch_schedule::id(),
                 account::create_account_shared_data_for_test(&EpochSchedule::without_warmup()),
             ),
+            (sysvar::rent::id(), create_default_rent_account()),
         ];
         let instruction_accounts = vec![
             AccountMeta {
@@ -501,6 +515,18 @@ impl BenchUpdateCommission {
                 is_signer: true,
                 is_writable: false,
             },
+            AccountMeta {
+                // `[WRITE]` Pending commission account
+                pubkey: pending_commission_pubkey,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                // `[]` System program
+                pubkey: system_program::id(),
+                is_signer: false,
+                is_writable: false,
+            },
         ];
         Self {
             instruction_data,
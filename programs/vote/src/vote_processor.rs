@@ -1,7 +1,10 @@
 //! Vote program processor
 
 use {
-    crate::vote_state,
+    crate::vote_state::{
+        self,
+        commission_schedule::{pending_commission_address, PENDING_COMMISSION_SIZE},
+    },
     log::*,
     solana_bincode::limited_deserialize,
     solana_instruction::error::InstructionError,
@@ -10,6 +13,7 @@ use {
         sysvar_cache::get_sysvar_with_account_check,
     },
     solana_pubkey::Pubkey,
+    solana_sdk_ids::system_program,
     solana_transaction_context::{BorrowedAccount, InstructionContext, TransactionContext},
     solana_vote_interface::{instruction::VoteInstruction, program::id, state::VoteAuthorize},
     std::collections::HashSet,
@@ -46,6 +50,86 @@ fn process_authorize_with_seed_instruction(
     )
 }
 
+/// Update the commission with the commission schedule in effect. An increase is recorded in the
+/// pending commission account, the third instruction account, which is created on the first
+/// scheduled increase and funded from the vote account. A decrease takes effect immediately and
+/// only needs the vote account and the authorized withdrawer, so that the legacy two-account
+/// instruction keeps working. If the pending commission account is supplied as well, the decrease
+/// cancels the pending increase and closes the account.
+fn process_update_commission_with_schedule(
+    invoke_context: &mut InvokeContext,
+    commission: u8,
+    signers: &HashSet<Pubkey>,
+) -> Result<(), InstructionError> {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+
+    let sysvar_cache = invoke_context.get_sysvar_cache();
+    let rent = sysvar_cache.get_rent()?;
+    let mut vote_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    let pending_commission = vote_state::schedule_commission_update(
+        &mut vote_account,
+        commission,
+        signers,
+        sysvar_cache.get_epoch_schedule()?.as_ref(),
+        sysvar_cache.get_clock()?.as_ref(),
+    )?;
+    let is_pending_account_supplied = instruction_context.get_number_of_instruction_accounts() > 2;
+    if pending_commission.is_some() {
+        instruction_context.check_number_of_instruction_accounts(3)?;
+    } else if !is_pending_account_supplied {
+        return Ok(());
+    }
+
+    let mut pending_account =
+        instruction_context.try_borrow_instruction_account(transaction_context, 2)?;
+    let pending_address = *pending_account.get_key();
+    if pending_address != pending_commission_address(vote_account.get_key()) {
+        return Err(InstructionError::InvalidArgument);
+    }
+    let pending_account_exists = *pending_account.get_owner() == id();
+
+    let Some(pending_commission) = pending_commission else {
+        // a decrease cancels the pending increase
+        if pending_account_exists {
+            vote_account.checked_add_lamports(pending_account.get_lamports())?;
+            pending_account.set_lamports(0)?;
+            pending_account.set_data_length(0)?;
+            pending_account.set_owner(system_program::id().as_ref())?;
+        }
+        return Ok(());
+    };
+
+    if !pending_account_exists {
+        // the vote account funds the pending commission account and has to stay rent exempt
+        let shortfall = rent
+            .minimum_balance(PENDING_COMMISSION_SIZE)
+            .saturating_sub(pending_account.get_lamports());
+        vote_account.checked_sub_lamports(shortfall)?;
+        if !rent.is_exempt(vote_account.get_lamports(), vote_account.get_data().len()) {
+            return Err(InstructionError::InsufficientFunds);
+        }
+        pending_account.checked_add_lamports(shortfall)?;
+        drop(vote_account);
+        drop(pending_account);
+        invoke_context.create_program_derived_account(
+            &pending_address,
+            None,
+            PENDING_COMMISSION_SIZE,
+            &id(),
+        )?;
+
+        let transaction_context = &invoke_context.transaction_context;
+        let instruction_context = transaction_context.get_current_instruction_context()?;
+        return instruction_context
+            .try_borrow_instruction_account(transaction_context, 2)?
+            .set_data_from_slice(&pending_commission.to_bytes());
+    }
+
+    pending_account.set_data_from_slice(&pending_commission.to_bytes())
+}
+
 // Citing `runtime/src/block_cost_limit.rs`, vote has statically defined 2100
 // units; can consume based on instructions in the future like `bpf_loader` does.
 pub const DEFAULT_COMPUTE_UNITS: u64 = 2_100;
@@ -63,6 +147,7 @@ declare_process_instruction!(Entrypoint, DEFAULT_COMPUTE_UNITS, |invoke_context|
     }
 
     let signers = instruction_context.get_signers(transaction_context)?;
+
     match limited_deserialize(data, solana_packet::PACKET_DATA_SIZE as u64)? {
         VoteInstruction::InitializeAccount(vote_init) => {
             let rent = get_sysvar_with_account_check::rent(invoke_context, instruction_context, 1)?;
@@ -118,6 +203,18 @@ declare_process_instruction!(Entrypoint, DEFAULT_COMPUTE_UNITS, |invoke_context|
             vote_state::update_validator_identity(&mut me, node_pubkey, &signers)
         }
         VoteInstruction::UpdateCommission(commission) => {
+            if invoke_context
+                .get_feature_set()
+                .enable_vote_commission_schedule
+            {
+                drop(me);
+                return process_update_commission_with_schedule(
+                    invoke_context,
+                    commission,
+                    &signers,
+                );
+            }
+
             let sysvar_cache = invoke_context.get_sysvar_cache();
 
             vote_state::update_commission(
@@ -223,30 +320,37 @@ mod tests {
             vote_error::VoteError,
             vote_instruction::{
                 authorize, authorize_checked, compact_update_vote_state,
-                compact_update_vote_state_switch, create_account_with_config, update_commission,
+                compact_update_vote_state_switch, create_account_with_config,
                 update_validator_identity, update_vote_state, update_vote_state_switch, vote,
                 vote_switch, withdraw, CreateVoteAccountConfig, VoteInstruction,
             },
             vote_state::{
-                self, Lockout, TowerSync, Vote, VoteAuthorize, VoteAuthorizeCheckedWithSeedArgs,
+                self,
+                commission_schedule::{self, PendingCommission},
+                Lockout, TowerSync, Vote, VoteAuthorize, VoteAuthorizeCheckedWithSeedArgs,
                 VoteAuthorizeWithSeedArgs, VoteInit, VoteState, VoteStateUpdate, VoteStateVersions,
             },
         },
+        agave_feature_set::{enable_vote_commission_schedule, FeatureSet},
         bincode::serialize,
         solana_account::{
             self as account, state_traits::StateMut, Account, AccountSharedData, ReadableAccount,
+            WritableAccount,
         },
         solana_clock::Clock,
         solana_epoch_schedule::EpochSchedule,
         solana_hash::Hash,
         solana_instruction::{AccountMeta, Instruction},
-        solana_program_runtime::invoke_context::mock_process_instruction,
+        solana_program_runtime::{
+            invoke_context::{mock_process_instruction, mock_process_instruction_with_feature_set},
+            loaded_programs::ProgramCacheEntry,
+        },
         solana_pubkey::Pubkey,
         solana_rent::Rent,
-        solana_sdk_ids::sysvar,
+        solana_sdk_ids::{native_loader, sysvar},
         solana_slot_hashes::SlotHashes,
         solana_vote_interface::instruction::{tower_sync, tower_sync_switch},
-        std::{collections::HashSet, str::FromStr},
+        std::{collections::HashSet, str::FromStr, sync::Arc},
     };
 
     struct VoteAccountTestFixtureWithAuthorities {
@@ -278,11 +382,49 @@ mod tests {
             instruction_accounts,
             expected_result,
             Entrypoint::vm,
+            add_system_program_builtin,
             |_invoke_context| {},
+        )
+    }
+
+    fn process_instruction_with_feature_set(
+        instruction_data: &[u8],
+        transaction_accounts: Vec<(Pubkey, AccountSharedData)>,
+        instruction_accounts: Vec<AccountMeta>,
+        expected_result: Result<(), InstructionError>,
+        feature_set: &FeatureSet,
+    ) -> Vec<AccountSharedData> {
+        mock_process_instruction_with_feature_set(
+            &id(),
+            Vec::new(),
+            instruction_data,
+            transaction_accounts,
+            instruction_accounts,
+            expected_result,
+            Entrypoint::vm,
+            add_system_program_builtin,
             |_invoke_context| {},
+            &feature_set.runtime_features(),
         )
     }
 
+    fn add_system_program_builtin(invoke_context: &mut InvokeContext) {
+        invoke_context.program_cache_for_tx_batch.replenish(
+            system_program::id(),
+            Arc::new(ProgramCacheEntry::new_builtin(
+                0,
+                0,
+                solana_system_program::system_processor::Entrypoint::vm,
+            )),
+        );
+    }
+
+    fn feature_set_without_commission_schedule() -> FeatureSet {
+        let mut feature_set = FeatureSet::all_enabled();
+        feature_set.deactivate(&enable_vote_commission_schedule::id());
+        feature_set
+    }
+
     fn process_instruction_as_one_arg(
         instruction: &Instruction,
         expected_result: Result<(), InstructionError>,
@@ -671,12 +813,15 @@ mod tests {
             },
         ];
 
+        let feature_set = feature_set_without_commission_schedule();
+
         // should pass
-        let accounts = process_instruction(
+        let accounts = process_instruction_with_feature_set(
             &serialize(&VoteInstruction::UpdateCommission(u8::MAX)).unwrap(),
             transaction_accounts.clone(),
             instruction_accounts.clone(),
             Ok(()),
+            &feature_set,
         );
         let vote_state: VoteState = StateMut::<VoteStateVersions>::state(&accounts[0])
             .unwrap()
@@ -684,11 +829,12 @@ mod tests {
         assert_eq!(vote_state.commission, u8::MAX);
 
         // should pass
-        let accounts = process_instruction(
+        let accounts = process_instruction_with_feature_set(
             &instruction_data,
             transaction_accounts.clone(),
             instruction_accounts.clone(),
             Ok(()),
+            &feature_set,
         );
         let vote_state: VoteState = StateMut::<VoteStateVersions>::state(&accounts[0])
             .unwrap()
@@ -697,11 +843,12 @@ mod tests {
 
         // should fail, authorized_withdrawer didn't sign the transaction
         instruction_accounts[1].is_signer = false;
-        let accounts = process_instruction(
+        let accounts = process_instruction_with_feature_set(
             &instruction_data,
             transaction_accounts,
             instruction_accounts,
             Err(InstructionError::MissingRequiredSignature),
+            &feature_set,
         );
         let vote_state: VoteState = StateMut::<VoteStateVersions>::state(&accounts[0])
             .unwrap()
@@ -709,6 +856,186 @@ mod tests {
         assert_eq!(vote_state.commission, 0);
     }
 
+    #[test]
+    fn test_vote_update_commission_schedule() {
+        let (vote_pubkey, _authorized_voter, authorized_withdrawer, mut vote_account) =
+            create_test_account_with_authorized();
+        let rent = Rent::default();
+        let vote_account_rent = rent.minimum_balance(vote_account.data().len());
+        let pending_commission_rent = rent.minimum_balance(PENDING_COMMISSION_SIZE);
+        let pending_address = pending_commission_address(&vote_pubkey);
+        let mut transaction_accounts = vec![
+            (vote_pubkey, vote_account.clone()),
+            (authorized_withdrawer, AccountSharedData::default()),
+            (pending_address, AccountSharedData::default()),
+            (
+                system_program::id(),
+                AccountSharedData::new(0, 0, &native_loader::id()),
+            ),
+            // Add the sysvar accounts so they're in the cache for mock processing
+            (
+                sysvar::clock::id(),
+                account::create_account_shared_data_for_test(&Clock::default()),
+            ),
+            (
+                sysvar::epoch_schedule::id(),
+                account::create_account_shared_data_for_test(&EpochSchedule::without_warmup()),
+            ),
+            (
+                sysvar::rent::id(),
+                account::create_account_shared_data_for_test(&rent),
+            ),
+        ];
+        let mut instruction_accounts =
+            commission_schedule::update_commission(&vote_pubkey, &authorized_withdrawer, 42)
+                .accounts;
+        let increase_instruction_data = serialize(&VoteInstruction::UpdateCommission(42)).unwrap();
+        let decrease_instruction_data = serialize(&VoteInstruction::UpdateCommission(0)).unwrap();
+
+        // should fail, the pending commission account is missing
+        process_instruction(
+            &increase_instruction_data,
+            transaction_accounts.clone(),
+            instruction_accounts[..2].to_vec(),
+            Err(InstructionError::NotEnoughAccountKeys),
+        );
+
+        // should fail, the pending commission account is not derived from the vote account
+        let mut wrong_instruction_accounts = instruction_accounts.clone();
+        wrong_instruction_accounts[2].pubkey = authorized_withdrawer;
+        process_instruction(
+            &increase_instruction_data,
+            transaction_accounts.clone(),
+            wrong_instruction_accounts,
+            Err(InstructionError::InvalidArgument),
+        );
+
+        // should fail, the vote account can't fund the pending commission account
+        vote_account.set_lamports(vote_account_rent);
+        transaction_accounts[0].1 = vote_account.clone();
+        process_instruction(
+            &increase_instruction_data,
+            transaction_accounts.clone(),
+            instruction_accounts.clone(),
+            Err(InstructionError::InsufficientFunds),
+        );
+
+        // an increase is recorded in the pending commission account, the vote account keeps its
+        // size and commission
+        vote_account.set_lamports(vote_account_rent + pending_commission_rent);
+        transaction_accounts[0].1 = vote_account.clone();
+        let accounts = process_instruction(
+            &increase_instruction_data,
+            transaction_accounts.clone(),
+            instruction_accounts.clone(),
+            Ok(()),
+        );
+        assert_eq!(accounts[0].data().len(), vote_account.data().len());
+        assert_eq!(accounts[0].lamports(), vote_account_rent);
+        let vote_state: VoteState = StateMut::<VoteStateVersions>::state(&accounts[0])
+            .unwrap()
+            .convert_to_current();
+        assert_eq!(vote_state.commission, 0);
+        assert_eq!(accounts[2].owner(), &id());
+        assert_eq!(accounts[2].lamports(), pending_commission_rent);
+        assert_eq!(
+            PendingCommission::from_account_data(accounts[2].data()),
+            Some(PendingCommission {
+                commission: 42,
+                effective_epoch: commission_schedule::COMMISSION_INCREASE_NOTICE_EPOCHS,
+            })
+        );
+
+        // a pre-funded pending commission account only needs the rent shortfall
+        let mut prefunded_transaction_accounts = transaction_accounts.clone();
+        prefunded_transaction_accounts[2].1 =
+            AccountSharedData::new(pending_commission_rent - 1, 0, &system_program::id());
+        let prefunded_accounts = process_instruction(
+            &increase_instruction_data,
+            prefunded_transaction_accounts,
+            instruction_accounts.clone(),
+            Ok(()),
+        );
+        assert_eq!(
+            prefunded_accounts[0].lamports(),
+            vote_account_rent + pending_commission_rent - 1
+        );
+        assert_eq!(prefunded_accounts[2].lamports(), pending_commission_rent);
+        assert_eq!(prefunded_accounts[2].owner(), &id());
+
+        // should fail, the pending commission account is owned by another program
+        let mut foreign_transaction_accounts = transaction_accounts.clone();
+        foreign_transaction_accounts[2].1 =
+            AccountSharedData::new(pending_commission_rent, 0, &Pubkey::new_unique());
+        process_instruction(
+            &increase_instruction_data,
+            foreign_transaction_accounts,
+            instruction_accounts.clone(),
+            Err(InstructionError::AccountAlreadyInitialized),
+        );
+
+        // a decrease applies immediately and closes the pending commission account
+        transaction_accounts[0].1 = accounts[0].clone();
+        transaction_accounts[2].1 = accounts[2].clone();
+        let accounts = process_instruction(
+            &decrease_instruction_data,
+            transaction_accounts.clone(),
+            instruction_accounts.clone(),
+            Ok(()),
+        );
+        let vote_state: VoteState = StateMut::<VoteStateVersions>::state(&accounts[0])
+            .unwrap()
+            .convert_to_current();
+        assert_eq!(vote_state.commission, 0);
+        assert_eq!(
+            accounts[0].lamports(),
+            vote_account_rent + pending_commission_rent
+        );
+        assert_eq!(accounts[2].lamports(), 0);
+        assert!(accounts[2].data().is_empty());
+        assert_eq!(accounts[2].owner(), &system_program::id());
+
+        // a decrease with the legacy two-account instruction applies immediately, the pending
+        // increase stays in place
+        let mut increased_vote_account = vote_account.clone();
+        let mut vote_state: VoteState = StateMut::<VoteStateVersions>::state(&vote_account)
+            .unwrap()
+            .convert_to_current();
+        vote_state.commission = 50;
+        increased_vote_account
+            .set_state(&VoteStateVersions::new_current(vote_state))
+            .unwrap();
+        let legacy_instruction = solana_vote_interface::instruction::update_commission(
+            &vote_pubkey,
+            &authorized_withdrawer,
+            10,
+        );
+        assert_eq!(legacy_instruction.accounts.len(), 2);
+        let mut legacy_transaction_accounts = transaction_accounts.clone();
+        legacy_transaction_accounts[0].1 = increased_vote_account;
+        legacy_transaction_accounts[2].1 = prefunded_accounts[2].clone();
+        let legacy_accounts = process_instruction(
+            &legacy_instruction.data,
+            legacy_transaction_accounts,
+            legacy_instruction.accounts.clone(),
+            Ok(()),
+        );
+        let vote_state: VoteState = StateMut::<VoteStateVersions>::state(&legacy_accounts[0])
+            .unwrap()
+            .convert_to_current();
+        assert_eq!(vote_state.commission, 10);
+        assert_eq!(legacy_accounts[2], prefunded_accounts[2]);
+
+        // should fail, authorized_withdrawer didn't sign the transaction
+        instruction_accounts[1].is_signer = false;
+        process_instruction(
+            &increase_instruction_data,
+            transaction_accounts,
+            instruction_accounts,
+            Err(InstructionError::MissingRequiredSignature),
+        );
+    }
+
     #[test]
     fn test_vote_signature() {
         let (vote_pubkey, vote_account) = create_test_account();
@@ -1899,7 +2226,11 @@ mod tests {
             Err(InstructionError::InvalidAccountData),
         );
         process_instruction_as_one_arg(
-            &update_commission(&Pubkey::new_unique(), &Pubkey::new_unique(), 0),
+            &commission_schedule::update_commission(
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+                0,
+            ),
             Err(InstructionError::InvalidAccountData),
         );
 
@@ -0,0 +1,161 @@
+//! Scheduled commission increases
+//!
+//! Once the commission schedule feature is active, a commission increase does not take effect
+//! immediately. It is recorded in a pending commission account, a PDA of the vote program derived
+//! from the vote account address, so that the size of the vote account never changes. At the
+//! epoch boundary, before the rewards of `effective_epoch` are calculated, the runtime applies the
+//! pending commission to the vote account and closes the pending commission account.
+//!
+//! Layout of the pending commission account data:
+//!   0. `u8` commission
+//!   1. `u64` little-endian epoch whose rewards are the first to use the commission
+use {
+    super::{VoteState, VoteStateVersions},
+    solana_account::{AccountSharedData, ReadableAccount, WritableAccount},
+    solana_clock::Epoch,
+    solana_instruction::{error::InstructionError, AccountMeta, Instruction},
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::system_program,
+    solana_vote_interface::{instruction as vote_instruction, program::id},
+};
+
+/// Number of epochs between scheduling a commission increase and it taking effect
+pub const COMMISSION_INCREASE_NOTICE_EPOCHS: Epoch = 2;
+
+/// Size of the pending commission account data
+pub const PENDING_COMMISSION_SIZE: usize = 9;
+
+const PENDING_COMMISSION_SEED: &[u8] = b"pending_commission";
+
+/// Address of the pending commission account of `vote_pubkey`
+pub fn pending_commission_address(vote_pubkey: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[PENDING_COMMISSION_SEED, vote_pubkey.as_ref()], &id()).0
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PendingCommission {
+    pub commission: u8,
+    pub effective_epoch: Epoch,
+}
+
+impl PendingCommission {
+    /// Read the pending commission from the data of a pending commission account
+    pub fn from_account_data(data: &[u8]) -> Option<Self> {
+        let bytes: &[u8; PENDING_COMMISSION_SIZE] = data.try_into().ok()?;
+        Some(Self {
+            commission: bytes[0],
+            effective_epoch: u64::from_le_bytes(bytes[1..].try_into().unwrap()),
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; PENDING_COMMISSION_SIZE] {
+        let mut bytes = [0; PENDING_COMMISSION_SIZE];
+        bytes[0] = self.commission;
+        bytes[1..].copy_from_slice(&self.effective_epoch.to_le_bytes());
+        bytes
+    }
+}
+
+/// Set the commission of `vote_account` to the pending commission, keeping the vote state
+/// version, and with it the size of the account data, unchanged
+pub fn apply_pending_commission(
+    vote_account: &mut AccountSharedData,
+    pending_commission: &PendingCommission,
+) -> Result<(), InstructionError> {
+    let mut versioned = bincode::deserialize::<VoteStateVersions>(vote_account.data())
+        .map_err(|_| InstructionError::InvalidAccountData)?;
+    match &mut versioned {
+        VoteStateVersions::V0_23_5(vote_state) => {
+            vote_state.commission = pending_commission.commission
+        }
+        VoteStateVersions::V1_14_11(vote_state) => {
+            vote_state.commission = pending_commission.commission
+        }
+        VoteStateVersions::Current(vote_state) => {
+            vote_state.commission = pending_commission.commission
+        }
+    }
+    VoteState::serialize(&versioned, vote_account.data_as_mut_slice())
+}
+
+/// Create an `UpdateCommission` instruction that includes the accounts needed to schedule a
+/// commission increase
+pub fn update_commission(
+    vote_pubkey: &Pubkey,
+    authorized_withdrawer_pubkey: &Pubkey,
+    commission: u8,
+) -> Instruction {
+    let mut instruction =
+        vote_instruction::update_commission(vote_pubkey, authorized_withdrawer_pubkey, commission);
+    instruction.accounts.extend([
+        AccountMeta::new(pending_commission_address(vote_pubkey), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    instruction
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_clock::Clock,
+        solana_vote_interface::state::{VoteInit, VoteState1_14_11},
+    };
+
+    #[test]
+    fn test_pending_commission_from_account_data() {
+        let pending_commission = PendingCommission {
+            commission: 42,
+            effective_epoch: 7,
+        };
+        let data = pending_commission.to_bytes();
+        assert_eq!(
+            PendingCommission::from_account_data(&data),
+            Some(pending_commission)
+        );
+        assert_eq!(
+            PendingCommission::from_account_data(&data[..data.len() - 1]),
+            None
+        );
+        assert_eq!(PendingCommission::from_account_data(&[]), None);
+    }
+
+    #[test]
+    fn test_apply_pending_commission() {
+        let vote_state = VoteState::new(
+            &VoteInit {
+                node_pubkey: Pubkey::new_unique(),
+                authorized_voter: Pubkey::new_unique(),
+                authorized_withdrawer: Pubkey::new_unique(),
+                commission: 10,
+            },
+            &Clock::default(),
+        );
+        let pending_commission = PendingCommission {
+            commission: 20,
+            effective_epoch: 0,
+        };
+
+        for versioned in [
+            VoteStateVersions::V1_14_11(Box::new(VoteState1_14_11::from(vote_state.clone()))),
+            VoteStateVersions::new_current(vote_state.clone()),
+        ] {
+            let data_len = VoteStateVersions::vote_state_size_of(matches!(
+                versioned,
+                VoteStateVersions::Current(_)
+            ));
+            let mut vote_account = AccountSharedData::new(1, data_len, &id());
+            VoteState::serialize(&versioned, vote_account.data_as_mut_slice()).unwrap();
+
+            apply_pending_commission(&mut vote_account, &pending_commission).unwrap();
+
+            assert_eq!(vote_account.data().len(), data_len);
+            let applied: VoteStateVersions = bincode::deserialize(vote_account.data()).unwrap();
+            assert_eq!(
+                std::mem::discriminant(&applied),
+                std::mem::discriminant(&versioned)
+            );
+            assert_eq!(applied.convert_to_current().commission, 20);
+        }
+    }
+}
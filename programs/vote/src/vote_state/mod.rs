@@ -1,7 +1,10 @@
 //! Vote state, vote program
 //! Receive and processes votes from validators
+pub mod commission_schedule;
+
 pub use solana_vote_interface::state::{vote_state_versions::*, *};
 use {
+    commission_schedule::{PendingCommission, COMMISSION_INCREASE_NOTICE_EPOCHS},
     log::*,
     solana_account::{AccountSharedData, ReadableAccount, WritableAccount},
     solana_clock::{Clock, Epoch, Slot},
@@ -777,6 +780,42 @@ pub fn update_commission<S: std::hash::BuildHasher>(
     set_vote_account_state(vote_account, vote_state)
 }
 
+/// Update the commission with the commission schedule in effect. A decrease takes effect
+/// immediately, while an increase is returned as the pending commission to record, which takes
+/// effect `COMMISSION_INCREASE_NOTICE_EPOCHS` epochs from now
+pub fn schedule_commission_update<S: std::hash::BuildHasher>(
+    vote_account: &mut BorrowedAccount,
+    commission: u8,
+    signers: &HashSet<Pubkey, S>,
+    epoch_schedule: &EpochSchedule,
+    clock: &Clock,
+) -> Result<Option<PendingCommission>, InstructionError> {
+    let mut vote_state = vote_account
+        .get_state::<VoteStateVersions>()?
+        .convert_to_current();
+    let is_increase = is_commission_increase(&vote_state, commission);
+
+    if is_increase && !is_commission_update_allowed(clock.slot, epoch_schedule) {
+        return Err(VoteError::CommissionUpdateTooLate.into());
+    }
+
+    // current authorized withdrawer must say "yay"
+    verify_authorized_signer(&vote_state.authorized_withdrawer, signers)?;
+
+    if is_increase {
+        Ok(Some(PendingCommission {
+            commission,
+            effective_epoch: clock
+                .epoch
+                .saturating_add(COMMISSION_INCREASE_NOTICE_EPOCHS),
+        }))
+    } else {
+        vote_state.commission = commission;
+        set_vote_account_state(vote_account, vote_state)?;
+        Ok(None)
+    }
+}
+
 /// Given a proposed new commission, returns true if this would be a commission increase, false otherwise
 pub fn is_commission_increase(vote_state: &VoteState, commission: u8) -> bool {
     commission > vote_state.commission
@@ -1431,6 +1470,115 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_schedule_commission_update() {
+        let node_pubkey = Pubkey::new_unique();
+        let withdrawer_pubkey = Pubkey::new_unique();
+        let vote_state = VoteState::new(
+            &VoteInit {
+                node_pubkey,
+                authorized_voter: withdrawer_pubkey,
+                authorized_withdrawer: withdrawer_pubkey,
+                commission: 10,
+            },
+            &Clock::default(),
+        );
+
+        let serialized =
+            bincode::serialize(&VoteStateVersions::Current(Box::new(vote_state))).unwrap();
+        let rent = Rent::default();
+        let lamports = rent.minimum_balance(serialized.len());
+        let mut vote_account = AccountSharedData::new(lamports, serialized.len(), &id());
+        vote_account.set_data_from_slice(&serialized);
+
+        let processor_account = AccountSharedData::new(0, 0, &solana_sdk_ids::native_loader::id());
+        let transaction_context = TransactionContext::new(
+            vec![(id(), processor_account), (node_pubkey, vote_account)],
+            rent,
+            0,
+            0,
+        );
+        let mut instruction_context = InstructionContext::default();
+        instruction_context.configure(&[0], &[InstructionAccount::new(1, 1, 0, false, true)], &[]);
+        let mut borrowed_account = instruction_context
+            .try_borrow_instruction_account(&transaction_context, 0)
+            .unwrap();
+
+        let epoch_schedule = EpochSchedule::without_warmup();
+        let clock = Clock {
+            epoch: 5,
+            slot: epoch_schedule.get_first_slot_in_epoch(5),
+            ..Clock::default()
+        };
+        let second_half_clock = Clock {
+            slot: clock.slot + (epoch_schedule.slots_per_epoch * 3) / 4,
+            ..clock.clone()
+        };
+        let signers: HashSet<Pubkey> = vec![withdrawer_pubkey].into_iter().collect();
+        let commission = |account: &BorrowedAccount| {
+            account
+                .get_state::<VoteStateVersions>()
+                .unwrap()
+                .convert_to_current()
+                .commission
+        };
+
+        // the withdrawer must sign
+        assert_eq!(
+            schedule_commission_update(
+                &mut borrowed_account,
+                20,
+                &HashSet::new(),
+                &epoch_schedule,
+                &clock,
+            ),
+            Err(InstructionError::MissingRequiredSignature)
+        );
+
+        // increases are still limited to the first half of the epoch
+        assert_eq!(
+            schedule_commission_update(
+                &mut borrowed_account,
+                20,
+                &signers,
+                &epoch_schedule,
+                &second_half_clock,
+            ),
+            Err(VoteError::CommissionUpdateTooLate.into())
+        );
+
+        // an increase is returned as pending and leaves the vote account untouched
+        let data_before = borrowed_account.get_data().to_vec();
+        assert_eq!(
+            schedule_commission_update(
+                &mut borrowed_account,
+                20,
+                &signers,
+                &epoch_schedule,
+                &clock
+            ),
+            Ok(Some(PendingCommission {
+                commission: 20,
+                effective_epoch: 5 + COMMISSION_INCREASE_NOTICE_EPOCHS,
+            }))
+        );
+        assert_eq!(borrowed_account.get_data(), &data_before[..]);
+
+        // a decrease applies immediately, even in the second half of the epoch
+        assert_eq!(
+            schedule_commission_update(
+                &mut borrowed_account,
+                5,
+                &signers,
+                &epoch_schedule,
+                &second_half_clock,
+            ),
+            Ok(None)
+        );
+        assert_eq!(commission(&borrowed_account), 5);
+        assert_eq!(borrowed_account.get_data().len(), data_before.len());
+    }
+
     #[test]
     fn test_vote_double_lockout_after_expiration() {
         let voter_pubkey = solana_pubkey::new_rand();
@@ -0,0 +1,208 @@
+//! Compares verifying proofs one by one against verifying them as a single batch.
+//!
+//! The ratio between the two is used to price the proofs of a `VerifyProofBatch` instruction
+//! relative to the compute units of the individual proof verification instructions.
+
+use {
+    criterion::{criterion_group, criterion_main, BenchmarkId, Criterion},
+    solana_zk_sdk::{
+        batch_verifier::BatchVerifier,
+        encryption::{
+            elgamal::ElGamalKeypair,
+            pedersen::{Pedersen, PedersenOpening},
+        },
+        zk_elgamal_proof_program::proof_data::{
+            BatchableZkProofData, BatchedRangeProofU128Data, BatchedRangeProofU256Data,
+            BatchedRangeProofU64Data, CiphertextCiphertextEqualityProofData,
+            CiphertextCommitmentEqualityProofData, ZkProofData,
+        },
+    },
+};
+
+const BATCH_SIZES: [usize; 4] = [1, 2, 4, 8];
+
+fn range_proof_u64_data() -> BatchedRangeProofU64Data {
+    let amount_1 = 255_u64;
+    let amount_2 = 77_u64;
+
+    let (commitment_1, opening_1) = Pedersen::new(amount_1);
+    let (commitment_2, opening_2) = Pedersen::new(amount_2);
+
+    BatchedRangeProofU64Data::new(
+        vec![&commitment_1, &commitment_2],
+        vec![amount_1, amount_2],
+        vec![32, 32],
+        vec![&opening_1, &opening_2],
+    )
+    .unwrap()
+}
+
+fn range_proof_u128_data() -> BatchedRangeProofU128Data {
+    let amount_1 = 65535_u64;
+    let amount_2 = 77_u64;
+
+    let (commitment_1, opening_1) = Pedersen::new(amount_1);
+    let (commitment_2, opening_2) = Pedersen::new(amount_2);
+
+    BatchedRangeProofU128Data::new(
+        vec![&commitment_1, &commitment_2],
+        vec![amount_1, amount_2],
+        vec![64, 64],
+        vec![&opening_1, &opening_2],
+    )
+    .unwrap()
+}
+
+fn range_proof_u256_data() -> BatchedRangeProofU256Data {
+    let amount_1 = 4294967295_u64;
+    let amount_2 = 77_u64;
+
+    let (commitment_1, opening_1) = Pedersen::new(amount_1);
+    let (commitment_2, opening_2) = Pedersen::new(amount_2);
+
+    BatchedRangeProofU256Data::new(
+        vec![&commitment_1, &commitment_2],
+        vec![amount_1, amount_2],
+        vec![128, 128],
+        vec![&opening_1, &opening_2],
+    )
+    .unwrap()
+}
+
+fn ciphertext_commitment_equality_data() -> CiphertextCommitmentEqualityProofData {
+    let keypair = ElGamalKeypair::new_rand();
+    let amount: u64 = 55;
+    let ciphertext = keypair.pubkey().encrypt(amount);
+    let (commitment, opening) = Pedersen::new(amount);
+
+    CiphertextCommitmentEqualityProofData::new(&keypair, &ciphertext, &commitment, &opening, amount)
+        .unwrap()
+}
+
+fn ciphertext_ciphertext_equality_data() -> CiphertextCiphertextEqualityProofData {
+    let first_keypair = ElGamalKeypair::new_rand();
+    let second_keypair = ElGamalKeypair::new_rand();
+
+    let amount: u64 = 55;
+    let first_ciphertext = first_keypair.pubkey().encrypt(amount);
+    let second_opening = PedersenOpening::new_rand();
+    let second_ciphertext = second_keypair
+        .pubkey()
+        .encrypt_with(amount, &second_opening);
+
+    CiphertextCiphertextEqualityProofData::new(
+        &first_keypair,
+        second_keypair.pubkey(),
+        &first_ciphertext,
+        &second_ciphertext,
+        &second_opening,
+        amount,
+    )
+    .unwrap()
+}
+
+fn bench_proof_batch<T, U>(c: &mut Criterion, name: &str, new_proof_data: fn() -> T)
+where
+    T: BatchableZkProofData<U>,
+    U: bytemuck::Pod,
+{
+    let mut group = c.benchmark_group(name);
+    for batch_size in BATCH_SIZES {
+        let proofs: Vec<T> = (0..batch_size).map(|_| new_proof_data()).collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("individual", batch_size),
+            &proofs,
+            |b, proofs| {
+                b.iter(|| {
+                    for proof_data in proofs {
+                        proof_data.verify_proof().unwrap();
+                    }
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("batched", batch_size),
+            &proofs,
+            |b, proofs| {
+                b.iter(|| {
+                    let mut batch = BatchVerifier::new();
+                    for proof_data in proofs {
+                        proof_data.append_to_batch(&mut batch).unwrap();
+                    }
+                    batch.verify().unwrap();
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_batched_range_proof_u64(c: &mut Criterion) {
+    bench_proof_batch(c, "batched_range_proof_u64", range_proof_u64_data);
+}
+
+fn bench_batched_range_proof_u128(c: &mut Criterion) {
+    bench_proof_batch(c, "batched_range_proof_u128", range_proof_u128_data);
+}
+
+fn bench_batched_range_proof_u256(c: &mut Criterion) {
+    bench_proof_batch(c, "batched_range_proof_u256", range_proof_u256_data);
+}
+
+fn bench_ciphertext_commitment_equality(c: &mut Criterion) {
+    bench_proof_batch(
+        c,
+        "ciphertext_commitment_equality",
+        ciphertext_commitment_equality_data,
+    );
+}
+
+fn bench_ciphertext_ciphertext_equality(c: &mut Criterion) {
+    bench_proof_batch(
+        c,
+        "ciphertext_ciphertext_equality",
+        ciphertext_ciphertext_equality_data,
+    );
+}
+
+fn bench_mixed_proof_batch(c: &mut Criterion) {
+    let range_proof = range_proof_u64_data();
+    let commitment_equality_proof = ciphertext_commitment_equality_data();
+    let ciphertext_equality_proof = ciphertext_ciphertext_equality_data();
+
+    let mut group = c.benchmark_group("mixed_proof_batch");
+    group.bench_function("individual", |b| {
+        b.iter(|| {
+            range_proof.verify_proof().unwrap();
+            commitment_equality_proof.verify_proof().unwrap();
+            ciphertext_equality_proof.verify_proof().unwrap();
+        })
+    });
+    group.bench_function("batched", |b| {
+        b.iter(|| {
+            let mut batch = BatchVerifier::new();
+            range_proof.append_to_batch(&mut batch).unwrap();
+            commitment_equality_proof
+                .append_to_batch(&mut batch)
+                .unwrap();
+            ciphertext_equality_proof
+                .append_to_batch(&mut batch)
+                .unwrap();
+            batch.verify().unwrap();
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_batched_range_proof_u64,
+    bench_batched_range_proof_u128,
+    bench_batched_range_proof_u256,
+    bench_ciphertext_commitment_equality,
+    bench_ciphertext_ciphertext_equality,
+    bench_mixed_proof_batch,
+);
+criterion_main!(benches);
@@ -1,4 +1,5 @@
 use {
+    agave_feature_set::enable_zk_proof_batch_verification,
     bytemuck::{bytes_of, Pod},
     solana_account::Account,
     solana_instruction::error::InstructionError,
@@ -15,7 +16,12 @@ use {
             grouped_elgamal::GroupedElGamal,
             pedersen::{Pedersen, PedersenOpening},
         },
-        zk_elgamal_proof_program::{self, instruction::*, proof_data::*, state::ProofContextState},
+        zk_elgamal_proof_program::{
+            self,
+            instruction::*,
+            proof_data::*,
+            state::{ProofContextState, ProofContextStateMeta},
+        },
     },
     std::mem::size_of,
 };
@@ -702,6 +708,339 @@ async fn test_batched_grouped_ciphertext_3_handles_validity() {
     .await;
 }
 
+#[tokio::test]
+async fn test_verify_proof_batch() {
+    let keypair = ElGamalKeypair::new_rand();
+    let amount: u64 = 55;
+    let ciphertext = keypair.pubkey().encrypt(amount);
+    let (commitment, opening) = Pedersen::new(amount);
+    let commitment_equality_proof_data = CiphertextCommitmentEqualityProofData::new(
+        &keypair,
+        &ciphertext,
+        &commitment,
+        &opening,
+        amount,
+    )
+    .unwrap();
+
+    let (incorrect_commitment, incorrect_opening) = Pedersen::new(0_u64);
+    let fail_commitment_equality_proof_data = CiphertextCommitmentEqualityProofData::new(
+        &keypair,
+        &ciphertext,
+        &incorrect_commitment,
+        &incorrect_opening,
+        amount,
+    )
+    .unwrap();
+
+    let second_keypair = ElGamalKeypair::new_rand();
+    let second_opening = PedersenOpening::new_rand();
+    let second_ciphertext = second_keypair
+        .pubkey()
+        .encrypt_with(amount, &second_opening);
+    let ciphertext_equality_proof_data = CiphertextCiphertextEqualityProofData::new(
+        &keypair,
+        second_keypair.pubkey(),
+        &ciphertext,
+        &second_ciphertext,
+        &second_opening,
+        amount,
+    )
+    .unwrap();
+
+    let pubkey_validity_proof_data = PubkeyValidityProofData::new(&keypair).unwrap();
+
+    let commitment_equality_instruction = ProofInstruction::VerifyCiphertextCommitmentEquality
+        .encode_verify_proof(None, &commitment_equality_proof_data);
+    let fail_commitment_equality_instruction = ProofInstruction::VerifyCiphertextCommitmentEquality
+        .encode_verify_proof(None, &fail_commitment_equality_proof_data);
+    let ciphertext_equality_instruction = ProofInstruction::VerifyCiphertextCiphertextEquality
+        .encode_verify_proof(None, &ciphertext_equality_proof_data);
+    let pubkey_validity_instruction = ProofInstruction::VerifyPubkeyValidity
+        .encode_verify_proof(None, &pubkey_validity_proof_data);
+
+    let mut program_test = ProgramTest::default();
+    program_test.set_compute_max_units(500_000);
+    let mut context = program_test.start_with_context().await;
+
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+
+    // verify a batch of valid proofs
+    let instructions = vec![verify_proof_batch(&[
+        commitment_equality_instruction.clone(),
+        ciphertext_equality_instruction.clone(),
+    ])];
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions.with_max_compute_unit_limit(),
+        Some(&payer.pubkey()),
+        &[payer],
+        client.get_latest_blockhash().await.unwrap(),
+    );
+    let batch_compute_units = client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap()
+        .metadata
+        .unwrap()
+        .compute_units_consumed;
+
+    // verifying the proofs as a batch is cheaper than verifying them individually
+    let mut individual_compute_units = 0;
+    for instruction in [
+        commitment_equality_instruction.clone(),
+        ciphertext_equality_instruction.clone(),
+    ] {
+        let transaction = Transaction::new_signed_with_payer(
+            &vec![instruction].with_max_compute_unit_limit(),
+            Some(&payer.pubkey()),
+            &[payer],
+            client.get_latest_blockhash().await.unwrap(),
+        );
+        individual_compute_units += client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap()
+            .metadata
+            .unwrap()
+            .compute_units_consumed;
+    }
+    assert!(batch_compute_units < individual_compute_units);
+
+    // try to verify a batch that contains an invalid proof, a proof that cannot be batched, a
+    // single proof, or truncated proof data
+    let mut truncated_instruction = verify_proof_batch(&[
+        commitment_equality_instruction.clone(),
+        ciphertext_equality_instruction.clone(),
+    ]);
+    truncated_instruction.data.pop();
+
+    for instruction in [
+        verify_proof_batch(&[
+            fail_commitment_equality_instruction,
+            ciphertext_equality_instruction.clone(),
+        ]),
+        verify_proof_batch(&[
+            commitment_equality_instruction.clone(),
+            pubkey_validity_instruction,
+        ]),
+        verify_proof_batch(&[commitment_equality_instruction]),
+        truncated_instruction,
+    ] {
+        let transaction = Transaction::new_signed_with_payer(
+            &vec![instruction].with_max_compute_unit_limit(),
+            Some(&payer.pubkey()),
+            &[payer],
+            client.get_latest_blockhash().await.unwrap(),
+        );
+        let err = client
+            .process_transaction(transaction)
+            .await
+            .unwrap_err()
+            .unwrap();
+        assert_eq!(
+            err,
+            TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_verify_proof_batch_with_context() {
+    let keypair = ElGamalKeypair::new_rand();
+    let amount: u64 = 55;
+    let ciphertext = keypair.pubkey().encrypt(amount);
+    let (commitment, opening) = Pedersen::new(amount);
+    let commitment_equality_proof_data = CiphertextCommitmentEqualityProofData::new(
+        &keypair,
+        &ciphertext,
+        &commitment,
+        &opening,
+        amount,
+    )
+    .unwrap();
+
+    let (range_commitment, range_opening) = Pedersen::new(amount);
+    let range_proof_data = BatchedRangeProofU64Data::new(
+        vec![&range_commitment, &range_commitment],
+        vec![amount, amount],
+        vec![32, 32],
+        vec![&range_opening, &range_opening],
+    )
+    .unwrap();
+
+    let mut program_test = ProgramTest::default();
+    program_test.set_compute_max_units(500_000);
+    let mut context = program_test.start_with_context().await;
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+
+    let commitment_equality_account = Keypair::new();
+    let range_proof_account = Keypair::new();
+    let context_state_authority = Keypair::new();
+    let commitment_equality_space =
+        size_of::<ProofContextState<CiphertextCommitmentEqualityProofContext>>();
+    let range_proof_space = size_of::<ProofContextState<BatchedRangeProofContext>>();
+
+    let create_context_state_accounts = || {
+        vec![
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &commitment_equality_account.pubkey(),
+                rent.minimum_balance(commitment_equality_space),
+                commitment_equality_space as u64,
+                &zk_elgamal_proof_program::id(),
+            ),
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &range_proof_account.pubkey(),
+                rent.minimum_balance(range_proof_space),
+                range_proof_space as u64,
+                &zk_elgamal_proof_program::id(),
+            ),
+        ]
+    };
+    let commitment_equality_instruction = ProofInstruction::VerifyCiphertextCommitmentEquality
+        .encode_verify_proof(
+            Some(ContextStateInfo {
+                context_state_account: &commitment_equality_account.pubkey(),
+                context_state_authority: &context_state_authority.pubkey(),
+            }),
+            &commitment_equality_proof_data,
+        );
+    let range_proof_instruction = ProofInstruction::VerifyBatchedRangeProofU64.encode_verify_proof(
+        Some(ContextStateInfo {
+            context_state_account: &range_proof_account.pubkey(),
+            context_state_authority: &context_state_authority.pubkey(),
+        }),
+        &range_proof_data,
+    );
+
+    // try to verify a batch with a context state account for only some of the proofs
+    let mut instructions = create_context_state_accounts();
+    instructions.push(verify_proof_batch(&[
+        commitment_equality_instruction.clone(),
+        ProofInstruction::VerifyBatchedRangeProofU64.encode_verify_proof(None, &range_proof_data),
+    ]));
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions.with_max_compute_unit_limit(),
+        Some(&payer.pubkey()),
+        &[payer, &commitment_equality_account, &range_proof_account],
+        client.get_latest_blockhash().await.unwrap(),
+    );
+    let err = client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(2, InstructionError::InvalidInstructionData)
+    );
+
+    // verify a batch and store the context state of each proof
+    let mut instructions = create_context_state_accounts();
+    instructions.push(verify_proof_batch(&[
+        commitment_equality_instruction.clone(),
+        range_proof_instruction.clone(),
+    ]));
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions.with_max_compute_unit_limit(),
+        Some(&payer.pubkey()),
+        &[payer, &commitment_equality_account, &range_proof_account],
+        client.get_latest_blockhash().await.unwrap(),
+    );
+    client.process_transaction(transaction).await.unwrap();
+
+    for (context_state_account, proof_type) in [
+        (
+            commitment_equality_account.pubkey(),
+            ProofType::CiphertextCommitmentEquality,
+        ),
+        (
+            range_proof_account.pubkey(),
+            ProofType::BatchedRangeProofU64,
+        ),
+    ] {
+        let account = client
+            .get_account(context_state_account)
+            .await
+            .unwrap()
+            .unwrap();
+        let context_state_meta = ProofContextStateMeta::try_from_bytes(&account.data).unwrap();
+        assert_eq!(
+            context_state_meta.context_state_authority,
+            context_state_authority.pubkey()
+        );
+        assert_eq!(context_state_meta.proof_type, proof_type.into());
+    }
+
+    // try overwriting the context states
+    let instructions = vec![verify_proof_batch(&[
+        commitment_equality_instruction,
+        range_proof_instruction,
+    ])];
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions.with_max_compute_unit_limit(),
+        Some(&payer.pubkey()),
+        &[payer],
+        client.get_latest_blockhash().await.unwrap(),
+    );
+    let err = client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::AccountAlreadyInitialized)
+    );
+}
+
+#[tokio::test]
+async fn test_verify_proof_batch_feature_disabled() {
+    let keypair = ElGamalKeypair::new_rand();
+    let amount: u64 = 55;
+    let ciphertext = keypair.pubkey().encrypt(amount);
+    let (commitment, opening) = Pedersen::new(amount);
+    let proof_data = CiphertextCommitmentEqualityProofData::new(
+        &keypair,
+        &ciphertext,
+        &commitment,
+        &opening,
+        amount,
+    )
+    .unwrap();
+    let instruction =
+        ProofInstruction::VerifyCiphertextCommitmentEquality.encode_verify_proof(None, &proof_data);
+
+    let mut program_test = ProgramTest::default();
+    program_test.deactivate_feature(enable_zk_proof_batch_verification::id());
+    let mut context = program_test.start_with_context().await;
+
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+
+    let instructions = vec![verify_proof_batch(&[instruction.clone(), instruction])];
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions.with_max_compute_unit_limit(),
+        Some(&payer.pubkey()),
+        &[payer],
+        client.get_latest_blockhash().await.unwrap(),
+    );
+    let err = client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+}
+
 async fn test_verify_proof_without_context<T, U>(
     proof_instruction: ProofInstruction,
     success_proof_data: &T,
@@ -5,12 +5,17 @@ use {
     solana_instruction::error::InstructionError,
     solana_log_collector::ic_msg,
     solana_program_runtime::{declare_process_instruction, invoke_context::InvokeContext},
+    solana_pubkey::Pubkey,
     solana_sdk_ids::system_program,
-    solana_zk_sdk::zk_elgamal_proof_program::{
-        id,
-        instruction::ProofInstruction,
-        proof_data::*,
-        state::{ProofContextState, ProofContextStateMeta},
+    solana_transaction_context::{IndexOfAccount, InstructionContext, TransactionContext},
+    solana_zk_sdk::{
+        batch_verifier::BatchVerifier,
+        zk_elgamal_proof_program::{
+            id,
+            instruction::ProofInstruction,
+            proof_data::*,
+            state::{ProofContextState, ProofContextStateMeta},
+        },
     },
     std::result::Result,
 };
@@ -29,8 +34,36 @@ pub const VERIFY_BATCHED_GROUPED_CIPHERTEXT_2_HANDLES_VALIDITY_COMPUTE_UNITS: u6
 pub const VERIFY_GROUPED_CIPHERTEXT_3_HANDLES_VALIDITY_COMPUTE_UNITS: u64 = 8_100;
 pub const VERIFY_BATCHED_GROUPED_CIPHERTEXT_3_HANDLES_VALIDITY_COMPUTE_UNITS: u64 = 16_400;
 
+// The cost of a `VerifyProofBatch` instruction is the base cost plus the batched cost of each proof
+// in the batch.
+//
+// The batched cost of a proof is the cost of its individual verification instruction scaled by
+// the time per proof of verifying a batch of 8 relative to verifying the proofs one by one, as
+// measured with
+//
+//   cargo bench -p solana-zk-elgamal-proof-program-tests --bench verify_proof_batch
+//
+// rounded to the nearest hundred units. The constants below encode these ratios:
+//   ciphertext-ciphertext equality  5_600 /   8_000 = 0.70
+//   ciphertext-commitment equality  4_500 /   6_400 = 0.70
+//   batched range proof u64        84_000 / 111_000 = 0.76
+//   batched range proof u128      150_000 / 200_000 = 0.75
+//   batched range proof u256      276_000 / 368_000 = 0.75
+// The u256 constant still carries the u128 ratio and must be replaced by the ratio of the
+// `batched_range_proof_u256` benchmark group. Re-run the benchmark and update the constants
+// together with this table whenever the verifier changes.
+pub const VERIFY_PROOF_BATCH_BASE_COMPUTE_UNITS: u64 = 3_000;
+pub const BATCHED_CIPHERTEXT_CIPHERTEXT_EQUALITY_COMPUTE_UNITS: u64 = 5_600;
+pub const BATCHED_CIPHERTEXT_COMMITMENT_EQUALITY_COMPUTE_UNITS: u64 = 4_500;
+pub const BATCHED_RANGE_PROOF_U64_COMPUTE_UNITS: u64 = 84_000;
+pub const BATCHED_RANGE_PROOF_U128_COMPUTE_UNITS: u64 = 150_000;
+pub const BATCHED_RANGE_PROOF_U256_COMPUTE_UNITS: u64 = 276_000;
+
 const INSTRUCTION_DATA_LENGTH_WITH_PROOF_ACCOUNT: usize = 5;
 
+/// A single proof is verified with its own proof verification instruction.
+const MIN_PROOF_BATCH_SIZE: usize = 2;
+
 fn process_verify_proof<T, U>(invoke_context: &mut InvokeContext) -> Result<(), InstructionError>
 where
     T: Pod + ZkProofData<U>,
@@ -92,40 +125,265 @@ where
 
     // create context state if additional accounts are provided with the instruction
     if instruction_context.get_number_of_instruction_accounts() > accessed_accounts {
-        let context_state_authority = *instruction_context
-            .try_borrow_instruction_account(
-                transaction_context,
-                accessed_accounts.checked_add(1).unwrap(),
-            )?
-            .get_key();
+        let context_state_authority = get_context_state_authority(
+            transaction_context,
+            instruction_context,
+            accessed_accounts,
+        )?;
+        let context_state_data =
+            ProofContextState::encode(&context_state_authority, T::PROOF_TYPE, &context_data);
+        store_proof_context_state(
+            transaction_context,
+            instruction_context,
+            accessed_accounts,
+            &context_state_data,
+        )?;
+    }
 
-        let mut proof_context_account = instruction_context
-            .try_borrow_instruction_account(transaction_context, accessed_accounts)?;
+    Ok(())
+}
 
-        if *proof_context_account.get_owner() != id() {
-            return Err(InstructionError::InvalidAccountOwner);
-        }
+/// Returns the authority of the proof context account at `index_in_instruction`, which is the
+/// next instruction account.
+fn get_context_state_authority(
+    transaction_context: &TransactionContext,
+    instruction_context: &InstructionContext,
+    index_in_instruction: IndexOfAccount,
+) -> Result<Pubkey, InstructionError> {
+    Ok(*instruction_context
+        .try_borrow_instruction_account(
+            transaction_context,
+            index_in_instruction.checked_add(1).unwrap(),
+        )?
+        .get_key())
+}
 
-        let proof_context_state_meta =
-            ProofContextStateMeta::try_from_bytes(proof_context_account.get_data())?;
+/// Stores the encoded context state of a verified proof in the uninitialized proof context account
+/// at `index_in_instruction`.
+fn store_proof_context_state(
+    transaction_context: &TransactionContext,
+    instruction_context: &InstructionContext,
+    index_in_instruction: IndexOfAccount,
+    context_state_data: &[u8],
+) -> Result<(), InstructionError> {
+    let mut proof_context_account = instruction_context
+        .try_borrow_instruction_account(transaction_context, index_in_instruction)?;
 
-        if proof_context_state_meta.proof_type != ProofType::Uninitialized.into() {
-            return Err(InstructionError::AccountAlreadyInitialized);
-        }
+    if *proof_context_account.get_owner() != id() {
+        return Err(InstructionError::InvalidAccountOwner);
+    }
 
-        let context_state_data =
-            ProofContextState::encode(&context_state_authority, T::PROOF_TYPE, &context_data);
+    let proof_context_state_meta =
+        ProofContextStateMeta::try_from_bytes(proof_context_account.get_data())?;
+
+    if proof_context_state_meta.proof_type != ProofType::Uninitialized.into() {
+        return Err(InstructionError::AccountAlreadyInitialized);
+    }
 
-        if proof_context_account.get_data().len() != context_state_data.len() {
-            return Err(InstructionError::InvalidAccountData);
+    if proof_context_account.get_data().len() != context_state_data.len() {
+        return Err(InstructionError::InvalidAccountData);
+    }
+
+    proof_context_account.set_data_from_slice(context_state_data)
+}
+
+/// Returns the compute units charged for a proof that is verified as part of a `VerifyProofBatch`
+/// instruction, or `None` if the proof cannot be batched.
+fn batched_proof_compute_units(proof_instruction: ProofInstruction) -> Option<u64> {
+    match proof_instruction {
+        ProofInstruction::VerifyCiphertextCiphertextEquality => {
+            Some(BATCHED_CIPHERTEXT_CIPHERTEXT_EQUALITY_COMPUTE_UNITS)
+        }
+        ProofInstruction::VerifyCiphertextCommitmentEquality => {
+            Some(BATCHED_CIPHERTEXT_COMMITMENT_EQUALITY_COMPUTE_UNITS)
+        }
+        ProofInstruction::VerifyBatchedRangeProofU64 => Some(BATCHED_RANGE_PROOF_U64_COMPUTE_UNITS),
+        ProofInstruction::VerifyBatchedRangeProofU128 => {
+            Some(BATCHED_RANGE_PROOF_U128_COMPUTE_UNITS)
+        }
+        ProofInstruction::VerifyBatchedRangeProofU256 => {
+            Some(BATCHED_RANGE_PROOF_U256_COMPUTE_UNITS)
         }
+        _ => None,
+    }
+}
+
+/// Adds a proof to `batch` and returns its encoded context state if it is to be stored for
+/// `context_state_authority`.
+fn append_proof_to_batch<T, U>(
+    invoke_context: &InvokeContext,
+    proof_data_raw: &[u8],
+    context_state_authority: Option<&Pubkey>,
+    batch: &mut BatchVerifier,
+) -> Result<Option<Vec<u8>>, InstructionError>
+where
+    T: Pod + BatchableZkProofData<U>,
+    U: Pod,
+{
+    let proof_data = bytemuck::try_from_bytes::<T>(proof_data_raw).map_err(|_| {
+        ic_msg!(invoke_context, "invalid proof data");
+        InstructionError::InvalidInstructionData
+    })?;
+    proof_data.append_to_batch(batch).map_err(|err| {
+        ic_msg!(invoke_context, "proof verification failed: {:?}", err);
+        InstructionError::InvalidInstructionData
+    })?;
+    Ok(context_state_authority.map(|context_state_authority| {
+        ProofContextState::encode(
+            context_state_authority,
+            T::PROOF_TYPE,
+            proof_data.context_data(),
+        )
+    }))
+}
+
+fn process_verify_proof_batch(invoke_context: &mut InvokeContext) -> Result<(), InstructionError> {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let instruction_data = instruction_context.get_instruction_data();
+
+    // split the batch into proofs and price the batch before verifying any of the proofs
+    let mut proofs = vec![];
+    let mut compute_units = VERIFY_PROOF_BATCH_BASE_COMPUTE_UNITS;
+    // the first byte is the instruction discriminator
+    let mut remaining_data = instruction_data.get(1..).unwrap_or_default();
+    while !remaining_data.is_empty() {
+        let proof_instruction = ProofInstruction::instruction_type(remaining_data)
+            .ok_or(InstructionError::InvalidInstructionData)?;
+        let (proof_data_len, proof_compute_units) = proof_instruction
+            .batched_proof_data_len()
+            .zip(batched_proof_compute_units(proof_instruction))
+            .ok_or_else(|| {
+                ic_msg!(invoke_context, "{:?} cannot be batched", proof_instruction);
+                InstructionError::InvalidInstructionData
+            })?;
+
+        // the first byte is the proof instruction discriminator
+        let proof_data_end = proof_data_len
+            .checked_add(1)
+            .ok_or(InstructionError::InvalidInstructionData)?;
+        let proof_data_raw = remaining_data.get(1..proof_data_end).ok_or_else(|| {
+            ic_msg!(invoke_context, "invalid proof data");
+            InstructionError::InvalidInstructionData
+        })?;
+
+        proofs.push((proof_instruction, proof_data_raw));
+        compute_units = compute_units.saturating_add(proof_compute_units);
+        remaining_data = &remaining_data[proof_data_end..];
+    }
+
+    if proofs.len() < MIN_PROOF_BATCH_SIZE {
+        ic_msg!(
+            invoke_context,
+            "proof batch must contain at least {} proofs",
+            MIN_PROOF_BATCH_SIZE
+        );
+        return Err(InstructionError::InvalidInstructionData);
+    }
+
+    // the context states are stored if a context state account and its authority are provided
+    // for each proof
+    let number_of_accounts = usize::from(instruction_context.get_number_of_instruction_accounts());
+    let stores_context_states = number_of_accounts > 0;
+    if stores_context_states && number_of_accounts != proofs.len().saturating_mul(2) {
+        ic_msg!(
+            invoke_context,
+            "proof batch must provide a context state account and authority for each proof"
+        );
+        return Err(InstructionError::InvalidInstructionData);
+    }
 
-        proof_context_account.set_data_from_slice(&context_state_data)?;
+    invoke_context
+        .consume_checked(compute_units)
+        .map_err(|_| InstructionError::ComputationalBudgetExceeded)?;
+
+    let mut batch = BatchVerifier::new();
+    let mut context_states = Vec::with_capacity(proofs.len());
+    for (index, (proof_instruction, proof_data_raw)) in proofs.into_iter().enumerate() {
+        let context_state_authority = if stores_context_states {
+            Some(get_context_state_authority(
+                transaction_context,
+                instruction_context,
+                context_state_account_index(index)?,
+            )?)
+        } else {
+            None
+        };
+        let context_state_authority = context_state_authority.as_ref();
+        let context_state = match proof_instruction {
+            ProofInstruction::VerifyCiphertextCiphertextEquality => append_proof_to_batch::<
+                CiphertextCiphertextEqualityProofData,
+                CiphertextCiphertextEqualityProofContext,
+            >(
+                invoke_context,
+                proof_data_raw,
+                context_state_authority,
+                &mut batch,
+            )?,
+            ProofInstruction::VerifyCiphertextCommitmentEquality => append_proof_to_batch::<
+                CiphertextCommitmentEqualityProofData,
+                CiphertextCommitmentEqualityProofContext,
+            >(
+                invoke_context,
+                proof_data_raw,
+                context_state_authority,
+                &mut batch,
+            )?,
+            ProofInstruction::VerifyBatchedRangeProofU64 => {
+                append_proof_to_batch::<BatchedRangeProofU64Data, BatchedRangeProofContext>(
+                    invoke_context,
+                    proof_data_raw,
+                    context_state_authority,
+                    &mut batch,
+                )?
+            }
+            ProofInstruction::VerifyBatchedRangeProofU128 => {
+                append_proof_to_batch::<BatchedRangeProofU128Data, BatchedRangeProofContext>(
+                    invoke_context,
+                    proof_data_raw,
+                    context_state_authority,
+                    &mut batch,
+                )?
+            }
+            ProofInstruction::VerifyBatchedRangeProofU256 => {
+                append_proof_to_batch::<BatchedRangeProofU256Data, BatchedRangeProofContext>(
+                    invoke_context,
+                    proof_data_raw,
+                    context_state_authority,
+                    &mut batch,
+                )?
+            }
+            _ => return Err(InstructionError::InvalidInstructionData),
+        };
+        context_states.push(context_state);
     }
 
+    batch.verify().map_err(|err| {
+        ic_msg!(invoke_context, "proof verification failed: {:?}", err);
+        InstructionError::InvalidInstructionData
+    })?;
+
+    for (index, context_state_data) in context_states.iter().enumerate() {
+        if let Some(context_state_data) = context_state_data {
+            store_proof_context_state(
+                transaction_context,
+                instruction_context,
+                context_state_account_index(index)?,
+                context_state_data,
+            )?;
+        }
+    }
     Ok(())
 }
 
+/// Returns the index of the proof context account of the proof at `proof_index` in a batch.
+fn context_state_account_index(proof_index: usize) -> Result<IndexOfAccount, InstructionError> {
+    proof_index
+        .checked_mul(2)
+        .and_then(|index| IndexOfAccount::try_from(index).ok())
+        .ok_or(InstructionError::InvalidInstructionData)
+}
+
 fn process_close_proof_context(invoke_context: &mut InvokeContext) -> Result<(), InstructionError> {
     let transaction_context = &invoke_context.transaction_context;
     let instruction_context = transaction_context.get_current_instruction_context()?;
@@ -319,5 +577,15 @@ declare_process_instruction!(Entrypoint, 0, |invoke_context| {
                 BatchedGroupedCiphertext3HandlesValidityProofContext,
             >(invoke_context)
         }
+        ProofInstruction::VerifyProofBatch => {
+            if !invoke_context
+                .get_feature_set()
+                .enable_zk_proof_batch_verification
+            {
+                return Err(InstructionError::InvalidInstructionData);
+            }
+            ic_msg!(invoke_context, "VerifyProofBatch");
+            process_verify_proof_batch(invoke_context)
+        }
     }
 });
@@ -1,9 +1,9 @@
 use {
     crate::remote_wallet::{
-        RemoteWallet, RemoteWalletError, RemoteWalletInfo, RemoteWalletManager,
+        select_host_device_path, RemoteWallet, RemoteWalletError, RemoteWalletInfo,
+        RemoteWalletManager,
     },
     console::Emoji,
-    dialoguer::{theme::ColorfulTheme, Select},
     semver::Version as FirmwareVersion,
     solana_derivation_path::DerivationPath,
     std::{fmt, rc::Rc},
@@ -606,46 +606,8 @@ pub fn get_ledger_from_info(
     keypair_name: &str,
     wallet_manager: &RemoteWalletManager,
 ) -> Result<Rc<LedgerWallet>, RemoteWalletError> {
-    let devices = wallet_manager.list_devices();
-    let mut matches = devices
-        .iter()
-        .filter(|&device_info| device_info.matches(&info));
-    if matches
-        .clone()
-        .all(|device_info| device_info.error.is_some())
-    {
-        let first_device = matches.next();
-        if let Some(device) = first_device {
-            return Err(device.error.clone().unwrap());
-        }
-    }
-    let mut matches: Vec<(String, String)> = matches
-        .filter(|&device_info| device_info.error.is_none())
-        .map(|device_info| {
-            let query_item = format!("{} ({})", device_info.get_pretty_path(), device_info.model,);
-            (device_info.host_device_path.clone(), query_item)
-        })
-        .collect();
-    if matches.is_empty() {
-        return Err(RemoteWalletError::NoDeviceFound);
-    }
-    matches.sort_by(|a, b| a.1.cmp(&b.1));
-    let (host_device_paths, items): (Vec<String>, Vec<String>) = matches.into_iter().unzip();
-
-    let wallet_host_device_path = if host_device_paths.len() > 1 {
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt(format!(
-                "Multiple hardware wallets found. Please select a device for {keypair_name:?}"
-            ))
-            .default(0)
-            .items(&items[..])
-            .interact()
-            .unwrap();
-        &host_device_paths[selection]
-    } else {
-        &host_device_paths[0]
-    };
-    wallet_manager.get_ledger(wallet_host_device_path)
+    let host_device_path = select_host_device_path(info, keypair_name, wallet_manager)?;
+    wallet_manager.get_ledger(&host_device_path)
 }
 
 //
@@ -5,3 +5,4 @@ pub mod ledger_error;
 pub mod locator;
 pub mod remote_keypair;
 pub mod remote_wallet;
+pub mod trezor;
@@ -1,11 +1,19 @@
+#[cfg(all(feature = "hidapi", feature = "rusb"))]
+use crate::trezor::open_webusb_devices;
 #[cfg(feature = "hidapi")]
-use {crate::ledger::is_valid_ledger, parking_lot::Mutex, std::sync::Arc};
+use {
+    crate::{ledger::is_valid_ledger, trezor::is_valid_trezor},
+    parking_lot::Mutex,
+    std::sync::Arc,
+};
 use {
     crate::{
         ledger::LedgerWallet,
         ledger_error::LedgerError,
         locator::{Locator, LocatorError, Manufacturer},
+        trezor::TrezorWallet,
     },
+    dialoguer::{theme::ColorfulTheme, Select},
     log::*,
     parking_lot::RwLock,
     solana_derivation_path::{DerivationPath, DerivationPathError},
@@ -28,6 +36,9 @@ pub enum RemoteWalletError {
     #[error("hidapi error")]
     Hid(String),
 
+    #[error("libusb error: {0}")]
+    Usb(String),
+
     #[error("device type mismatch")]
     DeviceTypeMismatch,
 
@@ -46,6 +57,9 @@ pub enum RemoteWalletError {
     #[error(transparent)]
     LedgerError(#[from] LedgerError),
 
+    #[error("Trezor failure: {0}")]
+    TrezorFailure(String),
+
     #[error("no device found")]
     NoDeviceFound,
 
@@ -69,14 +83,23 @@ impl From<hidapi::HidError> for RemoteWalletError {
     }
 }
 
+#[cfg(feature = "rusb")]
+impl From<rusb::Error> for RemoteWalletError {
+    fn from(err: rusb::Error) -> RemoteWalletError {
+        RemoteWalletError::Usb(err.to_string())
+    }
+}
+
 impl From<RemoteWalletError> for SignerError {
     fn from(err: RemoteWalletError) -> SignerError {
         match err {
             RemoteWalletError::Hid(hid_error) => SignerError::Connection(hid_error),
+            RemoteWalletError::Usb(usb_error) => SignerError::Connection(usb_error),
             RemoteWalletError::DeviceTypeMismatch => SignerError::Connection(err.to_string()),
             RemoteWalletError::InvalidDevice => SignerError::Connection(err.to_string()),
             RemoteWalletError::InvalidInput(input) => SignerError::InvalidInput(input),
             RemoteWalletError::LedgerError(e) => SignerError::Protocol(e.to_string()),
+            RemoteWalletError::TrezorFailure(e) => SignerError::Protocol(e),
             RemoteWalletError::NoDeviceFound => SignerError::NoDeviceFound,
             RemoteWalletError::Protocol(e) => SignerError::Protocol(e.to_string()),
             RemoteWalletError::UserCancel => {
@@ -117,31 +140,81 @@ impl RemoteWalletManager {
         let mut errors = vec![];
         for device_info in devices.filter(|&device_info| {
             is_valid_hid_device(device_info.usage_page(), device_info.interface_number())
-                && is_valid_ledger(device_info.vendor_id(), device_info.product_id())
+                && (is_valid_ledger(device_info.vendor_id(), device_info.product_id())
+                    || is_valid_trezor(device_info.vendor_id(), device_info.product_id()))
         }) {
+            let is_ledger = is_valid_ledger(device_info.vendor_id(), device_info.product_id());
+            let device_name = if is_ledger { "ledger" } else { "trezor" };
             match usb.open_path(device_info.path()) {
                 Ok(device) => {
-                    let mut ledger = LedgerWallet::new(device);
-                    let result = ledger.read_device(device_info);
-                    match result {
-                        Ok(info) => {
+                    let result = if is_ledger {
+                        let mut ledger = LedgerWallet::new(device);
+                        ledger.read_device(device_info).map(|info| {
                             ledger.pretty_path = info.get_pretty_path();
+                            (info, RemoteWalletType::Ledger(Rc::new(ledger)))
+                        })
+                    } else {
+                        let mut trezor = TrezorWallet::new(Box::new(device));
+                        trezor.read_device(device_info).map(|info| {
+                            trezor.pretty_path = info.get_pretty_path();
+                            (info, RemoteWalletType::Trezor(Rc::new(trezor)))
+                        })
+                    };
+                    match result {
+                        Ok((info, wallet_type)) => {
                             let path = device_info.path().to_str().unwrap().to_string();
                             trace!("Found device: {:?}", info);
                             detected_devices.push(Device {
                                 path,
                                 info,
-                                wallet_type: RemoteWalletType::Ledger(Rc::new(ledger)),
+                                wallet_type,
+                            })
+                        }
+                        Err(err) => {
+                            error!(
+                                "Error connecting to {} device to read info: {}",
+                                device_name, err
+                            );
+                            errors.push(err)
+                        }
+                    }
+                }
+                Err(err) => error!(
+                    "Error connecting to {} device to read info: {}",
+                    device_name, err
+                ),
+            }
+        }
+
+        // Trezor devices with WebUSB firmware have no HID interface
+        #[cfg(feature = "rusb")]
+        match open_webusb_devices() {
+            Ok(webusb_devices) => {
+                for (path, transport) in webusb_devices {
+                    let result = transport.and_then(|transport| {
+                        let mut trezor = TrezorWallet::new(Box::new(transport));
+                        trezor.read_info(path.clone()).map(|info| {
+                            trezor.pretty_path = info.get_pretty_path();
+                            (info, RemoteWalletType::Trezor(Rc::new(trezor)))
+                        })
+                    });
+                    match result {
+                        Ok((info, wallet_type)) => {
+                            trace!("Found device: {:?}", info);
+                            detected_devices.push(Device {
+                                path,
+                                info,
+                                wallet_type,
                             })
                         }
                         Err(err) => {
-                            error!("Error connecting to ledger device to read info: {}", err);
+                            error!("Error connecting to trezor device to read info: {}", err);
                             errors.push(err)
                         }
                     }
                 }
-                Err(err) => error!("Error connecting to ledger device to read info: {}", err),
             }
+            Err(err) => error!("Error listing WebUSB devices: {}", err),
         }
 
         let num_curr_devices = detected_devices.len();
@@ -183,6 +256,23 @@ impl RemoteWalletManager {
             })
     }
 
+    /// Get a particular Trezor wallet
+    #[allow(unreachable_patterns)]
+    pub fn get_trezor(
+        &self,
+        host_device_path: &str,
+    ) -> Result<Rc<TrezorWallet>, RemoteWalletError> {
+        self.devices
+            .read()
+            .iter()
+            .find(|device| device.info.host_device_path == host_device_path)
+            .ok_or(RemoteWalletError::PubkeyNotFound)
+            .and_then(|device| match &device.wallet_type {
+                RemoteWalletType::Trezor(trezor) => Ok(trezor.clone()),
+                _ => Err(RemoteWalletError::DeviceTypeMismatch),
+            })
+    }
+
     /// Get wallet info.
     pub fn get_wallet_info(&self, pubkey: &Pubkey) -> Option<RemoteWalletInfo> {
         self.devices
@@ -260,6 +350,7 @@ pub struct Device {
 #[derive(Debug)]
 pub enum RemoteWalletType {
     Ledger(Rc<LedgerWallet>),
+    Trezor(Rc<TrezorWallet>),
 }
 
 /// Remote wallet information.
@@ -300,6 +391,55 @@ impl RemoteWalletInfo {
     }
 }
 
+/// Choose the host device path of a wallet based on matching info fields, prompting the user
+/// if several wallets match
+pub(crate) fn select_host_device_path(
+    info: RemoteWalletInfo,
+    keypair_name: &str,
+    wallet_manager: &RemoteWalletManager,
+) -> Result<String, RemoteWalletError> {
+    let devices = wallet_manager.list_devices();
+    let mut matches = devices
+        .iter()
+        .filter(|&device_info| device_info.matches(&info));
+    if matches
+        .clone()
+        .all(|device_info| device_info.error.is_some())
+    {
+        let first_device = matches.next();
+        if let Some(device) = first_device {
+            return Err(device.error.clone().unwrap());
+        }
+    }
+    let mut matches: Vec<(String, String)> = matches
+        .filter(|&device_info| device_info.error.is_none())
+        .map(|device_info| {
+            let query_item = format!("{} ({})", device_info.get_pretty_path(), device_info.model,);
+            (device_info.host_device_path.clone(), query_item)
+        })
+        .collect();
+    if matches.is_empty() {
+        return Err(RemoteWalletError::NoDeviceFound);
+    }
+    matches.sort_by(|a, b| a.1.cmp(&b.1));
+    let (host_device_paths, items): (Vec<String>, Vec<String>) = matches.into_iter().unzip();
+
+    let wallet_host_device_path = if host_device_paths.len() > 1 {
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "Multiple hardware wallets found. Please select a device for {keypair_name:?}"
+            ))
+            .default(0)
+            .items(&items[..])
+            .interact()
+            .unwrap();
+        &host_device_paths[selection]
+    } else {
+        &host_device_paths[0]
+    };
+    Ok(wallet_host_device_path.clone())
+}
+
 /// Helper to determine if a device is a valid HID
 pub fn is_valid_hid_device(usage_page: u16, interface_number: i32) -> bool {
     usage_page == HID_GLOBAL_USAGE_PAGE || interface_number == HID_USB_DEVICE_CLASS as i32
@@ -0,0 +1,704 @@
+use {
+    crate::{
+        locator::Manufacturer,
+        remote_wallet::{
+            select_host_device_path, RemoteWallet, RemoteWalletError, RemoteWalletInfo,
+            RemoteWalletManager,
+        },
+    },
+    console::Emoji,
+    log::*,
+    semver::Version as FirmwareVersion,
+    solana_derivation_path::DerivationPath,
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    std::{convert::TryFrom, fmt, rc::Rc},
+};
+
+static CHECK_MARK: Emoji = Emoji("✅ ", "");
+
+/// Trezor One vendor and product IDs
+const TREZOR_ONE_VID: u16 = 0x534c;
+const TREZOR_ONE_PID: u16 = 0x0001;
+/// Trezor Model T, Safe 3 and Safe 5 vendor and product IDs
+const TREZOR_VID: u16 = 0x1209;
+const TREZOR_PID: u16 = 0x53c1;
+
+const REPORT_SIZE: usize = 64;
+const HID_PACKET_SIZE: usize = REPORT_SIZE + HID_PREFIX_ZERO;
+
+#[cfg(windows)]
+const HID_PREFIX_ZERO: usize = 1;
+#[cfg(not(windows))]
+const HID_PREFIX_ZERO: usize = 0;
+
+/// Every report starts with `?`, the first report of a message continues with `##`, the message
+/// type (2 bytes big endian) and the payload length (4 bytes big endian)
+const REPORT_MAGIC: u8 = b'?';
+const MESSAGE_MAGIC: [u8; 2] = [b'#', b'#'];
+const MESSAGE_HEADER_LEN: usize = 9;
+/// Responses are at most a few hundred bytes, anything larger is a protocol error
+const MAX_RESPONSE_LEN: usize = 64 * 1024;
+
+mod message_type {
+    pub const INITIALIZE: u16 = 0;
+    pub const FAILURE: u16 = 3;
+    pub const FEATURES: u16 = 17;
+    pub const PIN_MATRIX_REQUEST: u16 = 18;
+    pub const BUTTON_REQUEST: u16 = 26;
+    pub const BUTTON_ACK: u16 = 27;
+    pub const PASSPHRASE_REQUEST: u16 = 41;
+    pub const PASSPHRASE_ACK: u16 = 42;
+    pub const SOLANA_GET_PUBLIC_KEY: u16 = 900;
+    pub const SOLANA_PUBLIC_KEY: u16 = 901;
+    pub const SOLANA_SIGN_TX: u16 = 904;
+    pub const SOLANA_TX_SIGNATURE: u16 = 905;
+}
+
+/// `Failure` codes meaning the user rejected the operation
+const FAILURE_ACTION_CANCELLED: u64 = 4;
+const FAILURE_PIN_CANCELLED: u64 = 6;
+
+/// Minimal protobuf encoding, for the few messages of the Trezor wire protocol used here
+mod proto {
+    use crate::remote_wallet::RemoteWalletError;
+
+    const WIRE_TYPE_VARINT: u64 = 0;
+    const WIRE_TYPE_FIXED64: u64 = 1;
+    const WIRE_TYPE_LEN: u64 = 2;
+    const WIRE_TYPE_FIXED32: u64 = 5;
+
+    pub enum FieldValue<'a> {
+        Varint(u64),
+        Bytes(&'a [u8]),
+    }
+
+    fn encode_varint(buf: &mut Vec<u8>, mut value: u64) {
+        while value >= 0x80 {
+            buf.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        buf.push(value as u8);
+    }
+
+    pub fn encode_varint_field(buf: &mut Vec<u8>, field: u32, value: u64) {
+        encode_varint(buf, (u64::from(field) << 3) | WIRE_TYPE_VARINT);
+        encode_varint(buf, value);
+    }
+
+    pub fn encode_bytes_field(buf: &mut Vec<u8>, field: u32, value: &[u8]) {
+        encode_varint(buf, (u64::from(field) << 3) | WIRE_TYPE_LEN);
+        encode_varint(buf, value.len() as u64);
+        buf.extend_from_slice(value);
+    }
+
+    fn decode_varint(data: &mut &[u8]) -> Result<u64, RemoteWalletError> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = data
+                .split_first()
+                .ok_or(RemoteWalletError::Protocol("Truncated varint"))?;
+            *data = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(RemoteWalletError::Protocol("Varint too long"))
+    }
+
+    fn split_off<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], RemoteWalletError> {
+        if data.len() < len {
+            return Err(RemoteWalletError::Protocol("Truncated field"));
+        }
+        let (value, rest) = data.split_at(len);
+        *data = rest;
+        Ok(value)
+    }
+
+    /// Decodes the fields of a message, skipping the fixed size ones
+    pub fn decode_fields(mut data: &[u8]) -> Result<Vec<(u32, FieldValue)>, RemoteWalletError> {
+        let mut fields = vec![];
+        while !data.is_empty() {
+            let key = decode_varint(&mut data)?;
+            let field = u32::try_from(key >> 3)
+                .map_err(|_| RemoteWalletError::Protocol("Invalid field number"))?;
+            match key & 0x7 {
+                WIRE_TYPE_VARINT => {
+                    fields.push((field, FieldValue::Varint(decode_varint(&mut data)?)))
+                }
+                WIRE_TYPE_LEN => {
+                    let len = decode_varint(&mut data)? as usize;
+                    fields.push((field, FieldValue::Bytes(split_off(&mut data, len)?)));
+                }
+                WIRE_TYPE_FIXED64 => {
+                    split_off(&mut data, 8)?;
+                }
+                WIRE_TYPE_FIXED32 => {
+                    split_off(&mut data, 4)?;
+                }
+                _ => return Err(RemoteWalletError::Protocol("Unsupported wire type")),
+            }
+        }
+        Ok(fields)
+    }
+
+    pub fn bytes_field<'a>(fields: &[(u32, FieldValue<'a>)], field: u32) -> Option<&'a [u8]> {
+        fields.iter().find_map(|(number, value)| match value {
+            FieldValue::Bytes(bytes) if *number == field => Some(*bytes),
+            _ => None,
+        })
+    }
+
+    pub fn varint_field(fields: &[(u32, FieldValue)], field: u32) -> Option<u64> {
+        fields.iter().find_map(|(number, value)| match value {
+            FieldValue::Varint(value) if *number == field => Some(*value),
+            _ => None,
+        })
+    }
+
+    pub fn string_field(fields: &[(u32, FieldValue)], field: u32) -> Option<String> {
+        bytes_field(fields, field).map(|bytes| String::from_utf8_lossy(bytes).to_string())
+    }
+}
+
+/// The link to a Trezor device, a HID device or a mock in tests
+pub trait TrezorTransport {
+    /// Writes one report, returns the number of bytes written
+    fn write(&self, report: &[u8]) -> Result<usize, RemoteWalletError>;
+    /// Reads one report, returns the number of bytes read
+    fn read(&self, report: &mut [u8]) -> Result<usize, RemoteWalletError>;
+}
+
+#[cfg(feature = "hidapi")]
+impl TrezorTransport for hidapi::HidDevice {
+    fn write(&self, report: &[u8]) -> Result<usize, RemoteWalletError> {
+        Ok(hidapi::HidDevice::write(self, report)?)
+    }
+
+    fn read(&self, report: &mut [u8]) -> Result<usize, RemoteWalletError> {
+        Ok(hidapi::HidDevice::read(self, report)?)
+    }
+}
+
+/// Trezor devices with WebUSB firmware, all but the Trezor One before firmware 1.7, expose the
+/// wire protocol on the interrupt endpoints of their first interface
+#[cfg(feature = "rusb")]
+mod webusb {
+    use {super::*, std::time::Duration};
+
+    const INTERFACE: u8 = 0;
+    const ENDPOINT_OUT: u8 = 0x01;
+    const ENDPOINT_IN: u8 = 0x81;
+    /// libusb blocks without a timeout, like a HID read
+    const NO_TIMEOUT: Duration = Duration::ZERO;
+
+    pub struct WebUsbTransport(rusb::DeviceHandle<rusb::GlobalContext>);
+
+    impl TrezorTransport for WebUsbTransport {
+        fn write(&self, report: &[u8]) -> Result<usize, RemoteWalletError> {
+            // Reports carry a zero HID report ID on Windows, WebUSB has none
+            let n = self
+                .0
+                .write_interrupt(ENDPOINT_OUT, &report[HID_PREFIX_ZERO..], NO_TIMEOUT)?;
+            Ok(n + HID_PREFIX_ZERO)
+        }
+
+        fn read(&self, report: &mut [u8]) -> Result<usize, RemoteWalletError> {
+            Ok(self.0.read_interrupt(ENDPOINT_IN, report, NO_TIMEOUT)?)
+        }
+    }
+
+    /// Opens the connected Trezor devices which speak WebUSB, keyed by their host device path
+    pub fn open_devices(
+    ) -> Result<Vec<(String, Result<WebUsbTransport, RemoteWalletError>)>, RemoteWalletError> {
+        let mut devices = vec![];
+        for device in rusb::devices()?.iter() {
+            let descriptor = device.device_descriptor()?;
+            if descriptor.vendor_id() != TREZOR_VID || descriptor.product_id() != TREZOR_PID {
+                continue;
+            }
+            let host_device_path =
+                format!("webusb:{:03}:{:03}", device.bus_number(), device.address());
+            let transport = device
+                .open()
+                .and_then(|handle| {
+                    handle.claim_interface(INTERFACE)?;
+                    Ok(WebUsbTransport(handle))
+                })
+                .map_err(RemoteWalletError::from);
+            devices.push((host_device_path, transport));
+        }
+        Ok(devices)
+    }
+}
+
+#[cfg(feature = "rusb")]
+pub use webusb::{open_devices as open_webusb_devices, WebUsbTransport};
+
+/// Trezor Wallet device
+pub struct TrezorWallet {
+    pub transport: Box<dyn TrezorTransport>,
+    pub pretty_path: String,
+    pub version: FirmwareVersion,
+}
+
+impl fmt::Debug for TrezorWallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TrezorTransport")
+    }
+}
+
+impl TrezorWallet {
+    pub fn new(transport: Box<dyn TrezorTransport>) -> Self {
+        Self {
+            transport,
+            pretty_path: String::default(),
+            version: FirmwareVersion::new(0, 0, 0),
+        }
+    }
+
+    // Wire Protocol:
+    //		* Report Magic				(1 byte, `?`)
+    //		* Message Magic				(2 bytes, `##`, first report only)
+    //		* Message Type				(2 bytes big endian, first report only)
+    //		* Payload Length			(4 bytes big endian, first report only)
+    //		* Payload				(Variable, protobuf encoded)
+    //
+    fn write_message(&self, message_type: u16, payload: &[u8]) -> Result<(), RemoteWalletError> {
+        let payload_len = u32::try_from(payload.len())
+            .map_err(|_| RemoteWalletError::InvalidInput("Message is too long".to_string()))?;
+        let mut data = Vec::with_capacity(MESSAGE_HEADER_LEN - 1 + payload.len());
+        data.extend_from_slice(&MESSAGE_MAGIC);
+        data.extend_from_slice(&message_type.to_be_bytes());
+        data.extend_from_slice(&payload_len.to_be_bytes());
+        data.extend_from_slice(payload);
+
+        for chunk in data.chunks(REPORT_SIZE - 1) {
+            let mut report = [0_u8; HID_PACKET_SIZE];
+            report[HID_PREFIX_ZERO] = REPORT_MAGIC;
+            report[HID_PREFIX_ZERO + 1..HID_PREFIX_ZERO + 1 + chunk.len()].copy_from_slice(chunk);
+            trace!("Trezor write {:?}", &report[..]);
+            let n = self.transport.write(&report)?;
+            if n < HID_PACKET_SIZE {
+                return Err(RemoteWalletError::Protocol("Write data size mismatch"));
+            }
+        }
+        Ok(())
+    }
+
+    fn read_report(&self) -> Result<[u8; REPORT_SIZE], RemoteWalletError> {
+        let mut report = [0_u8; REPORT_SIZE];
+        let n = self.transport.read(&mut report)?;
+        trace!("Trezor read {:?}", &report[..n]);
+        if n != REPORT_SIZE || report[0] != REPORT_MAGIC {
+            return Err(RemoteWalletError::Protocol("Unexpected report header"));
+        }
+        Ok(report)
+    }
+
+    fn read_message(&self) -> Result<(u16, Vec<u8>), RemoteWalletError> {
+        let report = self.read_report()?;
+        if report[1..3] != MESSAGE_MAGIC {
+            return Err(RemoteWalletError::Protocol("Unexpected message header"));
+        }
+        let message_type = u16::from_be_bytes([report[3], report[4]]);
+        let payload_len = u32::from_be_bytes([report[5], report[6], report[7], report[8]]) as usize;
+        if payload_len > MAX_RESPONSE_LEN {
+            return Err(RemoteWalletError::Protocol("Message size too large"));
+        }
+
+        let mut payload = report[MESSAGE_HEADER_LEN..].to_vec();
+        while payload.len() < payload_len {
+            payload.extend_from_slice(&self.read_report()?[1..]);
+        }
+        payload.truncate(payload_len);
+        Ok((message_type, payload))
+    }
+
+    /// Sends a request and returns the response, acknowledging the button and passphrase
+    /// requests on the way
+    fn call(
+        &self,
+        message_type: u16,
+        payload: &[u8],
+        expected_response_type: u16,
+    ) -> Result<Vec<u8>, RemoteWalletError> {
+        let mut waiting_for_approval = false;
+        let mut request = (message_type, payload.to_vec());
+        loop {
+            self.write_message(request.0, &request.1)?;
+            let (response_type, response) = self.read_message()?;
+            request = match response_type {
+                message_type::BUTTON_REQUEST => {
+                    if !waiting_for_approval {
+                        waiting_for_approval = true;
+                        println!(
+                            "Waiting for your approval on {} {}",
+                            self.name(),
+                            self.pretty_path
+                        );
+                    }
+                    (message_type::BUTTON_ACK, vec![])
+                }
+                message_type::PASSPHRASE_REQUEST => {
+                    // PassphraseAck.on_device, the passphrase is entered on the device
+                    let mut ack = vec![];
+                    proto::encode_varint_field(&mut ack, 3, 1);
+                    (message_type::PASSPHRASE_ACK, ack)
+                }
+                message_type::PIN_MATRIX_REQUEST => {
+                    return Err(RemoteWalletError::InvalidInput(
+                        "Trezor is locked, unlock it before use".to_string(),
+                    ));
+                }
+                message_type::FAILURE => return Err(Self::parse_failure(&response)),
+                response_type if response_type == expected_response_type => {
+                    if waiting_for_approval {
+                        println!("{CHECK_MARK}Approved");
+                    }
+                    return Ok(response);
+                }
+                _ => return Err(RemoteWalletError::Protocol("Unexpected response message")),
+            };
+        }
+    }
+
+    fn parse_failure(payload: &[u8]) -> RemoteWalletError {
+        let Ok(fields) = proto::decode_fields(payload) else {
+            return RemoteWalletError::Protocol("Invalid failure message");
+        };
+        match proto::varint_field(&fields, 1) {
+            Some(FAILURE_ACTION_CANCELLED) | Some(FAILURE_PIN_CANCELLED) => {
+                RemoteWalletError::UserCancel
+            }
+            _ => RemoteWalletError::TrezorFailure(
+                proto::string_field(&fields, 2).unwrap_or_else(|| "Unknown error".to_string()),
+            ),
+        }
+    }
+
+    fn read_features(&mut self) -> Result<(String, String), RemoteWalletError> {
+        let features = self.call(message_type::INITIALIZE, &[], message_type::FEATURES)?;
+        let fields = proto::decode_fields(&features)?;
+        let version_part = |field| proto::varint_field(&fields, field).unwrap_or_default();
+        self.version = FirmwareVersion::new(version_part(2), version_part(3), version_part(4));
+        let serial = proto::string_field(&fields, 6).unwrap_or_else(|| "Unknown".to_string());
+        let model = proto::string_field(&fields, 21)
+            .map(|model| format!("trezor-{}", model.to_lowercase().replace(' ', "-")))
+            .unwrap_or_else(|| "trezor".to_string());
+        Ok((model, serial))
+    }
+
+    /// Reads the device info of a device connected at `host_device_path`
+    pub fn read_info(
+        &mut self,
+        host_device_path: String,
+    ) -> Result<RemoteWalletInfo, RemoteWalletError> {
+        let (model, serial) = self.read_features()?;
+        let pubkey_result = self.get_pubkey(&DerivationPath::default(), false);
+        let (pubkey, error) = match pubkey_result {
+            Ok(pubkey) => (pubkey, None),
+            Err(err) => (Pubkey::default(), Some(err)),
+        };
+        Ok(RemoteWalletInfo {
+            model,
+            manufacturer: Manufacturer::Trezor,
+            serial,
+            host_device_path,
+            pubkey,
+            error,
+        })
+    }
+
+    fn encode_address_n(payload: &mut Vec<u8>, derivation_path: &DerivationPath) {
+        for index in derivation_path.path() {
+            proto::encode_varint_field(payload, 1, index.to_bits().into());
+        }
+    }
+}
+
+#[cfg(not(feature = "hidapi"))]
+impl RemoteWallet<Self> for TrezorWallet {}
+#[cfg(feature = "hidapi")]
+impl RemoteWallet<hidapi::DeviceInfo> for TrezorWallet {
+    fn name(&self) -> &str {
+        "Trezor hardware wallet"
+    }
+
+    fn read_device(
+        &mut self,
+        dev_info: &hidapi::DeviceInfo,
+    ) -> Result<RemoteWalletInfo, RemoteWalletError> {
+        self.read_info(dev_info.path().to_string_lossy().to_string())
+    }
+
+    fn get_pubkey(
+        &self,
+        derivation_path: &DerivationPath,
+        confirm_key: bool,
+    ) -> Result<Pubkey, RemoteWalletError> {
+        let mut payload = vec![];
+        Self::encode_address_n(&mut payload, derivation_path);
+        proto::encode_varint_field(&mut payload, 2, confirm_key.into());
+
+        let response = self.call(
+            message_type::SOLANA_GET_PUBLIC_KEY,
+            &payload,
+            message_type::SOLANA_PUBLIC_KEY,
+        )?;
+        let fields = proto::decode_fields(&response)?;
+        proto::bytes_field(&fields, 1)
+            .and_then(|key| Pubkey::try_from(key).ok())
+            .ok_or(RemoteWalletError::Protocol("Key packet size mismatch"))
+    }
+
+    fn sign_message(
+        &self,
+        derivation_path: &DerivationPath,
+        data: &[u8],
+    ) -> Result<Signature, RemoteWalletError> {
+        // Off-chain messages start with the Domain Specifier b"\xffsolana offchain", see
+        // `LedgerWallet::sign_message`
+        if !data.is_empty() && data[0] == 0xff {
+            return self.sign_offchain_message(derivation_path, data);
+        }
+
+        let mut payload = vec![];
+        Self::encode_address_n(&mut payload, derivation_path);
+        proto::encode_bytes_field(&mut payload, 2, data);
+
+        let response = self.call(
+            message_type::SOLANA_SIGN_TX,
+            &payload,
+            message_type::SOLANA_TX_SIGNATURE,
+        )?;
+        let fields = proto::decode_fields(&response)?;
+        proto::bytes_field(&fields, 1)
+            .and_then(|signature| Signature::try_from(signature).ok())
+            .ok_or(RemoteWalletError::Protocol(
+                "Signature packet size mismatch",
+            ))
+    }
+
+    fn sign_offchain_message(
+        &self,
+        _derivation_path: &DerivationPath,
+        _message: &[u8],
+    ) -> Result<Signature, RemoteWalletError> {
+        // The Trezor firmware only signs Solana transactions, it has no message for
+        // signing off-chain messages
+        Err(RemoteWalletError::InvalidInput(
+            "Off-chain message signing is not supported by Trezor devices".to_string(),
+        ))
+    }
+}
+
+/// Check if the detected device is a valid `Trezor device` by checking both the product ID and the vendor ID
+pub fn is_valid_trezor(vendor_id: u16, product_id: u16) -> bool {
+    (vendor_id == TREZOR_ONE_VID && product_id == TREZOR_ONE_PID)
+        || (vendor_id == TREZOR_VID && product_id == TREZOR_PID)
+}
+
+/// Choose a Trezor wallet based on matching info fields
+pub fn get_trezor_from_info(
+    info: RemoteWalletInfo,
+    keypair_name: &str,
+    wallet_manager: &RemoteWalletManager,
+) -> Result<Rc<TrezorWallet>, RemoteWalletError> {
+    let host_device_path = select_host_device_path(info, keypair_name, wallet_manager)?;
+    wallet_manager.get_trezor(&host_device_path)
+}
+
+#[cfg(all(test, feature = "hidapi"))]
+mod tests {
+    use {
+        super::*,
+        assert_matches::assert_matches,
+        std::{cell::RefCell, collections::VecDeque},
+    };
+
+    /// Replays queued response reports, and records the written reports
+    #[derive(Clone, Default)]
+    struct MockTransport {
+        reads: Rc<RefCell<VecDeque<Vec<u8>>>>,
+        writes: Rc<RefCell<Vec<Vec<u8>>>>,
+    }
+
+    impl MockTransport {
+        fn queue_response(&self, message_type: u16, payload: &[u8]) {
+            let recorder = MockTransport::default();
+            TrezorWallet::new(Box::new(recorder.clone()))
+                .write_message(message_type, payload)
+                .unwrap();
+            self.reads.borrow_mut().extend(
+                recorder
+                    .writes
+                    .take()
+                    .into_iter()
+                    .map(|report| report[HID_PREFIX_ZERO..].to_vec()),
+            );
+        }
+
+        /// Decodes the messages written so far
+        fn written_messages(&self) -> Vec<(u16, Vec<u8>)> {
+            let replay = MockTransport::default();
+            replay.reads.borrow_mut().extend(
+                self.writes
+                    .take()
+                    .into_iter()
+                    .map(|report| report[HID_PREFIX_ZERO..].to_vec()),
+            );
+            let wallet = TrezorWallet::new(Box::new(replay.clone()));
+            let mut messages = vec![];
+            while !replay.reads.borrow().is_empty() {
+                messages.push(wallet.read_message().unwrap());
+            }
+            messages
+        }
+    }
+
+    impl TrezorTransport for MockTransport {
+        fn write(&self, report: &[u8]) -> Result<usize, RemoteWalletError> {
+            self.writes.borrow_mut().push(report.to_vec());
+            Ok(report.len())
+        }
+
+        fn read(&self, report: &mut [u8]) -> Result<usize, RemoteWalletError> {
+            let response = self
+                .reads
+                .borrow_mut()
+                .pop_front()
+                .ok_or_else(|| RemoteWalletError::Hid("no response".to_string()))?;
+            report[..response.len()].copy_from_slice(&response);
+            Ok(response.len())
+        }
+    }
+
+    fn address_n(derivation_path: &DerivationPath) -> Vec<u8> {
+        let mut payload = vec![];
+        TrezorWallet::encode_address_n(&mut payload, derivation_path);
+        payload
+    }
+
+    #[test]
+    fn test_is_valid_trezor() {
+        assert!(is_valid_trezor(0x1209, 0x53c1));
+        assert!(is_valid_trezor(0x534c, 0x0001));
+        assert!(!is_valid_trezor(0x1209, 0x0001));
+        assert!(!is_valid_trezor(0x2c97, 0x0001));
+    }
+
+    #[test]
+    fn test_read_device_features() {
+        let transport = MockTransport::default();
+        let mut features = vec![];
+        proto::encode_bytes_field(&mut features, 1, b"trezor.io");
+        proto::encode_varint_field(&mut features, 2, 2);
+        proto::encode_varint_field(&mut features, 3, 8);
+        proto::encode_varint_field(&mut features, 4, 1);
+        proto::encode_bytes_field(&mut features, 6, b"0123456789ABCDEF");
+        proto::encode_bytes_field(&mut features, 21, b"Safe 3");
+        transport.queue_response(message_type::FEATURES, &features);
+
+        let mut wallet = TrezorWallet::new(Box::new(transport.clone()));
+        let (model, serial) = wallet.read_features().unwrap();
+        assert_eq!(model, "trezor-safe-3");
+        assert_eq!(serial, "0123456789ABCDEF");
+        assert_eq!(wallet.version, FirmwareVersion::new(2, 8, 1));
+        assert_eq!(
+            transport.written_messages(),
+            vec![(message_type::INITIALIZE, vec![])]
+        );
+    }
+
+    #[test]
+    fn test_get_pubkey() {
+        let transport = MockTransport::default();
+        let pubkey = Pubkey::new_unique();
+        let mut response = vec![];
+        proto::encode_bytes_field(&mut response, 1, pubkey.as_ref());
+        transport.queue_response(message_type::SOLANA_PUBLIC_KEY, &response);
+
+        let wallet = TrezorWallet::new(Box::new(transport.clone()));
+        let derivation_path = DerivationPath::new_bip44(Some(1), Some(0));
+        assert_eq!(wallet.get_pubkey(&derivation_path, false).unwrap(), pubkey);
+
+        let mut request = address_n(&derivation_path);
+        proto::encode_varint_field(&mut request, 2, 0);
+        assert_eq!(
+            transport.written_messages(),
+            vec![(message_type::SOLANA_GET_PUBLIC_KEY, request)]
+        );
+    }
+
+    #[test]
+    fn test_sign_message() {
+        let transport = MockTransport::default();
+        let signature = Signature::from([7; 64]);
+        let mut response = vec![];
+        proto::encode_bytes_field(&mut response, 1, signature.as_ref());
+        transport.queue_response(message_type::BUTTON_REQUEST, &[]);
+        transport.queue_response(message_type::SOLANA_TX_SIGNATURE, &response);
+
+        // Spans several reports
+        let message: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        let wallet = TrezorWallet::new(Box::new(transport.clone()));
+        let derivation_path = DerivationPath::default();
+        assert_eq!(
+            wallet.sign_message(&derivation_path, &message).unwrap(),
+            signature
+        );
+
+        let mut request = address_n(&derivation_path);
+        proto::encode_bytes_field(&mut request, 2, &message);
+        assert_eq!(
+            transport.written_messages(),
+            vec![
+                (message_type::SOLANA_SIGN_TX, request),
+                (message_type::BUTTON_ACK, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sign_offchain_message() {
+        let transport = MockTransport::default();
+        let wallet = TrezorWallet::new(Box::new(transport.clone()));
+        let message = b"\xffsolana offchain";
+        assert_matches!(
+            wallet.sign_message(&DerivationPath::default(), message),
+            Err(RemoteWalletError::InvalidInput(_))
+        );
+        assert!(transport.written_messages().is_empty());
+    }
+
+    #[test]
+    fn test_failure() {
+        let transport = MockTransport::default();
+        let wallet = TrezorWallet::new(Box::new(transport.clone()));
+
+        let mut failure = vec![];
+        proto::encode_varint_field(&mut failure, 1, FAILURE_ACTION_CANCELLED);
+        transport.queue_response(message_type::FAILURE, &failure);
+        assert_matches!(
+            wallet.get_pubkey(&DerivationPath::default(), true),
+            Err(RemoteWalletError::UserCancel)
+        );
+
+        let mut failure = vec![];
+        proto::encode_varint_field(&mut failure, 1, 9);
+        proto::encode_bytes_field(&mut failure, 2, b"Invalid transaction");
+        transport.queue_response(message_type::FAILURE, &failure);
+        assert_matches!(
+            wallet.sign_message(&DerivationPath::default(), &[1, 0, 1]),
+            Err(RemoteWalletError::TrezorFailure(message)) if message == "Invalid transaction"
+        );
+
+        transport.queue_response(message_type::SOLANA_PUBLIC_KEY, &[0x0a, 0x02, 1, 2]);
+        assert_matches!(
+            wallet.get_pubkey(&DerivationPath::default(), false),
+            Err(RemoteWalletError::Protocol("Key packet size mismatch"))
+        );
+    }
+}
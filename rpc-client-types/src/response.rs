@@ -7,7 +7,7 @@ use {
     solana_transaction_error::TransactionResult as Result,
     solana_transaction_status_client_types::{
         ConfirmedTransactionStatusWithSignature, TransactionConfirmationStatus, UiConfirmedBlock,
-        UiInnerInstructions, UiTransactionError, UiTransactionReturnData,
+        UiInnerInstructions, UiInstructionInvocation, UiTransactionError, UiTransactionReturnData,
     },
     std::{collections::HashMap, fmt, net::SocketAddr, str::FromStr},
     thiserror::Error,
@@ -404,6 +404,7 @@ pub struct RpcSimulateTransactionResult {
     pub loaded_accounts_data_size: Option<u32>,
     pub return_data: Option<UiTransactionReturnData>,
     pub inner_instructions: Option<Vec<UiInnerInstructions>>,
+    pub invocation_trace: Option<Vec<UiInstructionInvocation>>,
     pub replacement_blockhash: Option<RpcBlockhash>,
 }
 
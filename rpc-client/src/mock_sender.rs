@@ -355,6 +355,7 @@ impl RpcSender for MockSender {
                     loaded_accounts_data_size: None,
                     return_data: None,
                     inner_instructions: None,
+                    invocation_trace: None,
                     replacement_blockhash: None
                 },
             })?,
@@ -39,6 +39,7 @@ pub fn get_parsed_token_account(
         .and_then(|mint_account| get_additional_mint_data(bank, mint_account.data()).ok())
         .map(|data| AccountAdditionalDataV3 {
             spl_token_additional_data: Some(data),
+            ..AccountAdditionalDataV3::default()
         });
 
     encode_ui_account(
@@ -64,6 +65,7 @@ where
                 let (_, data) = get_mint_owner_and_additional_data(&bank, &mint_pubkey).ok()?;
                 let data = AccountAdditionalDataV3 {
                     spl_token_additional_data: Some(data),
+                    ..AccountAdditionalDataV3::default()
                 };
                 mint_data.insert(mint_pubkey, data);
                 Some(data)
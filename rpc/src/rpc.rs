@@ -19,7 +19,7 @@ use {
     solana_account::{AccountSharedData, ReadableAccount},
     solana_account_decoder::{
         encode_ui_account,
-        parse_account_data::SplTokenAdditionalDataV2,
+        parse_account_data::{AccountAdditionalDataV3, SplTokenAdditionalDataV2},
         parse_token::{is_known_spl_token_id, token_amount_to_ui_amount_v3, UiTokenAmount},
         UiAccount, UiAccountEncoding, UiDataSliceConfig, MAX_BASE58_BYTES,
     },
@@ -89,10 +89,13 @@ use {
         ConfirmedTransactionStatusWithSignature, ConfirmedTransactionWithStatusMeta,
         EncodedConfirmedTransactionWithStatusMeta, Reward, RewardType, Rewards,
         TransactionBinaryEncoding, TransactionConfirmationStatus, TransactionStatus,
-        UiConfirmedBlock, UiTransactionEncoding,
+        UiConfirmedBlock, UiInstructionInvocation, UiTransactionEncoding,
     },
     solana_validator_exit::Exit,
-    solana_vote_program::vote_state::MAX_LOCKOUT_HISTORY,
+    solana_vote_program::vote_state::{
+        commission_schedule::{pending_commission_address, PendingCommission},
+        MAX_LOCKOUT_HISTORY,
+    },
     spl_generic_token::{
         token::{SPL_TOKEN_ACCOUNT_MINT_OFFSET, SPL_TOKEN_ACCOUNT_OWNER_OFFSET},
         token_2022::{self, ACCOUNTTYPE_ACCOUNT},
@@ -2517,6 +2520,10 @@ fn get_encoded_account(
                 && encoding == UiAccountEncoding::JsonParsed
             {
                 get_parsed_token_account(bank, pubkey, account, overwrite_accounts)
+            } else if solana_vote_program::check_id(account.owner())
+                && encoding == UiAccountEncoding::JsonParsed
+            {
+                get_parsed_vote_account(bank, pubkey, account, data_slice, overwrite_accounts)
             } else {
                 encode_account(&account, pubkey, encoding, data_slice)?
             };
@@ -2526,6 +2533,37 @@ fn get_encoded_account(
     }
 }
 
+/// Parses a vote account together with the commission increase scheduled in its pending
+/// commission account, if any
+fn get_parsed_vote_account(
+    bank: &Bank,
+    pubkey: &Pubkey,
+    account: AccountSharedData,
+    data_slice: Option<UiDataSliceConfig>,
+    // only used for simulation results
+    overwrite_accounts: Option<&HashMap<Pubkey, AccountSharedData>>,
+) -> UiAccount {
+    let additional_data = account_resolver::get_account_from_overwrites_or_bank(
+        &pending_commission_address(pubkey),
+        bank,
+        overwrite_accounts,
+    )
+    .filter(|pending_account| solana_vote_program::check_id(pending_account.owner()))
+    .and_then(|pending_account| PendingCommission::from_account_data(pending_account.data()))
+    .map(|pending_commission| AccountAdditionalDataV3 {
+        vote_pending_commission: Some(pending_commission),
+        ..AccountAdditionalDataV3::default()
+    });
+
+    encode_ui_account(
+        pubkey,
+        &account,
+        UiAccountEncoding::JsonParsed,
+        additional_data,
+        data_slice,
+    )
+}
+
 fn encode_account<T: ReadableAccount>(
     account: &T,
     pubkey: &Pubkey,
@@ -3911,7 +3949,8 @@ pub mod rpc_full {
                     loaded_accounts_data_size,
                     return_data,
                     inner_instructions: _, // Always `None` due to `enable_cpi_recording = false`
-                } = preflight_bank.simulate_transaction(&transaction, false)
+                    invocation_trace: _, // Always `None` due to `enable_invocation_tracing = false`
+                } = preflight_bank.simulate_transaction(&transaction, false, false)
                 {
                     match err {
                         TransactionError::BlockhashNotFound => {
@@ -3931,6 +3970,7 @@ pub mod rpc_full {
                             loaded_accounts_data_size: Some(loaded_accounts_data_size),
                             return_data: return_data.map(|return_data| return_data.into()),
                             inner_instructions: None,
+                            invocation_trace: None,
                             replacement_blockhash: None,
                         },
                     }
@@ -3965,6 +4005,7 @@ pub mod rpc_full {
                 accounts: config_accounts,
                 min_context_slot,
                 inner_instructions: enable_cpi_recording,
+                invocation_trace: enable_invocation_trace,
             } = config.unwrap_or_default();
             let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
@@ -4013,7 +4054,12 @@ pub mod rpc_full {
                 loaded_accounts_data_size,
                 return_data,
                 inner_instructions,
-            } = bank.simulate_transaction(&transaction, enable_cpi_recording);
+                invocation_trace,
+            } = bank.simulate_transaction(
+                &transaction,
+                enable_cpi_recording,
+                enable_invocation_trace,
+            );
 
             let account_keys = transaction.message().account_keys();
             let number_of_accounts = account_keys.len();
@@ -4069,6 +4115,12 @@ pub mod rpc_full {
                     .map(|converted| parse_ui_inner_instructions(converted, &account_keys))
                     .collect()
             });
+            let invocation_trace = invocation_trace.map(|invocation_trace| {
+                invocation_trace
+                    .into_iter()
+                    .map(UiInstructionInvocation::from)
+                    .collect()
+            });
 
             Ok(new_response(
                 bank,
@@ -4080,6 +4132,7 @@ pub mod rpc_full {
                     loaded_accounts_data_size: Some(loaded_accounts_data_size),
                     return_data: return_data.map(|return_data| return_data.into()),
                     inner_instructions,
+                    invocation_trace,
                     replacement_blockhash: blockhash,
                 },
             ))
@@ -5631,6 +5684,65 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_rpc_get_account_info_vote_pending_commission() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+
+        let vote_pubkey = Pubkey::new_unique();
+        rpc.store_vote_account(&vote_pubkey, VoteState::default());
+        let request = create_test_request(
+            "getAccountInfo",
+            Some(json!([vote_pubkey.to_string(), {"encoding": "jsonParsed"}])),
+        );
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result["value"]["data"]["parsed"]["type"], "vote");
+        assert!(result["value"]["data"]["parsed"]["info"]
+            .get("pendingCommission")
+            .is_none());
+
+        let pending_commission = PendingCommission {
+            commission: 42,
+            effective_epoch: 7,
+        };
+        let pending_account = AccountSharedData::create(
+            bank.get_minimum_balance_for_rent_exemption(
+                vote_state::commission_schedule::PENDING_COMMISSION_SIZE,
+            ),
+            pending_commission.to_bytes().to_vec(),
+            solana_vote_program::id(),
+            false,
+            0,
+        );
+        bank.store_account(&pending_commission_address(&vote_pubkey), &pending_account);
+        let request = create_test_request(
+            "getAccountInfo",
+            Some(json!([vote_pubkey.to_string(), {"encoding": "jsonParsed"}])),
+        );
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            result["value"]["data"]["parsed"]["info"]["pendingCommission"],
+            json!({"commission": 42, "effectiveEpoch": 7})
+        );
+
+        // the pending commission account is not parsed on its own
+        let request = create_test_request(
+            "getAccountInfo",
+            Some(json!([
+                pending_commission_address(&vote_pubkey).to_string(),
+                {"encoding": "jsonParsed"}
+            ])),
+        );
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            result["value"]["data"],
+            json!([
+                BASE64_STANDARD.encode(pending_commission.to_bytes()),
+                "base64"
+            ])
+        );
+    }
+
     #[test]
     fn test_encode_account_does_not_throw_when_slice_larger_than_account() {
         let data = vec![42; 5];
@@ -6045,6 +6157,7 @@ pub mod tests {
                     ],
                     "err":null,
                     "innerInstructions": null,
+                    "invocationTrace": null,
                     "loadedAccountsDataSize": loaded_accounts_data_size,
                     "logs":[
                         "Program 11111111111111111111111111111111 invoke [1]",
@@ -6132,6 +6245,7 @@ pub mod tests {
                     "accounts":null,
                     "err":null,
                     "innerInstructions":null,
+                    "invocationTrace": null,
                     "loadedAccountsDataSize": loaded_accounts_data_size,
                     "logs":[
                         "Program 11111111111111111111111111111111 invoke [1]",
@@ -6163,6 +6277,7 @@ pub mod tests {
                     "accounts":null,
                     "err":null,
                     "innerInstructions":null,
+                    "invocationTrace": null,
                     "loadedAccountsDataSize": loaded_accounts_data_size,
                     "logs":[
                         "Program 11111111111111111111111111111111 invoke [1]",
@@ -6218,6 +6333,7 @@ pub mod tests {
                     "err":"BlockhashNotFound",
                     "accounts":null,
                     "innerInstructions":null,
+                    "invocationTrace": null,
                     "loadedAccountsDataSize":0,
                     "logs":[],
                     "replacementBlockhash": null,
@@ -6252,6 +6368,7 @@ pub mod tests {
                     "accounts":null,
                     "err":null,
                     "innerInstructions":null,
+                    "invocationTrace": null,
                     "loadedAccountsDataSize": loaded_accounts_data_size,
                     "logs":[
                         "Program 11111111111111111111111111111111 invoke [1]",
@@ -6403,6 +6520,7 @@ pub mod tests {
                     ],
                     "err": null,
                     "innerInstructions": null,
+                    "invocationTrace": null,
                     "loadedAccountsDataSize": loaded_accounts_data_size,
                     "logs":[
                         "Program 11111111111111111111111111111111 invoke [1]",
@@ -6483,6 +6601,7 @@ pub mod tests {
                     "accounts": null,
                     "err":null,
                     "innerInstructions": null,
+                    "invocationTrace": null,
                     "loadedAccountsDataSize": loaded_accounts_data_size,
                     "logs":[
                         "Program TestProgram11111111111111111111111111111111 invoke [1]",
@@ -6527,6 +6646,7 @@ pub mod tests {
                     "accounts": null,
                     "err":null,
                     "innerInstructions": null,
+                    "invocationTrace": null,
                     "loadedAccountsDataSize": loaded_accounts_data_size,
                     "logs":[
                         "Program TestProgram11111111111111111111111111111111 invoke [1]",
@@ -6592,6 +6712,67 @@ pub mod tests {
                         ]
                         }
                     ],
+                    "invocationTrace": null,
+                    "loadedAccountsDataSize": loaded_accounts_data_size,
+                    "logs":[
+                        "Program TestProgram11111111111111111111111111111111 invoke [1]",
+                        "I am logging from a builtin program!",
+                        "I am about to CPI to System!",
+                        "Program 11111111111111111111111111111111 invoke [2]",
+                        "Program 11111111111111111111111111111111 success",
+                        "All done!",
+                        "Program TestProgram11111111111111111111111111111111 success"
+                    ],
+                    "replacementBlockhash": null,
+                    "returnData":null,
+                    "unitsConsumed":TestBuiltinEntrypoint::COMPUTE_UNITS + 150,
+                }
+            },
+            "id": 1,
+        });
+        let expected: Response =
+            serde_json::from_value(expected).expect("expected response deserialization");
+        let result: Response = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(result, expected);
+
+        // `invocationTrace` provided as `true`, should have per-instruction compute usage
+        let req = format!(
+            r#"{{"jsonrpc":"2.0",
+                 "id":1,
+                 "method":"simulateTransaction",
+                 "params":[
+                   "{}",
+                   {{ "invocationTrace": true, "encoding": "base64" }}
+                 ]
+            }}"#,
+            tx_serialized_encoded,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let expected = json!({
+            "jsonrpc": "2.0",
+            "result": {
+                "context": {"slot": 0, "apiVersion": RpcApiVersion::default()},
+                "value":{
+                    "accounts": null,
+                    "err":null,
+                    "innerInstructions": null,
+                    "invocationTrace": [
+                        {
+                            "programId": "TestProgram11111111111111111111111111111111",
+                            "stackHeight": 1,
+                            "computeUnitsConsumed": TestBuiltinEntrypoint::COMPUTE_UNITS + 150,
+                            "syscallUnitsConsumed": 0,
+                            "accountsDataLenDelta": 0,
+                        },
+                        {
+                            "programId": "11111111111111111111111111111111",
+                            "stackHeight": 2,
+                            "computeUnitsConsumed": 150,
+                            "syscallUnitsConsumed": 0,
+                            "accountsDataLenDelta": 0,
+                        }
+                    ],
                     "loadedAccountsDataSize": loaded_accounts_data_size,
                     "logs":[
                         "Program TestProgram11111111111111111111111111111111 invoke [1]",
@@ -6853,7 +7034,7 @@ pub mod tests {
         assert_eq!(
             res,
             Some(
-                r#"{"jsonrpc":"2.0","error":{"code":-32002,"message":"Transaction simulation failed: Blockhash not found","data":{"accounts":null,"err":"BlockhashNotFound","innerInstructions":null,"loadedAccountsDataSize":0,"logs":[],"replacementBlockhash":null,"returnData":null,"unitsConsumed":0}},"id":1}"#.to_string(),
+                r#"{"jsonrpc":"2.0","error":{"code":-32002,"message":"Transaction simulation failed: Blockhash not found","data":{"accounts":null,"err":"BlockhashNotFound","innerInstructions":null,"invocationTrace":null,"loadedAccountsDataSize":0,"logs":[],"replacementBlockhash":null,"returnData":null,"unitsConsumed":0}},"id":1}"#.to_string(),
             )
         );
 
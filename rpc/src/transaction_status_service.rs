@@ -445,6 +445,7 @@ pub(crate) mod tests {
             log_messages: None,
             inner_instructions: None,
             return_data: None,
+            invocation_trace: None,
             executed_units: 0,
             fee_details: FeeDetails::default(),
             loaded_account_stats: TransactionLoadedAccountsStats::default(),
@@ -571,6 +572,7 @@ pub(crate) mod tests {
             log_messages: None,
             inner_instructions: None,
             return_data: None,
+            invocation_trace: None,
             executed_units: 0,
             fee_details: FeeDetails::default(),
             loaded_account_stats: TransactionLoadedAccountsStats::default(),
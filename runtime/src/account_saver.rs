@@ -195,6 +195,7 @@ mod tests {
                     log_messages: None,
                     inner_instructions: None,
                     return_data: None,
+                    invocation_trace: None,
                     executed_units: 0,
                     accounts_data_len_delta: 0,
                 },
@@ -163,7 +163,9 @@ use {
         versioned::VersionedTransaction,
         Transaction, TransactionVerificationMode,
     },
-    solana_transaction_context::{TransactionAccount, TransactionReturnData},
+    solana_transaction_context::{
+        InstructionInvocation, TransactionAccount, TransactionReturnData,
+    },
     solana_transaction_error::{TransactionError, TransactionResult as Result},
     solana_vote::vote_account::{VoteAccount, VoteAccountsHashMap},
     std::{
@@ -215,6 +217,7 @@ mod recent_blockhashes_account;
 mod serde_snapshot;
 mod sysvar_cache;
 pub(crate) mod tests;
+mod vote_commission;
 
 pub const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 60.0 * 60.0;
 
@@ -319,6 +322,7 @@ pub struct TransactionSimulationResult {
     pub loaded_accounts_data_size: u32,
     pub return_data: Option<TransactionReturnData>,
     pub inner_instructions: Option<Vec<InnerInstructions>>,
+    pub invocation_trace: Option<Vec<InstructionInvocation>>,
 }
 
 #[derive(Clone, Debug)]
@@ -1615,6 +1619,10 @@ impl Bank {
             self.apply_feature_activations(ApplyFeatureActivationsCaller::NewFromParent, false)
         }));
 
+        // Apply the commission increases in effect for the rewards of the parent epoch before the
+        // stakes, and with them the vote accounts used for rewards, are snapshotted
+        self.apply_pending_vote_commissions(parent_epoch, &thread_pool);
+
         // Add new entry to stakes.stake_history, set appropriate epoch and
         // update vote accounts with warmed up stakes before saving a
         // snapshot of stakes in epoch stakes
@@ -3119,10 +3127,15 @@ impl Bank {
         &self,
         transaction: &impl TransactionWithMeta,
         enable_cpi_recording: bool,
+        enable_invocation_tracing: bool,
     ) -> TransactionSimulationResult {
         assert!(self.is_frozen(), "simulation bank must be frozen");
 
-        self.simulate_transaction_unchecked(transaction, enable_cpi_recording)
+        self.simulate_transaction_unchecked(
+            transaction,
+            enable_cpi_recording,
+            enable_invocation_tracing,
+        )
     }
 
     /// Run transactions against a bank without committing the results; does not check if the bank
@@ -3131,6 +3144,7 @@ impl Bank {
         &self,
         transaction: &impl TransactionWithMeta,
         enable_cpi_recording: bool,
+        enable_invocation_tracing: bool,
     ) -> TransactionSimulationResult {
         let account_keys = transaction.account_keys();
         let number_of_accounts = account_keys.len();
@@ -3159,6 +3173,7 @@ impl Bank {
                     enable_log_recording: true,
                     enable_return_data_recording: true,
                     enable_transaction_balance_recording: false,
+                    enable_invocation_tracing,
                 },
             },
         );
@@ -3174,6 +3189,7 @@ impl Bank {
             logs,
             return_data,
             inner_instructions,
+            invocation_trace,
             units_consumed,
             loaded_accounts_data_size,
         ) = match processing_result {
@@ -3192,6 +3208,7 @@ impl Bank {
                         details.log_messages,
                         details.return_data,
                         details.inner_instructions,
+                        details.invocation_trace,
                         details.executed_units,
                         executed_tx.loaded_transaction.loaded_accounts_data_size,
                     )
@@ -3202,11 +3219,12 @@ impl Bank {
                     None,
                     None,
                     None,
+                    None,
                     0,
                     fees_only_tx.rollback_accounts.data_size() as u32,
                 ),
             },
-            Err(error) => (vec![], Err(error), None, None, None, 0, 0),
+            Err(error) => (vec![], Err(error), None, None, None, None, 0, 0),
         };
         let logs = logs.unwrap_or_default();
 
@@ -3218,6 +3236,7 @@ impl Bank {
             loaded_accounts_data_size,
             return_data,
             inner_instructions,
+            invocation_trace,
         }
     }
 
@@ -3710,6 +3729,7 @@ impl Bank {
                             log_messages: execution_details.log_messages,
                             inner_instructions: execution_details.inner_instructions,
                             return_data: execution_details.return_data,
+                            invocation_trace: execution_details.invocation_trace,
                             executed_units,
                             fee_details,
                             loaded_account_stats: TransactionLoadedAccountsStats {
@@ -3723,6 +3743,7 @@ impl Bank {
                         log_messages: None,
                         inner_instructions: None,
                         return_data: None,
+                        invocation_trace: None,
                         executed_units,
                         fee_details: fees_only_tx.fee_details,
                         loaded_account_stats: TransactionLoadedAccountsStats {
@@ -3878,6 +3899,7 @@ impl Bank {
                 enable_log_recording: true,
                 enable_return_data_recording: true,
                 enable_transaction_balance_recording: false,
+                enable_invocation_tracing: true,
             },
             &mut ExecuteTimings::default(),
             Some(1000 * 1000),
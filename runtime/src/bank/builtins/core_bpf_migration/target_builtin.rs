@@ -121,6 +121,14 @@ mod tests {
         solana_sdk_ids::zk_elgamal_proof_program::id(),
         Some(feature_set::zk_elgamal_proof_program_enabled::id())
     )]
+    #[test_case(
+        solana_upgrade_timelock_interface::id(),
+        Some(feature_set::enable_upgrade_timelock_program::id())
+    )]
+    #[test_case(
+        solana_multisig_interface::id(),
+        Some(feature_set::enable_multisig_program::id())
+    )]
     fn test_target_program_builtin(program_address: Pubkey, activation_feature: Option<Pubkey>) {
         let migration_target = CoreBpfMigrationTargetType::Builtin;
         let mut bank = create_simple_test_bank(0);
@@ -251,6 +251,7 @@ fn new_executed_processing_result(
                 log_messages: None,
                 inner_instructions: None,
                 return_data: None,
+                invocation_trace: None,
                 executed_units: 0,
                 accounts_data_len_delta: 0,
             },
@@ -1944,6 +1945,7 @@ fn test_load_and_execute_commit_transactions_fees_only() {
             log_messages: None,
             inner_instructions: None,
             return_data: None,
+            invocation_trace: None,
             executed_units: 0,
             fee_details: FeeDetails::new(5000, 0),
             loaded_account_stats: TransactionLoadedAccountsStats {
@@ -4629,6 +4631,7 @@ fn test_pre_post_transaction_balances() {
             enable_log_recording: false,
             enable_return_data_recording: false,
             enable_transaction_balance_recording: true,
+            enable_invocation_tracing: false,
         },
         &mut ExecuteTimings::default(),
         None,
@@ -8158,6 +8161,7 @@ fn test_tx_log_order(relax_intrabatch_account_locks: bool) {
                 enable_log_recording: true,
                 enable_return_data_recording: false,
                 enable_transaction_balance_recording: false,
+                enable_invocation_tracing: false,
             },
             &mut ExecuteTimings::default(),
             None,
@@ -8270,6 +8274,7 @@ fn test_tx_return_data() {
                     enable_log_recording: false,
                     enable_return_data_recording: true,
                     enable_transaction_balance_recording: false,
+                    enable_invocation_tracing: false,
                 },
                 &mut ExecuteTimings::default(),
                 None,
@@ -11307,7 +11312,7 @@ fn test_failed_simulation_compute_units() {
 
     bank.freeze();
     let sanitized = RuntimeTransaction::from_transaction_for_tests(transaction);
-    let simulation = bank.simulate_transaction(&sanitized, false);
+    let simulation = bank.simulate_transaction(&sanitized, false, false);
     assert_eq!(expected_consumed_units, simulation.units_consumed);
     assert_eq!(
         expected_loaded_program_account_data_size,
@@ -11334,7 +11339,7 @@ fn test_failed_simulation_load_error() {
 
     bank.freeze();
     let sanitized = RuntimeTransaction::from_transaction_for_tests(transaction);
-    let simulation = bank.simulate_transaction(&sanitized, false);
+    let simulation = bank.simulate_transaction(&sanitized, false, false);
     assert_eq!(
         simulation,
         TransactionSimulationResult {
@@ -11345,6 +11350,7 @@ fn test_failed_simulation_load_error() {
             loaded_accounts_data_size: 0,
             return_data: None,
             inner_instructions: None,
+            invocation_trace: None,
         }
     );
 }
@@ -0,0 +1,245 @@
+use {
+    super::Bank,
+    agave_feature_set as feature_set,
+    log::warn,
+    rayon::{prelude::*, ThreadPool},
+    solana_account::{AccountSharedData, ReadableAccount, WritableAccount},
+    solana_clock::Epoch,
+    solana_pubkey::Pubkey,
+    solana_vote_program::vote_state::commission_schedule::{
+        apply_pending_commission, pending_commission_address, PendingCommission,
+    },
+};
+
+impl Bank {
+    /// Apply the scheduled commission increases that are in effect for the rewards of
+    /// `rewarded_epoch`.
+    ///
+    /// Must run before the stakes cache is activated for the new epoch, so that the epoch stakes,
+    /// and with them the rewards calculation, see the applied commission. Each applied pending
+    /// commission account is closed and its lamports are returned to the vote account, which
+    /// keeps the capitalization unchanged.
+    pub(super) fn apply_pending_vote_commissions(
+        &self,
+        rewarded_epoch: Epoch,
+        thread_pool: &ThreadPool,
+    ) {
+        if !self
+            .feature_set
+            .is_active(&feature_set::enable_vote_commission_schedule::id())
+        {
+            return;
+        }
+
+        let vote_pubkeys: Vec<Pubkey> = self
+            .stakes_cache
+            .stakes()
+            .vote_accounts()
+            .iter()
+            .map(|(vote_pubkey, _vote_account)| *vote_pubkey)
+            .collect();
+        let pending_commissions: Vec<_> = thread_pool.install(|| {
+            vote_pubkeys
+                .into_par_iter()
+                .filter_map(|vote_pubkey| {
+                    let pending_address = pending_commission_address(&vote_pubkey);
+                    let pending_account = self.get_account_with_fixed_root(&pending_address)?;
+                    if !solana_vote_program::check_id(pending_account.owner()) {
+                        return None;
+                    }
+                    let pending_commission =
+                        PendingCommission::from_account_data(pending_account.data())?;
+                    (pending_commission.effective_epoch <= rewarded_epoch).then_some((
+                        vote_pubkey,
+                        pending_address,
+                        pending_account,
+                        pending_commission,
+                    ))
+                })
+                .collect()
+        });
+
+        for (vote_pubkey, pending_address, pending_account, pending_commission) in
+            pending_commissions
+        {
+            let Some(mut vote_account) = self.get_account_with_fixed_root(&vote_pubkey) else {
+                continue;
+            };
+            if let Err(err) = apply_pending_commission(&mut vote_account, &pending_commission) {
+                warn!("failed to apply pending commission of vote account {vote_pubkey}: {err}");
+                continue;
+            }
+            vote_account
+                .checked_add_lamports(pending_account.lamports())
+                .expect("vote account lamports can't overflow the capitalization");
+            self.store_account(&vote_pubkey, &vote_account);
+            self.store_account(&pending_address, &AccountSharedData::default());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::genesis_utils::{create_genesis_config_with_leader, GenesisConfigInfo},
+        solana_rent::Rent,
+        solana_vote_program::vote_state::{
+            commission_schedule::PENDING_COMMISSION_SIZE, VoteStateVersions,
+        },
+        std::sync::Arc,
+    };
+
+    fn new_thread_pool() -> ThreadPool {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap()
+    }
+
+    fn store_pending_commission(
+        bank: &Bank,
+        vote_pubkey: &Pubkey,
+        pending_commission: &PendingCommission,
+    ) -> u64 {
+        let lamports = Rent::default().minimum_balance(PENDING_COMMISSION_SIZE);
+        let mut pending_account = AccountSharedData::new(
+            lamports,
+            PENDING_COMMISSION_SIZE,
+            &solana_vote_program::id(),
+        );
+        pending_account.set_data_from_slice(&pending_commission.to_bytes());
+        bank.store_account(&pending_commission_address(vote_pubkey), &pending_account);
+        lamports
+    }
+
+    fn vote_commission(bank: &Bank, vote_pubkey: &Pubkey) -> u8 {
+        let vote_account = bank.get_account(vote_pubkey).unwrap();
+        bincode::deserialize::<VoteStateVersions>(vote_account.data())
+            .unwrap()
+            .convert_to_current()
+            .commission
+    }
+
+    #[test]
+    fn test_apply_pending_vote_commissions() {
+        let GenesisConfigInfo { genesis_config, .. } =
+            create_genesis_config_with_leader(1_000_000_000, &Pubkey::new_unique(), 42);
+        let bank = Arc::new(Bank::new_for_tests(&genesis_config));
+        let vote_pubkey = *bank
+            .stakes_cache
+            .stakes()
+            .vote_accounts()
+            .iter()
+            .next()
+            .unwrap()
+            .0;
+        let vote_account = bank.get_account(&vote_pubkey).unwrap();
+        let commission = vote_commission(&bank, &vote_pubkey);
+        let pending_commission = PendingCommission {
+            commission: commission + 10,
+            effective_epoch: 2,
+        };
+        let pending_lamports = store_pending_commission(&bank, &vote_pubkey, &pending_commission);
+        let capitalization = bank.capitalization();
+        let thread_pool = new_thread_pool();
+
+        // not in effect yet
+        bank.apply_pending_vote_commissions(1, &thread_pool);
+        assert_eq!(vote_commission(&bank, &vote_pubkey), commission);
+        assert!(bank
+            .get_account(&pending_commission_address(&vote_pubkey))
+            .is_some());
+
+        // in effect, the vote account keeps its size and stays in the stakes cache
+        bank.apply_pending_vote_commissions(2, &thread_pool);
+        assert_eq!(vote_commission(&bank, &vote_pubkey), commission + 10);
+        let applied_vote_account = bank.get_account(&vote_pubkey).unwrap();
+        assert_eq!(applied_vote_account.data().len(), vote_account.data().len());
+        assert_eq!(
+            applied_vote_account.lamports(),
+            vote_account.lamports() + pending_lamports
+        );
+        assert_eq!(
+            bank.get_balance(&pending_commission_address(&vote_pubkey)),
+            0
+        );
+        let cached_vote_account = bank
+            .stakes_cache
+            .stakes()
+            .vote_accounts()
+            .get(&vote_pubkey)
+            .cloned()
+            .unwrap();
+        assert_eq!(
+            cached_vote_account.vote_state_view().commission(),
+            commission + 10
+        );
+        assert_eq!(bank.capitalization(), capitalization);
+    }
+
+    #[test]
+    fn test_apply_pending_vote_commissions_ignores_foreign_account() {
+        let GenesisConfigInfo { genesis_config, .. } =
+            create_genesis_config_with_leader(1_000_000_000, &Pubkey::new_unique(), 42);
+        let bank = Arc::new(Bank::new_for_tests(&genesis_config));
+        let vote_pubkey = *bank
+            .stakes_cache
+            .stakes()
+            .vote_accounts()
+            .iter()
+            .next()
+            .unwrap()
+            .0;
+        let commission = vote_commission(&bank, &vote_pubkey);
+        let pending_commission = PendingCommission {
+            commission: commission + 10,
+            effective_epoch: 0,
+        };
+        let mut pending_account = AccountSharedData::new(
+            1_000_000,
+            PENDING_COMMISSION_SIZE,
+            &solana_pubkey::new_rand(),
+        );
+        pending_account.set_data_from_slice(&pending_commission.to_bytes());
+        bank.store_account(&pending_commission_address(&vote_pubkey), &pending_account);
+
+        bank.apply_pending_vote_commissions(0, &new_thread_pool());
+        assert_eq!(vote_commission(&bank, &vote_pubkey), commission);
+        assert_eq!(
+            bank.get_account(&pending_commission_address(&vote_pubkey)),
+            Some(pending_account)
+        );
+    }
+
+    #[test]
+    fn test_apply_pending_vote_commissions_feature_disabled() {
+        let GenesisConfigInfo {
+            mut genesis_config, ..
+        } = create_genesis_config_with_leader(1_000_000_000, &Pubkey::new_unique(), 42);
+        genesis_config
+            .accounts
+            .remove(&feature_set::enable_vote_commission_schedule::id());
+        let bank = Arc::new(Bank::new_for_tests(&genesis_config));
+        let vote_pubkey = *bank
+            .stakes_cache
+            .stakes()
+            .vote_accounts()
+            .iter()
+            .next()
+            .unwrap()
+            .0;
+        let commission = vote_commission(&bank, &vote_pubkey);
+        store_pending_commission(
+            &bank,
+            &vote_pubkey,
+            &PendingCommission {
+                commission: commission + 10,
+                effective_epoch: 0,
+            },
+        );
+
+        bank.apply_pending_vote_commissions(0, &new_thread_pool());
+        assert_eq!(vote_commission(&bank, &vote_pubkey), commission);
+    }
+}
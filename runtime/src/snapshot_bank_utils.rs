@@ -1,15 +1,11 @@
 #[cfg(feature = "dev-context-only-utils")]
-use {
-    crate::{
-        bank::BankFieldsToDeserialize,
-        serde_snapshot::fields_from_streams,
-        snapshot_utils::{
-            deserialize_snapshot_data_files, verify_unpacked_snapshots_dir_and_version,
-            SnapshotRootPaths, UnpackedSnapshotsDirAndVersion,
-        },
+use crate::{
+    bank::BankFieldsToDeserialize,
+    serde_snapshot::fields_from_streams,
+    snapshot_utils::{
+        deserialize_snapshot_data_files, verify_unpacked_snapshots_dir_and_version,
+        SnapshotRootPaths, UnpackedSnapshotsDirAndVersion,
     },
-    solana_accounts_db::accounts_file::StorageAccess,
-    tempfile::TempDir,
 };
 use {
     crate::{
@@ -43,13 +39,15 @@ use {
         accounts_db::{
             AccountStorageEntry, AccountsDbConfig, AtomicAccountsFileId, CalcAccountsHashDataSource,
         },
-        accounts_hash::MerkleOrLatticeAccountsHash,
+        accounts_file::StorageAccess,
+        accounts_hash::{AccountsLtHash, MerkleOrLatticeAccountsHash},
         accounts_update_notifier_interface::AccountsUpdateNotifier,
         utils::remove_dir_contents,
     },
     solana_builtins::prototype::BuiltinPrototype,
     solana_clock::{Epoch, Slot},
     solana_genesis_config::GenesisConfig,
+    solana_hash::Hash,
     solana_measure::{measure::Measure, measure_time},
     solana_pubkey::Pubkey,
     solana_slot_history::{Check, SlotHistory},
@@ -59,6 +57,7 @@ use {
         path::{Path, PathBuf},
         sync::{atomic::AtomicBool, Arc},
     },
+    tempfile::TempDir,
 };
 
 pub fn serialize_status_cache(
@@ -155,6 +154,161 @@ fn bank_fields_from_snapshots(
     })
 }
 
+/// Summary of a snapshot's contents, gathered without rebuilding its bank
+#[derive(Debug)]
+pub struct SnapshotSummary {
+    pub slot: Slot,
+    pub parent_slot: Slot,
+    pub epoch: Epoch,
+    pub block_height: u64,
+    pub bank_hash: Hash,
+    pub parent_bank_hash: Hash,
+    pub capitalization: u64,
+    pub transaction_count: u64,
+    pub signature_count: u64,
+    pub accounts_data_len: u64,
+    pub accounts_lt_hash: Option<AccountsLtHash>,
+    /// Epoch stakes, sorted by epoch
+    pub epoch_stakes: Vec<EpochStakesSummary>,
+    pub status_cache: StatusCacheSummary,
+    pub storages: StoragesSummary,
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct EpochStakesSummary {
+    pub epoch: Epoch,
+    pub total_stake: u64,
+    pub num_vote_accounts: usize,
+    pub num_nodes: usize,
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct StatusCacheSummary {
+    pub num_slots: usize,
+    pub num_blockhashes: usize,
+    pub num_statuses: usize,
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct StoragesSummary {
+    pub num_files: usize,
+    /// Number of accounts stored in the files, including accounts shadowed by newer versions
+    pub num_stored_accounts: usize,
+    pub total_bytes: u64,
+    pub largest_file_bytes: u64,
+    pub oldest_slot: Option<Slot>,
+    pub newest_slot: Option<Slot>,
+}
+
+/// Unarchives the given snapshots into a temporary directory and summarizes their contents
+///
+/// The bank isn't rebuilt, so neither a genesis config nor the accounts index is needed.
+pub fn summarize_snapshot_archives(
+    full_snapshot_archive_info: &FullSnapshotArchiveInfo,
+    incremental_snapshot_archive_info: Option<&IncrementalSnapshotArchiveInfo>,
+    storage_access: StorageAccess,
+) -> snapshot_utils::Result<SnapshotSummary> {
+    let temp_unpack_dir = TempDir::new()?;
+    let temp_accounts_dir = TempDir::new()?;
+    let account_paths = vec![temp_accounts_dir.path().to_path_buf()];
+
+    let (
+        UnarchivedSnapshots {
+            full_storage,
+            incremental_storage,
+            bank_fields,
+            full_unpacked_snapshots_dir_and_version,
+            incremental_unpacked_snapshots_dir_and_version,
+            ..
+        },
+        _guard,
+    ) = verify_and_unarchive_snapshots(
+        &temp_unpack_dir,
+        full_snapshot_archive_info,
+        incremental_snapshot_archive_info,
+        &account_paths,
+        storage_access,
+    )?;
+
+    let mut storages = StoragesSummary::default();
+    for storage in full_storage.values().chain(
+        incremental_storage
+            .iter()
+            .flat_map(|storage| storage.values()),
+    ) {
+        let mut num_accounts = 0;
+        storage
+            .accounts
+            .scan_pubkeys(|_| num_accounts += 1)
+            .map_err(|err| SnapshotError::RebuildStorages(err.to_string()))?;
+        let file_bytes = storage.accounts.len() as u64;
+        storages.num_files += 1;
+        storages.num_stored_accounts += num_accounts;
+        storages.total_bytes += file_bytes;
+        storages.largest_file_bytes = storages.largest_file_bytes.max(file_bytes);
+        storages.oldest_slot = Some(
+            storages
+                .oldest_slot
+                .map_or(storage.slot(), |slot| slot.min(storage.slot())),
+        );
+        storages.newest_slot = Some(
+            storages
+                .newest_slot
+                .map_or(storage.slot(), |slot| slot.max(storage.slot())),
+        );
+    }
+
+    // The status cache of the incremental snapshot supersedes the full snapshot's
+    let status_cache_path = incremental_unpacked_snapshots_dir_and_version
+        .as_ref()
+        .unwrap_or(&full_unpacked_snapshots_dir_and_version)
+        .unpacked_snapshots_dir
+        .join(snapshot_utils::SNAPSHOT_STATUS_CACHE_FILENAME);
+    let slot_deltas = deserialize_status_cache(&status_cache_path)?;
+    let mut status_cache = StatusCacheSummary {
+        num_slots: slot_deltas.len(),
+        ..StatusCacheSummary::default()
+    };
+    for (_slot, _is_root, status) in &slot_deltas {
+        let status = status.lock().unwrap();
+        status_cache.num_blockhashes += status.len();
+        status_cache.num_statuses += status
+            .values()
+            .map(|(_key_index, statuses)| statuses.len())
+            .sum::<usize>();
+    }
+
+    let bank_fields = bank_fields.collapse_into();
+    let mut epoch_stakes: Vec<_> = bank_fields
+        .versioned_epoch_stakes
+        .iter()
+        .map(|(epoch, stakes)| EpochStakesSummary {
+            epoch: *epoch,
+            total_stake: stakes.total_stake(),
+            num_vote_accounts: stakes.stakes().vote_accounts().len(),
+            num_nodes: stakes.node_id_to_vote_accounts().len(),
+        })
+        .collect();
+    epoch_stakes.sort_unstable_by_key(|epoch_stakes| epoch_stakes.epoch);
+
+    Ok(SnapshotSummary {
+        slot: bank_fields.slot,
+        parent_slot: bank_fields.parent_slot,
+        epoch: bank_fields.epoch,
+        block_height: bank_fields.block_height,
+        bank_hash: bank_fields.hash,
+        parent_bank_hash: bank_fields.parent_hash,
+        capitalization: bank_fields.capitalization,
+        transaction_count: bank_fields.transaction_count,
+        signature_count: bank_fields.signature_count,
+        accounts_data_len: bank_fields.accounts_data_len,
+        accounts_lt_hash: bank_fields.accounts_lt_hash,
+        epoch_stakes,
+        status_cache,
+        storages,
+    })
+}
+
 /// Rebuild bank from snapshot archives.  Handles either just a full snapshot, or both a full
 /// snapshot and an incremental snapshot.
 #[allow(clippy::too_many_arguments)]
@@ -982,7 +1136,8 @@ mod tests {
                 purge_all_bank_snapshots, purge_bank_snapshot,
                 purge_bank_snapshots_older_than_slot, purge_incomplete_bank_snapshots,
                 purge_old_bank_snapshots, purge_old_bank_snapshots_at_startup,
-                snapshot_storage_rebuilder::get_slot_and_append_vec_id, BankSnapshotKind,
+                snapshot_storage_rebuilder::get_slot_and_append_vec_id,
+                unarchive_snapshot_storage_file, BankSnapshotKind, ZstdConfig,
                 BANK_SNAPSHOT_PRE_FILENAME_EXTENSION, SNAPSHOT_FULL_SNAPSHOT_SLOT_FILENAME,
             },
             status_cache::Status,
@@ -1064,8 +1219,12 @@ mod tests {
 
     /// Test roundtrip of bank to a full snapshot, then back again.  This test creates the simplest
     /// bank possible, so the contents of the snapshot archive will be quite minimal.
-    #[test]
-    fn test_roundtrip_bank_to_and_from_full_snapshot_simple() {
+    #[test_case(ArchiveFormat::TarZstd { config: ZstdConfig::default() })]
+    #[test_case(ArchiveFormat::TarZstdChunked { config: ZstdConfig::default() })]
+    #[test_case(ArchiveFormat::TarLz4)]
+    fn test_roundtrip_bank_to_and_from_full_snapshot_simple(
+        snapshot_archive_format: ArchiveFormat,
+    ) {
         let genesis_config = GenesisConfig::default();
         let original_bank = Bank::new_for_tests(&genesis_config);
 
@@ -1075,7 +1234,6 @@ mod tests {
         let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
         let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
         let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
-        let snapshot_archive_format = SnapshotConfig::default().archive_format;
 
         let snapshot_archive_info = bank_to_full_snapshot_archive(
             &bank_snapshots_dir,
@@ -1087,6 +1245,22 @@ mod tests {
         )
         .unwrap();
 
+        // A single storage file can be extracted without restoring the whole snapshot
+        let storage = original_bank.get_snapshot_storages(None).pop().unwrap();
+        let unpack_dir = tempfile::TempDir::new().unwrap();
+        let unpacked_path = unarchive_snapshot_storage_file(
+            snapshot_archive_info.path(),
+            snapshot_archive_format,
+            storage.slot(),
+            storage.id(),
+            unpack_dir.path(),
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read(unpacked_path).unwrap().len(),
+            storage.accounts.len(),
+        );
+
         let (roundtrip_bank, _) = bank_from_snapshot_archives(
             &[accounts_dir],
             bank_snapshots_dir.path(),
@@ -1109,6 +1283,40 @@ mod tests {
         assert_eq!(original_bank, roundtrip_bank);
     }
 
+    /// Test that a snapshot archive can be summarized without rebuilding its bank
+    #[test]
+    fn test_summarize_snapshot_archives() {
+        let genesis_config = GenesisConfig::default();
+        let bank = Bank::new_for_tests(&genesis_config);
+        bank.fill_bank_with_ticks_for_tests();
+
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archive_info = bank_to_full_snapshot_archive(
+            &bank_snapshots_dir,
+            &bank,
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            SnapshotConfig::default().archive_format,
+        )
+        .unwrap();
+
+        let summary =
+            summarize_snapshot_archives(&snapshot_archive_info, None, StorageAccess::default())
+                .unwrap();
+        assert_eq!(summary.slot, bank.slot());
+        assert_eq!(summary.bank_hash, bank.hash());
+        assert_eq!(summary.capitalization, bank.capitalization());
+        assert_eq!(summary.epoch_stakes.len(), bank.epoch_stakes_map().len(),);
+        assert_eq!(
+            summary.storages.num_files,
+            bank.get_snapshot_storages(None).len(),
+        );
+        assert!(summary.storages.num_stored_accounts > 0);
+    }
+
     /// This tests handling of obsolete accounts during a full snapshot with obsolete accounts
     /// marked in the accounts database. This test injects them directly
     #[test]
@@ -36,6 +36,7 @@ pub struct SnapshotMinimizer<'a> {
     bank: &'a Bank,
     starting_slot: Slot,
     minimized_account_set: DashSet<Pubkey>,
+    included_owners: HashSet<Pubkey>,
 }
 
 impl<'a> SnapshotMinimizer<'a> {
@@ -46,12 +47,33 @@ impl<'a> SnapshotMinimizer<'a> {
     /// This function will modify accounts_db by removing accounts not needed to replay [starting_slot, ending_slot],
     /// and update the bank's capitalization.
     pub fn minimize(bank: &'a Bank, starting_slot: Slot, transaction_account_set: DashSet<Pubkey>) {
+        Self::minimize_with_included_owners(
+            bank,
+            starting_slot,
+            transaction_account_set,
+            HashSet::new(),
+        );
+    }
+
+    /// Same as `minimize`, but also keeps every account owned by one of `included_owners`.
+    ///
+    /// This is used to create small snapshots scoped to the programs and accounts a test cares
+    /// about. The accounts needed to boot the bank (features, sysvars, builtins, vote and stake
+    /// accounts) are kept as well.
+    pub fn minimize_with_included_owners(
+        bank: &'a Bank,
+        starting_slot: Slot,
+        account_set: DashSet<Pubkey>,
+        included_owners: HashSet<Pubkey>,
+    ) {
         let minimizer = SnapshotMinimizer {
             bank,
             starting_slot,
-            minimized_account_set: transaction_account_set,
+            minimized_account_set: account_set,
+            included_owners,
         };
 
+        minimizer.add_accounts(Self::get_included_owner_accounts, "included owner accounts");
         minimizer.add_accounts(Self::get_active_bank_features, "active bank features");
         minimizer.add_accounts(Self::get_inactive_bank_features, "inactive bank features");
         minimizer.add_accounts(Self::get_static_runtime_accounts, "static runtime accounts");
@@ -97,6 +119,25 @@ impl<'a> SnapshotMinimizer<'a> {
         );
     }
 
+    /// Used to get accounts owned by `included_owners` in `minimize`
+    fn get_included_owner_accounts(&self) {
+        if self.included_owners.is_empty() {
+            return;
+        }
+        self.bank
+            .scan_all_accounts(
+                |item| {
+                    if let Some((pubkey, account, _slot)) = item {
+                        if self.included_owners.contains(account.owner()) {
+                            self.minimized_account_set.insert(*pubkey);
+                        }
+                    }
+                },
+                false,
+            )
+            .unwrap();
+    }
+
     /// Used to get active bank feature accounts in `minimize`.
     fn get_active_bank_features(&self) {
         self.bank
@@ -381,7 +422,7 @@ mod tests {
         solana_sdk_ids::bpf_loader_upgradeable,
         solana_signer::Signer,
         solana_stake_interface as stake,
-        std::sync::Arc,
+        std::{collections::HashSet, sync::Arc},
         tempfile::TempDir,
     };
 
@@ -403,6 +444,7 @@ mod tests {
             bank: &bank,
             starting_slot: 0,
             minimized_account_set: DashSet::new(),
+            included_owners: HashSet::new(),
         };
         minimizer.get_vote_accounts();
 
@@ -431,6 +473,7 @@ mod tests {
             bank: &bank,
             starting_slot: 0,
             minimized_account_set: DashSet::new(),
+            included_owners: HashSet::new(),
         };
         minimizer.get_stake_accounts();
 
@@ -470,6 +513,7 @@ mod tests {
             bank: &bank,
             starting_slot: 0,
             minimized_account_set: owner_accounts,
+            included_owners: HashSet::new(),
         };
 
         minimizer.get_owner_accounts();
@@ -477,6 +521,41 @@ mod tests {
         assert!(minimizer.minimized_account_set.contains(&owner_pubkey));
     }
 
+    #[test]
+    fn test_minimization_get_included_owner_accounts() {
+        solana_logger::setup();
+
+        let (genesis_config, _) = create_genesis_config(1_000_000);
+        let bank = Arc::new(Bank::new_for_tests(&genesis_config));
+
+        let included_owner = solana_pubkey::new_rand();
+        let owned_pubkey = solana_pubkey::new_rand();
+        let other_pubkey = solana_pubkey::new_rand();
+        bank.store_account(
+            &owned_pubkey,
+            &AccountSharedData::new(1, 0, &included_owner),
+        );
+        bank.store_account(
+            &other_pubkey,
+            &AccountSharedData::new(1, 0, &solana_pubkey::new_rand()),
+        );
+
+        let minimizer = SnapshotMinimizer {
+            bank: &bank,
+            starting_slot: 0,
+            minimized_account_set: DashSet::new(),
+            included_owners: HashSet::from([included_owner]),
+        };
+        minimizer.get_included_owner_accounts();
+        assert_eq!(minimizer.minimized_account_set.len(), 1);
+        assert!(minimizer.minimized_account_set.contains(&owned_pubkey));
+
+        // The owner itself is picked up along with the other owners
+        minimizer.get_owner_accounts();
+        assert!(minimizer.minimized_account_set.contains(&included_owner));
+        assert!(!minimizer.minimized_account_set.contains(&other_pubkey));
+    }
+
     #[test]
     fn test_minimization_add_programdata_accounts() {
         solana_logger::setup();
@@ -507,6 +586,7 @@ mod tests {
             bank: &bank,
             starting_slot: 0,
             minimized_account_set: programdata_accounts,
+            included_owners: HashSet::new(),
         };
         minimizer.get_programdata_accounts();
         assert_eq!(minimizer.minimized_account_set.len(), 1);
@@ -564,6 +644,7 @@ mod tests {
             bank: &bank,
             starting_slot: current_slot,
             minimized_account_set,
+            included_owners: HashSet::new(),
         };
         minimizer.minimize_accounts_db();
 
@@ -664,4 +745,93 @@ mod tests {
         roundtrip_bank.wait_for_initial_accounts_hash_verification_completed_for_tests();
         assert_eq!(roundtrip_bank, *bank);
     }
+
+    /// Ensure that a snapshot minimized to the accounts of some owners only keeps those accounts,
+    /// and is loadable.
+    #[test]
+    fn test_minimize_with_included_owners() {
+        let genesis_config_info = genesis_utils::create_genesis_config(123_456_789_000_000_000);
+        let (bank, bank_forks) =
+            Bank::new_with_bank_forks_for_tests(&genesis_config_info.genesis_config);
+
+        // write the accounts to a slot older than the snapshot slot, which is never minimized
+        let included_owner = Pubkey::new_unique();
+        let owned_pubkey = Pubkey::new_unique();
+        let other_pubkey = Pubkey::new_unique();
+        let slot = bank.slot() + 1;
+        let bank = Bank::new_from_parent(bank, &Pubkey::default(), slot);
+        let bank = bank_forks
+            .write()
+            .unwrap()
+            .insert(bank)
+            .clone_without_scheduler();
+        bank.store_account(
+            &owned_pubkey,
+            &AccountSharedData::new(1_000_000_000, 0, &included_owner),
+        );
+        bank.store_account(
+            &other_pubkey,
+            &AccountSharedData::new(1_000_000_000, 0, &Pubkey::new_unique()),
+        );
+        bank.fill_bank_with_ticks_for_tests();
+        bank.squash();
+        bank.force_flush_accounts_cache();
+
+        let slot = bank.slot() + 1;
+        let bank = Bank::new_from_parent(bank, &Pubkey::default(), slot);
+        let bank = bank_forks
+            .write()
+            .unwrap()
+            .insert(bank)
+            .clone_without_scheduler();
+        bank.fill_bank_with_ticks_for_tests();
+        bank.squash();
+        bank.force_flush_accounts_cache();
+
+        SnapshotMinimizer::minimize_with_included_owners(
+            &bank,
+            bank.slot(),
+            DashSet::new(),
+            HashSet::from([included_owner]),
+        );
+        assert!(bank.get_account(&owned_pubkey).is_some());
+        assert!(bank.get_account(&other_pubkey).is_none());
+
+        let snapshot_config = SnapshotConfig::default();
+        let bank_snapshots_dir = TempDir::new().unwrap();
+        let snapshot_archives_dir = TempDir::new().unwrap();
+        let snapshot = snapshot_bank_utils::bank_to_full_snapshot_archive(
+            &bank_snapshots_dir,
+            &bank,
+            Some(snapshot_config.snapshot_version),
+            &snapshot_archives_dir,
+            &snapshot_archives_dir,
+            snapshot_config.archive_format,
+        )
+        .unwrap();
+        let (_accounts_tempdir, accounts_dir) = snapshot_utils::create_tmp_accounts_dir_for_tests();
+        let (roundtrip_bank, _) = snapshot_bank_utils::bank_from_snapshot_archives(
+            &[accounts_dir],
+            &bank_snapshots_dir,
+            &snapshot,
+            None,
+            &genesis_config_info.genesis_config,
+            &RuntimeConfig::default(),
+            None,
+            None,
+            None,
+            false,
+            false,
+            false,
+            Some(ACCOUNTS_DB_CONFIG_FOR_TESTING),
+            None,
+            Arc::default(),
+        )
+        .unwrap();
+
+        roundtrip_bank.wait_for_initial_accounts_hash_verification_completed_for_tests();
+        assert_eq!(roundtrip_bank, *bank);
+        assert!(roundtrip_bank.get_account(&owned_pubkey).is_some());
+        assert!(roundtrip_bank.get_account(&other_pubkey).is_none());
+    }
 }
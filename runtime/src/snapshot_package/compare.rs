@@ -1,5 +1,7 @@
 use {
     super::{AccountsPackage, AccountsPackageKind, SnapshotKind, SnapshotPackage},
+    solana_account::AccountSharedData,
+    solana_pubkey::Pubkey,
     std::cmp::Ordering::{self, Equal, Greater, Less},
 };
 
@@ -53,6 +55,58 @@ pub fn cmp_snapshot_kinds_by_priority(a: &SnapshotKind, b: &SnapshotKind) -> Ord
     }
 }
 
+/// A difference between the accounts of two snapshots
+#[derive(Debug, Eq, PartialEq)]
+pub enum AccountDiff {
+    /// The account only exists in the new snapshot
+    Added(AccountSharedData),
+    /// The account only exists in the old snapshot
+    Removed(AccountSharedData),
+    /// The account exists in both snapshots, with different contents
+    Changed {
+        old: AccountSharedData,
+        new: AccountSharedData,
+    },
+}
+
+/// Compare two streams of accounts, both sorted by pubkey, and call `f` with every account that
+/// differs between them
+///
+/// Only one account of each stream is held at a time, so snapshots of any size can be compared.
+pub fn diff_accounts_sorted_by_pubkey(
+    old_accounts: impl IntoIterator<Item = (Pubkey, AccountSharedData)>,
+    new_accounts: impl IntoIterator<Item = (Pubkey, AccountSharedData)>,
+    mut f: impl FnMut(Pubkey, AccountDiff),
+) {
+    let mut old_accounts = old_accounts.into_iter().peekable();
+    let mut new_accounts = new_accounts.into_iter().peekable();
+    loop {
+        let ordering = match (old_accounts.peek(), new_accounts.peek()) {
+            (None, None) => break,
+            (Some(_), None) => Less,
+            (None, Some(_)) => Greater,
+            (Some((old_pubkey, _)), Some((new_pubkey, _))) => old_pubkey.cmp(new_pubkey),
+        };
+        match ordering {
+            Less => {
+                let (pubkey, old) = old_accounts.next().unwrap();
+                f(pubkey, AccountDiff::Removed(old));
+            }
+            Greater => {
+                let (pubkey, new) = new_accounts.next().unwrap();
+                f(pubkey, AccountDiff::Added(new));
+            }
+            Equal => {
+                let (pubkey, old) = old_accounts.next().unwrap();
+                let (_, new) = new_accounts.next().unwrap();
+                if old != new {
+                    f(pubkey, AccountDiff::Changed { old, new });
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use {super::*, solana_clock::Slot};
@@ -335,4 +389,44 @@ mod tests {
             assert_eq!(expected_result, actual_result);
         }
     }
+
+    #[test]
+    fn test_diff_accounts_sorted_by_pubkey() {
+        let owner = Pubkey::new_unique();
+        let account = |lamports| AccountSharedData::new(lamports, 0, &owner);
+        let mut pubkeys: Vec<_> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        pubkeys.sort_unstable();
+
+        let old_accounts = vec![
+            (pubkeys[0], account(1)),
+            (pubkeys[1], account(2)),
+            (pubkeys[2], account(3)),
+            (pubkeys[4], account(5)),
+        ];
+        let new_accounts = vec![
+            (pubkeys[1], account(2)),
+            (pubkeys[2], account(33)),
+            (pubkeys[3], account(4)),
+            (pubkeys[4], account(5)),
+        ];
+
+        let mut diffs = vec![];
+        diff_accounts_sorted_by_pubkey(old_accounts, new_accounts, |pubkey, diff| {
+            diffs.push((pubkey, diff))
+        });
+        assert_eq!(
+            diffs,
+            vec![
+                (pubkeys[0], AccountDiff::Removed(account(1))),
+                (
+                    pubkeys[2],
+                    AccountDiff::Changed {
+                        old: account(3),
+                        new: account(33),
+                    },
+                ),
+                (pubkeys[3], AccountDiff::Added(account(4))),
+            ],
+        );
+    }
 }
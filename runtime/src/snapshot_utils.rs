@@ -24,7 +24,7 @@ use {
     solana_accounts_db::{
         account_storage::AccountStorageMap,
         account_storage_reader::AccountStorageReader,
-        accounts_db::{AccountStorageEntry, AtomicAccountsFileId},
+        accounts_db::{AccountStorageEntry, AccountsFileId, AtomicAccountsFileId},
         accounts_file::{AccountsFile, AccountsFileError, StorageAccess},
         accounts_hash::{AccountsDeltaHash, AccountsHash},
         hardened_unpack::{self, ArchiveChunker, BytesChannelReader, MultiBytes, UnpackError},
@@ -82,9 +82,8 @@ pub const DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN: NonZeroUsize =
     NonZeroUsize::new(2).unwrap();
 pub const DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN: NonZeroUsize =
     NonZeroUsize::new(4).unwrap();
-pub const FULL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str =
-    r"^snapshot-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar\.zst|tar\.lz4)$";
-pub const INCREMENTAL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^incremental-snapshot-(?P<base>[[:digit:]]+)-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar\.zst|tar\.lz4)$";
+pub const FULL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^snapshot-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar\.zst|tar\.lz4|chunked\.tar\.zst)$";
+pub const INCREMENTAL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^incremental-snapshot-(?P<base>[[:digit:]]+)-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar\.zst|tar\.lz4|chunked\.tar\.zst)$";
 
 #[derive(Copy, Clone, Default, Eq, PartialEq, Debug)]
 pub enum SnapshotVersion {
@@ -1113,7 +1112,7 @@ fn archive_snapshot(
         let archive_file = fs::File::create(&staging_archive_path)
             .map_err(|err| E::CreateArchiveFile(err, staging_archive_path.clone()))?;
 
-        let do_archive_files = |encoder: &mut dyn Write| -> std::result::Result<(), E> {
+        let do_archive_files = |encoder: &mut dyn ArchiveWriter| -> std::result::Result<(), E> {
             let mut archive = tar::Builder::new(encoder);
             // Disable sparse file handling.  This seems to be the root cause of an issue when
             // upgrading v2.0 to v2.1, and the tar crate from 0.4.41 to 0.4.42.
@@ -1142,14 +1141,27 @@ fn archive_snapshot(
                         E::AccountStorageReaderError(err, storage.path().to_path_buf())
                     })?;
                 let mut header = tar::Header::new_gnu();
-                header.set_path(path_in_archive).map_err(|err| {
+                let size = reader.len() as u64;
+                header.set_path(&path_in_archive).map_err(|err| {
                     E::ArchiveAccountStorageFile(err, storage.path().to_path_buf())
                 })?;
-                header.set_size(reader.len() as u64);
+                header.set_size(size);
                 header.set_cksum();
                 archive.append(&header, reader).map_err(|err| {
                     E::ArchiveAccountStorageFile(err, storage.path().to_path_buf())
                 })?;
+                archive
+                    .get_mut()
+                    .storage_file_written(
+                        &format!(
+                            "{ACCOUNTS_DIR}/{}",
+                            AccountsFile::file_name(storage.slot(), storage.id())
+                        ),
+                        size,
+                    )
+                    .map_err(|err| {
+                        E::ArchiveAccountStorageFile(err, storage.path().to_path_buf())
+                    })?;
             }
 
             archive.into_inner().map_err(E::FinishArchive)?;
@@ -1173,6 +1185,16 @@ fn archive_snapshot(
                 let (_output, result) = encoder.finish();
                 result.map_err(E::FinishEncoder)?;
             }
+            ArchiveFormat::TarZstdChunked { config } => {
+                let mut encoder = ChunkedZstdEncoder::new(
+                    archive_file,
+                    config.compression_level,
+                    ChunkedZstdEncoder::<fs::File>::DEFAULT_CHUNK_SIZE,
+                )
+                .map_err(E::CreateEncoder)?;
+                do_archive_files(&mut encoder)?;
+                encoder.finish().map_err(E::FinishEncoder)?;
+            }
         };
     }
 
@@ -1717,6 +1739,7 @@ fn streaming_unarchive_snapshot(
     handles.push(spawn_archive_chunker_thread(
         snapshot_archive_path,
         archive_format,
+        num_threads,
         chunk_sender,
     ));
 
@@ -1749,17 +1772,31 @@ fn archive_chunker_from_path(
     Ok(ArchiveChunker::new(decompressor))
 }
 
+/// Spawns a thread decompressing the archive and sending its chunks to the
+/// unpacking threads.
+///
+/// The chunks of a `TarZstdChunked` archive are decompressed in parallel, on
+/// `num_chunked_decoder_threads` threads.
 fn spawn_archive_chunker_thread(
     archive_path: impl AsRef<Path>,
     archive_format: ArchiveFormat,
+    num_chunked_decoder_threads: usize,
     chunk_sender: Sender<MultiBytes>,
 ) -> JoinHandle<Result<()>> {
     let archive_path = archive_path.as_ref().to_path_buf();
     Builder::new()
         .name("solTarDecompr".to_string())
         .spawn(move || {
-            let chunker = archive_chunker_from_path(&archive_path, archive_format)?;
-            chunker.decode_and_send_chunks(chunk_sender)?;
+            if let ArchiveFormat::TarZstdChunked { .. } = archive_format {
+                hardened_unpack::decode_and_send_archive_chunks(
+                    &archive_path,
+                    num_chunked_decoder_threads,
+                    chunk_sender,
+                )?;
+            } else {
+                let chunker = archive_chunker_from_path(&archive_path, archive_format)?;
+                chunker.decode_and_send_chunks(chunk_sender)?;
+            }
             Ok(())
         })
         .unwrap()
@@ -1957,6 +1994,13 @@ fn unarchive_snapshot(
                 measure_name
             );
             info!("{}", measure_untar);
+            if let ArchiveFormat::TarZstdChunked { .. } = archive_format {
+                // A storage file missing from the archive would otherwise go
+                // unnoticed until the accounts hash is verified
+                let index =
+                    hardened_unpack::read_chunked_archive_index(snapshot_archive_path.as_ref())?;
+                SnapshotStorageRebuilder::check_archive_index(&accounts_db_fields, &index)?;
+            }
             create_snapshot_meta_files_for_unarchived_snapshot(&unpack_dir)?;
 
             Ok(UnarchivedSnapshot {
@@ -1978,6 +2022,46 @@ fn unarchive_snapshot(
     snapshot_result
 }
 
+/// Unpacks the account storage file of `slot` and `id` from a snapshot archive
+/// into `dst_dir`, and returns the path of the unpacked file.
+///
+/// Only the chunk holding the file is decompressed from a `TarZstdChunked`
+/// archive, other formats must be decompressed entirely.
+pub fn unarchive_snapshot_storage_file(
+    snapshot_archive_path: impl AsRef<Path>,
+    archive_format: ArchiveFormat,
+    slot: Slot,
+    id: AccountsFileId,
+    dst_dir: impl AsRef<Path>,
+) -> Result<PathBuf> {
+    let snapshot_archive_path = snapshot_archive_path.as_ref();
+    let file_name = AccountsFile::file_name(slot, id);
+    let unpacked_path = if let ArchiveFormat::TarZstdChunked { .. } = archive_format {
+        hardened_unpack::unpack_snapshot_storage_file_from_chunked_archive(
+            snapshot_archive_path,
+            &file_name,
+            dst_dir.as_ref(),
+        )?
+    } else {
+        let reader = BufReader::new(fs::File::open(snapshot_archive_path)?);
+        let decompressor = ArchiveFormatDecompressor::new(archive_format, reader)?;
+        hardened_unpack::unpack_snapshot_storage_file(
+            Archive::new(decompressor),
+            &file_name,
+            dst_dir.as_ref(),
+        )?
+    };
+    unpacked_path.ok_or_else(|| {
+        SnapshotError::Io(IoError::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "storage file {file_name} not found in snapshot archive '{}'",
+                snapshot_archive_path.display()
+            ),
+        ))
+    })
+}
+
 /// Streams snapshot dir files across channel
 /// Follow the flow of streaming_unarchive_snapshot(), but handle the from_dir case.
 fn streaming_snapshot_dir_files(
@@ -2480,7 +2564,8 @@ fn unpack_snapshot_local(
     assert!(num_threads > 0);
 
     let (chunk_sender, chunk_receiver) = crossbeam_channel::bounded(num_threads);
-    let handle = spawn_archive_chunker_thread(snapshot_path, archive_format, chunk_sender);
+    let handle =
+        spawn_archive_chunker_thread(snapshot_path, archive_format, num_threads, chunk_sender);
 
     // create 'num_threads' # of parallel workers, each receiving chunks of archive to extract.
     let all_unpacked_append_vec_map = (0..num_threads)
@@ -1,21 +1,34 @@
 use {
-    std::{fmt, str::FromStr},
+    solana_accounts_db::hardened_unpack::{ArchiveChunk, ArchiveChunkEntry, ChunkedArchiveIndex},
+    std::{
+        fmt,
+        io::{self, Write},
+        str::FromStr,
+    },
     strum::Display,
 };
 
 // SUPPORTED_ARCHIVE_COMPRESSION lists the compression types that can be
 // specified on the command line.
-pub const SUPPORTED_ARCHIVE_COMPRESSION: &[&str] = &["zstd", "lz4"];
+pub const SUPPORTED_ARCHIVE_COMPRESSION: &[&str] = &["zstd", "lz4", "zstd-chunked"];
 pub const DEFAULT_ARCHIVE_COMPRESSION: &str = "zstd";
 
 pub const TAR_ZSTD_EXTENSION: &str = "tar.zst";
 pub const TAR_LZ4_EXTENSION: &str = "tar.lz4";
+pub const TAR_ZSTD_CHUNKED_EXTENSION: &str = "chunked.tar.zst";
 
 /// The different archive formats used for snapshots
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Display)]
 pub enum ArchiveFormat {
-    TarZstd { config: ZstdConfig },
+    TarZstd {
+        config: ZstdConfig,
+    },
     TarLz4,
+    /// Independently compressed zstd chunks plus an index of the storage
+    /// files, so the archive can be unpacked in parallel or in part
+    TarZstdChunked {
+        config: ZstdConfig,
+    },
 }
 
 impl ArchiveFormat {
@@ -24,6 +37,7 @@ impl ArchiveFormat {
         match self {
             ArchiveFormat::TarZstd { .. } => TAR_ZSTD_EXTENSION,
             ArchiveFormat::TarLz4 => TAR_LZ4_EXTENSION,
+            ArchiveFormat::TarZstdChunked { .. } => TAR_ZSTD_CHUNKED_EXTENSION,
         }
     }
 
@@ -33,6 +47,9 @@ impl ArchiveFormat {
                 config: ZstdConfig::default(),
             }),
             "lz4" => Some(ArchiveFormat::TarLz4),
+            "zstd-chunked" => Some(ArchiveFormat::TarZstdChunked {
+                config: ZstdConfig::default(),
+            }),
             _ => None,
         }
     }
@@ -49,6 +66,9 @@ impl TryFrom<&str> for ArchiveFormat {
                 config: ZstdConfig::default(),
             }),
             TAR_LZ4_EXTENSION => Ok(ArchiveFormat::TarLz4),
+            TAR_ZSTD_CHUNKED_EXTENSION => Ok(ArchiveFormat::TarZstdChunked {
+                config: ZstdConfig::default(),
+            }),
             _ => Err(ParseError::InvalidExtension(extension.to_string())),
         }
     }
@@ -70,7 +90,9 @@ pub enum ArchiveFormatDecompressor<R> {
 impl<R: std::io::BufRead> ArchiveFormatDecompressor<R> {
     pub fn new(format: ArchiveFormat, input: R) -> std::io::Result<Self> {
         Ok(match format {
-            ArchiveFormat::TarZstd { .. } => {
+            // The chunks of a chunked archive decode as one stream, and the
+            // trailing index is a skippable frame
+            ArchiveFormat::TarZstd { .. } | ArchiveFormat::TarZstdChunked { .. } => {
                 Self::Zstd(zstd::stream::read::Decoder::with_buffer(input)?)
             }
             ArchiveFormat::TarLz4 => {
@@ -89,6 +111,135 @@ impl<R: std::io::BufRead> std::io::Read for ArchiveFormatDecompressor<R> {
     }
 }
 
+/// A writer receiving a snapshot archive, notified of the archive entries
+/// so chunked formats can split the archive between them.
+pub(crate) trait ArchiveWriter: Write {
+    /// Called once the account storage file at `path` in the archive has
+    /// been written
+    fn storage_file_written(&mut self, _path: &str, _size: u64) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<W: Write> ArchiveWriter for zstd::stream::write::Encoder<'_, W> {}
+impl<W: Write> ArchiveWriter for lz4::Encoder<W> {}
+
+/// Counts the bytes written to the inner writer
+struct CountingWriter<W> {
+    inner: W,
+    count: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.count += len as u64;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Writes a `TarZstdChunked` archive.
+///
+/// A new chunk is started after the first storage file that takes the
+/// current chunk past `chunk_size` uncompressed bytes, so every chunk holds
+/// whole tar entries.
+pub(crate) struct ChunkedZstdEncoder<W: Write> {
+    encoder: Option<zstd::stream::write::Encoder<'static, CountingWriter<W>>>,
+    compression_level: i32,
+    chunk_size: u64,
+    /// Offset of the current chunk in the output
+    chunk_offset: u64,
+    /// Uncompressed size of the current chunk
+    chunk_len: u64,
+    chunk_entries: Vec<ArchiveChunkEntry>,
+    index: ChunkedArchiveIndex,
+}
+
+impl<W: Write> ChunkedZstdEncoder<W> {
+    // Large enough for compression to be efficient, small enough for a
+    // snapshot to be split in many more chunks than there are cores
+    pub(crate) const DEFAULT_CHUNK_SIZE: u64 = 64 * 1024 * 1024;
+
+    pub(crate) fn new(output: W, compression_level: i32, chunk_size: u64) -> io::Result<Self> {
+        let output = CountingWriter {
+            inner: output,
+            count: 0,
+        };
+        Ok(Self {
+            encoder: Some(zstd::stream::write::Encoder::new(
+                output,
+                compression_level,
+            )?),
+            compression_level,
+            chunk_size,
+            chunk_offset: 0,
+            chunk_len: 0,
+            chunk_entries: vec![],
+            index: ChunkedArchiveIndex::default(),
+        })
+    }
+
+    fn encoder(&mut self) -> &mut zstd::stream::write::Encoder<'static, CountingWriter<W>> {
+        self.encoder
+            .as_mut()
+            .expect("encoder is only taken while finishing a chunk")
+    }
+
+    /// Finishes the current zstd frame and records it in the index
+    fn finish_chunk(&mut self) -> io::Result<CountingWriter<W>> {
+        let output = self.encoder.take().unwrap().finish()?;
+        self.index.chunks.push(ArchiveChunk {
+            offset: self.chunk_offset,
+            compressed_size: output.count - self.chunk_offset,
+            decompressed_size: self.chunk_len,
+            entries: std::mem::take(&mut self.chunk_entries),
+        });
+        self.chunk_offset = output.count;
+        self.chunk_len = 0;
+        Ok(output)
+    }
+
+    /// Finishes the last chunk and appends the index, returning the output
+    pub(crate) fn finish(mut self) -> io::Result<W> {
+        let mut output = self.finish_chunk()?;
+        self.index.write_to(&mut output)?;
+        Ok(output.inner)
+    }
+}
+
+impl<W: Write> Write for ChunkedZstdEncoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.encoder().write(buf)?;
+        self.chunk_len += len as u64;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.encoder().flush()
+    }
+}
+
+impl<W: Write> ArchiveWriter for ChunkedZstdEncoder<W> {
+    fn storage_file_written(&mut self, path: &str, size: u64) -> io::Result<()> {
+        self.chunk_entries.push(ArchiveChunkEntry {
+            path: path.to_string(),
+            size,
+        });
+        if self.chunk_len >= self.chunk_size {
+            let output = self.finish_chunk()?;
+            self.encoder = Some(zstd::stream::write::Encoder::new(
+                output,
+                self.compression_level,
+            )?);
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum ParseError {
     InvalidExtension(String),
@@ -113,7 +264,13 @@ pub struct ZstdConfig {
 
 #[cfg(test)]
 mod tests {
-    use {super::*, std::iter::zip};
+    use {
+        super::*,
+        solana_accounts_db::hardened_unpack::{
+            open_archive_chunk, read_chunked_archive_index, unpack_snapshot_storage_file,
+        },
+        std::{fs::File, io::Read, iter::zip},
+    };
     const INVALID_EXTENSION: &str = "zip";
 
     #[test]
@@ -126,6 +283,13 @@ mod tests {
             TAR_ZSTD_EXTENSION
         );
         assert_eq!(ArchiveFormat::TarLz4.extension(), TAR_LZ4_EXTENSION);
+        assert_eq!(
+            ArchiveFormat::TarZstdChunked {
+                config: ZstdConfig::default(),
+            }
+            .extension(),
+            TAR_ZSTD_CHUNKED_EXTENSION
+        );
     }
 
     #[test]
@@ -140,6 +304,12 @@ mod tests {
             ArchiveFormat::try_from(TAR_LZ4_EXTENSION),
             Ok(ArchiveFormat::TarLz4)
         );
+        assert_eq!(
+            ArchiveFormat::try_from(TAR_ZSTD_CHUNKED_EXTENSION),
+            Ok(ArchiveFormat::TarZstdChunked {
+                config: ZstdConfig::default(),
+            })
+        );
         assert_eq!(
             ArchiveFormat::try_from(INVALID_EXTENSION),
             Err(ParseError::InvalidExtension(INVALID_EXTENSION.to_string()))
@@ -158,6 +328,12 @@ mod tests {
             ArchiveFormat::from_str(TAR_LZ4_EXTENSION),
             Ok(ArchiveFormat::TarLz4)
         );
+        assert_eq!(
+            ArchiveFormat::from_str(TAR_ZSTD_CHUNKED_EXTENSION),
+            Ok(ArchiveFormat::TarZstdChunked {
+                config: ZstdConfig::default(),
+            })
+        );
         assert_eq!(
             ArchiveFormat::from_str(INVALID_EXTENSION),
             Err(ParseError::InvalidExtension(INVALID_EXTENSION.to_string()))
@@ -171,6 +347,9 @@ mod tests {
                 config: ZstdConfig::default(),
             }),
             Some(ArchiveFormat::TarLz4),
+            Some(ArchiveFormat::TarZstdChunked {
+                config: ZstdConfig::default(),
+            }),
         ];
 
         for (arg, expected) in zip(SUPPORTED_ARCHIVE_COMPRESSION.iter(), golden.into_iter()) {
@@ -179,4 +358,78 @@ mod tests {
 
         assert_eq!(ArchiveFormat::from_cli_arg("bad"), None);
     }
+
+    #[test]
+    fn test_chunked_zstd_encoder() {
+        let storages = [
+            ("accounts/1.1", 300),
+            ("accounts/2.2", 5000),
+            ("accounts/3.3", 10),
+        ];
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        let archive_path = tmp_dir.path().join("snapshot.chunked.tar.zst");
+
+        // A tiny chunk size puts every storage file in its own chunk
+        let mut encoder =
+            ChunkedZstdEncoder::new(File::create(&archive_path).unwrap(), 0, 1).unwrap();
+        {
+            let mut archive = tar::Builder::new(&mut encoder as &mut dyn ArchiveWriter);
+            for (path, size) in storages {
+                let mut header = tar::Header::new_gnu();
+                header.set_path(path).unwrap();
+                header.set_size(size);
+                header.set_cksum();
+                archive
+                    .append(&header, &vec![7; size as usize][..])
+                    .unwrap();
+                archive.get_mut().storage_file_written(path, size).unwrap();
+            }
+            archive.finish().unwrap();
+        }
+        encoder.finish().unwrap();
+
+        let index = read_chunked_archive_index(&archive_path).unwrap();
+        let indexed_storages: Vec<_> = index
+            .chunks
+            .iter()
+            .flat_map(|chunk| &chunk.entries)
+            .map(|entry| (entry.path.as_str(), entry.size))
+            .collect();
+        assert_eq!(indexed_storages, storages);
+        assert_eq!(index.chunks[1].entries.len(), 1);
+
+        // The whole archive still decodes as a single tar stream
+        let decoder = ArchiveFormatDecompressor::new(
+            ArchiveFormat::TarZstdChunked {
+                config: ZstdConfig::default(),
+            },
+            io::BufReader::new(File::open(&archive_path).unwrap()),
+        )
+        .unwrap();
+        let paths: Vec<_> = tar::Archive::new(decoder)
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect();
+        assert_eq!(paths, storages.map(|(path, _)| path));
+
+        // A single chunk decodes on its own
+        let chunk = index.find_storage_file("2.2").unwrap();
+        let mut decompressed = vec![];
+        open_archive_chunk(&archive_path, chunk)
+            .unwrap()
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed.len() as u64, chunk.decompressed_size);
+
+        let unpacked_path = unpack_snapshot_storage_file(
+            tar::Archive::new(&decompressed[..]),
+            "2.2",
+            tmp_dir.path(),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(unpacked_path, tmp_dir.path().join("2.2"));
+        assert_eq!(std::fs::read(unpacked_path).unwrap(), vec![7; 5000]);
+    }
 }
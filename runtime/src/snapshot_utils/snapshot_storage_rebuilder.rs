@@ -17,12 +17,13 @@ use {
     solana_accounts_db::{
         account_storage::AccountStorageMap,
         accounts_db::{AccountsFileId, AtomicAccountsFileId},
-        accounts_file::StorageAccess,
+        accounts_file::{AccountsFile, StorageAccess},
+        hardened_unpack::ChunkedArchiveIndex,
     },
     solana_clock::Slot,
     solana_nohash_hasher::BuildNoHashHasher,
     std::{
-        collections::HashMap,
+        collections::{HashMap, HashSet},
         path::PathBuf,
         str::FromStr as _,
         sync::{
@@ -84,6 +85,34 @@ impl SnapshotStorageRebuilder {
         Ok(account_storage_map)
     }
 
+    /// Checks that every storage file listed in the snapshot's accounts db
+    /// fields is present in the index of a chunked snapshot archive
+    pub(crate) fn check_archive_index(
+        accounts_db_fields: &AccountsDbFields<SerializableAccountStorageEntry>,
+        index: &ChunkedArchiveIndex,
+    ) -> Result<(), SnapshotError> {
+        let archived_storages: HashSet<_> = index
+            .chunks
+            .iter()
+            .flat_map(|chunk| chunk.entries.iter())
+            .filter_map(|entry| entry.path.strip_prefix("accounts/"))
+            .filter_map(|file_name| get_slot_and_append_vec_id(file_name).ok())
+            .collect();
+
+        let snapshot_storage_lengths = snapshot_storage_lengths_from_fields(accounts_db_fields);
+        for (slot, storage_lengths) in &snapshot_storage_lengths {
+            for id in storage_lengths.keys() {
+                if !archived_storages.contains(&(*slot, *id)) {
+                    return Err(SnapshotError::RebuildStorages(format!(
+                        "storage file {} is missing from the archive index",
+                        AccountsFile::file_name(*slot, *id as AccountsFileId),
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Create the SnapshotStorageRebuilder for storing state during rebuilding
     ///     - pre-allocates data for storage paths
     fn new(
@@ -328,7 +357,7 @@ pub(crate) fn get_slot_and_append_vec_id(filename: &str) -> Result<(Slot, usize)
 
 #[cfg(test)]
 mod tests {
-    use {super::*, solana_accounts_db::accounts_file::AccountsFile};
+    use super::*;
 
     #[test]
     fn test_get_slot_and_append_vec_id() {
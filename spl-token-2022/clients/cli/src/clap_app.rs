@@ -136,6 +136,7 @@ pub enum CommandName {
     Balance,
     Supply,
     Accounts,
+    Holders,
     Address,
     AccountInfo,
     MultisigInfo,
@@ -166,6 +167,10 @@ pub enum CommandName {
     DepositConfidentialTokens,
     WithdrawConfidentialTokens,
     ApplyPendingBalance,
+    ConfidentialMint,
+    ConfidentialBurn,
+    ApplyPendingBurn,
+    ConfidentialSupply,
     UpdateGroupAddress,
     UpdateMemberAddress,
     UpdateUiAmountMultiplier,
@@ -869,6 +874,18 @@ pub fn app<'a>(
                             before it can make confidential transfers."
                         )
                 )
+                .arg(
+                    Arg::with_name("enable_confidential_mint_burn")
+                        .long("enable-confidential-mint-burn")
+                        .takes_value(false)
+                        .requires("enable_confidential_transfers")
+                        .help(
+                            "Enable the mint authority to mint and burn tokens confidentially. \
+                            The confidential supply is encrypted under an ElGamal key derived \
+                            from the mint authority keypair, which is also set as the \
+                            confidential transfer auditor of the mint."
+                        )
+                )
                 .arg(
                     Arg::with_name("transfer_hook")
                         .long("transfer-hook")
@@ -1375,7 +1392,7 @@ pub fn app<'a>(
                         .value_name("TOKEN_AMOUNT")
                         .takes_value(true)
                         .index(2)
-                        .required(true)
+                        .required_unless("batch")
                         .help("Amount to send, in tokens; accepts keyword ALL"),
                 )
                 .arg(
@@ -1384,7 +1401,7 @@ pub fn app<'a>(
                         .value_name("RECIPIENT_WALLET_ADDRESS or RECIPIENT_TOKEN_ACCOUNT_ADDRESS")
                         .takes_value(true)
                         .index(3)
-                        .required(true)
+                        .required_unless("batch")
                         .help("If a token account address is provided, use it as the recipient. \
                                Otherwise assume the recipient address is a user wallet and transfer to \
                                the associated token account")
@@ -1481,6 +1498,24 @@ pub fn app<'a>(
                         .help("Send tokens confidentially. Both sender and recipient accounts must \
                             be pre-configured for confidential transfers.")
                 )
+                .arg(
+                    Arg::with_name("batch")
+                        .long("batch")
+                        .value_name("RECIPIENTS_FILE")
+                        .takes_value(true)
+                        .conflicts_with_all(&[
+                            "amount",
+                            "recipient",
+                            "confidential",
+                            "expected_fee",
+                            "transfer_hook_account",
+                            "sign_only",
+                        ])
+                        .help("Transfer to multiple recipients in a single Batch instruction. \
+                            The file lists one \"<RECIPIENT_ADDRESS> <TOKEN_AMOUNT>\" pair per line, \
+                            lines starting with '#' are ignored. All the transfers must fit in a \
+                            single transaction. Only supported by the SPL Token program.")
+                )
                 .arg(multisig_signer_arg())
                 .arg(mint_decimals_arg())
                 .nonce_args(true)
@@ -1934,6 +1969,53 @@ pub fn app<'a>(
                 )
                 .arg(owner_address_arg())
         )
+        .subcommand(
+            SubCommand::with_name(CommandName::Holders.into())
+                .about("List the holders of a token, aggregated by owner")
+                .arg(
+                    Arg::with_name("token")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("TOKEN_MINT_ADDRESS")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The token address"),
+                )
+                .arg(
+                    Arg::with_name("at_slot")
+                        .long("at-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .value_parser(clap::value_parser!(u64))
+                        .help(
+                            "Fail unless the RPC node has processed at least this slot, \
+                            for snapshots consistent with a given slot"
+                        ),
+                )
+                .arg(
+                    Arg::with_name("paginate")
+                        .long("paginate")
+                        .takes_value(false)
+                        .help(
+                            "Fetch the token accounts in 256 pages split by the first byte \
+                            of their owner, for tokens with too many accounts for a single \
+                            RPC response"
+                        ),
+                )
+                .arg(
+                    Arg::with_name("exclude_frozen")
+                        .long("exclude-frozen")
+                        .takes_value(false)
+                        .help("Exclude the balances of frozen accounts"),
+                )
+                .arg(
+                    Arg::with_name("csv")
+                        .long("csv")
+                        .takes_value(false)
+                        .conflicts_with("output_format")
+                        .help("Print the holders as CSV"),
+                )
+        )
         .subcommand(
             SubCommand::with_name(CommandName::Address.into())
                 .about("Get wallet address")
@@ -2721,6 +2803,154 @@ pub fn app<'a>(
                 .arg(multisig_signer_arg())
                 .nonce_args(true)
         )
+        .subcommand(
+            SubCommand::with_name(CommandName::ConfidentialMint.into())
+                .about("Mint new tokens confidentially")
+                .arg(
+                    Arg::with_name("token")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("TOKEN_MINT_ADDRESS")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The token address with confidential mint and burn enabled"),
+                )
+                .arg(
+                    Arg::with_name("amount")
+                        .value_parser(Amount::parse)
+                        .value_name("TOKEN_AMOUNT")
+                        .takes_value(true)
+                        .index(2)
+                        .required(true)
+                        .help("Amount to mint, in tokens"),
+                )
+                .arg(
+                    Arg::with_name("recipient")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("RECIPIENT_TOKEN_ACCOUNT_ADDRESS")
+                        .takes_value(true)
+                        .conflicts_with("recipient_owner")
+                        .index(3)
+                        .help("The token account address of recipient, which must be \
+                            configured for confidential transfers \
+                            [default: associated token account for --mint-authority]"),
+                )
+                .arg(
+                    Arg::with_name("recipient_owner")
+                        .long("recipient-owner")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("RECIPIENT_WALLET_ADDRESS")
+                        .takes_value(true)
+                        .conflicts_with("recipient")
+                        .help("The owner of the recipient associated token account"),
+                )
+                .arg(
+                    Arg::with_name("mint_authority")
+                        .long("mint-authority")
+                        .alias("owner")
+                        .value_name("KEYPAIR")
+                        .validator(|s| is_valid_signer(s))
+                        .takes_value(true)
+                        .help(
+                            "Specify the mint authority keypair, the supply encryption keys \
+                             are derived from it. This may be a keypair file or the ASK keyword. \
+                             Defaults to the client keypair."
+                        ),
+                )
+                .arg(mint_decimals_arg())
+                .nonce_args(true)
+        )
+        .subcommand(
+            SubCommand::with_name(CommandName::ConfidentialBurn.into())
+                .about("Burn tokens from the available confidential balance of an account")
+                .arg(
+                    Arg::with_name("token")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("TOKEN_MINT_ADDRESS")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The token address with confidential mint and burn enabled"),
+                )
+                .arg(
+                    Arg::with_name("amount")
+                        .value_parser(Amount::parse)
+                        .value_name("TOKEN_AMOUNT")
+                        .takes_value(true)
+                        .index(2)
+                        .required(true)
+                        .help("Amount to burn, in tokens"),
+                )
+                .arg(
+                    Arg::with_name("address")
+                        .long("address")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("TOKEN_ACCOUNT_ADDRESS")
+                        .takes_value(true)
+                        .help("The address of the token account to burn from \
+                            [default: owner's associated token account]")
+                )
+                .arg(
+                    owner_address_arg()
+                )
+                .arg(multisig_signer_arg())
+                .arg(mint_decimals_arg())
+                .nonce_args(true)
+        )
+        .subcommand(
+            SubCommand::with_name(CommandName::ApplyPendingBurn.into())
+                .about("Subtract the pending confidential burns from the confidential supply")
+                .arg(
+                    Arg::with_name("token")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("TOKEN_MINT_ADDRESS")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The token address with confidential mint and burn enabled"),
+                )
+                .arg(
+                    Arg::with_name("mint_authority")
+                        .long("mint-authority")
+                        .alias("owner")
+                        .value_name("KEYPAIR")
+                        .validator(|s| is_valid_signer(s))
+                        .takes_value(true)
+                        .help(
+                            "Specify the mint authority keypair. \
+                             This may be a keypair file or the ASK keyword. \
+                             Defaults to the client keypair."
+                        ),
+                )
+                .arg(multisig_signer_arg())
+                .nonce_args(true)
+        )
+        .subcommand(
+            SubCommand::with_name(CommandName::ConfidentialSupply.into())
+                .about("Get the confidential token supply, excluding the pending burns")
+                .arg(
+                    Arg::with_name("token")
+                        .validator(|s| is_valid_pubkey(s))
+                        .value_name("TOKEN_MINT_ADDRESS")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The token address with confidential mint and burn enabled"),
+                )
+                .arg(
+                    Arg::with_name("auditor")
+                        .long("auditor")
+                        .value_name("KEYPAIR")
+                        .validator(|s| is_valid_signer(s))
+                        .takes_value(true)
+                        .help(
+                            "Specify the auditor keypair the supply encryption keys are \
+                             derived from, the mint authority when the token was created. \
+                             This may be a keypair file or the ASK keyword. \
+                             Defaults to the client keypair."
+                        ),
+                )
+        )
         .subcommand(
             SubCommand::with_name(CommandName::UpdateUiAmountMultiplier.into())
                 .about("Update UI multiplier")
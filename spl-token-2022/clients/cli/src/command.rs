@@ -13,8 +13,11 @@ use {
     serde::Serialize,
     solana_account_decoder::{
         parse_account_data::SplTokenAdditionalDataV2,
-        parse_token::{get_token_account_mint, parse_token_v3, TokenAccountType, UiAccountState},
-        UiAccountData,
+        parse_token::{
+            get_token_account_mint, parse_token_v3, token_amount_to_ui_amount_v3, TokenAccountType,
+            UiAccountState,
+        },
+        UiAccountData, UiAccountEncoding, UiDataSliceConfig,
     },
     solana_clap_v3_utils::{
         input_parsers::{pubkey_of_signer, pubkeys_of_multiple_signers, Amount},
@@ -24,19 +27,32 @@ use {
         return_signers_data, CliSignOnlyData, CliSignature, OutputFormat, QuietDisplay,
         ReturnSignersConfig, VerboseDisplay,
     },
-    solana_client::rpc_request::TokenAccountsFilter,
+    solana_client::{
+        rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+        rpc_filter::{Memcmp, RpcFilterType},
+        rpc_request::{RpcRequest, TokenAccountsFilter},
+        rpc_response::{OptionalContext, RpcKeyedAccount},
+    },
     solana_remote_wallet::remote_wallet::RemoteWalletManager,
     solana_sdk::{
         instruction::AccountMeta,
         native_token::*,
         program_option::COption,
+        program_pack::Pack,
         pubkey::Pubkey,
         signature::{Keypair, Signer},
     },
     solana_system_interface::program as system_program,
-    spl_associated_token_account_client::address::get_associated_token_address_with_program_id,
+    spl_associated_token_account_client::{
+        address::get_associated_token_address_with_program_id,
+        instruction::create_associated_token_account_idempotent,
+    },
     spl_token_2022::{
         extension::{
+            confidential_mint_burn::{
+                account_info::{BurnAccountInfo, SupplyAccountInfo},
+                ConfidentialMintBurn,
+            },
             confidential_transfer::{
                 account_info::{
                     ApplyPendingBalanceAccountInfo, TransferAccountInfo, WithdrawAccountInfo,
@@ -73,7 +89,8 @@ use {
         },
     },
     spl_token_confidential_transfer_proof_generation::{
-        transfer::TransferProofData, withdraw::WithdrawProofData,
+        burn::BurnProofData, mint::MintProofData, transfer::TransferProofData,
+        withdraw::WithdrawProofData,
     },
     spl_token_group_interface::state::TokenGroup,
     spl_token_metadata_interface::state::{Field, TokenMetadata},
@@ -263,6 +280,7 @@ async fn command_create_token(
     default_account_state: Option<AccountState>,
     transfer_fee: Option<(u16, u64)>,
     confidential_transfer_auto_approve: Option<bool>,
+    enable_confidential_mint_burn: bool,
     transfer_hook_program_id: Option<Pubkey>,
     enable_metadata: bool,
     enable_group: bool,
@@ -325,11 +343,33 @@ async fn command_create_token(
         });
     }
 
+    // The confidential supply is encrypted under keys derived from the mint authority, which
+    // also audits the confidential transfers of the mint
+    let confidential_supply_keys = if enable_confidential_mint_burn {
+        let signer = config.default_signer()?;
+        if signer.pubkey() != authority {
+            return Err(format!(
+                "The mint authority {} must be the client keypair to derive the confidential \
+                supply encryption keys",
+                authority
+            )
+            .into());
+        }
+        Some((
+            ElGamalKeypair::new_from_signer(signer.as_ref(), b"").unwrap(),
+            AeKey::new_from_signer(signer.as_ref(), b"").unwrap(),
+        ))
+    } else {
+        None
+    };
+
     if let Some(auto_approve) = confidential_transfer_auto_approve {
         extensions.push(ExtensionInitializationParams::ConfidentialTransferMint {
             authority: Some(authority),
             auto_approve_new_accounts: auto_approve,
-            auditor_elgamal_pubkey: None,
+            auditor_elgamal_pubkey: confidential_supply_keys
+                .as_ref()
+                .map(|(supply_elgamal_keypair, _)| (*supply_elgamal_keypair.pubkey()).into()),
         });
         if transfer_fee.is_some() {
             // Deriving ElGamal key from default signer. Custom ElGamal keys
@@ -348,6 +388,13 @@ async fn command_create_token(
         }
     }
 
+    if let Some((supply_elgamal_keypair, supply_aes_key)) = &confidential_supply_keys {
+        extensions.push(ExtensionInitializationParams::ConfidentialMintBurn {
+            supply_elgamal_pubkey: (*supply_elgamal_keypair.pubkey()).into(),
+            decryptable_supply: supply_aes_key.encrypt(0).into(),
+        });
+    }
+
     if let Some(program_id) = transfer_hook_program_id {
         extensions.push(ExtensionInitializationParams::TransferHook {
             authority: Some(authority),
@@ -1779,6 +1826,209 @@ async fn command_transfer(
     })
 }
 
+fn parse_batch_recipients(path: &str) -> Result<Vec<(Pubkey, Amount)>, Error> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| format!("Unable to read recipients file {}: {}", path, err))?;
+    let mut recipients = vec![];
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parse_error = || {
+            format!(
+                "{}:{}: expected \"<RECIPIENT_ADDRESS> <TOKEN_AMOUNT>\"",
+                path,
+                line_number + 1
+            )
+        };
+        let mut fields = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|field| !field.is_empty());
+        let (Some(recipient), Some(amount), None) = (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(parse_error().into());
+        };
+        let recipient = Pubkey::from_str(recipient).map_err(|_| parse_error())?;
+        let amount = Amount::parse_decimal(amount).map_err(|_| parse_error())?;
+        recipients.push((recipient, amount));
+    }
+    if recipients.is_empty() {
+        return Err(format!("No recipients in {}", path).into());
+    }
+    Ok(recipients)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn command_transfer_batch(
+    config: &Config<'_>,
+    token_pubkey: Pubkey,
+    recipients: Vec<(Pubkey, Amount)>,
+    sender: Option<Pubkey>,
+    sender_owner: Pubkey,
+    allow_unfunded_recipient: bool,
+    fund_recipient: bool,
+    mint_decimals: Option<u8>,
+    memo: Option<String>,
+    bulk_signers: BulkSigners,
+    no_wait: bool,
+    allow_non_system_account_recipient: bool,
+) -> CommandResult {
+    // only p-token implements the Batch instruction
+    if config.program_id != spl_token::id() {
+        return Err(format!(
+            "Batch transfers are only supported by the SPL Token program {}",
+            spl_token::id()
+        )
+        .into());
+    }
+
+    let mint_info = config.get_mint_info(&token_pubkey, mint_decimals).await?;
+    if mint_decimals.is_some() && mint_decimals != Some(mint_info.decimals) {
+        return Err(format!(
+            "Decimals {} was provided, but actual value is {}",
+            mint_decimals.unwrap(),
+            mint_info.decimals
+        )
+        .into());
+    }
+    let token = token_client_from_config(config, &token_pubkey, Some(mint_info.decimals))?;
+
+    let sender = sender.unwrap_or_else(|| token.get_associated_token_address(&sender_owner));
+    let sender_balance = token.get_account_info(&sender).await?.base.amount;
+
+    println_display(
+        config,
+        format!(
+            "Batch transfer to {} recipients\n  Sender: {}",
+            recipients.len(),
+            sender
+        ),
+    );
+
+    let fee_payer = config.fee_payer()?.pubkey();
+    let mut create_instructions = vec![];
+    let mut transfer_instructions = vec![];
+    let mut total_amount = 0u64;
+    for (recipient, ui_amount) in recipients {
+        let amount = amount_to_raw_amount(ui_amount, mint_info.decimals, None, "TOKEN_AMOUNT");
+        total_amount = total_amount
+            .checked_add(amount)
+            .ok_or("Error: Total transfer amount overflows")?;
+
+        // same rules as a single transfer: token accounts of this program are used as is,
+        // wallets receive in their associated token account
+        let recipient_token_account = match config.program_client.get_account(recipient).await? {
+            Some(account)
+                if account.owner == config.program_id
+                    && StateWithExtensionsOwned::<Account>::unpack(account.data.clone())
+                        .is_ok() =>
+            {
+                recipient
+            }
+            Some(account) if account.owner == config.program_id => {
+                return Err(format!(
+                    "Error: Recipient {} is owned by this token program, but is not a token account.",
+                    recipient
+                )
+                .into());
+            }
+            Some(account)
+                if account.owner != system_program::id() && !allow_non_system_account_recipient =>
+            {
+                return Err(format!(
+                    "Error: The recipient address {} is not owned by the System Program. \
+                    Add `--allow-non-system-account-recipient` to complete the transfer.",
+                    recipient
+                )
+                .into());
+            }
+            None if !allow_unfunded_recipient => {
+                return Err(format!(
+                    "Error: The recipient address {} is not funded. \
+                    Add `--allow-unfunded-recipient` to complete the transfer.",
+                    recipient
+                )
+                .into());
+            }
+            _ => {
+                let recipient_token_account = token.get_associated_token_address(&recipient);
+                if config
+                    .program_client
+                    .get_account(recipient_token_account)
+                    .await?
+                    .is_none()
+                {
+                    if !fund_recipient {
+                        return Err(format!(
+                            "Error: Recipient's associated token account {} does not exist. \
+                            Add `--fund-recipient` to fund their account",
+                            recipient_token_account
+                        )
+                        .into());
+                    }
+                    // the associated token account program can't be called from a batch
+                    create_instructions.push(create_associated_token_account_idempotent(
+                        &fee_payer,
+                        &recipient,
+                        &token_pubkey,
+                        &config.program_id,
+                    ));
+                }
+                recipient_token_account
+            }
+        };
+
+        println_display(
+            config,
+            format!(
+                "  Recipient: {} {}",
+                recipient_token_account,
+                spl_token::amount_to_ui_amount(amount, mint_info.decimals)
+            ),
+        );
+        transfer_instructions.push(spl_token::instruction::transfer_checked(
+            &config.program_id,
+            &sender,
+            &token_pubkey,
+            &recipient_token_account,
+            &sender_owner,
+            &config.multisigner_pubkeys,
+            amount,
+            mint_info.decimals,
+        )?);
+    }
+
+    if total_amount > sender_balance {
+        return Err(format!(
+            "Error: Sender has insufficient funds, current balance is {}",
+            spl_token_2022::amount_to_ui_amount_string_trimmed(sender_balance, mint_info.decimals)
+        )
+        .into());
+    }
+
+    if let Some(text) = memo {
+        token.with_memo(text, vec![config.default_signer()?.pubkey()]);
+    }
+
+    let mut instructions = create_instructions;
+    instructions.push(spl_token::instruction::batch(
+        &config.program_id,
+        &transfer_instructions,
+    )?);
+    let res = token.process_ixs(&instructions, &bulk_signers).await?;
+
+    let tx_return = finish_tx(config, &res, no_wait).await?;
+    Ok(match tx_return {
+        TransactionReturnData::CliSignature(signature) => {
+            config.output_format.formatted_string(&signature)
+        }
+        TransactionReturnData::CliSignOnlyData(sign_only_data) => {
+            config.output_format.formatted_string(&sign_only_data)
+        }
+    })
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn command_burn(
     config: &Config<'_>,
@@ -2366,6 +2616,221 @@ async fn command_accounts(
     }
 }
 
+// Byte offsets of the base token account fields read by `command_holders`, the token
+// accounts are fetched from the owner to the delegated amount
+const HOLDER_DATA_SLICE_OFFSET: usize = 32;
+const HOLDER_DATA_SLICE_LENGTH: usize = 97;
+
+#[derive(Default)]
+struct HolderBalance {
+    amount: u64,
+    frozen_amount: u64,
+    delegated_amount: u64,
+    accounts: usize,
+}
+
+fn check_holders_slot(slot: u64, at_slot: Option<u64>) -> Result<(), Error> {
+    match at_slot {
+        Some(at_slot) if slot < at_slot => Err(format!(
+            "RPC node returned slot {}, which is below the requested slot {}",
+            slot, at_slot
+        )
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+async fn command_holders(
+    config: &Config<'_>,
+    token_pubkey: Pubkey,
+    at_slot: Option<u64>,
+    paginate: bool,
+    exclude_frozen: bool,
+    csv: bool,
+) -> CommandResult {
+    let mint_response = config
+        .rpc_client
+        .get_account_with_config(
+            &token_pubkey,
+            RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(config.rpc_client.commitment()),
+                min_context_slot: at_slot,
+                ..RpcAccountInfoConfig::default()
+            },
+        )
+        .await?;
+    let mut slot = mint_response.context.slot;
+    check_holders_slot(slot, at_slot)?;
+    let mint_account = mint_response
+        .value
+        .ok_or_else(|| format!("Could not find token mint {}", token_pubkey))?;
+    if !VALID_TOKEN_PROGRAM_IDS.contains(&mint_account.owner) {
+        return Err(format!(
+            "Mint {} is owned by {}, not a token program",
+            token_pubkey, mint_account.owner
+        )
+        .into());
+    }
+    let program_id = mint_account.owner;
+    let mint_state = StateWithExtensionsOwned::<Mint>::unpack(mint_account.data)
+        .map_err(|_| format!("Could not deserialize token mint {}", token_pubkey))?;
+    let decimals = mint_state.base.decimals;
+    let supply = mint_state.base.supply;
+
+    // token accounts without extensions have the base size, token-2022 accounts with extensions
+    // are tagged with their account type
+    let mut account_filters = vec![RpcFilterType::DataSize(Account::LEN as u64)];
+    if program_id == spl_token_2022::id() {
+        account_filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            Account::LEN,
+            vec![spl_token_2022::extension::AccountType::Account as u8],
+        )));
+    }
+    let pages: Vec<Option<u8>> = if paginate {
+        (0..=u8::MAX).map(Some).collect()
+    } else {
+        vec![None]
+    };
+
+    let mut holders = HashMap::<Pubkey, HolderBalance>::new();
+    let mut accounts_slot = None;
+    for account_filter in account_filters {
+        for page in &pages {
+            let mut filters = vec![
+                account_filter.clone(),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, token_pubkey.to_bytes().to_vec())),
+            ];
+            if let Some(owner_prefix) = page {
+                filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    HOLDER_DATA_SLICE_OFFSET,
+                    vec![*owner_prefix],
+                )));
+            }
+            let program_accounts_config = RpcProgramAccountsConfig {
+                filters: Some(filters),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    data_slice: Some(UiDataSliceConfig {
+                        offset: HOLDER_DATA_SLICE_OFFSET,
+                        length: HOLDER_DATA_SLICE_LENGTH,
+                    }),
+                    commitment: Some(config.rpc_client.commitment()),
+                    min_context_slot: at_slot,
+                },
+                with_context: Some(true),
+                ..RpcProgramAccountsConfig::default()
+            };
+            // `get_program_accounts_with_config` drops the context, which is needed to know that
+            // every page was read at the same slot
+            let response = config
+                .rpc_client
+                .send::<OptionalContext<Vec<RpcKeyedAccount>>>(
+                    RpcRequest::GetProgramAccounts,
+                    serde_json::json!([program_id.to_string(), program_accounts_config]),
+                )
+                .await?;
+            let OptionalContext::Context(response) = response else {
+                return Err("RPC node did not return the slot of the token accounts".into());
+            };
+            let page_slot = response.context.slot;
+            check_holders_slot(page_slot, at_slot)?;
+            match accounts_slot {
+                Some(accounts_slot) if accounts_slot != page_slot => {
+                    return Err(format!(
+                        "Token accounts were read at different slots ({} and {}), \
+                        the holders would be inconsistent",
+                        accounts_slot, page_slot
+                    )
+                    .into());
+                }
+                _ => accounts_slot = Some(page_slot),
+            }
+
+            for RpcKeyedAccount { pubkey, account } in response.value {
+                let address = Pubkey::from_str(&pubkey)
+                    .map_err(|_| format!("Invalid token account address {}", pubkey))?;
+                let data = account
+                    .data
+                    .decode()
+                    .ok_or_else(|| format!("Could not decode token account {}", address))?;
+                if data.len() != HOLDER_DATA_SLICE_LENGTH {
+                    return Err(
+                        format!("Unexpected data length of token account {}", address).into(),
+                    );
+                }
+                // owner: Pubkey, amount: u64, delegate: COption<Pubkey>, state: AccountState,
+                // is_native: COption<u64>, delegated_amount: u64
+                let owner = Pubkey::try_from(&data[0..32]).unwrap();
+                let amount = u64::from_le_bytes(data[32..40].try_into().unwrap());
+                let has_delegate = data[40..44] == [1, 0, 0, 0];
+                let frozen = data[76] == AccountState::Frozen as u8;
+                let delegated_amount = u64::from_le_bytes(data[89..97].try_into().unwrap());
+
+                if frozen && exclude_frozen {
+                    continue;
+                }
+                let holder = holders.entry(owner).or_default();
+                holder.amount += amount;
+                holder.accounts += 1;
+                if frozen {
+                    holder.frozen_amount += amount;
+                }
+                if has_delegate {
+                    holder.delegated_amount += delegated_amount.min(amount);
+                }
+            }
+        }
+    }
+
+    if let Some(accounts_slot) = accounts_slot {
+        slot = accounts_slot;
+    }
+
+    let mut holders = holders
+        .into_iter()
+        .filter(|(_, holder)| holder.amount > 0)
+        .collect::<Vec<_>>();
+    holders.sort_by(|(a_owner, a), (b_owner, b)| {
+        b.amount.cmp(&a.amount).then_with(|| a_owner.cmp(b_owner))
+    });
+
+    let additional_data = SplTokenAdditionalDataV2::with_decimals(decimals);
+    let cli_token_holders = CliTokenHolders {
+        mint: token_pubkey.to_string(),
+        program_id: program_id.to_string(),
+        slot,
+        supply: token_amount_to_ui_amount_v3(supply, &additional_data),
+        holders: holders
+            .into_iter()
+            .map(|(owner, holder)| CliTokenHolder {
+                owner: owner.to_string(),
+                balance: token_amount_to_ui_amount_v3(holder.amount, &additional_data),
+                percent_of_supply: if supply == 0 {
+                    0.0
+                } else {
+                    holder.amount as f64 * 100.0 / supply as f64
+                },
+                accounts: holder.accounts,
+                frozen_amount: spl_token_2022::amount_to_ui_amount_string_trimmed(
+                    holder.frozen_amount,
+                    decimals,
+                ),
+                delegated_amount: spl_token_2022::amount_to_ui_amount_string_trimmed(
+                    holder.delegated_amount,
+                    decimals,
+                ),
+            })
+            .collect(),
+    };
+
+    if csv {
+        Ok(cli_token_holders.to_csv())
+    } else {
+        Ok(config.output_format.formatted_string(&cli_token_holders))
+    }
+}
+
 async fn command_address(
     config: &Config<'_>,
     token: Option<Pubkey>,
@@ -3566,6 +4031,405 @@ async fn command_apply_pending_balance(
     })
 }
 
+// Returns the auditor ElGamal pubkey of a mint with confidential transfers
+fn confidential_transfer_auditor_elgamal_pubkey(
+    mint_state: &StateWithExtensionsOwned<Mint>,
+) -> Result<Option<elgamal::ElGamalPubkey>, Error> {
+    let confidential_transfer_mint = mint_state.get_extension::<ConfidentialTransferMint>()?;
+    Option::<PodElGamalPubkey>::from(confidential_transfer_mint.auditor_elgamal_pubkey)
+        .map(elgamal::ElGamalPubkey::try_from)
+        .transpose()
+        .map_err(|_| "Invalid auditor ElGamal pubkey".into())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn command_confidential_mint(
+    config: &Config<'_>,
+    token_pubkey: Pubkey,
+    ui_amount: Amount,
+    recipient: Pubkey,
+    mint_info: MintInfo,
+    mint_authority: Pubkey,
+    bulk_signers: BulkSigners,
+    supply_elgamal_keypair: &ElGamalKeypair,
+    supply_aes_key: &AeKey,
+) -> CommandResult {
+    if config.sign_only {
+        panic!("Sign-only is not yet supported.");
+    }
+
+    let amount = amount_to_raw_amount(ui_amount, mint_info.decimals, None, "TOKEN_AMOUNT");
+
+    println_display(
+        config,
+        format!(
+            "Minting {} confidential tokens\n  Token: {}\n  Recipient: {}",
+            spl_token::amount_to_ui_amount(amount, mint_info.decimals),
+            token_pubkey,
+            recipient
+        ),
+    );
+
+    let token = token_client_from_config(config, &token_pubkey, Some(mint_info.decimals))?;
+
+    let mint_state = token.get_mint_info().await?;
+    let supply_account_info =
+        SupplyAccountInfo::new(mint_state.get_extension::<ConfidentialMintBurn>()?);
+    if supply_account_info.supply_elgamal_pubkey != (*supply_elgamal_keypair.pubkey()).into() {
+        return Err(format!(
+            "The supply of {} isn't encrypted under the keys of mint authority {}",
+            token_pubkey, mint_authority
+        )
+        .into());
+    }
+    let auditor_elgamal_pubkey = confidential_transfer_auditor_elgamal_pubkey(&mint_state)?;
+
+    let recipient_state = token.get_account_info(&recipient).await?;
+    let recipient_elgamal_pubkey: elgamal::ElGamalPubkey = recipient_state
+        .get_extension::<ConfidentialTransferAccount>()
+        .map_err(|_| {
+            format!(
+                "Recipient {} is not configured for confidential transfers",
+                recipient
+            )
+        })?
+        .elgamal_pubkey
+        .try_into()
+        .map_err(|_| "Invalid recipient ElGamal pubkey")?;
+
+    let MintProofData {
+        equality_proof_data,
+        ciphertext_validity_proof_data_with_ciphertext,
+        range_proof_data,
+    } = supply_account_info.generate_split_mint_proof_data(
+        amount,
+        supply_elgamal_keypair,
+        supply_aes_key,
+        &recipient_elgamal_pubkey,
+        auditor_elgamal_pubkey.as_ref(),
+    )?;
+
+    let context_state_authority = config.fee_payer()?;
+    let context_state_authority_pubkey = context_state_authority.pubkey();
+    let equality_proof_context_state_account = Keypair::new();
+    let equality_proof_pubkey = equality_proof_context_state_account.pubkey();
+    let ciphertext_validity_proof_context_state_account = Keypair::new();
+    let ciphertext_validity_proof_pubkey = ciphertext_validity_proof_context_state_account.pubkey();
+    let range_proof_context_state_account = Keypair::new();
+    let range_proof_pubkey = range_proof_context_state_account.pubkey();
+
+    // setup proofs
+    let create_range_proof_context_signer = &[&range_proof_context_state_account];
+    let create_equality_proof_context_signer = &[&equality_proof_context_state_account];
+    let create_ciphertext_validity_proof_context_signer =
+        &[&ciphertext_validity_proof_context_state_account];
+
+    let _ = try_join!(
+        token.confidential_transfer_create_context_state_account(
+            &range_proof_pubkey,
+            &context_state_authority_pubkey,
+            &range_proof_data,
+            true,
+            create_range_proof_context_signer
+        ),
+        token.confidential_transfer_create_context_state_account(
+            &equality_proof_pubkey,
+            &context_state_authority_pubkey,
+            &equality_proof_data,
+            false,
+            create_equality_proof_context_signer
+        ),
+        token.confidential_transfer_create_context_state_account(
+            &ciphertext_validity_proof_pubkey,
+            &context_state_authority_pubkey,
+            &ciphertext_validity_proof_data_with_ciphertext.proof_data,
+            false,
+            create_ciphertext_validity_proof_context_signer
+        )
+    )?;
+
+    // do the mint
+    let ciphertext_validity_proof_account_with_ciphertext = ProofAccountWithCiphertext {
+        context_state_account: ciphertext_validity_proof_pubkey,
+        ciphertext_lo: ciphertext_validity_proof_data_with_ciphertext.ciphertext_lo,
+        ciphertext_hi: ciphertext_validity_proof_data_with_ciphertext.ciphertext_hi,
+    };
+    let mint_result = token
+        .confidential_transfer_mint(
+            &mint_authority,
+            &recipient,
+            Some(&equality_proof_pubkey),
+            Some(&ciphertext_validity_proof_account_with_ciphertext),
+            Some(&range_proof_pubkey),
+            amount,
+            supply_elgamal_keypair,
+            &recipient_elgamal_pubkey,
+            auditor_elgamal_pubkey.as_ref(),
+            supply_aes_key,
+            Some(supply_account_info),
+            &bulk_signers,
+        )
+        .await?;
+
+    // close context state accounts
+    let close_context_state_signer = &[&context_state_authority];
+    let _ = try_join!(
+        token.confidential_transfer_close_context_state_account(
+            &equality_proof_pubkey,
+            &context_state_authority_pubkey,
+            &context_state_authority_pubkey,
+            close_context_state_signer
+        ),
+        token.confidential_transfer_close_context_state_account(
+            &ciphertext_validity_proof_pubkey,
+            &context_state_authority_pubkey,
+            &context_state_authority_pubkey,
+            close_context_state_signer
+        ),
+        token.confidential_transfer_close_context_state_account(
+            &range_proof_pubkey,
+            &context_state_authority_pubkey,
+            &context_state_authority_pubkey,
+            close_context_state_signer
+        ),
+    )?;
+
+    let tx_return = finish_tx(config, &mint_result, false).await?;
+    Ok(match tx_return {
+        TransactionReturnData::CliSignature(signature) => {
+            config.output_format.formatted_string(&signature)
+        }
+        TransactionReturnData::CliSignOnlyData(sign_only_data) => {
+            config.output_format.formatted_string(&sign_only_data)
+        }
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn command_confidential_burn(
+    config: &Config<'_>,
+    token_pubkey: Pubkey,
+    owner: Pubkey,
+    maybe_account: Option<Pubkey>,
+    bulk_signers: BulkSigners,
+    ui_amount: Amount,
+    mint_decimals: Option<u8>,
+    elgamal_keypair: &ElGamalKeypair,
+    aes_key: &AeKey,
+) -> CommandResult {
+    if config.sign_only {
+        panic!("Sign-only is not yet supported.");
+    }
+
+    // check if mint decimals provided is consistent
+    let mint_info = config.get_mint_info(&token_pubkey, mint_decimals).await?;
+    if mint_decimals.is_some() && mint_decimals != Some(mint_info.decimals) {
+        return Err(format!(
+            "Decimals {} was provided, but actual value is {}",
+            mint_decimals.unwrap(),
+            mint_info.decimals
+        )
+        .into());
+    }
+
+    let token = token_client_from_config(config, &token_pubkey, Some(mint_info.decimals))?;
+
+    // derive ATA if account address not provided
+    let token_account_address =
+        maybe_account.unwrap_or_else(|| token.get_associated_token_address(&owner));
+
+    let amount = match ui_amount {
+        Amount::All => {
+            return Err(
+                "ALL keyword is not currently supported for confidential burn"
+                    .to_string()
+                    .into(),
+            );
+        }
+        ui_amount => amount_to_raw_amount(ui_amount, mint_info.decimals, None, "TOKEN_AMOUNT"),
+    };
+
+    println_display(
+        config,
+        format!(
+            "Burning {} confidential tokens\n  Source: {}",
+            spl_token::amount_to_ui_amount(amount, mint_info.decimals),
+            token_account_address
+        ),
+    );
+
+    let mint_state = token.get_mint_info().await?;
+    let supply_elgamal_pubkey: elgamal::ElGamalPubkey = mint_state
+        .get_extension::<ConfidentialMintBurn>()?
+        .supply_elgamal_pubkey
+        .try_into()
+        .map_err(|_| "Invalid supply ElGamal pubkey")?;
+    let auditor_elgamal_pubkey = confidential_transfer_auditor_elgamal_pubkey(&mint_state)?;
+
+    let state_with_extension = token.get_account_info(&token_account_address).await?;
+    let burn_account_info =
+        BurnAccountInfo::new(state_with_extension.get_extension::<ConfidentialTransferAccount>()?);
+
+    let BurnProofData {
+        equality_proof_data,
+        ciphertext_validity_proof_data_with_ciphertext,
+        range_proof_data,
+    } = burn_account_info.generate_split_burn_proof_data(
+        amount,
+        elgamal_keypair,
+        aes_key,
+        &supply_elgamal_pubkey,
+        auditor_elgamal_pubkey.as_ref(),
+    )?;
+
+    let context_state_authority = config.fee_payer()?;
+    let context_state_authority_pubkey = context_state_authority.pubkey();
+    let equality_proof_context_state_account = Keypair::new();
+    let equality_proof_pubkey = equality_proof_context_state_account.pubkey();
+    let ciphertext_validity_proof_context_state_account = Keypair::new();
+    let ciphertext_validity_proof_pubkey = ciphertext_validity_proof_context_state_account.pubkey();
+    let range_proof_context_state_account = Keypair::new();
+    let range_proof_pubkey = range_proof_context_state_account.pubkey();
+
+    // setup proofs
+    let create_range_proof_context_signer = &[&range_proof_context_state_account];
+    let create_equality_proof_context_signer = &[&equality_proof_context_state_account];
+    let create_ciphertext_validity_proof_context_signer =
+        &[&ciphertext_validity_proof_context_state_account];
+
+    let _ = try_join!(
+        token.confidential_transfer_create_context_state_account(
+            &range_proof_pubkey,
+            &context_state_authority_pubkey,
+            &range_proof_data,
+            true,
+            create_range_proof_context_signer
+        ),
+        token.confidential_transfer_create_context_state_account(
+            &equality_proof_pubkey,
+            &context_state_authority_pubkey,
+            &equality_proof_data,
+            false,
+            create_equality_proof_context_signer
+        ),
+        token.confidential_transfer_create_context_state_account(
+            &ciphertext_validity_proof_pubkey,
+            &context_state_authority_pubkey,
+            &ciphertext_validity_proof_data_with_ciphertext.proof_data,
+            false,
+            create_ciphertext_validity_proof_context_signer
+        )
+    )?;
+
+    // do the burn
+    let ciphertext_validity_proof_account_with_ciphertext = ProofAccountWithCiphertext {
+        context_state_account: ciphertext_validity_proof_pubkey,
+        ciphertext_lo: ciphertext_validity_proof_data_with_ciphertext.ciphertext_lo,
+        ciphertext_hi: ciphertext_validity_proof_data_with_ciphertext.ciphertext_hi,
+    };
+    let burn_result = token
+        .confidential_transfer_burn(
+            &owner,
+            &token_account_address,
+            Some(&equality_proof_pubkey),
+            Some(&ciphertext_validity_proof_account_with_ciphertext),
+            Some(&range_proof_pubkey),
+            amount,
+            elgamal_keypair,
+            &supply_elgamal_pubkey,
+            auditor_elgamal_pubkey.as_ref(),
+            aes_key,
+            Some(burn_account_info),
+            &bulk_signers,
+        )
+        .await?;
+
+    // close context state accounts
+    let close_context_state_signer = &[&context_state_authority];
+    let _ = try_join!(
+        token.confidential_transfer_close_context_state_account(
+            &equality_proof_pubkey,
+            &token_account_address,
+            &context_state_authority_pubkey,
+            close_context_state_signer
+        ),
+        token.confidential_transfer_close_context_state_account(
+            &ciphertext_validity_proof_pubkey,
+            &token_account_address,
+            &context_state_authority_pubkey,
+            close_context_state_signer
+        ),
+        token.confidential_transfer_close_context_state_account(
+            &range_proof_pubkey,
+            &token_account_address,
+            &context_state_authority_pubkey,
+            close_context_state_signer
+        ),
+    )?;
+
+    let tx_return = finish_tx(config, &burn_result, false).await?;
+    Ok(match tx_return {
+        TransactionReturnData::CliSignature(signature) => {
+            config.output_format.formatted_string(&signature)
+        }
+        TransactionReturnData::CliSignOnlyData(sign_only_data) => {
+            config.output_format.formatted_string(&sign_only_data)
+        }
+    })
+}
+
+async fn command_apply_pending_burn(
+    config: &Config<'_>,
+    token_pubkey: Pubkey,
+    mint_authority: Pubkey,
+    bulk_signers: BulkSigners,
+) -> CommandResult {
+    let token = token_client_from_config(config, &token_pubkey, None)?;
+    let res = token
+        .confidential_transfer_apply_pending_burn(&mint_authority, &bulk_signers)
+        .await?;
+
+    let tx_return = finish_tx(config, &res, false).await?;
+    Ok(match tx_return {
+        TransactionReturnData::CliSignature(signature) => {
+            config.output_format.formatted_string(&signature)
+        }
+        TransactionReturnData::CliSignOnlyData(sign_only_data) => {
+            config.output_format.formatted_string(&sign_only_data)
+        }
+    })
+}
+
+async fn command_confidential_supply(
+    config: &Config<'_>,
+    token_pubkey: Pubkey,
+    auditor_elgamal_keypair: &ElGamalKeypair,
+    auditor_aes_key: &AeKey,
+) -> CommandResult {
+    let account = config.get_account_checked(&token_pubkey).await?;
+    let mint_state = StateWithExtensionsOwned::<Mint>::unpack(account.data)
+        .map_err(|_| format!("Could not deserialize token mint {}", token_pubkey))?;
+    let supply_account_info =
+        SupplyAccountInfo::new(mint_state.get_extension::<ConfidentialMintBurn>()?);
+    if supply_account_info.supply_elgamal_pubkey != (*auditor_elgamal_keypair.pubkey()).into() {
+        return Err(format!(
+            "The supply of {} isn't encrypted under the keys of the auditor",
+            token_pubkey
+        )
+        .into());
+    }
+
+    let supply =
+        supply_account_info.decrypted_current_supply(auditor_aes_key, auditor_elgamal_keypair)?;
+    let cli_token_amount = CliTokenAmount {
+        amount: token_amount_to_ui_amount_v3(
+            supply,
+            &SplTokenAdditionalDataV2::with_decimals(mint_state.base.decimals),
+        ),
+    };
+    Ok(config.output_format.formatted_string(&cli_token_amount))
+}
+
 async fn command_update_multiplier(
     config: &Config<'_>,
     token_pubkey: Pubkey,
@@ -3784,6 +4648,7 @@ pub async fn process_command(
                 default_account_state,
                 transfer_fee,
                 confidential_transfer_auto_approve,
+                arg_matches.is_present("enable_confidential_mint_burn"),
                 transfer_hook_program_id,
                 arg_matches.is_present("enable_metadata"),
                 arg_matches.is_present("enable_group"),
@@ -4026,6 +4891,40 @@ pub async fn process_command(
             )
             .await
         }
+        (CommandName::Transfer, arg_matches) if arg_matches.is_present("batch") => {
+            let token = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+            let recipients = parse_batch_recipients(arg_matches.value_of("batch").unwrap())?;
+            let sender = pubkey_of_signer(arg_matches, "from", &mut wallet_manager).unwrap();
+
+            let (owner_signer, owner) =
+                config.signer_or_default(arg_matches, "owner", &mut wallet_manager);
+            if config.multisigner_pubkeys.is_empty() {
+                push_signer_with_dedup(owner_signer, &mut bulk_signers);
+            }
+
+            let mint_decimals = arg_matches.get_one::<u8>(MINT_DECIMALS_ARG.name).copied();
+            let allow_unfunded_recipient = arg_matches.is_present("allow_empty_recipient")
+                || arg_matches.is_present("allow_unfunded_recipient");
+            let memo = value_t!(arg_matches, "memo", String).ok();
+
+            command_transfer_batch(
+                config,
+                token,
+                recipients,
+                sender,
+                owner,
+                allow_unfunded_recipient,
+                arg_matches.is_present("fund_recipient"),
+                mint_decimals,
+                memo,
+                bulk_signers,
+                arg_matches.is_present("no_wait"),
+                arg_matches.is_present("allow_non_system_account_recipient"),
+            )
+            .await
+        }
         (CommandName::Transfer, arg_matches) => {
             let token = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
                 .unwrap()
@@ -4374,6 +5273,22 @@ pub async fn process_command(
             )
             .await
         }
+        (CommandName::Holders, arg_matches) => {
+            let token = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+            let at_slot = arg_matches.get_one::<u64>("at_slot").copied();
+
+            command_holders(
+                config,
+                token,
+                at_slot,
+                arg_matches.is_present("paginate"),
+                arg_matches.is_present("exclude_frozen"),
+                arg_matches.is_present("csv"),
+            )
+            .await
+        }
         (CommandName::Address, arg_matches) => {
             let token = pubkey_of_signer(arg_matches, "token", &mut wallet_manager).unwrap();
             let owner = config.pubkey_or_default(arg_matches, "owner", &mut wallet_manager)?;
@@ -4833,6 +5748,120 @@ pub async fn process_command(
             )
             .await
         }
+        (CommandName::ConfidentialMint, arg_matches) => {
+            let (mint_authority_signer, mint_authority) =
+                config.signer_or_default(arg_matches, "mint_authority", &mut wallet_manager);
+
+            // Deriving ElGamal and AES key from signer. Custom ElGamal and AES keys will be
+            // supported in the future once upgrading to clap-v3.
+            //
+            // NOTE:: Seed bytes are hardcoded to be empty bytes for now. They will be
+            // updated once custom ElGamal and AES keys are supported.
+            let supply_elgamal_keypair =
+                ElGamalKeypair::new_from_signer(&*mint_authority_signer, b"").unwrap();
+            let supply_aes_key = AeKey::new_from_signer(&*mint_authority_signer, b"").unwrap();
+            push_signer_with_dedup(mint_authority_signer, &mut bulk_signers);
+
+            let token = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+            let amount = *arg_matches.get_one::<Amount>("amount").unwrap();
+            let mint_decimals = arg_matches.get_one::<u8>(MINT_DECIMALS_ARG.name).copied();
+            let mint_info = config.get_mint_info(&token, mint_decimals).await?;
+            let recipient = if let Some(address) =
+                pubkey_of_signer(arg_matches, "recipient", &mut wallet_manager).unwrap()
+            {
+                address
+            } else if let Some(address) =
+                pubkey_of_signer(arg_matches, "recipient_owner", &mut wallet_manager).unwrap()
+            {
+                get_associated_token_address_with_program_id(&address, &token, &config.program_id)
+            } else {
+                config.associated_token_address_for_token_and_program(
+                    &mint_info.address,
+                    &mint_authority,
+                    &mint_info.program_id,
+                )?
+            };
+            config.check_account(&recipient, Some(token)).await?;
+
+            command_confidential_mint(
+                config,
+                token,
+                amount,
+                recipient,
+                mint_info,
+                mint_authority,
+                bulk_signers,
+                &supply_elgamal_keypair,
+                &supply_aes_key,
+            )
+            .await
+        }
+        (CommandName::ConfidentialBurn, arg_matches) => {
+            let token = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+            let amount = *arg_matches.get_one::<Amount>("amount").unwrap();
+            let account = pubkey_of_signer(arg_matches, "address", &mut wallet_manager).unwrap();
+
+            let (owner_signer, owner) =
+                config.signer_or_default(arg_matches, "owner", &mut wallet_manager);
+            let mint_decimals = arg_matches.get_one::<u8>(MINT_DECIMALS_ARG.name).copied();
+
+            // Deriving ElGamal and AES key from signer. Custom ElGamal and AES keys will be
+            // supported in the future once upgrading to clap-v3.
+            //
+            // NOTE:: Seed bytes are hardcoded to be empty bytes for now. They will be
+            // updated once custom ElGamal and AES keys are supported.
+            let elgamal_keypair = ElGamalKeypair::new_from_signer(&*owner_signer, b"").unwrap();
+            let aes_key = AeKey::new_from_signer(&*owner_signer, b"").unwrap();
+
+            if config.multisigner_pubkeys.is_empty() {
+                push_signer_with_dedup(owner_signer, &mut bulk_signers);
+            }
+
+            command_confidential_burn(
+                config,
+                token,
+                owner,
+                account,
+                bulk_signers,
+                amount,
+                mint_decimals,
+                &elgamal_keypair,
+                &aes_key,
+            )
+            .await
+        }
+        (CommandName::ApplyPendingBurn, arg_matches) => {
+            let token = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+            let (mint_authority_signer, mint_authority) =
+                config.signer_or_default(arg_matches, "mint_authority", &mut wallet_manager);
+            if config.multisigner_pubkeys.is_empty() {
+                push_signer_with_dedup(mint_authority_signer, &mut bulk_signers);
+            }
+
+            command_apply_pending_burn(config, token, mint_authority, bulk_signers).await
+        }
+        (CommandName::ConfidentialSupply, arg_matches) => {
+            let token = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+            let (auditor_signer, _) =
+                config.signer_or_default(arg_matches, "auditor", &mut wallet_manager);
+
+            // The supply is encrypted under the keys derived from the mint authority when the
+            // token was created, which is also the auditor of the mint
+            let auditor_elgamal_keypair =
+                ElGamalKeypair::new_from_signer(&*auditor_signer, b"").unwrap();
+            let auditor_aes_key = AeKey::new_from_signer(&*auditor_signer, b"").unwrap();
+
+            command_confidential_supply(config, token, &auditor_elgamal_keypair, &auditor_aes_key)
+                .await
+        }
         (CommandName::UpdateUiAmountMultiplier, arg_matches) => {
             let token_pubkey = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
                 .unwrap()
@@ -332,6 +332,99 @@ impl fmt::Display for CliMint {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliTokenHolder {
+    pub(crate) owner: String,
+    pub(crate) balance: UiTokenAmount,
+    pub(crate) percent_of_supply: f64,
+    pub(crate) accounts: usize,
+    pub(crate) frozen_amount: String,
+    pub(crate) delegated_amount: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliTokenHolders {
+    pub(crate) mint: String,
+    pub(crate) program_id: String,
+    pub(crate) slot: u64,
+    pub(crate) supply: UiTokenAmount,
+    pub(crate) holders: Vec<CliTokenHolder>,
+}
+
+impl CliTokenHolders {
+    pub(crate) fn to_csv(&self) -> String {
+        let mut csv =
+            "owner,amount,ui_amount,percent_of_supply,accounts,frozen_ui_amount,delegated_ui_amount"
+            .to_string();
+        for holder in &self.holders {
+            csv.push_str(&format!(
+                "\n{},{},{},{},{},{},{}",
+                holder.owner,
+                holder.balance.amount,
+                holder.balance.real_number_string_trimmed(),
+                holder.percent_of_supply,
+                holder.accounts,
+                holder.frozen_amount,
+                holder.delegated_amount,
+            ));
+        }
+        csv
+    }
+}
+
+impl QuietDisplay for CliTokenHolders {}
+impl VerboseDisplay for CliTokenHolders {}
+
+impl fmt::Display for CliTokenHolders {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln_name_value(f, "Mint:", &self.mint)?;
+        writeln_name_value(f, "Slot:", &self.slot.to_string())?;
+        writeln_name_value(f, "Supply:", &self.supply.real_number_string_trimmed())?;
+        writeln_name_value(f, "Holders:", &self.holders.len().to_string())?;
+        writeln!(f)?;
+
+        let max_len_balance = self
+            .holders
+            .iter()
+            .map(|holder| holder.balance.real_number_string_trimmed().len())
+            .max()
+            .unwrap_or_default()
+            .max("Balance".len());
+        let header = format!(
+            "{:<44}  {:<3$}  {:>9}  Accounts",
+            "Owner", "Balance", "% Supply", max_len_balance
+        );
+        writeln!(f, "{}", header)?;
+        writeln!(f, "{}", "-".repeat(header.len()))?;
+        for holder in &self.holders {
+            let frozen = if holder.frozen_amount != "0" {
+                format!("  {}  Frozen: {}", WARNING, holder.frozen_amount)
+            } else {
+                "".to_string()
+            };
+            let delegated = if holder.delegated_amount != "0" {
+                format!("  Delegated: {}", holder.delegated_amount)
+            } else {
+                "".to_string()
+            };
+            writeln!(
+                f,
+                "{:<44}  {:<6$}  {:>8.4}%  {:<8}{}{}",
+                holder.owner,
+                holder.balance.real_number_string_trimmed(),
+                holder.percent_of_supply,
+                holder.accounts,
+                frozen,
+                delegated,
+                max_len_balance,
+            )?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct CliTokenAccounts {
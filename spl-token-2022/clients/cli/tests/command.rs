@@ -17,6 +17,7 @@ use {
     spl_associated_token_account_client::address::get_associated_token_address_with_program_id,
     spl_token_2022::{
         extension::{
+            confidential_mint_burn::ConfidentialMintBurn,
             confidential_transfer::{ConfidentialTransferAccount, ConfidentialTransferMint},
             confidential_transfer_fee::ConfidentialTransferFeeConfig,
             cpi_guard::CpiGuard,
@@ -106,9 +107,11 @@ async fn main() {
         async_trial!(balance_after_mint_with_owner, test_validator, payer),
         async_trial!(accounts, test_validator, payer),
         async_trial!(accounts_with_owner, test_validator, payer),
+        async_trial!(holders, test_validator, payer),
         async_trial!(wrapped_sol, test_validator, payer),
         async_trial!(transfer, test_validator, payer),
         async_trial!(transfer_fund_recipient, test_validator, payer),
+        async_trial!(transfer_batch, test_validator, payer),
         async_trial!(transfer_non_standard_recipient, test_validator, payer),
         async_trial!(allow_non_system_account_recipient, test_validator, payer),
         async_trial!(close_account, test_validator, payer),
@@ -145,6 +148,7 @@ async fn main() {
         async_trial!(metadata, test_validator, payer),
         async_trial!(group, test_validator, payer),
         async_trial!(confidential_transfer_with_fee, test_validator, payer),
+        async_trial!(confidential_mint_burn, test_validator, payer),
         async_trial!(compute_budget, test_validator, payer),
         async_trial!(scaled_ui_amount, test_validator, payer),
         async_trial!(pause, test_validator, payer),
@@ -164,12 +168,23 @@ const TEST_DECIMALS: u8 = 9;
 async fn new_validator_for_test() -> (TestValidator, Keypair) {
     solana_logger::setup();
     let mut test_validator_genesis = TestValidatorGenesis::default();
-    test_validator_genesis.add_upgradeable_programs_with_path(&[UpgradeableProgramInfo {
-        program_id: spl_token_2022::id(),
-        loader: bpf_loader_upgradeable::id(),
-        program_path: PathBuf::from("../../target/deploy/spl_token_2022.so"),
-        upgrade_authority: Pubkey::new_unique(),
-    }]);
+    test_validator_genesis.add_upgradeable_programs_with_path(&[
+        UpgradeableProgramInfo {
+            program_id: spl_token_2022::id(),
+            loader: bpf_loader_upgradeable::id(),
+            program_path: PathBuf::from("../../target/deploy/spl_token_2022.so"),
+            upgrade_authority: Pubkey::new_unique(),
+        },
+        // the in-tree token program, p-token is the only one with the Batch instruction
+        UpgradeableProgramInfo {
+            program_id: spl_token::id(),
+            loader: bpf_loader_upgradeable::id(),
+            program_path: PathBuf::from(
+                "../../../spl-token/target/deploy/pinocchio_token_program.so",
+            ),
+            upgrade_authority: Pubkey::new_unique(),
+        },
+    ]);
     test_validator_genesis.start_async().await
 }
 
@@ -838,6 +853,66 @@ async fn accounts_with_owner(test_validator: &TestValidator, payer: &Keypair) {
     }
 }
 
+async fn holders(test_validator: &TestValidator, payer: &Keypair) {
+    for program_id in VALID_TOKEN_PROGRAM_IDS.iter() {
+        let mut config = test_config_with_default_signer(test_validator, payer, program_id);
+        let token = create_token(&config, payer).await;
+        let account = create_associated_account(&config, payer, &token, &payer.pubkey()).await;
+        let aux_account = create_auxiliary_account(&config, payer, token).await;
+        let other_owner = Pubkey::new_unique();
+        let other_account = create_associated_account(&config, payer, &token, &other_owner).await;
+        let _empty_account =
+            create_associated_account(&config, payer, &token, &Pubkey::new_unique()).await;
+        mint_tokens(&config, payer, token, 60.0, account)
+            .await
+            .unwrap();
+        mint_tokens(&config, payer, token, 15.0, aux_account)
+            .await
+            .unwrap();
+        mint_tokens(&config, payer, token, 25.0, other_account)
+            .await
+            .unwrap();
+
+        for paginate in [false, true] {
+            let token_str = token.to_string();
+            let mut args = vec!["spl-token", CommandName::Holders.into(), &token_str];
+            if paginate {
+                args.push("--paginate");
+            }
+            let result = process_test_command(&config, payer, args).await.unwrap();
+            let value: serde_json::Value = serde_json::from_str(&result).unwrap();
+            let holders = value["holders"].as_array().unwrap();
+            assert_eq!(holders.len(), 2);
+            assert_eq!(holders[0]["owner"], payer.pubkey().to_string());
+            assert_eq!(holders[0]["accounts"], 2);
+            assert_eq!(holders[0]["balance"]["uiAmountString"], "75");
+            assert_eq!(holders[0]["percentOfSupply"], 75.0);
+            assert_eq!(holders[1]["owner"], other_owner.to_string());
+            assert_eq!(holders[1]["percentOfSupply"], 25.0);
+        }
+
+        config.output_format = OutputFormat::Display;
+        let result = process_test_command(
+            &config,
+            payer,
+            &[
+                "spl-token",
+                CommandName::Holders.into(),
+                &token.to_string(),
+                "--csv",
+            ],
+        )
+        .await
+        .unwrap();
+        let mut lines = result.lines();
+        assert!(lines.next().unwrap().starts_with("owner,amount,"));
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with(&format!("{},75000000000,75,", payer.pubkey())));
+        assert_eq!(lines.count(), 1);
+    }
+}
 async fn wrapped_sol(test_validator: &TestValidator, payer: &Keypair) {
     for program_id in VALID_TOKEN_PROGRAM_IDS.iter() {
         let config = test_config_with_default_signer(test_validator, payer, program_id);
@@ -965,6 +1040,102 @@ async fn transfer_fund_recipient(test_validator: &TestValidator, payer: &Keypair
     }
 }
 
+async fn transfer_batch(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token::id());
+    let token = create_token(&config, payer).await;
+    let source = create_associated_account(&config, payer, &token, &payer.pubkey()).await;
+    let funded_recipient = create_auxiliary_account(&config, payer, token).await;
+    let unfunded_recipient = Keypair::new().pubkey();
+    let unfunded_recipient_token_account =
+        get_associated_token_address_with_program_id(&unfunded_recipient, &token, &spl_token::id());
+    mint_tokens(&config, payer, token, 100.0, source)
+        .await
+        .unwrap();
+
+    let recipients_file = NamedTempFile::new().unwrap();
+    std::fs::write(
+        &recipients_file,
+        format!("# recipient amount\n{funded_recipient} 10\n{unfunded_recipient} 2.5\n"),
+    )
+    .unwrap();
+    let recipients_path = recipients_file.path().to_str().unwrap();
+
+    // batch fails to unfunded recipient without flag
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Transfer.into(),
+            "--fund-recipient",
+            &token.to_string(),
+            "--batch",
+            recipients_path,
+        ],
+    )
+    .await
+    .unwrap_err();
+
+    // with unfunded flag, batch goes through
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Transfer.into(),
+            "--fund-recipient",
+            "--allow-unfunded-recipient",
+            &token.to_string(),
+            "--batch",
+            recipients_path,
+        ],
+    )
+    .await
+    .unwrap();
+
+    for (address, ui_amount) in [
+        (source, 87.5),
+        (funded_recipient, 10.0),
+        (unfunded_recipient_token_account, 2.5),
+    ] {
+        let account = config.rpc_client.get_account(&address).await.unwrap();
+        let token_account = StateWithExtensionsOwned::<Account>::unpack(account.data).unwrap();
+        assert_eq!(
+            token_account.base.amount,
+            spl_token::ui_amount_to_amount(ui_amount, TEST_DECIMALS)
+        );
+    }
+
+    // Token-2022 doesn't implement the Batch instruction
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let token = create_token(&config, payer).await;
+    let source = create_associated_account(&config, payer, &token, &payer.pubkey()).await;
+    let recipient = create_auxiliary_account(&config, payer, token).await;
+    mint_tokens(&config, payer, token, 100.0, source)
+        .await
+        .unwrap();
+    std::fs::write(&recipients_file, format!("{recipient} 10\n")).unwrap();
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Transfer.into(),
+            &token.to_string(),
+            "--batch",
+            recipients_path,
+        ],
+    )
+    .await
+    .unwrap_err();
+    let account = config.rpc_client.get_account(&source).await.unwrap();
+    let token_account = StateWithExtensionsOwned::<Account>::unpack(account.data).unwrap();
+    assert_eq!(
+        token_account.base.amount,
+        spl_token::ui_amount_to_amount(100.0, TEST_DECIMALS)
+    );
+}
+
 async fn transfer_non_standard_recipient(test_validator: &TestValidator, payer: &Keypair) {
     for program_id in VALID_TOKEN_PROGRAM_IDS.iter() {
         for other_program_id in VALID_TOKEN_PROGRAM_IDS
@@ -2956,6 +3127,167 @@ async fn confidential_transfer_with_fee(test_validator: &TestValidator, payer: &
     );
 }
 
+async fn confidential_mint_burn(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+
+    // create token with confidential mint and burn enabled
+    let token = Keypair::new();
+    let token_keypair_file = NamedTempFile::new().unwrap();
+    write_keypair_file(&token, &token_keypair_file).unwrap();
+    let token_pubkey = token.pubkey();
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            token_keypair_file.path().to_str().unwrap(),
+            "--decimals",
+            "2",
+            "--enable-confidential-transfers",
+            "auto",
+            "--enable-confidential-mint-burn",
+        ],
+    )
+    .await
+    .unwrap();
+
+    let account = config.rpc_client.get_account(&token_pubkey).await.unwrap();
+    let test_mint = StateWithExtensionsOwned::<Mint>::unpack(account.data).unwrap();
+    let supply_elgamal_pubkey = test_mint
+        .get_extension::<ConfidentialMintBurn>()
+        .unwrap()
+        .supply_elgamal_pubkey;
+    let extension = test_mint
+        .get_extension::<ConfidentialTransferMint>()
+        .unwrap();
+    assert_eq!(
+        Option::<PodElGamalPubkey>::from(extension.auditor_elgamal_pubkey),
+        Some(supply_elgamal_pubkey),
+    );
+
+    // create a confidential transfer account
+    let token_account =
+        create_associated_account(&config, payer, &token_pubkey, &payer.pubkey()).await;
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::ConfigureConfidentialTransferAccount.into(),
+            &token_pubkey.to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+
+    // mint confidential tokens
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::ConfidentialMint.into(),
+            &token_pubkey.to_string(),
+            "100",
+            &token_account.to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let supply = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::ConfidentialSupply.into(),
+            &token_pubkey.to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+    let value: serde_json::Value = serde_json::from_str(&supply).unwrap();
+    assert_eq!(value["amount"], "10000");
+    assert_eq!(value["uiAmountString"], "100");
+
+    // the non-confidential supply is unchanged
+    let mint = config.rpc_client.get_account(&token_pubkey).await.unwrap();
+    let mint = StateWithExtensionsOwned::<Mint>::unpack(mint.data).unwrap();
+    assert_eq!(mint.base.supply, 0);
+
+    // burn confidential tokens
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::ApplyPendingBalance.into(),
+            &token_pubkey.to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::ConfidentialBurn.into(),
+            &token_pubkey.to_string(),
+            "40",
+        ],
+    )
+    .await
+    .unwrap();
+
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::ApplyPendingBurn.into(),
+            &token_pubkey.to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let supply = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::ConfidentialSupply.into(),
+            &token_pubkey.to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+    let value: serde_json::Value = serde_json::from_str(&supply).unwrap();
+    assert_eq!(value["amount"], "6000");
+    assert_eq!(value["uiAmountString"], "60");
+
+    // the supply can't be decrypted with other keys
+    let auditor = Keypair::new();
+    let auditor_keypair_file = NamedTempFile::new().unwrap();
+    write_keypair_file(&auditor, &auditor_keypair_file).unwrap();
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::ConfidentialSupply.into(),
+            &token_pubkey.to_string(),
+            "--auditor",
+            auditor_keypair_file.path().to_str().unwrap(),
+        ],
+    )
+    .await
+    .unwrap_err();
+}
+
 async fn multisig_transfer(test_validator: &TestValidator, payer: &Keypair) {
     let m = 3;
     let n = 5u8;
@@ -1429,6 +1429,73 @@ pub fn ui_amount_to_amount(
     })
 }
 
+/// Discriminator of the `Batch` instruction, only supported by p-token.
+pub const BATCH_DISCRIMINATOR: u8 = 255;
+
+/// Creates a `Batch` instruction, executing `instructions` in order within
+/// a single instruction.
+///
+/// The accounts of the instructions are appended in order, and the data of
+/// each instruction is prefixed by its number of accounts and its length.
+pub fn batch(
+    token_program_id: &Pubkey,
+    instructions: &[Instruction],
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    if instructions.is_empty() {
+        return Err(TokenError::InvalidInstruction.into());
+    }
+
+    let mut accounts = Vec::with_capacity(instructions.iter().map(|ix| ix.accounts.len()).sum());
+    let mut data = vec![BATCH_DISCRIMINATOR];
+    for instruction in instructions {
+        if instruction.program_id != *token_program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let num_accounts =
+            u8::try_from(instruction.accounts.len()).map_err(|_| ProgramError::InvalidArgument)?;
+        let data_len =
+            u8::try_from(instruction.data.len()).map_err(|_| TokenError::InvalidInstruction)?;
+        // a batch can't be nested in another batch
+        if matches!(instruction.data.first(), None | Some(&BATCH_DISCRIMINATOR)) {
+            return Err(TokenError::InvalidInstruction.into());
+        }
+        data.extend_from_slice(&[num_accounts, data_len]);
+        data.extend_from_slice(&instruction.data);
+        accounts.extend_from_slice(&instruction.accounts);
+    }
+
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Splits the data of a `Batch` instruction into the number of accounts and
+/// the data of each of its instructions.
+pub fn unpack_batch(input: &[u8]) -> Result<Vec<(usize, &[u8])>, ProgramError> {
+    let (&BATCH_DISCRIMINATOR, mut rest) =
+        input.split_first().ok_or(TokenError::InvalidInstruction)?
+    else {
+        return Err(TokenError::InvalidInstruction.into());
+    };
+    let mut instructions = vec![];
+    while let [num_accounts, data_len, remaining @ ..] = rest {
+        let data_len = *data_len as usize;
+        if data_len == 0 || remaining.len() < data_len {
+            return Err(TokenError::InvalidInstruction.into());
+        }
+        let (data, remaining) = remaining.split_at(data_len);
+        instructions.push((*num_accounts as usize, data));
+        rest = remaining;
+    }
+    if !rest.is_empty() || instructions.is_empty() {
+        return Err(TokenError::InvalidInstruction.into());
+    }
+    Ok(instructions)
+}
+
 /// Utility function that checks index is between `MIN_SIGNERS` and
 /// `MAX_SIGNERS`
 pub fn is_valid_signer_index(index: usize) -> bool {
@@ -1691,6 +1758,79 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_batch() {
+        let source = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let destinations = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let authority = Pubkey::new_unique();
+        let instructions = destinations
+            .iter()
+            .enumerate()
+            .map(|(i, destination)| {
+                transfer_checked(
+                    &crate::id(),
+                    &source,
+                    &mint,
+                    destination,
+                    &authority,
+                    &[],
+                    i as u64 + 1,
+                    6,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let batch_instruction = batch(&crate::id(), &instructions).unwrap();
+        assert_eq!(batch_instruction.program_id, crate::id());
+        assert_eq!(
+            batch_instruction.accounts,
+            [
+                instructions[0].accounts.clone(),
+                instructions[1].accounts.clone()
+            ]
+            .concat()
+        );
+        let mut expect = vec![BATCH_DISCRIMINATOR, 4, 10];
+        expect.extend_from_slice(&instructions[0].data);
+        expect.extend_from_slice(&[4, 10]);
+        expect.extend_from_slice(&instructions[1].data);
+        assert_eq!(batch_instruction.data, expect);
+
+        let unpacked = unpack_batch(&batch_instruction.data).unwrap();
+        assert_eq!(
+            unpacked,
+            vec![
+                (4, instructions[0].data.as_slice()),
+                (4, instructions[1].data.as_slice())
+            ]
+        );
+        for (_, data) in unpacked {
+            assert!(matches!(
+                TokenInstruction::unpack(data).unwrap(),
+                TokenInstruction::TransferChecked { decimals: 6, .. }
+            ));
+        }
+
+        // batches can't be empty, nested, or contain other programs' instructions
+        assert!(batch(&crate::id(), &[]).is_err());
+        assert!(batch(&crate::id(), &[batch_instruction.clone()]).is_err());
+        let mut other_program_instruction = instructions[0].clone();
+        other_program_instruction.program_id = Pubkey::new_unique();
+        assert_eq!(
+            batch(&crate::id(), &[other_program_instruction]),
+            Err(ProgramError::IncorrectProgramId)
+        );
+
+        // truncated or trailing data
+        let data = &batch_instruction.data;
+        assert!(unpack_batch(&data[..data.len() - 1]).is_err());
+        assert!(unpack_batch(&[data.as_slice(), &[1]].concat()).is_err());
+        assert!(unpack_batch(&[BATCH_DISCRIMINATOR]).is_err());
+        assert!(unpack_batch(&data[1..]).is_err());
+    }
+
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(1024))]
         #[test]
@@ -9,6 +9,7 @@ pub mod recvmmsg;
 pub mod sendmmsg;
 pub mod socket;
 pub mod streamer;
+pub mod xdp;
 
 #[macro_use]
 extern crate log;
@@ -0,0 +1,170 @@
+use {
+    dashmap::DashMap,
+    governor::{DefaultDirectRateLimiter, DefaultKeyedRateLimiter, Quota, RateLimiter},
+    std::{
+        net::{IpAddr, Ipv4Addr, Ipv6Addr},
+        num::NonZeroU32,
+    },
+};
+
+/// Unstaked streams from IPv4 addresses are rate limited per /24 prefix
+pub const IPV4_RATE_LIMIT_PREFIX_LEN: u32 = 24;
+/// Unstaked streams from IPv6 addresses are rate limited per /56 prefix
+pub const IPV6_RATE_LIMIT_PREFIX_LEN: u32 = 56;
+
+/// Returns the prefix of `ip` which its unstaked streams are rate limited by.
+/// IPv4-mapped IPv6 addresses are treated as IPv4 addresses.
+pub fn ip_prefix(ip: IpAddr) -> IpAddr {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => IpAddr::V4(Ipv4Addr::from(
+            u32::from(ip) & (u32::MAX << (32 - IPV4_RATE_LIMIT_PREFIX_LEN)),
+        )),
+        IpAddr::V6(ip) => IpAddr::V6(Ipv6Addr::from(
+            u128::from(ip) & (u128::MAX << (128 - IPV6_RATE_LIMIT_PREFIX_LEN)),
+        )),
+    }
+}
+
+/// Number of streams allowed and throttled for an IP prefix
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PrefixStreamStats {
+    pub allowed: u64,
+    pub throttled: u64,
+}
+
+/// Hierarchical token buckets for the streams of unstaked connections: every stream takes
+/// a token from the bucket of its IP prefix, and from the bucket shared by all the unstaked
+/// streams. A single prefix can then use at most its own share of the unstaked budget.
+pub struct PrefixStreamRateLimiter {
+    total: DefaultDirectRateLimiter,
+    prefixes: DefaultKeyedRateLimiter<IpAddr>,
+    stats: DashMap<IpAddr, PrefixStreamStats>,
+}
+
+impl PrefixStreamRateLimiter {
+    /// Create a new rate limiter. The rates are specified as the count per second.
+    pub fn new(max_streams_per_sec: u64, max_streams_per_prefix_per_sec: u64) -> Self {
+        let quota = |limit_per_second: u64| {
+            let limit_per_second = u32::try_from(limit_per_second).unwrap_or(u32::MAX);
+            Quota::per_second(NonZeroU32::new(limit_per_second).unwrap_or(NonZeroU32::MIN))
+        };
+        Self {
+            total: RateLimiter::direct(quota(max_streams_per_sec)),
+            prefixes: DefaultKeyedRateLimiter::keyed(quota(max_streams_per_prefix_per_sec)),
+            stats: DashMap::default(),
+        }
+    }
+
+    /// Check if a stream from the said `ip` is allowed.
+    pub fn is_allowed(&self, ip: &IpAddr) -> bool {
+        let prefix = ip_prefix(*ip);
+        // Check the prefix first, so that a throttled prefix does not drain the shared bucket.
+        let allowed = self.prefixes.check_key(&prefix).is_ok() && self.total.check().is_ok();
+        let mut stats = self.stats.entry(prefix).or_default();
+        if allowed {
+            stats.allowed = stats.allowed.saturating_add(1);
+        } else {
+            debug!("Stream from IP prefix {prefix:?} throttled");
+            stats.throttled = stats.throttled.saturating_add(1);
+        }
+        allowed
+    }
+
+    /// retain only keys whose rate-limiting start date is within the rate-limiting interval.
+    /// Otherwise drop them as inactive
+    pub fn retain_recent(&self) {
+        self.prefixes.retain_recent()
+    }
+
+    /// Returns the number of "live" prefixes in the rate limiter.
+    pub fn len(&self) -> usize {
+        self.prefixes.len()
+    }
+
+    /// Returns `true` if the rate limiter has no prefixes in it.
+    pub fn is_empty(&self) -> bool {
+        self.prefixes.is_empty()
+    }
+
+    /// Takes the stats accumulated since the last call, of at most `max_prefixes` prefixes,
+    /// the most throttled ones first.
+    pub fn take_stats(&self, max_prefixes: usize) -> Vec<(IpAddr, PrefixStreamStats)> {
+        let mut stats: Vec<_> = self
+            .stats
+            .iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect();
+        self.stats.clear();
+        stats.sort_unstable_by_key(|(prefix, stats)| {
+            (std::cmp::Reverse((stats.throttled, stats.allowed)), *prefix)
+        });
+        stats.truncate(max_prefixes);
+        stats
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_ip_prefix() {
+        assert_eq!(
+            ip_prefix("192.168.1.17".parse().unwrap()),
+            "192.168.1.0".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(
+            ip_prefix("::ffff:192.168.1.17".parse().unwrap()),
+            "192.168.1.0".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(
+            ip_prefix("2001:db8:aa:bbcc:1:2:3:4".parse().unwrap()),
+            "2001:db8:aa:bb00::".parse::<IpAddr>().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prefix_stream_rate_limiter() {
+        let limiter = PrefixStreamRateLimiter::new(5, 2);
+        let ip1 = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+        let ip2 = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 2));
+        let ip3 = IpAddr::V4(Ipv4Addr::new(192, 168, 2, 1));
+        let ip4 = IpAddr::V4(Ipv4Addr::new(192, 168, 3, 1));
+        // The addresses of a prefix share its bucket
+        assert!(limiter.is_allowed(&ip1));
+        assert!(limiter.is_allowed(&ip2));
+        assert!(!limiter.is_allowed(&ip1));
+        assert!(!limiter.is_allowed(&ip2));
+        assert_eq!(limiter.len(), 1);
+        // A throttled prefix does not starve the others
+        assert!(limiter.is_allowed(&ip3));
+        assert!(limiter.is_allowed(&ip3));
+        assert!(!limiter.is_allowed(&ip3));
+        // until the total budget is exhausted
+        assert!(limiter.is_allowed(&ip4));
+        assert!(!limiter.is_allowed(&ip4));
+        assert_eq!(limiter.len(), 3);
+
+        let stats = limiter.take_stats(2);
+        assert_eq!(
+            stats,
+            vec![
+                (
+                    ip_prefix(ip1),
+                    PrefixStreamStats {
+                        allowed: 2,
+                        throttled: 2
+                    }
+                ),
+                (
+                    ip_prefix(ip3),
+                    PrefixStreamStats {
+                        allowed: 2,
+                        throttled: 1
+                    }
+                ),
+            ]
+        );
+        assert!(limiter.take_stats(2).is_empty());
+    }
+}
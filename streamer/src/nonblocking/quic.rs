@@ -2,7 +2,9 @@ use {
     crate::{
         nonblocking::{
             connection_rate_limiter::{ConnectionRateLimiter, TotalConnectionRateLimiter},
+            prefix_rate_limiter::PrefixStreamRateLimiter,
             stream_throttle::{
+                max_unstaked_streams_per_sec, streams_per_throttling_interval,
                 ConnectionStreamCounter, StakedStreamLoadEMA, STREAM_THROTTLING_INTERVAL,
                 STREAM_THROTTLING_INTERVAL_MS,
             },
@@ -36,6 +38,7 @@ use {
     solana_transaction_metrics_tracker::signature_if_should_track_packet,
     std::{
         array,
+        collections::HashMap,
         fmt,
         iter::repeat_with,
         net::{IpAddr, SocketAddr, UdpSocket},
@@ -176,6 +179,8 @@ pub fn spawn_server_multi(
         max_connections_per_peer,
         max_streams_per_ms,
         max_connections_per_ipaddr_per_min,
+        max_unstaked_streams_per_ip_prefix_per_sec,
+        unstaked_stream_quotas,
         wait_for_chunk_timeout,
         coalesce,
         coalesce_channel_size,
@@ -209,6 +214,8 @@ pub fn spawn_server_multi(
         max_unstaked_connections,
         max_streams_per_ms,
         max_connections_per_ipaddr_per_min,
+        max_unstaked_streams_per_ip_prefix_per_sec,
+        Arc::new(unstaked_stream_quotas),
         stats.clone(),
         wait_for_chunk_timeout,
         coalesce,
@@ -280,6 +287,8 @@ async fn run_server(
     max_unstaked_connections: usize,
     max_streams_per_ms: u64,
     max_connections_per_ipaddr_per_min: u64,
+    max_unstaked_streams_per_ip_prefix_per_sec: u64,
+    unstaked_stream_quotas: Arc<HashMap<Pubkey, u64>>,
     stats: Arc<StreamerStats>,
     wait_for_chunk_timeout: Duration,
     coalesce: Duration,
@@ -292,6 +301,26 @@ async fn run_server(
     let overall_connection_rate_limiter = Arc::new(TotalConnectionRateLimiter::new(
         TOTAL_CONNECTIONS_PER_SECOND,
     ));
+    // The quotas reserved for unstaked identities are taken out of the streams allowed for
+    // all the unstaked peers
+    let reserved_unstaked_streams_per_sec = unstaked_stream_quotas
+        .values()
+        .fold(0u64, |total, quota| total.saturating_add(*quota));
+    let unstaked_streams_per_sec = max_unstaked_streams_per_sec(max_streams_per_ms);
+    if reserved_unstaked_streams_per_sec > unstaked_streams_per_sec {
+        warn!(
+            "{name}: the reserved stream quotas ({reserved_unstaked_streams_per_sec}/s) exceed \
+             the streams allowed for unstaked peers ({unstaked_streams_per_sec}/s)"
+        );
+    }
+    let prefix_rate_limiter = (max_unstaked_connections > 0
+        && max_unstaked_streams_per_ip_prefix_per_sec > 0)
+        .then(|| {
+            Arc::new(PrefixStreamRateLimiter::new(
+                unstaked_streams_per_sec.saturating_sub(reserved_unstaked_streams_per_sec),
+                max_unstaked_streams_per_ip_prefix_per_sec,
+            ))
+        });
 
     const WAIT_FOR_CONNECTION_TIMEOUT: Duration = Duration::from_secs(1);
     debug!("spawn quic server");
@@ -302,6 +331,7 @@ async fn run_server(
         stats.clone(),
         max_unstaked_connections,
         max_streams_per_ms,
+        reserved_unstaked_streams_per_sec,
     ));
     stats
         .quic_endpoints_count
@@ -352,6 +382,13 @@ async fn run_server(
 
         if last_datapoint.elapsed().as_secs() >= 5 {
             stats.report(name);
+            if let Some(prefix_rate_limiter) = &prefix_rate_limiter {
+                report_prefix_stream_stats(name, prefix_rate_limiter);
+                prefix_rate_limiter.retain_recent();
+                stats
+                    .stream_prefix_rate_limiter_length
+                    .store(prefix_rate_limiter.len(), Ordering::Relaxed);
+            }
             last_datapoint = Instant::now();
         }
 
@@ -399,6 +436,8 @@ async fn run_server(
                         max_staked_connections,
                         max_unstaked_connections,
                         max_streams_per_ms,
+                        prefix_rate_limiter.clone(),
+                        unstaked_stream_quotas.clone(),
                         stats.clone(),
                         wait_for_chunk_timeout,
                         stream_load_ema.clone(),
@@ -417,6 +456,20 @@ async fn run_server(
     }
 }
 
+/// Reports the streams of the IP prefixes of unstaked peers, the most throttled ones only
+fn report_prefix_stream_stats(name: &'static str, prefix_rate_limiter: &PrefixStreamRateLimiter) {
+    const MAX_REPORTED_PREFIXES: usize = 10;
+    for (prefix, prefix_stats) in prefix_rate_limiter.take_stats(MAX_REPORTED_PREFIXES) {
+        datapoint_info!(
+            "quic_unstaked_ip_prefix_streams",
+            "server" => name,
+            "prefix" => prefix.to_string(),
+            ("allowed", prefix_stats.allowed, i64),
+            ("throttled", prefix_stats.throttled, i64),
+        );
+    }
+}
+
 fn prune_unstaked_connection_table(
     unstaked_connection_table: &mut ConnectionTable,
     max_unstaked_connections: usize,
@@ -506,6 +559,10 @@ struct NewConnectionHandlerParams {
     stats: Arc<StreamerStats>,
     max_stake: u64,
     min_stake: u64,
+    // Streams per throttling interval reserved for the identity of an unstaked peer, shared by
+    // all the connections of the identity
+    reserved_stream_quota: Option<u64>,
+    prefix_rate_limiter: Option<Arc<PrefixStreamRateLimiter>>,
 }
 
 impl NewConnectionHandlerParams {
@@ -513,6 +570,7 @@ impl NewConnectionHandlerParams {
         packet_sender: Sender<PacketAccumulator>,
         max_connections_per_peer: usize,
         stats: Arc<StreamerStats>,
+        prefix_rate_limiter: Option<Arc<PrefixStreamRateLimiter>>,
     ) -> NewConnectionHandlerParams {
         NewConnectionHandlerParams {
             packet_sender,
@@ -523,6 +581,8 @@ impl NewConnectionHandlerParams {
             stats,
             max_stake: 0,
             min_stake: 0,
+            reserved_stream_quota: None,
+            prefix_rate_limiter,
         }
     }
 }
@@ -693,6 +753,8 @@ async fn setup_connection(
     max_staked_connections: usize,
     max_unstaked_connections: usize,
     max_streams_per_ms: u64,
+    prefix_rate_limiter: Option<Arc<PrefixStreamRateLimiter>>,
+    unstaked_stream_quotas: Arc<HashMap<Pubkey, u64>>,
     stats: Arc<StreamerStats>,
     wait_for_chunk_timeout: Duration,
     stream_load_ema: Arc<StakedStreamLoadEMA>,
@@ -735,11 +797,12 @@ async fn setup_connection(
                     return;
                 }
 
-                let params = get_connection_stake(&new_connection, &staked_nodes).map_or(
+                let mut params = get_connection_stake(&new_connection, &staked_nodes).map_or(
                     NewConnectionHandlerParams::new_unstaked(
                         packet_sender.clone(),
                         max_connections_per_peer,
                         stats.clone(),
+                        prefix_rate_limiter.clone(),
                     ),
                     |(pubkey, stake, total_stake, max_stake, min_stake)| {
                         // The heuristic is that the stake should be large engouh to have 1 stream pass throuh within one throttle
@@ -762,9 +825,27 @@ async fn setup_connection(
                             stats: stats.clone(),
                             max_stake,
                             min_stake,
+                            reserved_stream_quota: None,
+                            prefix_rate_limiter: prefix_rate_limiter.clone(),
                         }
                     },
                 );
+                if matches!(params.peer_type, ConnectionPeerType::Unstaked)
+                    && !unstaked_stream_quotas.is_empty()
+                {
+                    if let Some((pubkey, quota)) =
+                        get_remote_pubkey(&new_connection).and_then(|pubkey| {
+                            unstaked_stream_quotas
+                                .get(&pubkey)
+                                .map(|quota| (pubkey, *quota))
+                        })
+                    {
+                        // Key the connection by the identity, so that all the connections of
+                        // the identity share the stream counter of its quota
+                        params.remote_pubkey = Some(pubkey);
+                        params.reserved_stream_quota = Some(streams_per_throttling_interval(quota));
+                    }
+                }
 
                 match params.peer_type {
                     ConnectionPeerType::Staked(stake) => {
@@ -1058,8 +1139,14 @@ async fn handle_connection(
         remote_pubkey,
         stats,
         total_stake,
+        reserved_stream_quota,
+        prefix_rate_limiter,
         ..
     } = params;
+    // Unstaked peers with a reserved quota are not subject to the unstaked limits
+    let prefix_rate_limiter = prefix_rate_limiter.filter(|_| {
+        matches!(peer_type, ConnectionPeerType::Unstaked) && reserved_stream_quota.is_none()
+    });
 
     debug!(
         "quic new connection {} streams: {} connections: {}",
@@ -1083,8 +1170,9 @@ async fn handle_connection(
             _ = cancel.cancelled() => break,
         };
 
-        let max_streams_per_throttling_interval =
-            stream_load_ema.available_load_capacity_in_throttling_duration(peer_type, total_stake);
+        let max_streams_per_throttling_interval = reserved_stream_quota.unwrap_or_else(|| {
+            stream_load_ema.available_load_capacity_in_throttling_duration(peer_type, total_stake)
+        });
 
         let throttle_interval_start = stream_counter.reset_throttling_params_if_needed();
         let streams_read_in_throttle_interval = stream_counter.stream_count.load(Ordering::Relaxed);
@@ -1117,6 +1205,21 @@ async fn handle_connection(
                 sleep(throttle_duration).await;
             }
         }
+        if let Some(prefix_rate_limiter) = &prefix_rate_limiter {
+            if !prefix_rate_limiter.is_allowed(&remote_addr.ip()) {
+                // The IP prefix of the peer used up its share of the unstaked streams. Back
+                // off for a throttling interval.
+                debug!("Throttling stream from {remote_addr:?}, IP prefix rate limit exceeded");
+                stats.throttled_streams.fetch_add(1, Ordering::Relaxed);
+                stats
+                    .throttled_unstaked_streams_per_ip_prefix
+                    .fetch_add(1, Ordering::Relaxed);
+                sleep(STREAM_THROTTLING_INTERVAL).await;
+            }
+        }
+        if reserved_stream_quota.is_some() {
+            stats.reserved_quota_streams.fetch_add(1, Ordering::Relaxed);
+        }
         stream_load_ema.increment_load(peer_type);
         stream_counter.stream_count.fetch_add(1, Ordering::Relaxed);
         stats.total_streams.fetch_add(1, Ordering::Relaxed);
@@ -2437,6 +2540,70 @@ pub mod test {
         assert!(stats.throttled_unstaked_streams.load(Ordering::Relaxed) > 0);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reserved_stream_quota_shared_by_identity_connections() {
+        solana_logger::setup_with_default_filter();
+
+        // 2 streams per throttling interval for the identity
+        let client_keypair = Keypair::new();
+        let SpawnTestServerResult {
+            join_handle,
+            exit,
+            receiver,
+            server_address,
+            stats,
+        } = setup_quic_server(
+            None,
+            QuicServerParams {
+                max_connections_per_peer: 2,
+                unstaked_stream_quotas: HashMap::from([(client_keypair.pubkey(), 20)]),
+                ..QuicServerParams::default_for_tests()
+            },
+        );
+
+        let connection1 = make_client_endpoint(&server_address, Some(&client_keypair)).await;
+        let connection2 = make_client_endpoint(&server_address, Some(&client_keypair)).await;
+
+        let expected_num_txs = 80;
+        let start_time = tokio::time::Instant::now();
+        for i in 0..expected_num_txs {
+            let connection = if i % 2 == 0 {
+                &connection1
+            } else {
+                &connection2
+            };
+            let mut send_stream = connection.open_uni().await.unwrap();
+            let data = format!("{i}").into_bytes();
+            send_stream.write_all(&data).await.unwrap();
+            send_stream.finish().unwrap();
+        }
+
+        let mut num_txs_received = 0;
+        while num_txs_received < expected_num_txs && start_time.elapsed() < Duration::from_secs(10)
+        {
+            if let Ok(packets) = receiver.try_recv() {
+                num_txs_received += packets.len();
+            } else {
+                sleep(Duration::from_millis(10)).await;
+            }
+        }
+        let elapsed = start_time.elapsed();
+        info!("Elapsed receiving: {elapsed:?}");
+        assert_eq!(expected_num_txs, num_txs_received);
+        // Both connections draw from the quota of the identity, so reading the streams takes
+        // about twice as many throttling intervals as with a quota per connection.
+        assert!(elapsed >= Duration::from_millis(1800));
+
+        exit.store(true, Ordering::Relaxed);
+        join_handle.await.unwrap();
+
+        assert_eq!(
+            stats.reserved_quota_streams.load(Ordering::Relaxed),
+            expected_num_txs
+        );
+        assert!(stats.throttled_unstaked_streams.load(Ordering::Relaxed) > 0);
+    }
+
     #[test]
     fn test_client_connection_tracker() {
         let stats = Arc::new(StreamerStats::default());
@@ -19,6 +19,21 @@ const STREAM_LOAD_EMA_INTERVAL_MS: u64 = 5;
 const STREAM_LOAD_EMA_INTERVAL_COUNT: u64 = 10;
 const EMA_WINDOW_MS: u64 = STREAM_LOAD_EMA_INTERVAL_MS * STREAM_LOAD_EMA_INTERVAL_COUNT;
 
+/// Returns the streams per second allowed for all the unstaked connections
+pub(crate) fn max_unstaked_streams_per_sec(max_streams_per_ms: u64) -> u64 {
+    Percentage::from(MAX_UNSTAKED_STREAMS_PERCENT).apply_to(max_streams_per_ms * 1000)
+}
+
+/// Converts a quota of streams per second to the streams allowed in a throttling interval,
+/// a non zero quota allows at least one stream per interval
+pub(crate) fn streams_per_throttling_interval(streams_per_sec: u64) -> u64 {
+    if streams_per_sec == 0 {
+        0
+    } else {
+        (streams_per_sec.saturating_mul(STREAM_THROTTLING_INTERVAL_MS) / 1000).max(1)
+    }
+}
+
 pub(crate) struct StakedStreamLoadEMA {
     current_load_ema: AtomicU64,
     load_in_recent_interval: AtomicU64,
@@ -38,6 +53,7 @@ impl StakedStreamLoadEMA {
         stats: Arc<StreamerStats>,
         max_unstaked_connections: usize,
         max_streams_per_ms: u64,
+        reserved_unstaked_streams_per_sec: u64,
     ) -> Self {
         let allow_unstaked_streams = max_unstaked_connections > 0;
         let max_staked_load_in_ema_window = if allow_unstaked_streams {
@@ -48,9 +64,14 @@ impl StakedStreamLoadEMA {
             max_streams_per_ms * EMA_WINDOW_MS
         };
 
+        // The streams reserved for unstaked identities are not shared by the other unstaked
+        // connections
         let max_unstaked_load_in_throttling_window = if allow_unstaked_streams {
             Percentage::from(MAX_UNSTAKED_STREAMS_PERCENT)
                 .apply_to(max_streams_per_ms * STREAM_THROTTLING_INTERVAL_MS)
+                .saturating_sub(streams_per_throttling_interval(
+                    reserved_unstaked_streams_per_sec,
+                ))
                 .saturating_div(max_unstaked_connections as u64)
         } else {
             0
@@ -239,6 +260,7 @@ pub mod test {
             Arc::new(StreamerStats::default()),
             DEFAULT_MAX_UNSTAKED_CONNECTIONS,
             DEFAULT_MAX_STREAMS_PER_MS,
+            0,
         ));
         // 50K packets per ms * 20% / 500 max unstaked connections
         assert_eq!(
@@ -248,6 +270,33 @@ pub mod test {
             ),
             20
         );
+
+        // 50K packets per ms * 20% - 5K reserved per 100ms / 500 max unstaked connections
+        let load_ema = Arc::new(StakedStreamLoadEMA::new(
+            Arc::new(StreamerStats::default()),
+            DEFAULT_MAX_UNSTAKED_CONNECTIONS,
+            DEFAULT_MAX_STREAMS_PER_MS,
+            50_000,
+        ));
+        assert_eq!(
+            load_ema.available_load_capacity_in_throttling_duration(
+                ConnectionPeerType::Unstaked,
+                10000,
+            ),
+            10
+        );
+    }
+
+    #[test]
+    fn test_streams_per_throttling_interval() {
+        // 500K packets per second * 20%
+        assert_eq!(
+            max_unstaked_streams_per_sec(DEFAULT_MAX_STREAMS_PER_MS),
+            100_000
+        );
+        assert_eq!(streams_per_throttling_interval(0), 0);
+        assert_eq!(streams_per_throttling_interval(1), 1);
+        assert_eq!(streams_per_throttling_interval(100_000), 10_000);
     }
 
     #[test]
@@ -256,6 +305,7 @@ pub mod test {
             Arc::new(StreamerStats::default()),
             DEFAULT_MAX_UNSTAKED_CONNECTIONS,
             DEFAULT_MAX_STREAMS_PER_MS,
+            0,
         ));
 
         // EMA load is used for staked connections to calculate max number of allowed streams.
@@ -348,6 +398,7 @@ pub mod test {
             Arc::new(StreamerStats::default()),
             0,
             DEFAULT_MAX_STREAMS_PER_MS,
+            0,
         ));
 
         // EMA load is used for staked connections to calculate max number of allowed streams.
@@ -438,6 +489,7 @@ pub mod test {
             Arc::new(StreamerStats::default()),
             DEFAULT_MAX_UNSTAKED_CONNECTIONS,
             DEFAULT_MAX_STREAMS_PER_MS,
+            0,
         ));
         stream_load_ema
             .load_in_recent_interval
@@ -467,6 +519,7 @@ pub mod test {
             Arc::new(StreamerStats::default()),
             DEFAULT_MAX_UNSTAKED_CONNECTIONS,
             DEFAULT_MAX_STREAMS_PER_MS,
+            0,
         ));
         stream_load_ema
             .load_in_recent_interval
@@ -487,6 +540,7 @@ pub mod test {
             Arc::new(StreamerStats::default()),
             DEFAULT_MAX_UNSTAKED_CONNECTIONS,
             DEFAULT_MAX_STREAMS_PER_MS,
+            0,
         ));
         stream_load_ema
             .load_in_recent_interval
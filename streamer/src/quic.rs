@@ -13,11 +13,13 @@ use {
     solana_keypair::Keypair,
     solana_packet::PACKET_DATA_SIZE,
     solana_perf::packet::PacketBatch,
+    solana_pubkey::Pubkey,
     solana_quic_definitions::{
         NotifyKeyUpdate, QUIC_MAX_TIMEOUT, QUIC_MAX_UNSTAKED_CONCURRENT_STREAMS,
     },
     solana_tls_utils::{new_dummy_x509_certificate, tls_server_config_builder},
     std::{
+        collections::HashMap,
         net::UdpSocket,
         num::NonZeroUsize,
         sync::{
@@ -45,6 +47,11 @@ pub const DEFAULT_MAX_STREAMS_PER_MS: u64 = 500;
 /// per IP address. Might be adjusted later.
 pub const DEFAULT_MAX_CONNECTIONS_PER_IPADDR_PER_MINUTE: u64 = 8;
 
+/// The streams per second from the unstaked peers of a particular IP
+/// prefix (/24 for IPv4, /56 for IPv6). A tenth of the streams allowed
+/// for all the unstaked peers with the default limits.
+pub const DEFAULT_MAX_UNSTAKED_STREAMS_PER_IP_PREFIX_PER_SEC: u64 = 10_000;
+
 // This will be adjusted and parameterized in follow-on PRs.
 pub const DEFAULT_QUIC_ENDPOINTS: usize = 1;
 
@@ -209,6 +216,12 @@ pub struct StreamerStats {
     pub(crate) throttled_staked_streams: AtomicUsize,
     pub(crate) throttled_unstaked_streams: AtomicUsize,
     pub(crate) connection_rate_limiter_length: AtomicUsize,
+    // Unstaked streams throttled because their IP prefix exceeded its share of
+    // the unstaked streams.
+    pub(crate) throttled_unstaked_streams_per_ip_prefix: AtomicUsize,
+    pub(crate) stream_prefix_rate_limiter_length: AtomicUsize,
+    // Streams of unstaked peers read within the quota reserved for their identity.
+    pub(crate) reserved_quota_streams: AtomicUsize,
     // All connections in various states such as Incoming, Connecting, Connection
     pub(crate) open_connections: AtomicUsize,
     pub(crate) refused_connections_too_many_open_connections: AtomicUsize,
@@ -513,6 +526,17 @@ impl StreamerStats {
                 self.throttled_staked_streams.swap(0, Ordering::Relaxed),
                 i64
             ),
+            (
+                "throttled_unstaked_streams_per_ip_prefix",
+                self.throttled_unstaked_streams_per_ip_prefix
+                    .swap(0, Ordering::Relaxed),
+                i64
+            ),
+            (
+                "reserved_quota_streams",
+                self.reserved_quota_streams.swap(0, Ordering::Relaxed),
+                i64
+            ),
             (
                 "process_sampled_packets_us_90pct",
                 process_sampled_packets_us_hist
@@ -550,6 +574,12 @@ impl StreamerStats {
                 self.connection_rate_limiter_length.load(Ordering::Relaxed),
                 i64
             ),
+            (
+                "stream_prefix_rate_limiter_length",
+                self.stream_prefix_rate_limiter_length
+                    .load(Ordering::Relaxed),
+                i64
+            ),
             (
                 "outstanding_incoming_connection_attempts",
                 self.outstanding_incoming_connection_attempts
@@ -611,6 +641,14 @@ pub struct QuicServerParams {
     pub max_unstaked_connections: usize,
     pub max_streams_per_ms: u64,
     pub max_connections_per_ipaddr_per_min: u64,
+    /// Streams per second the unstaked peers of an IP prefix may open, out of
+    /// the streams allowed for all the unstaked peers. 0 disables the limit.
+    pub max_unstaked_streams_per_ip_prefix_per_sec: u64,
+    /// Streams per second reserved for the identities of unstaked peers, e.g.
+    /// RPC partners, out of the streams allowed for all the unstaked peers. The
+    /// quota of an identity is shared by all its connections, whose streams are
+    /// not subject to the unstaked limits.
+    pub unstaked_stream_quotas: HashMap<Pubkey, u64>,
     pub wait_for_chunk_timeout: Duration,
     pub coalesce: Duration,
     pub coalesce_channel_size: usize,
@@ -625,6 +663,9 @@ impl Default for QuicServerParams {
             max_unstaked_connections: DEFAULT_MAX_UNSTAKED_CONNECTIONS,
             max_streams_per_ms: DEFAULT_MAX_STREAMS_PER_MS,
             max_connections_per_ipaddr_per_min: DEFAULT_MAX_CONNECTIONS_PER_IPADDR_PER_MINUTE,
+            max_unstaked_streams_per_ip_prefix_per_sec:
+                DEFAULT_MAX_UNSTAKED_STREAMS_PER_IP_PREFIX_PER_SEC,
+            unstaked_stream_quotas: HashMap::default(),
             wait_for_chunk_timeout: DEFAULT_WAIT_FOR_CHUNK_TIMEOUT,
             coalesce: DEFAULT_TPU_COALESCE,
             coalesce_channel_size: DEFAULT_MAX_COALESCE_CHANNEL_SIZE,
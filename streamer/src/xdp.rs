@@ -0,0 +1,279 @@
+//! The `xdp` module receives the packets sent to UDP sockets through AF_XDP, straight from the
+//! UMEM into `PacketBatch`es.
+//!
+//! The XDP receivers run next to the recvmmsg receivers of the sockets: the packets which
+//! aren't redirected to an AF_XDP socket, eg because XDP isn't available or the packet arrived
+//! on a receive queue without a socket, are still received through recvmmsg.
+
+use {
+    crate::{
+        packet::{PacketBatchRecycler, PinnedPacketBatch, PACKETS_PER_BATCH},
+        streamer::{ChannelSend, StreamerReceiveStats},
+    },
+    solana_packet::{Packet, PACKET_DATA_SIZE},
+    solana_perf::packet::PacketBatch,
+    std::{
+        net::{SocketAddr, SocketAddrV4, UdpSocket},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::JoinHandle,
+    },
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct XdpReceiverConfig {
+    /// The interface the packets are received on, the interface of the default route if unset
+    pub interface: Option<String>,
+    /// The CPU cores of the receive threads, one receive queue of the interface is bound per
+    /// core, in order
+    pub cpus: Vec<usize>,
+    pub zero_copy: bool,
+}
+
+impl XdpReceiverConfig {
+    pub fn new(interface: Option<impl Into<String>>, cpus: Vec<usize>, zero_copy: bool) -> Self {
+        Self {
+            interface: interface.map(|s| s.into()),
+            cpus,
+            zero_copy,
+        }
+    }
+}
+
+// Fills packet batches with the packets received from an XDP receive queue
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+struct PacketBatchFiller<S: ChannelSend<PacketBatch>> {
+    packet_batch: PinnedPacketBatch,
+    packet_batch_sender: S,
+    recycler: PacketBatchRecycler,
+    stats: Arc<StreamerReceiveStats>,
+    ports: Vec<u16>,
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+impl<S: ChannelSend<PacketBatch>> PacketBatchFiller<S> {
+    fn new(
+        packet_batch_sender: S,
+        recycler: PacketBatchRecycler,
+        stats: Arc<StreamerReceiveStats>,
+        ports: Vec<u16>,
+    ) -> Self {
+        Self {
+            packet_batch: Self::new_packet_batch(&recycler, stats.name),
+            packet_batch_sender,
+            recycler,
+            stats,
+            ports,
+        }
+    }
+
+    fn new_packet_batch(recycler: &PacketBatchRecycler, name: &'static str) -> PinnedPacketBatch {
+        PinnedPacketBatch::new_with_recycler(recycler, PACKETS_PER_BATCH, name)
+    }
+
+    fn push(&mut self, src: SocketAddrV4, dst_port: u16, payload: &[u8]) {
+        // the program redirects the packets sent to any of the ports it was loaded with
+        if payload.len() > PACKET_DATA_SIZE || !self.ports.contains(&dst_port) {
+            self.stats
+                .num_packets_dropped
+                .fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        let mut packet = Packet::default();
+        packet.buffer_mut()[..payload.len()].copy_from_slice(payload);
+        packet.meta_mut().size = payload.len();
+        packet.meta_mut().set_socket_addr(&SocketAddr::V4(src));
+        self.packet_batch.push(packet);
+        if self.packet_batch.len() == PACKETS_PER_BATCH {
+            self.send();
+        }
+    }
+
+    fn send(&mut self) {
+        let len = self.packet_batch.len();
+        if len == 0 {
+            return;
+        }
+        let StreamerReceiveStats {
+            packets_count,
+            packet_batches_count,
+            full_packet_batches_count,
+            max_channel_len,
+            num_packets_dropped,
+            ..
+        } = &*self.stats;
+        packets_count.fetch_add(len, Ordering::Relaxed);
+        packet_batches_count.fetch_add(1, Ordering::Relaxed);
+        max_channel_len.fetch_max(self.packet_batch_sender.len(), Ordering::Relaxed);
+        if len == PACKETS_PER_BATCH {
+            full_packet_batches_count.fetch_add(1, Ordering::Relaxed);
+        }
+        let packet_batch = std::mem::replace(
+            &mut self.packet_batch,
+            Self::new_packet_batch(&self.recycler, self.stats.name),
+        );
+        if let Err(crossbeam_channel::TrySendError::Full(_)) =
+            self.packet_batch_sender.try_send(packet_batch.into())
+        {
+            num_packets_dropped.fetch_add(len, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl<S: ChannelSend<PacketBatch>> agave_xdp::rx_loop::RxHandler for PacketBatchFiller<S> {
+    fn handle_packet(&mut self, src: SocketAddrV4, dst_port: u16, payload: &[u8]) {
+        self.push(src, dst_port, payload);
+    }
+
+    fn flush(&mut self) {
+        self.send();
+    }
+}
+
+/// Spawns the threads receiving the packets sent to `sockets` through XDP. Returns an error if
+/// XDP isn't available, in which case the packets keep being received through recvmmsg.
+#[cfg(not(target_os = "linux"))]
+pub fn spawn_xdp_receivers(
+    _thread_name: &'static str,
+    _config: &XdpReceiverConfig,
+    _sockets: &[Arc<UdpSocket>],
+    _exit: Arc<AtomicBool>,
+    _packet_batch_sender: impl ChannelSend<PacketBatch> + Clone,
+    _recycler: PacketBatchRecycler,
+    _stats: Arc<StreamerReceiveStats>,
+) -> Result<Vec<JoinHandle<()>>, Box<dyn std::error::Error>> {
+    Err("XDP is only supported on Linux".into())
+}
+
+/// Spawns the threads receiving the packets sent to `sockets` through XDP. Returns an error if
+/// XDP isn't available, in which case the packets keep being received through recvmmsg.
+#[cfg(target_os = "linux")]
+pub fn spawn_xdp_receivers(
+    thread_name: &'static str,
+    config: &XdpReceiverConfig,
+    sockets: &[Arc<UdpSocket>],
+    exit: Arc<AtomicBool>,
+    packet_batch_sender: impl ChannelSend<PacketBatch> + Clone,
+    recycler: PacketBatchRecycler,
+    stats: Arc<StreamerReceiveStats>,
+) -> Result<Vec<JoinHandle<()>>, Box<dyn std::error::Error>> {
+    use {
+        agave_xdp::{
+            device::{NetworkDevice, QueueId},
+            load_xdp_rx_program,
+            map::XskMap,
+            rx_loop::rx_loop,
+        },
+        caps::{
+            CapSet,
+            Capability::{CAP_BPF, CAP_NET_ADMIN, CAP_NET_RAW},
+        },
+        std::{sync::Mutex, thread::Builder},
+    };
+
+    if config.cpus.is_empty() {
+        return Err("no CPU cores configured for the XDP receivers".into());
+    }
+    let mut ports = sockets
+        .iter()
+        .map(|socket| socket.local_addr().map(|addr| addr.port()))
+        .collect::<Result<Vec<_>, _>>()?;
+    ports.sort_unstable();
+    ports.dedup();
+
+    let dev = Arc::new(match &config.interface {
+        Some(interface) => NetworkDevice::new(interface.clone())?,
+        None => NetworkDevice::new_from_default_route()?,
+    });
+
+    // switch to higher caps while we setup XDP
+    for cap in [CAP_NET_ADMIN, CAP_NET_RAW, CAP_BPF] {
+        caps::raise(None, CapSet::Effective, cap)
+            .map_err(|e| format!("failed to raise {cap:?} capability: {e}"))?;
+    }
+    let program = XskMap::new(config.cpus.len() as u32)
+        .map_err(|e| format!("failed to create xsk map: {e}"))
+        .and_then(|xsk_map| {
+            let ebpf = load_xdp_rx_program(dev.if_index(), &xsk_map, &ports)
+                .map_err(|e| format!("failed to attach xdp program: {e}"))?;
+            Ok(XdpRxProgram {
+                xsk_map,
+                _ebpf: Mutex::new(ebpf),
+            })
+        });
+    for cap in [CAP_NET_ADMIN, CAP_NET_RAW, CAP_BPF] {
+        caps::drop(None, CapSet::Effective, cap)
+            .map_err(|e| format!("failed to drop {cap:?} capability: {e}"))?;
+    }
+    // the program stays attached until all the receivers exit
+    let program = Arc::new(program?);
+
+    let (ready_sender, ready_receiver) = crossbeam_channel::bounded(config.cpus.len());
+    let threads = config
+        .cpus
+        .iter()
+        .enumerate()
+        .map(|(i, &cpu_id)| {
+            let dev = dev.clone();
+            let exit = exit.clone();
+            let program = program.clone();
+            let ready_sender = ready_sender.clone();
+            let zero_copy = config.zero_copy;
+            let mut filler = PacketBatchFiller::new(
+                packet_batch_sender.clone(),
+                recycler.clone(),
+                stats.clone(),
+                ports.clone(),
+            );
+            Builder::new()
+                .name(format!("{thread_name}{i:02}"))
+                .spawn(move || {
+                    let queue_id = QueueId(i as u64);
+                    let mut ready_sender = Some(ready_sender);
+                    let result = rx_loop(
+                        &dev,
+                        queue_id,
+                        zero_copy,
+                        Some(cpu_id),
+                        &program.xsk_map,
+                        &exit,
+                        || {
+                            if let Some(sender) = ready_sender.take() {
+                                let _ = sender.send(Ok(()));
+                            }
+                        },
+                        &mut filler,
+                    );
+                    if let Err(e) = result {
+                        match ready_sender.take() {
+                            Some(sender) => {
+                                let _ = sender.send(Err(format!("queue {queue_id:?}: {e}")));
+                            }
+                            None => error!("xdp receiver of queue {queue_id:?} failed: {e}"),
+                        }
+                    }
+                })
+                .unwrap()
+        })
+        .collect();
+    drop(ready_sender);
+
+    // wait for every receiver to be set up, the packets of the queues which failed are still
+    // received through recvmmsg
+    for result in ready_receiver.iter() {
+        if let Err(e) = result {
+            warn!("failed to start xdp receiver on {}: {e}", dev.name());
+        }
+    }
+    Ok(threads)
+}
+
+// The program redirecting the packets to the AF_XDP sockets, it's detached on drop
+#[cfg(target_os = "linux")]
+struct XdpRxProgram {
+    xsk_map: agave_xdp::map::XskMap,
+    _ebpf: std::sync::Mutex<aya::Ebpf>,
+}
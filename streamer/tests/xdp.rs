@@ -0,0 +1,89 @@
+#![cfg(target_os = "linux")]
+
+use {
+    solana_perf::packet::PacketBatchRecycler,
+    solana_streamer::{
+        streamer::StreamerReceiveStats,
+        xdp::{spawn_xdp_receivers, XdpReceiverConfig},
+    },
+    std::{
+        net::{IpAddr, UdpSocket},
+        process::Command,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    },
+};
+
+// Receives packets sent over a veth pair through XDP. Requires root, see ci/test-xdp-veth.sh
+// for the setup of the veth pair:
+//  * AGAVE_XDP_TEST_INTERFACE: the veth interface the packets are received on
+//  * AGAVE_XDP_TEST_ADDR: the address of AGAVE_XDP_TEST_INTERFACE
+//  * AGAVE_XDP_TEST_PEER_NETNS: the network namespace of the peer interface
+#[test]
+#[ignore]
+fn test_xdp_receive_veth() {
+    solana_logger::setup();
+    let interface = std::env::var("AGAVE_XDP_TEST_INTERFACE").unwrap();
+    let addr: IpAddr = std::env::var("AGAVE_XDP_TEST_ADDR")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let peer_netns = std::env::var("AGAVE_XDP_TEST_PEER_NETNS").unwrap();
+
+    let socket = Arc::new(UdpSocket::bind((addr, 0)).unwrap());
+    let port = socket.local_addr().unwrap().port();
+    let exit = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    let stats = Arc::new(StreamerReceiveStats::new("xdp_test_receiver"));
+    let threads = spawn_xdp_receivers(
+        "solXdpTest",
+        &XdpReceiverConfig::new(Some(interface), vec![0], false),
+        &[socket.clone()],
+        exit.clone(),
+        sender,
+        PacketBatchRecycler::default(),
+        stats.clone(),
+    )
+    .unwrap();
+
+    const NUM_PACKETS: usize = 10;
+    for i in 0..NUM_PACKETS {
+        let status = Command::new("ip")
+            .args(["netns", "exec", &peer_netns, "bash", "-c"])
+            .arg(format!("echo -n xdp-test-{i} > /dev/udp/{addr}/{port}"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    let mut received = vec![];
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while received.len() < NUM_PACKETS && Instant::now() < deadline {
+        let Ok(batch) = receiver.recv_timeout(Duration::from_millis(100)) else {
+            continue;
+        };
+        for packet in batch.iter() {
+            assert_ne!(packet.meta().addr, addr);
+            received.push(packet.data(..).unwrap().to_vec());
+        }
+    }
+    received.sort();
+    let mut expected: Vec<_> = (0..NUM_PACKETS)
+        .map(|i| format!("xdp-test-{i}").into_bytes())
+        .collect();
+    expected.sort();
+    assert_eq!(received, expected);
+    assert_eq!(stats.packets_count.load(Ordering::Relaxed), NUM_PACKETS);
+
+    // the packets were redirected before reaching the socket
+    socket.set_nonblocking(true).unwrap();
+    assert!(socket.recv(&mut [0; 64]).is_err());
+
+    exit.store(true, Ordering::Relaxed);
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
@@ -37,6 +37,10 @@ pub struct SVMFeatureSet {
     pub formalize_loaded_transaction_data_size: bool,
     pub disable_zk_elgamal_proof_program: bool,
     pub reenable_zk_elgamal_proof_program: bool,
+    pub enable_stake_redelegate_instruction: bool,
+    pub enable_vote_commission_schedule: bool,
+    pub enable_zk_proof_batch_verification: bool,
+    pub record_loader_v4_elf_hash: bool,
 }
 
 impl SVMFeatureSet {
@@ -79,6 +83,10 @@ impl SVMFeatureSet {
             formalize_loaded_transaction_data_size: true,
             disable_zk_elgamal_proof_program: true,
             reenable_zk_elgamal_proof_program: true,
+            enable_stake_redelegate_instruction: true,
+            enable_vote_commission_schedule: true,
+            enable_zk_proof_batch_verification: true,
+            record_loader_v4_elf_hash: true,
         }
     }
 }
@@ -0,0 +1,63 @@
+//! Account storage used by the rollup engine.
+
+use {
+    solana_account::AccountSharedData,
+    solana_pubkey::Pubkey,
+    std::collections::{hash_map, HashMap},
+};
+
+/// Storage backend for rollup accounts.
+///
+/// The engine reads every account a transaction references through
+/// `get_account` and writes back the accounts modified by each committed
+/// transaction through `store_account`. Implementations are free to lazily
+/// hoist accounts from elsewhere, e.g. from the base chain, on first access.
+pub trait AccountStore {
+    /// Returns the current state of `pubkey`, or `None` if it doesn't exist.
+    fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData>;
+
+    /// Overwrites the state of `pubkey`.
+    fn store_account(&mut self, pubkey: &Pubkey, account: &AccountSharedData);
+}
+
+/// A simple `AccountStore` backed by a `HashMap`.
+#[derive(Debug, Default, Clone)]
+pub struct InMemoryAccountStore {
+    accounts: HashMap<Pubkey, AccountSharedData>,
+}
+
+impl InMemoryAccountStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, Pubkey, AccountSharedData> {
+        self.accounts.iter()
+    }
+}
+
+impl FromIterator<(Pubkey, AccountSharedData)> for InMemoryAccountStore {
+    fn from_iter<I: IntoIterator<Item = (Pubkey, AccountSharedData)>>(iter: I) -> Self {
+        Self {
+            accounts: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl AccountStore for InMemoryAccountStore {
+    fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.accounts.get(pubkey).cloned()
+    }
+
+    fn store_account(&mut self, pubkey: &Pubkey, account: &AccountSharedData) {
+        self.accounts.insert(*pubkey, account.clone());
+    }
+}
@@ -0,0 +1,147 @@
+//! State commitment for the rollup, based on the lattice hash.
+//!
+//! Each account is hashed exactly like the accounts lt hash maintained by
+//! AccountsDb, so a rollup commitment over a set of accounts is directly
+//! comparable to one computed by a validator over the same accounts.
+
+use {
+    solana_account::ReadableAccount,
+    solana_lattice_hash::lt_hash::{Checksum, LtHash},
+    solana_pubkey::Pubkey,
+    std::collections::HashSet,
+};
+
+/// Returns the lattice hash of a single account.
+///
+/// Zero-lamport accounts don't exist, so they hash to the identity.
+pub fn account_lt_hash(pubkey: &Pubkey, account: &impl ReadableAccount) -> LtHash {
+    if account.lamports() == 0 {
+        return LtHash::identity();
+    }
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&account.lamports().to_le_bytes());
+    hasher.update(account.data());
+    hasher.update(&[u8::from(account.executable())]);
+    hasher.update(account.owner().as_ref());
+    hasher.update(pubkey.as_ref());
+    LtHash::with(&hasher)
+}
+
+/// An incrementally maintained commitment to the rollup state.
+///
+/// The commitment covers every account the engine has loaded or written,
+/// at its latest state. Updating an account mixes out its previous hash and
+/// mixes in the new one, so the cost of an update doesn't depend on the size
+/// of the state.
+#[derive(Debug, Clone)]
+pub struct StateCommitment {
+    lt_hash: LtHash,
+    accounts: HashSet<Pubkey>,
+}
+
+impl Default for StateCommitment {
+    fn default() -> Self {
+        Self {
+            lt_hash: LtHash::identity(),
+            accounts: HashSet::default(),
+        }
+    }
+}
+
+impl StateCommitment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `pubkey` to the committed state, if it isn't already part of it.
+    pub fn observe(&mut self, pubkey: &Pubkey, account: Option<&impl ReadableAccount>) {
+        if !self.accounts.insert(*pubkey) {
+            return;
+        }
+        if let Some(account) = account {
+            self.lt_hash.mix_in(&account_lt_hash(pubkey, account));
+        }
+    }
+
+    /// Replaces the committed state of `pubkey`.
+    ///
+    /// `old` must be the state the account had when it was last observed or
+    /// updated.
+    pub fn update(
+        &mut self,
+        pubkey: &Pubkey,
+        old: Option<&impl ReadableAccount>,
+        new: &impl ReadableAccount,
+    ) {
+        if !self.accounts.insert(*pubkey) {
+            if let Some(old) = old {
+                self.lt_hash.mix_out(&account_lt_hash(pubkey, old));
+            }
+        }
+        self.lt_hash.mix_in(&account_lt_hash(pubkey, new));
+    }
+
+    /// Returns the number of accounts covered by the commitment.
+    pub fn num_accounts(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn lt_hash(&self) -> &LtHash {
+        &self.lt_hash
+    }
+
+    pub fn checksum(&self) -> Checksum {
+        self.lt_hash.checksum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_account::AccountSharedData};
+
+    #[test]
+    fn test_update_matches_recomputation() {
+        let pubkeys: Vec<_> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let owner = Pubkey::new_unique();
+        let mut commitment = StateCommitment::new();
+
+        for (i, pubkey) in pubkeys.iter().enumerate() {
+            let account = AccountSharedData::new(i as u64 + 1, 0, &owner);
+            commitment.observe(pubkey, Some(&account));
+        }
+        let new_account = AccountSharedData::new(42, 8, &owner);
+        commitment.update(
+            &pubkeys[0],
+            Some(&AccountSharedData::new(1, 0, &owner)),
+            &new_account,
+        );
+        // Observing an account that is already committed is a no-op
+        commitment.observe(&pubkeys[1], Some(&new_account));
+
+        let mut expected = LtHash::identity();
+        expected.mix_in(&account_lt_hash(&pubkeys[0], &new_account));
+        for (i, pubkey) in pubkeys.iter().enumerate().skip(1) {
+            let account = AccountSharedData::new(i as u64 + 1, 0, &owner);
+            expected.mix_in(&account_lt_hash(pubkey, &account));
+        }
+        assert_eq!(commitment.lt_hash(), &expected);
+        assert_eq!(commitment.num_accounts(), pubkeys.len());
+    }
+
+    #[test]
+    fn test_zero_lamport_account_is_identity() {
+        let pubkey = Pubkey::new_unique();
+        let account = AccountSharedData::new(0, 100, &Pubkey::new_unique());
+        assert_eq!(account_lt_hash(&pubkey, &account), LtHash::identity());
+
+        let mut commitment = StateCommitment::new();
+        commitment.observe(&pubkey, Some(&AccountSharedData::new(1, 0, &pubkey)));
+        commitment.update(
+            &pubkey,
+            Some(&AccountSharedData::new(1, 0, &pubkey)),
+            &account,
+        );
+        assert_eq!(commitment.lt_hash(), &LtHash::identity());
+    }
+}
@@ -0,0 +1,281 @@
+//! The rollup engine, executing batches of transactions on top of an
+//! `AccountStore` with the SVM's `TransactionBatchProcessor`.
+
+use {
+    crate::{account_store::AccountStore, commitment::StateCommitment},
+    solana_account::{Account, AccountSharedData, ReadableAccount},
+    solana_bpf_loader_program::syscalls::create_program_runtime_environment_v1,
+    solana_clock::Slot,
+    solana_compute_budget::compute_budget_limits::ComputeBudgetLimits,
+    solana_fee_structure::{FeeDetails, FeeStructure},
+    solana_hash::Hash,
+    solana_lattice_hash::lt_hash::Checksum,
+    solana_program_runtime::{
+        execution_budget::SVMTransactionExecutionBudget,
+        invoke_context::BuiltinFunctionWithContext,
+        loaded_programs::{BlockRelation, ForkGraph, ProgramCacheEntry},
+    },
+    solana_pubkey::Pubkey,
+    solana_rent_collector::RentCollector,
+    solana_svm::{
+        account_loader::{CheckedTransactionDetails, TransactionCheckResult},
+        transaction_processing_result::{ProcessedTransaction, TransactionProcessingResult},
+        transaction_processor::{
+            TransactionBatchProcessor, TransactionProcessingConfig,
+            TransactionProcessingEnvironment,
+        },
+    },
+    solana_svm_callback::{InvokeContextCallback, TransactionProcessingCallback},
+    solana_svm_feature_set::SVMFeatureSet,
+    solana_svm_transaction::svm_transaction::SVMTransaction,
+    solana_system_program::system_processor,
+    solana_transaction_error::TransactionResult,
+    std::sync::{Arc, Mutex, RwLock},
+};
+
+/// The rollup doesn't have slots or forks, so every relationship is unknown.
+pub struct RollupForkGraph {}
+
+impl ForkGraph for RollupForkGraph {
+    fn relationship(&self, _a: Slot, _b: Slot) -> BlockRelation {
+        BlockRelation::Unknown
+    }
+}
+
+/// The outcome of executing a batch of transactions.
+#[derive(Debug)]
+pub struct BatchOutput {
+    /// The status of each transaction, in batch order.
+    pub statuses: Vec<TransactionResult<()>>,
+    /// The state commitment after the batch was committed.
+    pub checksum: Checksum,
+}
+
+impl BatchOutput {
+    pub fn num_successful(&self) -> usize {
+        self.statuses.iter().filter(|status| status.is_ok()).count()
+    }
+}
+
+/// Adapts an `AccountStore` to the callback interface of the SVM, recording
+/// every account loaded into the state commitment.
+struct StoreCallback<'a, S> {
+    store: &'a S,
+    commitment: &'a Mutex<StateCommitment>,
+}
+
+impl<S: AccountStore> InvokeContextCallback for StoreCallback<'_, S> {}
+
+impl<S: AccountStore> TransactionProcessingCallback for StoreCallback<'_, S> {
+    fn account_matches_owners(&self, account: &Pubkey, owners: &[Pubkey]) -> Option<usize> {
+        self.get_account_shared_data(account)
+            .and_then(|account| owners.iter().position(|key| account.owner() == key))
+    }
+
+    fn get_account_shared_data(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        let account = self.store.get_account(pubkey);
+        self.commitment
+            .lock()
+            .unwrap()
+            .observe(pubkey, account.as_ref());
+        account
+    }
+}
+
+/// A minimal SVM rollup.
+///
+/// Transactions are executed against the accounts in `S`, and the accounts
+/// modified by each processed transaction are written back once the batch
+/// completes. No fees are charged and signatures are not verified; both are
+/// expected to be enforced by whoever submits batches to the engine.
+pub struct RollupEngine<S: AccountStore> {
+    store: S,
+    commitment: Mutex<StateCommitment>,
+    processor: TransactionBatchProcessor<RollupForkGraph>,
+    feature_set: SVMFeatureSet,
+    rent_collector: RentCollector,
+    // The processor only holds a weak reference to the fork graph
+    _fork_graph: Arc<RwLock<RollupForkGraph>>,
+}
+
+impl<S: AccountStore> RollupEngine<S> {
+    pub fn new(store: S) -> Self {
+        let feature_set = SVMFeatureSet::all_enabled();
+        let compute_budget = SVMTransactionExecutionBudget::default();
+        let fork_graph = Arc::new(RwLock::new(RollupForkGraph {}));
+
+        // Programs deployed in slot 0 only become visible in slot 1, which
+        // includes programs owned by BPF Loader v2.
+        let processor = TransactionBatchProcessor::new(
+            /* slot */ 1,
+            /* epoch */ 1,
+            Arc::downgrade(&fork_graph),
+            Some(Arc::new(
+                create_program_runtime_environment_v1(&feature_set, &compute_budget, false, false)
+                    .unwrap(),
+            )),
+            None,
+        );
+
+        let mut engine = Self {
+            store,
+            commitment: Mutex::default(),
+            processor,
+            feature_set,
+            rent_collector: RentCollector::default(),
+            _fork_graph: fork_graph,
+        };
+        engine.add_builtin(
+            solana_system_program::id(),
+            "system_program",
+            system_processor::Entrypoint::vm,
+        );
+        engine.add_builtin(
+            solana_sdk_ids::bpf_loader::id(),
+            "solana_bpf_loader_program",
+            solana_bpf_loader_program::Entrypoint::vm,
+        );
+        engine
+    }
+
+    fn add_builtin(
+        &mut self,
+        program_id: Pubkey,
+        name: &str,
+        entrypoint: BuiltinFunctionWithContext,
+    ) {
+        if self.store.get_account(&program_id).is_none() {
+            // A placeholder executable account, like the one a bank creates
+            // for its builtins. It is loaded and then ignored.
+            let account = AccountSharedData::from(Account {
+                lamports: 1,
+                data: name.as_bytes().to_vec(),
+                owner: solana_sdk_ids::native_loader::id(),
+                executable: true,
+                rent_epoch: 0,
+            });
+            self.store_account(&program_id, &account);
+        }
+        let callback = StoreCallback {
+            store: &self.store,
+            commitment: &self.commitment,
+        };
+        self.processor.add_builtin(
+            &callback,
+            program_id,
+            name,
+            ProgramCacheEntry::new_builtin(0, name.len(), entrypoint),
+        );
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    /// Returns the current state commitment.
+    pub fn commitment(&self) -> StateCommitment {
+        self.commitment.lock().unwrap().clone()
+    }
+
+    pub fn checksum(&self) -> Checksum {
+        self.commitment.lock().unwrap().checksum()
+    }
+
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.store.get_account(pubkey)
+    }
+
+    /// Writes `account` to the store and updates the state commitment.
+    pub fn store_account(&mut self, pubkey: &Pubkey, account: &AccountSharedData) {
+        let old = self.store.get_account(pubkey);
+        self.commitment
+            .get_mut()
+            .unwrap()
+            .update(pubkey, old.as_ref(), account);
+        self.store.store_account(pubkey, account);
+    }
+
+    /// Executes `transactions` in order and commits the results.
+    ///
+    /// Later transactions in the batch observe the writes of earlier ones.
+    /// Transactions that fail leave the state untouched.
+    pub fn execute_batch(&mut self, transactions: &[impl SVMTransaction]) -> BatchOutput {
+        let processing_environment = TransactionProcessingEnvironment {
+            blockhash: Hash::default(),
+            blockhash_lamports_per_signature: FeeStructure::default().lamports_per_signature,
+            epoch_total_stake: 0,
+            feature_set: self.feature_set,
+            rent_collector: Some(&self.rent_collector),
+        };
+        let callback = StoreCallback {
+            store: &self.store,
+            commitment: &self.commitment,
+        };
+        let output = self.processor.load_and_execute_sanitized_transactions(
+            &callback,
+            transactions,
+            check_results(transactions.len()),
+            &processing_environment,
+            &TransactionProcessingConfig::default(),
+        );
+
+        let statuses = transactions
+            .iter()
+            .zip(output.processing_results)
+            .map(|(transaction, processing_result)| {
+                self.commit_transaction(transaction, processing_result)
+            })
+            .collect();
+
+        BatchOutput {
+            statuses,
+            checksum: self.checksum(),
+        }
+    }
+
+    fn commit_transaction(
+        &mut self,
+        transaction: &impl SVMTransaction,
+        processing_result: TransactionProcessingResult,
+    ) -> TransactionResult<()> {
+        let executed_tx = match processing_result? {
+            ProcessedTransaction::Executed(executed_tx) => executed_tx,
+            // Fees are always zero, so there's nothing to roll back
+            ProcessedTransaction::FeesOnly(fees_only_tx) => return Err(fees_only_tx.load_error),
+        };
+        executed_tx.execution_details.status.clone()?;
+
+        for (i, (pubkey, account)) in executed_tx.loaded_transaction.accounts.iter().enumerate() {
+            if !transaction.is_writable(i) {
+                continue;
+            }
+            // Invoked accounts which aren't passed to any program can't be
+            // modified by the transaction.
+            if transaction.is_invoked(i) && !transaction.is_instruction_account(i) {
+                continue;
+            }
+            self.store_account(pubkey, account);
+        }
+        Ok(())
+    }
+}
+
+/// The engine doesn't pre-check transactions the way a bank does, so every
+/// transaction is considered valid with the default compute budget.
+fn check_results(len: usize) -> Vec<TransactionCheckResult> {
+    let compute_budget_limits = ComputeBudgetLimits::default();
+    vec![
+        Ok(CheckedTransactionDetails::new(
+            None,
+            Ok(compute_budget_limits.get_compute_budget_and_limits(
+                compute_budget_limits.loaded_accounts_bytes,
+                FeeDetails::default(),
+            )),
+        ));
+        len
+    ]
+}
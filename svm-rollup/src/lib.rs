@@ -0,0 +1,28 @@
+//! A minimal SVM rollup engine.
+//!
+//! Transactions are executed off-chain in batches with the SVM's
+//! `TransactionBatchProcessor`, against accounts held in an `AccountStore`.
+//! The engine maintains a lattice hash commitment to the state it has touched,
+//! and the `Settler` turns the net state changes of a set of tracked accounts
+//! into the transfers needed to settle them on the base chain.
+//!
+//! ```text
+//!   base chain --(hoist accounts)--> AccountStore
+//!                                        |
+//!   transactions ---> RollupEngine::execute_batch ---> StateCommitment
+//!                                        |
+//!   base chain <--(net transfers)--- Settler
+//! ```
+#![allow(clippy::arithmetic_side_effects)]
+
+pub mod account_store;
+pub mod commitment;
+pub mod engine;
+pub mod settler;
+
+pub use {
+    account_store::{AccountStore, InMemoryAccountStore},
+    commitment::StateCommitment,
+    engine::{BatchOutput, RollupEngine},
+    settler::{Asset, SettlementError, Settler, Transfer},
+};
@@ -0,0 +1,307 @@
+//! Settlement of rollup state to the base chain.
+//!
+//! The settler snapshots a set of tracked accounts when the rollup opens, and
+//! later compares the snapshot with the accounts' final state. Only the net
+//! change of each account matters: however many transfers happened inside the
+//! rollup, at most `n - 1` base chain transfers are needed to settle `n`
+//! accounts holding the same asset.
+
+use {
+    crate::account_store::AccountStore,
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_instruction::Instruction,
+    solana_program_pack::Pack,
+    solana_pubkey::Pubkey,
+    solana_system_interface::instruction as system_instruction,
+    std::collections::BTreeMap,
+    thiserror::Error,
+};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SettlementError {
+    #[error("token account {0} is missing or invalid")]
+    InvalidTokenAccount(Pubkey),
+
+    #[error("net changes of {asset} do not sum to zero (off by {imbalance})")]
+    Unbalanced { asset: Asset, imbalance: i128 },
+}
+
+/// An asset settled by the settler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Asset {
+    /// Native lamports held by a system account.
+    Native,
+    /// SPL tokens of the given mint.
+    Token(Pubkey),
+}
+
+impl std::fmt::Display for Asset {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Asset::Native => write!(f, "native lamports"),
+            Asset::Token(mint) => write!(f, "token {mint}"),
+        }
+    }
+}
+
+/// A single net transfer on the base chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    pub asset: Asset,
+    /// The source account, a system account or a token account.
+    pub from: Pubkey,
+    /// The destination account, a system account or a token account.
+    pub to: Pubkey,
+    /// The account that must sign the transfer.
+    pub authority: Pubkey,
+    pub amount: u64,
+}
+
+impl Transfer {
+    pub fn to_instruction(&self) -> Instruction {
+        match self.asset {
+            Asset::Native => system_instruction::transfer(&self.from, &self.to, self.amount),
+            Asset::Token(_) => spl_token::instruction::transfer(
+                &spl_token::id(),
+                &self.from,
+                &self.to,
+                &self.authority,
+                &[],
+                self.amount,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+struct Balance {
+    asset: Asset,
+    authority: Pubkey,
+    amount: u64,
+}
+
+/// Returns the settleable balance held by `account`.
+///
+/// Accounts owned by the token program are settled in tokens, every other
+/// account is settled in lamports.
+fn balance_of(
+    pubkey: &Pubkey,
+    account: Option<&AccountSharedData>,
+) -> Result<Balance, SettlementError> {
+    match account {
+        Some(account) if account.owner() == &spl_token::id() => {
+            let token_account = spl_token::state::Account::unpack(account.data())
+                .map_err(|_| SettlementError::InvalidTokenAccount(*pubkey))?;
+            Ok(Balance {
+                asset: Asset::Token(token_account.mint),
+                authority: token_account.owner,
+                amount: token_account.amount,
+            })
+        }
+        account => Ok(Balance {
+            asset: Asset::Native,
+            authority: *pubkey,
+            amount: account
+                .map(|account| account.lamports())
+                .unwrap_or_default(),
+        }),
+    }
+}
+
+/// Computes the net base chain transfers for a set of tracked accounts.
+pub struct Settler {
+    opening: Vec<(Pubkey, Option<AccountSharedData>)>,
+}
+
+impl Settler {
+    /// Snapshots `accounts` in `store`, typically right before the rollup
+    /// starts executing transactions.
+    pub fn open(store: &impl AccountStore, accounts: &[Pubkey]) -> Self {
+        let opening = accounts
+            .iter()
+            .map(|pubkey| (*pubkey, store.get_account(pubkey)))
+            .collect();
+        Self { opening }
+    }
+
+    /// Returns the transfers which move the base chain from the opening
+    /// snapshot to the current state of `store`.
+    ///
+    /// The changes of every asset must sum to zero across the tracked
+    /// accounts, i.e. value may move between tracked accounts but must not
+    /// enter or leave the tracked set.
+    pub fn transfers(&self, store: &impl AccountStore) -> Result<Vec<Transfer>, SettlementError> {
+        // Keyed by asset, then by account, so the output is deterministic
+        let mut deltas: BTreeMap<Asset, BTreeMap<Pubkey, (Pubkey, i128)>> = BTreeMap::new();
+
+        for (pubkey, opening) in &self.opening {
+            let opening = balance_of(pubkey, opening.as_ref())?;
+            let closing = balance_of(pubkey, store.get_account(pubkey).as_ref())?;
+            if opening.asset != closing.asset {
+                return Err(SettlementError::InvalidTokenAccount(*pubkey));
+            }
+            let delta = i128::from(closing.amount) - i128::from(opening.amount);
+            if delta != 0 {
+                deltas
+                    .entry(opening.asset)
+                    .or_default()
+                    .insert(*pubkey, (opening.authority, delta));
+            }
+        }
+
+        let mut transfers = vec![];
+        for (asset, deltas) in deltas {
+            let imbalance: i128 = deltas.values().map(|(_, delta)| delta).sum();
+            if imbalance != 0 {
+                return Err(SettlementError::Unbalanced { asset, imbalance });
+            }
+
+            let mut debtors = deltas
+                .iter()
+                .filter(|(_, (_, delta))| *delta < 0)
+                .map(|(pubkey, (authority, delta))| (*pubkey, *authority, -delta))
+                .collect::<Vec<_>>();
+            let mut creditors = deltas
+                .iter()
+                .filter(|(_, (_, delta))| *delta > 0)
+                .map(|(pubkey, (_, delta))| (*pubkey, *delta))
+                .collect::<Vec<_>>();
+
+            // Each step settles at least one side completely, so this emits
+            // fewer transfers than there are accounts
+            let (mut d, mut c) = (0, 0);
+            while d < debtors.len() && c < creditors.len() {
+                let amount = debtors[d].2.min(creditors[c].1);
+                transfers.push(Transfer {
+                    asset,
+                    from: debtors[d].0,
+                    to: creditors[c].0,
+                    authority: debtors[d].1,
+                    amount: u64::try_from(amount).unwrap(),
+                });
+                debtors[d].2 -= amount;
+                creditors[c].1 -= amount;
+                if debtors[d].2 == 0 {
+                    d += 1;
+                }
+                if creditors[c].1 == 0 {
+                    c += 1;
+                }
+            }
+        }
+        Ok(transfers)
+    }
+
+    /// Returns the base chain instructions settling the current state of
+    /// `store`. See `transfers`.
+    pub fn instructions(
+        &self,
+        store: &impl AccountStore,
+    ) -> Result<Vec<Instruction>, SettlementError> {
+        self.transfers(store).map(|transfers| {
+            transfers
+                .iter()
+                .map(Transfer::to_instruction)
+                .collect::<Vec<_>>()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::account_store::InMemoryAccountStore, solana_account::WritableAccount,
+        spl_token::state::AccountState, std::collections::HashMap,
+    };
+
+    fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> AccountSharedData {
+        let mut account =
+            AccountSharedData::new(1, spl_token::state::Account::LEN, &spl_token::id());
+        spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: AccountState::Initialized,
+            ..spl_token::state::Account::default()
+        }
+        .pack_into_slice(account.data_as_mut_slice());
+        account
+    }
+
+    #[test]
+    fn test_native_transfers_are_netted() {
+        let system = solana_system_interface::program::id();
+        let keys: Vec<_> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let mut store: InMemoryAccountStore = keys
+            .iter()
+            .map(|key| (*key, AccountSharedData::new(100, 0, &system)))
+            .collect();
+        let settler = Settler::open(&store, &keys);
+
+        for (key, lamports) in keys.iter().zip([70, 130, 90, 110]) {
+            store.store_account(key, &AccountSharedData::new(lamports, 0, &system));
+        }
+        let transfers = settler.transfers(&store).unwrap();
+        assert!(transfers.len() < keys.len());
+
+        let mut balances: HashMap<_, i128> = keys.iter().map(|key| (*key, 100)).collect();
+        for transfer in &transfers {
+            assert_eq!(transfer.asset, Asset::Native);
+            assert_eq!(transfer.authority, transfer.from);
+            *balances.get_mut(&transfer.from).unwrap() -= i128::from(transfer.amount);
+            *balances.get_mut(&transfer.to).unwrap() += i128::from(transfer.amount);
+        }
+        for key in &keys {
+            assert_eq!(
+                balances[key],
+                i128::from(store.get_account(key).unwrap().lamports())
+            );
+        }
+    }
+
+    #[test]
+    fn test_token_transfers() {
+        let mint = Pubkey::new_unique();
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (alice_token, bob_token) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut store: InMemoryAccountStore = [
+            (alice_token, token_account(&mint, &alice, 50)),
+            (bob_token, token_account(&mint, &bob, 0)),
+        ]
+        .into_iter()
+        .collect();
+        let settler = Settler::open(&store, &[alice_token, bob_token]);
+
+        store.store_account(&alice_token, &token_account(&mint, &alice, 20));
+        store.store_account(&bob_token, &token_account(&mint, &bob, 30));
+        assert_eq!(
+            settler.transfers(&store).unwrap(),
+            vec![Transfer {
+                asset: Asset::Token(mint),
+                from: alice_token,
+                to: bob_token,
+                authority: alice,
+                amount: 30,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unbalanced() {
+        let system = solana_system_interface::program::id();
+        let key = Pubkey::new_unique();
+        let mut store: InMemoryAccountStore = [(key, AccountSharedData::new(100, 0, &system))]
+            .into_iter()
+            .collect();
+        let settler = Settler::open(&store, &[key]);
+
+        store.store_account(&key, &AccountSharedData::new(60, 0, &system));
+        assert_eq!(
+            settler.transfers(&store),
+            Err(SettlementError::Unbalanced {
+                asset: Asset::Native,
+                imbalance: -40,
+            })
+        );
+    }
+}
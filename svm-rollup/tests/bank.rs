@@ -0,0 +1,178 @@
+#![allow(clippy::arithmetic_side_effects)]
+
+use {
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_accounts_db::accounts_db::AccountsDb,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_runtime::{
+        bank::Bank,
+        bank_forks::BankForks,
+        genesis_utils::{create_genesis_config, GenesisConfigInfo},
+    },
+    solana_signer::Signer,
+    solana_svm_rollup::{commitment::account_lt_hash, AccountStore, RollupEngine, Settler},
+    solana_system_interface::instruction as system_instruction,
+    solana_transaction::{sanitized::SanitizedTransaction, Transaction},
+    std::{
+        collections::{HashMap, HashSet},
+        sync::{Arc, RwLock},
+    },
+};
+
+/// Hoists accounts from a bank on first access, like a rollup loading its
+/// state from the base chain.
+struct BankAccountStore {
+    bank: Arc<Bank>,
+    accounts: HashMap<Pubkey, AccountSharedData>,
+}
+
+impl AccountStore for BankAccountStore {
+    fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.accounts
+            .get(pubkey)
+            .cloned()
+            .or_else(|| self.bank.get_account(pubkey))
+    }
+
+    fn store_account(&mut self, pubkey: &Pubkey, account: &AccountSharedData) {
+        self.accounts.insert(*pubkey, account.clone());
+    }
+}
+
+struct TestContext {
+    bank: Arc<Bank>,
+    _bank_forks: Arc<RwLock<BankForks>>,
+    mint_keypair: Keypair,
+    participants: Vec<Keypair>,
+}
+
+impl TestContext {
+    fn new(num_participants: usize, lamports: u64) -> Self {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(1_000_000_000_000);
+        let (bank, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        let participants: Vec<_> = (0..num_participants).map(|_| Keypair::new()).collect();
+        for participant in &participants {
+            bank.transfer(lamports, &mint_keypair, &participant.pubkey())
+                .unwrap();
+        }
+        Self {
+            bank,
+            _bank_forks: bank_forks,
+            mint_keypair,
+            participants,
+        }
+    }
+
+    fn pubkeys(&self) -> Vec<Pubkey> {
+        self.participants.iter().map(Keypair::pubkey).collect()
+    }
+
+    fn engine(&self) -> RollupEngine<BankAccountStore> {
+        RollupEngine::new(BankAccountStore {
+            bank: self.bank.clone(),
+            accounts: HashMap::new(),
+        })
+    }
+}
+
+fn transfer(from: &Pubkey, to: &Pubkey, lamports: u64) -> SanitizedTransaction {
+    SanitizedTransaction::try_from_legacy_transaction(
+        Transaction::new_with_payer(
+            &[system_instruction::transfer(from, to, lamports)],
+            Some(from),
+        ),
+        &HashSet::new(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_execute_and_settle() {
+    let context = TestContext::new(3, 10_000_000);
+    let [alice, bob, carol] = context.pubkeys().try_into().unwrap();
+    let mut engine = context.engine();
+    let settler = Settler::open(engine.store(), &[alice, bob, carol]);
+
+    let output = engine.execute_batch(&[
+        transfer(&alice, &bob, 1_000_000),
+        transfer(&bob, &carol, 3_000_000),
+        transfer(&carol, &alice, 500_000),
+        // Alice can't afford this, so it must not change any state
+        transfer(&alice, &carol, 20_000_000),
+    ]);
+    assert_eq!(output.num_successful(), 3);
+    assert!(output.statuses[3].is_err());
+
+    let balance = |pubkey: &Pubkey| engine.get_account(pubkey).unwrap().lamports();
+    assert_eq!(balance(&alice), 9_500_000);
+    assert_eq!(balance(&bob), 8_000_000);
+    assert_eq!(balance(&carol), 12_500_000);
+
+    // The rollup hasn't touched the bank
+    assert_eq!(context.bank.get_balance(&alice), 10_000_000);
+
+    // Alice and Bob both owe Carol, which nets out to two transfers
+    let instructions = settler.instructions(engine.store()).unwrap();
+    assert_eq!(instructions.len(), 2);
+    let [alice_keypair, bob_keypair, _] = &context.participants[..] else {
+        unreachable!()
+    };
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&context.mint_keypair.pubkey()),
+        &[&context.mint_keypair, alice_keypair, bob_keypair],
+        context.bank.last_blockhash(),
+    );
+    context.bank.process_transaction(&transaction).unwrap();
+
+    for pubkey in [alice, bob, carol] {
+        assert_eq!(context.bank.get_balance(&pubkey), balance(&pubkey));
+    }
+}
+
+#[test]
+fn test_commitment_is_independent_of_batching() {
+    let context = TestContext::new(4, 10_000_000);
+    let pubkeys = context.pubkeys();
+    let transactions: Vec<_> = pubkeys
+        .iter()
+        .zip(pubkeys.iter().cycle().skip(1))
+        .enumerate()
+        .map(|(i, (from, to))| transfer(from, to, (i as u64 + 1) * 100_000))
+        .collect();
+
+    let mut single_batch = context.engine();
+    let output = single_batch.execute_batch(&transactions);
+    assert_eq!(output.num_successful(), transactions.len());
+
+    let mut many_batches = context.engine();
+    for transaction in &transactions {
+        many_batches.execute_batch(std::slice::from_ref(transaction));
+    }
+    assert_eq!(many_batches.checksum(), output.checksum);
+
+    // A failing transaction only loads accounts which are already committed
+    let output = single_batch.execute_batch(&[transfer(&pubkeys[0], &pubkeys[1], u64::MAX)]);
+    assert_eq!(output.num_successful(), 0);
+    assert_eq!(output.checksum, many_batches.checksum());
+}
+
+#[test]
+fn test_account_lt_hash_matches_accounts_db() {
+    let context = TestContext::new(2, 10_000_000);
+    let mut pubkeys = context.pubkeys();
+    pubkeys.push(solana_system_interface::program::id());
+
+    for pubkey in pubkeys {
+        let account = context.bank.get_account(&pubkey).unwrap();
+        assert_eq!(
+            account_lt_hash(&pubkey, &account),
+            AccountsDb::lt_hash_account(&account, &pubkey).0,
+        );
+    }
+}
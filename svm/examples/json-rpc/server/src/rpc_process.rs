@@ -340,6 +340,7 @@ impl JsonRpcRequestProcessor {
                     enable_log_recording: true,
                     enable_return_data_recording: true,
                     enable_transaction_balance_recording: true,
+                    enable_invocation_tracing: false,
                 },
             },
         );
@@ -514,6 +515,7 @@ impl JsonRpcRequestProcessor {
             .and_then(|mint_account| self.get_additional_mint_data(mint_account.data()).ok())
             .map(|data| AccountAdditionalDataV3 {
                 spl_token_additional_data: Some(data),
+                ..AccountAdditionalDataV3::default()
             });
 
         encode_ui_account(
@@ -673,6 +675,7 @@ pub mod rpc {
                 accounts: config_accounts,
                 min_context_slot: _,
                 inner_instructions: enable_cpi_recording,
+                invocation_trace: _,
             } = config.unwrap_or_default();
             let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
@@ -764,6 +767,7 @@ pub mod rpc {
                     loaded_accounts_data_size: Some(loaded_accounts_data_size),
                     return_data: return_data.map(|return_data| return_data.into()),
                     inner_instructions,
+                    invocation_trace: None,
                     replacement_blockhash: None,
                 },
             ))
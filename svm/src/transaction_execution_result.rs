@@ -8,7 +8,7 @@ use {
     crate::account_loader::LoadedTransaction,
     solana_program_runtime::loaded_programs::ProgramCacheEntry,
     solana_pubkey::Pubkey,
-    solana_transaction_context::TransactionReturnData,
+    solana_transaction_context::{InstructionInvocation, TransactionReturnData},
     solana_transaction_error::TransactionResult,
     std::{collections::HashMap, sync::Arc},
 };
@@ -38,6 +38,7 @@ pub struct TransactionExecutionDetails {
     pub log_messages: Option<Vec<String>>,
     pub inner_instructions: Option<InnerInstructionsList>,
     pub return_data: Option<TransactionReturnData>,
+    pub invocation_trace: Option<Vec<InstructionInvocation>>,
     pub executed_units: u64,
     /// The change in accounts data len for this transaction.
     /// NOTE: This value is valid IFF `status` is `Ok`.
@@ -89,15 +89,19 @@ pub struct ExecutionRecordingConfig {
     pub enable_log_recording: bool,
     pub enable_return_data_recording: bool,
     pub enable_transaction_balance_recording: bool,
+    pub enable_invocation_tracing: bool,
 }
 
 impl ExecutionRecordingConfig {
+    /// Invocation tracing is left disabled, since it is only meant for
+    /// simulation and profiling
     pub fn new_single_setting(option: bool) -> Self {
         ExecutionRecordingConfig {
             enable_return_data_recording: option,
             enable_log_recording: option,
             enable_cpi_recording: option,
             enable_transaction_balance_recording: option,
+            enable_invocation_tracing: false,
         }
     }
 }
@@ -884,6 +888,9 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             compute_budget,
             self.execution_cost,
         );
+        if config.recording_config.enable_invocation_tracing {
+            invoke_context.enable_invocation_tracing();
+        }
 
         let mut process_message_time = Measure::start("process_message_time");
         let process_result = process_message(
@@ -895,6 +902,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         );
         process_message_time.stop();
 
+        let invocation_trace = invoke_context.take_invocation_trace();
         drop(invoke_context);
 
         execute_timings.execute_accessories.process_message_us += process_message_time.as_us();
@@ -976,6 +984,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                 log_messages,
                 inner_instructions,
                 return_data,
+                invocation_trace,
                 executed_units,
                 accounts_data_len_delta,
             },
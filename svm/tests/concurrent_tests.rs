@@ -256,6 +256,7 @@ fn svm_concurrent() {
                     enable_return_data_recording: false,
                     enable_cpi_recording: false,
                     enable_transaction_balance_recording: false,
+                    enable_invocation_tracing: false,
                 },
                 ..Default::default()
             };
@@ -104,6 +104,7 @@ impl SvmTestEnvironment<'_> {
                 enable_return_data_recording: true,
                 enable_cpi_recording: false,
                 enable_transaction_balance_recording: false,
+                enable_invocation_tracing: false,
             },
             ..Default::default()
         };
@@ -1,7 +1,7 @@
 use {
     crate::args::{
-        Args, BalancesArgs, Command, DistributeTokensArgs, SenderStakeArgs, SplTokenArgs,
-        StakeArgs, TransactionLogArgs,
+        Args, BalancesArgs, Command, DistributeTokensArgs, NonceArgs, SenderStakeArgs,
+        SplTokenArgs, StakeArgs, TpuClientArgs, TransactionLogArgs,
     },
     clap::{Arg, ArgMatches, Command as ClapCommand},
     solana_clap_utils::{
@@ -92,6 +92,33 @@ where
                         .long("fee-payer")
                         .value_name("KEYPAIR")
                         .help("Fee payer keypair"),
+                )
+                .arg(
+                    Arg::new("transfers_per_transaction")
+                        .long("transfers-per-transaction")
+                        .value_name("NUMBER")
+                        .default_value("1")
+                        .help("Number of recipients to pay in each transaction"),
+                )
+                .arg(
+                    Arg::new("nonce")
+                        .long("nonce")
+                        .value_name("ADDRESS")
+                        .help("Sign transactions with the durable nonce held by this nonce account"),
+                )
+                .arg(
+                    Arg::new("nonce_authority")
+                        .long("nonce-authority")
+                        .value_name("KEYPAIR")
+                        .requires("nonce")
+                        .help("Nonce authority keypair; defaults to the fee payer"),
+                )
+                .arg(
+                    Arg::new("use_tpu_client")
+                        .long("use-tpu-client")
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with("nonce")
+                        .help("Send transactions directly to the upcoming leaders"),
                 ),
         )
         .subcommand(
@@ -284,6 +311,33 @@ where
                         .long("fee-payer")
                         .value_name("KEYPAIR")
                         .help("Fee payer keypair"),
+                )
+                .arg(
+                    Arg::new("transfers_per_transaction")
+                        .long("transfers-per-transaction")
+                        .value_name("NUMBER")
+                        .default_value("1")
+                        .help("Number of recipients to pay in each transaction"),
+                )
+                .arg(
+                    Arg::new("nonce")
+                        .long("nonce")
+                        .value_name("ADDRESS")
+                        .help("Sign transactions with the durable nonce held by this nonce account"),
+                )
+                .arg(
+                    Arg::new("nonce_authority")
+                        .long("nonce-authority")
+                        .value_name("KEYPAIR")
+                        .requires("nonce")
+                        .help("Nonce authority keypair; defaults to the fee payer"),
+                )
+                .arg(
+                    Arg::new("use_tpu_client")
+                        .long("use-tpu-client")
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with("nonce")
+                        .help("Send transactions directly to the upcoming leaders"),
                 ),
         )
         .subcommand(
@@ -312,6 +366,23 @@ where
                         .help("Mint address"),
                 )
         )
+        .subcommand(
+            ClapCommand::new("verify")
+                .about("Check that every recipient holds at least its allocated amount")
+                .arg(
+                    Arg::new("input_csv")
+                        .long("input-csv")
+                        .required(true)
+                        .value_name("FILE")
+                        .help("Input CSV file"),
+                )
+                .arg(
+                    Arg::new("mint_address")
+                        .long("mint-address")
+                        .value_name("ADDRESS")
+                        .help("Mint address; checks SOL balances if not specified"),
+                )
+        )
         .subcommand(
             ClapCommand::new("transaction-log")
                 .about("Transaction log")
@@ -379,9 +450,45 @@ fn parse_distribute_tokens_args(
         spl_token_args: None,
         output_path: matches.get_one::<String>("output_path").map(|path| path.to_string()),
         dry_run: matches.get_flag("dry_run"),
+        transfers_per_transaction: parse_transfers_per_transaction(matches)?,
+        nonce_args: parse_nonce_args(matches)?,
+        tpu_client_args: matches.get_flag("use_tpu_client").then(TpuClientArgs::default),
     })
 }
 
+fn parse_transfers_per_transaction(matches: &ArgMatches) -> Result<usize, Box<dyn Error>> {
+    let transfers_per_transaction = matches
+        .get_one::<String>("transfers_per_transaction")
+        .unwrap()
+        .parse::<usize>()?;
+    if transfers_per_transaction == 0 {
+        return Err("transfers-per-transaction must be at least 1".into());
+    }
+    Ok(transfers_per_transaction)
+}
+
+fn parse_nonce_args(matches: &ArgMatches) -> Result<Option<NonceArgs>, Box<dyn Error>> {
+    let nonce_account = match pubkey_of_signer(matches, "nonce", &mut None)? {
+        Some(nonce_account) => nonce_account,
+        None => return Ok(None),
+    };
+    // Like the fee payer, falls back to the default wallet
+    let nonce_authority = signer_from_path(
+        matches,
+        matches
+            .get_one::<String>("nonce_authority")
+            .or_else(|| matches.get_one::<String>("fee_payer"))
+            .map(|s| s.as_str())
+            .unwrap_or(""),
+        "nonce_authority",
+        &mut None,
+    )?;
+    Ok(Some(NonceArgs {
+        nonce_account,
+        nonce_authority,
+    }))
+}
+
 fn parse_create_stake_args(
     matches: &ArgMatches,
 ) -> Result<DistributeTokensArgs, Box<dyn Error>> {
@@ -439,6 +546,9 @@ fn parse_create_stake_args(
         spl_token_args: None,
         output_path: matches.get_one::<String>("output_path").map(|path| path.to_string()),
         dry_run: matches.get_flag("dry_run"),
+        transfers_per_transaction: 1,
+        nonce_args: None,
+        tpu_client_args: None,
     })
 }
 
@@ -532,6 +642,9 @@ fn parse_distribute_stake_args(
         spl_token_args: None,
         output_path: matches.get_one::<String>("output_path").map(|path| path.to_string()),
         dry_run: matches.get_flag("dry_run"),
+        transfers_per_transaction: 1,
+        nonce_args: None,
+        tpu_client_args: None,
     })
 }
 
@@ -586,6 +699,9 @@ fn parse_distribute_spl_tokens_args(
         spl_token_args: Some(spl_token_args),
         output_path: matches.get_one::<String>("output_path").map(|path| path.to_string()),
         dry_run: matches.get_flag("dry_run"),
+        transfers_per_transaction: parse_transfers_per_transaction(matches)?,
+        nonce_args: parse_nonce_args(matches)?,
+        tpu_client_args: matches.get_flag("use_tpu_client").then(TpuClientArgs::default),
     })
 }
 
@@ -636,6 +752,7 @@ where
         }
         Some(("balances", matches)) => Command::Balances(parse_balances_args(matches)?),
         Some(("spl-token-balances", matches)) => Command::Balances(parse_balances_args(matches)?),
+        Some(("verify", matches)) => Command::Verify(parse_balances_args(matches)?),
         Some(("transaction-log", matches)) => {
             Command::TransactionLog(parse_transaction_log_args(matches))
         }
@@ -24,6 +24,27 @@ pub struct DistributeTokensArgs {
     pub stake_args: Option<StakeArgs>,
     pub spl_token_args: Option<SplTokenArgs>,
     pub transfer_amount: Option<u64>,
+    /// Number of recipients paid by each transaction. Only plain SOL and SPL
+    /// token distributions can be packed, stake distributions always use 1.
+    pub transfers_per_transaction: usize,
+    pub nonce_args: Option<NonceArgs>,
+    pub tpu_client_args: Option<TpuClientArgs>,
+}
+
+/// Submit every transaction with a durable nonce instead of a recent
+/// blockhash. Transactions are then sent one at a time, and a transaction
+/// recorded in the db before a crash can always be resolved on the next run:
+/// it either landed, or the nonce moved on and it never will.
+pub struct NonceArgs {
+    pub nonce_account: Pubkey,
+    pub nonce_authority: Box<dyn Signer>,
+}
+
+/// Send transactions directly to the upcoming leaders over QUIC, rather than
+/// one at a time through the RPC node.
+#[derive(Default)]
+pub struct TpuClientArgs {
+    pub websocket_url: String,
 }
 
 #[derive(Default)]
@@ -48,6 +69,7 @@ pub enum Command {
     DistributeTokens(DistributeTokensArgs),
     Balances(BalancesArgs),
     TransactionLog(TransactionLogArgs),
+    Verify(BalancesArgs),
 }
 
 pub struct Args {
@@ -6,6 +6,7 @@ use {
         db::{self, TransactionInfo},
         spl_token::*,
         token_display::Token,
+        tpu_sender::{TpuSender, TPU_BATCH_SIZE},
     },
     chrono::prelude::*,
     console::style,
@@ -15,18 +16,23 @@ use {
     pickledb::PickleDb,
     serde::{Deserialize, Serialize},
     solana_account_decoder::parse_token::real_number_string,
-    solana_clock::Slot,
+    solana_clock::{Slot, MAX_PROCESSING_AGE},
     solana_commitment_config::CommitmentConfig,
     solana_hash::Hash,
     solana_instruction::Instruction,
     solana_message::Message,
     solana_native_token::{lamports_to_sol, sol_to_lamports},
+    solana_packet::PACKET_DATA_SIZE,
     solana_rpc_client::rpc_client::RpcClient,
     solana_rpc_client_api::{
-        client_error::{Error as ClientError, Result as ClientResult},
+        client_error::{
+            Error as ClientError, ErrorKind as ClientErrorKind, Result as ClientResult,
+        },
         config::RpcSendTransactionConfig,
-        request::{MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_MULTIPLE_ACCOUNTS},
+        custom_error::JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
+        request::{RpcError, MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_MULTIPLE_ACCOUNTS},
     },
+    solana_rpc_client_nonce_utils as nonce_utils,
     solana_signature::Signature,
     solana_signer::{unique_signers, Signer},
     solana_stake_interface::{
@@ -34,12 +40,13 @@ use {
         state::{Authorized, Lockup, StakeAuthorize, StakeStateV2},
     },
     solana_system_interface::instruction as system_instruction,
-    solana_transaction::Transaction,
+    solana_transaction::{uses_durable_nonce, Transaction},
     solana_transaction_status::TransactionStatus,
     spl_associated_token_account::get_associated_token_address,
-    spl_token::solana_program::program_error::ProgramError,
+    spl_token::solana_program::{program_error::ProgramError, program_pack::Pack},
     std::{
         cmp::{self},
+        collections::HashSet,
         io,
         str::FromStr,
         sync::{
@@ -138,6 +145,14 @@ pub enum Error {
     ProgramError(#[from] ProgramError),
     #[error("Exit signal received")]
     ExitSignal,
+    #[error("Nonce error: {0}")]
+    NonceError(#[from] nonce_utils::Error),
+    #[error("Transaction of {0} bytes is too large, use fewer transfers per transaction")]
+    TransactionTooLarge(usize),
+    #[error("TPU client error: {0}")]
+    TpuClientError(String),
+    #[error("{0} recipients do not hold their expected balance")]
+    VerificationFailed(usize),
 }
 
 fn merge_allocations(allocations: &[TypedAllocation]) -> Vec<TypedAllocation> {
@@ -349,50 +364,130 @@ fn build_messages(
         }
     }
 
-    for (i, allocation) in allocations.iter().enumerate() {
+    let transfers_per_transaction = args.transfers_per_transaction.max(1);
+    let fee_payer_pubkey = args.fee_payer.pubkey();
+    // Recipients may appear more than once, but each associated token account
+    // must only be created once
+    let mut pending_associated_token_accounts = HashSet::new();
+    for (chunk_index, allocation_chunk) in allocations.chunks(transfers_per_transaction).enumerate()
+    {
         if exit.load(Ordering::SeqCst) {
             db.dump()?;
             return Err(Error::ExitSignal);
         }
         let new_stake_account_keypair = Keypair::new();
-        let lockup_date = allocation.lockup_date;
-
-        let do_create_associated_token_account = if let Some(spl_token_args) = &args.spl_token_args
-        {
-            let do_create_associated_token_account =
-                existing_associated_token_accounts[i].is_none();
-            if do_create_associated_token_account {
-                *created_accounts += 1;
-            }
-            println!(
-                "{:<44}  {:>24}",
-                allocation.recipient,
-                real_number_string(allocation.amount, spl_token_args.decimals)
-            );
-            do_create_associated_token_account
+        let mut instructions = vec![];
+        for (i, allocation) in allocation_chunk.iter().enumerate() {
+            let associated_token_account_exists = existing_associated_token_accounts
+                .get(chunk_index * transfers_per_transaction + i)
+                .is_some_and(Option::is_some);
+            instructions.extend(allocation_instructions(
+                allocation,
+                associated_token_account_exists,
+                &mut pending_associated_token_accounts,
+                &new_stake_account_keypair.pubkey(),
+                args,
+                created_accounts,
+            ));
+        }
+        let message = if let Some(nonce_args) = &args.nonce_args {
+            // The nonce is only fetched when the transaction is signed
+            Message::new_with_nonce(
+                instructions,
+                Some(&fee_payer_pubkey),
+                &nonce_args.nonce_account,
+                &nonce_args.nonce_authority.pubkey(),
+            )
         } else {
-            println!(
-                "{:<44}  {:>24.9}",
-                allocation.recipient,
-                lamports_to_sol(allocation.amount)
-            );
-            false
+            Message::new_with_blockhash(
+                &instructions,
+                Some(&fee_payer_pubkey),
+                &Hash::default(), // populated by a real blockhash for balance check and submission
+            )
         };
-        let instructions = distribution_instructions(
-            allocation,
-            &new_stake_account_keypair.pubkey(),
-            args,
-            lockup_date,
-            do_create_associated_token_account,
+        check_transaction_size(&message)?;
+        messages.push(message);
+        stake_extras.push((new_stake_account_keypair, allocation_chunk[0].lockup_date));
+    }
+    Ok(())
+}
+
+/// Returns the instructions paying a single allocation, printing it along
+/// the way.
+fn allocation_instructions(
+    allocation: &TypedAllocation,
+    associated_token_account_exists: bool,
+    pending_associated_token_accounts: &mut HashSet<Pubkey>,
+    new_stake_account_address: &Pubkey,
+    args: &DistributeTokensArgs,
+    created_accounts: &mut u64,
+) -> Vec<Instruction> {
+    let do_create_associated_token_account = if let Some(spl_token_args) = &args.spl_token_args {
+        let associated_token_address =
+            get_associated_token_address(&allocation.recipient, &spl_token_args.mint);
+        let do_create_associated_token_account = !associated_token_account_exists
+            && pending_associated_token_accounts.insert(associated_token_address);
+        if do_create_associated_token_account {
+            *created_accounts += 1;
+        }
+        println!(
+            "{:<44}  {:>24}",
+            allocation.recipient,
+            real_number_string(allocation.amount, spl_token_args.decimals)
         );
-        let fee_payer_pubkey = args.fee_payer.pubkey();
-        let message = Message::new_with_blockhash(
-            &instructions,
-            Some(&fee_payer_pubkey),
-            &Hash::default(), // populated by a real blockhash for balance check and submission
+        do_create_associated_token_account
+    } else {
+        println!(
+            "{:<44}  {:>24.9}",
+            allocation.recipient,
+            lamports_to_sol(allocation.amount)
         );
-        messages.push(message);
-        stake_extras.push((new_stake_account_keypair, lockup_date));
+        false
+    };
+    distribution_instructions(
+        allocation,
+        new_stake_account_address,
+        args,
+        allocation.lockup_date,
+        do_create_associated_token_account,
+    )
+}
+
+fn check_transaction_size(message: &Message) -> Result<(), Error> {
+    let transaction = Transaction::new_unsigned(message.clone());
+    let size = bincode::serialized_size(&transaction).unwrap() as usize;
+    if size > PACKET_DATA_SIZE {
+        return Err(Error::TransactionTooLarge(size));
+    }
+    Ok(())
+}
+
+/// Records the transaction paying `allocations` in the db.
+fn set_transaction_info(
+    db: &mut PickleDb,
+    allocations: &[TypedAllocation],
+    transaction: &Transaction,
+    new_stake_account_address: Option<&Pubkey>,
+    last_valid_block_height: u64,
+    lockup_date: Option<DateTime<Utc>>,
+) -> Result<(), Error> {
+    if let [allocation] = allocations {
+        db::set_transaction_info(
+            db,
+            &allocation.recipient,
+            allocation.amount,
+            transaction,
+            new_stake_account_address,
+            false,
+            last_valid_block_height,
+            lockup_date,
+        )?;
+    } else {
+        let recipients = allocations
+            .iter()
+            .map(|allocation| (allocation.recipient, allocation.amount))
+            .collect::<Vec<_>>();
+        db::set_packed_transaction_info(db, &recipients, transaction, last_valid_block_height)?;
     }
     Ok(())
 }
@@ -406,14 +501,31 @@ fn send_messages(
     messages: Vec<Message>,
     stake_extras: StakeExtras,
 ) -> Result<(), Error> {
-    for ((allocation, message), (new_stake_account_keypair, lockup_date)) in
-        allocations.iter().zip(messages).zip(stake_extras)
+    let tpu_sender = match &args.tpu_client_args {
+        Some(tpu_client_args) if !args.dry_run => Some(TpuSender::new(
+            client.url(),
+            tpu_client_args.websocket_url.clone(),
+        )?),
+        _ => None,
+    };
+    let mut tpu_batch = vec![];
+
+    for ((allocation_chunk, message), (new_stake_account_keypair, lockup_date)) in allocations
+        .chunks(args.transfers_per_transaction.max(1))
+        .zip(messages)
+        .zip(stake_extras)
     {
         if exit.load(Ordering::SeqCst) {
+            if let Some(tpu_sender) = &tpu_sender {
+                // Every batched transaction has already been recorded
+                tpu_sender.send_transactions(&tpu_batch)?;
+            }
             db.dump()?;
             return Err(Error::ExitSignal);
         }
         let new_stake_account_address = new_stake_account_keypair.pubkey();
+        let new_stake_account_address_option =
+            args.stake_args.as_ref().map(|_| &new_stake_account_address);
 
         let mut signers = vec![&*args.fee_payer, &*args.sender_keypair];
         if let Some(stake_args) = &args.stake_args {
@@ -422,7 +534,7 @@ fn send_messages(
                 signers.push(&*sender_stake_args.stake_authority);
                 signers.push(&*sender_stake_args.withdraw_authority);
                 signers.push(&new_stake_account_keypair);
-                if allocation.lockup_date.is_some() {
+                if lockup_date.is_some() {
                     if let Some(lockup_authority) = &sender_stake_args.lockup_authority {
                         signers.push(&**lockup_authority);
                     } else {
@@ -431,41 +543,102 @@ fn send_messages(
                 }
             }
         }
+        if let Some(nonce_args) = &args.nonce_args {
+            signers.push(&*nonce_args.nonce_authority);
+        }
         let signers = unique_signers(signers);
-        let result: ClientResult<(Transaction, u64)> = {
-            if args.dry_run {
-                Ok((Transaction::new_unsigned(message), u64::MAX))
-            } else {
-                let (blockhash, last_valid_block_height) =
-                    client.get_latest_blockhash_with_commitment(CommitmentConfig::default())?;
-                let transaction = Transaction::new(&signers, message, blockhash);
-                let config = RpcSendTransactionConfig {
-                    skip_preflight: true,
-                    ..RpcSendTransactionConfig::default()
-                };
-                client.send_transaction_with_config(&transaction, config)?;
-                Ok((transaction, last_valid_block_height))
+
+        if args.dry_run {
+            set_transaction_info(
+                db,
+                allocation_chunk,
+                &Transaction::new_unsigned(message),
+                new_stake_account_address_option,
+                u64::MAX,
+                lockup_date,
+            )?;
+            continue;
+        }
+
+        if let Some(nonce_args) = &args.nonce_args {
+            let nonce_account = nonce_utils::get_account_with_commitment(
+                client,
+                &nonce_args.nonce_account,
+                client.commitment(),
+            )?;
+            let nonce = nonce_utils::data_from_account(&nonce_account)?.blockhash();
+            let last_valid_block_height = client
+                .get_latest_blockhash_with_commitment(CommitmentConfig::default())?
+                .1;
+            let transaction = Transaction::new(&signers, message, nonce);
+            // Record the transaction before sending it. If this run dies before
+            // the transaction lands, the next run resolves it through the nonce.
+            set_transaction_info(
+                db,
+                allocation_chunk,
+                &transaction,
+                new_stake_account_address_option,
+                last_valid_block_height,
+                lockup_date,
+            )?;
+            db.dump()?;
+            // The next transaction can only be signed once this one has
+            // advanced the nonce
+            if let Err(e) = client.send_and_confirm_transaction_with_spinner(&transaction) {
+                eprintln!(
+                    "Error sending transaction {}: {}",
+                    transaction.signatures[0], e
+                );
             }
+            continue;
+        }
+
+        let (blockhash, last_valid_block_height) =
+            client.get_latest_blockhash_with_commitment(CommitmentConfig::default())?;
+        let transaction = Transaction::new(&signers, message, blockhash);
+        if let Some(tpu_sender) = &tpu_sender {
+            set_transaction_info(
+                db,
+                allocation_chunk,
+                &transaction,
+                new_stake_account_address_option,
+                last_valid_block_height,
+                lockup_date,
+            )?;
+            tpu_batch.push(transaction);
+            if tpu_batch.len() >= TPU_BATCH_SIZE {
+                tpu_sender.send_transactions(&tpu_batch)?;
+                tpu_batch.clear();
+            }
+            continue;
+        }
+
+        let config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            ..RpcSendTransactionConfig::default()
         };
-        match result {
-            Ok((transaction, last_valid_block_height)) => {
-                let new_stake_account_address_option =
-                    args.stake_args.as_ref().map(|_| &new_stake_account_address);
-                db::set_transaction_info(
+        match client.send_transaction_with_config(&transaction, config) {
+            Ok(_) => {
+                set_transaction_info(
                     db,
-                    &allocation.recipient,
-                    allocation.amount,
+                    allocation_chunk,
                     &transaction,
                     new_stake_account_address_option,
-                    false,
                     last_valid_block_height,
                     lockup_date,
                 )?;
             }
             Err(e) => {
-                eprintln!("Error sending tokens to {}: {}", allocation.recipient, e);
+                for allocation in allocation_chunk {
+                    eprintln!("Error sending tokens to {}: {}", allocation.recipient, e);
+                }
             }
-        };
+        }
+    }
+
+    if let Some(tpu_sender) = tpu_sender {
+        tpu_sender.send_transactions(&tpu_batch)?;
+        tpu_sender.shutdown()?;
     }
     Ok(())
 }
@@ -727,10 +900,15 @@ fn update_finalized_transactions(
     exit: Arc<AtomicBool>,
 ) -> Result<Option<usize>, Error> {
     let transaction_infos = db::read_transaction_infos(db);
+    // A packed transaction is recorded once per recipient, but only needs to
+    // be checked once
+    let mut seen_signatures = HashSet::new();
     let unconfirmed_transactions: Vec<_> = transaction_infos
         .iter()
         .filter_map(|info| {
-            if info.finalized_date.is_some() {
+            if info.finalized_date.is_some()
+                || !seen_signatures.insert(info.transaction.signatures[0])
+            {
                 None
             } else {
                 Some((&info.transaction, info.last_valid_block_height))
@@ -776,10 +954,53 @@ fn log_transaction_confirmations(
     confirmations: &mut Option<usize>,
 ) -> Result<(), Error> {
     let finalized_block_height = client.get_block_height()?;
-    for ((transaction, last_valid_block_height), opt_transaction_status) in unconfirmed_transactions
-        .into_iter()
-        .zip(statuses.into_iter())
+    for ((transaction, last_valid_block_height), mut opt_transaction_status) in
+        unconfirmed_transactions
+            .into_iter()
+            .zip(statuses.into_iter())
     {
+        if opt_transaction_status.is_none() && uses_durable_nonce(transaction).is_some() {
+            // Durable nonce transactions don't expire with their blockhash,
+            // the nonce account tells whether they can still land
+            match nonce_transaction_state(client, transaction, last_valid_block_height)? {
+                NonceTransactionState::Landed(transaction_status) => {
+                    opt_transaction_status = Some(transaction_status);
+                }
+                NonceTransactionState::Dropped => {
+                    eprintln!(
+                        "Nonce advanced without transaction {}, discarding transaction record",
+                        transaction.signatures[0]
+                    );
+                    eprintln!();
+                    db::remove_transaction_info(db, &transaction.signatures[0])?;
+                    continue;
+                }
+                NonceTransactionState::Unknown => {
+                    eprintln!(
+                        "Nonce advanced, but transaction {} can't be found in the transaction \
+                         history of the RPC node. Check it manually, the transaction record is \
+                         kept so that the transfer isn't sent again",
+                        transaction.signatures[0]
+                    );
+                    eprintln!();
+                    continue;
+                }
+                NonceTransactionState::Pending => {
+                    let config = RpcSendTransactionConfig {
+                        skip_preflight: true,
+                        ..RpcSendTransactionConfig::default()
+                    };
+                    if let Err(e) = client.send_transaction_with_config(transaction, config) {
+                        eprintln!(
+                            "Error resending transaction {}: {}",
+                            transaction.signatures[0], e
+                        );
+                    }
+                    *confirmations = Some(0);
+                    continue;
+                }
+            }
+        }
         match db::update_finalized_transaction(
             db,
             &transaction.signatures[0],
@@ -802,6 +1023,68 @@ fn log_transaction_confirmations(
     Ok(())
 }
 
+enum NonceTransactionState {
+    /// The transaction was executed.
+    Landed(TransactionStatus),
+    /// The nonce was advanced by another transaction, so this one can never
+    /// land.
+    Dropped,
+    /// The nonce was advanced, but the RPC node's transaction history can't
+    /// tell whether by this transaction.
+    Unknown,
+    /// The nonce is unchanged, so the transaction may still land.
+    Pending,
+}
+
+fn nonce_transaction_state(
+    client: &RpcClient,
+    transaction: &Transaction,
+    last_valid_block_height: u64,
+) -> Result<NonceTransactionState, Error> {
+    let nonce_instruction = uses_durable_nonce(transaction).unwrap();
+    let nonce_account_index = nonce_instruction.accounts[0] as usize;
+    let nonce_address = transaction.message.account_keys[nonce_account_index];
+    let nonce_account = nonce_utils::get_account_with_commitment(
+        client,
+        &nonce_address,
+        CommitmentConfig::finalized(),
+    )?;
+    if nonce_utils::data_from_account(&nonce_account)?.blockhash()
+        == transaction.message.recent_blockhash
+    {
+        return Ok(NonceTransactionState::Pending);
+    }
+    // The status may have been evicted from the recent status cache
+    let transaction_status =
+        match client.get_signature_statuses_with_history(&transaction.signatures[..1]) {
+            Ok(response) => response.value.into_iter().next().flatten(),
+            Err(err) if is_transaction_history_not_available(&err) => {
+                return Ok(NonceTransactionState::Unknown);
+            }
+            Err(err) => return Err(err.into()),
+        };
+    if let Some(transaction_status) = transaction_status {
+        return Ok(NonceTransactionState::Landed(transaction_status));
+    }
+    // The transaction was recorded no earlier than `MAX_PROCESSING_AGE` blocks
+    // before `last_valid_block_height`, and a slot is never lower than its
+    // block height. A missing status only proves the transaction didn't land
+    // if the node's ledger reaches back that far.
+    let earliest_slot = last_valid_block_height.saturating_sub(MAX_PROCESSING_AGE as u64);
+    if client.minimum_ledger_slot()? > earliest_slot {
+        return Ok(NonceTransactionState::Unknown);
+    }
+    Ok(NonceTransactionState::Dropped)
+}
+
+fn is_transaction_history_not_available(err: &ClientError) -> bool {
+    matches!(
+        err.kind(),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+            if *code == JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE
+    )
+}
+
 pub fn get_fee_estimate_for_messages(
     messages: &[Message],
     client: &RpcClient,
@@ -946,6 +1229,81 @@ pub fn process_balances(
     Ok(())
 }
 
+/// Checks that every recipient holds at least the amount allocated to it,
+/// printing the recipients that don't.
+pub fn process_verify(
+    client: &RpcClient,
+    args: &BalancesArgs,
+    exit: Arc<AtomicBool>,
+) -> Result<(), Error> {
+    let allocations: Vec<TypedAllocation> =
+        read_allocations(&args.input_csv, None, false, args.spl_token_args.is_some())?;
+    let allocations = merge_allocations(&allocations);
+    let token = |amount| {
+        if let Some(spl_token_args) = &args.spl_token_args {
+            Token::spl_token(amount, spl_token_args.decimals)
+        } else {
+            Token::sol(amount)
+        }
+    };
+
+    println!(
+        "{}",
+        style(format!(
+            "{:<44}  {:>24}  {:>24}",
+            "Recipient", "Expected Balance", "Actual Balance"
+        ))
+        .bold()
+    );
+
+    let mut failures = 0;
+    for allocation_chunk in allocations.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        if exit.load(Ordering::SeqCst) {
+            return Err(Error::ExitSignal);
+        }
+        let addresses = allocation_chunk
+            .iter()
+            .map(|allocation| {
+                if let Some(spl_token_args) = &args.spl_token_args {
+                    get_associated_token_address(&allocation.recipient, &spl_token_args.mint)
+                } else {
+                    allocation.recipient
+                }
+            })
+            .collect::<Vec<_>>();
+        let accounts = client.get_multiple_accounts(&addresses)?;
+        for (allocation, account) in allocation_chunk.iter().zip(accounts) {
+            let actual = match account {
+                Some(account) if args.spl_token_args.is_some() => {
+                    spl_token::state::Account::unpack(&account.data)
+                        .map(|token_account| token_account.amount)
+                        .unwrap_or_default()
+                }
+                Some(account) => account.lamports,
+                None => 0,
+            };
+            if actual < allocation.amount {
+                failures += 1;
+                println!(
+                    "{:<44}  {:>24}  {:>24}",
+                    allocation.recipient,
+                    token(allocation.amount).to_string(),
+                    style(token(actual).to_string()).red(),
+                );
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(Error::VerificationFailed(failures));
+    }
+    println!(
+        "All {} recipients hold their expected balance",
+        allocations.len()
+    );
+    Ok(())
+}
+
 pub fn process_transaction_log(args: &TransactionLogArgs) -> Result<(), Error> {
     let db = db::open_db(&args.transaction_db, true)?;
     db::write_transaction_log(&db, &args.output_path)?;
@@ -1019,6 +1377,9 @@ pub fn test_process_distribute_tokens_with_client(
         stake_args: None,
         spl_token_args: None,
         transfer_amount,
+        transfers_per_transaction: 1,
+        nonce_args: None,
+        tpu_client_args: None,
     };
     let confirmations = process_allocations(client, &args, exit.clone()).unwrap();
     assert_eq!(confirmations, None);
@@ -1126,6 +1487,9 @@ pub fn test_process_create_stake_with_client(client: &RpcClient, sender_keypair:
         spl_token_args: None,
         sender_keypair: Box::new(sender_keypair),
         transfer_amount: None,
+        transfers_per_transaction: 1,
+        nonce_args: None,
+        tpu_client_args: None,
     };
     let confirmations = process_allocations(client, &args, exit.clone()).unwrap();
     assert_eq!(confirmations, None);
@@ -1258,6 +1622,9 @@ pub fn test_process_distribute_stake_with_client(client: &RpcClient, sender_keyp
         spl_token_args: None,
         sender_keypair: Box::new(sender_keypair),
         transfer_amount: None,
+        transfers_per_transaction: 1,
+        nonce_args: None,
+        tpu_client_args: None,
     };
     let confirmations = process_allocations(client, &args, exit.clone()).unwrap();
     assert_eq!(confirmations, None);
@@ -1304,6 +1671,8 @@ pub fn test_process_distribute_stake_with_client(client: &RpcClient, sender_keyp
 mod tests {
     use {
         super::*,
+        crate::args::NonceArgs,
+        assert_matches::assert_matches,
         solana_instruction::AccountMeta,
         solana_keypair::{read_keypair_file, write_keypair_file},
         solana_signer::Signer,
@@ -1806,6 +2175,9 @@ mod tests {
             spl_token_args: None,
             sender_keypair: Box::new(Keypair::new()),
             transfer_amount: None,
+            transfers_per_transaction: 1,
+            nonce_args: None,
+            tpu_client_args: None,
         };
         let lockup_date = lockup_date_str.parse().unwrap();
         let instructions = distribution_instructions(
@@ -1855,6 +2227,9 @@ mod tests {
             stake_args,
             spl_token_args: None,
             transfer_amount: None,
+            transfers_per_transaction: 1,
+            nonce_args: None,
+            tpu_client_args: None,
         };
         (allocations, args)
     }
@@ -2310,6 +2685,9 @@ mod tests {
             stake_args: None,
             spl_token_args: None,
             transfer_amount: None,
+            transfers_per_transaction: 1,
+            nonce_args: None,
+            tpu_client_args: None,
         };
         let allocation = TypedAllocation {
             recipient,
@@ -2432,6 +2810,9 @@ mod tests {
             stake_args: None,
             spl_token_args: None,
             transfer_amount: None,
+            transfers_per_transaction: 1,
+            nonce_args: None,
+            tpu_client_args: None,
         };
         let allocation = TypedAllocation {
             recipient,
@@ -2548,6 +2929,9 @@ mod tests {
             stake_args: None,
             spl_token_args: None,
             transfer_amount: None,
+            transfers_per_transaction: 1,
+            nonce_args: None,
+            tpu_client_args: None,
         };
 
         let exit = Arc::new(AtomicBool::new(false));
@@ -2692,4 +3076,250 @@ mod tests {
         assert_eq!(transaction_info.len(), 1);
         assert_eq!(confs, None);
     }
+
+    fn write_allocations_csv(allocations: &[(Pubkey, u64)]) -> NamedTempFile {
+        let allocations_file = NamedTempFile::new().unwrap();
+        let mut wtr = csv::WriterBuilder::new()
+            .from_path(allocations_file.path())
+            .unwrap();
+        wtr.write_record(["recipient", "amount"]).unwrap();
+        for (recipient, amount) in allocations {
+            wtr.write_record([recipient.to_string(), lamports_to_sol(*amount).to_string()])
+                .unwrap();
+        }
+        wtr.flush().unwrap();
+        allocations_file
+    }
+
+    fn funded_fee_payer(client: &RpcClient, sender_keypair: &Keypair) -> Keypair {
+        let fee_payer = Keypair::new();
+        let transaction = transfer(
+            client,
+            sol_to_lamports(10.0),
+            sender_keypair,
+            &fee_payer.pubkey(),
+        )
+        .unwrap();
+        client
+            .send_and_confirm_transaction_with_spinner(&transaction)
+            .unwrap();
+        fee_payer
+    }
+
+    #[test]
+    fn test_process_packed_allocations() {
+        let alice = Keypair::new();
+        let test_validator = simple_test_validator_no_fees(alice.pubkey());
+        let url = test_validator.rpc_url();
+        let client = RpcClient::new_with_commitment(url, CommitmentConfig::processed());
+        let exit = Arc::new(AtomicBool::default());
+        let fee_payer = funded_fee_payer(&client, &alice);
+
+        let allocations: Vec<_> = (1..=3)
+            .map(|i| (Pubkey::new_unique(), sol_to_lamports(i as f64)))
+            .collect();
+        let allocations_file = write_allocations_csv(&allocations);
+        let input_csv = allocations_file.path().to_str().unwrap().to_string();
+        let dir = tempdir().unwrap();
+        let transaction_db = dir
+            .path()
+            .join("transactions.db")
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let args = DistributeTokensArgs {
+            sender_keypair: Box::new(alice),
+            fee_payer: Box::new(fee_payer),
+            dry_run: false,
+            input_csv: input_csv.clone(),
+            transaction_db: transaction_db.clone(),
+            output_path: None,
+            stake_args: None,
+            spl_token_args: None,
+            transfer_amount: None,
+            transfers_per_transaction: 2,
+            nonce_args: None,
+            tpu_client_args: None,
+        };
+        process_allocations(&client, &args, exit.clone()).unwrap();
+
+        let transaction_infos =
+            db::read_transaction_infos(&db::open_db(&transaction_db, true).unwrap());
+        assert_eq!(transaction_infos.len(), 3);
+        assert!(transaction_infos
+            .iter()
+            .all(|info| info.finalized_date.is_some()));
+        let signatures: HashSet<_> = transaction_infos
+            .iter()
+            .map(|info| info.transaction.signatures[0])
+            .collect();
+        assert_eq!(signatures.len(), 2);
+        for (recipient, amount) in &allocations {
+            assert_eq!(client.get_balance(recipient).unwrap(), *amount);
+        }
+
+        // Run it again, and check there's no double-spend
+        process_allocations(&client, &args, exit.clone()).unwrap();
+        for (recipient, amount) in &allocations {
+            assert_eq!(client.get_balance(recipient).unwrap(), *amount);
+        }
+
+        let balances_args = BalancesArgs {
+            input_csv,
+            spl_token_args: None,
+        };
+        process_verify(&client, &balances_args, exit.clone()).unwrap();
+
+        // A recipient short of its allocation fails verification
+        let (recipient, amount) = allocations[0];
+        let allocations_file = write_allocations_csv(&[(recipient, amount + 1)]);
+        let balances_args = BalancesArgs {
+            input_csv: allocations_file.path().to_str().unwrap().to_string(),
+            spl_token_args: None,
+        };
+        assert_matches!(
+            process_verify(&client, &balances_args, exit),
+            Err(Error::VerificationFailed(1))
+        );
+    }
+
+    #[test]
+    fn test_process_allocations_with_nonce() {
+        let alice = Keypair::new();
+        let test_validator = simple_test_validator_no_fees(alice.pubkey());
+        let url = test_validator.rpc_url();
+        let client = RpcClient::new_with_commitment(url, CommitmentConfig::processed());
+        let exit = Arc::new(AtomicBool::default());
+        let fee_payer = funded_fee_payer(&client, &alice);
+
+        let nonce_keypair = Keypair::new();
+        let nonce_authority = Keypair::new();
+        let transaction = Transaction::new_signed_with_payer(
+            &system_instruction::create_nonce_account(
+                &fee_payer.pubkey(),
+                &nonce_keypair.pubkey(),
+                &nonce_authority.pubkey(),
+                sol_to_lamports(1.0),
+            ),
+            Some(&fee_payer.pubkey()),
+            &[&fee_payer, &nonce_keypair],
+            client.get_latest_blockhash().unwrap(),
+        );
+        client
+            .send_and_confirm_transaction_with_spinner(&transaction)
+            .unwrap();
+
+        let allocations: Vec<_> = (1..=3)
+            .map(|i| (Pubkey::new_unique(), sol_to_lamports(i as f64)))
+            .collect();
+        let allocations_file = write_allocations_csv(&allocations);
+        let dir = tempdir().unwrap();
+        let transaction_db = dir
+            .path()
+            .join("transactions.db")
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let args = DistributeTokensArgs {
+            sender_keypair: Box::new(alice),
+            fee_payer: Box::new(fee_payer),
+            dry_run: false,
+            input_csv: allocations_file.path().to_str().unwrap().to_string(),
+            transaction_db: transaction_db.clone(),
+            output_path: None,
+            stake_args: None,
+            spl_token_args: None,
+            transfer_amount: None,
+            transfers_per_transaction: 1,
+            nonce_args: Some(NonceArgs {
+                nonce_account: nonce_keypair.pubkey(),
+                nonce_authority: Box::new(nonce_authority),
+            }),
+            tpu_client_args: None,
+        };
+        process_allocations(&client, &args, exit.clone()).unwrap();
+
+        // A transaction landing in the same slot as the previous one can't
+        // advance the nonce; its record is discarded and it's paid again by
+        // the next run. Either way, no recipient may be paid twice.
+        process_allocations(&client, &args, exit).unwrap();
+        let transaction_infos =
+            db::read_transaction_infos(&db::open_db(&transaction_db, true).unwrap());
+        assert_eq!(transaction_infos.len(), 3);
+        assert!(transaction_infos
+            .iter()
+            .all(|info| uses_durable_nonce(&info.transaction).is_some()));
+        for (recipient, amount) in &allocations {
+            assert_eq!(client.get_balance(recipient).unwrap(), *amount);
+        }
+    }
+
+    #[test]
+    fn test_is_transaction_history_not_available() {
+        let rpc_error = |code| {
+            ClientError::from(RpcError::RpcResponseError {
+                code,
+                message: String::new(),
+                data: solana_rpc_client_api::request::RpcResponseErrorData::Empty,
+            })
+        };
+        assert!(is_transaction_history_not_available(&rpc_error(
+            JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE
+        )));
+        assert!(!is_transaction_history_not_available(&rpc_error(-32000)));
+        assert!(!is_transaction_history_not_available(&ClientError::from(
+            io::Error::other("connection refused")
+        )));
+    }
+
+    #[test]
+    fn test_build_messages_transaction_too_large() {
+        let client = RpcClient::new_mock("mock_client".to_string());
+        let dir = tempdir().unwrap();
+        let db_file = dir
+            .path()
+            .join("build_messages_too_large.db")
+            .to_str()
+            .unwrap()
+            .to_string();
+        let mut db = db::open_db(&db_file, false).unwrap();
+
+        let allocations: Vec<_> = (0..40)
+            .map(|_| TypedAllocation {
+                recipient: Pubkey::new_unique(),
+                amount: sol_to_lamports(1.0),
+                lockup_date: None,
+            })
+            .collect();
+        let args = DistributeTokensArgs {
+            sender_keypair: Box::new(Keypair::new()),
+            fee_payer: Box::new(Keypair::new()),
+            dry_run: true,
+            input_csv: "".to_string(),
+            transaction_db: "".to_string(),
+            output_path: None,
+            stake_args: None,
+            spl_token_args: None,
+            transfer_amount: None,
+            transfers_per_transaction: allocations.len(),
+            nonce_args: None,
+            tpu_client_args: None,
+        };
+
+        assert_matches!(
+            build_messages(
+                &client,
+                &mut db,
+                &allocations,
+                &args,
+                Arc::new(AtomicBool::new(false)),
+                &mut vec![],
+                &mut vec![],
+                &mut 0,
+            ),
+            Err(Error::TransactionTooLarge(_))
+        );
+    }
 }
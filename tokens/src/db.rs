@@ -127,7 +127,62 @@ pub fn set_transaction_info(
         lockup_date,
     };
     let signature = transaction.signatures[0];
-    db.set(&signature.to_string(), &transaction_info)?;
+    db.set(&transaction_info_key(&signature, 0), &transaction_info)?;
+    Ok(())
+}
+
+/// Returns the db key of the `index`th recipient paid by the transaction
+/// with `signature`.
+///
+/// The first recipient is keyed by the bare signature, so a db written by a
+/// distribution that doesn't pack transfers looks exactly like it used to.
+fn transaction_info_key(signature: &Signature, index: usize) -> String {
+    if index == 0 {
+        signature.to_string()
+    } else {
+        format!("{signature}:{index}")
+    }
+}
+
+/// Returns the db keys of every recipient paid by the transaction with
+/// `signature`.
+fn transaction_info_keys(db: &PickleDb, signature: &Signature) -> Vec<String> {
+    (0..)
+        .map(|index| transaction_info_key(signature, index))
+        .take_while(|key| db.exists(key))
+        .collect()
+}
+
+/// Records a transaction paying several recipients, one `TransactionInfo`
+/// per recipient.
+pub fn set_packed_transaction_info(
+    db: &mut PickleDb,
+    recipients: &[(Pubkey, u64)],
+    transaction: &Transaction,
+    last_valid_block_height: u64,
+) -> Result<(), Error> {
+    let signature = transaction.signatures[0];
+    for (index, (recipient, amount)) in recipients.iter().enumerate() {
+        let transaction_info = TransactionInfo {
+            recipient: *recipient,
+            amount: *amount,
+            new_stake_account_address: None,
+            finalized_date: None,
+            transaction: transaction.clone(),
+            last_valid_block_height,
+            lockup_date: None,
+        };
+        db.set(&transaction_info_key(&signature, index), &transaction_info)?;
+    }
+    Ok(())
+}
+
+/// Removes every record of the transaction with `signature`, so its
+/// recipients will be paid again by the next run.
+pub fn remove_transaction_info(db: &mut PickleDb, signature: &Signature) -> Result<(), Error> {
+    for key in transaction_info_keys(db, signature) {
+        db.rem(&key)?;
+    }
     Ok(())
 }
 
@@ -170,14 +225,17 @@ pub fn update_finalized_transaction(
         eprintln!("Error in transaction with signature {signature}: {e}");
         eprintln!("Discarding transaction record");
         eprintln!();
-        db.rem(&signature.to_string())?;
+        remove_transaction_info(db, signature)?;
         return Ok(None);
     }
 
     // Transaction is rooted. Set the finalized date in the database.
-    let mut transaction_info = db.get::<TransactionInfo>(&signature.to_string()).unwrap();
-    transaction_info.finalized_date = Some(Utc::now());
-    db.set(&signature.to_string(), &transaction_info)?;
+    let finalized_date = Some(Utc::now());
+    for key in transaction_info_keys(db, signature) {
+        let mut transaction_info = db.get::<TransactionInfo>(&key).unwrap();
+        transaction_info.finalized_date = finalized_date;
+        db.set(&key, &transaction_info)?;
+    }
     Ok(None)
 }
 
@@ -380,4 +438,56 @@ mod tests {
             .finalized_date
             .is_some());
     }
+
+    #[test]
+    fn test_packed_transaction_info() {
+        let mut db =
+            PickleDb::new_yaml(NamedTempFile::new().unwrap(), PickleDbDumpPolicy::NeverDump);
+        let transaction = Transaction {
+            signatures: vec![Signature::from([1; 64])],
+            ..Transaction::default()
+        };
+        let signature = transaction.signatures[0];
+        let recipients: Vec<_> = (1..=3)
+            .map(|amount| (Pubkey::new_unique(), amount))
+            .collect();
+        set_packed_transaction_info(&mut db, &recipients, &transaction, 42).unwrap();
+        db.set(
+            &Signature::default().to_string(),
+            &TransactionInfo::default(),
+        )
+        .unwrap();
+
+        let mut transaction_infos = read_transaction_infos(&db);
+        transaction_infos.retain(|info| info.transaction.signatures[0] == signature);
+        transaction_infos.sort_by_key(|info| info.amount);
+        assert_eq!(
+            transaction_infos
+                .iter()
+                .map(|info| (info.recipient, info.amount))
+                .collect::<Vec<_>>(),
+            recipients
+        );
+
+        // Finalizing the transaction finalizes every recipient
+        let transaction_status = TransactionStatus {
+            slot: 0,
+            confirmations: None,
+            err: None,
+            status: Ok(()),
+            confirmation_status: Some(TransactionConfirmationStatus::Finalized),
+        };
+        update_finalized_transaction(&mut db, &signature, Some(transaction_status), 0, 0).unwrap();
+        assert!(read_transaction_infos(&db)
+            .iter()
+            .filter(|info| info.transaction.signatures[0] == signature)
+            .all(|info| info.finalized_date.is_some()));
+
+        // Removing the transaction leaves other records alone
+        remove_transaction_info(&mut db, &signature).unwrap();
+        assert_eq!(
+            read_transaction_infos(&db),
+            vec![TransactionInfo::default()]
+        );
+    }
 }
@@ -6,3 +6,4 @@ mod db;
 pub mod spl_token;
 pub mod stake;
 pub mod token_display;
+pub mod tpu_sender;
@@ -28,7 +28,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         Config::default()
     };
     let json_rpc_url = normalize_to_url_if_moniker(command_args.url.unwrap_or(config.json_rpc_url));
-    let client = RpcClient::new(json_rpc_url);
+    let client = RpcClient::new(json_rpc_url.clone());
 
     let exit = Arc::new(AtomicBool::default());
     // Initialize CTRL-C handler to ensure db changes are written before exit.
@@ -44,12 +44,19 @@ fn main() -> Result<(), Box<dyn Error>> {
         Command::DistributeTokens(mut args) => {
             spl_token::update_token_args(&client, &mut args.spl_token_args)?;
             stake::update_stake_args(&client, &mut args.stake_args)?;
+            if let Some(tpu_client_args) = &mut args.tpu_client_args {
+                tpu_client_args.websocket_url = Config::compute_websocket_url(&json_rpc_url);
+            }
             commands::process_allocations(&client, &args, exit)?;
         }
         Command::Balances(mut args) => {
             spl_token::update_decimals(&client, &mut args.spl_token_args)?;
             commands::process_balances(&client, &args, exit)?;
         }
+        Command::Verify(mut args) => {
+            spl_token::update_decimals(&client, &mut args.spl_token_args)?;
+            commands::process_verify(&client, &args, exit)?;
+        }
         Command::TransactionLog(args) => {
             commands::process_transaction_log(&args)?;
         }
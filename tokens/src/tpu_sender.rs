@@ -0,0 +1,144 @@
+//! Concurrent transaction submission through `solana-tpu-client-next`.
+
+use {
+    crate::commands::Error,
+    async_trait::async_trait,
+    log::*,
+    solana_rpc_client::nonblocking::rpc_client::RpcClient,
+    solana_tpu_client_next::{
+        connection_workers_scheduler::{
+            BindTarget, ConnectionWorkersSchedulerConfig, ConnectionWorkersSchedulerError, Fanout,
+            WorkersBroadcaster,
+        },
+        leader_updater::create_leader_updater,
+        transaction_batch::TransactionBatch,
+        workers_cache::WorkersCache,
+        ConnectionWorkersScheduler, SendTransactionStats,
+    },
+    solana_transaction::Transaction,
+    std::{net::SocketAddr, sync::Arc},
+    tokio::{
+        runtime::Runtime,
+        sync::{mpsc, watch},
+        task::JoinHandle,
+    },
+    tokio_util::sync::CancellationToken,
+};
+
+/// Number of transactions handed to the connection workers at once.
+pub const TPU_BATCH_SIZE: usize = 64;
+
+type SchedulerHandle =
+    JoinHandle<Result<Arc<SendTransactionStats>, ConnectionWorkersSchedulerError>>;
+
+/// Waits for room in each worker's channel instead of dropping batches, so
+/// that a large distribution is throttled to the speed of the connections.
+struct BlockingBroadcaster;
+
+#[async_trait]
+impl WorkersBroadcaster for BlockingBroadcaster {
+    async fn send_to_workers(
+        workers: &mut WorkersCache,
+        leaders: &[SocketAddr],
+        transaction_batch: TransactionBatch,
+    ) -> Result<(), ConnectionWorkersSchedulerError> {
+        for leader in leaders {
+            if !workers.contains(leader) {
+                continue;
+            }
+            if let Err(err) = workers
+                .send_transactions_to_address(leader, transaction_batch.clone())
+                .await
+            {
+                warn!("Failed to send transactions to {leader}: {err}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sends transactions straight to the upcoming leaders over QUIC.
+///
+/// Delivery is best effort: transactions are neither confirmed nor retried,
+/// that is left to the transaction db finalization.
+pub struct TpuSender {
+    runtime: Runtime,
+    sender: mpsc::Sender<TransactionBatch>,
+    scheduler: SchedulerHandle,
+    cancel: CancellationToken,
+}
+
+impl TpuSender {
+    pub fn new(json_rpc_url: String, websocket_url: String) -> Result<Self, Error> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let rpc_client = Arc::new(RpcClient::new(json_rpc_url));
+        let leader_updater = runtime
+            .block_on(create_leader_updater(rpc_client, websocket_url, None))
+            .map_err(|err| Error::TpuClientError(err.to_string()))?;
+
+        let (sender, receiver) = mpsc::channel(16);
+        // The sender never changes identity
+        let (_update_identity_sender, update_identity_receiver) = watch::channel(None);
+        let cancel = CancellationToken::new();
+        let scheduler = ConnectionWorkersScheduler::new(
+            leader_updater,
+            receiver,
+            update_identity_receiver,
+            cancel.clone(),
+        );
+        let config = ConnectionWorkersSchedulerConfig {
+            bind: BindTarget::Address("0.0.0.0:0".parse().unwrap()),
+            stake_identity: None,
+            num_connections: 8,
+            skip_check_transaction_age: true,
+            worker_channel_size: 2,
+            max_reconnect_attempts: 4,
+            leaders_fanout: Fanout {
+                send: 2,
+                connect: 3,
+            },
+        };
+        let scheduler =
+            runtime.spawn(scheduler.run_with_broadcaster::<BlockingBroadcaster>(config));
+        Ok(Self {
+            runtime,
+            sender,
+            scheduler,
+            cancel,
+        })
+    }
+
+    /// Queues `transactions` for sending, blocking while the connection
+    /// workers are busy.
+    pub fn send_transactions(&self, transactions: &[Transaction]) -> Result<(), Error> {
+        let wire_transactions = transactions
+            .iter()
+            .map(|transaction| bincode::serialize(transaction).unwrap())
+            .collect::<Vec<_>>();
+        self.sender
+            .blocking_send(TransactionBatch::new(wire_transactions))
+            .map_err(|_| Error::TpuClientError("connection workers stopped".to_string()))
+    }
+
+    /// Waits for the queued transactions to be sent, then closes every
+    /// connection.
+    pub fn shutdown(self) -> Result<(), Error> {
+        let Self {
+            runtime,
+            sender,
+            scheduler,
+            cancel,
+        } = self;
+        // The scheduler drains the channel before exiting once it is closed
+        drop(sender);
+        let result = runtime.block_on(scheduler);
+        cancel.cancel();
+        match result {
+            Ok(Ok(_stats)) => Ok(()),
+            Ok(Err(err)) => Err(Error::TpuClientError(err.to_string())),
+            Err(err) => Err(Error::TpuClientError(err.to_string())),
+        }
+    }
+}
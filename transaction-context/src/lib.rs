@@ -610,6 +610,24 @@ pub struct TransactionReturnData {
     pub data: Vec<u8>,
 }
 
+/// Compute and account data usage of a single instruction or CPI
+#[cfg_attr(
+    feature = "serde",
+    derive(serde_derive::Deserialize, serde_derive::Serialize)
+)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstructionInvocation {
+    pub program_id: Pubkey,
+    /// Top level instructions are at `TRANSACTION_LEVEL_STACK_HEIGHT`
+    pub stack_height: usize,
+    /// Includes the compute units of nested invocations
+    pub compute_units_consumed: u64,
+    /// Compute units charged by syscalls made directly by this invocation
+    pub syscall_units_consumed: u64,
+    /// Includes the account data changes of nested invocations
+    pub accounts_data_len_delta: i64,
+}
+
 /// Loaded instruction shared between runtime and programs.
 ///
 /// This context is valid for the entire duration of a (possibly cross program) instruction being processed.
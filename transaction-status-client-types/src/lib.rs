@@ -21,7 +21,7 @@ use {
     solana_reward_info::RewardType,
     solana_signature::Signature,
     solana_transaction::versioned::{TransactionVersion, VersionedTransaction},
-    solana_transaction_context::TransactionReturnData,
+    solana_transaction_context::{InstructionInvocation, TransactionReturnData},
     solana_transaction_error::{TransactionError, TransactionResult},
     thiserror::Error,
 };
@@ -430,6 +430,28 @@ impl From<TransactionReturnData> for UiTransactionReturnData {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiInstructionInvocation {
+    pub program_id: String,
+    pub stack_height: usize,
+    pub compute_units_consumed: u64,
+    pub syscall_units_consumed: u64,
+    pub accounts_data_len_delta: i64,
+}
+
+impl From<InstructionInvocation> for UiInstructionInvocation {
+    fn from(invocation: InstructionInvocation) -> Self {
+        Self {
+            program_id: invocation.program_id.to_string(),
+            stack_height: invocation.stack_height,
+            compute_units_consumed: invocation.compute_units_consumed,
+            syscall_units_consumed: invocation.syscall_units_consumed,
+            accounts_data_len_delta: invocation.accounts_data_len_delta,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, Hash, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum UiReturnDataEncoding {
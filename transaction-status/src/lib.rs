@@ -10,9 +10,9 @@ pub use {
         TransactionBinaryEncoding, TransactionConfirmationStatus, TransactionDetails,
         TransactionStatus, TransactionStatusMeta, TransactionTokenBalance, UiAccountsList,
         UiAddressTableLookup, UiCompiledInstruction, UiConfirmedBlock, UiInnerInstructions,
-        UiInstruction, UiLoadedAddresses, UiMessage, UiParsedInstruction, UiParsedMessage,
-        UiPartiallyDecodedInstruction, UiRawMessage, UiReturnDataEncoding, UiTransaction,
-        UiTransactionEncoding, UiTransactionReturnData, UiTransactionStatusMeta,
+        UiInstruction, UiInstructionInvocation, UiLoadedAddresses, UiMessage, UiParsedInstruction,
+        UiParsedMessage, UiPartiallyDecodedInstruction, UiRawMessage, UiReturnDataEncoding,
+        UiTransaction, UiTransactionEncoding, UiTransactionReturnData, UiTransactionStatusMeta,
         UiTransactionTokenBalance,
     },
 };
@@ -52,6 +52,7 @@ pub mod parse_address_lookup_table;
 pub mod parse_associated_token;
 pub mod parse_bpf_loader;
 pub mod parse_instruction;
+pub mod parse_multisig;
 pub mod parse_stake;
 pub mod parse_system;
 pub mod parse_token;
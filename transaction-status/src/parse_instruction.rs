@@ -4,6 +4,7 @@ use {
         parse_address_lookup_table::parse_address_lookup_table,
         parse_associated_token::parse_associated_token,
         parse_bpf_loader::{parse_bpf_loader, parse_bpf_upgradeable_loader},
+        parse_multisig::parse_multisig,
         parse_stake::parse_stake,
         parse_system::parse_system,
         parse_token::parse_token,
@@ -40,6 +41,7 @@ static PARSABLE_PROGRAM_IDS: std::sync::LazyLock<HashMap<Pubkey, ParsableProgram
                 solana_sdk_ids::bpf_loader_upgradeable::id(),
                 ParsableProgram::BpfUpgradeableLoader,
             ),
+            (solana_multisig_interface::id(), ParsableProgram::Multisig),
             (stake::id(), ParsableProgram::Stake),
             (system_program::id(), ParsableProgram::System),
             (vote::id(), ParsableProgram::Vote),
@@ -86,6 +88,7 @@ pub enum ParsableProgram {
     SplToken,
     BpfLoader,
     BpfUpgradeableLoader,
+    Multisig,
     Stake,
     System,
     Vote,
@@ -115,6 +118,9 @@ pub fn parse(
         ParsableProgram::BpfUpgradeableLoader => {
             serde_json::to_value(parse_bpf_upgradeable_loader(instruction, account_keys)?)?
         }
+        ParsableProgram::Multisig => {
+            serde_json::to_value(parse_multisig(instruction, account_keys)?)?
+        }
         ParsableProgram::Stake => serde_json::to_value(parse_stake(instruction, account_keys)?)?,
         ParsableProgram::System => serde_json::to_value(parse_system(instruction, account_keys)?)?,
         ParsableProgram::Vote => serde_json::to_value(parse_vote(instruction, account_keys)?)?,
@@ -0,0 +1,287 @@
+use {
+    crate::parse_instruction::{
+        check_num_accounts, ParsableProgram, ParseInstructionError, ParsedInstructionEnum,
+    },
+    bincode::deserialize,
+    serde_json::{json, Value},
+    solana_message::{compiled_instruction::CompiledInstruction, AccountKeys},
+    solana_multisig_interface::{instruction::MultisigInstruction, state::ProposedInstruction},
+};
+
+pub fn parse_multisig(
+    instruction: &CompiledInstruction,
+    account_keys: &AccountKeys,
+) -> Result<ParsedInstructionEnum, ParseInstructionError> {
+    let multisig_instruction: MultisigInstruction = deserialize(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::Multisig))?;
+    match instruction.accounts.iter().max() {
+        Some(index) if (*index as usize) < account_keys.len() => {}
+        _ => {
+            // Runtime should prevent this from ever happening
+            return Err(ParseInstructionError::InstructionKeyMismatch(
+                ParsableProgram::Multisig,
+            ));
+        }
+    }
+    match multisig_instruction {
+        MultisigInstruction::CreateMultisig { threshold, signers } => {
+            check_num_multisig_accounts(&instruction.accounts, 3)?;
+            let signers: Vec<String> = signers.iter().map(|signer| signer.to_string()).collect();
+            Ok(ParsedInstructionEnum {
+                instruction_type: "createMultisig".to_string(),
+                info: json!({
+                    "multisig": account_keys[instruction.accounts[0] as usize].to_string(),
+                    "payer": account_keys[instruction.accounts[1] as usize].to_string(),
+                    "systemProgram": account_keys[instruction.accounts[2] as usize].to_string(),
+                    "threshold": threshold,
+                    "signers": signers,
+                }),
+            })
+        }
+        MultisigInstruction::Propose { instructions } => {
+            check_num_multisig_accounts(&instruction.accounts, 5)?;
+            let instructions: Vec<Value> = instructions
+                .iter()
+                .map(parse_proposed_instruction)
+                .collect();
+            Ok(ParsedInstructionEnum {
+                instruction_type: "propose".to_string(),
+                info: json!({
+                    "multisig": account_keys[instruction.accounts[0] as usize].to_string(),
+                    "proposal": account_keys[instruction.accounts[1] as usize].to_string(),
+                    "proposer": account_keys[instruction.accounts[2] as usize].to_string(),
+                    "payer": account_keys[instruction.accounts[3] as usize].to_string(),
+                    "systemProgram": account_keys[instruction.accounts[4] as usize].to_string(),
+                    "instructions": instructions,
+                }),
+            })
+        }
+        MultisigInstruction::Approve => {
+            check_num_multisig_accounts(&instruction.accounts, 3)?;
+            Ok(ParsedInstructionEnum {
+                instruction_type: "approve".to_string(),
+                info: json!({
+                    "multisig": account_keys[instruction.accounts[0] as usize].to_string(),
+                    "proposal": account_keys[instruction.accounts[1] as usize].to_string(),
+                    "signer": account_keys[instruction.accounts[2] as usize].to_string(),
+                }),
+            })
+        }
+        MultisigInstruction::Execute => {
+            check_num_multisig_accounts(&instruction.accounts, 2)?;
+            Ok(ParsedInstructionEnum {
+                instruction_type: "execute".to_string(),
+                info: json!({
+                    "multisig": account_keys[instruction.accounts[0] as usize].to_string(),
+                    "proposal": account_keys[instruction.accounts[1] as usize].to_string(),
+                }),
+            })
+        }
+        MultisigInstruction::SetConfig { threshold, signers } => {
+            check_num_multisig_accounts(&instruction.accounts, 2)?;
+            let signers: Vec<String> = signers.iter().map(|signer| signer.to_string()).collect();
+            Ok(ParsedInstructionEnum {
+                instruction_type: "setConfig".to_string(),
+                info: json!({
+                    "multisig": account_keys[instruction.accounts[0] as usize].to_string(),
+                    "vault": account_keys[instruction.accounts[1] as usize].to_string(),
+                    "threshold": threshold,
+                    "signers": signers,
+                }),
+            })
+        }
+    }
+}
+
+fn parse_proposed_instruction(instruction: &ProposedInstruction) -> Value {
+    let accounts: Vec<Value> = instruction
+        .accounts
+        .iter()
+        .map(|account_meta| {
+            json!({
+                "pubkey": account_meta.pubkey.to_string(),
+                "isSigner": account_meta.is_signer,
+                "isWritable": account_meta.is_writable,
+            })
+        })
+        .collect();
+    json!({
+        "programId": instruction.program_id.to_string(),
+        "accounts": accounts,
+        "data": bs58::encode(&instruction.data).into_string(),
+    })
+}
+
+fn check_num_multisig_accounts(accounts: &[u8], num: usize) -> Result<(), ParseInstructionError> {
+    check_num_accounts(accounts, num, ParsableProgram::Multisig)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        solana_message::Message,
+        solana_multisig_interface::{get_proposal_address, get_vault_address, instruction},
+        solana_pubkey::Pubkey,
+        solana_sdk_ids::system_program,
+        solana_system_interface::instruction as system_instruction,
+    };
+
+    #[test]
+    fn test_parse_create_multisig_ix() {
+        let multisig = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let signers = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let instruction = instruction::create_multisig(&multisig, &payer, 2, signers.clone());
+        let mut message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_multisig(
+                &message.instructions[0],
+                &AccountKeys::new(&message.account_keys, None)
+            )
+            .unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "createMultisig".to_string(),
+                info: json!({
+                    "multisig": multisig.to_string(),
+                    "payer": payer.to_string(),
+                    "systemProgram": system_program::id().to_string(),
+                    "threshold": 2,
+                    "signers": [signers[0].to_string(), signers[1].to_string()],
+                }),
+            }
+        );
+        assert!(parse_multisig(
+            &message.instructions[0],
+            &AccountKeys::new(&message.account_keys[0..2], None)
+        )
+        .is_err());
+        let keys = message.account_keys.clone();
+        message.instructions[0].accounts.pop();
+        assert!(parse_multisig(&message.instructions[0], &AccountKeys::new(&keys, None)).is_err());
+    }
+
+    #[test]
+    fn test_parse_propose_ix() {
+        let multisig = Pubkey::new_unique();
+        let proposer = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let vault = get_vault_address(&multisig);
+        let transfer_instruction = system_instruction::transfer(&vault, &recipient, 42);
+        let instruction = instruction::propose(
+            &multisig,
+            3,
+            &proposer,
+            &proposer,
+            vec![transfer_instruction.clone().into()],
+        );
+        let mut message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_multisig(
+                &message.instructions[0],
+                &AccountKeys::new(&message.account_keys, None)
+            )
+            .unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "propose".to_string(),
+                info: json!({
+                    "multisig": multisig.to_string(),
+                    "proposal": get_proposal_address(&multisig, 3).to_string(),
+                    "proposer": proposer.to_string(),
+                    "payer": proposer.to_string(),
+                    "systemProgram": system_program::id().to_string(),
+                    "instructions": [{
+                        "programId": system_program::id().to_string(),
+                        "accounts": [
+                            {
+                                "pubkey": vault.to_string(),
+                                "isSigner": true,
+                                "isWritable": true,
+                            },
+                            {
+                                "pubkey": recipient.to_string(),
+                                "isSigner": false,
+                                "isWritable": true,
+                            },
+                        ],
+                        "data": bs58::encode(&transfer_instruction.data).into_string(),
+                    }],
+                }),
+            }
+        );
+        let keys = message.account_keys.clone();
+        message.instructions[0].accounts.pop();
+        assert!(parse_multisig(&message.instructions[0], &AccountKeys::new(&keys, None)).is_err());
+    }
+
+    #[test]
+    fn test_parse_approve_and_execute_ix() {
+        let multisig = Pubkey::new_unique();
+        let signer = Pubkey::new_unique();
+        let proposal = get_proposal_address(&multisig, 0);
+
+        let instruction = instruction::approve(&multisig, 0, &signer);
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_multisig(
+                &message.instructions[0],
+                &AccountKeys::new(&message.account_keys, None)
+            )
+            .unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "approve".to_string(),
+                info: json!({
+                    "multisig": multisig.to_string(),
+                    "proposal": proposal.to_string(),
+                    "signer": signer.to_string(),
+                }),
+            }
+        );
+
+        let transfer_instruction =
+            system_instruction::transfer(&get_vault_address(&multisig), &signer, 42);
+        let instruction = instruction::execute(&multisig, 0, &[transfer_instruction.into()]);
+        let mut message = Message::new(&[instruction], Some(&signer));
+        assert_eq!(
+            parse_multisig(
+                &message.instructions[0],
+                &AccountKeys::new(&message.account_keys, None)
+            )
+            .unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "execute".to_string(),
+                info: json!({
+                    "multisig": multisig.to_string(),
+                    "proposal": proposal.to_string(),
+                }),
+            }
+        );
+        let keys = message.account_keys.clone();
+        message.instructions[0].accounts.truncate(1);
+        assert!(parse_multisig(&message.instructions[0], &AccountKeys::new(&keys, None)).is_err());
+    }
+
+    #[test]
+    fn test_parse_set_config_ix() {
+        let multisig = Pubkey::new_unique();
+        let signers = vec![Pubkey::new_unique()];
+        let instruction = instruction::set_config(&multisig, 1, signers.clone());
+        let message = Message::new(&[instruction], None);
+        assert_eq!(
+            parse_multisig(
+                &message.instructions[0],
+                &AccountKeys::new(&message.account_keys, None)
+            )
+            .unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "setConfig".to_string(),
+                info: json!({
+                    "multisig": multisig.to_string(),
+                    "vault": get_vault_address(&multisig).to_string(),
+                    "threshold": 1,
+                    "signers": [signers[0].to_string()],
+                }),
+            }
+        );
+    }
+}
@@ -39,6 +39,9 @@ pub fn parse_token(
             ));
         }
     }
+    if instruction.data.first() == Some(&spl_token::instruction::BATCH_DISCRIMINATOR) {
+        return parse_batch(instruction, account_keys);
+    }
     if let Ok(token_instruction) = TokenInstruction::unpack(&instruction.data) {
         match token_instruction {
             TokenInstruction::InitializeMint {
@@ -846,6 +849,44 @@ impl From<ExtensionType> for UiExtensionType {
     }
 }
 
+// Expands a p-token `Batch` instruction into its parsed inner instructions, which consume the
+// accounts of the batch in order
+fn parse_batch(
+    instruction: &CompiledInstruction,
+    account_keys: &AccountKeys,
+) -> Result<ParsedInstructionEnum, ParseInstructionError> {
+    let batch = spl_token::instruction::unpack_batch(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::SplToken))?;
+    let mut accounts = instruction.accounts.as_slice();
+    let mut instructions = Vec::with_capacity(batch.len());
+    for (num_accounts, data) in batch {
+        if data.first() == Some(&spl_token::instruction::BATCH_DISCRIMINATOR) {
+            return Err(ParseInstructionError::InstructionNotParsable(
+                ParsableProgram::SplToken,
+            ));
+        }
+        check_num_token_accounts(accounts, num_accounts)?;
+        let (inner_accounts, remaining_accounts) = accounts.split_at(num_accounts);
+        accounts = remaining_accounts;
+        let parsed = parse_token(
+            &CompiledInstruction {
+                program_id_index: instruction.program_id_index,
+                accounts: inner_accounts.to_vec(),
+                data: data.to_vec(),
+            },
+            account_keys,
+        )?;
+        instructions.push(json!({
+            "type": parsed.instruction_type,
+            "info": parsed.info,
+        }));
+    }
+    Ok(ParsedInstructionEnum {
+        instruction_type: "batch".to_string(),
+        info: json!({ "instructions": instructions }),
+    })
+}
+
 fn parse_signers(
     map: &mut Map<String, Value>,
     last_nonsigner_index: usize,
@@ -1802,6 +1843,60 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_parse_batch() {
+        let keys: Vec<Pubkey> = repeat_with(solana_pubkey::new_rand).take(5).collect();
+        let instructions = vec![
+            spl_token::instruction::transfer_checked(
+                &spl_token::id(),
+                &keys[0],
+                &keys[1],
+                &keys[2],
+                &keys[3],
+                &[],
+                42,
+                2,
+            )
+            .unwrap(),
+            spl_token::instruction::burn(&spl_token::id(), &keys[4], &keys[1], &keys[3], &[], 7)
+                .unwrap(),
+        ];
+        let expected = instructions
+            .iter()
+            .map(|instruction| {
+                let message = Message::new(&[instruction.clone()], None);
+                let parsed = parse_token(
+                    &message.instructions[0],
+                    &AccountKeys::new(&message.account_keys, None),
+                )
+                .unwrap();
+                json!({ "type": parsed.instruction_type, "info": parsed.info })
+            })
+            .collect::<Vec<_>>();
+
+        let batch_ix = spl_token::instruction::batch(&spl_token::id(), &instructions).unwrap();
+        let message = Message::new(&[batch_ix], None);
+        let compiled_instruction = &message.instructions[0];
+        let account_keys = AccountKeys::new(&message.account_keys, None);
+        assert_eq!(
+            parse_token(compiled_instruction, &account_keys).unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "batch".to_string(),
+                info: json!({ "instructions": expected }),
+            }
+        );
+
+        // not enough accounts for the last instruction
+        let mut truncated = compiled_instruction.clone();
+        truncated.accounts.pop();
+        assert!(parse_token(&truncated, &account_keys).is_err());
+
+        // malformed batch data
+        let mut malformed = compiled_instruction.clone();
+        malformed.data.pop();
+        assert!(parse_token(&malformed, &account_keys).is_err());
+    }
+
     fn test_token_ix_not_enough_keys(program_id: &Pubkey) {
         let keys: Vec<Pubkey> = repeat_with(solana_pubkey::new_rand).take(10).collect();
 
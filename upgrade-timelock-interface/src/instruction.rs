@@ -0,0 +1,206 @@
+//! Instructions of the upgrade timelock program
+
+use {
+    crate::{get_timelock_address, id},
+    serde_derive::{Deserialize, Serialize},
+    solana_hash::Hash,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::{loader_v4, system_program},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UpgradeTimelockInstruction {
+    /// Creates the timelock account of a loader-v4 program and transfers the
+    /// authority of the program to it.
+    ///
+    /// # Account references
+    ///   0. `[writable]` The timelock account, see `get_timelock_address`.
+    ///   1. `[writable]` The program account.
+    ///   2. `[signer]` The current authority of the program.
+    ///   3. `[writable, signer]` The payer for the timelock account.
+    ///   4. `[]` The system program.
+    ///   5. `[]` The loader-v4 program.
+    Initialize {
+        /// Address which is allowed to stage and cancel upgrades
+        authority: Pubkey,
+        /// Number of slots between staging an upgrade and applying it
+        delay_slots: u64,
+    },
+
+    /// Stages an upgrade from a buffer, replacing any pending upgrade.
+    ///
+    /// The upgrade can be applied once the delay of the timelock has passed,
+    /// provided the program data in the buffer still has the given hash.
+    ///
+    /// # Account references
+    ///   0. `[writable]` The timelock account.
+    ///   1. `[]` The buffer, a loader-v4 account holding the new program data.
+    ///   2. `[signer]` The timelock authority.
+    StageUpgrade {
+        /// SHA-256 hash of the program data in the buffer
+        buffer_hash: Hash,
+    },
+
+    /// Cancels the pending upgrade.
+    ///
+    /// # Account references
+    ///   0. `[writable]` The timelock account.
+    ///   1. `[signer]` The timelock authority.
+    CancelUpgrade,
+
+    /// Applies the pending upgrade once its delay has passed.
+    ///
+    /// The program is retracted, resized to the length of the buffer, overwritten
+    /// with the program data of the buffer and deployed again. The program account
+    /// needs to hold enough lamports to be rent exempt at its new size. Anyone can
+    /// apply a pending upgrade.
+    ///
+    /// # Account references
+    ///   0. `[writable]` The timelock account.
+    ///   1. `[writable]` The program account.
+    ///   2. `[]` The buffer of the pending upgrade.
+    ///   3. `[]` The loader-v4 program.
+    ApplyUpgrade,
+
+    /// Stages a release of the program authority to a new authority,
+    /// replacing any pending release.
+    ///
+    /// The release can be applied once the delay of the timelock has passed.
+    ///
+    /// # Account references
+    ///   0. `[writable]` The timelock account.
+    ///   1. `[signer]` The timelock authority.
+    StageRelease {
+        /// Address the authority of the program is handed over to
+        new_authority: Pubkey,
+    },
+
+    /// Cancels the pending release.
+    ///
+    /// # Account references
+    ///   0. `[writable]` The timelock account.
+    ///   1. `[signer]` The timelock authority.
+    CancelRelease,
+
+    /// Applies the pending release once its delay has passed.
+    ///
+    /// The authority of the program is handed over to the new authority and
+    /// the timelock account is closed, discarding any pending upgrade. The new
+    /// authority can then upgrade the program without a delay, or finalize it
+    /// through the loader-v4 program.
+    ///
+    /// # Account references
+    ///   0. `[writable]` The timelock account.
+    ///   1. `[writable]` The program account.
+    ///   2. `[writable, signer]` The new authority of the pending release,
+    ///      which receives the lamports of the timelock account.
+    ///   3. `[]` The loader-v4 program.
+    ReleaseAuthority,
+}
+
+pub fn initialize(
+    program_address: &Pubkey,
+    current_authority: &Pubkey,
+    timelock_authority: &Pubkey,
+    delay_slots: u64,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bincode(
+        id(),
+        &UpgradeTimelockInstruction::Initialize {
+            authority: *timelock_authority,
+            delay_slots,
+        },
+        vec![
+            AccountMeta::new(get_timelock_address(program_address), false),
+            AccountMeta::new(*program_address, false),
+            AccountMeta::new_readonly(*current_authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(loader_v4::id(), false),
+        ],
+    )
+}
+
+pub fn stage_upgrade(
+    program_address: &Pubkey,
+    timelock_authority: &Pubkey,
+    buffer_address: &Pubkey,
+    buffer_hash: Hash,
+) -> Instruction {
+    Instruction::new_with_bincode(
+        id(),
+        &UpgradeTimelockInstruction::StageUpgrade { buffer_hash },
+        vec![
+            AccountMeta::new(get_timelock_address(program_address), false),
+            AccountMeta::new_readonly(*buffer_address, false),
+            AccountMeta::new_readonly(*timelock_authority, true),
+        ],
+    )
+}
+
+pub fn cancel_upgrade(program_address: &Pubkey, timelock_authority: &Pubkey) -> Instruction {
+    Instruction::new_with_bincode(
+        id(),
+        &UpgradeTimelockInstruction::CancelUpgrade,
+        vec![
+            AccountMeta::new(get_timelock_address(program_address), false),
+            AccountMeta::new_readonly(*timelock_authority, true),
+        ],
+    )
+}
+
+pub fn apply_upgrade(program_address: &Pubkey, buffer_address: &Pubkey) -> Instruction {
+    Instruction::new_with_bincode(
+        id(),
+        &UpgradeTimelockInstruction::ApplyUpgrade,
+        vec![
+            AccountMeta::new(get_timelock_address(program_address), false),
+            AccountMeta::new(*program_address, false),
+            AccountMeta::new_readonly(*buffer_address, false),
+            AccountMeta::new_readonly(loader_v4::id(), false),
+        ],
+    )
+}
+
+pub fn stage_release(
+    program_address: &Pubkey,
+    timelock_authority: &Pubkey,
+    new_authority: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bincode(
+        id(),
+        &UpgradeTimelockInstruction::StageRelease {
+            new_authority: *new_authority,
+        },
+        vec![
+            AccountMeta::new(get_timelock_address(program_address), false),
+            AccountMeta::new_readonly(*timelock_authority, true),
+        ],
+    )
+}
+
+pub fn cancel_release(program_address: &Pubkey, timelock_authority: &Pubkey) -> Instruction {
+    Instruction::new_with_bincode(
+        id(),
+        &UpgradeTimelockInstruction::CancelRelease,
+        vec![
+            AccountMeta::new(get_timelock_address(program_address), false),
+            AccountMeta::new_readonly(*timelock_authority, true),
+        ],
+    )
+}
+
+pub fn release_authority(program_address: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    Instruction::new_with_bincode(
+        id(),
+        &UpgradeTimelockInstruction::ReleaseAuthority,
+        vec![
+            AccountMeta::new(get_timelock_address(program_address), false),
+            AccountMeta::new(*program_address, false),
+            AccountMeta::new(*new_authority, true),
+            AccountMeta::new_readonly(loader_v4::id(), false),
+        ],
+    )
+}
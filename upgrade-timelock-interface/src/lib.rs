@@ -0,0 +1,21 @@
+//! Interface of the upgrade timelock program.
+//!
+//! The upgrade timelock program takes over the authority of a loader-v4 program.
+//! From then on, an upgrade has to be staged together with the hash of the buffer
+//! it is going to be copied from, and it can only be applied once the delay of the
+//! timelock has passed. This gives users of the program a notice period in which
+//! they can inspect the pending upgrade before the program changes under them.
+
+pub mod instruction;
+pub mod state;
+
+use solana_pubkey::Pubkey;
+
+solana_pubkey::declare_id!("UpgradeTimeLock1111111111111111111111111111");
+
+/// Returns the address of the timelock account of `program_address`.
+///
+/// The timelock account is also the loader-v4 authority of the program.
+pub fn get_timelock_address(program_address: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_address.as_ref()], &id()).0
+}
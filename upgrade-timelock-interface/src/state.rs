@@ -0,0 +1,103 @@
+//! State of a timelock account
+
+use {
+    serde_derive::{Deserialize, Serialize},
+    solana_clock::Slot,
+    solana_hash::Hash,
+    solana_instruction::error::InstructionError,
+    solana_pubkey::Pubkey,
+};
+
+/// An upgrade which was staged and can be applied from `earliest_slot` on
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingUpgrade {
+    /// Loader-v4 account the new program data is copied from
+    pub buffer: Pubkey,
+    /// SHA-256 hash of the program data in `buffer`
+    pub buffer_hash: Hash,
+    /// Slot in which the upgrade was staged
+    pub staged_slot: Slot,
+    /// First slot in which the upgrade can be applied
+    pub earliest_slot: Slot,
+}
+
+/// A release of the program authority which was staged and can be applied from
+/// `earliest_slot` on
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingRelease {
+    /// Address the authority of the program is handed over to
+    pub new_authority: Pubkey,
+    /// Slot in which the release was staged
+    pub staged_slot: Slot,
+    /// First slot in which the release can be applied
+    pub earliest_slot: Slot,
+}
+
+/// State of the timelock account of a program
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeTimelock {
+    /// The program guarded by this timelock
+    pub program: Pubkey,
+    /// Address which is allowed to stage and cancel upgrades
+    pub authority: Pubkey,
+    /// Number of slots between staging an upgrade and applying it
+    pub delay_slots: u64,
+    /// The currently staged upgrade, if any
+    pub pending_upgrade: Option<PendingUpgrade>,
+    /// The currently staged release of the program authority, if any
+    pub pending_release: Option<PendingRelease>,
+}
+
+impl UpgradeTimelock {
+    /// Size of the serialized state, including a pending upgrade and release
+    pub const fn size_of() -> usize {
+        202 // see test_upgrade_timelock_size_of
+    }
+
+    pub fn deserialize(data: &[u8]) -> Result<Self, InstructionError> {
+        bincode::deserialize(data).map_err(|_| InstructionError::InvalidAccountData)
+    }
+
+    pub fn serialize(&self, data: &mut [u8]) -> Result<(), InstructionError> {
+        bincode::serialize_into(data, self).map_err(|_| InstructionError::AccountDataTooSmall)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrade_timelock_size_of() {
+        let timelock = UpgradeTimelock {
+            program: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            delay_slots: u64::MAX,
+            pending_upgrade: Some(PendingUpgrade {
+                buffer: Pubkey::new_unique(),
+                buffer_hash: Hash::new_from_array([1; 32]),
+                staged_slot: u64::MAX,
+                earliest_slot: u64::MAX,
+            }),
+            pending_release: Some(PendingRelease {
+                new_authority: Pubkey::new_unique(),
+                staged_slot: u64::MAX,
+                earliest_slot: u64::MAX,
+            }),
+        };
+        let size = bincode::serialized_size(&timelock).unwrap();
+        assert_eq!(size as usize, UpgradeTimelock::size_of());
+
+        let mut data = vec![0; UpgradeTimelock::size_of()];
+        timelock.serialize(&mut data).unwrap();
+        assert_eq!(UpgradeTimelock::deserialize(&data).unwrap(), timelock);
+
+        let timelock = UpgradeTimelock {
+            pending_upgrade: None,
+            pending_release: None,
+            ..timelock
+        };
+        timelock.serialize(&mut data).unwrap();
+        assert_eq!(UpgradeTimelock::deserialize(&data).unwrap(), timelock);
+    }
+}
@@ -913,6 +913,12 @@ pub struct StakedNodesOverrides {
     pub staked_map_id: HashMap<Pubkey, u64>,
 }
 
+#[derive(Default, Deserialize, Clone)]
+pub struct UnstakedStreamQuotas {
+    #[serde(deserialize_with = "deserialize_pubkey_map")]
+    pub stream_quota_map_id: HashMap<Pubkey, u64>,
+}
+
 pub fn deserialize_pubkey_map<'de, D>(des: D) -> std::result::Result<HashMap<Pubkey, u64>, D::Error>
 where
     D: Deserializer<'de>,
@@ -939,6 +945,18 @@ pub fn load_staked_nodes_overrides(
     }
 }
 
+pub fn load_unstaked_stream_quotas(
+    path: &String,
+) -> std::result::Result<UnstakedStreamQuotas, Box<dyn error::Error>> {
+    debug!("Loading unstaked stream quotas configuration from {}", path);
+    if Path::new(&path).exists() {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_yaml::from_reader(file)?)
+    } else {
+        Err(format!("Unstaked stream quotas provided '{path}' a non-existing file path.").into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -34,7 +34,8 @@ use {
     solana_streamer::quic::{
         DEFAULT_MAX_CONNECTIONS_PER_IPADDR_PER_MINUTE, DEFAULT_MAX_QUIC_CONNECTIONS_PER_PEER,
         DEFAULT_MAX_STAKED_CONNECTIONS, DEFAULT_MAX_STREAMS_PER_MS,
-        DEFAULT_MAX_UNSTAKED_CONNECTIONS, DEFAULT_QUIC_ENDPOINTS,
+        DEFAULT_MAX_UNSTAKED_CONNECTIONS, DEFAULT_MAX_UNSTAKED_STREAMS_PER_IP_PREFIX_PER_SEC,
+        DEFAULT_QUIC_ENDPOINTS,
     },
     solana_tpu_client::tpu_client::{DEFAULT_TPU_CONNECTION_POOL_SIZE, DEFAULT_VOTE_USE_QUIC},
     std::{cmp::Ordering, path::PathBuf, str::FromStr},
@@ -234,6 +235,7 @@ pub struct DefaultArgs {
     pub tpu_max_fwd_staked_connections: String,
     pub tpu_max_fwd_unstaked_connections: String,
     pub tpu_max_streams_per_ms: String,
+    pub tpu_max_unstaked_streams_per_ip_prefix_per_second: String,
 
     pub num_quic_endpoints: String,
     pub vote_use_quic: String,
@@ -334,6 +336,8 @@ impl DefaultArgs {
                 .to_string(),
             tpu_max_fwd_unstaked_connections: 0.to_string(),
             tpu_max_streams_per_ms: DEFAULT_MAX_STREAMS_PER_MS.to_string(),
+            tpu_max_unstaked_streams_per_ip_prefix_per_second:
+                DEFAULT_MAX_UNSTAKED_STREAMS_PER_IP_PREFIX_PER_SEC.to_string(),
             num_quic_endpoints: DEFAULT_QUIC_ENDPOINTS.to_string(),
             rpc_max_request_body_size: MAX_REQUEST_BODY_SIZE.to_string(),
             banking_trace_dir_byte_limit: BANKING_TRACE_DIR_DEFAULT_BYTE_LIMIT.to_string(),
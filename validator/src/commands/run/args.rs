@@ -946,6 +946,29 @@ pub fn add_args(app: Command, default_args: &DefaultArgs) -> Command {
             .hide(hidden_unless_forced())
             .help("Controls the max number of streams for a TPU service."),
     )
+    .arg(
+        Arg::new("tpu_max_unstaked_streams_per_ip_prefix_per_second")
+            .long("tpu-max-unstaked-streams-per-ip-prefix-per-second")
+            .value_name("COUNT")
+            .default_value((*Box::leak(Box::new(default_args.tpu_max_unstaked_streams_per_ip_prefix_per_second.clone()))).as_str())
+            .hide(hidden_unless_forced())
+            .help(
+                "Controls the max number of streams per second from the unstaked nodes of an \
+                 IP prefix (/24 for IPv4, /56 for IPv6) for a TPU service. 0 disables the limit.",
+            ),
+    )
+    .arg(
+        Arg::new("tpu_unstaked_stream_quotas")
+            .long("tpu-unstaked-stream-quotas")
+            .value_name("PATH")
+            .help(
+                "Provide path to a yaml file with the number of QUIC streams per second \
+                 reserved for specific unstaked identities, e.g. RPC partners. The quota of an \
+                 identity is shared by all its connections and taken out of the streams allowed \
+                 for unstaked nodes, whose limits don't apply to these identities. \
+                 Format of the file: `stream_quota_map_id: {<pubkey>: <streams per second>}",
+            ),
+    )
     .arg(
         Arg::new("num_quic_endpoints")
             .long("num-quic-endpoints")
@@ -1683,6 +1706,34 @@ pub fn add_args(app: Command, default_args: &DefaultArgs) -> Command {
             .requires("retransmit_xdp_cpu_cores")
             .help("EXPERIMENTAL: Enable XDP zero copy. Requires hardware support"),
     )
+    .arg(
+        Arg::new("shred_fetch_xdp_interface")
+            .hide(hidden_unless_forced())
+            .long("experimental-shred-fetch-xdp-interface")
+            .value_name("INTERFACE")
+            .requires("shred_fetch_xdp_cpu_cores")
+            .help("EXPERIMENTAL: The network interface to receive shreds on through XDP"),
+    )
+    .arg(
+        Arg::new("shred_fetch_xdp_cpu_cores")
+            .hide(hidden_unless_forced())
+            .long("experimental-shred-fetch-xdp-cpu-cores")
+            .value_name("CPU_LIST")
+            .value_parser(clap::value_parser!(String))
+            .help(
+                "EXPERIMENTAL: Receive shreds through XDP on the specified CPU cores, one \
+                 receive queue of the interface per core. Falls back to recvmmsg if XDP is \
+                 not available",
+            ),
+    )
+    .arg(
+        Arg::new("shred_fetch_xdp_zero_copy")
+            .hide(hidden_unless_forced())
+            .long("experimental-shred-fetch-xdp-zero-copy")
+            .action(ArgAction::SetTrue)
+            .requires("shred_fetch_xdp_cpu_cores")
+            .help("EXPERIMENTAL: Enable XDP zero copy receive. Requires hardware support"),
+    )
     .arg(
         Arg::new("use_connection_cache")
             .long("use-connection-cache")
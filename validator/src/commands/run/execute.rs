@@ -1,6 +1,8 @@
 use {
     crate::{
-        admin_rpc_service::{self, load_staked_nodes_overrides, StakedNodesOverrides},
+        admin_rpc_service::{
+            self, load_staked_nodes_overrides, load_unstaked_stream_quotas, StakedNodesOverrides,
+        },
         bootstrap,
         cli::{self},
         commands::{run::args::RunArgs, FromClapArgMatches},
@@ -67,6 +69,7 @@ use {
     solana_streamer::{
         quic::{QuicServerParams, DEFAULT_TPU_COALESCE},
         socket::SocketAddrSpace,
+        xdp::XdpReceiverConfig,
     },
     solana_tpu_client::tpu_client::DEFAULT_TPU_ENABLE_UDP,
     solana_turbine::xdp::{set_cpu_affinity, XdpConfig},
@@ -181,6 +184,20 @@ pub fn execute(
         }
         .staked_map_id,
     ));
+    let tpu_unstaked_stream_quotas = matches
+        .get_one::<String>("tpu_unstaked_stream_quotas")
+        .map(|p| {
+            load_unstaked_stream_quotas(p)
+                .unwrap_or_else(|err| {
+                    error!(
+                        "Failed to load tpu-unstaked-stream-quotas from {}: {}",
+                        p, err
+                    );
+                    clap::Error::new(ErrorKind::InvalidValue).exit()
+                })
+                .stream_quota_map_id
+        })
+        .unwrap_or_default();
 
     let init_complete_file = matches.get_one::<String>("init_complete_file");
 
@@ -671,6 +688,15 @@ pub fn execute(
             xdp_zero_copy,
         )
     });
+    let shred_fetch_xdp_interface = matches.get_one::<String>("shred_fetch_xdp_interface");
+    let shred_fetch_xdp_zero_copy = matches.get_flag("shred_fetch_xdp_zero_copy");
+    let shred_fetch_xdp = matches.get_one::<String>("shred_fetch_xdp_cpu_cores").map(|cpus| {
+        XdpReceiverConfig::new(
+            shred_fetch_xdp_interface.map(|s| s.as_str()),
+            parse_cpu_ranges(cpus).unwrap(),
+            shred_fetch_xdp_zero_copy,
+        )
+    });
 
     let mut validator_config = ValidatorConfig {
         require_tower: matches.get_flag("require_tower"),
@@ -876,6 +902,7 @@ pub fn execute(
             .get_one::<String>("wen_restart_coordinator")
             .and_then(|s| s.parse::<Pubkey>().ok()),
         retransmit_xdp,
+        shred_fetch_xdp,
         use_tpu_client_next: !matches.get_flag("use_connection_cache"),
         ..ValidatorConfig::default()
     };
@@ -885,8 +912,14 @@ pub fn execute(
         .as_ref()
         .map(|xdp| xdp.cpus.clone())
         .unwrap_or_default()
-        .iter()
-        .cloned()
+        .into_iter()
+        .chain(
+            validator_config
+                .shred_fetch_xdp
+                .as_ref()
+                .map(|xdp| xdp.cpus.clone())
+                .unwrap_or_default(),
+        )
         .collect::<HashSet<_>>();
     if !reserved.is_empty() {
         let available = core_affinity::get_core_ids()
@@ -1055,7 +1088,9 @@ pub fn execute(
             });
         let mut archive_format = ArchiveFormat::from_cli_arg(&archive_format_str)
             .unwrap_or_else(|| panic!("Archive format not recognized: {archive_format_str}"));
-        if let ArchiveFormat::TarZstd { config } = &mut archive_format {
+        if let ArchiveFormat::TarZstd { config } | ArchiveFormat::TarZstdChunked { config } =
+            &mut archive_format
+        {
             config.compression_level = matches
                 .get_one::<String>("snapshot_zstd_compression_level")
                 .and_then(|s| s.parse::<i32>().ok())
@@ -1411,6 +1446,13 @@ pub fn execute(
             eprintln!("tpu_max_connections_per_ipaddr_per_minute is required");
             std::process::exit(1);
         });
+    let tpu_max_unstaked_streams_per_ip_prefix_per_second = matches
+        .get_one::<String>("tpu_max_unstaked_streams_per_ip_prefix_per_second")
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            eprintln!("tpu_max_unstaked_streams_per_ip_prefix_per_second is required");
+            std::process::exit(1);
+        });
     let max_streams_per_ms = matches
         .get_one::<String>("tpu_max_streams_per_ms")
         .and_then(|s| s.parse::<u64>().ok())
@@ -1528,6 +1570,9 @@ pub fn execute(
         max_unstaked_connections: tpu_max_unstaked_connections.try_into().unwrap(),
         max_streams_per_ms,
         max_connections_per_ipaddr_per_min: tpu_max_connections_per_ipaddr_per_minute,
+        max_unstaked_streams_per_ip_prefix_per_sec:
+            tpu_max_unstaked_streams_per_ip_prefix_per_second,
+        unstaked_stream_quotas: tpu_unstaked_stream_quotas.clone(),
         coalesce: tpu_coalesce,
         num_threads: tpu_transaction_receive_threads,
         ..Default::default()
@@ -1539,6 +1584,9 @@ pub fn execute(
         max_unstaked_connections: tpu_max_fwd_unstaked_connections.try_into().unwrap(),
         max_streams_per_ms,
         max_connections_per_ipaddr_per_min: tpu_max_connections_per_ipaddr_per_minute,
+        max_unstaked_streams_per_ip_prefix_per_sec:
+            tpu_max_unstaked_streams_per_ip_prefix_per_second,
+        unstaked_stream_quotas: tpu_unstaked_stream_quotas,
         coalesce: tpu_coalesce,
         num_threads: tpu_transaction_forward_receive_threads,
         ..Default::default()
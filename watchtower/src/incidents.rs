@@ -0,0 +1,260 @@
+//! Incidents opened and resolved by the watchtower checks, and the status page listing them
+
+use {
+    log::*,
+    solana_hash::Hash,
+    solana_metrics::{datapoint_error, datapoint_info, http::respond_to_get_request},
+    solana_notifier::{NotificationType, Notifier},
+    std::{
+        collections::{BTreeMap, HashMap},
+        io,
+        net::{SocketAddr, TcpListener},
+        sync::{Arc, RwLock},
+        thread,
+        time::{Duration, SystemTime},
+    },
+};
+
+/// A failed check
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure {
+    /// Identifies the incident the failure opens, a failure with the same key keeps it open
+    pub key: String,
+    pub message: String,
+    /// Sent once the incident is resolved, defaults to an all clear message
+    pub resolve_message: Option<String>,
+}
+
+impl Failure {
+    pub fn new(key: &str, message: String) -> Self {
+        Self {
+            key: key.to_string(),
+            message,
+            resolve_message: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Incident {
+    incident: Hash,
+    message: String,
+    resolve_message: Option<String>,
+    opened: SystemTime,
+}
+
+type OpenIncidents = Arc<RwLock<BTreeMap<String, Incident>>>;
+
+/// Opens an incident once a check failed more than `unhealthy_threshold` consecutive times,
+/// and resolves it on the first success
+pub struct Incidents {
+    notifier: Notifier,
+    name_suffix: String,
+    unhealthy_threshold: usize,
+    /// Number of consecutive failures, by key
+    consecutive_failures: HashMap<String, usize>,
+    open: OpenIncidents,
+}
+
+impl Incidents {
+    pub fn new(notifier: Notifier, name_suffix: String, unhealthy_threshold: usize) -> Self {
+        Self {
+            notifier,
+            name_suffix,
+            unhealthy_threshold,
+            consecutive_failures: HashMap::new(),
+            open: OpenIncidents::default(),
+        }
+    }
+
+    /// Updates the incidents with the failures of the latest checks.  Incidents without a
+    /// failure are only resolved if `checks_complete`, as the other checks may not have run.
+    pub fn update(&mut self, failures: Vec<Failure>, checks_complete: bool) {
+        datapoint_info!("watchtower-sanity", ("ok", failures.is_empty(), bool));
+
+        let mut open = self.open.write().unwrap();
+        for failure in &failures {
+            let num_consecutive_failures = self
+                .consecutive_failures
+                .entry(failure.key.clone())
+                .or_default();
+            *num_consecutive_failures += 1;
+
+            let notification_msg = format!(
+                "agave-watchtower{}: Error: {}: {}",
+                self.name_suffix, failure.key, failure.message
+            );
+            if *num_consecutive_failures <= self.unhealthy_threshold {
+                info!(
+                    "Failure {} of {}: {}",
+                    num_consecutive_failures, self.unhealthy_threshold, notification_msg
+                );
+                continue;
+            }
+
+            datapoint_error!(
+                "watchtower-sanity-failure",
+                ("test", failure.key, String),
+                ("err", failure.message, String)
+            );
+            let incident = open.entry(failure.key.clone()).or_insert_with(|| Incident {
+                incident: Hash::new_unique(),
+                message: String::new(),
+                resolve_message: None,
+                opened: SystemTime::now(),
+            });
+            if incident.message != failure.message {
+                self.notifier.send(
+                    &notification_msg,
+                    &NotificationType::Trigger {
+                        incident: incident.incident,
+                    },
+                );
+                incident.message.clone_from(&failure.message);
+            }
+            incident
+                .resolve_message
+                .clone_from(&failure.resolve_message);
+        }
+
+        if !checks_complete {
+            return;
+        }
+        let failed = |key: &String| failures.iter().any(|failure| &failure.key == key);
+        self.consecutive_failures.retain(|key, _| failed(key));
+        let resolved: Vec<_> = open.keys().filter(|key| !failed(key)).cloned().collect();
+        for key in resolved {
+            let incident = open.remove(&key).unwrap();
+            let msg = incident.resolve_message.unwrap_or_else(|| {
+                let alarm_duration = incident.opened.elapsed().unwrap_or_default();
+                let alarm_duration = Duration::from_secs(alarm_duration.as_secs()); // Drop milliseconds in message
+                format!(
+                    "All clear after {}",
+                    humantime::format_duration(alarm_duration)
+                )
+            });
+            info!("{}: {}", key, msg);
+            self.notifier.send(
+                &format!("agave-watchtower{}: {}: {}", self.name_suffix, key, msg),
+                &NotificationType::Resolve {
+                    incident: incident.incident,
+                },
+            );
+        }
+    }
+
+    /// Serves the status page, listing the open incidents, on `bind_address`
+    pub fn serve_status_page(&self, bind_address: SocketAddr) -> io::Result<()> {
+        let listener = TcpListener::bind(bind_address)?;
+        let open = self.open.clone();
+        thread::Builder::new()
+            .name("solWtchtwrStatus".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    let result = stream.and_then(|stream| {
+                        respond_to_get_request(stream, |path| match path {
+                            "/" => Some((
+                                "text/plain; charset=utf-8",
+                                render_status_page(&open.read().unwrap(), false),
+                            )),
+                            "/incidents" => Some((
+                                "application/json",
+                                render_status_page(&open.read().unwrap(), true),
+                            )),
+                            _ => None,
+                        })
+                    });
+                    if let Err(err) = result {
+                        debug!("status page request failed: {err}");
+                    }
+                }
+            })?;
+        info!("Serving the status page on http://{bind_address}/");
+        Ok(())
+    }
+}
+
+fn render_status_page(open: &BTreeMap<String, Incident>, json: bool) -> String {
+    if json {
+        let incidents: Vec<_> = open
+            .iter()
+            .map(|(key, incident)| {
+                serde_json::json!({
+                    "key": key,
+                    "incident": incident.incident.to_string(),
+                    "message": incident.message,
+                    "opened": humantime::format_rfc3339_seconds(incident.opened).to_string(),
+                })
+            })
+            .collect();
+        return serde_json::json!({ "incidents": incidents }).to_string();
+    }
+
+    if open.is_empty() {
+        return "No open incidents\n".to_string();
+    }
+    open.iter()
+        .map(|(key, incident)| {
+            format!(
+                "{} {key}: {}\n",
+                humantime::format_rfc3339_seconds(incident.opened),
+                incident.message
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_incident(incidents: &Incidents, key: &str) -> Option<(Hash, String)> {
+        incidents
+            .open
+            .read()
+            .unwrap()
+            .get(key)
+            .map(|incident| (incident.incident, incident.message.clone()))
+    }
+
+    #[test]
+    fn test_incidents_update() {
+        let mut incidents = Incidents::new(Notifier::new("WATCHTOWER_TEST_"), String::new(), 1);
+        let failure = |message: &str| Failure::new("rpc", message.to_string());
+
+        // the incident is only triggered once the failures exceed the threshold
+        incidents.update(vec![failure("down")], true);
+        assert_eq!(open_incident(&incidents, "rpc"), None);
+        incidents.update(vec![failure("down")], true);
+        let (incident, message) = open_incident(&incidents, "rpc").unwrap();
+        assert_eq!(message, "down");
+
+        // a new message keeps the incident open
+        incidents.update(vec![failure("still down")], true);
+        assert_eq!(
+            open_incident(&incidents, "rpc"),
+            Some((incident, "still down".to_string()))
+        );
+
+        // the incident is not resolved while some checks did not run
+        incidents.update(vec![], false);
+        assert_eq!(
+            open_incident(&incidents, "rpc"),
+            Some((incident, "still down".to_string()))
+        );
+
+        // and is resolved by the first complete run without the failure
+        incidents.update(vec![Failure::new("other", "down".to_string())], true);
+        assert_eq!(open_incident(&incidents, "rpc"), None);
+        assert!(!incidents.consecutive_failures.contains_key("rpc"));
+
+        // a failure after the incident was resolved counts towards the threshold again, and
+        // triggers a new incident
+        incidents.update(vec![failure("down")], true);
+        assert_eq!(open_incident(&incidents, "rpc"), None);
+        incidents.update(vec![failure("down")], true);
+        let (retriggered_incident, message) = open_incident(&incidents, "rpc").unwrap();
+        assert_ne!(retriggered_incident, incident);
+        assert_eq!(message, "down");
+    }
+}
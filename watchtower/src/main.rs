@@ -2,6 +2,10 @@
 #![allow(clippy::arithmetic_side_effects)]
 
 use {
+    crate::{
+        incidents::{Failure, Incidents},
+        rules::{load_rules, Rule, RuleState},
+    },
     clap::{value_t, value_t_or_exit, values_t, App, Arg},
     log::*,
     solana_clap_utils::{
@@ -11,20 +15,17 @@ use {
     },
     solana_cli_output::display::format_labeled_address,
     solana_hash::Hash,
-    solana_metrics::{datapoint_error, datapoint_info},
     solana_native_token::{sol_to_lamports, Sol},
-    solana_notifier::{NotificationType, Notifier},
+    solana_notifier::Notifier,
     solana_pubkey::Pubkey,
     solana_rpc_client::rpc_client::RpcClient,
     solana_rpc_client_api::{client_error, response::RpcVoteAccountStatus},
-    std::{
-        collections::HashMap,
-        error,
-        thread::sleep,
-        time::{Duration, Instant},
-    },
+    std::{collections::HashMap, error, net::SocketAddr, thread::sleep, time::Duration},
 };
 
+mod incidents;
+mod rules;
+
 // Validator wrapper functions for clap v3 compatibility
 fn validator_is_url(s: &str) -> Result<(), String> {
     is_url(s)
@@ -51,6 +52,8 @@ struct Config {
     validator_identity_pubkeys: Vec<Pubkey>,
     name_suffix: String,
     acceptable_slot_range: u64,
+    rules: Vec<Rule>,
+    status_bind_address: Option<SocketAddr>,
 }
 
 fn get_config() -> Config {
@@ -200,6 +203,22 @@ fn get_config() -> Config {
                 .validator(|s| is_parsable::<u64>(s.to_string()))
                 .help("Acceptable range of slots for endpoints, checked at watchtower startup")
         )
+        .arg(
+            Arg::with_name("rules_config")
+                .long("rules-config")
+                .value_name("PATH")
+                .takes_value(true)
+                .help("YAML file of custom checks, each with an RPC method, a JSON path into \
+                    its result and a threshold. See the watchtower README for the format")
+        )
+        .arg(
+            Arg::with_name("status_bind_address")
+                .long("status-bind-address")
+                .value_name("HOST:PORT")
+                .takes_value(true)
+                .validator(|s| is_parsable::<SocketAddr>(s.to_string()))
+                .help("Serve a status page listing the open incidents on this address")
+        )
         .get_matches();
 
     let config = if let Some(config_file) = matches.value_of("config_file") {
@@ -234,6 +253,17 @@ fn get_config() -> Config {
 
     let acceptable_slot_range = value_t_or_exit!(matches, "acceptable_slot_range", u64);
 
+    let rules = matches
+        .value_of("rules_config")
+        .map(|path| {
+            load_rules(path, rpc_timeout).unwrap_or_else(|err| {
+                eprintln!("Unable to load rules from {path}: {err}");
+                std::process::exit(1);
+            })
+        })
+        .unwrap_or_default();
+    let status_bind_address = value_t!(matches, "status_bind_address", SocketAddr).ok();
+
     let config = Config {
         address_labels: config.address_labels,
         ignore_http_bad_gateway,
@@ -247,6 +277,8 @@ fn get_config() -> Config {
         validator_identity_pubkeys,
         name_suffix,
         acceptable_slot_range,
+        rules,
+        status_bind_address,
     };
 
     info!("RPC URLs: {:?}", config.json_rpc_urls);
@@ -285,12 +317,13 @@ struct EndpointData {
     rpc_client: RpcClient,
     last_transaction_count: u64,
     last_recent_blockhash: Hash,
+    rule_states: Vec<RuleState>,
 }
 
 fn query_endpoint(
     config: &Config,
     endpoint: &mut EndpointData,
-) -> client_error::Result<Option<(&'static str, String)>> {
+) -> client_error::Result<Vec<Failure>> {
     info!("Querying {}", endpoint.rpc_client.url());
 
     match get_cluster_info(config, &endpoint.rpc_client) {
@@ -329,7 +362,7 @@ fn query_endpoint(
             if transaction_count > endpoint.last_transaction_count {
                 endpoint.last_transaction_count = transaction_count;
             } else {
-                failures.push((
+                failures.push(Failure::new(
                     "transaction-count",
                     format!(
                         "Transaction count is not advancing: {transaction_count} <= {0}",
@@ -341,7 +374,7 @@ fn query_endpoint(
             if recent_blockhash != endpoint.last_recent_blockhash {
                 endpoint.last_recent_blockhash = recent_blockhash;
             } else {
-                failures.push((
+                failures.push(Failure::new(
                     "recent-blockhash",
                     format!("Unable to get new blockhash: {recent_blockhash}"),
                 ));
@@ -350,7 +383,7 @@ fn query_endpoint(
             if config.monitor_active_stake
                 && current_stake_percent < config.active_stake_alert_threshold as f64
             {
-                failures.push((
+                failures.push(Failure::new(
                     "current-stake",
                     format!("Current stake is {current_stake_percent:.2}%"),
                 ));
@@ -376,7 +409,7 @@ fn query_endpoint(
 
                 if let Some(balance) = validator_balances.get(validator_identity) {
                     if *balance < config.minimum_validator_identity_balance {
-                        failures.push((
+                        failures.push(Failure::new(
                             "balance",
                             format!("{} has {}", formatted_validator_identity, Sol(*balance)),
                         ));
@@ -385,21 +418,25 @@ fn query_endpoint(
             }
 
             if !validator_errors.is_empty() {
-                failures.push(("delinquent", validator_errors.join(",")));
+                failures.push(Failure::new("delinquent", validator_errors.join(",")));
+            }
+
+            for (rule, state) in config.rules.iter().zip(&mut endpoint.rule_states) {
+                failures.extend(rule.check(&endpoint.rpc_client, state));
             }
 
             for failure in &failures {
-                error!("{} sanity failure: {}", failure.0, failure.1);
+                error!("{} sanity failure: {}", failure.key, failure.message);
             }
 
-            Ok(failures.into_iter().next()) // Only report the first failure if any
+            Ok(failures)
         }
         Err(err) => {
             if let client_error::ErrorKind::Reqwest(reqwest_err) = err.kind() {
                 if let Some(client_error::reqwest::StatusCode::BAD_GATEWAY) = reqwest_err.status() {
                     if config.ignore_http_bad_gateway {
                         warn!("Error suppressed: {}", err);
-                        return Ok(None);
+                        return Ok(vec![]);
                     }
                 }
             }
@@ -467,6 +504,7 @@ fn main() -> Result<(), Box<dyn error::Error>> {
             rpc_client: RpcClient::new_with_timeout(url, config.rpc_timeout),
             last_transaction_count: 0,
             last_recent_blockhash: Hash::default(),
+            rule_states: vec![None; config.rules.len()],
         })
         .collect();
 
@@ -477,103 +515,68 @@ fn main() -> Result<(), Box<dyn error::Error>> {
 
     let min_agreeing_endpoints = endpoints.len() / 2 + 1;
 
-    let notifier = Notifier::default();
-
-    let mut last_notification_msg = "".into();
-    let mut num_consecutive_failures = 0;
-    let mut last_success = Instant::now();
-    let mut incident = Hash::new_unique();
+    let mut incidents = Incidents::new(
+        Notifier::default(),
+        config.name_suffix.clone(),
+        config.unhealthy_threshold,
+    );
+    if let Some(status_bind_address) = config.status_bind_address {
+        incidents.serve_status_page(status_bind_address)?;
+    }
 
     loop {
-        let mut failures = HashMap::new(); // test_name -> message
+        let mut failures = HashMap::new(); // key -> (number of endpoints, failure)
 
         let mut num_healthy = 0;
         let mut num_reachable = 0;
 
         for endpoint in &mut endpoints {
-            match query_endpoint(&config, endpoint) {
-                Ok(None) => {
+            if let Ok(endpoint_failures) = query_endpoint(&config, endpoint) {
+                num_reachable += 1;
+                if endpoint_failures.is_empty() {
                     num_healthy += 1;
-                    num_reachable += 1;
                 }
-                Ok(Some((failure_test_name, failure_error_message))) => {
-                    num_reachable += 1;
 
-                    // Collecting only one failure of each type
+                // Collecting only one failure of each key
+                for failure in endpoint_failures {
                     failures
-                        .entry(failure_test_name)
-                        .or_insert(failure_error_message.clone());
+                        .entry(failure.key.clone())
+                        .or_insert((0, failure))
+                        .0 += 1;
                 }
-                Err(_) => {}
             }
         }
 
-        if num_reachable < min_agreeing_endpoints {
-            failures.clear(); // Ignoring other failures when watchtower is unreliable
-
-            let watchtower_unreliable_msg = format!(
-                "Watchtower is unreliable, {} of {} RPC endpoints are reachable",
-                num_reachable,
-                endpoints.len()
-            );
-            failures.insert("watchtower-reliability", watchtower_unreliable_msg);
-        }
-
-        if num_healthy < min_agreeing_endpoints {
-            if failures.len() > 1 {
-                failures.clear(); // Ignoring other failures when watchtower is unreliable
-
-                let watchtower_unreliable_msg =
-                    "Watchtower is unreliable, RPC endpoints provide inconsistent information"
-                        .into();
-                failures.insert("watchtower-reliability", watchtower_unreliable_msg);
-            }
-
-            let (failure_test_name, failure_error_message) = failures.iter().next().unwrap();
-            let notification_msg = format!(
-                "agave-watchtower{}: Error: {}: {}",
-                config.name_suffix, failure_test_name, failure_error_message
-            );
-            num_consecutive_failures += 1;
-            if num_consecutive_failures > config.unhealthy_threshold {
-                datapoint_info!("watchtower-sanity", ("ok", false, bool));
-                if last_notification_msg != notification_msg {
-                    notifier.send(&notification_msg, &NotificationType::Trigger { incident });
-                }
-                datapoint_error!(
-                    "watchtower-sanity-failure",
-                    ("test", failure_test_name, String),
-                    ("err", failure_error_message, String)
-                );
-                last_notification_msg = notification_msg;
-            } else {
-                info!(
-                    "Failure {} of {}: {}",
-                    num_consecutive_failures, config.unhealthy_threshold, notification_msg
-                );
-            }
+        // A check only fails if enough endpoints agree on it
+        let mut failures: Vec<_> = failures
+            .into_values()
+            .filter(|(num_endpoints, _)| *num_endpoints >= min_agreeing_endpoints)
+            .map(|(_, failure)| failure)
+            .collect();
+        failures.sort_unstable_by(|a, b| a.key.cmp(&b.key));
+
+        // Ignoring other failures when watchtower is unreliable
+        let reliable = if num_reachable < min_agreeing_endpoints {
+            failures = vec![Failure::new(
+                "watchtower-reliability",
+                format!(
+                    "Watchtower is unreliable, {} of {} RPC endpoints are reachable",
+                    num_reachable,
+                    endpoints.len()
+                ),
+            )];
+            false
+        } else if num_healthy < min_agreeing_endpoints && failures.is_empty() {
+            failures = vec![Failure::new(
+                "watchtower-reliability",
+                "Watchtower is unreliable, RPC endpoints provide inconsistent information".into(),
+            )];
+            false
         } else {
-            datapoint_info!("watchtower-sanity", ("ok", true, bool));
-            if !last_notification_msg.is_empty() {
-                let alarm_duration = Instant::now().duration_since(last_success);
-                let alarm_duration = alarm_duration - config.interval; // Subtract the period before the first error
-                let alarm_duration = Duration::from_secs(alarm_duration.as_secs()); // Drop milliseconds in message
-
-                let all_clear_msg = format!(
-                    "All clear after {}",
-                    humantime::format_duration(alarm_duration)
-                );
-                info!("{}", all_clear_msg);
-                notifier.send(
-                    &format!("agave-watchtower{}: {}", config.name_suffix, all_clear_msg),
-                    &NotificationType::Resolve { incident },
-                );
-            }
-            last_notification_msg = "".into();
-            last_success = Instant::now();
-            num_consecutive_failures = 0;
-            incident = Hash::new_unique();
-        }
+            true
+        };
+
+        incidents.update(failures, reliable);
         sleep(config.interval);
     }
 }
@@ -0,0 +1,333 @@
+//! Custom checks, defined in the watchtower rules file
+//!
+//! A rule queries an RPC method, extracts a number from the result with a JSON path, and
+//! compares it against a threshold.  With a reference, the number observed on the reference
+//! RPC URL is subtracted first:
+//!
+//! ```yaml
+//! rules:
+//!   - key: slot-lag
+//!     method: getSlot
+//!     reference:
+//!       url: https://api.mainnet-beta.solana.com
+//!     min: -150
+//!     message: "Slot is {value} slots from the reference"
+//!     resolve-message: "Slot caught up with the reference"
+//!   - key: vote-credit-rate
+//!     method: getVoteAccounts
+//!     params: [{"votePubkey": "<VOTE ACCOUNT>"}]
+//!     path: current.0.epochCredits.-1.1
+//!     mode: rate
+//!     min: 1.0
+//! ```
+
+use {
+    crate::incidents::Failure,
+    log::*,
+    serde_derive::Deserialize,
+    serde_json::Value,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_api::request::RpcRequest,
+    std::{
+        collections::HashSet,
+        error,
+        fs::File,
+        time::{Duration, Instant},
+    },
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    rules: Vec<RuleSpec>,
+}
+
+/// How the observed value is compared against the thresholds
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum RuleMode {
+    /// The value itself
+    #[default]
+    Value,
+    /// The change of the value since the previous check
+    Change,
+    /// The change of the value per second since the previous check
+    Rate,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct ReferenceSpec {
+    url: String,
+    method: Option<String>,
+    params: Option<Value>,
+    path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct RuleSpec {
+    key: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    #[serde(default)]
+    path: String,
+    #[serde(default)]
+    mode: RuleMode,
+    reference: Option<ReferenceSpec>,
+    min: Option<f64>,
+    max: Option<f64>,
+    message: Option<String>,
+    resolve_message: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    /// Array index, negative indexes count from the end of the array
+    Index(i64),
+}
+
+fn parse_path(path: &str) -> Vec<PathSegment> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            segment
+                .parse()
+                .map(PathSegment::Index)
+                .unwrap_or_else(|_| PathSegment::Key(segment.to_string()))
+        })
+        .collect()
+}
+
+fn extract_number(value: &Value, path: &[PathSegment]) -> Result<f64, String> {
+    let mut value = value;
+    for segment in path {
+        value = match (segment, value) {
+            (PathSegment::Key(key), Value::Object(object)) => object.get(key),
+            (PathSegment::Index(index), Value::Array(array)) => {
+                let index = if *index < 0 {
+                    array.len().checked_sub(index.unsigned_abs() as usize)
+                } else {
+                    Some(*index as usize)
+                };
+                index.and_then(|index| array.get(index))
+            }
+            (PathSegment::Index(index), Value::Object(object)) => object.get(&index.to_string()),
+            _ => None,
+        }
+        .ok_or_else(|| format!("{segment:?} not found in {value}"))?;
+    }
+
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => string.parse().ok(),
+        Value::Bool(boolean) => Some(if *boolean { 1. } else { 0. }),
+        _ => None,
+    }
+    .ok_or_else(|| format!("{value} is not a number"))
+}
+
+struct Query {
+    method: &'static str,
+    params: Value,
+    path: Vec<PathSegment>,
+}
+
+impl Query {
+    fn new(method: &str, params: Value, path: &str) -> Self {
+        Self {
+            // Rules are loaded once, and `RpcRequest::Custom` needs a static method name
+            method: Box::leak(method.to_string().into_boxed_str()),
+            params,
+            path: parse_path(path),
+        }
+    }
+
+    fn observe(&self, rpc_client: &RpcClient) -> Result<f64, String> {
+        let result: Value = rpc_client
+            .send(
+                RpcRequest::Custom {
+                    method: self.method,
+                },
+                self.params.clone(),
+            )
+            .map_err(|err| format!("{} failed: {err}", self.method))?;
+        extract_number(&result, &self.path)
+    }
+}
+
+pub struct Rule {
+    key: String,
+    query: Query,
+    reference: Option<(RpcClient, Query)>,
+    mode: RuleMode,
+    min: Option<f64>,
+    max: Option<f64>,
+    message: Option<String>,
+    resolve_message: Option<String>,
+}
+
+/// The previous observation of a rule, for the `change` and `rate` modes
+pub type RuleState = Option<(f64, Instant)>;
+
+impl Rule {
+    fn new(spec: RuleSpec, rpc_timeout: Duration) -> Result<Self, String> {
+        if spec.key.is_empty() {
+            return Err("rule key is empty".to_string());
+        }
+        if spec.min.is_none() && spec.max.is_none() {
+            return Err(format!("rule {} has neither a min nor a max", spec.key));
+        }
+
+        let reference = spec.reference.map(|reference| {
+            (
+                RpcClient::new_with_timeout(reference.url, rpc_timeout),
+                Query::new(
+                    reference.method.as_deref().unwrap_or(&spec.method),
+                    reference.params.unwrap_or_else(|| spec.params.clone()),
+                    reference.path.as_deref().unwrap_or(&spec.path),
+                ),
+            )
+        });
+
+        Ok(Self {
+            query: Query::new(&spec.method, spec.params, &spec.path),
+            key: spec.key,
+            reference,
+            mode: spec.mode,
+            min: spec.min,
+            max: spec.max,
+            message: spec.message,
+            resolve_message: spec.resolve_message,
+        })
+    }
+
+    fn failure(&self, message: String) -> Failure {
+        Failure {
+            key: self.key.clone(),
+            message,
+            resolve_message: self.resolve_message.clone(),
+        }
+    }
+
+    /// Checks the rule against `rpc_client`, returns the failure if the rule is broken
+    pub fn check(&self, rpc_client: &RpcClient, state: &mut RuleState) -> Option<Failure> {
+        let observed = self.query.observe(rpc_client).and_then(|value| {
+            let Some((reference_client, reference_query)) = &self.reference else {
+                return Ok(value);
+            };
+            reference_query
+                .observe(reference_client)
+                .map(|reference_value| value - reference_value)
+                .map_err(|err| format!("reference {err}"))
+        });
+        let value = match observed {
+            Ok(value) => value,
+            Err(err) => return Some(self.failure(err)),
+        };
+
+        let value = match self.mode {
+            RuleMode::Value => value,
+            RuleMode::Change | RuleMode::Rate => {
+                let now = Instant::now();
+                let (previous_value, previous_time) = state.replace((value, now))?;
+                let change = value - previous_value;
+                if self.mode == RuleMode::Rate {
+                    let elapsed = now.duration_since(previous_time).as_secs_f64();
+                    if elapsed == 0. {
+                        return None;
+                    }
+                    change / elapsed
+                } else {
+                    change
+                }
+            }
+        };
+        info!("{}: {}", self.key, value);
+
+        let threshold = match (self.min, self.max) {
+            (Some(min), _) if value < min => format!("below {min}"),
+            (_, Some(max)) if value > max => format!("above {max}"),
+            _ => return None,
+        };
+        let message = match &self.message {
+            Some(message) => message.replace("{value}", &value.to_string()),
+            None => format!("{} is {value}, {threshold}", self.key),
+        };
+        Some(self.failure(message))
+    }
+}
+
+/// Loads the rules of the rules file at `path`
+pub fn load_rules(path: &str, rpc_timeout: Duration) -> Result<Vec<Rule>, Box<dyn error::Error>> {
+    let rules_file: RulesFile = serde_yaml::from_reader(File::open(path)?)?;
+
+    let mut keys = HashSet::new();
+    rules_file
+        .rules
+        .into_iter()
+        .map(|spec| {
+            if !keys.insert(spec.key.clone()) {
+                return Err(format!("duplicate rule key {}", spec.key).into());
+            }
+            Rule::new(spec, rpc_timeout).map_err(Into::into)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    #[test]
+    fn test_extract_number() {
+        let value = json!({
+            "value": {"amount": "1000", "decimals": 6},
+            "current": [{"epochCredits": [[1, 100, 0], [2, 250, 100]]}],
+            "healthy": true,
+        });
+        let extract = |path| extract_number(&value, &parse_path(path));
+
+        assert_eq!(extract("value.amount"), Ok(1000.));
+        assert_eq!(extract("value.decimals"), Ok(6.));
+        assert_eq!(extract("current.0.epochCredits.-1.1"), Ok(250.));
+        assert_eq!(extract("current.0.epochCredits.0.1"), Ok(100.));
+        assert_eq!(extract("healthy"), Ok(1.));
+        assert!(extract("current.0.epochCredits.-3.1").is_err());
+        assert!(extract("value.missing").is_err());
+        assert!(extract("value").is_err());
+        assert_eq!(extract_number(&json!(42), &parse_path("")), Ok(42.));
+    }
+
+    #[test]
+    fn test_rules_file() {
+        let rules_file: RulesFile = serde_yaml::from_str(
+            "rules:\n  - key: token-supply-drift\n    method: getTokenSupply\n    params: \
+             [\"mint\"]\n    path: value.amount\n    mode: change\n    max: 0\n    \
+             resolve-message: Supply is stable\n",
+        )
+        .unwrap();
+        let rule = Rule::new(
+            rules_file.rules.into_iter().next().unwrap(),
+            Duration::from_secs(1),
+        )
+        .unwrap();
+        assert_eq!(rule.key, "token-supply-drift");
+        assert_eq!(rule.mode, RuleMode::Change);
+        assert_eq!(rule.query.method, "getTokenSupply");
+        assert_eq!(rule.query.params, json!(["mint"]));
+        assert_eq!(rule.resolve_message.as_deref(), Some("Supply is stable"));
+
+        assert!(serde_yaml::from_str::<RulesFile>("rules:\n  - key: a\n    unknown: 1\n").is_err());
+        let no_threshold: RulesFile =
+            serde_yaml::from_str("rules:\n  - key: a\n    method: getSlot\n").unwrap();
+        assert!(Rule::new(
+            no_threshold.rules.into_iter().next().unwrap(),
+            Duration::from_secs(1)
+        )
+        .is_err());
+    }
+}
@@ -11,6 +11,8 @@
 #[cfg(target_os = "linux")]
 pub mod device;
 #[cfg(target_os = "linux")]
+pub mod map;
+#[cfg(target_os = "linux")]
 pub mod netlink;
 #[cfg(target_os = "linux")]
 pub mod packet;
@@ -19,6 +21,8 @@ mod program;
 #[cfg(target_os = "linux")]
 pub mod route;
 #[cfg(target_os = "linux")]
+pub mod rx_loop;
+#[cfg(target_os = "linux")]
 pub mod socket;
 #[cfg(target_os = "linux")]
 pub mod tx_loop;
@@ -26,7 +30,7 @@ pub mod tx_loop;
 pub mod umem;
 
 #[cfg(target_os = "linux")]
-pub use program::load_xdp_program;
+pub use program::{load_xdp_program, load_xdp_rx_program};
 use std::io;
 
 #[cfg(target_os = "linux")]
@@ -0,0 +1,118 @@
+use {
+    crate::device::QueueId,
+    std::{
+        io, mem,
+        os::fd::{AsFd, AsRawFd as _, BorrowedFd, FromRawFd as _, OwnedFd},
+    },
+};
+
+// bpf() commands
+const BPF_MAP_CREATE: libc::c_long = 0;
+const BPF_MAP_UPDATE_ELEM: libc::c_long = 2;
+const BPF_MAP_DELETE_ELEM: libc::c_long = 3;
+
+const BPF_MAP_TYPE_XSKMAP: u32 = 17;
+const BPF_ANY: u64 = 0;
+
+// the prefix of union bpf_attr used by BPF_MAP_CREATE
+#[repr(C)]
+struct MapCreateAttr {
+    map_type: u32,
+    key_size: u32,
+    value_size: u32,
+    max_entries: u32,
+    map_flags: u32,
+}
+
+// the prefix of union bpf_attr used by BPF_MAP_UPDATE_ELEM and BPF_MAP_DELETE_ELEM
+#[repr(C)]
+struct MapElemAttr {
+    map_fd: u32,
+    _pad: u32,
+    key: u64,
+    value: u64,
+    flags: u64,
+}
+
+/// A BPF_MAP_TYPE_XSKMAP, mapping receive queues to the AF_XDP sockets the XDP program
+/// redirects their packets to.
+pub struct XskMap {
+    fd: OwnedFd,
+    max_entries: u32,
+}
+
+impl XskMap {
+    pub fn new(max_entries: u32) -> Result<Self, io::Error> {
+        let attr = MapCreateAttr {
+            map_type: BPF_MAP_TYPE_XSKMAP,
+            key_size: mem::size_of::<u32>() as u32,
+            value_size: mem::size_of::<u32>() as u32,
+            max_entries,
+            map_flags: 0,
+        };
+        // Safety: attr is a valid bpf_attr prefix for BPF_MAP_CREATE
+        let fd = unsafe { bpf(BPF_MAP_CREATE, &attr)? };
+        Ok(Self {
+            // Safety: BPF_MAP_CREATE returns a new file descriptor
+            fd: unsafe { OwnedFd::from_raw_fd(fd as i32) },
+            max_entries,
+        })
+    }
+
+    pub fn max_entries(&self) -> u32 {
+        self.max_entries
+    }
+
+    /// Redirects the packets of `queue_id` to `socket`
+    pub fn insert(&self, queue_id: QueueId, socket: BorrowedFd<'_>) -> Result<(), io::Error> {
+        let key = queue_id.0 as u32;
+        let value = socket.as_raw_fd() as u32;
+        let attr = MapElemAttr {
+            map_fd: self.fd.as_raw_fd() as u32,
+            _pad: 0,
+            key: &key as *const u32 as u64,
+            value: &value as *const u32 as u64,
+            flags: BPF_ANY,
+        };
+        // Safety: key and value outlive the syscall
+        unsafe { bpf(BPF_MAP_UPDATE_ELEM, &attr)? };
+        Ok(())
+    }
+
+    /// Stops redirecting the packets of `queue_id`, they are passed to the kernel again
+    pub fn remove(&self, queue_id: QueueId) -> Result<(), io::Error> {
+        let key = queue_id.0 as u32;
+        let attr = MapElemAttr {
+            map_fd: self.fd.as_raw_fd() as u32,
+            _pad: 0,
+            key: &key as *const u32 as u64,
+            value: 0,
+            flags: 0,
+        };
+        // Safety: key outlives the syscall
+        unsafe { bpf(BPF_MAP_DELETE_ELEM, &attr)? };
+        Ok(())
+    }
+}
+
+impl AsFd for XskMap {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+unsafe fn bpf<T>(cmd: libc::c_long, attr: &T) -> Result<libc::c_long, io::Error> {
+    // Safety: just a libc wrapper, the caller passes a valid attr for cmd
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_bpf,
+            cmd,
+            attr as *const T,
+            mem::size_of::<T>() as u32,
+        )
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(result)
+}
@@ -1,6 +1,9 @@
 #![allow(clippy::arithmetic_side_effects)]
 
-use {libc::ETH_P_IP, std::net::Ipv4Addr};
+use {
+    libc::{ETH_P_IP, IPPROTO_UDP},
+    std::net::{Ipv4Addr, SocketAddrV4},
+};
 
 pub const ETH_HEADER_SIZE: usize = 14;
 pub const IP_HEADER_SIZE: usize = 20;
@@ -59,6 +62,41 @@ pub fn write_udp_header(
     }
 }
 
+/// Parses an ethernet frame carrying an IPv4 UDP packet. Returns the source address, the
+/// destination port and the payload of the packet.
+pub fn parse_udp_packet(packet: &[u8]) -> Option<(SocketAddrV4, u16, &[u8])> {
+    if packet.len() < ETH_HEADER_SIZE + IP_HEADER_SIZE + UDP_HEADER_SIZE
+        || u16::from_be_bytes([packet[12], packet[13]]) != ETH_P_IP as u16
+    {
+        return None;
+    }
+
+    let ip = &packet[ETH_HEADER_SIZE..];
+    let ihl = usize::from(ip[0] & 0x0f) * 4;
+    if ip[0] >> 4 != 4 || ihl < IP_HEADER_SIZE || ip[9] != IPPROTO_UDP as u8 {
+        return None;
+    }
+    let total_len = usize::from(u16::from_be_bytes([ip[2], ip[3]]));
+    if total_len < ihl + UDP_HEADER_SIZE || total_len > ip.len() {
+        return None;
+    }
+    let src_ip = Ipv4Addr::new(ip[12], ip[13], ip[14], ip[15]);
+
+    let udp = &ip[ihl..total_len];
+    let src_port = u16::from_be_bytes([udp[0], udp[1]]);
+    let dst_port = u16::from_be_bytes([udp[2], udp[3]]);
+    let udp_len = usize::from(u16::from_be_bytes([udp[4], udp[5]]));
+    if udp_len < UDP_HEADER_SIZE || udp_len > udp.len() {
+        return None;
+    }
+
+    Some((
+        SocketAddrV4::new(src_ip, src_port),
+        dst_port,
+        &udp[UDP_HEADER_SIZE..udp_len],
+    ))
+}
+
 fn calculate_udp_checksum(udp_packet: &[u8], src_ip: &Ipv4Addr, dst_ip: &Ipv4Addr) -> u16 {
     let udp_len = udp_packet.len();
 
@@ -112,3 +150,56 @@ fn calculate_ip_checksum(header: &[u8]) -> u16 {
 
     !(sum as u16)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_udp_packet() {
+        const HEADER_SIZE: usize = ETH_HEADER_SIZE + IP_HEADER_SIZE + UDP_HEADER_SIZE;
+        let src_ip = Ipv4Addr::new(10, 0, 0, 1);
+        let dst_ip = Ipv4Addr::new(10, 0, 0, 2);
+        let payload = b"hello shreds";
+        let mut packet = vec![0u8; HEADER_SIZE + payload.len()];
+        packet[HEADER_SIZE..].copy_from_slice(payload);
+        write_eth_header(&mut packet, &[1; 6], &[2; 6]);
+        write_ip_header(
+            &mut packet[ETH_HEADER_SIZE..],
+            &src_ip,
+            &dst_ip,
+            (UDP_HEADER_SIZE + payload.len()) as u16,
+        );
+        write_udp_header(
+            &mut packet[ETH_HEADER_SIZE + IP_HEADER_SIZE..],
+            &src_ip,
+            8000,
+            &dst_ip,
+            8001,
+            payload.len() as u16,
+            true,
+        );
+
+        assert_eq!(
+            parse_udp_packet(&packet),
+            Some((SocketAddrV4::new(src_ip, 8000), 8001, &payload[..]))
+        );
+        // ethernet padding is ignored
+        let mut padded = packet.clone();
+        padded.extend([0u8; 16]);
+        assert_eq!(
+            parse_udp_packet(&padded),
+            Some((SocketAddrV4::new(src_ip, 8000), 8001, &payload[..]))
+        );
+        // truncated
+        assert_eq!(parse_udp_packet(&packet[..packet.len() - 1]), None);
+        // not UDP
+        let mut tcp = packet.clone();
+        tcp[ETH_HEADER_SIZE + 9] = 6;
+        assert_eq!(parse_udp_packet(&tcp), None);
+        // not IPv4
+        let mut arp = packet;
+        arp[12..14].copy_from_slice(&0x0806u16.to_be_bytes());
+        assert_eq!(parse_udp_packet(&arp), None);
+    }
+}
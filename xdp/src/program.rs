@@ -1,8 +1,21 @@
 #![allow(clippy::arithmetic_side_effects)]
 
 #[cfg(target_os = "linux")]
-use aya::{programs::Xdp, Ebpf};
-use std::io::{Cursor, Write};
+use {
+    crate::map::XskMap,
+    aya::{
+        programs::{xdp::XdpFlags, Xdp},
+        Ebpf,
+    },
+    std::os::fd::{AsFd as _, AsRawFd as _},
+};
+use {
+    libc::ETH_P_IP,
+    std::{
+        io::{Cursor, Write},
+        os::fd::RawFd,
+    },
+};
 
 macro_rules! write_fields {
     ($w:expr, $($x:expr),*) => {
@@ -38,25 +51,168 @@ const XDP_PROG: &[u8] = &[
 // the string table
 const STRTAB: &[u8] = b"\0xdp\0.symtab\0.strtab\0";
 
+// XDP actions
+const XDP_PASS: i32 = 2;
+
+// helper functions
+const BPF_FUNC_REDIRECT_MAP: i32 = 51;
+
+// ld_imm64 source register flagging the immediate as a map file descriptor
+const BPF_PSEUDO_MAP_FD: u8 = 1;
+
+// registers
+const R0: u8 = 0;
+const R1: u8 = 1;
+const R2: u8 = 2;
+const R3: u8 = 3;
+const R4: u8 = 4;
+
+// opcodes
+const LDX_W: u8 = 0x61;
+const LDX_H: u8 = 0x69;
+const LDX_B: u8 = 0x71;
+const LD_IMM64: u8 = 0x18;
+const MOV64_IMM: u8 = 0xb7;
+const MOV64_REG: u8 = 0xbf;
+const ADD64_IMM: u8 = 0x07;
+const AND64_IMM: u8 = 0x57;
+const JA: u8 = 0x05;
+const JEQ_IMM: u8 = 0x15;
+const JNE_IMM: u8 = 0x55;
+const JGT_REG: u8 = 0x2d;
+const CALL: u8 = 0x85;
+const EXIT: u8 = 0x95;
+
+// offsets in struct xdp_md
+const XDP_MD_DATA: i16 = 0;
+const XDP_MD_DATA_END: i16 = 4;
+const XDP_MD_RX_QUEUE_INDEX: i16 = 16;
+
+// offsets of the header fields the program matches on, assuming no IP options
+const ETH_TYPE_OFF: i16 = 12;
+const IP_VERSION_IHL_OFF: i16 = 14;
+const IP_FRAG_OFF: i16 = 20;
+const IP_PROTOCOL_OFF: i16 = 23;
+const UDP_DST_PORT_OFF: i16 = 36;
+const UDP_PAYLOAD_OFF: i32 = 42;
+
 pub fn load_xdp_program(if_index: u32) -> Result<Ebpf, Box<dyn std::error::Error>> {
-    let elf = generate_xdp_elf();
+    let elf = generate_xdp_elf(XDP_PROG);
     let mut ebpf = Ebpf::load(&elf).unwrap();
     let p: &mut Xdp = ebpf.program_mut("xdp").unwrap().try_into().unwrap();
     p.load()?;
 
-    p.attach_to_if_index(if_index, aya::programs::xdp::XdpFlags::DRV_MODE)?;
+    p.attach_to_if_index(if_index, XdpFlags::DRV_MODE)?;
 
     Ok(ebpf)
 }
 
-fn generate_xdp_elf() -> Vec<u8> {
+/// Loads and attaches a program redirecting the IPv4 UDP packets sent to `ports` to the AF_XDP
+/// sockets in `xsk_map`. All other packets, and the packets of the queues without a socket, are
+/// passed to the kernel. Falls back to generic (SKB) mode if the driver doesn't support XDP.
+pub fn load_xdp_rx_program(
+    if_index: u32,
+    xsk_map: &XskMap,
+    ports: &[u16],
+) -> Result<Ebpf, Box<dyn std::error::Error>> {
+    let elf = generate_xdp_elf(&xdp_rx_program(xsk_map.as_fd().as_raw_fd(), ports));
+    let mut ebpf = Ebpf::load(&elf)?;
+    let p: &mut Xdp = ebpf.program_mut("xdp").unwrap().try_into()?;
+    p.load()?;
+
+    if let Err(e) = p.attach_to_if_index(if_index, XdpFlags::DRV_MODE) {
+        log::warn!("failed to attach xdp program in driver mode, trying generic mode: {e}");
+        p.attach_to_if_index(if_index, XdpFlags::SKB_MODE)?;
+    }
+
+    Ok(ebpf)
+}
+
+fn insn(code: u8, dst: u8, src: u8, off: i16, imm: i32) -> [u8; 8] {
+    let mut insn = [0u8; 8];
+    insn[0] = code;
+    insn[1] = (src << 4) | dst;
+    insn[2..4].copy_from_slice(&off.to_le_bytes());
+    insn[4..8].copy_from_slice(&imm.to_le_bytes());
+    insn
+}
+
+// the program loads header fields with native endianness, convert the network order
+// constants they are compared to
+fn network_u16(value: u16) -> i32 {
+    u16::from_ne_bytes(value.to_be_bytes()) as i32
+}
+
+fn xdp_rx_program(xsk_map_fd: RawFd, ports: &[u16]) -> Vec<u8> {
+    // header checks, each jumps to the pass label if it fails
+    let checks: &[(u8, u8, u8, i16, i32)] = &[
+        (LDX_W, R2, R1, XDP_MD_DATA, 0),
+        (LDX_W, R3, R1, XDP_MD_DATA_END, 0),
+        (MOV64_REG, R4, R2, 0, 0),
+        (ADD64_IMM, R4, 0, 0, UDP_PAYLOAD_OFF),
+        // bounds check required by the verifier before any packet access
+        (JGT_REG, R4, R3, 0, 0),
+        (LDX_H, R4, R2, ETH_TYPE_OFF, 0),
+        (JNE_IMM, R4, 0, 0, network_u16(ETH_P_IP as u16)),
+        // IPv4 without options
+        (LDX_B, R4, R2, IP_VERSION_IHL_OFF, 0),
+        (JNE_IMM, R4, 0, 0, 0x45),
+        (LDX_B, R4, R2, IP_PROTOCOL_OFF, 0),
+        (JNE_IMM, R4, 0, 0, libc::IPPROTO_UDP),
+        // let the kernel reassemble fragments
+        (LDX_H, R4, R2, IP_FRAG_OFF, 0),
+        (AND64_IMM, R4, 0, 0, network_u16(0x3fff)),
+        (JNE_IMM, R4, 0, 0, 0),
+        (LDX_H, R4, R2, UDP_DST_PORT_OFF, 0),
+    ];
+    // the ports, then a jump to the pass label
+    let redirect = checks.len() + ports.len() + 1;
+    // rx_queue_index load, map fd (2 slots), flags, call, exit
+    let pass = redirect + 6;
+
+    let mut program = Vec::with_capacity((pass + 2) * 8);
+    for (pc, &(code, dst, src, off, imm)) in checks.iter().enumerate() {
+        let off = if matches!(code, JGT_REG | JNE_IMM) {
+            (pass - pc - 1) as i16
+        } else {
+            off
+        };
+        program.extend(insn(code, dst, src, off, imm));
+    }
+    for (i, port) in ports.iter().enumerate() {
+        let pc = checks.len() + i;
+        program.extend(insn(
+            JEQ_IMM,
+            R4,
+            0,
+            (redirect - pc - 1) as i16,
+            network_u16(*port),
+        ));
+    }
+    program.extend(insn(JA, 0, 0, (pass - redirect) as i16, 0));
+
+    // bpf_redirect_map(&xsk_map, ctx->rx_queue_index, XDP_PASS), the flags are the action
+    // taken if the queue has no socket
+    program.extend(insn(LDX_W, R2, R1, XDP_MD_RX_QUEUE_INDEX, 0));
+    program.extend(insn(LD_IMM64, R1, BPF_PSEUDO_MAP_FD, 0, xsk_map_fd));
+    program.extend(insn(0, 0, 0, 0, 0));
+    program.extend(insn(MOV64_IMM, R3, 0, 0, XDP_PASS));
+    program.extend(insn(CALL, 0, 0, 0, BPF_FUNC_REDIRECT_MAP));
+    program.extend(insn(EXIT, 0, 0, 0, 0));
+
+    program.extend(insn(MOV64_IMM, R0, 0, 0, XDP_PASS));
+    program.extend(insn(EXIT, 0, 0, 0, 0));
+    program
+}
+
+fn generate_xdp_elf(program: &[u8]) -> Vec<u8> {
     let mut buffer = vec![0u8; 4096];
     let mut cursor = Cursor::new(&mut buffer);
 
     // start after the header
     let xdp_off = 64;
     cursor.set_position(xdp_off);
-    cursor.write_all(XDP_PROG).unwrap();
+    cursor.write_all(program).unwrap();
     let xdp_size = cursor.position() - xdp_off;
 
     // write the string table
@@ -71,7 +227,7 @@ fn generate_xdp_elf() -> Vec<u8> {
         &mut cursor,
         1, // index
         0,
-        XDP_PROG.len() as u64,
+        program.len() as u64,
         STB_GLOBAL | STT_FUNC,
         0,
         1, // section index
@@ -201,3 +357,47 @@ fn write_section_headers(
     write_section_header(w, STRTAB_SYMTAB_OFF, SHT_SYMTAB, 0, 0, symtab_off, symtab_size, 2, 1, 0, 0)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xdp_rx_program() {
+        let ports = [8001, 8002];
+        let program = xdp_rx_program(42, &ports);
+        let insns: Vec<_> = program.chunks_exact(8).collect();
+        assert_eq!(insns.len(), 15 + ports.len() + 1 + 6 + 2);
+
+        // every jump lands within the program
+        for (pc, insn) in insns.iter().enumerate() {
+            if matches!(insn[0], JA | JEQ_IMM | JNE_IMM | JGT_REG) {
+                let off = i16::from_le_bytes([insn[2], insn[3]]) as usize;
+                assert!(pc + off + 1 < insns.len());
+            }
+        }
+
+        // the port checks jump to the redirect
+        let redirect = 15 + ports.len() + 1;
+        for (i, port) in ports.iter().enumerate() {
+            let insn = insns[15 + i];
+            assert_eq!(insn[0], JEQ_IMM);
+            assert_eq!(
+                i32::from_le_bytes(insn[4..8].try_into().unwrap()),
+                network_u16(*port)
+            );
+            let off = i16::from_le_bytes([insn[2], insn[3]]) as usize;
+            assert_eq!(15 + i + off + 1, redirect);
+        }
+
+        // the map fd is loaded into r1
+        let ld_map = insns[redirect + 1];
+        assert_eq!(ld_map[0], LD_IMM64);
+        assert_eq!(ld_map[1], (BPF_PSEUDO_MAP_FD << 4) | R1);
+        assert_eq!(i32::from_le_bytes(ld_map[4..8].try_into().unwrap()), 42);
+
+        // the program ends with the pass label
+        assert_eq!(insns[insns.len() - 2][0], MOV64_IMM);
+        assert_eq!(insns[insns.len() - 1][0], EXIT);
+    }
+}
@@ -0,0 +1,179 @@
+#![allow(clippy::arithmetic_side_effects)]
+
+use {
+    crate::{
+        device::{DeviceQueue, NetworkDevice, QueueId, RxFillRing},
+        map::XskMap,
+        packet::parse_udp_packet,
+        set_cpu_affinity,
+        socket::{Rx, Socket},
+        umem::{Frame as _, PageAlignedMemory, SliceUmem, SliceUmemFrame, Umem as _},
+    },
+    caps::{
+        CapSet,
+        Capability::{CAP_NET_ADMIN, CAP_NET_RAW},
+    },
+    libc::{poll, pollfd, sysconf, POLLIN, _SC_PAGESIZE},
+    std::{
+        io,
+        net::SocketAddrV4,
+        os::fd::{AsFd as _, AsRawFd as _},
+        sync::atomic::{AtomicBool, Ordering},
+    },
+};
+
+// Ring size used when the driver doesn't report its ring sizes, eg veth
+const DEFAULT_RING_SIZE: usize = 2048;
+
+// How long we wait for packets before checking the exit flag
+const POLL_TIMEOUT_MS: i32 = 100;
+
+/// Consumes the packets received by `rx_loop`
+pub trait RxHandler {
+    /// Called for every UDP packet received. `payload` points into the UMEM, the frame is
+    /// given back to the kernel once this returns.
+    fn handle_packet(&mut self, src: SocketAddrV4, dst_port: u16, payload: &[u8]);
+
+    /// Called once all the packets available in the ring have been handled
+    fn flush(&mut self);
+}
+
+/// Receives the packets which the program loaded by `load_xdp_rx_program` redirects to
+/// `queue_id`, until `exit` is set.
+///
+/// `ready` is called once the AF_XDP socket is bound and registered in `xsk_map`. Errors
+/// setting up the socket are returned before `ready` is called, in which case the packets of
+/// the queue keep being passed to the kernel.
+#[allow(clippy::too_many_arguments)]
+pub fn rx_loop(
+    dev: &NetworkDevice,
+    queue_id: QueueId,
+    zero_copy: bool,
+    cpu_id: Option<usize>,
+    xsk_map: &XskMap,
+    exit: &AtomicBool,
+    ready: impl FnOnce(),
+    handler: &mut impl RxHandler,
+) -> Result<(), io::Error> {
+    log::info!(
+        "starting xdp rx loop on {} queue {queue_id:?} cpu {cpu_id:?}",
+        dev.name()
+    );
+
+    if let Some(cpu_id) = cpu_id {
+        set_cpu_affinity([cpu_id])?;
+    }
+
+    // some drivers require frame_size=page_size
+    let frame_size = unsafe { sysconf(_SC_PAGESIZE) } as usize;
+
+    let queue = match dev.open_queue(queue_id) {
+        Ok(queue) if queue.rx_size() > 0 => queue,
+        _ => DeviceQueue::new(
+            dev.if_index(),
+            queue_id,
+            DEFAULT_RING_SIZE,
+            DEFAULT_RING_SIZE,
+        ),
+    };
+    let rx_size = queue.rx_size().next_power_of_two();
+    // the frames in the fill ring plus the ones being handled
+    let frame_count = rx_size * 2;
+
+    // try to allocate huge pages first, then fall back to regular pages
+    const HUGE_2MB: usize = 2 * 1024 * 1024;
+    let mut memory =
+        PageAlignedMemory::alloc_with_page_size(frame_size, frame_count, HUGE_2MB, true)
+            .or_else(|_| {
+                log::warn!("huge page alloc failed, falling back to regular page size");
+                PageAlignedMemory::alloc(frame_size, frame_count)
+            })
+            .map_err(|_| io::Error::other("failed to allocate UMEM"))?;
+    let umem = SliceUmem::new(&mut memory, frame_size as u32)?;
+
+    // we need NET_ADMIN and NET_RAW for the socket
+    for cap in [CAP_NET_ADMIN, CAP_NET_RAW] {
+        caps::raise(None, CapSet::Effective, cap).map_err(io::Error::other)?;
+    }
+
+    let socket = Socket::rx(queue, umem, zero_copy, rx_size, rx_size).and_then(|(socket, rx)| {
+        xsk_map.insert(queue_id, socket.as_fd())?;
+        Ok((socket, rx))
+    });
+
+    // we don't need higher caps anymore
+    for cap in [CAP_NET_ADMIN, CAP_NET_RAW] {
+        caps::drop(None, CapSet::Effective, cap).map_err(io::Error::other)?;
+    }
+
+    let (mut socket, rx) = socket?;
+    let mut poll_fd = pollfd {
+        fd: socket.as_fd().as_raw_fd(),
+        events: POLLIN,
+        revents: 0,
+    };
+    let umem = socket.umem();
+    let Rx {
+        // this is where we give frames to the kernel to receive packets into
+        mut fill,
+        // this is where we get the frames packets were received into
+        ring,
+    } = rx;
+    let mut ring = ring.expect("rx socket has an rx ring");
+
+    refill(&mut fill, umem);
+
+    ready();
+
+    while !exit.load(Ordering::Relaxed) {
+        // commit the frames we've consumed and check if there are new ones
+        ring.sync(true);
+        if ring.available() == 0 {
+            // Safety: just a libc wrapper, poll_fd is valid
+            unsafe { poll(&mut poll_fd, 1, POLL_TIMEOUT_MS) };
+            continue;
+        }
+
+        while let Some((offset, len)) = ring.read() {
+            let frame = umem.received_frame(offset, len);
+            if let Some((src, dst_port, payload)) = parse_udp_packet(umem.map_frame(&frame)) {
+                handler.handle_packet(src, dst_port, payload);
+            }
+            umem.release(offset);
+        }
+        ring.commit();
+        handler.flush();
+
+        refill(&mut fill, umem);
+    }
+
+    // pass the packets of this queue to the kernel again
+    xsk_map.remove(queue_id)
+}
+
+// Gives all the available frames to the kernel to receive packets into
+fn refill<'a>(fill: &mut RxFillRing<SliceUmemFrame<'a>>, umem: &mut SliceUmem<'a>) {
+    fill.sync(false);
+    while fill.available() > 0 {
+        let Some(frame) = umem.reserve() else {
+            break;
+        };
+        let offset = frame.offset();
+        if fill.write(frame).is_err() {
+            umem.release(offset);
+            break;
+        }
+    }
+    fill.commit();
+    if fill.needs_wakeup() {
+        if let Err(e) = fill.wake() {
+            // these are non-fatal errors
+            if !matches!(
+                e.raw_os_error(),
+                Some(libc::EBUSY | libc::ENOBUFS | libc::EAGAIN)
+            ) {
+                log::error!("network interface driver error: {e:?}");
+            }
+        }
+    }
+}
@@ -4,7 +4,7 @@ use {
             mmap_ring, DeviceQueue, RingConsumer, RingMmap, RingProducer, RxFillRing,
             TxCompletionRing, XdpDesc,
         },
-        umem::{Frame, Umem},
+        umem::{Frame, FrameOffset, Umem},
     },
     libc::{
         bind, getsockopt, sa_family_t, sendto, setsockopt, sockaddr, sockaddr_xdp, socket,
@@ -331,7 +331,6 @@ impl<F: Frame> TxRing<F> {
 }
 
 pub struct RxRing {
-    #[allow(dead_code)]
     mmap: RingMmap<XdpDesc>,
     consumer: RingConsumer,
     size: u32,
@@ -350,6 +349,14 @@ impl RxRing {
         }
     }
 
+    /// Returns the offset of the next received frame in the UMEM and the length of its packet
+    pub fn read(&mut self) -> Option<(FrameOffset, usize)> {
+        let index = self.consumer.consume()? & self.size.saturating_sub(1);
+        // Safety: index is within the ring so the pointer is valid
+        let desc = unsafe { self.mmap.desc.add(index as usize).read() };
+        Some((FrameOffset(desc.addr as usize), desc.len as usize))
+    }
+
     pub fn capacity(&self) -> usize {
         self.size as usize
     }
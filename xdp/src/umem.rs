@@ -89,6 +89,16 @@ impl<'a> SliceUmem<'a> {
     pub fn available(&self) -> usize {
         self.available_frames.len()
     }
+
+    /// Returns the frame the kernel received a packet of `len` bytes into at `offset`
+    pub(crate) fn received_frame(&self, offset: FrameOffset, len: usize) -> SliceUmemFrame<'a> {
+        debug_assert!(offset.0 % self.frame_size as usize + len <= self.frame_size as usize);
+        SliceUmemFrame {
+            offset: offset.0,
+            len,
+            _buf: PhantomData,
+        }
+    }
 }
 
 impl<'a> Umem for SliceUmem<'a> {
@@ -0,0 +1,244 @@
+//! Batch verification of zero-knowledge proofs.
+//!
+//! The verification of a range proof or a sigma proof ends with a single multiscalar
+//! multiplication that must evaluate to the identity point. A batch verifier collects these final
+//! checks for a sequence of proofs, scales each of them by a random weight, and evaluates their sum
+//! as a single multiscalar multiplication. A batch that contains an invalid proof passes the
+//! combined check with only negligible probability.
+//!
+//! The weights are derived from a transcript that is bound to the transcripts of all proofs in the
+//! batch before any weight is generated. This keeps verification deterministic while preventing a
+//! prover from choosing proofs that cancel each other out.
+
+use {
+    crate::{
+        transcript::TranscriptProtocol, zk_elgamal_proof_program::errors::ProofVerificationError,
+    },
+    curve25519_dalek::{
+        ristretto::RistrettoPoint,
+        scalar::Scalar,
+        traits::{IsIdentity, VartimeMultiscalarMul},
+    },
+    merlin::Transcript,
+};
+
+/// The final check of a proof: a multiscalar multiplication that evaluates to the identity if and
+/// only if the proof is valid.
+pub(crate) struct MultiscalarCheck {
+    scalars: Vec<Scalar>,
+    points: Vec<Option<RistrettoPoint>>,
+}
+
+impl MultiscalarCheck {
+    pub(crate) fn new(scalars: Vec<Scalar>, points: Vec<Option<RistrettoPoint>>) -> Self {
+        debug_assert_eq!(scalars.len(), points.len());
+        Self { scalars, points }
+    }
+
+    /// Evaluates the multiscalar multiplication. Returns `None` if any of the points failed to
+    /// decompress.
+    pub(crate) fn evaluate(&self) -> Option<RistrettoPoint> {
+        RistrettoPoint::optional_multiscalar_mul(&self.scalars, self.points.iter().copied())
+    }
+}
+
+/// Verifies a sequence of proofs with a single multiscalar multiplication.
+pub struct BatchVerifier {
+    transcript: Transcript,
+    checks: Vec<MultiscalarCheck>,
+}
+
+impl Default for BatchVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatchVerifier {
+    pub fn new() -> Self {
+        Self {
+            transcript: Transcript::new(b"batch-verifier"),
+            checks: Vec::new(),
+        }
+    }
+
+    /// Returns the number of proofs in the batch.
+    pub fn len(&self) -> usize {
+        self.checks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    /// Adds the final check of a proof to the batch.
+    ///
+    /// The `proof_transcript` must have absorbed the proof context and all components of the proof
+    /// so that the weight of the check is bound to the proof.
+    pub(crate) fn append_check(
+        &mut self,
+        proof_transcript: &mut Transcript,
+        check: MultiscalarCheck,
+    ) {
+        let mut binding = [0u8; 64];
+        proof_transcript.challenge_bytes(b"batch-binding", &mut binding);
+        self.transcript.append_message(b"proof", &binding);
+        self.checks.push(check);
+    }
+
+    /// Verifies all proofs in the batch.
+    ///
+    /// The error does not identify which of the proofs is invalid. To find it, the proofs must be
+    /// verified individually.
+    pub fn verify(mut self) -> Result<(), ProofVerificationError> {
+        let weights: Vec<Scalar> = (0..self.checks.len())
+            .map(|_| self.transcript.challenge_scalar(b"w"))
+            .collect();
+
+        let scalars = self
+            .checks
+            .iter()
+            .zip(weights.iter())
+            .flat_map(|(check, weight)| check.scalars.iter().map(move |scalar| weight * scalar));
+        let points = self
+            .checks
+            .iter()
+            .flat_map(|check| check.points.iter().copied());
+
+        let batch_check = RistrettoPoint::optional_multiscalar_mul(scalars, points)
+            .ok_or(ProofVerificationError::BatchVerification)?;
+
+        if batch_check.is_identity() {
+            Ok(())
+        } else {
+            Err(ProofVerificationError::BatchVerification)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::{
+            encryption::{
+                elgamal::ElGamalKeypair,
+                pedersen::{Pedersen, PedersenOpening},
+            },
+            zk_elgamal_proof_program::proof_data::{
+                BatchableZkProofData, BatchedRangeProofU64Data,
+                CiphertextCiphertextEqualityProofData, CiphertextCommitmentEqualityProofData,
+                ZkProofData,
+            },
+        },
+    };
+
+    fn range_proof_u64_data(amount_1: u64, amount_2: u64) -> BatchedRangeProofU64Data {
+        let (commitment_1, opening_1) = Pedersen::new(amount_1);
+        let (commitment_2, opening_2) = Pedersen::new(amount_2);
+
+        BatchedRangeProofU64Data::new(
+            vec![&commitment_1, &commitment_2],
+            vec![amount_1, amount_2],
+            vec![32, 32],
+            vec![&opening_1, &opening_2],
+        )
+        .unwrap()
+    }
+
+    fn ciphertext_commitment_equality_data(
+        encrypted_amount: u64,
+        committed_amount: u64,
+    ) -> CiphertextCommitmentEqualityProofData {
+        let keypair = ElGamalKeypair::new_rand();
+        let ciphertext = keypair.pubkey().encrypt(encrypted_amount);
+        let (commitment, opening) = Pedersen::new(committed_amount);
+
+        CiphertextCommitmentEqualityProofData::new(
+            &keypair,
+            &ciphertext,
+            &commitment,
+            &opening,
+            encrypted_amount,
+        )
+        .unwrap()
+    }
+
+    fn ciphertext_ciphertext_equality_data(amount: u64) -> CiphertextCiphertextEqualityProofData {
+        let first_keypair = ElGamalKeypair::new_rand();
+        let second_keypair = ElGamalKeypair::new_rand();
+
+        let first_ciphertext = first_keypair.pubkey().encrypt(amount);
+        let second_opening = PedersenOpening::new_rand();
+        let second_ciphertext = second_keypair
+            .pubkey()
+            .encrypt_with(amount, &second_opening);
+
+        CiphertextCiphertextEqualityProofData::new(
+            &first_keypair,
+            second_keypair.pubkey(),
+            &first_ciphertext,
+            &second_ciphertext,
+            &second_opening,
+            amount,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_batch_verifier_correctness() {
+        let mut batch = BatchVerifier::new();
+        assert!(batch.is_empty());
+
+        range_proof_u64_data(55, 77)
+            .append_to_batch(&mut batch)
+            .unwrap();
+        range_proof_u64_data(0, u32::MAX as u64)
+            .append_to_batch(&mut batch)
+            .unwrap();
+        ciphertext_commitment_equality_data(55, 55)
+            .append_to_batch(&mut batch)
+            .unwrap();
+        ciphertext_ciphertext_equality_data(55)
+            .append_to_batch(&mut batch)
+            .unwrap();
+
+        assert_eq!(batch.len(), 4);
+        assert!(batch.verify().is_ok());
+    }
+
+    #[test]
+    fn test_batch_verifier_invalid_proof() {
+        // the commitment does not encode the encrypted amount
+        let invalid_proof_data = ciphertext_commitment_equality_data(55, 56);
+        assert!(invalid_proof_data.verify_proof().is_err());
+
+        let mut batch = BatchVerifier::new();
+        range_proof_u64_data(55, 77)
+            .append_to_batch(&mut batch)
+            .unwrap();
+        invalid_proof_data.append_to_batch(&mut batch).unwrap();
+        ciphertext_ciphertext_equality_data(55)
+            .append_to_batch(&mut batch)
+            .unwrap();
+
+        assert_eq!(
+            batch.verify().unwrap_err(),
+            ProofVerificationError::BatchVerification
+        );
+
+        // a range proof on an amount that exceeds its bit-length
+        let mut batch = BatchVerifier::new();
+        range_proof_u64_data(55, u32::MAX as u64 + 1)
+            .append_to_batch(&mut batch)
+            .unwrap();
+        ciphertext_ciphertext_equality_data(55)
+            .append_to_batch(&mut batch)
+            .unwrap();
+
+        assert_eq!(
+            batch.verify().unwrap_err(),
+            ProofVerificationError::BatchVerification
+        );
+    }
+}
@@ -19,6 +19,8 @@
 // `clippy::op_ref` is turned off to prevent clippy from warning that this is not idiomatic code.
 #![allow(clippy::arithmetic_side_effects, clippy::op_ref)]
 
+#[cfg(not(target_os = "solana"))]
+pub mod batch_verifier;
 pub mod encryption;
 pub mod errors;
 pub mod pod;
@@ -16,6 +16,7 @@ use crate::{RISTRETTO_POINT_LEN, SCALAR_LEN};
 #[cfg(not(target_os = "solana"))]
 use {
     crate::{
+        batch_verifier::{BatchVerifier, MultiscalarCheck},
         encryption::pedersen::{Pedersen, PedersenCommitment, PedersenOpening, G, H},
         range_proof::{
             errors::{RangeProofGenerationError, RangeProofVerificationError},
@@ -29,7 +30,7 @@ use {
     curve25519_dalek::{
         ristretto::{CompressedRistretto, RistrettoPoint},
         scalar::Scalar,
-        traits::{IsIdentity, VartimeMultiscalarMul},
+        traits::IsIdentity,
     },
     merlin::Transcript,
     rand::rngs::OsRng,
@@ -284,13 +285,47 @@ impl RangeProof {
         })
     }
 
-    #[allow(clippy::many_single_char_names)]
     pub fn verify(
         &self,
         comms: Vec<&PedersenCommitment>,
         bit_lengths: Vec<usize>,
         transcript: &mut Transcript,
     ) -> Result<(), RangeProofVerificationError> {
+        let mega_check = self
+            .verification_check(comms, bit_lengths, transcript)?
+            .evaluate()
+            .ok_or(RangeProofVerificationError::MultiscalarMul)?;
+
+        if mega_check.is_identity() {
+            Ok(())
+        } else {
+            Err(RangeProofVerificationError::AlgebraicRelation)
+        }
+    }
+
+    /// Adds the range proof to a batch of proofs. The proof is not verified until the batch is
+    /// verified.
+    pub(crate) fn append_to_batch(
+        &self,
+        comms: Vec<&PedersenCommitment>,
+        bit_lengths: Vec<usize>,
+        transcript: &mut Transcript,
+        batch: &mut BatchVerifier,
+    ) -> Result<(), RangeProofVerificationError> {
+        let check = self.verification_check(comms, bit_lengths, transcript)?;
+        batch.append_check(transcript, check);
+        Ok(())
+    }
+
+    /// Computes the multiscalar multiplication that evaluates to the identity if and only if the
+    /// range proof is valid.
+    #[allow(clippy::many_single_char_names)]
+    fn verification_check(
+        &self,
+        comms: Vec<&PedersenCommitment>,
+        bit_lengths: Vec<usize>,
+        transcript: &mut Transcript,
+    ) -> Result<MultiscalarCheck, RangeProofVerificationError> {
         // commitments and bit-lengths must be same length vectors
         if comms.len() != bit_lengths.len() {
             return Err(RangeProofVerificationError::VectorLengthMismatch);
@@ -364,37 +399,32 @@ impl RangeProof {
             w * (self.t_x - a * b) + d * (delta(&bit_lengths, &y, &z) - self.t_x);
         let value_commitment_scalars = util::exp_iter(z).take(m).map(|z_exp| d * zz * z_exp);
 
-        let mega_check = RistrettoPoint::optional_multiscalar_mul(
-            iter::once(Scalar::ONE)
-                .chain(iter::once(x))
-                .chain(iter::once(d * x))
-                .chain(iter::once(d * x * x))
-                .chain(iter::once(-self.e_blinding - d * self.t_x_blinding))
-                .chain(iter::once(basepoint_scalar))
-                .chain(x_sq.iter().cloned())
-                .chain(x_inv_sq.iter().cloned())
-                .chain(gs)
-                .chain(hs)
-                .chain(value_commitment_scalars),
-            iter::once(self.A.decompress())
-                .chain(iter::once(self.S.decompress()))
-                .chain(iter::once(self.T_1.decompress()))
-                .chain(iter::once(self.T_2.decompress()))
-                .chain(iter::once(Some(*H)))
-                .chain(iter::once(Some(G)))
-                .chain(self.ipp_proof.L_vec.iter().map(|L| L.decompress()))
-                .chain(self.ipp_proof.R_vec.iter().map(|R| R.decompress()))
-                .chain(bp_gens.G(nm).map(|&x| Some(x)))
-                .chain(bp_gens.H(nm).map(|&x| Some(x)))
-                .chain(comms.iter().map(|V| Some(*V.get_point()))),
-        )
-        .ok_or(RangeProofVerificationError::MultiscalarMul)?;
+        let scalars = iter::once(Scalar::ONE)
+            .chain(iter::once(x))
+            .chain(iter::once(d * x))
+            .chain(iter::once(d * x * x))
+            .chain(iter::once(-self.e_blinding - d * self.t_x_blinding))
+            .chain(iter::once(basepoint_scalar))
+            .chain(x_sq.iter().cloned())
+            .chain(x_inv_sq.iter().cloned())
+            .chain(gs)
+            .chain(hs)
+            .chain(value_commitment_scalars)
+            .collect();
+        let points = iter::once(self.A.decompress())
+            .chain(iter::once(self.S.decompress()))
+            .chain(iter::once(self.T_1.decompress()))
+            .chain(iter::once(self.T_2.decompress()))
+            .chain(iter::once(Some(*H)))
+            .chain(iter::once(Some(G)))
+            .chain(self.ipp_proof.L_vec.iter().map(|L| L.decompress()))
+            .chain(self.ipp_proof.R_vec.iter().map(|R| R.decompress()))
+            .chain(bp_gens.G(nm).map(|&x| Some(x)))
+            .chain(bp_gens.H(nm).map(|&x| Some(x)))
+            .chain(comms.iter().map(|V| Some(*V.get_point())))
+            .collect();
 
-        if mega_check.is_identity() {
-            Ok(())
-        } else {
-            Err(RangeProofVerificationError::AlgebraicRelation)
-        }
+        Ok(MultiscalarCheck::new(scalars, points))
     }
 
     // Following the dalek rangeproof library signature for now. The exact method signature can be
@@ -8,6 +8,7 @@ use wasm_bindgen::prelude::*;
 #[cfg(not(target_os = "solana"))]
 use {
     crate::{
+        batch_verifier::{BatchVerifier, MultiscalarCheck},
         encryption::{
             elgamal::{ElGamalCiphertext, ElGamalKeypair, ElGamalPubkey},
             pedersen::{PedersenOpening, G, H},
@@ -27,7 +28,7 @@ use {
     curve25519_dalek::{
         ristretto::{CompressedRistretto, RistrettoPoint},
         scalar::Scalar,
-        traits::{IsIdentity, VartimeMultiscalarMul},
+        traits::IsIdentity,
     },
     merlin::Transcript,
 };
@@ -143,6 +144,54 @@ impl CiphertextCiphertextEqualityProof {
         second_ciphertext: &ElGamalCiphertext,
         transcript: &mut Transcript,
     ) -> Result<(), EqualityProofVerificationError> {
+        let check = self
+            .verification_check(
+                first_pubkey,
+                second_pubkey,
+                first_ciphertext,
+                second_ciphertext,
+                transcript,
+            )?
+            .evaluate()
+            .ok_or(SigmaProofVerificationError::MultiscalarMul)?;
+
+        if check.is_identity() {
+            Ok(())
+        } else {
+            Err(SigmaProofVerificationError::AlgebraicRelation.into())
+        }
+    }
+
+    /// Adds a ciphertext-ciphertext equality proof to a batch of proofs. The proof is not verified
+    /// until the batch is verified.
+    pub(crate) fn append_to_batch(
+        &self,
+        first_pubkey: &ElGamalPubkey,
+        second_pubkey: &ElGamalPubkey,
+        first_ciphertext: &ElGamalCiphertext,
+        second_ciphertext: &ElGamalCiphertext,
+        transcript: &mut Transcript,
+        batch: &mut BatchVerifier,
+    ) -> Result<(), EqualityProofVerificationError> {
+        let check = self.verification_check(
+            first_pubkey,
+            second_pubkey,
+            first_ciphertext,
+            second_ciphertext,
+            transcript,
+        )?;
+        batch.append_check(transcript, check);
+        Ok(())
+    }
+
+    fn verification_check(
+        &self,
+        first_pubkey: &ElGamalPubkey,
+        second_pubkey: &ElGamalPubkey,
+        first_ciphertext: &ElGamalCiphertext,
+        second_ciphertext: &ElGamalCiphertext,
+        transcript: &mut Transcript,
+    ) -> Result<MultiscalarCheck, EqualityProofVerificationError> {
         transcript.ciphertext_ciphertext_equality_proof_domain_separator();
 
         // extract the relevant scalar and Ristretto points from the inputs
@@ -191,46 +240,40 @@ impl CiphertextCiphertextEqualityProof {
             .decompress()
             .ok_or(SigmaProofVerificationError::Deserialization)?;
 
-        let check = RistrettoPoint::vartime_multiscalar_mul(
+        Ok(MultiscalarCheck::new(
             vec![
-                &self.z_s,            // z_s
-                &(-&c),               // -c
-                &(-&Scalar::ONE),     // -identity
-                &(&w * &self.z_x),    // w * z_x
-                &(&w * &self.z_s),    // w * z_s
-                &(&w_negated * &c),   // -w * c
-                &w_negated,           // -w
-                &(&ww * &self.z_x),   // ww * z_x
-                &(&ww * &self.z_r),   // ww * z_r
-                &(&ww_negated * &c),  // -ww * c
-                &ww_negated,          // -ww
-                &(&www * &self.z_r),  // z_r
-                &(&www_negated * &c), // -www * c
-                &www_negated,
+                self.z_s,          // z_s
+                -&c,               // -c
+                -&Scalar::ONE,     // -identity
+                &w * &self.z_x,    // w * z_x
+                &w * &self.z_s,    // w * z_s
+                &w_negated * &c,   // -w * c
+                w_negated,         // -w
+                &ww * &self.z_x,   // ww * z_x
+                &ww * &self.z_r,   // ww * z_r
+                &ww_negated * &c,  // -ww * c
+                ww_negated,        // -ww
+                &www * &self.z_r,  // z_r
+                &www_negated * &c, // -www * c
+                www_negated,
             ],
             vec![
-                P_first,  // P_first
-                &(*H),    // H
-                &Y_0,     // Y_0
-                &G,       // G
-                D_first,  // D_first
-                C_first,  // C_first
-                &Y_1,     // Y_1
-                &G,       // G
-                &(*H),    // H
-                C_second, // C_second
-                &Y_2,     // Y_2
-                P_second, // P_second
-                D_second, // D_second
-                &Y_3,     // Y_3
+                Some(*P_first),  // P_first
+                Some(*H),        // H
+                Some(Y_0),       // Y_0
+                Some(G),         // G
+                Some(*D_first),  // D_first
+                Some(*C_first),  // C_first
+                Some(Y_1),       // Y_1
+                Some(G),         // G
+                Some(*H),        // H
+                Some(*C_second), // C_second
+                Some(Y_2),       // Y_2
+                Some(*P_second), // P_second
+                Some(*D_second), // D_second
+                Some(Y_3),       // Y_3
             ],
-        );
-
-        if check.is_identity() {
-            Ok(())
-        } else {
-            Err(SigmaProofVerificationError::AlgebraicRelation.into())
-        }
+        ))
     }
 
     pub fn to_bytes(&self) -> [u8; CIPHERTEXT_CIPHERTEXT_EQUALITY_PROOF_LEN] {
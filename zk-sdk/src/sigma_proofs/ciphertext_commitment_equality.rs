@@ -13,6 +13,7 @@ use wasm_bindgen::prelude::*;
 #[cfg(not(target_os = "solana"))]
 use {
     crate::{
+        batch_verifier::{BatchVerifier, MultiscalarCheck},
         encryption::{
             elgamal::{ElGamalCiphertext, ElGamalKeypair, ElGamalPubkey},
             pedersen::{PedersenCommitment, PedersenOpening, G, H},
@@ -32,7 +33,7 @@ use {
     curve25519_dalek::{
         ristretto::{CompressedRistretto, RistrettoPoint},
         scalar::Scalar,
-        traits::{IsIdentity, VartimeMultiscalarMul},
+        traits::IsIdentity,
     },
     merlin::Transcript,
 };
@@ -141,6 +142,40 @@ impl CiphertextCommitmentEqualityProof {
         commitment: &PedersenCommitment,
         transcript: &mut Transcript,
     ) -> Result<(), EqualityProofVerificationError> {
+        let check = self
+            .verification_check(pubkey, ciphertext, commitment, transcript)?
+            .evaluate()
+            .ok_or(SigmaProofVerificationError::MultiscalarMul)?;
+
+        if check.is_identity() {
+            Ok(())
+        } else {
+            Err(SigmaProofVerificationError::AlgebraicRelation.into())
+        }
+    }
+
+    /// Adds a ciphertext-commitment equality proof to a batch of proofs. The proof is not verified
+    /// until the batch is verified.
+    pub(crate) fn append_to_batch(
+        &self,
+        pubkey: &ElGamalPubkey,
+        ciphertext: &ElGamalCiphertext,
+        commitment: &PedersenCommitment,
+        transcript: &mut Transcript,
+        batch: &mut BatchVerifier,
+    ) -> Result<(), EqualityProofVerificationError> {
+        let check = self.verification_check(pubkey, ciphertext, commitment, transcript)?;
+        batch.append_check(transcript, check);
+        Ok(())
+    }
+
+    fn verification_check(
+        &self,
+        pubkey: &ElGamalPubkey,
+        ciphertext: &ElGamalCiphertext,
+        commitment: &PedersenCommitment,
+        transcript: &mut Transcript,
+    ) -> Result<MultiscalarCheck, EqualityProofVerificationError> {
         transcript.ciphertext_commitment_equality_proof_domain_separator();
 
         // extract the relevant scalar and Ristretto points from the inputs
@@ -179,40 +214,34 @@ impl CiphertextCommitmentEqualityProof {
             .decompress()
             .ok_or(SigmaProofVerificationError::Deserialization)?;
 
-        let check = RistrettoPoint::vartime_multiscalar_mul(
+        Ok(MultiscalarCheck::new(
             vec![
-                &self.z_s,           // z_s
-                &(-&c),              // -c
-                &(-&Scalar::ONE),    // -identity
-                &(&w * &self.z_x),   // w * z_x
-                &(&w * &self.z_s),   // w * z_s
-                &(&w_negated * &c),  // -w * c
-                &w_negated,          // -w
-                &(&ww * &self.z_x),  // ww * z_x
-                &(&ww * &self.z_r),  // ww * z_r
-                &(&ww_negated * &c), // -ww * c
-                &ww_negated,         // -ww
+                self.z_s,         // z_s
+                -&c,              // -c
+                -&Scalar::ONE,    // -identity
+                &w * &self.z_x,   // w * z_x
+                &w * &self.z_s,   // w * z_s
+                &w_negated * &c,  // -w * c
+                w_negated,        // -w
+                &ww * &self.z_x,  // ww * z_x
+                &ww * &self.z_r,  // ww * z_r
+                &ww_negated * &c, // -ww * c
+                ww_negated,       // -ww
             ],
             vec![
-                P,            // P
-                &(*H),        // H
-                &Y_0,         // Y_0
-                &G,           // G
-                D,            // D
-                C_ciphertext, // C_ciphertext
-                &Y_1,         // Y_1
-                &G,           // G
-                &(*H),        // H
-                C_commitment, // C_commitment
-                &Y_2,         // Y_2
+                Some(*P),            // P
+                Some(*H),            // H
+                Some(Y_0),           // Y_0
+                Some(G),             // G
+                Some(*D),            // D
+                Some(*C_ciphertext), // C_ciphertext
+                Some(Y_1),           // Y_1
+                Some(G),             // G
+                Some(*H),            // H
+                Some(*C_commitment), // C_commitment
+                Some(Y_2),           // Y_2
             ],
-        );
-
-        if check.is_identity() {
-            Ok(())
-        } else {
-            Err(SigmaProofVerificationError::AlgebraicRelation.into())
-        }
+        ))
     }
 
     pub fn to_bytes(&self) -> [u8; CIPHERTEXT_COMMITMENT_EQUALITY_PROOF_LEN] {
@@ -25,6 +25,11 @@
 //! NOTE: A context-state account must be pre-allocated to the exact size of the context data that
 //! is expected for a proof type before it is included as part of a proof verification instruction.
 //!
+//! The `VerifyProofBatch` instruction verifies a sequence of range proofs and equality proofs
+//! with a single batch verification. The proofs must be provided as instruction data. The context
+//! data of each proof can be stored in a context-state account, like with the individual proof
+//! verification instructions.
+//!
 //! The `CloseContextState` instruction closes a context state account. A transaction containing
 //! this instruction must be signed by the context account's owner. This instruction can be used by
 //! the account owner to reclaim lamports for storage.
@@ -33,7 +38,10 @@
 //! [`context-state`]: https://docs.solanalabs.com/runtime/zk-token-proof#context-data
 
 use {
-    crate::zk_elgamal_proof_program::proof_data::ZkProofData,
+    crate::zk_elgamal_proof_program::proof_data::{
+        BatchedRangeProofU128Data, BatchedRangeProofU256Data, BatchedRangeProofU64Data,
+        CiphertextCiphertextEqualityProofData, CiphertextCommitmentEqualityProofData, ZkProofData,
+    },
     bytemuck::{bytes_of, Pod},
     num_derive::{FromPrimitive, ToPrimitive},
     num_traits::{FromPrimitive, ToPrimitive},
@@ -281,6 +289,30 @@ pub enum ProofInstruction {
     ///   ii. `u32` byte offset if proof is provided as an account
     ///
     VerifyBatchedGroupedCiphertext3HandlesValidity,
+
+    /// Verify a batch of zero-knowledge proofs.
+    ///
+    /// The proofs in a batch are verified together, which is cheaper than verifying each proof
+    /// with a separate instruction. The instruction fails if any proof in the batch is invalid.
+    /// Only proofs of the `VerifyCiphertextCiphertextEquality`,
+    /// `VerifyCiphertextCommitmentEquality`, and `VerifyBatchedRangeProof{64, 128, 256}`
+    /// instructions can be batched.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Verify the proofs without storing their context data
+    ///   None
+    ///
+    ///   * Store the context data of each proof in a context-state account, for each proof in
+    ///     order
+    ///   0. `[writable]` The proof context account to create
+    ///   1. `[]` The proof context account owner
+    ///
+    /// Data expected by this instruction:
+    ///   A sequence of at least two proofs. Each proof is encoded as the discriminator of its
+    ///   proof verification instruction followed by its proof data.
+    ///
+    VerifyProofBatch,
 }
 
 /// Pubkeys associated with a context state account to be used as parameters to functions.
@@ -310,6 +342,26 @@ pub fn close_context_state(
     }
 }
 
+/// Create a `VerifyProofBatch` instruction from a sequence of proof verification instructions.
+///
+/// Each instruction must be encoded with [`ProofInstruction::encode_verify_proof`] with the proof
+/// data in the instruction. The batch is rejected unless either all or none of the instructions
+/// come with a context state account.
+pub fn verify_proof_batch(instructions: &[Instruction]) -> Instruction {
+    let mut data = vec![ToPrimitive::to_u8(&ProofInstruction::VerifyProofBatch).unwrap()];
+    let mut accounts = vec![];
+    for instruction in instructions {
+        data.extend_from_slice(&instruction.data);
+        accounts.extend_from_slice(&instruction.accounts);
+    }
+
+    Instruction {
+        program_id: crate::zk_elgamal_proof_program::id(),
+        accounts,
+        data,
+    }
+}
+
 impl ProofInstruction {
     pub fn encode_verify_proof<T, U>(
         &self,
@@ -380,4 +432,27 @@ impl ProofInstruction {
             .get(1..)
             .and_then(|data| bytemuck::try_from_bytes(data).ok())
     }
+
+    /// Returns the length of the proof data of a proof verification instruction that can be
+    /// included in a `VerifyProofBatch` instruction.
+    pub fn batched_proof_data_len(&self) -> Option<usize> {
+        match self {
+            Self::VerifyCiphertextCiphertextEquality => {
+                Some(std::mem::size_of::<CiphertextCiphertextEqualityProofData>())
+            }
+            Self::VerifyCiphertextCommitmentEquality => {
+                Some(std::mem::size_of::<CiphertextCommitmentEqualityProofData>())
+            }
+            Self::VerifyBatchedRangeProofU64 => {
+                Some(std::mem::size_of::<BatchedRangeProofU64Data>())
+            }
+            Self::VerifyBatchedRangeProofU128 => {
+                Some(std::mem::size_of::<BatchedRangeProofU128Data>())
+            }
+            Self::VerifyBatchedRangeProofU256 => {
+                Some(std::mem::size_of::<BatchedRangeProofU256Data>())
+            }
+            _ => None,
+        }
+    }
 }
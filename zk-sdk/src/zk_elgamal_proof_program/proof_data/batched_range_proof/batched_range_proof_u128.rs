@@ -3,6 +3,7 @@
 #[cfg(not(target_os = "solana"))]
 use {
     crate::{
+        batch_verifier::BatchVerifier,
         encryption::pedersen::{PedersenCommitment, PedersenOpening},
         range_proof::RangeProof,
         zk_elgamal_proof_program::{
@@ -16,7 +17,8 @@ use {
     crate::{
         range_proof::pod::PodRangeProofU128,
         zk_elgamal_proof_program::proof_data::{
-            batched_range_proof::BatchedRangeProofContext, ProofType, ZkProofData,
+            batched_range_proof::BatchedRangeProofContext, BatchableZkProofData, ProofType,
+            ZkProofData,
         },
     },
     bytemuck_derive::{Pod, Zeroable},
@@ -97,6 +99,30 @@ impl ZkProofData<BatchedRangeProofContext> for BatchedRangeProofU128Data {
     }
 }
 
+impl BatchableZkProofData<BatchedRangeProofContext> for BatchedRangeProofU128Data {
+    #[cfg(not(target_os = "solana"))]
+    fn append_to_batch(&self, batch: &mut BatchVerifier) -> Result<(), ProofVerificationError> {
+        let (commitments, bit_lengths) = self.context.try_into()?;
+        let num_commitments = commitments.len();
+
+        if num_commitments > MAX_COMMITMENTS || num_commitments != bit_lengths.len() {
+            return Err(ProofVerificationError::IllegalCommitmentLength);
+        }
+
+        let mut transcript = self.context_data().new_transcript();
+        let proof: RangeProof = self.proof.try_into()?;
+
+        proof
+            .append_to_batch(
+                commitments.iter().collect(),
+                bit_lengths,
+                &mut transcript,
+                batch,
+            )
+            .map_err(|e| e.into())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use {
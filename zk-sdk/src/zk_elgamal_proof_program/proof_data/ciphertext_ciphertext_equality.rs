@@ -7,17 +7,10 @@
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
-use {
-    crate::{
-        encryption::pod::elgamal::{PodElGamalCiphertext, PodElGamalPubkey},
-        sigma_proofs::pod::PodCiphertextCiphertextEqualityProof,
-        zk_elgamal_proof_program::proof_data::{pod::impl_wasm_to_bytes, ProofType, ZkProofData},
-    },
-    bytemuck_derive::{Pod, Zeroable},
-};
 #[cfg(not(target_os = "solana"))]
 use {
     crate::{
+        batch_verifier::BatchVerifier,
         encryption::{
             elgamal::{ElGamalCiphertext, ElGamalKeypair, ElGamalPubkey},
             pedersen::PedersenOpening,
@@ -32,6 +25,16 @@ use {
     merlin::Transcript,
     std::convert::TryInto,
 };
+use {
+    crate::{
+        encryption::pod::elgamal::{PodElGamalCiphertext, PodElGamalPubkey},
+        sigma_proofs::pod::PodCiphertextCiphertextEqualityProof,
+        zk_elgamal_proof_program::proof_data::{
+            pod::impl_wasm_to_bytes, BatchableZkProofData, ProofType, ZkProofData,
+        },
+    },
+    bytemuck_derive::{Pod, Zeroable},
+};
 
 /// The instruction data that is needed for the
 /// `ProofInstruction::VerifyCiphertextCiphertextEquality` instruction.
@@ -133,6 +136,32 @@ impl ZkProofData<CiphertextCiphertextEqualityProofContext>
     }
 }
 
+impl BatchableZkProofData<CiphertextCiphertextEqualityProofContext>
+    for CiphertextCiphertextEqualityProofData
+{
+    #[cfg(not(target_os = "solana"))]
+    fn append_to_batch(&self, batch: &mut BatchVerifier) -> Result<(), ProofVerificationError> {
+        let mut transcript = self.context.new_transcript();
+
+        let first_pubkey = self.context.first_pubkey.try_into()?;
+        let second_pubkey = self.context.second_pubkey.try_into()?;
+        let first_ciphertext = self.context.first_ciphertext.try_into()?;
+        let second_ciphertext = self.context.second_ciphertext.try_into()?;
+        let proof: CiphertextCiphertextEqualityProof = self.proof.try_into()?;
+
+        proof
+            .append_to_batch(
+                &first_pubkey,
+                &second_pubkey,
+                &first_ciphertext,
+                &second_ciphertext,
+                &mut transcript,
+                batch,
+            )
+            .map_err(|e| e.into())
+    }
+}
+
 #[allow(non_snake_case)]
 #[cfg(not(target_os = "solana"))]
 impl CiphertextCiphertextEqualityProofContext {
@@ -7,20 +7,10 @@
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
-use {
-    crate::{
-        encryption::pod::{
-            elgamal::{PodElGamalCiphertext, PodElGamalPubkey},
-            pedersen::PodPedersenCommitment,
-        },
-        sigma_proofs::pod::PodCiphertextCommitmentEqualityProof,
-        zk_elgamal_proof_program::proof_data::{pod::impl_wasm_to_bytes, ProofType, ZkProofData},
-    },
-    bytemuck_derive::{Pod, Zeroable},
-};
 #[cfg(not(target_os = "solana"))]
 use {
     crate::{
+        batch_verifier::BatchVerifier,
         encryption::{
             elgamal::{ElGamalCiphertext, ElGamalKeypair},
             pedersen::{PedersenCommitment, PedersenOpening},
@@ -35,6 +25,19 @@ use {
     merlin::Transcript,
     std::convert::TryInto,
 };
+use {
+    crate::{
+        encryption::pod::{
+            elgamal::{PodElGamalCiphertext, PodElGamalPubkey},
+            pedersen::PodPedersenCommitment,
+        },
+        sigma_proofs::pod::PodCiphertextCommitmentEqualityProof,
+        zk_elgamal_proof_program::proof_data::{
+            pod::impl_wasm_to_bytes, BatchableZkProofData, ProofType, ZkProofData,
+        },
+    },
+    bytemuck_derive::{Pod, Zeroable},
+};
 /// The instruction data that is needed for the
 /// `ProofInstruction::VerifyCiphertextCommitmentEquality` instruction.
 ///
@@ -119,6 +122,24 @@ impl ZkProofData<CiphertextCommitmentEqualityProofContext>
     }
 }
 
+impl BatchableZkProofData<CiphertextCommitmentEqualityProofContext>
+    for CiphertextCommitmentEqualityProofData
+{
+    #[cfg(not(target_os = "solana"))]
+    fn append_to_batch(&self, batch: &mut BatchVerifier) -> Result<(), ProofVerificationError> {
+        let mut transcript = self.context.new_transcript();
+
+        let pubkey = self.context.pubkey.try_into()?;
+        let ciphertext = self.context.ciphertext.try_into()?;
+        let commitment = self.context.commitment.try_into()?;
+        let proof: CiphertextCommitmentEqualityProof = self.proof.try_into()?;
+
+        proof
+            .append_to_batch(&pubkey, &ciphertext, &commitment, &mut transcript, batch)
+            .map_err(|e| e.into())
+    }
+}
+
 #[allow(non_snake_case)]
 #[cfg(not(target_os = "solana"))]
 impl CiphertextCommitmentEqualityProofContext {
@@ -1,5 +1,7 @@
 #[cfg(not(target_os = "solana"))]
-use crate::zk_elgamal_proof_program::errors::ProofVerificationError;
+use crate::{
+    batch_verifier::BatchVerifier, zk_elgamal_proof_program::errors::ProofVerificationError,
+};
 use {
     bytemuck::Pod,
     num_derive::{FromPrimitive, ToPrimitive},
@@ -70,3 +72,10 @@ pub trait ZkProofData<T: Pod> {
     #[cfg(not(target_os = "solana"))]
     fn verify_proof(&self) -> Result<(), ProofVerificationError>;
 }
+
+/// Proof data that can be verified as part of a batch of proofs.
+pub trait BatchableZkProofData<T: Pod>: ZkProofData<T> {
+    /// Adds the proof to a batch of proofs. The proof is verified only when the batch is verified.
+    #[cfg(not(target_os = "solana"))]
+    fn append_to_batch(&self, batch: &mut BatchVerifier) -> Result<(), ProofVerificationError>;
+}